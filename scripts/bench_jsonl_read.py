@@ -0,0 +1,107 @@
+#!/usr/bin/env python3
+"""
+Compare the buffered and mmap read paths, and the JSON decoders, for large transcripts.
+
+Parses one JSONL file with each reader (jsonl_parser.parse_jsonl_file with
+and without mmap_threshold) and prints the best time of several runs, so
+"mmap_threshold_mb" can be tuned for a machine's disks. The buffered read
+is also timed with the standard library's json decoder, showing what the
+fast extra (orjson) saves when it is installed. Without --file a synthetic
+Claude Code transcript of --size-mb is written to a temporary directory
+first; the first run of each reader warms the page cache.
+
+Usage:
+    python3 scripts/bench_jsonl_read.py [--file session.jsonl]
+        [--size-mb 200] [--repeat 3]
+"""
+import argparse
+import json
+import sys
+import tempfile
+import time
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+from src.data import jsonl_parser  # noqa: E402
+from src.data.jsonl_parser import parse_jsonl_file  # noqa: E402
+
+
+def write_transcript(path: Path, size_mb: float) -> None:
+    """Write synthetic assistant entries until the file reaches size_mb."""
+    target = int(size_mb * 1024 * 1024)
+    written = 0
+    with open(path, "w", encoding="utf-8") as f:
+        index = 0
+        while written < target:
+            line = json.dumps({
+                "type": "assistant",
+                "sessionId": "bench-session",
+                "uuid": f"uuid-{index}",
+                "requestId": f"req-{index}",
+                "timestamp": "2026-10-01T10:00:00Z",
+                "cwd": "/work/bench",
+                "gitBranch": "main",
+                "version": "2.0.0",
+                "message": {
+                    "id": f"msg-{index}",
+                    "model": "claude-sonnet-4-5",
+                    "content": [{"type": "text", "text": "x" * 400}],
+                    "usage": {
+                        "input_tokens": 1200, "output_tokens": 300,
+                        "cache_creation_input_tokens": 0, "cache_read_input_tokens": 40000,
+                    },
+                },
+            }) + "\n"
+            f.write(line)
+            written += len(line)
+            index += 1
+
+
+def best_time(path: Path, mmap_threshold: int | None, repeat: int) -> tuple[float, int]:
+    """Fastest full parse of path in seconds, and the records it yielded."""
+    best, records = float("inf"), 0
+    for _ in range(repeat):
+        start = time.perf_counter()
+        records = sum(1 for _ in parse_jsonl_file(path, mmap_threshold=mmap_threshold))
+        best = min(best, time.perf_counter() - start)
+    return best, records
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__, formatter_class=argparse.RawDescriptionHelpFormatter)
+    parser.add_argument("--file", type=Path, help="Transcript to read (default: a synthetic one)")
+    parser.add_argument("--size-mb", type=float, default=200, help="Size of the synthetic transcript")
+    parser.add_argument("--repeat", type=int, default=3, help="Runs per reader; the fastest counts")
+    args = parser.parse_args()
+
+    with tempfile.TemporaryDirectory() as tmp:
+        path = args.file
+        if path is None:
+            path = Path(tmp) / "bench.jsonl"
+            print(f"Writing a {args.size_mb:g} MB synthetic transcript...")
+            write_transcript(path, args.size_mb)
+        size_mb = path.stat().st_size / (1024 * 1024)
+
+        buffered, buffered_records = best_time(path, None, args.repeat)
+        mapped, mapped_records = best_time(path, 1, args.repeat)
+        decoder = jsonl_parser._json_loads
+        jsonl_parser._json_loads = json.loads
+        try:
+            stdlib, stdlib_records = best_time(path, None, args.repeat)
+        finally:
+            jsonl_parser._json_loads = decoder
+        if not mapped_records == stdlib_records == buffered_records:
+            print(f"Record counts differ: buffered {buffered_records}, mmap {mapped_records}, json {stdlib_records}")
+            return 1
+
+    print(f"{path.name}: {size_mb:,.1f} MB, {buffered_records:,} records, best of {args.repeat}")
+    print(f"  buffered  {buffered:7.2f}s  {size_mb / buffered:8.1f} MB/s")
+    print(f"  mmap      {mapped:7.2f}s  {size_mb / mapped:8.1f} MB/s  ({buffered / mapped:.2f}x)")
+    print(f"decoder: {decoder.__module__}")
+    print(f"  json      {stdlib:7.2f}s  {size_mb / stdlib:8.1f} MB/s  (buffered is {stdlib / buffered:.2f}x faster)")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
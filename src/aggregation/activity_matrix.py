@@ -0,0 +1,86 @@
+#region Imports
+from collections.abc import Iterable
+from dataclasses import dataclass, field
+from datetime import datetime
+
+from src.aggregation.daily_stats import WEEKDAY_NAMES
+
+#endregion
+
+
+#region Constants
+# Cell values a matrix can be drawn by (ccg punchcard --metric)
+ACTIVITY_METRICS = ("tokens", "prompts")
+#endregion
+
+
+#region Data Classes
+
+
+def _empty_grid() -> list[list[int]]:
+    return [[0] * 24 for _ in range(7)]
+
+
+@dataclass
+class ActivityMatrix:
+    """
+    Tokens and prompts by weekday and hour of day (local time).
+
+    Attributes:
+        tokens: 7 rows (Monday first) of 24 hourly token totals
+        prompts: 7 rows (Monday first) of 24 hourly prompt counts
+    """
+
+    tokens: list[list[int]] = field(default_factory=_empty_grid)
+    prompts: list[list[int]] = field(default_factory=_empty_grid)
+
+    def grid(self, metric: str) -> list[list[int]]:
+        """The 7x24 grid of one of ACTIVITY_METRICS."""
+        return self.prompts if metric == "prompts" else self.tokens
+
+    def peak(self, metric: str) -> tuple[str, int, int] | None:
+        """Busiest cell as (weekday name, hour, value), or None without activity."""
+        grid = self.grid(metric)
+        value, weekday, hour = max((grid[d][h], d, h) for d in range(7) for h in range(24))
+        return (WEEKDAY_NAMES[weekday], hour, value) if value > 0 else None
+
+    def hour_totals(self, metric: str) -> list[int]:
+        """Totals per hour of day across all weekdays."""
+        grid = self.grid(metric)
+        return [sum(row[h] for row in grid) for h in range(24)]
+
+    def weekday_totals(self, metric: str) -> list[int]:
+        """Totals per weekday, Monday first."""
+        return [sum(row) for row in self.grid(metric)]
+
+
+#endregion
+
+
+#region Functions
+
+
+def build_activity_matrix(hours: Iterable[dict]) -> ActivityMatrix:
+    """
+    Fold hourly_snapshots rows into a weekday x hour-of-day matrix.
+
+    Rows are keyed by UTC hour and land in the local weekday and hour they
+    fall on, so the matrix follows the machine's timezone. Works in both
+    storage modes, since the hourly rollup is kept at ingest either way.
+
+    Args:
+        hours: Dicts with hour (UTC ISO string), prompts, and total_tokens
+            (storage.api.get_hourly_range)
+
+    Returns:
+        ActivityMatrix summed over every row
+    """
+    matrix = ActivityMatrix()
+    for row in hours:
+        local = datetime.fromisoformat(row["hour"]).astimezone()
+        matrix.tokens[local.weekday()][local.hour] += row["total_tokens"]
+        matrix.prompts[local.weekday()][local.hour] += row["prompts"]
+    return matrix
+
+
+#endregion
@@ -0,0 +1,209 @@
+#region Imports
+import math
+from dataclasses import dataclass, field
+from pathlib import Path
+
+from src.config.settings import encode_project_dir
+
+#endregion
+
+
+#region Constants
+# Separates commits in the `git log` output parse_git_log reads
+GIT_LOG_FORMAT = "%x1e%ad"
+
+# Fewest active days before a correlation is reported
+MIN_CORRELATION_DAYS = 3
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class CommitDay:
+    """
+    Claude usage and git activity for one repository on one day.
+
+    Attributes:
+        date: Day (YYYY-MM-DD, local time)
+        commits: Non-merge commits authored that day
+        lines_added: Lines added by those commits
+        lines_deleted: Lines deleted by those commits
+        tokens: Claude tokens used in the repository (or below it)
+        cost: Estimated API cost of those tokens in dollars
+    """
+
+    date: str
+    commits: int = 0
+    lines_added: int = 0
+    lines_deleted: int = 0
+    tokens: int = 0
+    cost: float = 0.0
+
+    @property
+    def lines_changed(self) -> int:
+        """Lines added plus lines deleted."""
+        return self.lines_added + self.lines_deleted
+
+
+@dataclass
+class CommitReport:
+    """
+    Claude spend set against shipped commits for one repository.
+
+    Attributes:
+        days: Days with commits or Claude usage, oldest first
+    """
+
+    days: list[CommitDay] = field(default_factory=list)
+
+    @property
+    def commits(self) -> int:
+        """Commits across all days."""
+        return sum(day.commits for day in self.days)
+
+    @property
+    def lines_changed(self) -> int:
+        """Changed lines across all days."""
+        return sum(day.lines_changed for day in self.days)
+
+    @property
+    def tokens(self) -> int:
+        """Claude tokens across all days."""
+        return sum(day.tokens for day in self.days)
+
+    @property
+    def cost(self) -> float:
+        """Estimated API cost across all days in dollars."""
+        return sum(day.cost for day in self.days)
+
+    @property
+    def cost_per_commit(self) -> float | None:
+        """Total cost over total commits (None without commits)."""
+        return self.cost / self.commits if self.commits else None
+
+    @property
+    def cost_per_100_lines(self) -> float | None:
+        """Total cost per 100 changed lines (None without changed lines)."""
+        return self.cost / self.lines_changed * 100 if self.lines_changed else None
+
+    @property
+    def days_without_commits(self) -> int:
+        """Days Claude was used in the repository but nothing was committed."""
+        return sum(1 for day in self.days if day.tokens and not day.commits)
+
+    @property
+    def days_without_usage(self) -> int:
+        """Days with commits but no Claude usage in the repository."""
+        return sum(1 for day in self.days if day.commits and not day.tokens)
+
+    @property
+    def correlation(self) -> float | None:
+        """
+        Pearson correlation of daily cost with daily commits.
+
+        None with fewer than MIN_CORRELATION_DAYS days, or when either
+        series is constant.
+        """
+        if len(self.days) < MIN_CORRELATION_DAYS:
+            return None
+        return pearson([day.cost for day in self.days], [day.commits for day in self.days])
+
+
+#endregion
+
+
+#region Functions
+
+
+def pearson(xs: list[float], ys: list[float]) -> float | None:
+    """Pearson correlation coefficient, or None when either series is constant."""
+    n = len(xs)
+    if n == 0 or n != len(ys):
+        return None
+    mean_x, mean_y = sum(xs) / n, sum(ys) / n
+    cov = sum((x - mean_x) * (y - mean_y) for x, y in zip(xs, ys))
+    var_x = sum((x - mean_x) ** 2 for x in xs)
+    var_y = sum((y - mean_y) ** 2 for y in ys)
+    if var_x == 0 or var_y == 0:
+        return None
+    return cov / math.sqrt(var_x * var_y)
+
+
+def parse_git_log(output: str) -> dict[str, dict[str, int]]:
+    """
+    Commits and changed lines per day from `git log --numstat`.
+
+    Expects GIT_LOG_FORMAT with a YYYY-MM-DD date, e.g.
+    `git log --no-merges --numstat --date=format-local:%Y-%m-%d --format=%x1e%ad`.
+    Binary files ("-" counts) add no lines.
+
+    Args:
+        output: git log output
+
+    Returns:
+        {date: {"commits", "lines_added", "lines_deleted"}}
+    """
+    days: dict[str, dict[str, int]] = {}
+    for chunk in output.split("\x1e"):
+        lines = chunk.strip().splitlines()
+        if not lines:
+            continue
+        day = days.setdefault(lines[0].strip(), {"commits": 0, "lines_added": 0, "lines_deleted": 0})
+        day["commits"] += 1
+        for line in lines[1:]:
+            parts = line.split("\t", 2)
+            if len(parts) == 3:
+                day["lines_added"] += int(parts[0]) if parts[0].isdigit() else 0
+                day["lines_deleted"] += int(parts[1]) if parts[1].isdigit() else 0
+    return days
+
+
+def _in_repo(folder: str, repo: Path) -> bool:
+    """True when a usage folder is the repository or below it (or its encoded name, in aggregate mode)."""
+    root = str(repo).replace("\\", "/").rstrip("/")
+    path = folder.replace("\\", "/").rstrip("/")
+    if path == root or path.startswith(root + "/"):
+        return True
+    encoded = encode_project_dir(repo)
+    return folder == encoded or folder.startswith(encoded + "-")
+
+
+def correlate_commits(
+    repo: Path,
+    daily_projects: dict[str, dict[str, dict]],
+    git_days: dict[str, dict[str, int]],
+) -> CommitReport:
+    """
+    Set a repository's daily Claude usage against its daily git activity.
+
+    Usage counts toward the repository when its folder is the repository or
+    below it (worktrees elsewhere do not count). In aggregate storage mode
+    folders are Claude's encoded names, which can also match a sibling
+    directory sharing the repository's prefix.
+
+    Args:
+        repo: Absolute repository path
+        daily_projects: Per-day project usage (storage.api.get_daily_projects)
+        git_days: Per-day git activity (parse_git_log)
+
+    Returns:
+        CommitReport with a day for every date with commits or usage
+    """
+    days: dict[str, CommitDay] = {}
+    for date, projects in daily_projects.items():
+        for folder, values in projects.items():
+            if _in_repo(folder, repo):
+                day = days.setdefault(date, CommitDay(date))
+                day.tokens += values.get("tokens", 0)
+                day.cost += values.get("cost", 0.0)
+    for date, activity in git_days.items():
+        day = days.setdefault(date, CommitDay(date))
+        day.commits += activity["commits"]
+        day.lines_added += activity["lines_added"]
+        day.lines_deleted += activity["lines_deleted"]
+    return CommitReport([days[date] for date in sorted(days) if days[date].tokens or days[date].commits])
+
+
+#endregion
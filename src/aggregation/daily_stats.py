@@ -1,13 +1,67 @@
 #region Imports
-from collections import defaultdict
+from collections.abc import Iterable
 from dataclasses import dataclass
 from datetime import datetime, timedelta
+from enum import Enum
 
 from src.models.usage_record import UsageRecord
 
 #endregion
 
 
+#region Constants
+WEEKDAY_NAMES = ("Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun")
+
+# Active-hours estimate: a record marks its whole bucket as active
+ACTIVITY_BUCKET_MINUTES = 5
+
+# Group key for records without a git branch
+NO_BRANCH = "(no branch)"
+#endregion
+
+
+#region Enums
+
+
+class GroupBy(str, Enum):
+    """
+    Dimension aggregate() groups records by.
+
+    Time keys use local time like date_key: DAY "2026-10-16", WEEK (ISO)
+    "2026-W42", MONTH "2026-10", HOUR "2026-10-16T14:00". MODEL skips
+    records without a model (user prompts), BRANCH keys records outside a
+    git repository as NO_BRANCH, and PROJECT keys by folder.
+    """
+
+    DAY = "day"
+    WEEK = "week"
+    MONTH = "month"
+    MODEL = "model"
+    PROJECT = "project"
+    BRANCH = "branch"
+    HOUR = "hour"
+
+    def key(self, record: UsageRecord) -> str | None:
+        """Group key of a record, or None when it belongs to no group."""
+        if self is GroupBy.DAY:
+            return record.date_key
+        if self is GroupBy.MONTH:
+            return record.date_key[:7]
+        if self is GroupBy.WEEK:
+            year, week, _ = record.timestamp.astimezone().isocalendar()
+            return f"{year}-W{week:02d}"
+        if self is GroupBy.HOUR:
+            return record.timestamp.astimezone().strftime("%Y-%m-%dT%H:00")
+        if self is GroupBy.MODEL:
+            return record.model
+        if self is GroupBy.BRANCH:
+            return record.git_branch or NO_BRANCH
+        return record.folder
+
+
+#endregion
+
+
 #region Data Classes
 
 
@@ -17,7 +71,7 @@ class DailyStats:
     Aggregated statistics for a single day.
 
     Attributes:
-        date: Date in YYYY-MM-DD format
+        date: Date in YYYY-MM-DD format (the group key when built by aggregate())
         total_prompts: Number of user prompts (user messages)
         total_responses: Number of assistant responses (assistant messages)
         total_sessions: Number of unique sessions
@@ -28,6 +82,9 @@ class DailyStats:
         cache_read_tokens: Total cache read tokens
         models: Set of unique model names used
         folders: Set of unique project folders
+        cost: Estimated API cost in dollars (only set from daily snapshots)
+        active_hours: Estimated hours active (union of 5-minute buckets
+            touched by any record; zero when built from daily snapshots)
     """
 
     date: str
@@ -41,6 +98,8 @@ class DailyStats:
     cache_read_tokens: int
     models: set[str]
     folders: set[str]
+    cost: float = 0.0
+    active_hours: float = 0.0
 
 
 @dataclass
@@ -55,41 +114,96 @@ class AggregatedStats:
 
     daily_stats: dict[str, DailyStats]
     overall_totals: DailyStats
+
+
+@dataclass
+class WeekdayAverage:
+    """
+    Average usage for one day of the week.
+
+    Attributes:
+        weekday: Short day name (Mon-Sun)
+        days: Calendar days of this weekday in the range
+        active_days: Those days with any token usage
+        avg_tokens: Mean tokens per calendar day (idle days count as zero)
+        avg_cost: Mean estimated cost per calendar day
+    """
+
+    weekday: str
+    days: int
+    active_days: int
+    avg_tokens: float
+    avg_cost: float
+
+
+@dataclass
+class MonthlySummary:
+    """
+    Totals for one calendar month, compared with the month before.
+
+    Attributes:
+        month: Month in YYYY-MM format
+        total_tokens: Total tokens in the month
+        cost: Estimated API cost in dollars
+        active_days: Days with any token usage
+        sessions: Sum of daily session counts (a session spanning midnight
+            counts once per day)
+        tokens_change: Percent change in tokens vs previous month (None if
+            the previous month had no usage or is the first month)
+        cost_change: Percent change in cost vs previous month
+    """
+
+    month: str
+    total_tokens: int = 0
+    cost: float = 0.0
+    active_days: int = 0
+    sessions: int = 0
+    tokens_change: float | None = None
+    cost_change: float | None = None
 #endregion
 
 
 #region Functions
 
 
-def aggregate_by_day(records: list[UsageRecord]) -> dict[str, DailyStats]:
+def aggregate(records: Iterable[UsageRecord], group_by: GroupBy) -> dict[str, DailyStats]:
     """
-    Aggregate usage records by day.
+    Aggregate usage records by any GroupBy dimension in one pass.
 
-    Groups records by date and calculates totals for each metric.
+    Shared by the per-day, per-project, and per-model reports so each does
+    not keep its own grouping loop.
 
     Args:
-        records: List of usage records to aggregate
+        records: Usage records (list or iterator)
+        group_by: Dimension to group by
 
     Returns:
-        Dictionary mapping date strings (YYYY-MM-DD) to DailyStats objects
-
-    Raises:
-        ValueError: If records list is empty
+        Dictionary mapping group keys (see GroupBy) to DailyStats objects,
+        whose date field holds the key; keys appear in first-seen order
     """
-    if not records:
-        return {}
-
-    # Group records by date
-    daily_data: defaultdict[str, list[UsageRecord]] = defaultdict(list)
+    groups: dict[str, _StatsAccumulator] = {}
     for record in records:
-        daily_data[record.date_key].append(record)
+        key = group_by.key(record)
+        if key is None:
+            continue
+        group = groups.get(key)
+        if group is None:
+            group = groups[key] = _StatsAccumulator()
+        group.add(record)
+    return {key: group.to_stats(key) for key, group in groups.items()}
 
-    # Aggregate statistics for each day
-    daily_stats: dict[str, DailyStats] = {}
-    for date, day_records in daily_data.items():
-        daily_stats[date] = _calculate_day_stats(date, day_records)
 
-    return daily_stats
+def aggregate_by_day(records: list[UsageRecord]) -> dict[str, DailyStats]:
+    """
+    Aggregate usage records by day.
+
+    Args:
+        records: List of usage records to aggregate
+
+    Returns:
+        Dictionary mapping date strings (YYYY-MM-DD) to DailyStats objects
+    """
+    return aggregate(records, GroupBy.DAY)
 
 
 def calculate_overall_stats(records: list[UsageRecord]) -> DailyStats:
@@ -120,22 +234,178 @@ def calculate_overall_stats(records: list[UsageRecord]) -> DailyStats:
     return _calculate_day_stats("all", records)
 
 
-def aggregate_all(records: list[UsageRecord]) -> AggregatedStats:
+def aggregate_all(records: Iterable[UsageRecord]) -> AggregatedStats:
     """
     Create complete aggregated statistics from usage records.
 
+    Single pass, so records may be a stream (storage.api.iter_historical_records)
+    that is never held in memory all at once.
+
     Args:
-        records: List of all usage records
+        records: Usage records (list or iterator)
 
     Returns:
         AggregatedStats object with daily and overall totals
     """
+    days: dict[str, _StatsAccumulator] = {}
+    overall = _StatsAccumulator()
+    for record in records:
+        day = days.get(record.date_key)
+        if day is None:
+            day = days[record.date_key] = _StatsAccumulator()
+        day.add(record)
+        overall.add(record)
+
     return AggregatedStats(
-        daily_stats=aggregate_by_day(records),
-        overall_totals=calculate_overall_stats(records),
+        daily_stats={date: day.to_stats(date) for date, day in days.items()},
+        overall_totals=overall.to_stats("all"),
     )
 
 
+def aggregate_daily_snapshots(days: list[dict]) -> AggregatedStats:
+    """
+    Build aggregated statistics straight from stored daily snapshots.
+
+    The fast path for exports: no per-message records are loaded, and the
+    per-day model mix supplies models and cost.
+
+    Args:
+        days: Rows from storage.api.load_daily_snapshots
+
+    Returns:
+        AggregatedStats object with daily and overall totals
+    """
+    daily_stats: dict[str, DailyStats] = {}
+    for day in days:
+        daily_stats[day["date"]] = DailyStats(
+            date=day["date"],
+            total_prompts=day["prompts"],
+            total_responses=day["responses"],
+            total_sessions=day["sessions"],
+            total_tokens=day["total_tokens"],
+            input_tokens=day["input_tokens"],
+            output_tokens=day["output_tokens"],
+            cache_creation_tokens=day["cache_creation_tokens"],
+            cache_read_tokens=day["cache_read_tokens"],
+            models=set(day["models"]),
+            folders=set(),
+            cost=day["cost"],
+        )
+
+    days_list = list(daily_stats.values())
+    overall = DailyStats(
+        date="all",
+        total_prompts=sum(d.total_prompts for d in days_list),
+        total_responses=sum(d.total_responses for d in days_list),
+        total_sessions=sum(d.total_sessions for d in days_list),
+        total_tokens=sum(d.total_tokens for d in days_list),
+        input_tokens=sum(d.input_tokens for d in days_list),
+        output_tokens=sum(d.output_tokens for d in days_list),
+        cache_creation_tokens=sum(d.cache_creation_tokens for d in days_list),
+        cache_read_tokens=sum(d.cache_read_tokens for d in days_list),
+        models=set().union(*(d.models for d in days_list)),
+        folders=set(),
+        cost=sum(d.cost for d in days_list),
+    )
+    return AggregatedStats(daily_stats=daily_stats, overall_totals=overall)
+
+
+def average_by_weekday(
+    daily_stats: dict[str, DailyStats],
+    start_date: str | None = None,
+    end_date: str | None = None,
+) -> list[WeekdayAverage]:
+    """
+    Average tokens and cost per weekday across a date range.
+
+    Every calendar day in the range counts, so an idle Tuesday pulls the
+    Tuesday average down instead of being skipped.
+
+    Args:
+        daily_stats: Dictionary of daily statistics (cost set when built
+            from daily snapshots)
+        start_date: Inclusive start (YYYY-MM-DD); defaults to earliest day
+        end_date: Inclusive end (YYYY-MM-DD); defaults to latest day
+
+    Returns:
+        Seven WeekdayAverage entries, Monday first (empty if no data)
+    """
+    if not daily_stats:
+        return []
+
+    start = datetime.strptime(start_date or min(daily_stats), "%Y-%m-%d").date()
+    end = datetime.strptime(end_date or max(daily_stats), "%Y-%m-%d").date()
+
+    days = [0] * 7
+    active = [0] * 7
+    tokens = [0] * 7
+    cost = [0.0] * 7
+    current = start
+    while current <= end:
+        weekday = current.weekday()
+        days[weekday] += 1
+        day = daily_stats.get(current.strftime("%Y-%m-%d"))
+        if day and day.total_tokens > 0:
+            active[weekday] += 1
+            tokens[weekday] += day.total_tokens
+            cost[weekday] += day.cost
+        current += timedelta(days=1)
+
+    return [
+        WeekdayAverage(
+            weekday=WEEKDAY_NAMES[i],
+            days=days[i],
+            active_days=active[i],
+            avg_tokens=tokens[i] / days[i] if days[i] else 0.0,
+            avg_cost=cost[i] / days[i] if days[i] else 0.0,
+        )
+        for i in range(7)
+    ]
+
+
+def summarize_by_month(daily_stats: dict[str, DailyStats]) -> list[MonthlySummary]:
+    """
+    Roll daily statistics up into calendar months.
+
+    Months without usage between the first and last tracked month are kept
+    (as zeros) so each change compares against the calendar month before.
+
+    Args:
+        daily_stats: Dictionary of daily statistics
+
+    Returns:
+        MonthlySummary objects, oldest month first (empty if no data)
+    """
+    if not daily_stats:
+        return []
+
+    months: dict[str, MonthlySummary] = {}
+    for date, day in daily_stats.items():
+        summary = months.setdefault(date[:7], MonthlySummary(month=date[:7]))
+        summary.total_tokens += day.total_tokens
+        summary.cost += day.cost
+        summary.sessions += day.total_sessions
+        if day.total_tokens > 0:
+            summary.active_days += 1
+
+    year, month = map(int, min(months).split("-"))
+    last = max(months)
+    result: list[MonthlySummary] = []
+    while True:
+        key = f"{year:04d}-{month:02d}"
+        summary = months.get(key) or MonthlySummary(month=key)
+        if result:
+            previous = result[-1]
+            summary.tokens_change = _percent_change(previous.total_tokens, summary.total_tokens)
+            summary.cost_change = _percent_change(previous.cost, summary.cost)
+        result.append(summary)
+        if key == last:
+            break
+        year, month = (year + 1, 1) if month == 12 else (year, month + 1)
+
+    return result
+
+
 def get_date_range(daily_stats: dict[str, DailyStats], days: int = 365) -> list[str]:
     """
     Get a list of dates for the specified range, ending today.
@@ -165,6 +435,63 @@ def get_date_range(daily_stats: dict[str, DailyStats], days: int = 365) -> list[
     return date_range
 
 
+class _StatsAccumulator:
+    """Running totals for one DailyStats, fed one record at a time."""
+
+    def __init__(self) -> None:
+        self.sessions: set[str] = set()
+        self.models: set[str] = set()
+        self.folders: set[str] = set()
+        self.prompts = 0
+        self.responses = 0
+        self.total_tokens = 0
+        self.input_tokens = 0
+        self.output_tokens = 0
+        self.cache_creation_tokens = 0
+        self.cache_read_tokens = 0
+        self.buckets: set[int] = set()
+
+    def add(self, record: UsageRecord) -> None:
+        self.sessions.add(record.session_id)
+        if record.model:
+            self.models.add(record.model)
+        self.folders.add(record.folder)
+
+        # Aggregate-mode synthetic day records carry a placeholder timestamp
+        if record.folder != "aggregate":
+            self.buckets.add(int(record.timestamp.timestamp() // (ACTIVITY_BUCKET_MINUTES * 60)))
+
+        # Count message types separately
+        if record.is_user_prompt:
+            self.prompts += 1
+        elif record.is_assistant_response:
+            self.responses += 1
+
+        # Token usage only available on assistant responses
+        if record.token_usage:
+            self.total_tokens += record.token_usage.total_tokens
+            self.input_tokens += record.token_usage.input_tokens
+            self.output_tokens += record.token_usage.output_tokens
+            self.cache_creation_tokens += record.token_usage.cache_creation_tokens
+            self.cache_read_tokens += record.token_usage.cache_read_tokens
+
+    def to_stats(self, date: str) -> DailyStats:
+        return DailyStats(
+            date=date,
+            total_prompts=self.prompts,
+            total_responses=self.responses,
+            total_sessions=len(self.sessions),
+            total_tokens=self.total_tokens,
+            input_tokens=self.input_tokens,
+            output_tokens=self.output_tokens,
+            cache_creation_tokens=self.cache_creation_tokens,
+            cache_read_tokens=self.cache_read_tokens,
+            models=self.models,
+            folders=self.folders,
+            active_hours=len(self.buckets) * ACTIVITY_BUCKET_MINUTES / 60,
+        )
+
+
 def _calculate_day_stats(date: str, records: list[UsageRecord]) -> DailyStats:
     """
     Calculate statistics for a single day's records.
@@ -176,49 +503,17 @@ def _calculate_day_stats(date: str, records: list[UsageRecord]) -> DailyStats:
     Returns:
         DailyStats object with aggregated metrics
     """
-    unique_sessions = set()
-    models = set()
-    folders = set()
-
-    total_prompts = 0
-    total_responses = 0
-    total_tokens = 0
-    input_tokens = 0
-    output_tokens = 0
-    cache_creation_tokens = 0
-    cache_read_tokens = 0
-
+    accumulator = _StatsAccumulator()
     for record in records:
-        unique_sessions.add(record.session_id)
-        if record.model:
-            models.add(record.model)
-        folders.add(record.folder)
+        accumulator.add(record)
+    return accumulator.to_stats(date)
 
-        # Count message types separately
-        if record.is_user_prompt:
-            total_prompts += 1
-        elif record.is_assistant_response:
-            total_responses += 1
 
-        # Token usage only available on assistant responses
-        if record.token_usage:
-            total_tokens += record.token_usage.total_tokens
-            input_tokens += record.token_usage.input_tokens
-            output_tokens += record.token_usage.output_tokens
-            cache_creation_tokens += record.token_usage.cache_creation_tokens
-            cache_read_tokens += record.token_usage.cache_read_tokens
-
-    return DailyStats(
-        date=date,
-        total_prompts=total_prompts,
-        total_responses=total_responses,
-        total_sessions=len(unique_sessions),
-        total_tokens=total_tokens,
-        input_tokens=input_tokens,
-        output_tokens=output_tokens,
-        cache_creation_tokens=cache_creation_tokens,
-        cache_read_tokens=cache_read_tokens,
-        models=models,
-        folders=folders,
-    )
+def _percent_change(previous: float, current: float) -> float | None:
+    """
+    Percent change from previous to current, or None if previous is zero.
+    """
+    if not previous:
+        return None
+    return (current - previous) / previous * 100
 #endregion
@@ -0,0 +1,204 @@
+"""
+Error, interruption, and compaction statistics for `ccg stats`.
+
+Summarizes the session_events table: API errors Claude Code retried or gave
+up on, rate-limit hits, turns the user interrupted, the tokens those failed
+turns had already used, and how quickly sessions exhaust their context.
+"""
+#region Imports
+from collections import Counter
+from dataclasses import dataclass, field
+from datetime import datetime
+
+#endregion
+
+
+#region Constants
+# Consecutive API errors in one session that make a retry storm
+RETRY_STORM_MIN_ERRORS = 3
+
+# Largest gap (seconds) between errors of the same storm
+RETRY_STORM_GAP_SECONDS = 120
+
+# Most frequent error messages listed
+TOP_ERROR_DETAILS = 3
+
+# A project exhausts its context chronically when its compacted sessions
+# average this many compactions, over at least CHRONIC_MIN_COMPACTIONS
+CHRONIC_COMPACTIONS_PER_SESSION = 2.0
+CHRONIC_MIN_COMPACTIONS = 3
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class EventSummary:
+    """
+    Error and interruption totals over a set of session events.
+
+    Attributes:
+        api_errors: API errors (retried or not), excluding rate limits
+        rate_limits: Rate- or usage-limit errors
+        interruptions: Turns the user interrupted
+        failed_turns: Turns that ended in an interruption or an error
+        burned_tokens: Tokens used by failed turns before they failed
+        retry_storms: Runs of RETRY_STORM_MIN_ERRORS+ errors in one session,
+            each within RETRY_STORM_GAP_SECONDS of the previous
+        sessions_affected: Sessions with any event
+        top_errors: (detail, count) for the most frequent errors
+    """
+
+    api_errors: int = 0
+    rate_limits: int = 0
+    interruptions: int = 0
+    failed_turns: int = 0
+    burned_tokens: int = 0
+    retry_storms: int = 0
+    sessions_affected: int = 0
+    top_errors: list[tuple[str, int]] = field(default_factory=list)
+
+
+@dataclass
+class ChronicProject:
+    """
+    A project whose sessions keep running out of context.
+
+    Attributes:
+        folder: Project folder path
+        compactions: Compactions in the project
+        sessions: Sessions with at least one compaction
+    """
+
+    folder: str
+    compactions: int
+    sessions: int
+
+    @property
+    def per_session(self) -> float:
+        """Average compactions per compacted session."""
+        return self.compactions / self.sessions
+
+
+@dataclass
+class CompactionSummary:
+    """
+    Context compaction totals over a set of session events.
+
+    Attributes:
+        compactions: Compactions (automatic and manual /compact)
+        auto: Compactions Claude Code triggered when the context filled
+        sessions: Sessions with at least one compaction
+        avg_messages: Average messages until a compaction (None when no
+            compaction has a message count)
+        avg_context_tokens: Average context size when compacted
+        chronic_projects: Projects that compact chronically, worst first
+    """
+
+    compactions: int = 0
+    auto: int = 0
+    sessions: int = 0
+    avg_messages: float | None = None
+    avg_context_tokens: float = 0.0
+    chronic_projects: list[ChronicProject] = field(default_factory=list)
+#endregion
+
+
+#region Functions
+
+
+def summarize_events(events: list[dict]) -> EventSummary:
+    """
+    Total the session events from storage.
+
+    Args:
+        events: Rows from storage.api.get_session_events, ordered by
+            session then timestamp
+
+    Returns:
+        EventSummary (all zero when there are no events)
+    """
+    summary = EventSummary()
+    details: Counter[str] = Counter()
+    sessions: set[str] = set()
+    storm_length = 0
+    last_error: tuple[str, datetime] | None = None
+
+    for event in events:
+        if event["event_type"] == "compaction":
+            continue
+        sessions.add(event["session_id"])
+        if event["ends_turn"]:
+            summary.failed_turns += 1
+            summary.burned_tokens += event["turn_tokens"] or 0
+        if event["event_type"] == "interrupted":
+            summary.interruptions += 1
+            continue
+
+        if event["event_type"] == "rate_limit":
+            summary.rate_limits += 1
+        else:
+            summary.api_errors += 1
+        details[event["detail"]] += 1
+
+        timestamp = datetime.fromisoformat(event["timestamp"])
+        if (
+            last_error is not None
+            and last_error[0] == event["session_id"]
+            and (timestamp - last_error[1]).total_seconds() <= RETRY_STORM_GAP_SECONDS
+        ):
+            storm_length += 1
+        else:
+            storm_length = 1
+        if storm_length == RETRY_STORM_MIN_ERRORS:
+            summary.retry_storms += 1
+        last_error = (event["session_id"], timestamp)
+
+    summary.sessions_affected = len(sessions)
+    summary.top_errors = details.most_common(TOP_ERROR_DETAILS)
+    return summary
+
+
+def summarize_compactions(events: list[dict]) -> CompactionSummary:
+    """
+    Total the compaction events from storage.
+
+    Args:
+        events: Rows from storage.api.get_session_events (other event types
+            are ignored)
+
+    Returns:
+        CompactionSummary (all zero when nothing was compacted)
+    """
+    compactions = [e for e in events if e["event_type"] == "compaction"]
+    summary = CompactionSummary(compactions=len(compactions))
+    if not compactions:
+        return summary
+
+    summary.auto = sum(1 for e in compactions if e["detail"] == "auto")
+    summary.sessions = len({e["session_id"] for e in compactions})
+    counted = [e["messages_before"] for e in compactions if e["messages_before"] is not None]
+    if counted:
+        summary.avg_messages = sum(counted) / len(counted)
+    summary.avg_context_tokens = sum(e["context_tokens"] or 0 for e in compactions) / len(compactions)
+
+    by_project: dict[str, list[str]] = {}
+    for event in compactions:
+        by_project.setdefault(event["folder"], []).append(event["session_id"])
+    projects = [
+        ChronicProject(folder, len(session_ids), len(set(session_ids)))
+        for folder, session_ids in by_project.items()
+    ]
+    summary.chronic_projects = sorted(
+        (
+            p for p in projects
+            if p.compactions >= CHRONIC_MIN_COMPACTIONS
+            and p.per_session >= CHRONIC_COMPACTIONS_PER_SESSION
+        ),
+        key=lambda p: (-p.per_session, -p.compactions, p.folder),
+    )
+    return summary
+
+
+#endregion
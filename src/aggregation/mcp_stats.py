@@ -0,0 +1,90 @@
+"""
+MCP server statistics for `ccg stats --mcp`.
+
+Totals the mcp_calls table per server: how often each server's tools are
+called, in how many sessions, and roughly how many tokens the calls and
+their results move through the context.
+"""
+#region Imports
+from collections import Counter, defaultdict
+from dataclasses import dataclass
+
+#endregion
+
+
+#region Constants
+# Rough characters per token for tool result text
+CHARS_PER_RESULT_TOKEN = 4
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class McpServerStats:
+    """
+    Usage of one MCP server.
+
+    Attributes:
+        server: Server name (from "mcp__<server>__<tool>")
+        calls: Tool invocations
+        sessions: Sessions that called the server
+        call_tokens: Output tokens spent writing the calls (estimated share)
+        result_tokens: Estimated tokens of tool results fed back as input
+        top_tool: Most called tool and its count
+    """
+
+    server: str
+    calls: int
+    sessions: int
+    call_tokens: int
+    result_tokens: int
+    top_tool: tuple[str, int]
+
+    @property
+    def total_tokens(self) -> int:
+        """Estimated token traffic in both directions."""
+        return self.call_tokens + self.result_tokens
+#endregion
+
+
+#region Functions
+
+
+def summarize_mcp_calls(calls: list[dict]) -> list[McpServerStats]:
+    """
+    Total MCP calls per server.
+
+    Args:
+        calls: Rows from storage.api.get_mcp_calls
+
+    Returns:
+        One entry per server, most called first
+    """
+    tools: dict[str, Counter[str]] = defaultdict(Counter)
+    sessions: dict[str, set[str]] = defaultdict(set)
+    call_tokens: Counter[str] = Counter()
+    result_chars: Counter[str] = Counter()
+    for call in calls:
+        server = call["server"]
+        tools[server][call["tool"]] += 1
+        sessions[server].add(call["session_id"])
+        call_tokens[server] += call["call_tokens"] or 0
+        result_chars[server] += call["result_chars"] or 0
+
+    servers = [
+        McpServerStats(
+            server=server,
+            calls=sum(counts.values()),
+            sessions=len(sessions[server]),
+            call_tokens=call_tokens[server],
+            result_tokens=result_chars[server] // CHARS_PER_RESULT_TOKEN,
+            top_tool=counts.most_common(1)[0],
+        )
+        for server, counts in tools.items()
+    ]
+    return sorted(servers, key=lambda s: (-s.calls, s.server))
+
+
+#endregion
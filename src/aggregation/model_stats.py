@@ -0,0 +1,152 @@
+#region Imports
+from collections.abc import Iterable
+from dataclasses import dataclass
+from typing import TYPE_CHECKING
+
+from src.models.usage_record import UsageRecord
+
+if TYPE_CHECKING:
+    from src.storage.pricing import PricingTable
+
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class ModelDay:
+    """
+    Token usage and estimated cost of one model on one day.
+
+    Attributes:
+        date: Day (YYYY-MM-DD, local time), or "all" for totals
+        model: Model name
+        responses: Assistant responses
+        input_tokens: Input tokens
+        output_tokens: Output tokens
+        cache_creation_tokens: Cache write tokens (5m and 1h)
+        cache_read_tokens: Cache read tokens
+        cache_creation_1h_tokens: Part of cache_creation_tokens with a 1h TTL
+        cost: Estimated API cost in dollars
+    """
+
+    date: str
+    model: str
+    responses: int = 0
+    input_tokens: int = 0
+    output_tokens: int = 0
+    cache_creation_tokens: int = 0
+    cache_read_tokens: int = 0
+    cache_creation_1h_tokens: int = 0
+    cost: float = 0.0
+
+    @property
+    def total_tokens(self) -> int:
+        """Tokens across all categories."""
+        return self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+
+    def add(self, other: "ModelDay") -> None:
+        """Add another row's responses, tokens, and cost to this one."""
+        self.responses += other.responses
+        self.input_tokens += other.input_tokens
+        self.output_tokens += other.output_tokens
+        self.cache_creation_tokens += other.cache_creation_tokens
+        self.cache_read_tokens += other.cache_read_tokens
+        self.cache_creation_1h_tokens += other.cache_creation_1h_tokens
+        self.cost += other.cost
+
+
+#endregion
+
+
+#region Functions
+
+
+def aggregate_by_model_day(records: Iterable[UsageRecord], pricing: "PricingTable") -> list[ModelDay]:
+    """
+    Tokens and cost per model per day from usage records.
+
+    Single pass, so records may be a stream. Cost honors long-context rates
+    per request (UsageRecord.cost).
+
+    Args:
+        records: Usage records (list or iterator); only assistant responses count
+        pricing: Pricing lookup (storage.api.get_pricing_table)
+
+    Returns:
+        One ModelDay per (date, model), by date then most tokens first
+    """
+    days: dict[tuple[str, str], ModelDay] = {}
+    for record in records:
+        if not record.is_assistant_response:
+            continue
+        model = record.model or "unknown"
+        day = days.get((record.date_key, model))
+        if day is None:
+            day = days[(record.date_key, model)] = ModelDay(record.date_key, model)
+        day.responses += 1
+        if record.token_usage:
+            day.input_tokens += record.token_usage.input_tokens
+            day.output_tokens += record.token_usage.output_tokens
+            day.cache_creation_tokens += record.token_usage.cache_creation_tokens
+            day.cache_read_tokens += record.token_usage.cache_read_tokens
+            day.cache_creation_1h_tokens += record.token_usage.cache_creation_1h_tokens
+            day.cost += record.token_usage.cost(pricing, record.model)
+    return sorted(days.values(), key=lambda day: (day.date, -day.total_tokens, day.model))
+
+
+def model_days_from_snapshots(days: list[dict], pricing: "PricingTable") -> list[ModelDay]:
+    """
+    Tokens and cost per model per day from stored daily snapshots.
+
+    The fast path: reads the daily_model_snapshots rollup that ingestion
+    keeps in both storage modes, so no usage_records or JSONL are needed.
+    The rollup keeps no per-request sizes, so all tokens bill at standard
+    (not long-context) rates.
+
+    Args:
+        days: Rows from storage.api.load_daily_snapshots
+        pricing: Pricing lookup (storage.api.get_pricing_table)
+
+    Returns:
+        One ModelDay per (date, model), by date then most tokens first
+    """
+    model_days = []
+    for day in days:
+        for model, mix in day["models"].items():
+            model_day = ModelDay(
+                date=day["date"],
+                model=model,
+                responses=mix.get("responses", 0) or 0,
+                input_tokens=mix.get("input_tokens", 0) or 0,
+                output_tokens=mix.get("output_tokens", 0) or 0,
+                cache_creation_tokens=mix.get("cache_creation_tokens", 0) or 0,
+                cache_read_tokens=mix.get("cache_read_tokens", 0) or 0,
+                cache_creation_1h_tokens=mix.get("cache_creation_1h_tokens", 0) or 0,
+            )
+            model_day.cost = pricing.cost(
+                model, model_day.input_tokens, model_day.output_tokens, model_day.cache_creation_tokens,
+                model_day.cache_read_tokens, model_day.cache_creation_1h_tokens,
+            )
+            model_days.append(model_day)
+    return sorted(model_days, key=lambda day: (day.date, -day.total_tokens, day.model))
+
+
+def totals_by_model(model_days: Iterable[ModelDay]) -> list[ModelDay]:
+    """
+    Sum per-day rows into one "all" row per model.
+
+    Args:
+        model_days: Output of aggregate_by_model_day or model_days_from_snapshots
+
+    Returns:
+        One ModelDay per model (date "all"), most tokens first
+    """
+    totals: dict[str, ModelDay] = {}
+    for day in model_days:
+        totals.setdefault(day.model, ModelDay("all", day.model)).add(day)
+    return sorted(totals.values(), key=lambda total: (-total.total_tokens, total.model))
+
+
+#endregion
@@ -0,0 +1,175 @@
+#region Imports
+from collections.abc import Iterable
+from dataclasses import dataclass
+from datetime import date, datetime
+from typing import TYPE_CHECKING
+
+from src.models.usage_record import UsageRecord
+
+if TYPE_CHECKING:
+    from src.storage.pricing import PricingTable
+
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class ProjectActivity:
+    """
+    Lifetime usage and activity bounds for one project folder.
+
+    Attributes:
+        folder: Project folder path (or Claude's encoded folder name in
+            aggregate storage mode)
+        first_date: First day with activity (YYYY-MM-DD)
+        last_date: Most recent day with activity (YYYY-MM-DD)
+        active_days: Days with any activity
+        sessions: Number of sessions
+        prompts: Number of user prompts
+        total_tokens: Total tokens across all categories
+        cost: Estimated API cost in dollars
+    """
+
+    folder: str
+    first_date: str
+    last_date: str
+    active_days: int = 0
+    sessions: int = 0
+    prompts: int = 0
+    total_tokens: int = 0
+    cost: float = 0.0
+
+    def days_idle(self, today: date) -> int:
+        """Whole days since the last activity."""
+        return (today - datetime.strptime(self.last_date, "%Y-%m-%d").date()).days
+
+
+@dataclass
+class ProjectBudget:
+    """
+    Monthly cost budget for one project folder and its spend so far.
+
+    Attributes:
+        folder: Budgeted folder (covers everything below it too)
+        budget: Monthly budget in dollars
+        spent: Estimated API cost this month in dollars
+    """
+
+    folder: str
+    budget: float
+    spent: float = 0.0
+
+    @property
+    def used(self) -> float:
+        """Fraction of the budget spent (above 1.0 when over)."""
+        return self.spent / self.budget if self.budget > 0 else 0.0
+
+    @property
+    def over_budget(self) -> bool:
+        return self.spent > self.budget
+#endregion
+
+
+#region Functions
+
+
+def aggregate_by_project(records: Iterable[UsageRecord], pricing: "PricingTable") -> list[ProjectActivity]:
+    """
+    Tokens, prompts, sessions, and estimated cost per project folder.
+
+    Single pass, so records may be a stream (storage.api.iter_historical_records).
+
+    Args:
+        records: Usage records (list or iterator)
+        pricing: Pricing lookup (storage.api.get_pricing_table)
+
+    Returns:
+        One ProjectActivity per folder, costliest first (tokens break ties)
+    """
+    projects: dict[str, ProjectActivity] = {}
+    sessions: dict[str, set[str]] = {}
+    days: dict[str, set[str]] = {}
+    for record in records:
+        day = record.date_key
+        project = projects.get(record.folder)
+        if project is None:
+            project = projects[record.folder] = ProjectActivity(record.folder, day, day)
+            sessions[record.folder], days[record.folder] = set(), set()
+        project.first_date = min(project.first_date, day)
+        project.last_date = max(project.last_date, day)
+        sessions[record.folder].add(record.session_id)
+        days[record.folder].add(day)
+        if record.is_user_prompt:
+            project.prompts += 1
+        if record.token_usage:
+            project.total_tokens += record.token_usage.total_tokens
+            project.cost += record.token_usage.cost(pricing, record.model)
+
+    for folder, project in projects.items():
+        project.sessions = len(sessions[folder])
+        project.active_days = len(days[folder])
+    return sorted(projects.values(), key=lambda p: (-p.cost, -p.total_tokens, p.folder))
+
+
+def find_idle_projects(
+    projects: list[dict],
+    idle_days: int,
+    today: date | None = None,
+) -> list[ProjectActivity]:
+    """
+    Projects with no activity in the last idle_days days.
+
+    Args:
+        projects: Rows from storage.api.get_project_activity
+        idle_days: Minimum days since last activity
+        today: Reference date (default: today)
+
+    Returns:
+        Idle ProjectActivity objects, longest idle first (cost breaks ties)
+    """
+    today = today or datetime.now().date()
+    idle = [
+        project for project in (ProjectActivity(**row) for row in projects)
+        if project.days_idle(today) >= idle_days
+    ]
+    return sorted(idle, key=lambda p: (p.last_date, -p.cost))
+
+
+def check_project_budgets(
+    budgets: dict[str, float],
+    daily_projects: dict[str, dict[str, dict]],
+) -> list[ProjectBudget]:
+    """
+    Spend against each project budget.
+
+    A project folder counts toward a budget when it is the budgeted folder
+    or below it; with nested budgets it counts toward each of them.
+
+    Args:
+        budgets: Folder -> monthly budget (config get_project_budgets)
+        daily_projects: Per-day project costs for the budget month
+            (storage.api.get_daily_projects)
+
+    Returns:
+        ProjectBudget objects, most used first
+    """
+    folder_costs: dict[str, float] = {}
+    for projects in daily_projects.values():
+        for folder, values in projects.items():
+            folder_costs[folder] = folder_costs.get(folder, 0.0) + values.get("cost", 0.0)
+
+    results = []
+    for folder, budget in budgets.items():
+        root = folder.replace("\\", "/").rstrip("/")
+        spent = 0.0
+        for project, cost in folder_costs.items():
+            path = project.replace("\\", "/").rstrip("/")
+            if path == root or path.startswith(root + "/"):
+                spent += cost
+        results.append(ProjectBudget(folder=folder, budget=budget, spent=spent))
+    return sorted(results, key=lambda b: b.used, reverse=True)
+
+
+#endregion
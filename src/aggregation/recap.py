@@ -0,0 +1,317 @@
+"""
+Monthly recap for `ccg recap`.
+
+Condenses one calendar month of daily snapshots into a handful of facts
+(MonthRecap) and renders them as templated sentences, compared against the
+month before.
+"""
+#region Imports
+import calendar
+from dataclasses import asdict, dataclass, field
+from datetime import date, datetime, timedelta
+
+from src.aggregation.daily_stats import DailyStats
+from src.utils.folders import short_folder
+
+#endregion
+
+
+#region Constants
+# Projects named in the recap
+RECAP_TOP_PROJECTS = 3
+
+# Share shift (percentage points) before a model mix change is called out
+MODEL_SHIFT_POINTS = 10.0
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class MonthRecap:
+    """
+    Facts behind one month's recap.
+
+    Attributes:
+        month: Month in YYYY-MM format
+        total_tokens: Tokens used in the month
+        cost: Estimated API cost in the month
+        active_days: Days with any token usage
+        days_in_month: Calendar days in the month
+        sessions: Sessions started in the month (summed per day)
+        prompts: User prompts in the month
+        biggest_day: Date with the most tokens (None if idle all month)
+        biggest_day_tokens: Tokens on biggest_day
+        longest_streak: Longest run of consecutive active days
+        streak_start: First day of that run (None if no active days)
+        top_projects: (project folder, tokens) pairs, largest first
+        model_shares: Model -> percentage of the month's tokens
+    """
+
+    month: str
+    total_tokens: int = 0
+    cost: float = 0.0
+    active_days: int = 0
+    days_in_month: int = 0
+    sessions: int = 0
+    prompts: int = 0
+    biggest_day: str | None = None
+    biggest_day_tokens: int = 0
+    longest_streak: int = 0
+    streak_start: str | None = None
+    top_projects: list[tuple[str, int]] = field(default_factory=list)
+    model_shares: dict[str, float] = field(default_factory=dict)
+
+    def to_dict(self) -> dict:
+        """JSON-serializable form, stored in monthly_summaries."""
+        return asdict(self)
+
+    @classmethod
+    def from_dict(cls, data: dict) -> "MonthRecap":
+        """Inverse of to_dict (JSON turns the project tuples into lists)."""
+        data = dict(data)
+        data["top_projects"] = [tuple(pair) for pair in data.get("top_projects", [])]
+        return cls(**data)
+#endregion
+
+
+#region Functions
+
+
+def month_bounds(month: str) -> tuple[str, str]:
+    """
+    First and last day of a month.
+
+    Args:
+        month: Month in YYYY-MM format
+
+    Returns:
+        (start, end) dates in YYYY-MM-DD format
+
+    Raises:
+        ValueError: If month is not YYYY-MM
+    """
+    first = datetime.strptime(month, "%Y-%m").date()
+    last = first.replace(day=calendar.monthrange(first.year, first.month)[1])
+    return first.isoformat(), last.isoformat()
+
+
+def previous_month(month: str) -> str:
+    """The month before a YYYY-MM month, in the same format."""
+    first = datetime.strptime(month, "%Y-%m").date()
+    return (first - timedelta(days=1)).strftime("%Y-%m")
+
+
+def build_month_recap(month: str, days: list[dict], project_tokens: dict[str, int]) -> MonthRecap:
+    """
+    Collect the recap facts for one month.
+
+    Args:
+        month: Month in YYYY-MM format
+        days: Rows from storage.api.load_daily_snapshots for the month
+        project_tokens: Project folder -> tokens for the month
+            (storage.api.get_project_tokens)
+
+    Returns:
+        MonthRecap for the month
+    """
+    start, end = month_bounds(month)
+    recap = MonthRecap(month=month, days_in_month=int(end[-2:]))
+
+    model_tokens: dict[str, int] = {}
+    active_dates: set[str] = set()
+    for day in days:
+        if not start <= day["date"] <= end:
+            continue
+        recap.total_tokens += day["total_tokens"]
+        recap.cost += day["cost"]
+        recap.sessions += day["sessions"]
+        recap.prompts += day["prompts"]
+        if day["total_tokens"] > 0:
+            active_dates.add(day["date"])
+        if day["total_tokens"] > recap.biggest_day_tokens:
+            recap.biggest_day, recap.biggest_day_tokens = day["date"], day["total_tokens"]
+        for model, mix in day["models"].items():
+            model_tokens[model] = model_tokens.get(model, 0) + mix["total_tokens"]
+    recap.active_days = len(active_dates)
+    recap.longest_streak, recap.streak_start = longest_streak(active_dates, start, end)
+
+    model_total = sum(model_tokens.values())
+    if model_total:
+        recap.model_shares = {
+            model: tokens * 100 / model_total
+            for model, tokens in sorted(model_tokens.items(), key=lambda item: item[1], reverse=True)
+            if tokens
+        }
+
+    recap.top_projects = top_projects(project_tokens)
+    return recap
+
+
+def longest_streak(active_dates: set[str], start: str, end: str) -> tuple[int, str | None]:
+    """
+    Longest run of consecutive active days within [start, end].
+
+    Args:
+        active_dates: Dates (YYYY-MM-DD) with any usage
+        start: Inclusive start (YYYY-MM-DD)
+        end: Inclusive end (YYYY-MM-DD)
+
+    Returns:
+        (length, first day of the run), or (0, None) with no active days
+    """
+    best, best_start = 0, None
+    run_start, run = None, 0
+    current = date.fromisoformat(start)
+    while current.isoformat() <= end:
+        if current.isoformat() in active_dates:
+            run_start = run_start if run else current.isoformat()
+            run += 1
+            if run > best:
+                best, best_start = run, run_start
+        else:
+            run = 0
+        current += timedelta(days=1)
+    return best, best_start
+
+
+def current_streak(active_dates: set[str], today: str) -> tuple[int, str | None]:
+    """
+    Run of consecutive active days ending today.
+
+    A day without usage yet does not break the streak until it is over, so
+    the run may end yesterday instead (as on a GitHub contributions graph).
+
+    Args:
+        active_dates: Dates (YYYY-MM-DD) with any usage
+        today: The day the streak runs up to (YYYY-MM-DD)
+
+    Returns:
+        (length, first day of the run), or (0, None) when neither today nor
+        yesterday was active
+    """
+    current = date.fromisoformat(today)
+    if today not in active_dates:
+        current -= timedelta(days=1)
+    run, run_start = 0, None
+    while current.isoformat() in active_dates:
+        run, run_start = run + 1, current.isoformat()
+        current -= timedelta(days=1)
+    return run, run_start
+
+
+def activity_streaks(daily_stats: dict[str, DailyStats], start: str, end: str) -> tuple[int, int]:
+    """
+    Current and longest streaks of active days within [start, end].
+
+    Args:
+        daily_stats: Date (YYYY-MM-DD) -> DailyStats; days with tokens count
+        start: Inclusive start (YYYY-MM-DD)
+        end: Inclusive end (YYYY-MM-DD); the current streak runs up to it
+
+    Returns:
+        (current streak, longest streak) in days
+    """
+    active_dates = {day for day, stats in daily_stats.items() if stats.total_tokens > 0 and start <= day <= end}
+    return current_streak(active_dates, end)[0], longest_streak(active_dates, start, end)[0]
+
+
+def top_projects(project_tokens: dict[str, int], limit: int = RECAP_TOP_PROJECTS) -> list[tuple[str, int]]:
+    """Largest (project folder, tokens) pairs, skipping idle projects."""
+    return sorted(
+        ((folder, tokens) for folder, tokens in project_tokens.items() if tokens),
+        key=lambda item: item[1],
+        reverse=True,
+    )[:limit]
+
+
+def recap_sentences(recap: MonthRecap, previous: MonthRecap | None = None) -> list[str]:
+    """
+    Render a recap as narrative sentences.
+
+    Args:
+        recap: Facts for the month
+        previous: Facts for the month before, for comparisons
+
+    Returns:
+        Sentences in reading order (a single sentence for an idle month)
+    """
+    month_name = datetime.strptime(recap.month, "%Y-%m").strftime("%B %Y")
+    if not recap.total_tokens:
+        return [f"No Claude Code usage was recorded in {month_name}."]
+
+    opening = (
+        f"In {month_name} you used {format_tokens(recap.total_tokens)} tokens "
+        f"(about ${recap.cost:,.2f} at API prices) across {recap.sessions:,} sessions, "
+        f"active on {recap.active_days} of {recap.days_in_month} days"
+    )
+    if previous and previous.total_tokens:
+        change = (recap.total_tokens - previous.total_tokens) * 100 / previous.total_tokens
+        direction = "up" if change >= 0 else "down"
+        opening += f", {direction} {abs(change):.0f}% on the month before"
+    sentences = [opening + "."]
+
+    biggest = date.fromisoformat(recap.biggest_day)
+    sentences.append(
+        f"Your biggest day was {biggest.strftime('%A')} the {_ordinal(biggest.day)}, "
+        f"with {format_tokens(recap.biggest_day_tokens)} tokens."
+    )
+
+    if recap.top_projects:
+        named = [
+            f"{short_folder(folder)} ({tokens * 100 / recap.total_tokens:.0f}%)"
+            for folder, tokens in recap.top_projects
+        ]
+        lead = "Most of the work went into" if len(named) > 1 else "All of the work went into"
+        sentences.append(f"{lead} {_join(named)}.")
+
+    sentences.append(_model_sentence(recap, previous))
+
+    if recap.longest_streak > 1:
+        streak_start = date.fromisoformat(recap.streak_start)
+        sentences.append(
+            f"Your longest streak was {recap.longest_streak} days in a row, "
+            f"starting {streak_start.strftime('%b')} {streak_start.day}."
+        )
+    return sentences
+
+
+def _model_sentence(recap: MonthRecap, previous: MonthRecap | None) -> str:
+    top_model, top_share = next(iter(recap.model_shares.items()), ("an unknown model", 100.0))
+    if not previous or not previous.model_shares:
+        return f"{top_model} handled {top_share:.0f}% of the tokens."
+
+    # Ordered by this month's share, so a tie names the model that's ahead now
+    shifts = {
+        model: recap.model_shares.get(model, 0.0) - previous.model_shares.get(model, 0.0)
+        for model in dict.fromkeys([*recap.model_shares, *previous.model_shares])
+    }
+    model, shift = max(shifts.items(), key=lambda item: abs(item[1]))
+    if abs(shift) < MODEL_SHIFT_POINTS:
+        return f"The model mix held steady, led by {top_model} at {top_share:.0f}%."
+    before, after = previous.model_shares.get(model, 0.0), recap.model_shares.get(model, 0.0)
+    verb = "grew" if shift > 0 else "shrank"
+    return f"{model} {verb} from {before:.0f}% to {after:.0f}% of the tokens."
+
+
+def format_tokens(tokens: int) -> str:
+    """Compact token count (1.2K, 3.4M, 5.6B)."""
+    for threshold, suffix in ((1_000_000_000, "B"), (1_000_000, "M"), (1_000, "K")):
+        if tokens >= threshold:
+            return f"{tokens / threshold:.1f}{suffix}"
+    return str(tokens)
+
+
+def _ordinal(day: int) -> str:
+    suffix = "th" if 11 <= day % 100 <= 13 else {1: "st", 2: "nd", 3: "rd"}.get(day % 10, "th")
+    return f"{day}{suffix}"
+
+
+def _join(items: list[str]) -> str:
+    if len(items) < 3:
+        return " and ".join(items)
+    return ", ".join(items[:-1]) + f", and {items[-1]}"
+
+
+#endregion
@@ -0,0 +1,358 @@
+#region Imports
+from collections.abc import Iterable
+from dataclasses import dataclass, field
+from datetime import datetime, timedelta
+
+from src.models.usage_record import Session, UsageRecord
+from src.storage.pricing import PricingTable
+
+#endregion
+
+
+#region Constants
+# Longest session title kept from the first prompt
+SESSION_TITLE_CHARS = 80
+
+# A session counts as active between two of its messages at most this far
+# apart; a longer gap means the window sat idle
+SESSION_IDLE_GAP = timedelta(minutes=10)
+
+# Entrypoints of sessions started without a human at the prompt
+# (`claude -p`, the Agent SDKs)
+HEADLESS_ENTRYPOINT_PREFIX = "sdk"
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class SessionSummary:
+    """
+    Totals and estimated cost for one Claude Code session.
+
+    Attributes:
+        session_id: Session identifier
+        folder: Project folder the session ran in
+        start: Timestamp of the first record
+        end: Timestamp of the last record
+        prompts: Number of user prompts
+        responses: Number of assistant responses
+        total_tokens: Total tokens across all categories
+        models: Models used in the session
+        cost: Estimated API cost in dollars
+    """
+
+    session_id: str
+    folder: str
+    start: datetime
+    end: datetime
+    prompts: int = 0
+    responses: int = 0
+    total_tokens: int = 0
+    models: set[str] = field(default_factory=set)
+    cost: float = 0.0
+
+
+@dataclass
+class TagSummary:
+    """
+    Totals for the sessions carrying one tag.
+
+    Attributes:
+        tag: Tag label (None for untagged sessions)
+        sessions: Number of sessions with the tag
+        total_tokens: Total tokens across those sessions
+        cost: Estimated API cost in dollars
+    """
+
+    tag: str | None
+    sessions: int = 0
+    total_tokens: int = 0
+    cost: float = 0.0
+
+
+@dataclass
+class DailyConcurrency:
+    """
+    How many sessions were active at once on one day.
+
+    Attributes:
+        date: Local day in YYYY-MM-DD format
+        max_concurrent: Most sessions active at the same moment
+        avg_concurrent: Average sessions active while any was active
+        overlap_minutes: Minutes with two or more sessions active
+    """
+
+    date: str
+    max_concurrent: int
+    avg_concurrent: float
+    overlap_minutes: float
+
+
+@dataclass
+class AutomationSplit:
+    """
+    Sessions and tokens split into interactive and automated usage.
+
+    Attributes:
+        interactive_sessions: Sessions a person drove
+        interactive_tokens: Their total tokens
+        automated_sessions: Headless sessions plus sessions in automation folders
+        automated_tokens: Their total tokens
+        headless_sessions: Automated sessions detected by entrypoint
+        tagged_sessions: Automated sessions in a tagged automation folder
+    """
+
+    interactive_sessions: int = 0
+    interactive_tokens: int = 0
+    automated_sessions: int = 0
+    automated_tokens: int = 0
+    headless_sessions: int = 0
+    tagged_sessions: int = 0
+#endregion
+
+
+#region Functions
+
+
+def summarize_sessions(
+    records: Iterable[UsageRecord],
+    pricing_rows: Iterable[tuple],
+) -> list[SessionSummary]:
+    """
+    Summarize usage per session, pricing each session's model mix.
+
+    Single pass, so records may be a stream from iter_historical_records.
+
+    Args:
+        records: Usage records (list or iterator)
+        pricing_rows: Rows from storage.api.get_model_pricing
+
+    Returns:
+        SessionSummary objects, most expensive first (tokens break ties)
+    """
+    pricing = PricingTable(pricing_rows)
+    sessions: dict[str, SessionSummary] = {}
+
+    for record in records:
+        summary = sessions.get(record.session_id)
+        if summary is None:
+            summary = sessions[record.session_id] = SessionSummary(
+                session_id=record.session_id,
+                folder=record.folder,
+                start=record.timestamp,
+                end=record.timestamp,
+            )
+        summary.start = min(summary.start, record.timestamp)
+        summary.end = max(summary.end, record.timestamp)
+
+        if record.is_user_prompt:
+            summary.prompts += 1
+        elif record.is_assistant_response:
+            summary.responses += 1
+
+        if record.token_usage:
+            summary.total_tokens += record.token_usage.total_tokens
+            summary.cost += record.cost(pricing)
+            if record.model:
+                summary.models.add(record.model)
+
+    return sorted(sessions.values(), key=lambda s: (s.cost, s.total_tokens), reverse=True)
+
+
+def _prompt_title(text: str | None) -> str | None:
+    """First prompt text as a one-line title, or None for non-prompt content."""
+    if not text:
+        return None
+    title = " ".join(text.split())
+    # Slash commands, hook output, and interruptions are not what the user asked
+    if not title or title.startswith(("<", "[Request interrupted")):
+        return None
+    if len(title) > SESSION_TITLE_CHARS:
+        title = title[:SESSION_TITLE_CHARS - 1].rstrip() + "…"
+    return title
+
+
+def build_sessions(records: Iterable[UsageRecord], titles: bool = False) -> list[Session]:
+    """
+    Session metadata for the sessions present in a batch of records.
+
+    Args:
+        records: Usage records (list or iterator), e.g. one ingestion run
+        titles: Keep a snippet of each session's first prompt as its title
+
+    Returns:
+        Session objects ordered by start time
+    """
+    sessions: dict[str, dict] = {}
+    for record in sorted(records, key=lambda r: r.timestamp):
+        session = sessions.get(record.session_id)
+        if session is None:
+            session = sessions[record.session_id] = {
+                "session_id": record.session_id, "folder": record.folder,
+                "start": record.timestamp, "end": record.timestamp,
+                "messages": 0, "total_tokens": 0, "title": None, "entrypoint": None,
+            }
+        session["end"] = record.timestamp
+        session["messages"] += 1
+        if record.token_usage:
+            session["total_tokens"] += record.token_usage.total_tokens
+        if titles and session["title"] is None and record.is_user_prompt:
+            session["title"] = _prompt_title(record.content)
+        if session["entrypoint"] is None:
+            session["entrypoint"] = record.entrypoint
+    return [Session(**session) for session in sessions.values()]
+
+
+def concurrency_by_day(
+    records: Iterable[UsageRecord],
+    idle_gap: timedelta = SESSION_IDLE_GAP,
+) -> list[DailyConcurrency]:
+    """
+    Concurrent sessions per day, from interleaved message timestamps.
+
+    Each session is active from one message to the next unless they are
+    more than idle_gap apart, so a window left open overnight does not
+    count as running. Single-pass over records, so a stream from
+    iter_historical_records works.
+
+    Args:
+        records: Usage records (list or iterator)
+        idle_gap: Longest gap between a session's messages still counted
+            as active
+
+    Returns:
+        DailyConcurrency per local day with any active span, oldest first
+    """
+    times: dict[str, list[datetime]] = {}
+    for record in records:
+        times.setdefault(record.session_id, []).append(record.timestamp.astimezone())
+
+    # Local day -> (moment, +1 start / -1 end) across all sessions
+    edges: dict[str, list[tuple[datetime, int]]] = {}
+
+    def add_span(start: datetime, end: datetime) -> None:
+        # Spans crossing midnight are split between the days
+        while start < end:
+            midnight = datetime.combine(start.date() + timedelta(days=1), datetime.min.time(), start.tzinfo)
+            piece_end = min(end, midnight)
+            edges.setdefault(start.strftime("%Y-%m-%d"), []).extend([(start, 1), (piece_end, -1)])
+            start = piece_end
+
+    for stamps in times.values():
+        stamps.sort()
+        start = previous = stamps[0]
+        for stamp in stamps[1:]:
+            if stamp - previous > idle_gap:
+                add_span(start, previous)
+                start = stamp
+            previous = stamp
+        add_span(start, previous)
+
+    days = []
+    for date, day_edges in sorted(edges.items()):
+        # Ends sort before starts at the same moment: back-to-back isn't overlap
+        day_edges.sort()
+        active = peak = 0
+        busy = weighted = overlap = 0.0
+        last = day_edges[0][0]
+        for moment, delta in day_edges:
+            seconds = (moment - last).total_seconds()
+            if active:
+                busy += seconds
+                weighted += active * seconds
+            if active >= 2:
+                overlap += seconds
+            active += delta
+            peak = max(peak, active)
+            last = moment
+        days.append(DailyConcurrency(
+            date=date,
+            max_concurrent=peak,
+            avg_concurrent=weighted / busy if busy else 0.0,
+            overlap_minutes=overlap / 60,
+        ))
+    return days
+
+
+def automation_reason(session: Session, automation_folders: Iterable[str]) -> str | None:
+    """
+    Why a session counts as automated, if it does.
+
+    Args:
+        session: Stored session metadata
+        automation_folders: Folders tagged with `ccg projects automate`
+
+    Returns:
+        "folder" when the session ran in (or below) a tagged folder,
+        "headless" when it was started by `claude -p` or an SDK, else None
+    """
+    folder = session.folder.replace("\\", "/").rstrip("/")
+    for tagged in automation_folders:
+        tagged = tagged.replace("\\", "/").rstrip("/")
+        if folder == tagged or folder.startswith(tagged + "/"):
+            return "folder"
+    if session.entrypoint and session.entrypoint.startswith(HEADLESS_ENTRYPOINT_PREFIX):
+        return "headless"
+    return None
+
+
+def split_automation(sessions: Iterable[Session], automation_folders: Iterable[str]) -> AutomationSplit:
+    """
+    Split sessions into interactive and automated usage.
+
+    Args:
+        sessions: Sessions from storage.api.get_sessions
+        automation_folders: Folders tagged with `ccg projects automate`
+
+    Returns:
+        AutomationSplit totals
+    """
+    folders = list(automation_folders)
+    split = AutomationSplit()
+    for session in sessions:
+        reason = automation_reason(session, folders)
+        if reason is None:
+            split.interactive_sessions += 1
+            split.interactive_tokens += session.total_tokens
+            continue
+        split.automated_sessions += 1
+        split.automated_tokens += session.total_tokens
+        if reason == "folder":
+            split.tagged_sessions += 1
+        else:
+            split.headless_sessions += 1
+    return split
+
+
+def summarize_by_tag(
+    summaries: Iterable[SessionSummary],
+    session_tags: dict[str, list[str]],
+) -> list[TagSummary]:
+    """
+    Roll session summaries up by tag.
+
+    A session with several tags counts in full under each of them, so tag
+    totals can add up to more than the overall total.
+
+    Args:
+        summaries: Session summaries from summarize_sessions
+        session_tags: Session id -> tags (storage.api.get_session_tags)
+
+    Returns:
+        TagSummary objects, most expensive first; untagged sessions are
+        grouped under tag None
+    """
+    tags: dict[str | None, TagSummary] = {}
+    for summary in summaries:
+        for tag in session_tags.get(summary.session_id) or [None]:
+            total = tags.setdefault(tag, TagSummary(tag=tag))
+            total.sessions += 1
+            total.total_tokens += summary.total_tokens
+            total.cost += summary.cost
+
+    return sorted(tags.values(), key=lambda t: (t.cost, t.total_tokens), reverse=True)
+
+
+#endregion
@@ -0,0 +1,208 @@
+"""
+Usage snapshots and their differences for `ccg diff`.
+
+A UsageSnapshot condenses a database into lifetime totals per project and
+per model. Two snapshots (the current database against an older copy or a
+saved JSON snapshot) diff into new and dropped projects, token and cost
+deltas, and model mix shifts.
+"""
+#region Imports
+from dataclasses import asdict, dataclass, field
+
+#endregion
+
+
+#region Constants
+# Bumped when the JSON written by `ccg diff --save` changes shape
+SNAPSHOT_FORMAT = 1
+
+# Share shift (percentage points) below which a model's mix counts as unchanged
+MIX_SHIFT_POINTS = 1.0
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class UsageSnapshot:
+    """
+    Lifetime usage totals of one database.
+
+    Attributes:
+        created_at: When the snapshot was taken (ISO timestamp)
+        first_date: First day with data (None for an empty database)
+        last_date: Last day with data
+        total_tokens: Tokens across all days
+        cost: Estimated API cost across all days
+        sessions: Sessions (summed per day)
+        prompts: User prompts
+        projects: Project folder -> {"tokens": int, "cost": float}
+        models: Model -> tokens
+    """
+
+    created_at: str
+    first_date: str | None = None
+    last_date: str | None = None
+    total_tokens: int = 0
+    cost: float = 0.0
+    sessions: int = 0
+    prompts: int = 0
+    projects: dict[str, dict] = field(default_factory=dict)
+    models: dict[str, int] = field(default_factory=dict)
+
+    def to_dict(self) -> dict:
+        """JSON-serializable form written by `ccg diff --save`."""
+        return {"format": SNAPSHOT_FORMAT, **asdict(self)}
+
+    @classmethod
+    def from_dict(cls, data: dict) -> "UsageSnapshot":
+        """
+        Inverse of to_dict.
+
+        Raises:
+            ValueError: If data is not a snapshot this release can read
+        """
+        if not isinstance(data, dict) or data.get("format") != SNAPSHOT_FORMAT:
+            raise ValueError("not a ccg usage snapshot (write one with: ccg diff --save <file>)")
+        fields = {key: value for key, value in data.items() if key != "format"}
+        try:
+            return cls(**fields)
+        except TypeError as e:
+            raise ValueError(f"malformed usage snapshot: {e}") from e
+
+    def model_shares(self) -> dict[str, float]:
+        """Model -> percentage of tokens, largest first."""
+        total = sum(self.models.values())
+        if not total:
+            return {}
+        return {
+            model: tokens * 100 / total
+            for model, tokens in sorted(self.models.items(), key=lambda item: item[1], reverse=True)
+        }
+
+
+@dataclass
+class ProjectDelta:
+    """
+    Change in one project's lifetime usage between two snapshots.
+
+    Attributes:
+        folder: Project folder
+        tokens: Token delta (new minus old)
+        cost: Cost delta in dollars
+        status: "new", "dropped", or "changed"
+    """
+
+    folder: str
+    tokens: int
+    cost: float
+    status: str
+
+
+@dataclass
+class SnapshotDiff:
+    """
+    Differences between an older and a newer snapshot.
+
+    Attributes:
+        old: The older snapshot
+        new: The newer snapshot
+        projects: Projects that appeared, disappeared, or changed, largest
+            cost change first
+        model_shifts: (model, old share %, new share %) for models whose
+            share moved at least MIX_SHIFT_POINTS, largest shift first
+    """
+
+    old: UsageSnapshot
+    new: UsageSnapshot
+    projects: list[ProjectDelta] = field(default_factory=list)
+    model_shifts: list[tuple[str, float, float]] = field(default_factory=list)
+
+    @property
+    def tokens(self) -> int:
+        return self.new.total_tokens - self.old.total_tokens
+
+    @property
+    def cost(self) -> float:
+        return self.new.cost - self.old.cost
+#endregion
+
+
+#region Functions
+
+
+def build_usage_snapshot(
+    created_at: str,
+    days: list[dict],
+    daily_projects: dict[str, dict[str, dict]],
+) -> UsageSnapshot:
+    """
+    Condense a database's daily data into a snapshot.
+
+    Args:
+        created_at: Snapshot timestamp (ISO)
+        days: Rows from load_daily_snapshots over the whole history
+        daily_projects: Per-day project usage from get_daily_projects
+
+    Returns:
+        UsageSnapshot with lifetime totals
+    """
+    snapshot = UsageSnapshot(created_at=created_at)
+    active = [day for day in days if day["total_tokens"] > 0]
+    if active:
+        snapshot.first_date, snapshot.last_date = active[0]["date"], active[-1]["date"]
+    for day in days:
+        snapshot.total_tokens += day["total_tokens"]
+        snapshot.cost += day["cost"]
+        snapshot.sessions += day["sessions"]
+        snapshot.prompts += day["prompts"]
+        for model, mix in day["models"].items():
+            snapshot.models[model] = snapshot.models.get(model, 0) + mix["total_tokens"]
+
+    for projects in daily_projects.values():
+        for folder, usage in projects.items():
+            totals = snapshot.projects.setdefault(folder, {"tokens": 0, "cost": 0.0})
+            totals["tokens"] += usage["tokens"]
+            totals["cost"] += usage["cost"]
+    return snapshot
+
+
+def diff_snapshots(old: UsageSnapshot, new: UsageSnapshot) -> SnapshotDiff:
+    """
+    Compare two snapshots.
+
+    Args:
+        old: Earlier snapshot (or the other machine's database)
+        new: Current snapshot
+
+    Returns:
+        SnapshotDiff listing project changes and model mix shifts
+    """
+    diff = SnapshotDiff(old=old, new=new)
+
+    for folder in dict.fromkeys([*new.projects, *old.projects]):
+        before, after = old.projects.get(folder), new.projects.get(folder)
+        tokens = (after or {}).get("tokens", 0) - (before or {}).get("tokens", 0)
+        cost = (after or {}).get("cost", 0.0) - (before or {}).get("cost", 0.0)
+        if before is None:
+            status = "new"
+        elif after is None:
+            status = "dropped"
+        elif tokens == 0:
+            continue
+        else:
+            status = "changed"
+        diff.projects.append(ProjectDelta(folder=folder, tokens=tokens, cost=cost, status=status))
+    diff.projects.sort(key=lambda delta: (abs(delta.cost), abs(delta.tokens)), reverse=True)
+
+    old_shares, new_shares = old.model_shares(), new.model_shares()
+    for model in dict.fromkeys([*new_shares, *old_shares]):
+        before, after = old_shares.get(model, 0.0), new_shares.get(model, 0.0)
+        if abs(after - before) >= MIX_SHIFT_POINTS:
+            diff.model_shifts.append((model, before, after))
+    diff.model_shifts.sort(key=lambda shift: abs(shift[2] - shift[1]), reverse=True)
+    return diff
+
+
+#endregion
@@ -0,0 +1,124 @@
+"""
+Year-in-review facts for `ccg wrapped`.
+
+Builds on the monthly recap helpers: the same daily snapshots and project
+totals, summarized over a calendar year, plus the busiest hour of the day
+from the hourly_snapshots rollup.
+"""
+#region Imports
+from collections.abc import Iterable
+from dataclasses import dataclass, field
+
+from src.aggregation.activity_matrix import build_activity_matrix
+from src.aggregation.recap import longest_streak, top_projects
+
+#endregion
+
+
+#region Constants
+# Projects listed on the wrapped image
+WRAPPED_TOP_PROJECTS = 5
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class YearWrapped:
+    """
+    Facts behind one year's wrapped image.
+
+    Attributes:
+        year: Calendar year
+        total_tokens: Tokens used in the year
+        cost: Estimated API cost in the year
+        active_days: Days with any token usage
+        sessions: Sessions (summed per day)
+        prompts: User prompts
+        busiest_day: Date with the most tokens (None if idle all year)
+        busiest_day_tokens: Tokens on busiest_day
+        busiest_month: Month (YYYY-MM) with the most tokens
+        busiest_hour: Local hour (0-23) with the most tokens, or None
+            without hourly totals
+        longest_streak: Longest run of consecutive active days
+        streak_start: First day of that run
+        top_model: Model with the most tokens
+        top_projects: (project folder, tokens) pairs, largest first
+    """
+
+    year: int
+    total_tokens: int = 0
+    cost: float = 0.0
+    active_days: int = 0
+    sessions: int = 0
+    prompts: int = 0
+    busiest_day: str | None = None
+    busiest_day_tokens: int = 0
+    busiest_month: str | None = None
+    busiest_hour: int | None = None
+    longest_streak: int = 0
+    streak_start: str | None = None
+    top_model: str | None = None
+    top_projects: list[tuple[str, int]] = field(default_factory=list)
+#endregion
+
+
+#region Functions
+
+
+def build_year_wrapped(
+    year: int,
+    days: list[dict],
+    project_tokens: dict[str, int],
+    hours: Iterable[dict] = (),
+) -> YearWrapped:
+    """
+    Collect the wrapped facts for one year.
+
+    Args:
+        year: Calendar year
+        days: Rows from storage.api.load_daily_snapshots for the year
+        project_tokens: Project folder -> tokens for the year
+        hours: Rows from storage.api.get_hourly_range for the year, used
+            only for the busiest hour
+
+    Returns:
+        YearWrapped for the year
+    """
+    start, end = f"{year}-01-01", f"{year}-12-31"
+    wrapped = YearWrapped(year=year)
+
+    model_tokens: dict[str, int] = {}
+    month_tokens: dict[str, int] = {}
+    active_dates: set[str] = set()
+    for day in days:
+        if not start <= day["date"] <= end:
+            continue
+        wrapped.total_tokens += day["total_tokens"]
+        wrapped.cost += day["cost"]
+        wrapped.sessions += day["sessions"]
+        wrapped.prompts += day["prompts"]
+        if day["total_tokens"] > 0:
+            active_dates.add(day["date"])
+        if day["total_tokens"] > wrapped.busiest_day_tokens:
+            wrapped.busiest_day, wrapped.busiest_day_tokens = day["date"], day["total_tokens"]
+        month_tokens[day["date"][:7]] = month_tokens.get(day["date"][:7], 0) + day["total_tokens"]
+        for model, mix in day["models"].items():
+            model_tokens[model] = model_tokens.get(model, 0) + mix["total_tokens"]
+
+    wrapped.active_days = len(active_dates)
+    wrapped.longest_streak, wrapped.streak_start = longest_streak(active_dates, start, end)
+    if any(month_tokens.values()):
+        wrapped.busiest_month = max(month_tokens, key=month_tokens.get)
+    if any(model_tokens.values()):
+        wrapped.top_model = max(model_tokens, key=model_tokens.get)
+    wrapped.top_projects = top_projects(project_tokens, WRAPPED_TOP_PROJECTS)
+
+    hour_tokens = build_activity_matrix(hours).hour_totals("tokens")
+    if any(hour_tokens):
+        wrapped.busiest_hour = hour_tokens.index(max(hour_tokens))
+    return wrapped
+
+
+#endregion
@@ -5,6 +5,9 @@ Main entry point for all claude-goblin commands.
 """
 
 import sys
+from contextlib import ExitStack
+from datetime import datetime
+from pathlib import Path
 
 # Consoles that aren't UTF-8 -- Windows cp1252, or a POSIX box pinned to a
 # non-UTF-8 locale -- make rich raise UnicodeEncodeError the moment output
@@ -22,20 +25,46 @@ for _stream in (sys.stdout, sys.stderr):
 import typer
 from rich.console import Console
 
+from src.aggregation.activity_matrix import ACTIVITY_METRICS
 from src.commands import (
+    diff,
+    doctor,
     export,
+    heatmap,
+    pull,
+    punchcard,
+    recap,
     stats,
     usage,
+    wrapped,
 )
 from src.commands import (
     help as help_cmd,
 )
+from src.commands.annotate import app as annotate_app
+from src.commands.budget import app as budget_app
+from src.commands.config import app as config_app
 from src.commands.container import app as container_app
+from src.commands.db import app as db_app
+from src.commands.dev import app as dev_app
+from src.commands.hooks import app as hooks_app
+from src.commands.imports import app as import_app
+from src.commands.imports.db import merge_profile_databases
+from src.commands.pricing import app as pricing_app
+from src.commands.projects import app as projects_app
 from src.commands.remove import app as remove_app
 from src.commands.restore import app as restore_app
+from src.commands.sessions import app as sessions_app
 from src.commands.setup import app as setup_app
 from src.commands.sync import app as sync_app
 from src.commands.update import app as update_app
+from src.commands.update_usage import check_fast_staleness
+from src.config.settings import set_claude_data_dirs
+from src.config.user_config import get_max_stale, get_profiles, parse_duration, set_max_stale, set_read_only
+from src.errors import GoblinError
+from src.storage import api, profile_db_path, set_db_path_override
+from src.visualization.export import DEFAULT_HEATMAP_SCALE, DEFAULT_HEATMAP_THEME, HEATMAP_SCALES, HEATMAP_THEMES
+from src.visualization.heatmap_server import DEFAULT_PORT
 
 # Version
 __version__ = "1.2.1"
@@ -57,6 +86,16 @@ app.add_typer(update_app, name="update")
 app.add_typer(restore_app, name="restore")
 app.add_typer(sync_app, name="sync")
 app.add_typer(container_app, name="container")
+app.add_typer(pricing_app, name="pricing")
+app.add_typer(sessions_app, name="sessions")
+app.add_typer(projects_app, name="projects")
+app.add_typer(budget_app, name="budget")
+app.add_typer(annotate_app, name="annotate")
+app.add_typer(import_app, name="import")
+app.add_typer(config_app, name="config")
+app.add_typer(hooks_app, name="hooks")
+app.add_typer(db_app, name="db")
+app.add_typer(dev_app, name="dev", hidden=True)
 
 
 def version_callback(value: bool):
@@ -69,6 +108,7 @@ def version_callback(value: bool):
 
 @app.callback()
 def main_callback(
+    ctx: typer.Context,
     version: bool = typer.Option(
         False,
         "--version",
@@ -76,10 +116,68 @@ def main_callback(
         help="Show version and exit",
         callback=version_callback,
         is_eager=True,
-    )
+    ),
+    data_dir: Path | None = typer.Option(
+        None,
+        "--data-dir",
+        help="Claude Code transcript directory (default: $CLAUDE_CONFIG_DIR/projects or ~/.claude/projects)",
+    ),
+    profile: list[str] | None = typer.Option(
+        None,
+        "--profile",
+        help="Use a profile from goblin_config.json; repeat to merge profiles",
+    ),
+    read_only: bool = typer.Option(
+        False,
+        "--read-only",
+        help="Never write the usage database or Claude settings.json (for shared or mounted databases)",
+    ),
+    max_stale: str | None = typer.Option(
+        None,
+        "--max-stale",
+        help="With --fast, refuse when the last full update is older than this (e.g. 12h, 1d)",
+    ),
 ):
     """Claude Goblin CLI callback for global options."""
-    pass
+    if read_only:
+        set_read_only(True)
+    if max_stale is not None:
+        try:
+            set_max_stale(parse_duration(max_stale))
+        except ValueError as e:
+            raise typer.BadParameter(str(e), param_hint="--max-stale")
+    if profile:
+        if data_dir is not None:
+            console.print("[red]--data-dir and --profile can't be combined[/red]")
+            raise typer.Exit(1)
+        _activate_profiles(profile)
+    elif data_dir is not None:
+        set_claude_data_dirs([data_dir])
+    # One database connection for the whole command, closed when it finishes
+    resources = ExitStack()
+    resources.enter_context(api.storage_handle())
+    ctx.call_on_close(resources.close)
+    # --fast skips ingestion, so say when hooks have stopped keeping data current
+    if "--fast" in sys.argv[1:]:
+        check_fast_staleness(console, get_max_stale())
+
+
+def _activate_profiles(names: list[str]) -> None:
+    """Point transcript discovery and storage at the selected profiles."""
+    profiles = get_profiles()
+    unknown = [name for name in names if name not in profiles]
+    if unknown:
+        known = ", ".join(sorted(profiles)) or "none configured"
+        console.print(f"[red]Unknown profile: {', '.join(unknown)} (profiles: {known})[/red]")
+        raise typer.Exit(1)
+
+    names = list(dict.fromkeys(names))
+    set_claude_data_dirs([profiles[name]["data_dir"] for name in names])
+    set_db_path_override(profile_db_path(names, profiles[names[0]]["db_path"]))
+    if len(names) > 1:
+        # The merged view starts from each profile's stored history, not
+        # just the transcripts still on disk
+        merge_profile_databases([profile_db_path([name], profiles[name]["db_path"]) for name in names])
 
 # Create console for commands
 console = Console()
@@ -87,7 +185,11 @@ console = Console()
 
 @app.command(name="usage")
 def usage_command(
-    live: bool = typer.Option(False, "--live", help="Auto-refresh dashboard every 5 seconds"),
+    live: bool = typer.Option(False, "--live", help="Auto-refresh dashboard when new data lands (and every 5 seconds)"),
+    interval: int | None = typer.Option(
+        None, "--interval", min=1,
+        help="Seconds between --live refreshes when no new data lands (default: 5; with --fast, none)",
+    ),
     fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
     anon: bool = typer.Option(False, "--anon", help="Anonymize project names to project-001, project-002, etc"),
     force: bool = typer.Option(False, "--force", help="Force re-parse all JSONL files (may take 4-5s for large histories)"),
@@ -102,6 +204,7 @@ def usage_command(
     - Token breakdown by project
 
     Use --live for auto-refreshing dashboard.
+    Use --interval N to change the --live fallback refresh interval.
     Use --fast to skip all updates and read from database only (requires existing database).
     Use --anon to anonymize project names (ranked by usage, project-001 is highest).
     Use --force to bypass incremental parsing cache and re-parse all JSONL files.
@@ -112,7 +215,7 @@ def usage_command(
     if remote:
         usage.run_remote(console, anon=anon)
     else:
-        usage.run(console, live=live, fast=fast, anon=anon, force=force)
+        usage.run(console, live=live, fast=fast, anon=anon, force=force, interval=interval)
 
 
 @app.command(name="stats")
@@ -120,6 +223,10 @@ def stats_command(
     fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
     force: bool = typer.Option(False, "--force", help="Force re-parse all JSONL files (may take 4-5s for large histories)"),
     remote: bool = typer.Option(False, "--remote", "-r", help="Query the remote DuckDB server instead of local"),
+    mcp: bool = typer.Option(False, "--mcp", help="Add per-server MCP tool call and token traffic stats"),
+    by: str | None = typer.Option(None, "--by", help="Break usage down by 'branch' or 'host' instead of the full report"),
+    from_date: str | None = typer.Option(None, "--from", help="With --by: start date (YYYY-MM-DD)"),
+    to_date: str | None = typer.Option(None, "--to", help="With --by: end date (YYYY-MM-DD)"),
 ):
     """
     Show detailed statistics and cost analysis.
@@ -135,20 +242,59 @@ def stats_command(
     Use --force to bypass incremental parsing cache and re-parse all JSONL files.
         Note: May take 4-5 seconds for large histories. Use when data seems stale.
     Use --remote to query the remote server (shows cross-device aggregate data).
+    Use --mcp to add which MCP servers were called and the tokens they moved.
+    Use --by branch for tokens and cost per git branch (--from/--to narrow it).
+    Use --by host for tokens and cost per machine in a merged database.
     """
-    if remote:
+    if by is not None:
+        if by not in ("branch", "host"):
+            console.print(f"[red]Invalid --by: {by} (choose from branch, host)[/red]")
+            raise typer.Exit(1)
+        window = {}
+        for flag, value in (("--from", from_date), ("--to", to_date)):
+            if value is not None:
+                try:
+                    window[flag] = datetime.strptime(value, "%Y-%m-%d").date().isoformat()
+                except ValueError:
+                    console.print(f"[red]Invalid date for {flag}: {value} (expected YYYY-MM-DD)[/red]")
+                    raise typer.Exit(1)
+        run_by = stats.run_by_branch if by == "branch" else stats.run_by_host
+        run_by(console, fast=fast, start_date=window.get("--from"), end_date=window.get("--to"))
+    elif remote:
         stats.run_remote(console)
     else:
-        stats.run(console, fast=fast, force=force)
+        stats.run(console, fast=fast, force=force, mcp=mcp)
 
 
 @app.command(name="export")
 def export_command(
+    target: str | None = typer.Argument(
+        None, help="Omit for the heatmap, 'projects' for a per-project file bundle, or 'data' for raw records"
+    ),
+    export_format: str = typer.Option(
+        "csv", "--format", help="Format for 'projects' (csv) or 'data' (csv, jsonl, parquet)"
+    ),
+    out_dir: Path = typer.Option(Path("ccg-projects"), "--dir", help="Output directory for 'projects'"),
     svg: bool = typer.Option(False, "--svg", help="Export as SVG instead of PNG"),
+    png: bool = typer.Option(False, "--png", help="Export as PNG even if export_format is svg"),
+    html: bool = typer.Option(
+        False, "--html", help="Export an HTML report whose days link to per-day detail pages"
+    ),
     open_file: bool = typer.Option(False, "--open", help="Open file after export"),
     fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
     year: int | None = typer.Option(None, "--year", "-y", help="Filter by year (default: current year)"),
+    from_date: str | None = typer.Option(None, "--from", help="Start of a custom window (YYYY-MM-DD)"),
+    to_date: str | None = typer.Option(None, "--to", help="End of a custom window (YYYY-MM-DD)"),
     output: str | None = typer.Option(None, "--output", "-o", help="Output file path"),
+    scale: str | None = typer.Option(
+        None, "--scale", help="Color intensity scale: linear, sqrt (default), log, or percentile"
+    ),
+    theme: str | None = typer.Option(
+        None, "--theme", help="Color gradient: claude (default), or colorblind-safe viridis or cividis"
+    ),
+    metric: str | None = typer.Option(
+        None, "--metric", help="What a day's color measures: tokens (default) or prompts"
+    ),
 ):
     """
     Export yearly heatmap as PNG or SVG.
@@ -156,19 +302,58 @@ def export_command(
     Generates a GitHub-style activity heatmap showing your Claude Code usage
     throughout the year. By default exports as PNG.
 
+    `ccg export projects` instead writes one CSV of daily tokens and cost per
+    project, plus an index.csv, into --dir (default ./ccg-projects).
+    `ccg export data` dumps every usage record with its estimated cost to
+    --output (default ./usage_records.<format>) for pandas or DuckDB.
+
     Use --fast to skip all updates and read from database only (requires existing database).
+    Use --from/--to to render an arbitrary window instead of a calendar year.
 
     Examples:
         ccg export --open                  Export current year as PNG and open it
         ccg export --svg                   Export as SVG instead
+        ccg export --html --open           HTML report with clickable days
         ccg export --fast                  Export from database without updating
         ccg export -y 2024                 Export specific year
+        ccg export --from 2025-03-01 --to 2025-09-30
+                                           Export a custom date window
         ccg export -o ~/usage.png          Specify output path
+        ccg export --scale percentile      Color by rank, for comparing years
+        ccg export --theme viridis         Colorblind-safe colors
+        ccg export --metric prompts        Color days by prompts instead of tokens
+        ccg export projects --format csv --dir ./out
+                                           Per-project CSVs plus an index
+        ccg export data --format parquet -o usage.parquet
+                                           Raw usage records with cost per row
     """
+    if target is not None:
+        if target not in ("projects", "data"):
+            console.print(f"[red]Unknown export target: {target} (expected 'projects' or 'data')[/red]")
+            raise typer.Exit(1)
+        for flag, value in (("--from", from_date), ("--to", to_date)):
+            if value is not None:
+                try:
+                    datetime.strptime(value, "%Y-%m-%d")
+                except ValueError:
+                    console.print(f"[red]Invalid date for {flag}: {value} (expected YYYY-MM-DD)[/red]")
+                    raise typer.Exit(1)
+        if target == "data":
+            export.run_data(
+                console, Path(output) if output else None, export_format, from_date, to_date, fast=fast
+            )
+        else:
+            export.run_projects(console, out_dir, export_format, from_date, to_date, fast=fast)
+        return
+
     # Pass parameters via sys.argv for backward compatibility with export command
     import sys
     if svg and "svg" not in sys.argv:
         sys.argv.append("svg")
+    if png and not svg and "png" not in sys.argv:
+        sys.argv.append("png")
+    if html and "html" not in sys.argv:
+        sys.argv.append("html")
     if open_file and "--open" not in sys.argv:
         sys.argv.append("--open")
     if fast and "--fast" not in sys.argv:
@@ -176,12 +361,295 @@ def export_command(
     if year is not None:
         if "--year" not in sys.argv and "-y" not in sys.argv:
             sys.argv.extend(["--year", str(year)])
+    if from_date is not None and "--from" not in sys.argv:
+        sys.argv.extend(["--from", from_date])
+    if to_date is not None and "--to" not in sys.argv:
+        sys.argv.extend(["--to", to_date])
     if output is not None:
         if "--output" not in sys.argv and "-o" not in sys.argv:
             sys.argv.extend(["--output", output])
+    if scale is not None and "--scale" not in sys.argv:
+        sys.argv.extend(["--scale", scale])
+    if theme is not None and "--theme" not in sys.argv:
+        sys.argv.extend(["--theme", theme])
+    if metric is not None and "--metric" not in sys.argv:
+        sys.argv.extend(["--metric", metric])
     export.run(console)
 
 
+@app.command(name="heatmap")
+def heatmap_command(
+    serve: bool = typer.Option(False, "--serve", help="Serve an auto-refreshing preview on localhost"),
+    port: int = typer.Option(DEFAULT_PORT, "--port", "-p", help="Port for --serve"),
+    year: int | None = typer.Option(None, "--year", "-y", help="Year to show (default: current year)"),
+    from_date: str | None = typer.Option(None, "--from", help="Start of a custom window (YYYY-MM-DD)"),
+    to_date: str | None = typer.Option(None, "--to", help="End of a custom window (YYYY-MM-DD)"),
+    open_browser: bool = typer.Option(False, "--open", help="Open the preview in your browser"),
+    scale: str = typer.Option(
+        DEFAULT_HEATMAP_SCALE, "--scale", help="Color intensity scale: linear, sqrt (default), log, or percentile"
+    ),
+    theme: str = typer.Option(
+        DEFAULT_HEATMAP_THEME, "--theme", help="Color gradient: claude (default), or colorblind-safe viridis or cividis"
+    ),
+):
+    """
+    Preview the activity heatmap in a browser.
+
+    With --serve, renders the heatmap SVG on http://127.0.0.1:<port>/ and
+    swaps in a fresh image whenever the usage database updates. Keep the tab
+    pinned for an always-current view. Reads daily snapshots only, so it
+    stays cheap; ingestion happens via hooks or `ccg update usage`.
+
+    Examples:
+        ccg heatmap --serve --open         Serve current year and open it
+        ccg heatmap --serve -p 9000        Use another port
+        ccg heatmap --serve --from 2026-01-01
+                                           Serve a custom window
+    """
+    if not serve:
+        console.print("[yellow]Use --serve for a live preview, or ccg export to write an image.[/yellow]")
+        raise typer.Exit(1)
+
+    window = {}
+    for flag, value in (("--from", from_date), ("--to", to_date)):
+        if value is not None:
+            try:
+                window[flag] = datetime.strptime(value, "%Y-%m-%d").date()
+            except ValueError:
+                console.print(f"[red]Invalid date for {flag}: {value} (expected YYYY-MM-DD)[/red]")
+                raise typer.Exit(1)
+    start, end = window.get("--from"), window.get("--to")
+    if start and end and start > end:
+        console.print(f"[red]--from {start} is after --to {end}[/red]")
+        raise typer.Exit(1)
+
+    if scale not in HEATMAP_SCALES:
+        console.print(f"[red]Invalid scale: {scale} (choose from {', '.join(HEATMAP_SCALES)})[/red]")
+        raise typer.Exit(1)
+    if theme not in HEATMAP_THEMES:
+        console.print(f"[red]Invalid theme: {theme} (choose from {', '.join(HEATMAP_THEMES)})[/red]")
+        raise typer.Exit(1)
+
+    heatmap.run(
+        console, port=port, year=year, start_date=start, end_date=end, open_browser=open_browser,
+        scale=scale, theme=theme,
+    )
+
+
+@app.command(name="recap")
+def recap_command(
+    month: str | None = typer.Option(None, "--month", "-m", help="Month to recap as YYYY-MM (default: current month)"),
+    refresh: bool = typer.Option(False, "--refresh", help="Recompute stored summaries for finished months"),
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+):
+    """
+    Summarize a month of usage in a few sentences.
+
+    Covers total tokens and cost, the biggest day, top projects, model mix
+    changes against the previous month, and the longest streak of active
+    days. Finished months are stored in the monthly_summaries table and
+    reused; pass --refresh after a rebuild or restore.
+
+    Examples:
+        ccg recap                          Recap the current month so far
+        ccg recap --month 2025-11          Recap November 2025
+    """
+    month = month or datetime.now().strftime("%Y-%m")
+    try:
+        datetime.strptime(month, "%Y-%m")
+    except ValueError:
+        console.print(f"[red]Invalid month: {month} (expected YYYY-MM)[/red]")
+        raise typer.Exit(1)
+
+    recap.run(console, month=month, refresh=refresh, fast=fast)
+
+
+@app.command(name="diff")
+def diff_command(
+    old: Path | None = typer.Argument(None, help="Older database (.db/.duckdb) or JSON snapshot to compare against"),
+    save: Path | None = typer.Option(None, "--save", help="Write the current usage snapshot to this JSON file"),
+    limit: int = typer.Option(15, "--limit", "-n", help="Most projects listed"),
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+):
+    """
+    Compare current usage against an older database or saved snapshot.
+
+    Lists new and dropped projects, token and cost deltas per project, and
+    model mix shifts. Save a snapshot at month end with --save, then diff
+    against it later; or diff against another machine's database file after
+    merging. Database files are read from a temporary copy and never changed.
+
+    Examples:
+        ccg diff --save ~/usage-2026-09.json   Save this month's snapshot
+        ccg diff ~/usage-2026-09.json          What changed since then
+        ccg diff ~/backup/usage_history.db     Compare against an old database
+    """
+    if old is None and save is None:
+        console.print("[red]Give a database or snapshot to compare against, or --save <file>[/red]")
+        raise typer.Exit(1)
+    if limit < 1:
+        raise typer.BadParameter("--limit must be at least 1")
+
+    raise typer.Exit(diff.run(console, old=old.expanduser() if old else None, save=save, limit=limit, fast=fast))
+
+
+@app.command(name="pull")
+def pull_command(
+    target: str = typer.Argument(..., help="Remote transcripts: ssh://[user@]host[:port][/path]"),
+    days: int | None = typer.Option(None, "--days", help="Only transcripts modified in the last N days"),
+    hostname: str | None = typer.Option(
+        None, "--hostname", help="Name stamped on the pulled records (default: the URL's host)"
+    ),
+):
+    """
+    Ingest a remote machine's usage over SSH.
+
+    Runs find+cat of the remote Claude Code transcripts (default
+    ~/.claude/projects, or the URL's path) and ingests the lines locally,
+    tagging records with the host name. Pulling again adds nothing twice.
+    Uses your ssh config and keys; password prompts are disabled.
+
+    Examples:
+        ccg pull ssh://me@devbox                  Pull devbox's transcripts
+        ccg pull ssh://devbox:2222/srv/claude/projects
+                                                  Custom port and directory
+        ccg pull ssh://devbox --days 7            Only the last week's files
+    """
+    if days is not None and days < 1:
+        raise typer.BadParameter("--days must be at least 1")
+    raise typer.Exit(pull.run(console, target, days=days, hostname=hostname))
+
+
+@app.command(name="wrapped")
+def wrapped_command(
+    year: int | None = typer.Argument(None, help="Year to review (default: current year)"),
+    svg: bool = typer.Option(False, "--svg", help="Export as SVG instead of PNG"),
+    output: Path | None = typer.Option(None, "--output", "-o", help="Output file path"),
+    open_file: bool = typer.Option(False, "--open", help="Open file after export"),
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+):
+    """
+    Export a shareable year-in-review image.
+
+    One image with the year's totals, its activity heatmap, top projects,
+    and fun facts (busiest day, month, and hour; favorite model). PNG
+    output needs CairoSVG (claude-goblin[export]); --svg works without it.
+
+    Examples:
+        ccg wrapped                        Review the current year
+        ccg wrapped 2025 --open            Review 2025 and open the PNG
+        ccg wrapped 2025 --svg -o wrapped.svg
+    """
+    wrapped.run(
+        console,
+        year=year or datetime.now().year,
+        format_type="svg" if svg else "png",
+        output=output,
+        fast=fast,
+        should_open=open_file,
+    )
+
+
+@app.command(name="punchcard")
+def punchcard_command(
+    metric: str = typer.Option("tokens", "--metric", "-m", help="Cell value: tokens (default) or prompts"),
+    from_date: str | None = typer.Option(None, "--from", help="Only usage on or after YYYY-MM-DD"),
+    to_date: str | None = typer.Option(None, "--to", help="Only usage on or before YYYY-MM-DD"),
+    svg: bool = typer.Option(False, "--svg", help="Export as SVG instead of printing"),
+    output: Path | None = typer.Option(None, "--output", "-o", help="SVG output file path"),
+    open_file: bool = typer.Option(False, "--open", help="Open file after export"),
+    theme: str = typer.Option(
+        "claude", "--theme", help="SVG color gradient: claude (default), or colorblind-safe viridis or cividis"
+    ),
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+):
+    """
+    Show when you use Claude: a weekday x hour-of-day punch card.
+
+    Each of the 7x24 cells is one hour of the week in local time, sized by
+    the tokens (or prompts) it has seen across the range. Prints to the
+    terminal by default; --svg writes an image instead.
+
+    Examples:
+        ccg punchcard                      All history, by tokens
+        ccg punchcard -m prompts --from 2026-01-01
+        ccg punchcard --svg --open         Export ~/.claude/usage/claude-punchcard.svg
+    """
+    if metric not in ACTIVITY_METRICS:
+        console.print(f"[red]Invalid metric: {metric} (choose from {', '.join(ACTIVITY_METRICS)})[/red]")
+        raise typer.Exit(1)
+    if theme not in HEATMAP_THEMES:
+        console.print(f"[red]Invalid theme: {theme} (choose from {', '.join(HEATMAP_THEMES)})[/red]")
+        raise typer.Exit(1)
+    for flag, value in (("--from", from_date), ("--to", to_date)):
+        if value is not None:
+            try:
+                datetime.strptime(value, "%Y-%m-%d")
+            except ValueError:
+                console.print(f"[red]Invalid date for {flag}: {value} (expected YYYY-MM-DD)[/red]")
+                raise typer.Exit(1)
+    if from_date and to_date and from_date > to_date:
+        console.print(f"[red]--from {from_date} is after --to {to_date}[/red]")
+        raise typer.Exit(1)
+
+    punchcard.run(
+        console,
+        metric=metric,
+        start_date=from_date,
+        end_date=to_date,
+        svg=svg or output is not None,
+        output=output,
+        fast=fast,
+        should_open=open_file,
+        theme=theme,
+    )
+
+
+@app.command(name="doctor")
+def doctor_command(
+    hook_days: int = typer.Option(
+        doctor.HOOK_STALE_DAYS, "--hook-days",
+        help="Flag the usage hook if it hasn't fired in this many days",
+    ),
+    quarantine: bool = typer.Option(
+        False, "--quarantine",
+        help="List entries held back for bad timestamps instead of running checks",
+    ),
+    dismiss: bool = typer.Option(
+        False, "--dismiss",
+        help="With --quarantine: mark the listed entries as reviewed",
+    ),
+    as_json: bool = typer.Option(
+        False, "--json",
+        help="Print findings as JSON (status, severity, fix) for scripts",
+    ),
+):
+    """
+    Check Claude Goblin's health.
+
+    Checks that the Claude data directory has transcripts, that the
+    database passes an integrity check with its indexes in place and daily
+    totals backed by records, that every model in use has pricing, that
+    the usage hook is installed, still firing, and succeeding (from the
+    update_runs log written by `ccg update usage`), and warns about
+    transcript entries quarantined for bad timestamps. Each finding comes
+    with the command that fixes it. Exits with status 1 when a check fails.
+
+    A good first step when numbers look wrong.
+
+    Examples:
+        ccg doctor                         Run all checks
+        ccg doctor --hook-days 7           Allow a week between hook runs
+        ccg doctor --quarantine            Review entries with bad timestamps
+        ccg doctor --quarantine --dismiss  Dismiss them after review
+        ccg doctor --json                  Machine-readable report
+    """
+    if quarantine:
+        doctor.review_quarantine(console, dismiss=dismiss)
+        return
+    raise typer.Exit(doctor.run(console, hook_days=hook_days, as_json=as_json))
+
+
 @app.command(name="help", hidden=True)
 def help_command():
     """
@@ -243,8 +711,18 @@ def main() -> None:
 
     Exit:
         Press Ctrl+C to exit
+
+    Errors with a recovery hint (src.errors) are printed with it instead
+    of a traceback.
     """
-    app()
+    try:
+        app()
+    except GoblinError as e:
+        console = Console()
+        console.print(f"[red]Error: {e}[/red]")
+        if e.hint:
+            console.print(f"[dim]{e.hint}[/dim]")
+        sys.exit(1)
 
 
 if __name__ == "__main__":
@@ -0,0 +1,31 @@
+"""
+Annotation commands for Claude Goblin.
+
+Provides subcommands for dated milestones ("launched v2") shown on exported
+heatmaps, the usage dashboard, and `ccg stats`:
+- add: Annotate a day
+- list: Show annotations
+- remove: Drop a day's annotations
+"""
+#region Imports
+import typer
+
+from src.commands.annotate import notes
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="annotate",
+    help="Dated milestones shown on heatmaps and stats",
+    no_args_is_help=True,
+)
+#endregion
+
+
+#region Command Registration
+app.command(name="add")(notes.annotate_add_command)
+app.command(name="list")(notes.annotate_list_command)
+app.command(name="remove")(notes.annotate_remove_command)
+#endregion
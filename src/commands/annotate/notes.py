@@ -0,0 +1,119 @@
+"""
+Annotation add/list/remove commands for Claude Goblin.
+
+Stores dated milestone labels in the "annotations" table, marked on
+exported heatmaps and listed next to their day or month by `ccg usage` and
+`ccg stats`, so usage spikes come with context.
+"""
+#region Imports
+from datetime import datetime
+
+import typer
+from rich.console import Console
+from rich.table import Table
+
+from src.config.user_config import is_read_only
+from src.storage import api
+
+#endregion
+
+
+#region Helper Functions
+
+
+def _parse_day(value: str, name: str) -> str:
+    """YYYY-MM-DD (or "today") as an ISO date."""
+    if value == "today":
+        return datetime.now().date().isoformat()
+    try:
+        return datetime.strptime(value, "%Y-%m-%d").date().isoformat()
+    except ValueError:
+        raise typer.BadParameter(f"{name} expects YYYY-MM-DD or 'today', got '{value}'")
+
+
+#endregion
+
+
+#region Commands
+
+
+def annotate_add_command(
+    day: str = typer.Argument(..., help="Day to annotate (YYYY-MM-DD or 'today')"),
+    label: str = typer.Argument(..., help="Milestone text, e.g. \"launched v2\""),
+) -> None:
+    """
+    Annotate a day with a milestone.
+
+    Examples:
+        ccg annotate add 2026-03-02 "launched v2"
+        ccg annotate add today "switched to opus"
+    """
+    console = Console()
+    day = _parse_day(day, "DAY")
+    label = label.strip()
+    if not label:
+        raise typer.BadParameter("LABEL must not be empty")
+    if is_read_only():
+        console.print("[red]Read-only mode: not changing annotations[/red]")
+        raise typer.Exit(1)
+
+    if api.add_annotation(day, label):
+        console.print(f"[green]Annotated {day}: {label}[/green]")
+    else:
+        console.print(f"[yellow]{day} already has '{label}'[/yellow]")
+
+
+def annotate_list_command(
+    from_date: str = typer.Option(None, "--from", help="Only annotations on or after YYYY-MM-DD"),
+    to_date: str = typer.Option(None, "--to", help="Only annotations on or before YYYY-MM-DD"),
+) -> None:
+    """
+    List annotations, oldest first.
+
+    Examples:
+        ccg annotate list
+        ccg annotate list --from 2026-01-01
+    """
+    console = Console()
+    start_date = _parse_day(from_date, "--from") if from_date else None
+    end_date = _parse_day(to_date, "--to") if to_date else None
+
+    annotations = api.get_annotations(start_date, end_date)
+    if not annotations:
+        console.print("[dim]No annotations. Add one with: ccg annotate add <YYYY-MM-DD> <label>[/dim]")
+        return
+
+    table = Table(title="Annotations", title_justify="left")
+    table.add_column("Date", no_wrap=True)
+    table.add_column("Label", overflow="fold")
+    for day in sorted(annotations):
+        for label in annotations[day]:
+            table.add_row(day, label)
+    console.print(table)
+
+
+def annotate_remove_command(
+    day: str = typer.Argument(..., help="Annotated day (YYYY-MM-DD or 'today')"),
+    label: str = typer.Argument(None, help="Label to remove (default: all of the day's annotations)"),
+) -> None:
+    """
+    Remove a day's annotation, or all of them.
+
+    Examples:
+        ccg annotate remove 2026-03-02 "launched v2"
+        ccg annotate remove 2026-03-02
+    """
+    console = Console()
+    day = _parse_day(day, "DAY")
+    if is_read_only():
+        console.print("[red]Read-only mode: not changing annotations[/red]")
+        raise typer.Exit(1)
+
+    removed = api.remove_annotations(day, label)
+    if not removed:
+        console.print(f"[yellow]No matching annotation on {day}[/yellow]")
+        raise typer.Exit(1)
+    console.print(f"[green]Removed {removed} annotation{'s' if removed != 1 else ''} from {day}[/green]")
+
+
+#endregion
@@ -0,0 +1,30 @@
+"""
+Budget commands for Claude Goblin.
+
+Provides subcommands for monthly cost budgets per project:
+- set: Budget a project folder at $X per month
+- remove: Drop a project's budget
+- status: Spend this month against every budget
+"""
+#region Imports
+import typer
+
+from src.commands.budget import limits, status
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="budget",
+    help="Monthly cost budgets per project",
+    no_args_is_help=True,
+)
+#endregion
+
+
+#region Command Registration
+app.command(name="set")(limits.budget_set_command)
+app.command(name="remove")(limits.budget_remove_command)
+app.command(name="status")(status.budget_status_command)
+#endregion
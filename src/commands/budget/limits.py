@@ -0,0 +1,69 @@
+"""
+Budget set/remove commands for Claude Goblin.
+
+Stores monthly cost budgets per project folder in the "project_budgets"
+config key, read by `ccg budget status` and the usage dashboard.
+"""
+#region Imports
+from pathlib import Path
+
+import typer
+from rich.console import Console
+
+from src.config.user_config import get_project_budgets, set_project_budgets
+
+#endregion
+
+
+#region Command
+
+
+def budget_set_command(
+    folder: Path = typer.Argument(..., help="Project folder to budget (covers folders below it)"),
+    amount: float = typer.Argument(..., help="Monthly budget in dollars of estimated API cost"),
+) -> None:
+    """
+    Budget a project folder at a monthly cost.
+
+    Examples:
+        ccg budget set ~/clients/acme 200     $200/month for a client repo
+        ccg budget set . 50                   Budget the current directory
+    """
+    console = Console()
+    if amount <= 0:
+        raise typer.BadParameter("amount must be greater than 0")
+
+    path = str(folder.expanduser().resolve())
+    budgets = get_project_budgets()
+    previous = budgets.get(path)
+    budgets[path] = amount
+    set_project_budgets(budgets)
+
+    if previous is None:
+        console.print(f"[green]Budgeted {path} at ${amount:,.2f}/month[/green]")
+    else:
+        console.print(f"[green]Budget for {path}: ${previous:,.2f} → ${amount:,.2f}/month[/green]")
+    console.print("[dim]Run 'ccg budget status' to see spend this month[/dim]")
+
+
+def budget_remove_command(
+    folder: Path = typer.Argument(..., help="Budgeted project folder"),
+) -> None:
+    """
+    Remove a project folder's budget.
+
+    Examples:
+        ccg budget remove ~/clients/acme
+    """
+    console = Console()
+    path = str(folder.expanduser().resolve())
+    budgets = get_project_budgets()
+    if budgets.pop(path, None) is None:
+        console.print(f"[yellow]No budget for {path}[/yellow]")
+        raise typer.Exit(1)
+
+    set_project_budgets(budgets)
+    console.print(f"[green]Removed budget for {path}[/green]")
+
+
+#endregion
@@ -0,0 +1,75 @@
+"""
+Budget status report for Claude Goblin.
+
+Lists every project budget with its estimated API cost so far this month,
+flagging projects that have gone over.
+"""
+#region Imports
+from datetime import datetime
+
+import typer
+from rich.console import Console
+from rich.table import Table
+
+from src.aggregation.project_stats import check_project_budgets
+from src.commands.update_usage import ingest_token_usage
+from src.config.user_config import get_project_budgets
+from src.storage import api
+
+#endregion
+
+
+#region Command
+
+
+def budget_status_command(
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+) -> None:
+    """
+    Show this month's spend against every project budget.
+
+    Examples:
+        ccg budget status           Update, then report
+        ccg budget status --fast    Report from the database only
+    """
+    console = Console()
+    budgets = get_project_budgets()
+    if not budgets:
+        console.print("[dim]No project budgets set. Add one with: ccg budget set <folder> <amount>[/dim]")
+        return
+
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    today = datetime.now().date()
+    month_start = today.replace(day=1).isoformat()
+    results = check_project_budgets(budgets, api.get_daily_projects(month_start, today.isoformat()))
+
+    table = Table(title=f"Project budgets, {today.strftime('%B %Y')}", title_justify="left")
+    table.add_column("Project", overflow="fold")
+    table.add_column("Budget", justify="right")
+    table.add_column("Spent", justify="right", style="green")
+    table.add_column("Used", justify="right")
+    table.add_column("Remaining", justify="right")
+
+    for result in results:
+        style = "bold red" if result.over_budget else "yellow" if result.used >= 0.8 else ""
+        table.add_row(
+            result.folder,
+            f"${result.budget:,.2f}",
+            f"${result.spent:,.2f}",
+            f"[{style}]{result.used:.0%}[/{style}]" if style else f"{result.used:.0%}",
+            f"${result.budget - result.spent:,.2f}" if not result.over_budget
+            else f"[bold red]-${result.spent - result.budget:,.2f}[/bold red]",
+        )
+
+    console.print(table)
+    over = sum(1 for result in results if result.over_budget)
+    if over:
+        console.print(f"[bold red]⚠ {over} of {len(results)} projects over budget[/bold red]")
+    else:
+        console.print(f"[dim]All {len(results)} projects within budget (estimated API cost)[/dim]")
+
+
+#endregion
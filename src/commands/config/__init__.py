@@ -0,0 +1,32 @@
+"""
+Config commands for Claude Goblin.
+
+Provides subcommands for reading and changing goblin_config.json:
+- get: Print a config value
+- set: Validate and store a config value
+- unset: Revert a key to its default
+- list: Show every settable key with its value
+"""
+#region Imports
+import typer
+
+from src.commands.config import values
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="config",
+    help="Read and change Claude Goblin settings",
+    no_args_is_help=True,
+)
+#endregion
+
+
+#region Command Registration
+app.command(name="get")(values.config_get_command)
+app.command(name="set")(values.config_set_command)
+app.command(name="unset")(values.config_unset_command)
+app.command(name="list")(values.config_list_command)
+#endregion
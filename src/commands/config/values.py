@@ -0,0 +1,187 @@
+"""
+Config get/set commands for Claude Goblin.
+
+Reads and writes the simple keys in ~/.claude/goblin_config.json with the
+same validation the config loaders apply, so a typo is rejected instead of
+silently falling back to a default.
+"""
+#region Imports
+import json
+from typing import Any
+
+import typer
+from rich.console import Console
+from rich.table import Table
+
+from src.config import user_config
+from src.config.user_config import (
+    CONFIG_KEY_ALIASES,
+    SETTABLE_CONFIG_KEYS,
+    get_env_override,
+    get_setting,
+    load_config,
+    parse_config_value,
+    resolve_config_key,
+    set_config_value,
+    unset_config_value,
+)
+
+#endregion
+
+
+#region Constants
+# Keys with nested values: shown by `get` but edited in the file itself
+STRUCTURED_CONFIG_KEYS = (
+    "profiles",
+    "extra_sources",
+    "watch_actions",
+    "sync_provider",
+    "sync_providers",
+    "sync_config",
+    "device_accounts",
+    "storage_format",
+    "device_id",
+)
+#endregion
+
+
+#region Commands
+
+
+def config_get_command(
+    key: str = typer.Argument(..., help="Config key (e.g. plan, storage_mode, export_format)"),
+) -> None:
+    """
+    Print the effective value of a config key.
+
+    Prints the value in effect: a CCG_* environment variable, then the
+    config file, then the default. Nested values print as JSON.
+
+    Examples:
+        ccg config get plan
+        ccg config get export_output
+    """
+    console = Console()
+    name = _known_key(console, key, include_structured=True)
+    default = SETTABLE_CONFIG_KEYS[name][1] if name in SETTABLE_CONFIG_KEYS else None
+    value = get_setting(name, default)
+    console.print(_format_value(value), markup=False, highlight=False)
+
+
+def config_set_command(
+    key: str = typer.Argument(..., help="Config key (e.g. plan, storage_mode, export_format)"),
+    value: str = typer.Argument(..., help="New value"),
+) -> None:
+    """
+    Validate and store a config value.
+
+    Examples:
+        ccg config set plan max-20x
+        ccg config set export_output "~/Pictures/claude-{year}.{format}"
+        ccg config set store_content true
+    """
+    console = Console()
+    name = _known_key(console, key, include_structured=True)
+    if name not in SETTABLE_CONFIG_KEYS:
+        console.print(
+            f"[yellow]'{name}' has a structured value; edit {user_config.CONFIG_PATH} "
+            "or use its setup command (ccg sync setup, ccg setup).[/yellow]"
+        )
+        raise typer.Exit(1)
+
+    try:
+        parsed = parse_config_value(name, value)
+    except ValueError as e:
+        console.print(f"[red]Invalid value for {name}: {e}[/red]")
+        raise typer.Exit(1)
+
+    set_config_value(name, parsed)
+    console.print(f"[green]{name} = {_format_value(parsed)}[/green]")
+    override = get_env_override(name)
+    if override is not None and override[1] is not None:
+        console.print(f"[yellow]{override[0]} is set and overrides this value[/yellow]")
+    if name == "storage_mode":
+        console.print("[dim]Run 'ccg update usage --force' to re-ingest history in the new mode[/dim]")
+
+
+def config_unset_command(
+    key: str = typer.Argument(..., help="Config key to revert to its default"),
+) -> None:
+    """
+    Remove a config key so its default applies again.
+
+    Examples:
+        ccg config unset export_output
+    """
+    console = Console()
+    name = _known_key(console, key, include_structured=False)
+
+    if unset_config_value(name):
+        default = SETTABLE_CONFIG_KEYS[name][1]
+        console.print(f"[green]{name} reset to default ({_format_value(default)})[/green]")
+    else:
+        console.print(f"[dim]{name} was not set[/dim]")
+
+
+def config_list_command() -> None:
+    """
+    Show every settable config key with its value, where it comes from,
+    and its default.
+
+    Examples:
+        ccg config list
+    """
+    console = Console()
+    config = load_config()
+
+    table = Table(title="Claude Goblin Config", show_header=True)
+    table.add_column("Key", style="cyan")
+    table.add_column("Value")
+    table.add_column("Source")
+    table.add_column("Default", style="dim")
+    table.add_column("Description", style="dim")
+    for name, (description, default) in SETTABLE_CONFIG_KEYS.items():
+        override = get_env_override(name)
+        if override is not None and override[1] is not None:
+            value, source = _format_value(override[1]), override[0]
+        elif name in config:
+            value, source = _format_value(config[name]), "config"
+        else:
+            value, source = _format_value(default), "default"
+        if override is not None and override[1] is None:
+            source += f" [yellow]({override[0]} invalid, ignored)[/yellow]"
+        table.add_row(name, value, source, _format_value(default), description)
+
+    console.print(table)
+    console.print(f"[dim]Config file: {user_config.CONFIG_PATH}[/dim]")
+    aliases = ", ".join(f"{alias} → {name}" for alias, name in CONFIG_KEY_ALIASES.items())
+    console.print(f"[dim]Aliases: {aliases}[/dim]")
+
+
+#endregion
+
+
+#region Helpers
+
+
+def _known_key(console: Console, key: str, include_structured: bool) -> str:
+    """Resolve a typed key, or exit listing the keys that exist."""
+    name = resolve_config_key(key)
+    known = list(SETTABLE_CONFIG_KEYS) + (list(STRUCTURED_CONFIG_KEYS) if include_structured else [])
+    if name not in known:
+        console.print(f"[red]Unknown config key: {key}[/red]")
+        console.print(f"[dim]Known keys: {', '.join(known)}[/dim]")
+        raise typer.Exit(1)
+    return name
+
+
+def _format_value(value: Any) -> str:
+    """Plain text for a config value (JSON for lists, dicts, and booleans)."""
+    if value is None:
+        return "(unset)"
+    if isinstance(value, str):
+        return value
+    return json.dumps(value)
+
+
+#endregion
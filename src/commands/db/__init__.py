@@ -0,0 +1,28 @@
+"""
+Database maintenance commands for Claude Goblin.
+
+Provides subcommands for repairing derived tables:
+- backfill-snapshots: Regenerate daily snapshots from individual records
+- encrypt: Convert the SQLite database to SQLCipher
+"""
+#region Imports
+import typer
+
+from src.commands.db import backfill, encrypt
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="db",
+    help="Database maintenance",
+    no_args_is_help=True,
+)
+#endregion
+
+
+#region Command Registration
+app.command(name="backfill-snapshots")(backfill.backfill_snapshots_command)
+app.command(name="encrypt")(encrypt.encrypt_command)
+#endregion
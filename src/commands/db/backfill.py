@@ -0,0 +1,115 @@
+"""
+Daily snapshot backfill for Claude Goblin.
+
+Regenerates daily_snapshots (and the model mix and hourly totals kept
+beside them) from usage_records for every date that has records, then
+checks each date against its records. Useful after imports, purges, or
+rebucketing, when snapshots drifted from the records they summarize.
+"""
+#region Imports
+import typer
+from rich.console import Console
+from rich.progress import BarColumn, MofNCompleteColumn, Progress, TextColumn
+
+from src.config.user_config import is_read_only
+from src.storage import api
+from src.storage.backups import create_backup
+
+#endregion
+
+
+#region Constants
+# Dates recomputed per storage call; one progress step each
+BACKFILL_BATCH_DAYS = 30
+
+# Mismatched dates listed after a failed verification
+MISMATCHES_SHOWN = 10
+#endregion
+
+
+#region Functions
+
+
+def backfill_snapshots(dates: list[str], progress: Progress | None = None) -> None:
+    """
+    Recompute daily snapshots for the given dates in batches.
+
+    Args:
+        dates: Dates (YYYY-MM-DD) with usage records
+        progress: Progress bar to advance by one per date, if any
+    """
+    task = progress.add_task("Recomputing", total=len(dates)) if progress else None
+    for i in range(0, len(dates), BACKFILL_BATCH_DAYS):
+        batch = dates[i:i + BACKFILL_BATCH_DAYS]
+        api.recompute_daily_snapshots(batch)
+        if progress:
+            progress.advance(task, len(batch))
+
+
+def _print_mismatches(console: Console, mismatches: list[dict]) -> None:
+    for mismatch in mismatches[:MISMATCHES_SHOWN]:
+        records, snapshot = mismatch["records"], mismatch["snapshot"]
+        stored = f"{snapshot['total_tokens']:,} tokens" if snapshot else "no snapshot"
+        console.print(f"  {mismatch['date']}: records {records['total_tokens']:,} tokens, snapshot {stored}")
+    if len(mismatches) > MISMATCHES_SHOWN:
+        console.print(f"  [dim]... and {len(mismatches) - MISMATCHES_SHOWN} more[/dim]")
+
+
+#endregion
+
+
+#region Command
+
+
+def backfill_snapshots_command(
+    check: bool = typer.Option(False, "--check", help="Only verify snapshots against records, change nothing"),
+) -> None:
+    """
+    Regenerate daily snapshots from individual usage records.
+
+    Recomputes every date that has records (full storage mode), backing up
+    the database first, then verifies each date's snapshot matches its
+    records. Dates without records keep their snapshots.
+
+    Examples:
+        ccg db backfill-snapshots
+        ccg db backfill-snapshots --check
+    """
+    console = Console()
+    if not check and is_read_only():
+        console.print("[red]Read-only mode: not rewriting snapshots (use --check to verify only)[/red]")
+        raise typer.Exit(1)
+
+    dates = api.get_record_dates()
+    if not dates:
+        console.print("[yellow]No individual usage records to rebuild from.[/yellow]")
+        console.print("[dim]Snapshots are the only history in aggregate storage mode.[/dim]")
+        raise typer.Exit(1)
+
+    if not check:
+        backup = create_backup("pre-backfill")
+        if backup is not None:
+            console.print(f"[dim]Backed up the database to {backup}[/dim]")
+        with Progress(
+            TextColumn("[bold #ff8800]{task.description}"),
+            BarColumn(complete_style="#ff8800"),
+            MofNCompleteColumn(),
+            TextColumn("days"),
+            console=console,
+            transient=True,
+        ) as progress:
+            backfill_snapshots(dates, progress)
+        console.print(f"[green]✓ Recomputed {len(dates):,} days ({dates[0]} to {dates[-1]})[/green]")
+
+    with console.status("[bold #ff8800]Verifying snapshots...", spinner="dots", spinner_style="#ff8800"):
+        mismatches = api.verify_daily_snapshots()
+    if mismatches:
+        console.print(f"[red]✗ {len(mismatches)} of {len(dates):,} days disagree with their records[/red]")
+        _print_mismatches(console, mismatches)
+        if check:
+            console.print("[dim]Fix with: ccg db backfill-snapshots[/dim]")
+        raise typer.Exit(1)
+    console.print(f"[green]✓ All {len(dates):,} days match their records[/green]")
+
+
+#endregion
@@ -0,0 +1,58 @@
+"""
+Database encryption for Claude Goblin.
+
+Converts the plaintext SQLite usage database into a SQLCipher-encrypted
+one and turns the "encryption" config key on, so every later connection
+opens it with the key from CCG_DB_KEY or the OS keychain.
+"""
+#region Imports
+import typer
+from rich.console import Console
+
+from src.config.user_config import get_storage_format, is_encryption_enabled, is_read_only, set_config_value
+from src.storage import api
+from src.storage.encryption import encrypt_database, get_database_key
+
+#endregion
+
+
+#region Command
+
+
+def encrypt_command() -> None:
+    """
+    Encrypt the SQLite usage database with SQLCipher.
+
+    Needs the encryption extra (pip install 'claude-goblin[encryption]') and
+    a key in CCG_DB_KEY or the OS keychain. The encrypted copy replaces the
+    file only once complete, so no plaintext backup is left behind; backups
+    taken earlier stay plaintext.
+
+    Examples:
+        CCG_DB_KEY=... ccg db encrypt
+        keyring set claude-goblin usage-db && ccg db encrypt
+    """
+    console = Console()
+    if is_read_only():
+        console.print("[red]Read-only mode: not encrypting the database[/red]")
+        raise typer.Exit(1)
+    if get_storage_format() != "sqlite":
+        console.print("[red]Encryption is only available for the SQLite storage format[/red]")
+        raise typer.Exit(1)
+    if is_encryption_enabled():
+        console.print("[yellow]Database encryption is already on.[/yellow]")
+        return
+
+    db_path = api.current_db_path()
+    key = get_database_key()
+    if db_path.exists():
+        api.checkpoint_database(db_path)
+        api.release_storage_handle(db_path)
+        encrypt_database(db_path, key)
+
+    set_config_value("encryption", True)
+    console.print(f"[green]✓ Encrypted {db_path}[/green]")
+    console.print("[dim]Earlier backups are still plaintext: ccg restore usage --list[/dim]")
+
+
+#endregion
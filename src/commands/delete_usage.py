@@ -45,8 +45,8 @@ def run(console: Console) -> None:
             console.print(f"  Days: {db_stats['total_days']}")
             console.print(f"  Range: {db_stats['oldest_date']} to {db_stats['newest_date']}\n")
 
-        # Delete the database file
-        db_path.unlink()
+        # Delete the database file (and its write-ahead log)
+        api.remove_database_files(db_path)
         console.print("[green]✓ Successfully deleted historical usage database[/green]")
         console.print(f"[dim]Deleted: {db_path}[/dim]")
 
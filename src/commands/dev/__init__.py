@@ -0,0 +1,26 @@
+"""
+Developer commands for Claude Goblin (hidden from --help).
+
+Provides subcommands for working on ccg itself:
+- validate-corpus: Report transcript lines and fields the parser doesn't handle
+"""
+#region Imports
+import typer
+
+from src.commands.dev import validate_corpus
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="dev",
+    help="Developer tools for working on ccg",
+    no_args_is_help=True,
+)
+#endregion
+
+
+#region Command Registration
+app.command(name="validate-corpus")(validate_corpus.validate_corpus_command)
+#endregion
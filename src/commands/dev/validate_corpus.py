@@ -0,0 +1,64 @@
+"""
+Corpus validation command for Claude Goblin.
+
+Runs the transcript parser over user-supplied files and reports lines it
+lost and fields, entry types, and content blocks it doesn't know, so a
+Claude Code format change can be caught (and filed with an example) before
+it skews usage numbers.
+"""
+#region Imports
+import json
+from pathlib import Path
+
+import typer
+from rich.console import Console
+from rich.table import Table
+
+from src.commands.update_usage import _print_parse_report
+from src.config.settings import get_claude_data_dir
+from src.data.corpus import validate_corpus
+
+#endregion
+
+
+#region Command
+
+
+def validate_corpus_command(
+    paths: list[Path] = typer.Argument(None, help="Transcripts or directories (default: the Claude data directory)"),
+    as_json: bool = typer.Option(False, "--json", help="Print the report as JSON"),
+) -> None:
+    """
+    Report what the parser doesn't understand in a set of transcripts.
+
+    Exits with status 1 when a line was lost or anything unknown turned up.
+
+    Examples:
+        ccg dev validate-corpus                         Check every local transcript
+        ccg dev validate-corpus ~/Downloads/odd.jsonl   Check a file from a bug report
+        ccg dev validate-corpus tests/fixtures/transcripts --json
+    """
+    console = Console()
+    report = validate_corpus([path.expanduser() for path in paths or [get_claude_data_dir()]])
+
+    if as_json:
+        # Plain print: Rich would wrap and highlight the JSON
+        print(json.dumps(report.to_dict(), indent=2))
+        raise typer.Exit(0 if report.clean else 1)
+
+    console.print(f"[bold]Parsed {report.records:,} records from {report.files:,} transcripts[/bold]")
+    _print_parse_report(console, report.stats, None)
+    if report.unknown:
+        table = Table(title="Unknown to the parser", title_justify="left")
+        table.add_column("Item", style="yellow")
+        table.add_column("Count", justify="right")
+        table.add_column("First seen", style="dim", overflow="fold")
+        for item, count in report.unknown.most_common():
+            table.add_row(item, f"{count:,}", report.examples[item])
+        console.print(table)
+    else:
+        console.print("[green]No unknown fields, entry types, or content blocks[/green]")
+    raise typer.Exit(0 if report.clean else 1)
+
+
+#endregion
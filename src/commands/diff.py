@@ -0,0 +1,166 @@
+"""
+Snapshot diff command for Claude Goblin.
+
+Compares the current database against an older database file or a JSON
+snapshot saved with `ccg diff --save`: new and dropped projects, token and
+cost deltas, and model mix shifts. Useful after merging machines or for
+monthly bookkeeping.
+"""
+#region Imports
+import json
+from datetime import datetime
+from pathlib import Path
+
+from rich.console import Console
+from rich.table import Table
+
+from src.aggregation.snapshot_diff import UsageSnapshot, build_usage_snapshot, diff_snapshots
+from src.commands.update_usage import ingest_token_usage
+from src.storage import api
+from src.utils._system import atomic_write_text
+from src.utils.folders import short_folder
+
+#endregion
+
+
+#region Helper Functions
+
+
+def _current_snapshot() -> UsageSnapshot:
+    return build_usage_snapshot(
+        datetime.now().isoformat(timespec="seconds"),
+        api.load_daily_snapshots(),
+        api.get_daily_projects(),
+    )
+
+
+def _load_snapshot(path: Path) -> UsageSnapshot:
+    """
+    Snapshot of a saved JSON snapshot or another database file.
+
+    Databases are read from a temporary copy, so opening one written by an
+    older release never migrates the original.
+
+    Raises:
+        ValueError: If the file is not a snapshot or database
+    """
+    if path.suffix == ".json":
+        try:
+            return UsageSnapshot.from_dict(json.loads(path.read_text(encoding="utf-8")))
+        except json.JSONDecodeError as e:
+            raise ValueError(f"{path} is not valid JSON: {e}") from e
+
+    with api.database_copy(path) as (backend, copy):
+        try:
+            days = backend.load_daily_snapshots(db_path=copy)
+            projects = backend.get_daily_projects(db_path=copy)
+        except Exception as e:
+            raise ValueError(f"cannot read {path} as a usage database: {e}") from e
+    created_at = datetime.fromtimestamp(path.stat().st_mtime).isoformat(timespec="seconds")
+    return build_usage_snapshot(created_at, days, projects)
+
+
+def _signed(value: float, money: bool = False) -> str:
+    if money:
+        return f"{'+' if value >= 0 else '-'}${abs(value):,.2f}"
+    return f"{value:+,}"
+
+
+#endregion
+
+
+#region Command
+
+
+def run(
+    console: Console,
+    old: Path | None = None,
+    save: Path | None = None,
+    limit: int = 15,
+    fast: bool = False,
+) -> int:
+    """
+    Compare the current database against an older one, or save a snapshot.
+
+    Args:
+        console: Rich console for output
+        old: Older database file or JSON snapshot to compare against
+        save: Write the current snapshot to this JSON file
+        limit: Most projects listed
+        fast: Skip ingestion and read from the database only
+
+    Returns:
+        Exit code (0 on success)
+    """
+    if old is not None and not old.exists():
+        console.print(f"[red]Not found: {old}[/red]")
+        return 1
+
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    current = _current_snapshot()
+    if save is not None:
+        save = save.expanduser()
+        save.parent.mkdir(parents=True, exist_ok=True)
+        atomic_write_text(save, json.dumps(current.to_dict(), indent=2))
+        console.print(f"[green]✓ Saved snapshot to {save.absolute()}[/green]")
+        if old is None:
+            return 0
+
+    try:
+        previous = _load_snapshot(old)
+    except ValueError as e:
+        console.print(f"[red]{e}[/red]")
+        return 1
+
+    diff = diff_snapshots(previous, current)
+    span = f"{previous.first_date} to {previous.last_date}" if previous.first_date else "no data"
+    console.print(f"[bold cyan]Changes since {old.name}[/bold cyan] [dim]({span}, taken {previous.created_at})[/dim]\n")
+
+    totals = Table(title="Totals", title_justify="left")
+    totals.add_column("Metric")
+    totals.add_column("Before", justify="right")
+    totals.add_column("Now", justify="right")
+    totals.add_column("Change", justify="right", style="#ff8800")
+    for label, before, now in (
+        ("Tokens", previous.total_tokens, current.total_tokens),
+        ("Sessions", previous.sessions, current.sessions),
+        ("Prompts", previous.prompts, current.prompts),
+        ("Projects", len(previous.projects), len(current.projects)),
+    ):
+        totals.add_row(label, f"{before:,}", f"{now:,}", _signed(now - before))
+    totals.add_row("Est. cost", f"${previous.cost:,.2f}", f"${current.cost:,.2f}", _signed(diff.cost, money=True))
+    console.print(totals)
+
+    if diff.projects:
+        table = Table(title="Projects", title_justify="left")
+        table.add_column("Project", overflow="crop")
+        table.add_column("Status")
+        table.add_column("Tokens", justify="right", style="#ff8800")
+        table.add_column("Cost", justify="right", style="green")
+        styles = {"new": "green", "dropped": "red", "changed": "dim"}
+        for delta in diff.projects[:limit]:
+            table.add_row(
+                short_folder(delta.folder),
+                f"[{styles[delta.status]}]{delta.status}[/{styles[delta.status]}]",
+                _signed(delta.tokens),
+                _signed(delta.cost, money=True),
+            )
+        console.print(table)
+        if len(diff.projects) > limit:
+            console.print(f"[dim]{len(diff.projects) - limit} more projects changed (--limit to show more)[/dim]")
+    else:
+        console.print("[dim]No project changes.[/dim]")
+
+    if diff.model_shifts:
+        console.print("\n[bold]Model mix[/bold]")
+        for model, before, after in diff.model_shifts:
+            console.print(f"  {model}: {before:.0f}% → {after:.0f}% [dim]({after - before:+.0f} pts)[/dim]")
+    else:
+        console.print("\n[dim]Model mix unchanged.[/dim]")
+    return 0
+
+
+#endregion
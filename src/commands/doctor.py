@@ -0,0 +1,369 @@
+"""
+Health checks for Claude Goblin.
+
+Each check returns a CheckResult with the fix to run when it is not ok;
+`ccg doctor` prints them (or, with --json, emits them as JSON) and exits
+non-zero when any check fails, so it can run from scripts or CI.
+"""
+#region Imports
+import json
+from dataclasses import dataclass
+from datetime import datetime
+from pathlib import Path
+
+from rich.console import Console
+from rich.table import Table
+
+from src.config.settings import get_claude_jsonl_files
+from src.config.user_config import get_storage_mode, is_read_only
+from src.data.jsonl_parser import is_known_field
+from src.errors import MissingDataDirError
+from src.hooks import usage as usage_hook
+from src.storage import api
+
+#endregion
+
+
+#region Constants
+# Days without a hook-triggered update before the usage hook is flagged
+HOOK_STALE_DAYS = 3
+
+# Quarantined entries shown by `ccg doctor --quarantine`
+QUARANTINE_SHOW_LIMIT = 50
+
+# Unknown transcript fields named in the check detail
+UNKNOWN_FIELDS_SHOWN = 5
+
+STATUS_STYLES = {
+    "ok": ("✓", "green"),
+    "warn": ("⚠", "yellow"),
+    "fail": ("✗", "red"),
+}
+
+# Severity reported by `ccg doctor --json` for each status
+STATUS_SEVERITIES = {
+    "ok": "info",
+    "warn": "warning",
+    "fail": "error",
+}
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class CheckResult:
+    """
+    Outcome of one health check.
+
+    Attributes:
+        name: Short check name
+        status: "ok", "warn", or "fail"
+        detail: Human-readable explanation
+        fix: Command or step that resolves a warning or failure
+    """
+
+    name: str
+    status: str
+    detail: str
+    fix: str | None = None
+
+    def to_dict(self) -> dict:
+        """JSON-ready form for `ccg doctor --json`."""
+        return {
+            "id": self.name.lower().replace(" ", "_"),
+            "name": self.name,
+            "status": self.status,
+            "severity": STATUS_SEVERITIES[self.status],
+            "detail": self.detail,
+            "fix": self.fix,
+        }
+#endregion
+
+
+#region Functions
+
+
+def check_data_dir() -> CheckResult:
+    """
+    Fail when there are no Claude Code transcripts to read.
+
+    Returns:
+        CheckResult for the transcript directory
+    """
+    name = "Data directory"
+    try:
+        files = get_claude_jsonl_files()
+    except MissingDataDirError as e:
+        return CheckResult(name, "fail", str(e), fix=e.hint)
+    if not files:
+        return CheckResult(
+            name, "warn", "Found, but holds no transcripts yet",
+            fix="Run a Claude Code session, or point --data-dir at the right directory",
+        )
+    return CheckResult(name, "ok", f"{len(files):,} transcript{'s' if len(files) != 1 else ''}")
+
+
+def check_database() -> list[CheckResult]:
+    """
+    Check the usage database's integrity, indexes, and daily totals.
+
+    Runs before other checks: those open the database through
+    init_database, which would quietly recreate missing indexes.
+
+    Returns:
+        CheckResults for integrity, indexes, and daily snapshots
+    """
+    db_path = api.current_db_path()
+    if not db_path.exists():
+        return [CheckResult("Database", "warn", f"No database at {db_path}", fix="ccg update usage")]
+
+    health = api.get_database_health()
+    results = []
+    errors = health["integrity_errors"]
+    if errors:
+        more = f" (+{len(errors) - 1} more)" if len(errors) > 1 else ""
+        results.append(CheckResult(
+            "Database integrity", "fail", f"{errors[0]}{more}",
+            fix="Restore a backup: ccg restore usage --list",
+        ))
+    else:
+        results.append(CheckResult("Database integrity", "ok", str(db_path)))
+
+    missing = health["missing_indexes"]
+    if missing:
+        results.append(CheckResult(
+            "Database indexes", "warn", f"Missing {', '.join(missing)}; queries will be slow",
+            fix="ccg update usage",
+        ))
+    else:
+        results.append(CheckResult("Database indexes", "ok", "All present"))
+
+    # Aggregate mode keeps daily totals without records by design
+    orphaned = health["orphaned_snapshot_days"]
+    if orphaned and get_storage_mode() == "full":
+        results.append(CheckResult(
+            "Daily snapshots", "warn",
+            f"{orphaned} days have daily totals but no usage records; totals and breakdowns will disagree",
+            fix="Restore a backup that has the records: ccg restore usage --list",
+        ))
+    else:
+        results.append(CheckResult("Daily snapshots", "ok", "Match the stored records"))
+    return results
+
+
+def check_pricing() -> CheckResult:
+    """
+    Warn about models with usage but no pricing, whose cost reads as $0.
+
+    Returns:
+        CheckResult for pricing coverage
+    """
+    name = "Pricing"
+    unpriced = api.get_database_stats().get("unpriced_models", [])
+    if not unpriced:
+        return CheckResult(name, "ok", "Every model in use has pricing")
+    return CheckResult(
+        name, "warn", f"No pricing for {', '.join(unpriced)}; costs undercount their usage",
+        fix=f"ccg pricing add {unpriced[0]} --like claude-sonnet-4-5",
+    )
+
+
+def _installed_usage_hook_commands() -> list[str]:
+    """
+    Commands of usage hooks in the user and current project settings.
+    """
+    commands = []
+    for settings_path in (Path.home() / ".claude" / "settings.json", Path.cwd() / ".claude" / "settings.json"):
+        try:
+            settings = json.loads(settings_path.read_text(encoding="utf-8"))
+        except (OSError, json.JSONDecodeError):
+            continue
+        for hook in settings.get("hooks", {}).get("Stop", []):
+            if usage_hook.is_hook(hook):
+                commands.extend(h.get("command", "") for h in hook.get("hooks", []))
+    return commands
+
+
+def check_usage_hook(stale_days: int = HOOK_STALE_DAYS, now: datetime | None = None) -> CheckResult:
+    """
+    Flag a usage hook that is installed but has stopped firing or failing.
+
+    Args:
+        stale_days: Days without a hook-triggered run before failing
+        now: Reference time (default: now)
+
+    Returns:
+        CheckResult for the usage hook
+    """
+    name = "Usage hook"
+    commands = _installed_usage_hook_commands()
+    if not commands:
+        return CheckResult(name, "warn", "Not installed", fix="ccg setup hooks usage")
+    if not any("--hook" in command for command in commands):
+        return CheckResult(
+            name, "warn", "Installed without health tracking",
+            fix="ccg setup hooks usage",
+        )
+
+    runs = api.get_update_runs(trigger="hook", limit=20)
+    if not runs:
+        return CheckResult(
+            name, "fail", "Installed but has never reported a run",
+            fix="Run a Claude Code session, then check that `ccg update usage --hook` works from your shell",
+        )
+
+    now = now or datetime.now()
+    last = runs[0]
+    idle_days = (now - datetime.fromisoformat(last["started_at"])).days
+    if idle_days >= stale_days:
+        return CheckResult(
+            name, "fail",
+            f"Hasn't fired in {idle_days} days (last run {last['started_at'][:16].replace('T', ' ')})",
+            fix="ccg setup hooks usage",
+        )
+    if last["exit_status"] != 0:
+        return CheckResult(
+            name, "fail", f"Last run failed: {last['error'] or 'unknown error'}",
+            fix="ccg update usage",
+        )
+
+    failures = sum(1 for run in runs if run["exit_status"] != 0)
+    detail = f"Last fired {last['started_at'][:16].replace('T', ' ')}"
+    if failures:
+        return CheckResult(
+            name, "warn", f"{detail}; {failures} of the last {len(runs)} runs failed",
+            fix="ccg update usage",
+        )
+    return CheckResult(name, "ok", detail)
+
+
+def check_quarantine() -> CheckResult:
+    """
+    Warn when ingestion held back entries with bad timestamps.
+
+    Returns:
+        CheckResult for the quarantine
+    """
+    name = "Quarantine"
+    count = api.count_quarantined_entries()
+    if not count:
+        return CheckResult(name, "ok", "No entries with bad timestamps")
+    return CheckResult(
+        name, "warn", f"{count} entries with bad timestamps held back",
+        fix="ccg doctor --quarantine",
+    )
+
+
+def check_transcript_format() -> CheckResult:
+    """
+    Warn when ingestion met transcript fields the parser doesn't know.
+
+    New fields usually mean Claude Code changed its transcript format,
+    which can move data the parser reads. Fields a later release has
+    learned about are no longer reported.
+
+    Returns:
+        CheckResult for transcript fields
+    """
+    name = "Transcript format"
+    unknown = [row for row in api.get_unknown_fields() if not is_known_field(row["field"])]
+    if not unknown:
+        return CheckResult(name, "ok", "No unknown transcript fields seen")
+    shown = ", ".join(f"{row['field']} ({row['occurrences']:,}x)" for row in unknown[:UNKNOWN_FIELDS_SHOWN])
+    more = f" and {len(unknown) - UNKNOWN_FIELDS_SHOWN} more" if len(unknown) > UNKNOWN_FIELDS_SHOWN else ""
+    return CheckResult(
+        name, "warn",
+        f"Unknown fields since {min(row['first_seen'] for row in unknown)[:10]}: {shown}{more}; "
+        f"Claude Code's transcript format may have changed",
+        fix="ccg dev validate-corpus",
+    )
+
+
+def review_quarantine(console: Console, dismiss: bool = False) -> None:
+    """
+    List quarantined entries, optionally dismissing them afterwards.
+
+    Only the listed entries (the newest QUARANTINE_SHOW_LIMIT) are
+    dismissed, so entries quarantined since, or not shown, keep warning.
+
+    Args:
+        console: Rich console for output
+        dismiss: Mark every listed entry as reviewed so it stops warning
+    """
+    entries = api.get_quarantined_entries(limit=QUARANTINE_SHOW_LIMIT)
+    if not entries:
+        console.print("[green]No quarantined entries[/green]")
+        return
+    total = api.count_quarantined_entries()
+
+    table = Table(title=f"Quarantined entries ({total})", title_justify="left")
+    table.add_column("Reason", style="yellow")
+    table.add_column("Timestamp")
+    table.add_column("Session", style="dim")
+    table.add_column("File", style="dim", overflow="fold")
+    for entry in entries:
+        table.add_row(
+            entry["reason"],
+            entry["raw_timestamp"] or "-",
+            entry["session_id"][:8],
+            entry["file_path"],
+        )
+    console.print(table)
+    if total > len(entries):
+        console.print(f"[dim]... and {total - len(entries)} more[/dim]")
+
+    if dismiss and is_read_only():
+        console.print("[red]Read-only mode: not dismissing entries[/red]")
+    elif dismiss:
+        dismissed = api.dismiss_quarantined_entries([(entry["file_path"], entry["entry_id"]) for entry in entries])
+        console.print(f"[green]Dismissed {dismissed} entries[/green]")
+        if total > len(entries):
+            console.print("[dim]Run again to review the rest[/dim]")
+    else:
+        console.print("[dim]Fix or ignore the transcripts, then run: ccg doctor --quarantine --dismiss[/dim]")
+
+
+def run(console: Console, hook_days: int = HOOK_STALE_DAYS, as_json: bool = False) -> int:
+    """
+    Run all health checks and print the results.
+
+    Args:
+        console: Rich console for output
+        hook_days: Days without a hook-triggered update before failing
+        as_json: Print a JSON report on stdout instead of the styled list
+
+    Returns:
+        Exit code: 1 if any check failed, else 0
+    """
+    results = [
+        check_data_dir(),
+        *check_database(),
+        check_pricing(),
+        check_usage_hook(hook_days),
+        check_quarantine(),
+        check_transcript_format(),
+    ]
+    healthy = not any(result.status == "fail" for result in results)
+
+    if as_json:
+        # Plain print: Rich would wrap and highlight the JSON
+        print(json.dumps({
+            "healthy": healthy,
+            "checked_at": datetime.now().isoformat(timespec="seconds"),
+            "checks": [result.to_dict() for result in results],
+        }, indent=2))
+        return 0 if healthy else 1
+
+    console.print("[bold]Claude Goblin doctor[/bold]\n")
+    for result in results:
+        symbol, color = STATUS_STYLES[result.status]
+        console.print(f"  [{color}]{symbol}[/{color}] [bold]{result.name}:[/bold] {result.detail}")
+        if result.fix:
+            console.print(f"      [dim]fix: {result.fix}[/dim]")
+
+    return 0 if healthy else 1
+
+
+#endregion
@@ -1,54 +1,435 @@
 #region Imports
+import csv
+import json
+import re
+import string
 import sys
-from datetime import datetime
+from collections.abc import Iterable
+from datetime import date, datetime
 from pathlib import Path
 
 from rich.console import Console
 
-from src.aggregation.daily_stats import aggregate_all
+from src.aggregation.daily_stats import aggregate_all, aggregate_daily_snapshots
 from src.config.settings import get_claude_jsonl_files
-from src.config.user_config import get_storage_mode
+from src.config.user_config import (
+    EXPORT_TEMPLATE_FIELDS,
+    get_export_format,
+    get_export_output_template,
+    get_storage_mode,
+)
 from src.data.jsonl_parser import parse_all_jsonl_files
+from src.errors import GoblinError
+from src.models.usage_record import UsageRecord
 from src.storage import api
 from src.storage.api import (
     get_database_stats,
-    load_historical_records,
+    iter_historical_records,
+    load_daily_snapshots,
     save_snapshot,
 )
-from src.utils._system import open_file
+from src.storage.pricing import PricingTable
+from src.utils._system import atomic_write, open_file
 
 #endregion
 
 
+#region Constants
+DEFAULT_EXPORT_DIR = Path.home() / ".claude" / "usage"
+
+# Formats `ccg export projects` can write
+PROJECT_EXPORT_FORMATS = ("csv",)
+
+PROJECT_INDEX_FILE = "index.csv"
+
+# Formats `ccg export data` can write
+DATA_EXPORT_FORMATS = ("csv", "jsonl", "parquet")
+
+# One row per usage record; message content is never exported
+DATA_EXPORT_COLUMNS = (
+    "timestamp", "date", "session_id", "message_uuid", "message_type", "model", "folder",
+    "git_branch", "version", "input_tokens", "output_tokens", "cache_creation_tokens",
+    "cache_read_tokens", "cache_creation_1h_tokens", "thinking_tokens", "total_tokens", "cost",
+    "is_subagent", "agent_id", "entrypoint", "hostname", "project",
+)
+
+# Rows buffered per Parquet row group
+PARQUET_BATCH_SIZE = 50_000
+#endregion
+
+
 #region Functions
 
 
+def resolve_export_template(
+    template: str,
+    format_type: str,
+    year: int,
+    start_date: date | None = None,
+    end_date: date | None = None,
+    metric: str = "tokens",
+) -> Path:
+    """
+    Expand an "export_output" path template into an output path.
+
+    Relative templates resolve under ~/.claude/usage/, a trailing "/" means
+    "this directory, default filename", and a missing extension gets the
+    export format appended.
+
+    Args:
+        template: Template such as "~/Pictures/claude/{year}-{metric}.{format}"
+        format_type: "png" or "svg"
+        year: Year being exported
+        start_date: Start of a custom window ({from}; "start" when open)
+        end_date: End of a custom window ({to}; today when open)
+        metric: Heatmap metric name ({metric})
+
+    Returns:
+        Absolute output path
+
+    Raises:
+        ValueError: If the template uses an unknown placeholder
+    """
+    names = {name for _, name, _, _ in string.Formatter().parse(template) if name is not None}
+    unknown = sorted(names - set(EXPORT_TEMPLATE_FIELDS))
+    if unknown:
+        allowed = ", ".join(f"{{{field}}}" for field in EXPORT_TEMPLATE_FIELDS)
+        raise ValueError(
+            f"Unknown placeholder in export_output: {', '.join(f'{{{n}}}' for n in unknown)} "
+            f"(allowed: {allowed})"
+        )
+
+    today = datetime.now().date()
+    rendered = template.format(**{
+        "year": year,
+        "metric": metric,
+        "format": format_type,
+        "date": today.isoformat(),
+        "from": start_date.isoformat() if start_date else "start",
+        "to": (end_date or today).isoformat(),
+    })
+    if rendered.endswith(("/", "\\")):
+        rendered += f"claude-usage.{format_type}"
+
+    output_path = Path(rendered).expanduser()
+    if not output_path.is_absolute():
+        output_path = DEFAULT_EXPORT_DIR / output_path
+    if not output_path.suffix:
+        output_path = output_path.with_suffix(f".{format_type}")
+    return output_path
+
+
+def _project_file_stem(folder: str, taken: set[str]) -> str:
+    """Filename stem for a project: its folder name, slugged and made unique."""
+    name = folder.replace("\\", "/").rstrip("/").split("/")[-1]
+    stem = re.sub(r"[^A-Za-z0-9._-]+", "-", name).strip("-.") or "project"
+    if stem.lower() == Path(PROJECT_INDEX_FILE).stem:
+        stem = f"{stem}-project"
+    unique, n = stem, 2
+    while unique.lower() in taken:
+        unique, n = f"{stem}-{n}", n + 1
+    taken.add(unique.lower())
+    return unique
+
+
+def write_project_csvs(daily_projects: dict[str, dict[str, dict]], out_dir: Path) -> list[dict]:
+    """
+    Write one CSV of daily tokens and cost per project, plus an index.
+
+    Args:
+        daily_projects: {date: {folder: {"tokens", "cost"}}} (api.get_daily_projects)
+        out_dir: Directory to write into (created if missing)
+
+    Returns:
+        Index rows (project, file, first_date, last_date, active_days,
+        tokens, cost), costliest project first
+    """
+    by_project: dict[str, list[tuple[str, int, float]]] = {}
+    for date in sorted(daily_projects):
+        for folder, values in daily_projects[date].items():
+            by_project.setdefault(folder, []).append((date, values.get("tokens", 0), values.get("cost", 0.0)))
+
+    out_dir.mkdir(parents=True, exist_ok=True)
+    taken: set[str] = set()
+    index = []
+    for folder in sorted(by_project, key=lambda f: (-sum(d[2] for d in by_project[f]), f)):
+        days = by_project[folder]
+        filename = f"{_project_file_stem(folder, taken)}.csv"
+        with atomic_write(out_dir / filename, newline="") as f:
+            writer = csv.writer(f)
+            writer.writerow(["date", "tokens", "cost"])
+            writer.writerows((date, tokens, f"{cost:.4f}") for date, tokens, cost in days)
+        index.append({
+            "project": folder,
+            "file": filename,
+            "first_date": days[0][0],
+            "last_date": days[-1][0],
+            "active_days": len(days),
+            "tokens": sum(d[1] for d in days),
+            "cost": sum(d[2] for d in days),
+        })
+
+    with atomic_write(out_dir / PROJECT_INDEX_FILE, newline="") as f:
+        writer = csv.writer(f)
+        writer.writerow(["project", "file", "first_date", "last_date", "active_days", "tokens", "cost"])
+        writer.writerows(
+            (row["project"], row["file"], row["first_date"], row["last_date"], row["active_days"],
+             row["tokens"], f"{row['cost']:.4f}")
+            for row in index
+        )
+    return index
+
+
+def _record_row(record: UsageRecord, pricing: PricingTable) -> dict:
+    """One DATA_EXPORT_COLUMNS row for a record, with its estimated cost."""
+    usage = record.token_usage
+    return {
+        "timestamp": record.timestamp.isoformat(),
+        "date": record.date_key,
+        "session_id": record.session_id,
+        "message_uuid": record.message_uuid,
+        "message_type": record.message_type,
+        "model": record.model,
+        "folder": record.folder,
+        "git_branch": record.git_branch,
+        "version": record.version,
+        "input_tokens": usage.input_tokens if usage else 0,
+        "output_tokens": usage.output_tokens if usage else 0,
+        "cache_creation_tokens": usage.cache_creation_tokens if usage else 0,
+        "cache_read_tokens": usage.cache_read_tokens if usage else 0,
+        "cache_creation_1h_tokens": usage.cache_creation_1h_tokens if usage else 0,
+        "thinking_tokens": usage.thinking_tokens if usage else 0,
+        "total_tokens": usage.total_tokens if usage else 0,
+        "cost": record.cost(pricing),
+        "is_subagent": record.is_subagent,
+        "agent_id": record.agent_id,
+        "entrypoint": record.entrypoint,
+        "hostname": record.hostname,
+        "project": record.project,
+    }
+
+
+def _write_parquet(rows: Iterable[dict], output: Path) -> int:
+    """Stream rows into a Parquet file in PARQUET_BATCH_SIZE row groups."""
+    try:
+        import pyarrow as pa
+        import pyarrow.parquet as pq
+    except ImportError:
+        raise GoblinError(
+            "Parquet export needs pyarrow",
+            "Install it with: pip install pyarrow (or export --format csv|jsonl)",
+        )
+
+    schema = pa.schema([
+        (name, pa.string()) for name in DATA_EXPORT_COLUMNS[:9]
+    ] + [
+        (name, pa.int64()) for name in DATA_EXPORT_COLUMNS[9:16]
+    ] + [
+        ("cost", pa.float64()),
+        ("is_subagent", pa.bool_()),
+        ("agent_id", pa.string()),
+        ("entrypoint", pa.string()),
+        ("hostname", pa.string()),
+        ("project", pa.string()),
+    ])
+    count = 0
+    with atomic_write(output, binary=True) as f, pq.ParquetWriter(f, schema) as writer:
+        batch: list[dict] = []
+        for row in rows:
+            batch.append(row)
+            if len(batch) >= PARQUET_BATCH_SIZE:
+                writer.write_table(pa.Table.from_pylist(batch, schema=schema))
+                count += len(batch)
+                batch = []
+        if batch or count == 0:
+            writer.write_table(pa.Table.from_pylist(batch, schema=schema))
+            count += len(batch)
+    return count
+
+
+def write_usage_records(
+    records: Iterable[UsageRecord],
+    pricing: PricingTable,
+    output: Path,
+    export_format: str = "csv",
+) -> int:
+    """
+    Dump usage records, one row each with its estimated cost, to a file.
+
+    Records are streamed, so the whole table never sits in memory. The
+    file is replaced only once every row is written (see atomic_write).
+
+    Args:
+        records: Records to write (api.iter_historical_records)
+        pricing: Pricing lookup for the per-row cost (api.get_pricing_table)
+        output: File to write (parent directories are created)
+        export_format: One of DATA_EXPORT_FORMATS
+
+    Returns:
+        Number of rows written
+
+    Raises:
+        ValueError: If export_format is not one of DATA_EXPORT_FORMATS
+        GoblinError: If Parquet is requested without pyarrow installed
+    """
+    if export_format not in DATA_EXPORT_FORMATS:
+        raise ValueError(f"Invalid format: {export_format} (choose from {', '.join(DATA_EXPORT_FORMATS)})")
+
+    output.parent.mkdir(parents=True, exist_ok=True)
+    rows = (_record_row(record, pricing) for record in records)
+    if export_format == "parquet":
+        return _write_parquet(rows, output)
+
+    count = 0
+    with atomic_write(output, newline="") as f:
+        if export_format == "csv":
+            writer = csv.DictWriter(f, fieldnames=DATA_EXPORT_COLUMNS)
+            writer.writeheader()
+            for row in rows:
+                writer.writerow({**row, "cost": f"{row['cost']:.6f}"})
+                count += 1
+        else:
+            for row in rows:
+                f.write(json.dumps(row) + "\n")
+                count += 1
+    return count
+
+
+def run_data(
+    console: Console,
+    output: Path | None,
+    export_format: str = "csv",
+    start_date: str | None = None,
+    end_date: str | None = None,
+    fast: bool = False,
+) -> None:
+    """
+    Export raw usage records with per-row cost for analysis elsewhere.
+
+    Args:
+        console: Rich console for output
+        output: File to write (default ./usage_records.<format>)
+        export_format: One of DATA_EXPORT_FORMATS
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+        fast: Skip updates, read from database only
+    """
+    from src.commands.update_usage import ingest_token_usage
+
+    if export_format not in DATA_EXPORT_FORMATS:
+        console.print(
+            f"[red]Invalid format: {export_format} (choose from {', '.join(DATA_EXPORT_FORMATS)})[/red]"
+        )
+        return
+
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    output = (output or Path(f"usage_records.{export_format}")).expanduser()
+    with console.status("[bold #ff8800]Writing usage records...", spinner="dots", spinner_style="#ff8800"):
+        count = write_usage_records(
+            iter_historical_records(start_date, end_date), api.get_pricing_table(), output, export_format
+        )
+
+    if count == 0:
+        output.unlink(missing_ok=True)
+        if get_storage_mode() == "aggregate":
+            console.print("[yellow]No usage records: aggregate storage mode keeps only daily totals.[/yellow]")
+        else:
+            console.print("[yellow]No usage records found. Run ccg usage to start tracking.[/yellow]")
+        return
+    console.print(f"[green]✓ Exported {count:,} record{'s' if count != 1 else ''} to: {output.absolute()}[/green]")
+
+
+def run_projects(
+    console: Console,
+    out_dir: Path,
+    export_format: str = "csv",
+    start_date: str | None = None,
+    end_date: str | None = None,
+    fast: bool = False,
+) -> None:
+    """
+    Export per-project daily tokens and cost as a bundle of files.
+
+    Args:
+        console: Rich console for output
+        out_dir: Directory for the per-project files and index
+        export_format: One of PROJECT_EXPORT_FORMATS
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+        fast: Skip updates, read from database only
+    """
+    from src.commands.update_usage import ingest_token_usage
+
+    if export_format not in PROJECT_EXPORT_FORMATS:
+        console.print(
+            f"[red]Invalid format: {export_format} (choose from {', '.join(PROJECT_EXPORT_FORMATS)})[/red]"
+        )
+        return
+
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    daily_projects = api.get_daily_projects(start_date, end_date)
+    if not daily_projects:
+        console.print("[yellow]No project data found. Run ccg usage to start tracking.[/yellow]")
+        return
+
+    index = write_project_csvs(daily_projects, out_dir)
+    console.print(
+        f"[green]✓ Exported {len(index)} project{'s' if len(index) != 1 else ''} to: {out_dir.absolute()}[/green]"
+    )
+    console.print(f"[dim]Index: {out_dir.absolute() / PROJECT_INDEX_FILE}[/dim]")
+
+
 def run(console: Console) -> None:
     """
-    Export the heatmap to PNG or SVG.
+    Export the heatmap to PNG, SVG, or an HTML report.
 
     Exports a GitHub-style activity heatmap as an image file.
     Supports PNG (default) and SVG formats, with optional file opening.
+    The HTML report is the SVG in a page whose days link to per-day
+    detail pages (sessions, projects, models), written next to it.
 
     Args:
         console: Rich console for output
 
     Flags:
-        svg: Export as SVG instead of PNG
+        svg / png / html: Output format (default: "export_format" config, else PNG)
         --open: Open file after export
         --fast: Skip updates, read directly from database (faster)
         --year YYYY or -y YYYY: Filter by year (default: current year)
+        --from YYYY-MM-DD / --to YYYY-MM-DD: Render exactly this window instead of a year
         -o FILE or --output FILE: Specify output file path
+        --scale SCALE: Color intensity scale (linear, sqrt, log, percentile; default sqrt)
+        --theme THEME: Color gradient (claude, viridis, cividis; default claude)
+        --metric METRIC: What a day's color measures (tokens, prompts; default tokens)
     """
-    from src.visualization.export import export_heatmap_png, export_heatmap_svg
+    from src.visualization.export import (
+        DEFAULT_HEATMAP_METRIC,
+        DEFAULT_HEATMAP_SCALE,
+        DEFAULT_HEATMAP_THEME,
+        HEATMAP_METRICS,
+        HEATMAP_SCALES,
+        HEATMAP_THEMES,
+        export_heatmap_png,
+        export_heatmap_svg,
+    )
 
     # Check for --fast flag
     fast_mode = "--fast" in sys.argv
 
-    # Determine format from arguments (PNG is default)
-    format_type = "png"
-    if "svg" in sys.argv:
+    # Determine format from arguments, falling back to the configured default
+    format_type = get_export_format()
+    if "html" in sys.argv:
+        format_type = "html"
+    elif "svg" in sys.argv:
         format_type = "svg"
+    elif "png" in sys.argv:
+        format_type = "png"
 
     # Check for --open flag
     should_open = "--open" in sys.argv
@@ -64,6 +445,54 @@ def run(console: Console) -> None:
                 return
             break
 
+    # Parse custom window (--from / --to YYYY-MM-DD); overrides --year
+    range_bounds = {}
+    for flag in ("--from", "--to"):
+        for i, arg in enumerate(sys.argv):
+            if arg == flag and i + 1 < len(sys.argv):
+                try:
+                    range_bounds[flag] = datetime.strptime(sys.argv[i + 1], "%Y-%m-%d").date()
+                except ValueError:
+                    console.print(f"[red]Invalid date for {flag}: {sys.argv[i + 1]} (expected YYYY-MM-DD)[/red]")
+                    return
+                break
+    start_date = range_bounds.get("--from")
+    end_date = range_bounds.get("--to")
+    if start_date and end_date and start_date > end_date:
+        console.print(f"[red]--from {start_date} is after --to {end_date}[/red]")
+        return
+    custom_range = start_date is not None or end_date is not None
+
+    # Parse color scale (--scale linear|sqrt|log|percentile)
+    scale = DEFAULT_HEATMAP_SCALE
+    for i, arg in enumerate(sys.argv):
+        if arg == "--scale" and i + 1 < len(sys.argv):
+            scale = sys.argv[i + 1]
+            if scale not in HEATMAP_SCALES:
+                console.print(f"[red]Invalid scale: {scale} (choose from {', '.join(HEATMAP_SCALES)})[/red]")
+                return
+            break
+
+    # Parse color theme (--theme claude|viridis|cividis)
+    theme = DEFAULT_HEATMAP_THEME
+    for i, arg in enumerate(sys.argv):
+        if arg == "--theme" and i + 1 < len(sys.argv):
+            theme = sys.argv[i + 1]
+            if theme not in HEATMAP_THEMES:
+                console.print(f"[red]Invalid theme: {theme} (choose from {', '.join(HEATMAP_THEMES)})[/red]")
+                return
+            break
+
+    # Parse color metric (--metric tokens|prompts)
+    metric = DEFAULT_HEATMAP_METRIC
+    for i, arg in enumerate(sys.argv):
+        if arg == "--metric" and i + 1 < len(sys.argv):
+            metric = sys.argv[i + 1]
+            if metric not in HEATMAP_METRICS:
+                console.print(f"[red]Invalid metric: {metric} (choose from {', '.join(HEATMAP_METRICS)})[/red]")
+                return
+            break
+
     # Default to current year if not specified
     if year_filter is None:
         year_filter = datetime.now().year
@@ -77,20 +506,31 @@ def run(console: Console) -> None:
             custom_output = True
             break
 
-    if not output_file:
-        output_file = f"claude-usage.{format_type}"
+    template = None if custom_output else get_export_output_template()
+    if template:
+        # Configured "export_output" template replaces the fixed default
+        try:
+            output_path = resolve_export_template(
+                template, format_type, year_filter, start_date, end_date, metric
+            )
+        except ValueError as e:
+            console.print(f"[red]{e}[/red]")
+            return
+        output_path.parent.mkdir(parents=True, exist_ok=True)
+    else:
+        if not output_file:
+            output_file = f"claude-usage.{format_type}"
 
-    # Use absolute path, or resolve based on whether -o flag was used
-    output_path = Path(output_file)
-    if not output_path.is_absolute():
-        if custom_output:
-            # If -o flag was used, resolve relative to current working directory
-            output_path = Path.cwd() / output_path
-        else:
-            # Default location: ~/.claude/usage/
-            default_dir = Path.home() / ".claude" / "usage"
-            default_dir.mkdir(parents=True, exist_ok=True)
-            output_path = default_dir / output_file
+        # Use absolute path, or resolve based on whether -o flag was used
+        output_path = Path(output_file)
+        if not output_path.is_absolute():
+            if custom_output:
+                # If -o flag was used, resolve relative to current working directory
+                output_path = Path.cwd() / output_path
+            else:
+                # Default location: ~/.claude/usage/
+                DEFAULT_EXPORT_DIR.mkdir(parents=True, exist_ok=True)
+                output_path = DEFAULT_EXPORT_DIR / output_file
 
     try:
         # Check if database exists when using --fast
@@ -125,21 +565,61 @@ def run(console: Console) -> None:
                         save_snapshot(current_records, storage_mode=get_storage_mode())
 
         # Load data from database
-        with console.status(f"[bold #ff8800]Loading data for {year_filter}...", spinner="dots", spinner_style="#ff8800"):
-            all_records = load_historical_records()
+        period_label = (
+            f"{start_date or 'start'} to {end_date or 'today'}" if custom_range else str(year_filter)
+        )
+        with console.status(f"[bold #ff8800]Loading data for {period_label}...", spinner="dots", spinner_style="#ff8800"):
+            # Only the displayed window is read; a year export scales its
+            # colors against that year's busiest day
+            if custom_range:
+                range_start = start_date.isoformat() if start_date else None
+                range_end = end_date.isoformat() if end_date else None
+            else:
+                range_start, range_end = f"{year_filter}-01-01", f"{year_filter}-12-31"
+            if fast_mode:
+                # Daily snapshots (with their model mix) carry everything the
+                # heatmap needs, so skip loading per-message usage_records
+                days = load_daily_snapshots(start_date=range_start, end_date=range_end)
+                stats = aggregate_daily_snapshots(days) if days else None
+            else:
+                # Stream records straight into the aggregator so memory stays
+                # flat on large databases
+                stats = aggregate_all(iter_historical_records(start_date=range_start, end_date=range_end))
+                if not stats.daily_stats:
+                    # Aggregate-mode databases keep daily snapshots only
+                    days = load_daily_snapshots(start_date=range_start, end_date=range_end)
+                    stats = aggregate_daily_snapshots(days) if days else None
 
-            if not all_records:
+            if stats is None:
                 console.print("[yellow]No usage data found in database. Run 'ccg usage' to ingest data first.[/yellow]")
                 return
 
-            stats = aggregate_all(all_records)
-
         console.print(f"[cyan]Exporting to {format_type.upper()}...[/cyan]")
 
+        range_kwargs = {"start_date": start_date, "end_date": end_date} if custom_range else {}
+        range_kwargs["scale"] = scale
+        range_kwargs["theme"] = theme
+        range_kwargs["metric"] = metric
+        range_kwargs["annotations"] = api.get_annotations(range_start, range_end)
         if format_type == "png":
-            export_heatmap_png(stats, output_path, year=year_filter)
+            export_heatmap_png(stats, output_path, year=year_filter, **range_kwargs)
+        elif format_type == "html":
+            from src.visualization.day_report import export_html_report
+
+            pages = export_html_report(stats, output_path, year=year_filter, **range_kwargs)
+            console.print(
+                f"[dim]{pages} day page{'s' if pages != 1 else ''} in "
+                f"{output_path.with_name(output_path.stem + '-days')}[/dim]"
+            )
         else:
-            export_heatmap_svg(stats, output_path, year=year_filter)
+            # Per-day cost and top project for the SVG's hover tooltips
+            if custom_range:
+                day_projects = api.get_daily_projects(range_start, range_end)
+            else:
+                day_projects = api.get_daily_projects(f"{year_filter}-01-01", f"{year_filter}-12-31")
+            export_heatmap_svg(
+                stats, output_path, year=year_filter, day_projects=day_projects, **range_kwargs
+            )
 
         console.print(f"[green]✓ Exported to: {output_path.absolute()}[/green]")
 
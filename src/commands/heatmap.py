@@ -0,0 +1,90 @@
+#region Imports
+import webbrowser
+from datetime import date, datetime
+
+from rich.console import Console
+
+from src.aggregation.daily_stats import aggregate_daily_snapshots
+from src.storage import api
+from src.visualization.day_report import load_day_details, render_day_page
+from src.visualization.export import DEFAULT_HEATMAP_SCALE, DEFAULT_HEATMAP_THEME, render_heatmap_svg
+from src.visualization.heatmap_server import make_heatmap_server
+
+#endregion
+
+
+#region Functions
+
+
+def run(
+    console: Console,
+    port: int,
+    year: int | None = None,
+    start_date: date | None = None,
+    end_date: date | None = None,
+    open_browser: bool = False,
+    scale: str = DEFAULT_HEATMAP_SCALE,
+    theme: str = DEFAULT_HEATMAP_THEME,
+) -> None:
+    """
+    Serve an auto-refreshing heatmap preview on localhost.
+
+    Each image request renders from daily snapshots, and the page reloads
+    the image whenever ingestion touches the refresh marker (hooks,
+    `ccg update usage`, or a running `ccg usage --live`). Active days link
+    to a detail page with that day's sessions, projects, and models.
+
+    Args:
+        console: Rich console for output
+        port: Port to listen on
+        year: Year to display (default: current year)
+        start_date: First day of a custom window (overrides year)
+        end_date: Last day of a custom window (overrides year)
+        open_browser: Open the preview in the default browser
+        scale: Color intensity scale, one of HEATMAP_SCALES
+        theme: Color gradient, one of HEATMAP_THEMES
+    """
+    custom_range = start_date is not None or end_date is not None
+    range_start = start_date.isoformat() if start_date else None
+    range_end = end_date.isoformat() if end_date else None
+    if year is None:
+        year = datetime.now().year
+
+    def render_svg() -> str:
+        days = api.load_daily_snapshots(start_date=range_start, end_date=range_end)
+        stats = aggregate_daily_snapshots(days)
+        annotations = api.get_annotations(range_start, range_end)
+        if custom_range:
+            return render_heatmap_svg(
+                stats, start_date=start_date, end_date=end_date, day_links="/day/{date}", scale=scale, theme=theme,
+                annotations=annotations,
+            )
+        return render_heatmap_svg(
+            stats, year=year, day_links="/day/{date}", scale=scale, theme=theme, annotations=annotations
+        )
+
+    def render_day(day: str) -> str | None:
+        details = load_day_details(day, day).get(day)
+        return render_day_page(details, "/") if details else None
+
+    try:
+        server = make_heatmap_server(render_svg, port=port, render_day=render_day)
+    except OSError as e:
+        console.print(f"[red]Cannot listen on port {port}: {e}[/red]")
+        return
+
+    url = f"http://127.0.0.1:{server.server_address[1]}/"
+    console.print(f"[green]Serving heatmap at {url}[/green]")
+    console.print("[dim]Refreshes when usage data updates. Press Ctrl+C to stop.[/dim]")
+    if open_browser:
+        webbrowser.open(url)
+
+    try:
+        server.serve_forever()
+    except KeyboardInterrupt:
+        console.print("\n[cyan]Stopped heatmap server[/cyan]")
+    finally:
+        server.server_close()
+
+
+#endregion
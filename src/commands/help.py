@@ -31,15 +31,62 @@ Automatically saves historical snapshots to preserve data beyond the 30-day roll
   ccg usage                          Show usage stats (single shot)
   ccg usage --live                   Show usage with auto-refresh
   ccg stats                          Show historical database statistics
+  ccg stats --mcp                    Add MCP server calls and token traffic
+  ccg stats --by branch              Tokens and cost per git branch (--from/--to)
+  ccg stats --by host                Tokens and cost per machine (--from/--to)
+  ccg sessions                       List top sessions by estimated cost
+  ccg sessions tag <id> <tag>        Label a session (e.g. debugging, greenfield)
+  ccg sessions tags                  Tokens and cost per session tag
+  ccg projects                       List top projects by estimated cost
+  ccg projects idle                  List projects idle for 30+ days
+  ccg projects automate <folder>     Count a folder's sessions as automated usage
+  ccg projects commits [repo]        Claude cost per git commit and changed line
+  ccg budget set <folder> <amount>   Budget a project at $amount per month
+  ccg budget status                  This month's spend against each project budget
+  ccg annotate add <date> <label>    Mark a day (e.g. "launched v2") on heatmaps and stats
+  ccg annotate list                  List annotations
+  ccg annotate remove <date> [label] Remove a day's annotations
+  ccg recap --month YYYY-MM          Narrative recap of a month's usage
+  ccg diff <old.db|old.json>         Compare usage against an older database or snapshot
+  ccg import db <path>               Merge another machine's usage_history.db
+  ccg pull ssh://user@host           Ingest a remote machine's transcripts over SSH
+  ccg db backfill-snapshots          Regenerate daily snapshots from usage records and verify
+  ccg db encrypt                     Encrypt the SQLite database with SQLCipher
   ccg export                         Export heatmap as PNG image (default)
                                      Use --svg for SVG format
+                                     Use --html for a page with clickable day details
                                      Use --open to open after export
                                      Use -o FILE to specify output path
                                      Use --year YYYY to select year (default: current)
+                                     Use --scale linear|sqrt|log|percentile for colors
+                                     Use --theme viridis|cividis for colorblind-safe colors
+  ccg export projects --dir ./out     One CSV of daily tokens/cost per project plus index.csv
+  ccg export data --format parquet   Raw usage records with cost per row (csv|jsonl|parquet)
+  ccg heatmap --serve                Serve an auto-refreshing heatmap on localhost
+  ccg wrapped [YEAR]                 Export a shareable year-in-review image
+  ccg punchcard                      Weekday x hour-of-day activity punch card (--svg to export)
   ccg update usage                   Update historical database with latest data
+  ccg update usage --watch           Ingest continuously as transcripts are written
+  ccg update usage --dry-run         Preview what an update would add
+  ccg update usage --strict          Report transcript lines that could not be parsed
+  ccg update usage --redact          Store only numeric usage data (no text, paths, branches)
+  ssh box cat ... | ccg update usage --stdin --hostname box
+                                     Ingest transcript lines piped from another machine
+  ccg doctor                         Check data dir, database, pricing, and hook health
+  ccg doctor --quarantine            Review entries held back for bad timestamps
+  ccg doctor --json                  Health report as JSON (severity, fix) for scripts
   ccg remove usage -f                Delete all historical data (creates backup)
-  ccg restore usage                  Restore database from backup (.db.bak file)
+  ccg restore usage                  Restore the newest database backup
+  ccg restore usage --list           List backups (restore one with --from <#|file>)
+  ccg pricing add <model> --like <m> Price a model by copying another's rates
+  ccg pricing set <model> --input <$> --output <$>
+                                     Override a model's rates (negotiated or new models)
+  ccg pricing update                 Fetch current model rates from a remote pricing file
+  ccg config set <key> <value>       Change a setting (ccg config list shows all)
   ccg setup hooks <type>             Configure Claude Code hooks (usage|audio|png)
+  ccg setup hooks audio --list-sounds
+                                     List and preview the sounds audio hooks can play
+  ccg hooks test <type>              Run an installed hook with a sample payload
   ccg setup container                Setup devcontainer for safe Claude execution
   ccg remove hooks [type]            Remove hooks (usage|audio|png, or all)
   ccg help                           Show this help message
@@ -0,0 +1,26 @@
+"""
+Hook commands for Claude Goblin.
+
+Provides subcommands for checking installed Claude Code hooks:
+- test: Run a hook type's installed commands with a synthetic payload
+"""
+#region Imports
+import typer
+
+from src.commands.hooks import testing
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="hooks",
+    help="Check installed Claude Code hooks",
+    no_args_is_help=True,
+)
+#endregion
+
+
+#region Command Registration
+app.command(name="test")(testing.hooks_test_command)
+#endregion
@@ -0,0 +1,87 @@
+"""
+Hook test command for Claude Goblin.
+
+Runs the commands a hook type installed with the payload Claude Code would
+send, and reports how each one exited.
+"""
+#region Imports
+import json
+import tempfile
+from pathlib import Path
+
+import typer
+from rich.console import Console
+
+from src.hooks.runner import HOOK_MATCHERS, find_installed_hooks, run_hook, synthetic_payload
+
+#endregion
+
+
+#region Command
+
+
+def hooks_test_command(
+    hook_type: str = typer.Argument(..., help=f"Hook type: {', '.join(HOOK_MATCHERS)}"),
+    show_payload: bool = typer.Option(False, "--show-payload", help="Print the JSON sent to each hook"),
+) -> None:
+    """
+    Run an installed hook with a synthetic payload and report the result.
+
+    Feeds each command the hook type installed (project and user level) the
+    JSON Claude Code sends on stdin, then shows its exit code, run time, and
+    output. Hooks run for real: usage ingests, png exports, audio plays.
+
+    Examples:
+        ccg hooks test usage
+        ccg hooks test audio --show-payload
+    """
+    console = Console()
+    if hook_type not in HOOK_MATCHERS:
+        console.print(f"[red]Unknown hook type: {hook_type}[/red]")
+        console.print(f"Valid types: {', '.join(HOOK_MATCHERS)}")
+        raise typer.Exit(1)
+
+    cwd = Path.cwd()
+    hooks = find_installed_hooks(hook_type, cwd)
+    if not hooks:
+        console.print(f"[yellow]No {hook_type} hook installed at project or user level[/yellow]")
+        console.print(f"[dim]Install with: ccg setup hooks {hook_type}[/dim]")
+        raise typer.Exit(1)
+
+    failed = 0
+    with tempfile.TemporaryDirectory(prefix="ccg-hooks-test-") as tmp:
+        transcript = Path(tmp) / "transcript.jsonl"
+        transcript.touch()
+        for hook in hooks:
+            payload = synthetic_payload(hook.event, cwd, transcript)
+            console.print(f"[bold]{hook.event}[/bold] [dim]({hook.scope}: {hook.settings_path})[/dim]")
+            console.print(f"  [dim]$ {hook.command}[/dim]")
+            if show_payload:
+                console.print(f"  [dim]stdin: {json.dumps(payload)}[/dim]")
+
+            with console.status(f"[bold #ff8800]Running {hook.event} hook...", spinner="dots", spinner_style="#ff8800"):
+                run = run_hook(hook, payload, cwd)
+
+            if run.ok:
+                console.print(f"  [green]✓ Exit 0 in {run.seconds:.1f}s[/green]")
+            elif run.exit_code is None:
+                failed += 1
+                console.print(f"  [red]✗ {run.stderr}[/red]")
+            else:
+                failed += 1
+                meaning = "blocks the action" if run.exit_code == 2 else "error"
+                console.print(f"  [red]✗ Exit {run.exit_code} ({meaning}) in {run.seconds:.1f}s[/red]")
+            for label, output in (("stdout", run.stdout), ("stderr", run.stderr if run.exit_code is not None else "")):
+                if output.strip():
+                    console.print(f"  [dim]{label}:[/dim]")
+                    for line in output.rstrip().splitlines():
+                        console.print(f"    {line}", markup=False, highlight=False)
+            console.print()
+
+    if failed:
+        console.print(f"[red]{failed} of {len(hooks)} hook command{'s' if len(hooks) != 1 else ''} failed[/red]")
+        raise typer.Exit(1)
+    console.print(f"[green]{len(hooks)} hook command{'s' if len(hooks) != 1 else ''} ran cleanly[/green]")
+
+
+#endregion
@@ -0,0 +1,26 @@
+"""
+Import commands for Claude Goblin.
+
+Provides subcommands for bringing usage from elsewhere into the local database:
+- db: Merge another machine's usage_history.db
+"""
+#region Imports
+import typer
+
+from src.commands.imports import db
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="import",
+    help="Merge usage from other machines into the local database",
+    no_args_is_help=True,
+)
+#endregion
+
+
+#region Command Registration
+app.command(name="db")(db.import_db_command)
+#endregion
@@ -0,0 +1,154 @@
+"""
+Database import command for Claude Goblin.
+
+Merges the usage records of another database (e.g. a laptop's
+usage_history.db) into the local one. Records already stored are skipped by
+the usual message identity, so importing the same file twice adds nothing.
+"""
+#region Imports
+from itertools import islice
+from pathlib import Path
+
+import typer
+from rich.console import Console
+
+from src.aggregation.session_stats import build_sessions
+from src.config.user_config import get_storage_mode, is_read_only
+from src.storage import api
+from src.storage.backups import create_backup
+
+#endregion
+
+
+#region Constants
+# Records read from the other database per save, bounding memory on large histories
+IMPORT_CHUNK_RECORDS = 50_000
+
+# ingest_status key prefix (+ resolved path) holding the file signature of
+# each profile database last merged into a merged --profile view
+PROFILE_MERGE_KEY_PREFIX = "merged_profile:"
+#endregion
+
+
+#region Functions
+
+
+def merge_database(path: Path) -> tuple[int, int]:
+    """
+    Merge another database's usage records into the local database.
+
+    Records are saved in full mode, which skips any already stored and
+    recomputes daily snapshots. The other file is read through a temporary
+    copy and never modified. Imported records carry no device and keep the
+    hostname they were stored with (none for databases older than it).
+
+    Args:
+        path: Database file to merge (.duckdb for DuckDB, anything else SQLite)
+
+    Returns:
+        (merged, skipped) record counts
+    """
+    merged = total = 0
+    with api.database_copy(path) as (backend, copy):
+        records = backend.iter_historical_records(db_path=copy)
+        while chunk := list(islice(records, IMPORT_CHUNK_RECORDS)):
+            total += len(chunk)
+            merged += api.save_snapshot(
+                chunk, storage_mode="full", device_id="", device_name="", device_type="", hostname=""
+            )
+            api.save_sessions(build_sessions(chunk))
+    return merged, total - merged
+
+
+def _file_signature(path: Path) -> str:
+    """Modification time and size of a database and its write-ahead sidecars."""
+    parts = []
+    for file in (path, *(path.with_name(path.name + suffix) for suffix in api.DATABASE_SIDECAR_SUFFIXES)):
+        try:
+            stat = file.stat()
+        except OSError:
+            continue
+        parts.append(f"{file.name}:{stat.st_mtime_ns}:{stat.st_size}")
+    return ";".join(parts)
+
+
+def merge_profile_databases(paths: list[Path]) -> int:
+    """
+    Bring the current (merged --profile) database up to date from its profiles' databases.
+
+    Each profile database is merged like `ccg import db`, so history the
+    profile kept after Claude Code deleted the transcripts is included. A
+    database unchanged since its last merge is skipped. Nothing is merged
+    in read-only or aggregate mode, which keep no records to merge into.
+
+    Args:
+        paths: The selected profiles' database files (missing ones are skipped)
+
+    Returns:
+        Records merged
+    """
+    if is_read_only() or get_storage_mode() != "full":
+        return 0
+    merged = 0
+    for path in paths:
+        if not path.is_file():
+            continue
+        key = PROFILE_MERGE_KEY_PREFIX + str(path.resolve())
+        signature = _file_signature(path)
+        if api.get_ingest_status(key) == signature:
+            continue
+        merged += merge_database(path)[0]
+        api.set_ingest_status(key, signature)
+    return merged
+
+
+#endregion
+
+
+#region Command
+
+
+def import_db_command(
+    path: Path = typer.Argument(..., help="usage_history.db (or .duckdb) from another machine"),
+) -> None:
+    """
+    Merge another machine's database into the local one.
+
+    Examples:
+        ccg import db ~/Downloads/usage_history.db
+        ccg import db /mnt/laptop/.claude/usage/usage_history.db
+    """
+    console = Console()
+    path = path.expanduser()
+    if not path.is_file():
+        console.print(f"[red]Not found: {path}[/red]")
+        raise typer.Exit(1)
+    if is_read_only():
+        console.print("[red]Read-only mode: not merging into the database[/red]")
+        raise typer.Exit(1)
+    if path.resolve() == api.current_db_path().resolve():
+        console.print("[red]That is the local database; nothing to merge.[/red]")
+        raise typer.Exit(1)
+    if get_storage_mode() != "full":
+        console.print("[yellow]Importing needs individual records, which aggregate mode does not keep.[/yellow]")
+        console.print("[dim]Switch with: ccg config set storage_mode full[/dim]")
+        raise typer.Exit(1)
+
+    backup = create_backup("import")
+    if backup is not None:
+        console.print(f"[dim]Backed up the local database to {backup}[/dim]")
+    try:
+        with console.status("[bold #ff8800]Merging records...", spinner="dots", spinner_style="#ff8800"):
+            merged, skipped = merge_database(path)
+    except Exception as e:
+        console.print(f"[red]Cannot read {path} as a usage database: {e}[/red]")
+        raise typer.Exit(1)
+
+    if not merged and not skipped:
+        console.print(f"[yellow]{path.name} has no individual records (it may use aggregate mode).[/yellow]")
+        return
+    console.print(f"[green]✓ Merged {merged:,} records from {path.name}[/green]")
+    console.print(f"[dim]Skipped {skipped:,} already in the local database; daily snapshots recomputed[/dim]")
+
+
+#endregion
@@ -0,0 +1,32 @@
+"""
+Pricing commands for Claude Goblin.
+
+Provides subcommands for managing model pricing used in cost estimates:
+- add: Price a model by copying another model's rates
+- set: Override a model's rates (kept in the config, so they survive re-init)
+- remove: Drop a rate override
+- update: Fetch current rates from a remote pricing file
+"""
+#region Imports
+import typer
+
+from src.commands.pricing import add, overrides, update
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="pricing",
+    help="Model pricing for cost estimates",
+    no_args_is_help=True,
+)
+#endregion
+
+
+#region Command Registration
+app.command(name="add")(add.pricing_add_command)
+app.command(name="set")(overrides.pricing_set_command)
+app.command(name="remove")(overrides.pricing_remove_command)
+app.command(name="update")(update.pricing_update_command)
+#endregion
@@ -0,0 +1,45 @@
+"""
+Pricing add command for Claude Goblin.
+
+Clones an existing model's rates onto a model that has no pricing row, so
+`ccg stats` stops excluding it from cost estimates.
+"""
+#region Imports
+import typer
+from rich.console import Console
+
+from src.config.user_config import is_read_only
+from src.storage import api
+
+#endregion
+
+
+#region Command
+
+
+def pricing_add_command(
+    model: str = typer.Argument(..., help="Model name as it appears in usage records"),
+    like: str = typer.Option(..., "--like", help="Existing model whose rates to copy (e.g. claude-sonnet-4-5)"),
+) -> None:
+    """
+    Price a model by copying another model's rates.
+
+    Examples:
+        ccg pricing add claude-sonnet-4-7 --like claude-sonnet-4-5
+    """
+    console = Console()
+    if is_read_only():
+        console.print("[red]Read-only mode: not changing model pricing[/red]")
+        raise typer.Exit(1)
+
+    try:
+        source = api.clone_model_pricing(model, like)
+    except ValueError as e:
+        console.print(f"[red]{e}[/red]")
+        raise typer.Exit(1)
+
+    console.print(f"[green]Priced {model} like {source}[/green]")
+    console.print("[dim]Run 'ccg stats' to see updated cost estimates[/dim]")
+
+
+#endregion
@@ -0,0 +1,86 @@
+"""
+Pricing set/remove commands for Claude Goblin.
+
+Stores per-model rate overrides in the "pricing_overrides" config key, for
+negotiated rates or models newer than the bundled pricing. Being config
+rather than database rows, they survive re-initializing, rebuilding, or
+restoring the database.
+"""
+#region Imports
+import typer
+from rich.console import Console
+
+from src.config.user_config import get_pricing_overrides, set_pricing_overrides
+
+#endregion
+
+
+#region Command
+
+
+def pricing_set_command(
+    model: str = typer.Argument(..., help="Model name as it appears in usage records, or a pricing row to replace"),
+    input_price: float = typer.Option(..., "--input", min=0, help="Input tokens, $ per million"),
+    output_price: float = typer.Option(..., "--output", min=0, help="Output tokens, $ per million"),
+    cache_write: float = typer.Option(
+        None, "--cache-write", min=0, help="5-minute cache writes, $ per million (default: 1.25x input)",
+    ),
+    cache_read: float = typer.Option(
+        None, "--cache-read", min=0, help="Cache reads, $ per million (default: 0.1x input)",
+    ),
+    cache_write_1h: float = typer.Option(
+        None, "--cache-write-1h", min=0, help="1-hour cache writes, $ per million (default: 1.6x --cache-write)",
+    ),
+) -> None:
+    """
+    Set a model's rates, overriding the bundled pricing.
+
+    Applies to every cost estimate, replacing the model's pricing row (and
+    its long-context rates) or adding one for a model without pricing.
+
+    Examples:
+        ccg pricing set claude-opus-4-8 --input 4 --output 20
+        ccg pricing set claude-sonnet-5 --input 3 --output 15 --cache-write 3.75 --cache-read 0.3
+    """
+    console = Console()
+    rates = {
+        "input_per_mtok": input_price,
+        "output_per_mtok": output_price,
+        "cache_write_per_mtok": input_price * 1.25 if cache_write is None else cache_write,
+        "cache_read_per_mtok": input_price * 0.1 if cache_read is None else cache_read,
+    }
+    if cache_write_1h is not None:
+        rates["cache_write_1h_per_mtok"] = cache_write_1h
+
+    overrides = get_pricing_overrides()
+    replaced = model in overrides
+    overrides[model] = rates
+    set_pricing_overrides(overrides)
+
+    verb = "Updated" if replaced else "Set"
+    console.print(
+        f"[green]{verb} pricing for {model}: ${input_price:g} input / ${output_price:g} output per MTok[/green]"
+    )
+    console.print("[dim]Run 'ccg stats' to see updated cost estimates[/dim]")
+
+
+def pricing_remove_command(
+    model: str = typer.Argument(..., help="Model whose override to drop"),
+) -> None:
+    """
+    Drop a model's rate override, returning it to the bundled pricing.
+
+    Examples:
+        ccg pricing remove claude-opus-4-8
+    """
+    console = Console()
+    overrides = get_pricing_overrides()
+    if overrides.pop(model, None) is None:
+        console.print(f"[yellow]No pricing override for {model}[/yellow]")
+        raise typer.Exit(1)
+
+    set_pricing_overrides(overrides)
+    console.print(f"[green]Removed pricing override for {model}[/green]")
+
+
+#endregion
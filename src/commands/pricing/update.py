@@ -0,0 +1,60 @@
+"""
+Pricing update command for Claude Goblin.
+
+Fetches current Anthropic model rates from a remote pricing file (LiteLLM's
+by default, see "pricing_source") and applies them to model_pricing, so new
+models are priced before a ccg release bundles them.
+"""
+#region Imports
+import typer
+from rich.console import Console
+
+from src.config.user_config import get_pricing_source, is_read_only
+from src.storage import api
+from src.storage.pricing_remote import load_remote_pricing, update_remote_pricing
+
+#endregion
+
+
+#region Command
+
+
+def pricing_update_command(
+    source: str = typer.Option(None, "--source", help="Pricing file URL (default: the pricing_source config key)"),
+) -> None:
+    """
+    Fetch current model pricing from a remote source.
+
+    Fetched rates replace the bundled rates of the same models and add
+    models the bundled pricing lacks; `ccg pricing set` overrides still
+    win. Set pricing_auto_update to refresh during `ccg update usage`.
+
+    Examples:
+        ccg pricing update
+        ccg config set pricing_auto_update true    Refresh once a day
+    """
+    console = Console()
+    if is_read_only():
+        console.print("[red]Read-only mode: not changing model pricing[/red]")
+        raise typer.Exit(1)
+
+    url = source or get_pricing_source()
+    known = {row[0] for row in api.get_model_pricing()}
+    try:
+        models = update_remote_pricing(url)
+    except RuntimeError as e:
+        console.print(f"[red]Pricing update failed: {e}[/red]")
+        fetched_at = load_remote_pricing().get("fetched_at")
+        if fetched_at:
+            console.print(f"[dim]Keeping rates fetched {fetched_at.replace('T', ' ')}[/dim]")
+        raise typer.Exit(1)
+
+    api.sync_model_pricing()
+    new = sorted(set(models) - known)
+    console.print(f"[green]Fetched pricing for {len(models)} models from {url}[/green]")
+    if new:
+        console.print(f"[green]New: {', '.join(new)}[/green]")
+    console.print("[dim]Run 'ccg stats' to see updated cost estimates[/dim]")
+
+
+#endregion
@@ -0,0 +1,31 @@
+"""
+Project commands for Claude Goblin.
+
+`ccg projects` on its own lists projects by estimated cost. Subcommands
+give per-project usage reports:
+- idle: Projects with no recent activity, with their lifetime usage
+- automate: Tag folders whose sessions are automated, not interactive
+- commits: Claude cost per git commit in a repository
+"""
+#region Imports
+import typer
+
+from src.commands.projects import automate, commits, idle, listing
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="projects",
+    help="Per-project usage and cost reports",
+)
+#endregion
+
+
+#region Command Registration
+app.callback(invoke_without_command=True)(listing.projects_command)
+app.command(name="idle")(idle.projects_idle_command)
+app.command(name="automate")(automate.projects_automate_command)
+app.command(name="commits")(commits.projects_commits_command)
+#endregion
@@ -0,0 +1,68 @@
+"""
+Automation folder tagging for Claude Goblin.
+
+Marks project folders whose sessions are driven by scripts, CI, or agents,
+so `ccg stats` can report automated token spend apart from interactive use.
+"""
+#region Imports
+from pathlib import Path
+
+import typer
+from rich.console import Console
+
+from src.config.user_config import get_automation_folders, set_automation_folders
+
+#endregion
+
+
+#region Command
+
+
+def projects_automate_command(
+    folders: list[Path] | None = typer.Argument(None, help="Project folders to tag (default: list tagged folders)"),
+    remove: bool = typer.Option(False, "--remove", help="Untag the folders instead"),
+) -> None:
+    """
+    Tag project folders as automation.
+
+    Sessions in a tagged folder (or below it) count as automated usage in
+    `ccg stats`, alongside sessions started headless (`claude -p`, SDKs).
+
+    Examples:
+        ccg projects automate ~/bots/triage      Tag a folder
+        ccg projects automate .                  Tag the current directory
+        ccg projects automate . --remove         Untag it
+        ccg projects automate                    List tagged folders
+    """
+    console = Console()
+    tagged = get_automation_folders()
+
+    if not folders:
+        if not tagged:
+            console.print("[dim]No automation folders tagged. Tag one with: ccg projects automate <folder>[/dim]")
+            return
+        console.print("[bold]Automation folders[/bold]")
+        for folder in tagged:
+            console.print(f"  {folder}")
+        return
+
+    paths = [str(folder.expanduser().resolve()) for folder in folders]
+    if remove:
+        missing = [path for path in paths if path not in tagged]
+        tagged = [folder for folder in tagged if folder not in paths]
+        for path in missing:
+            console.print(f"[yellow]Not tagged: {path}[/yellow]")
+        for path in paths:
+            if path not in missing:
+                console.print(f"[green]Untagged {path}[/green]")
+    else:
+        for path in paths:
+            if path in tagged:
+                console.print(f"[dim]Already tagged: {path}[/dim]")
+            else:
+                tagged.append(path)
+                console.print(f"[green]Tagged {path} as automation[/green]")
+    set_automation_folders(tagged)
+
+
+#endregion
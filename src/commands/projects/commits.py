@@ -0,0 +1,170 @@
+"""
+Cost-per-commit report for Claude Goblin.
+
+Sets a repository's daily Claude tokens and estimated cost against its git
+commits and changed lines, for judging AI spend per shipped commit.
+"""
+#region Imports
+import subprocess
+from datetime import datetime, timedelta
+from pathlib import Path
+
+import typer
+from rich.console import Console
+from rich.table import Table
+
+from src.aggregation.commit_stats import GIT_LOG_FORMAT, CommitReport, correlate_commits, parse_git_log
+from src.commands.update_usage import ingest_token_usage
+from src.storage import api
+
+#endregion
+
+
+#region Constants
+# Days covered when --from is not given
+DEFAULT_WINDOW_DAYS = 30
+#endregion
+
+
+#region Helper Functions
+
+
+def _parse_date(value: str | None, flag: str) -> str | None:
+    if value is None:
+        return None
+    try:
+        return datetime.strptime(value, "%Y-%m-%d").date().isoformat()
+    except ValueError:
+        raise typer.BadParameter(f"{flag} expects YYYY-MM-DD, got '{value}'")
+
+
+def read_git_activity(repo: Path, start_date: str, end_date: str, author: str | None = None) -> dict:
+    """
+    Non-merge commits and changed lines per local day from `git log`.
+
+    Args:
+        repo: Repository (or any directory inside it)
+        start_date: First day (YYYY-MM-DD)
+        end_date: Last day (YYYY-MM-DD)
+        author: Only commits whose author matches (git log --author)
+
+    Returns:
+        {date: {"commits", "lines_added", "lines_deleted"}} (parse_git_log)
+
+    Raises:
+        RuntimeError: If git is missing or the path is not a repository
+    """
+    command = [
+        "git", "-C", str(repo), "log", "--no-merges", "--numstat",
+        "--date=format-local:%Y-%m-%d", f"--format={GIT_LOG_FORMAT}",
+        f"--since={start_date} 00:00:00", f"--until={end_date} 23:59:59",
+    ]
+    if author:
+        command.append(f"--author={author}")
+    try:
+        result = subprocess.run(command, capture_output=True, text=True, encoding="utf-8", errors="replace")
+    except FileNotFoundError:
+        raise RuntimeError("git not found on PATH") from None
+    if result.returncode != 0:
+        raise RuntimeError(result.stderr.strip() or f"git log failed in {repo}")
+    return parse_git_log(result.stdout)
+
+
+def _money(value: float | None) -> str:
+    return f"${value:,.2f}" if value is not None else "-"
+
+
+def _print_report(console: Console, repo: Path, report: CommitReport, start_date: str, end_date: str) -> None:
+    table = Table(
+        title=f"AI Spend per Commit: {repo.name} ({start_date} to {end_date})", title_justify="left",
+    )
+    table.add_column("Date", no_wrap=True)
+    table.add_column("Commits", justify="right")
+    table.add_column("Lines +/-", justify="right")
+    table.add_column("Tokens", justify="right", style="#ff8800")
+    table.add_column("Cost", justify="right", style="green")
+    table.add_column("Cost/Commit", justify="right")
+    for day in report.days:
+        table.add_row(
+            day.date,
+            f"{day.commits:,}" if day.commits else "[dim]0[/dim]",
+            f"+{day.lines_added:,}/-{day.lines_deleted:,}" if day.commits else "",
+            f"{day.tokens:,}" if day.tokens else "[dim]0[/dim]",
+            f"${day.cost:,.2f}",
+            _money(day.cost / day.commits) if day.commits else "",
+        )
+    console.print(table)
+
+    console.print(
+        f"\n[bold]Total:[/bold] {report.commits:,} commits, {report.lines_changed:,} lines changed, "
+        f"{report.tokens:,} tokens, [green]${report.cost:,.2f}[/green]"
+    )
+    console.print(
+        f"[bold]Cost per commit:[/bold] {_money(report.cost_per_commit)}  "
+        f"[bold]per 100 changed lines:[/bold] {_money(report.cost_per_100_lines)}"
+    )
+    correlation = report.correlation
+    if correlation is not None:
+        console.print(
+            f"[bold]Daily cost vs commits correlation:[/bold] {correlation:+.2f} "
+            f"[dim](Pearson, over {len(report.days)} active days)[/dim]"
+        )
+    console.print(
+        f"[dim]{report.days_without_commits} day(s) with Claude usage and no commits; "
+        f"{report.days_without_usage} day(s) with commits and no Claude usage[/dim]"
+    )
+
+
+#endregion
+
+
+#region Command
+
+
+def projects_commits_command(
+    repo: Path = typer.Argument(Path("."), help="Git repository (default: current directory)"),
+    from_date: str = typer.Option(
+        None, "--from", help=f"First day YYYY-MM-DD (default: {DEFAULT_WINDOW_DAYS} days ago)"
+    ),
+    to_date: str = typer.Option(None, "--to", help="Last day YYYY-MM-DD (default: today)"),
+    author: str = typer.Option(None, "--author", help="Only count commits by this author (git log --author)"),
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+) -> None:
+    """
+    Correlate a repository's Claude cost with its commits and changed lines.
+
+    Claude usage counts toward the repository when it ran in the repository
+    or a folder below it. Commits are non-merge commits by author date.
+
+    Examples:
+        ccg projects commits                       This repo, last 30 days
+        ccg projects commits ~/src/api --from 2026-07-01
+        ccg projects commits . --author "Ada"      Only one engineer's commits
+    """
+    console = Console()
+    repo = repo.expanduser().resolve()
+    end_date = _parse_date(to_date, "--to") or datetime.now().date().isoformat()
+    start_date = _parse_date(from_date, "--from") or (
+        datetime.strptime(end_date, "%Y-%m-%d").date() - timedelta(days=DEFAULT_WINDOW_DAYS - 1)
+    ).isoformat()
+    if start_date > end_date:
+        raise typer.BadParameter("--from must not be after --to")
+
+    try:
+        git_days = read_git_activity(repo, start_date, end_date, author)
+    except RuntimeError as e:
+        console.print(f"[red]{e}[/red]")
+        raise typer.Exit(1)
+
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    report = correlate_commits(repo, api.get_daily_projects(start_date, end_date), git_days)
+    if not report.days:
+        console.print(f"[yellow]No commits or Claude usage in {repo} between {start_date} and {end_date}.[/yellow]")
+        return
+    _print_report(console, repo, report, start_date, end_date)
+
+
+#endregion
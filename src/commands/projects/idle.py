@@ -0,0 +1,85 @@
+"""
+Idle-project report for Claude Goblin.
+
+Lists projects that have gone quiet alongside what they used over their
+lifetime, so stale experiments are easy to spot before archiving or purging.
+"""
+#region Imports
+from datetime import datetime
+
+import typer
+from rich.console import Console
+from rich.table import Table
+
+from src.aggregation.project_stats import find_idle_projects
+from src.commands.update_usage import ingest_token_usage
+from src.storage import api
+from src.utils.folders import short_folder
+
+#endregion
+
+
+#region Command
+
+
+def projects_idle_command(
+    days: int = typer.Option(30, "--days", "-d", help="Minimum days without activity"),
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+) -> None:
+    """
+    List projects with no activity in the last N days.
+
+    Examples:
+        ccg projects idle              Quiet for 30+ days
+        ccg projects idle --days 90    Quiet for a quarter or more
+    """
+    console = Console()
+    if days < 1:
+        raise typer.BadParameter("--days must be at least 1")
+
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    projects = api.get_project_activity()
+    if not projects:
+        console.print("[yellow]No project data found. Run ccg usage to start tracking.[/yellow]")
+        return
+
+    today = datetime.now().date()
+    idle = find_idle_projects(projects, days, today)
+    if not idle:
+        console.print(f"[green]All {len(projects)} projects were active in the last {days} days.[/green]")
+        return
+
+    table = Table(title=f"Projects idle for {days}+ days", title_justify="left")
+    table.add_column("Project", overflow="crop")
+    table.add_column("Last Active", no_wrap=True)
+    table.add_column("Idle", justify="right")
+    table.add_column("First Active", no_wrap=True, style="dim")
+    table.add_column("Active Days", justify="right")
+    table.add_column("Sessions", justify="right")
+    table.add_column("Tokens", justify="right", style="#ff8800")
+    table.add_column("Cost", justify="right", style="green")
+
+    for project in idle:
+        table.add_row(
+            short_folder(project.folder),
+            project.last_date,
+            f"{project.days_idle(today)}d",
+            project.first_date,
+            f"{project.active_days:,}",
+            f"{project.sessions:,}",
+            f"{project.total_tokens:,}",
+            f"${project.cost:,.2f}",
+        )
+
+    console.print(table)
+    total_tokens = sum(p.total_tokens for p in idle)
+    console.print(
+        f"[dim]{len(idle)} of {len(projects)} projects idle, "
+        f"{total_tokens:,} lifetime tokens, ${sum(p.cost for p in idle):,.2f} estimated API cost[/dim]"
+    )
+
+
+#endregion
@@ -0,0 +1,130 @@
+"""
+Project listing for Claude Goblin.
+
+Ranks project folders by estimated API cost (or tokens), with their prompts
+and sessions, so the project table shows dollars instead of only tokens.
+"""
+#region Imports
+from datetime import datetime
+
+import typer
+from rich.console import Console
+from rich.table import Table
+
+from src.aggregation.project_stats import ProjectActivity, aggregate_by_project
+from src.commands.update_usage import ingest_token_usage
+from src.config.settings import get_claude_jsonl_files
+from src.data.jsonl_parser import parse_all_jsonl_files
+from src.storage import api
+from src.utils.folders import short_folder
+
+#endregion
+
+
+#region Helper Functions
+
+
+def _parse_date(value: str | None, flag: str) -> str | None:
+    if value is None:
+        return None
+    try:
+        return datetime.strptime(value, "%Y-%m-%d").date().isoformat()
+    except ValueError:
+        raise typer.BadParameter(f"{flag} expects YYYY-MM-DD, got '{value}'")
+
+
+def load_project_usage(
+    console: Console,
+    start_date: str | None = None,
+    end_date: str | None = None,
+) -> list[ProjectActivity]:
+    """
+    Per-project usage from usage_records, or live JSONL in aggregate mode.
+
+    Aggregate-mode databases keep no per-message folders, so projects there
+    come from the transcripts Claude Code still has on disk.
+    """
+    pricing = api.get_pricing_table()
+    if api.get_update_coverage()["total_records"] > 0:
+        return aggregate_by_project(api.iter_historical_records(start_date=start_date, end_date=end_date), pricing)
+
+    console.print("[dim]Aggregate storage mode: listing projects still on disk[/dim]")
+    records = [
+        r for r in parse_all_jsonl_files(get_claude_jsonl_files())
+        if (start_date is None or r.date_key >= start_date)
+        and (end_date is None or r.date_key <= end_date)
+    ]
+    return aggregate_by_project(records, pricing)
+
+
+#endregion
+
+
+#region Command
+
+
+def projects_command(
+    ctx: typer.Context,
+    limit: int = typer.Option(20, "--limit", "-n", help="Number of projects to show"),
+    sort: str = typer.Option("cost", "--sort", help="Rank by 'cost' or 'tokens'"),
+    from_date: str = typer.Option(None, "--from", help="Only usage on or after YYYY-MM-DD"),
+    to_date: str = typer.Option(None, "--to", help="Only usage on or before YYYY-MM-DD"),
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+) -> None:
+    """
+    List projects with tokens, prompts, sessions, and estimated cost.
+
+    Examples:
+        ccg projects                       Top 20 projects by cost
+        ccg projects --sort tokens -n 50   Top 50 by tokens
+        ccg projects --from 2026-10-01     Projects used this month
+    """
+    if ctx.invoked_subcommand is not None:
+        return
+
+    console = Console()
+    if sort not in ("cost", "tokens"):
+        raise typer.BadParameter("--sort must be 'cost' or 'tokens'")
+    start_date = _parse_date(from_date, "--from")
+    end_date = _parse_date(to_date, "--to")
+
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    with console.status("[bold #ff8800]Pricing projects...", spinner="dots", spinner_style="#ff8800"):
+        projects = load_project_usage(console, start_date, end_date)
+
+    if not projects:
+        console.print("[yellow]No projects found. Run ccg usage to start tracking.[/yellow]")
+        return
+
+    if sort == "tokens":
+        projects.sort(key=lambda p: p.total_tokens, reverse=True)
+
+    table = Table(title=f"Top {min(limit, len(projects))} Projects by {sort.title()}", title_justify="left")
+    table.add_column("Project", overflow="crop")
+    table.add_column("Last Active", no_wrap=True)
+    table.add_column("Days", justify="right")
+    table.add_column("Sessions", justify="right")
+    table.add_column("Prompts", justify="right")
+    table.add_column("Tokens", justify="right", style="#ff8800")
+    table.add_column("Cost", justify="right", style="green")
+
+    for project in projects[:limit]:
+        table.add_row(
+            short_folder(project.folder),
+            project.last_date,
+            f"{project.active_days:,}",
+            f"{project.sessions:,}",
+            f"{project.prompts:,}",
+            f"{project.total_tokens:,}",
+            f"${project.cost:,.2f}",
+        )
+
+    console.print(table)
+    total_cost = sum(p.cost for p in projects)
+    console.print(f"[dim]{len(projects):,} projects, ${total_cost:,.2f} estimated API cost in total[/dim]")
+
+
+#endregion
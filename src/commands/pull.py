@@ -0,0 +1,148 @@
+"""
+Remote pull command for Claude Goblin.
+
+Collects usage from a dev server over SSH without file sync: runs a remote
+find+cat of the Claude Code transcripts and ingests the streamed lines
+locally (like `ccg update usage --stdin`), stamping the records with the
+remote host's name so `ccg stats --by host` can tell machines apart.
+"""
+#region Imports
+import shlex
+import shutil
+import subprocess
+from urllib.parse import urlsplit
+
+from rich.console import Console
+
+from src.commands import update_usage
+from src.config.user_config import is_read_only
+
+#endregion
+
+
+#region Constants
+# Transcript directory read when the ssh:// URL has no path
+DEFAULT_REMOTE_DIR = "~/.claude/projects"
+
+# Fail instead of prompting for a password or host key mid-pull
+SSH_OPTIONS = ("-o", "BatchMode=yes")
+#endregion
+
+
+#region Functions
+
+
+def parse_ssh_target(target: str) -> tuple[str, int | None, str, str]:
+    """
+    Split an ssh://[user@]host[:port][/path] URL.
+
+    Args:
+        target: URL such as "ssh://me@devbox:2222/srv/claude/projects"
+
+    Returns:
+        Tuple of (ssh destination "user@host", port or None, remote
+        transcript directory, host name)
+
+    Raises:
+        ValueError: If the URL is not ssh:// or has no host, or the host or
+            user starts with "-" (ssh would read it as an option)
+    """
+    parts = urlsplit(target)
+    if parts.scheme != "ssh":
+        raise ValueError(f"Expected an ssh:// URL, got: {target}")
+    try:
+        host, port = parts.hostname, parts.port
+    except ValueError:
+        raise ValueError(f"Invalid port in {target}") from None
+    if not host:
+        raise ValueError(f"No host in {target}")
+    if host.startswith("-") or (parts.username or "").startswith("-"):
+        raise ValueError(f"Host and user may not start with '-': {target}")
+    destination = f"{parts.username}@{host}" if parts.username else host
+    remote_dir = parts.path if parts.path not in ("", "/") else DEFAULT_REMOTE_DIR
+    # ssh://host/~/dir is the home-relative form
+    if remote_dir.startswith("/~"):
+        remote_dir = remote_dir[1:]
+    return destination, port, remote_dir, host
+
+
+def remote_command(remote_dir: str, days: int | None = None) -> str:
+    """
+    Shell command that prints every transcript under remote_dir.
+
+    Each file is followed by a newline, so a transcript whose last line is
+    still being written cannot merge with the next file's first line.
+
+    Args:
+        remote_dir: Transcript directory on the remote ("~/" is its home)
+        days: Only files modified in the last N days
+
+    Returns:
+        Command for the remote shell
+    """
+    if remote_dir == "~" or remote_dir.startswith("~/"):
+        quoted = '"$HOME"' + (("/" + shlex.quote(remote_dir[2:])) if remote_dir[2:] else "")
+    else:
+        quoted = shlex.quote(remote_dir)
+    age = f" -mtime -{days}" if days is not None else ""
+    return (
+        f"find {quoted} -type f -name '*.jsonl'{age} "
+        """-exec sh -c 'for f; do cat "$f"; echo; done' sh {} +"""
+    )
+
+
+def run(
+    console: Console,
+    target: str,
+    days: int | None = None,
+    hostname: str | None = None,
+) -> int:
+    """
+    Pull a remote machine's transcripts over SSH and ingest them.
+
+    Args:
+        console: Rich console for output
+        target: ssh://[user@]host[:port][/path] of the remote transcripts
+        days: Only pull transcripts modified in the last N days
+        hostname: Name stamped on the records (default: the URL's host)
+
+    Returns:
+        Exit code (0 on success)
+    """
+    if is_read_only():
+        console.print("[red]Read-only mode: not updating the database[/red]")
+        return 1
+    try:
+        destination, port, remote_dir, host = parse_ssh_target(target)
+    except ValueError as e:
+        console.print(f"[red]{e}[/red]")
+        return 1
+    ssh = shutil.which("ssh")
+    if ssh is None:
+        console.print("[red]ssh not found on PATH[/red]")
+        return 1
+
+    # "--" ends ssh's options, so the destination is never read as one
+    command = [ssh, *SSH_OPTIONS, *(["-p", str(port)] if port else []), "--", destination,
+               remote_command(remote_dir, days)]
+    console.print(f"[dim]Pulling {remote_dir} from {destination}...[/dim]")
+    # stderr stays on the terminal so ssh and find errors are visible
+    with subprocess.Popen(
+        command, stdin=subprocess.DEVNULL, stdout=subprocess.PIPE, text=True, encoding="utf-8", errors="replace",
+    ) as proc:
+        update_usage.run(console, trigger="pull", stdin=proc.stdout, hostname=hostname or host)
+        proc.stdout.close()
+        status = proc.wait()
+
+    if status == 255:
+        console.print(f"[red]ssh could not connect to {destination} (exit 255)[/red]")
+        return 1
+    if status != 0:
+        # find exits non-zero for unreadable directories; what it could read was ingested
+        console.print(
+            f"[yellow]⚠ Remote command exited with status {status}; some transcripts may be missing[/yellow]"
+        )
+    return 0
+
+
+#endregion
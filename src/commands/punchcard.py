@@ -0,0 +1,75 @@
+#region Imports
+from pathlib import Path
+
+from rich.console import Console
+
+from src.aggregation.activity_matrix import build_activity_matrix
+from src.commands.export import DEFAULT_EXPORT_DIR
+from src.commands.update_usage import ingest_token_usage
+from src.storage import api
+from src.utils._system import open_file
+from src.visualization.export import DEFAULT_HEATMAP_THEME
+from src.visualization.punchcard import export_punchcard_svg, render_punchcard
+
+#endregion
+
+
+#region Functions
+
+
+def run(
+    console: Console,
+    metric: str = "tokens",
+    start_date: str | None = None,
+    end_date: str | None = None,
+    svg: bool = False,
+    output: Path | None = None,
+    fast: bool = False,
+    should_open: bool = False,
+    theme: str = DEFAULT_HEATMAP_THEME,
+) -> None:
+    """
+    Show when in the week Claude gets used, as a weekday x hour punch card.
+
+    Reads the hourly_snapshots rollup, so it works in aggregate storage
+    mode and stays fast over long histories.
+
+    Args:
+        console: Rich console for output
+        metric: "tokens" or "prompts"
+        start_date: Inclusive start (YYYY-MM-DD), or None for all history
+        end_date: Inclusive end (YYYY-MM-DD), or None for all history
+        svg: Write an SVG instead of printing to the terminal
+        output: SVG file (default: ~/.claude/usage/claude-punchcard.svg)
+        fast: Skip ingestion and read from the database only
+        should_open: Open the SVG after export
+        theme: Color gradient for the SVG, one of HEATMAP_THEMES
+    """
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    matrix = build_activity_matrix(api.get_hourly_range(start_date, end_date))
+    if matrix.peak(metric) is None:
+        console.print("[yellow]No hourly usage recorded in this range. Run ccg usage to start tracking.[/yellow]")
+        return
+
+    window = f"{start_date or 'start'} to {end_date or 'today'}"
+    if not svg:
+        console.print(f"[bold]Activity by weekday and hour[/bold] [dim]({metric}, {window})[/dim]\n")
+        render_punchcard(console, matrix, metric)
+        return
+
+    if output is None:
+        DEFAULT_EXPORT_DIR.mkdir(parents=True, exist_ok=True)
+        output = DEFAULT_EXPORT_DIR / "claude-punchcard.svg"
+    output = output.expanduser().absolute()
+
+    title = f"When you use Claude Code ({window})" if start_date or end_date else "When you use Claude Code"
+    export_punchcard_svg(matrix, output, metric, title, theme)
+    console.print(f"[green]✓ Exported to: {output}[/green]")
+    if should_open:
+        open_file(output)
+
+
+#endregion
@@ -0,0 +1,76 @@
+#region Imports
+from datetime import datetime
+
+from rich.console import Console
+
+from src.aggregation.recap import (
+    MonthRecap,
+    build_month_recap,
+    month_bounds,
+    previous_month,
+    recap_sentences,
+)
+from src.commands.update_usage import ingest_token_usage
+from src.config.user_config import is_read_only
+from src.storage import api
+
+#endregion
+
+
+#region Functions
+
+
+def load_month_recap(month: str, refresh: bool = False) -> MonthRecap:
+    """
+    Recap facts for a month, cached in monthly_summaries once it has ended.
+
+    The current month (and anything later) is always recomputed, since its
+    totals are still moving.
+
+    Args:
+        month: Month in YYYY-MM format
+        refresh: Recompute even if a stored summary exists
+
+    Returns:
+        MonthRecap for the month
+    """
+    finished = month < datetime.now().strftime("%Y-%m")
+    if finished and not refresh:
+        stored = api.get_monthly_summary(month)
+        if stored is not None:
+            return MonthRecap.from_dict(stored)
+
+    start, end = month_bounds(month)
+    recap = build_month_recap(
+        month,
+        api.load_daily_snapshots(start_date=start, end_date=end),
+        api.get_project_tokens(start, end),
+    )
+    if finished and not is_read_only():
+        api.save_monthly_summary(month, recap.to_dict())
+    return recap
+
+
+def run(console: Console, month: str, refresh: bool = False, fast: bool = False) -> None:
+    """
+    Print a narrative recap of one month's usage.
+
+    Args:
+        console: Rich console for output
+        month: Month in YYYY-MM format
+        refresh: Recompute stored summaries for finished months
+        fast: Skip ingestion and read from the database only
+    """
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    recap = load_month_recap(month, refresh=refresh)
+    previous = load_month_recap(previous_month(month), refresh=refresh)
+
+    title = datetime.strptime(month, "%Y-%m").strftime("%B %Y")
+    console.print(f"[bold cyan]Recap: {title}[/bold cyan]\n")
+    console.print(" ".join(recap_sentences(recap, previous)))
+
+
+#endregion
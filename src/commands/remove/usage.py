@@ -3,14 +3,13 @@ Remove usage command.
 
 Removes historical usage database (with automatic backup).
 """
-import shutil
-from datetime import datetime
-
 import typer
 from rich.console import Console
 
+from src.config.user_config import is_read_only
 from src.storage import api
 from src.storage.api import get_database_stats
+from src.storage.backups import create_backup
 
 
 def remove_usage_command(
@@ -38,6 +37,10 @@ def remove_usage_command(
         console.print("[yellow]To confirm deletion, use: ccg remove usage --force[/yellow]")
         return
 
+    if is_read_only():
+        console.print("[red]Read-only mode: not removing the database[/red]")
+        raise typer.Exit(1)
+
     db_path = api.current_db_path()
 
     if not db_path.exists():
@@ -53,20 +56,12 @@ def remove_usage_command(
             console.print(f"  Days: {db_stats['total_days']}")
             console.print(f"  Range: {db_stats['oldest_date']} to {db_stats['newest_date']}\n")
 
-        # Create backup before deletion
-        backup_path = db_path.parent / f"{db_path.name}.bak"
-        timestamp_backup = db_path.parent / f"{db_path.stem}.{datetime.now().strftime('%Y%m%d_%H%M%S')}{db_path.suffix}.bak"
-
-        # Always keep the .bak file for restore command
-        shutil.copy2(db_path, backup_path)
+        # Timestamped backup for `ccg restore usage`
+        backup_path = create_backup("remove", db_path)
         console.print(f"[green]Backup created: {backup_path}[/green]")
 
-        # Also keep a timestamped backup for safety
-        shutil.copy2(db_path, timestamp_backup)
-        console.print(f"[dim]Timestamped backup: {timestamp_backup}[/dim]")
-
-        # Delete the database file
-        db_path.unlink()
+        # Delete the database file (and its write-ahead log)
+        api.remove_database_files(db_path)
         console.print("\n[green]Successfully removed historical usage database[/green]")
         console.print(f"[dim]Deleted: {db_path}[/dim]")
         console.print("\n[dim]To restore: ccg restore usage[/dim]")
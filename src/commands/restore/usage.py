@@ -3,22 +3,31 @@ Restore usage command.
 
 Restores usage database from backup file.
 """
+import typer
 from rich.console import Console
 
 from src.commands import restore_backup as _restore_backup_module
 
 
-def restore_usage_command() -> None:
+def restore_usage_command(
+    list_backups: bool = typer.Option(False, "--list", help="List backups, newest first, without restoring"),
+    source: str = typer.Option(None, "--from", help="Backup to restore: its number from --list, or a file"),
+) -> None:
     """
     Restore usage database from backup file.
 
-    Restores the usage history database from a backup file (.db.bak).
-    Creates a safety backup of the current database before restoring.
-
-    Expected backup location: ~/.claude/usage/usage_history.db.bak
+    Restores the newest backup under ~/.claude/usage/backups/ unless --from
+    picks another. Creates a safety backup of the current database before
+    restoring.
 
     Examples:
-        ccg restore usage    Restore usage database from backup
+        ccg restore usage              Restore the newest backup
+        ccg restore usage --list       Show available backups
+        ccg restore usage --from 3     Restore backup #3 from --list
+        ccg restore usage --from ~/usage_history.db
     """
     console = Console()
-    _restore_backup_module.run(console)
+    if list_backups:
+        _restore_backup_module.list_command(console)
+        return
+    _restore_backup_module.run(console, source=source)
@@ -1,11 +1,12 @@
 #region Imports
-import os
 import shutil
-from datetime import datetime
 
 from rich.console import Console
+from rich.table import Table
 
+from src.config.user_config import is_read_only
 from src.storage import api
+from src.storage.backups import create_backup, list_backups, resolve_backup, rotate_backups
 
 #endregion
 
@@ -13,22 +14,60 @@ from src.storage import api
 #region Functions
 
 
-def run(console: Console) -> None:
+def list_command(console: Console) -> None:
+    """
+    Print the current database's backups, newest first, numbered for --from.
+
+    Args:
+        console: Rich console for output
+    """
+    backups = list_backups()
+    if not backups:
+        console.print("[yellow]No backups found.[/yellow]")
+        return
+
+    table = Table(title=f"Backups of {api.current_db_path().name}", title_justify="left")
+    table.add_column("#", justify="right", style="bold")
+    table.add_column("Taken")
+    table.add_column("Before")
+    table.add_column("Size", justify="right")
+    table.add_column("File", style="dim", overflow="fold")
+    for index, backup in enumerate(backups, start=1):
+        table.add_row(
+            str(index),
+            backup.created.strftime("%Y-%m-%d %H:%M:%S"),
+            backup.reason,
+            f"{backup.size / 1024 / 1024:,.1f} MB",
+            str(backup.path),
+        )
+    console.print(table)
+    console.print("[dim]Restore one with: ccg restore usage --from <#|file>[/dim]")
+
+
+def run(console: Console, source: str | None = None) -> None:
     """
     Restore database from backup file.
 
-    Restores the usage history database from a backup file (.db.bak).
-    Creates a safety backup of the current database before restoring.
+    Restores the newest backup, or the one chosen by source. The current
+    database is backed up first, so a restore can itself be undone.
 
     Args:
         console: Rich console for output
+        source: Backup number from --list (1 = newest) or a backup file
     """
-    db_path = api.current_db_path()
-    backup_path = db_path.parent / f"{db_path.name}.bak"
+    if is_read_only():
+        console.print("[red]Read-only mode: not restoring over the database[/red]")
+        return
 
-    if not backup_path.exists():
-        console.print("[yellow]No backup file found.[/yellow]")
-        console.print(f"[dim]Expected location: {backup_path}[/dim]")
+    db_path = api.current_db_path()
+    try:
+        backup_path = resolve_backup(source or "1")
+    except ValueError as e:
+        if source is None:
+            console.print("[yellow]No backup file found.[/yellow]")
+            console.print("[dim]Backups are made before ccg remove usage, restore usage, and import db[/dim]")
+        else:
+            console.print(f"[red]{e}[/red]")
         return
 
     console.print("[bold cyan]Restore Database from Backup[/bold cyan]\n")
@@ -36,17 +75,15 @@ def run(console: Console) -> None:
     console.print(f"[yellow]This will replace: {db_path}[/yellow]")
 
     # Show backup file info
-    backup_size = os.path.getsize(backup_path)
-    backup_time = os.path.getmtime(backup_path)
-    backup_date = datetime.fromtimestamp(backup_time).strftime("%Y-%m-%d %H:%M:%S")
-
-    console.print(f"[dim]Backup size: {backup_size:,} bytes[/dim]")
-    console.print(f"[dim]Backup date: {backup_date}[/dim]")
+    chosen = next((backup for backup in list_backups() if backup.path == backup_path), None)
+    console.print(f"[dim]Backup size: {backup_path.stat().st_size:,} bytes[/dim]")
+    if chosen is not None:
+        console.print(f"[dim]Backup date: {chosen.created:%Y-%m-%d %H:%M:%S} (before {chosen.reason})[/dim]")
     console.print("")
 
     if db_path.exists():
         console.print("[bold red]⚠️  WARNING: This will overwrite your current database![/bold red]")
-        console.print("[yellow]Consider backing up your current database first.[/yellow]")
+        console.print("[dim]It is backed up first; undo with: ccg restore usage --from 1[/dim]")
         console.print("")
 
     console.print("[cyan]Continue with restore? (yes/no):[/cyan] ", end="")
@@ -61,14 +98,17 @@ def run(console: Console) -> None:
         return
 
     try:
-        # Create a backup of current DB if it exists
-        if db_path.exists():
-            current_backup = db_path.parent / f"{db_path.name}.before_restore"
-            shutil.copy2(db_path, current_backup)
+        # Back up the current DB; rotation waits until the chosen backup
+        # has been copied, since it may be the oldest one kept
+        current_backup = create_backup("pre-restore", db_path, rotate=False)
+        if current_backup is not None:
             console.print(f"[dim]Current database backed up to: {current_backup}[/dim]")
 
-        # Restore from backup
+        # Restore from backup; the current database's write-ahead log must
+        # go with it, or it would be replayed into the restored file
+        api.remove_database_files(db_path)
         shutil.copy2(backup_path, db_path)
+        rotate_backups(db_path)
         console.print("[green]✓ Database restored from backup[/green]")
         console.print(f"[dim]Restored: {db_path}[/dim]")
 
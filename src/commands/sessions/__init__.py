@@ -0,0 +1,28 @@
+"""
+Session commands for Claude Goblin.
+
+`ccg sessions` on its own lists the top sessions by estimated cost;
+`ccg sessions tag` labels sessions and `ccg sessions tags` reports spend
+per label.
+"""
+#region Imports
+import typer
+
+from src.commands.sessions import listing, tagging
+
+#endregion
+
+
+#region App Setup
+app = typer.Typer(
+    name="sessions",
+    help="List and inspect Claude Code sessions",
+)
+#endregion
+
+
+#region Command Registration
+app.callback(invoke_without_command=True)(listing.sessions_command)
+app.command(name="tag")(tagging.tag_command)
+app.command(name="tags")(tagging.tags_command)
+#endregion
@@ -0,0 +1,155 @@
+"""
+Session listing for Claude Goblin.
+
+Ranks sessions by estimated API cost (or tokens) so expensive conversations
+stand out, not just token-heavy ones.
+"""
+#region Imports
+from datetime import datetime
+
+import typer
+from rich.console import Console
+from rich.table import Table
+
+from src.aggregation.session_stats import SessionSummary, summarize_sessions
+from src.commands.update_usage import ingest_token_usage
+from src.config.settings import get_claude_jsonl_files
+from src.data.jsonl_parser import parse_all_jsonl_files
+from src.storage import api
+from src.utils.folders import short_folder
+
+#endregion
+
+
+#region Helper Functions
+
+
+def _parse_date(value: str | None, flag: str) -> str | None:
+    if value is None:
+        return None
+    try:
+        return datetime.strptime(value, "%Y-%m-%d").date().isoformat()
+    except ValueError:
+        raise typer.BadParameter(f"{flag} expects YYYY-MM-DD, got '{value}'")
+
+
+def _format_duration(summary: SessionSummary) -> str:
+    minutes = int((summary.end - summary.start).total_seconds() // 60)
+    return f"{minutes // 60}h {minutes % 60:02d}m" if minutes >= 60 else f"{minutes}m"
+
+
+def load_session_summaries(
+    console: Console,
+    start_date: str | None = None,
+    end_date: str | None = None,
+) -> list[SessionSummary]:
+    """
+    Session summaries from usage_records, or live JSONL in aggregate mode.
+
+    Aggregate-mode databases keep daily totals only, so sessions there come
+    from the transcripts Claude Code still has on disk.
+    """
+    pricing_rows = api.get_model_pricing()
+    if api.get_update_coverage()["total_records"] > 0:
+        return summarize_sessions(
+            api.iter_historical_records(start_date=start_date, end_date=end_date),
+            pricing_rows,
+        )
+
+    console.print("[dim]Aggregate storage mode: listing sessions still on disk[/dim]")
+    records = [
+        r for r in parse_all_jsonl_files(get_claude_jsonl_files())
+        if (start_date is None or r.date_key >= start_date)
+        and (end_date is None or r.date_key <= end_date)
+    ]
+    return summarize_sessions(records, pricing_rows)
+
+
+#endregion
+
+
+#region Command
+
+
+def sessions_command(
+    ctx: typer.Context,
+    limit: int = typer.Option(20, "--limit", "-n", help="Number of sessions to show"),
+    sort: str = typer.Option("cost", "--sort", help="Rank by 'cost' or 'tokens'"),
+    from_date: str = typer.Option(None, "--from", help="Only sessions on or after YYYY-MM-DD"),
+    to_date: str = typer.Option(None, "--to", help="Only sessions on or before YYYY-MM-DD"),
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+) -> None:
+    """
+    List top sessions with tokens and estimated cost.
+
+    Examples:
+        ccg sessions                       Top 20 sessions by cost
+        ccg sessions --sort tokens -n 50   Top 50 by tokens
+        ccg sessions --from 2026-01-01     Sessions since January
+    """
+    if ctx.invoked_subcommand is not None:
+        return
+
+    console = Console()
+    if sort not in ("cost", "tokens"):
+        raise typer.BadParameter("--sort must be 'cost' or 'tokens'")
+    start_date = _parse_date(from_date, "--from")
+    end_date = _parse_date(to_date, "--to")
+
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    with console.status("[bold #ff8800]Pricing sessions...", spinner="dots", spinner_style="#ff8800"):
+        summaries = load_session_summaries(console, start_date, end_date)
+
+    if not summaries:
+        console.print("[yellow]No sessions found. Run ccg usage to start tracking.[/yellow]")
+        return
+
+    if sort == "tokens":
+        summaries.sort(key=lambda s: s.total_tokens, reverse=True)
+    session_tags = api.get_session_tags()
+    shown = summaries[:limit]
+    shown_ids = [summary.session_id for summary in shown]
+    # Claude Code's own summary line names a session best; the first-prompt
+    # title (store_content only) is the fallback
+    titles = {
+        session.session_id: session.title
+        for session in api.get_sessions(shown_ids)
+        if session.title
+    }
+    titles.update(api.get_session_summaries(shown_ids))
+
+    table = Table(title=f"Top {min(limit, len(summaries))} Sessions by {sort.title()}", title_justify="left")
+    table.add_column("Session", style="dim", no_wrap=True)
+    if titles:
+        table.add_column("Title", overflow="ellipsis", no_wrap=True, max_width=40)
+    table.add_column("Started", no_wrap=True)
+    table.add_column("Duration", justify="right")
+    table.add_column("Project", overflow="crop")
+    table.add_column("Prompts", justify="right")
+    table.add_column("Tokens", justify="right", style="#ff8800")
+    table.add_column("Cost", justify="right", style="green")
+    if session_tags:
+        table.add_column("Tags", style="cyan", overflow="fold")
+
+    for summary in shown:
+        table.add_row(
+            summary.session_id[:8],
+            *([titles.get(summary.session_id, "")] if titles else []),
+            summary.start.astimezone().strftime("%Y-%m-%d %H:%M"),
+            _format_duration(summary),
+            short_folder(summary.folder),
+            f"{summary.prompts:,}",
+            f"{summary.total_tokens:,}",
+            f"${summary.cost:,.2f}",
+            *([", ".join(session_tags.get(summary.session_id, []))] if session_tags else []),
+        )
+
+    console.print(table)
+    total_cost = sum(s.cost for s in summaries)
+    console.print(f"[dim]{len(summaries):,} sessions, ${total_cost:,.2f} estimated API cost in total[/dim]")
+
+
+#endregion
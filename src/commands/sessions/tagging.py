@@ -0,0 +1,137 @@
+"""
+Session tags for Claude Goblin.
+
+Free-form labels attached to sessions after the fact, so spend can be
+split by kind of work ("debugging", "greenfield") rather than by project.
+"""
+#region Imports
+import typer
+from rich.console import Console
+from rich.table import Table
+
+from src.aggregation.session_stats import summarize_by_tag
+from src.commands.sessions.listing import _parse_date, load_session_summaries
+from src.commands.update_usage import ingest_token_usage
+from src.config.user_config import is_read_only
+from src.storage import api
+
+#endregion
+
+
+#region Helper Functions
+
+
+def _resolve_session(console: Console, query: str) -> str:
+    """
+    Full session id for an id, unique prefix, or label (as shown by `ccg sessions`).
+
+    Id prefixes win; otherwise the query is matched, case-insensitively,
+    against the labels of sessions in the sessions table and their summaries.
+
+    Raises:
+        typer.Exit: If no session or more than one session matches
+    """
+    matches = {s.session_id for s in load_session_summaries(console) if s.session_id.startswith(query)}
+    if not matches:
+        needle = query.casefold()
+        matches = {s.session_id for s in api.get_sessions() if needle in s.label.casefold()}
+        matches |= {
+            session_id for session_id, summary in api.get_session_summaries().items()
+            if needle in summary.casefold()
+        }
+    if len(matches) == 1:
+        return matches.pop()
+    if not matches:
+        console.print(f"[red]No session matches '{query}'[/red]")
+    else:
+        console.print(f"[red]'{query}' matches {len(matches)} sessions; be more specific[/red]")
+    raise typer.Exit(1)
+
+
+#endregion
+
+
+#region Commands
+
+
+def tag_command(
+    session: str = typer.Argument(
+        ..., help="Session id, unique prefix, or part of its title (as shown by ccg sessions)"
+    ),
+    tags: list[str] = typer.Argument(None, help="Tags to attach (or detach with --remove)"),
+    remove: bool = typer.Option(False, "--remove", "-r", help="Detach the given tags (all tags if none given)"),
+) -> None:
+    """
+    Attach tags to a session, or detach them with --remove.
+
+    Examples:
+        ccg sessions tag 3f2a9c1d refactor          Tag a session
+        ccg sessions tag 3f2a9c1d debugging infra   Attach two tags
+        ccg sessions tag 3f2a9c1d --remove          Clear its tags
+        ccg sessions tag "flaky login test" debugging   Match by title
+    """
+    console = Console()
+    tags = [tag.strip() for tag in tags or [] if tag.strip()]
+    if not tags and not remove:
+        raise typer.BadParameter("Give at least one tag (or --remove to clear them)")
+    if is_read_only():
+        console.print("[red]Read-only mode: not changing session tags[/red]")
+        raise typer.Exit(1)
+
+    session_id = _resolve_session(console, session)
+    if remove:
+        removed = api.remove_session_tags(session_id, tags or None)
+        console.print(f"[green]Removed {removed} tag{'s' if removed != 1 else ''} from {session_id[:8]}[/green]")
+        return
+
+    api.add_session_tags(session_id, tags)
+    current = api.get_session_tags().get(session_id, [])
+    console.print(f"[green]{session_id[:8]} tagged: {', '.join(current)}[/green]")
+
+
+def tags_command(
+    from_date: str = typer.Option(None, "--from", help="Only sessions on or after YYYY-MM-DD"),
+    to_date: str = typer.Option(None, "--to", help="Only sessions on or before YYYY-MM-DD"),
+    fast: bool = typer.Option(False, "--fast", help="Skip updates, read from database only (faster)"),
+) -> None:
+    """
+    Report sessions, tokens, and estimated cost per tag.
+
+    Examples:
+        ccg sessions tags                  Spend by tag, all time
+        ccg sessions tags --from 2026-01-01
+    """
+    console = Console()
+    start_date = _parse_date(from_date, "--from")
+    end_date = _parse_date(to_date, "--to")
+
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    with console.status("[bold #ff8800]Pricing sessions...", spinner="dots", spinner_style="#ff8800"):
+        summaries = load_session_summaries(console, start_date, end_date)
+        session_tags = api.get_session_tags()
+
+    if not session_tags:
+        console.print("[yellow]No tagged sessions. Tag one with: ccg sessions tag <id> <tag>[/yellow]")
+        return
+
+    table = Table(title="Spend by Tag", title_justify="left")
+    table.add_column("Tag")
+    table.add_column("Sessions", justify="right")
+    table.add_column("Tokens", justify="right", style="#ff8800")
+    table.add_column("Cost", justify="right", style="green")
+    for total in summarize_by_tag(summaries, session_tags):
+        table.add_row(
+            total.tag or "[dim](untagged)[/dim]",
+            f"{total.sessions:,}",
+            f"{total.total_tokens:,}",
+            f"${total.cost:,.2f}",
+        )
+
+    console.print(table)
+    console.print("[dim]Sessions with several tags count under each[/dim]")
+
+
+#endregion
@@ -7,6 +7,7 @@ Sets up Claude Code hooks for automation.
 import typer
 from rich.console import Console
 
+from src.hooks import audio
 from src.hooks.manager import setup_hooks as _setup_hooks
 
 
@@ -20,6 +21,37 @@ def setup_hooks_command(
         "--user",
         help="Install hooks at user level (~/.claude/) instead of project level (.claude/)"
     ),
+    project_only: bool = typer.Option(
+        False,
+        "--project-only",
+        help="usage hook: only ingest the current project's sessions, tagged with its root"
+    ),
+    output: str | None = typer.Option(
+        None,
+        "--output",
+        "-o",
+        help="png hook: fixed output path (default: follow export settings)"
+    ),
+    svg: bool = typer.Option(
+        False,
+        "--svg",
+        help="png hook: always export SVG (default: follow export_format)"
+    ),
+    theme: str | None = typer.Option(
+        None,
+        "--theme",
+        help="png hook: color gradient, claude, viridis, or cividis (default: claude)"
+    ),
+    metric: str | None = typer.Option(
+        None,
+        "--metric",
+        help="png hook: what a day's color measures, tokens or prompts (default: tokens)"
+    ),
+    list_sounds: bool = typer.Option(
+        False,
+        "--list-sounds",
+        help="audio hook: list and preview the system sounds instead of installing"
+    ),
 ) -> None:
     """
     Setup Claude Code hooks for automation.
@@ -41,11 +73,29 @@ def setup_hooks_command(
     Examples:
         ccg setup hooks usage              Enable usage tracking (project-level)
         ccg setup hooks usage --user       Enable usage tracking (user-level)
+        ccg setup hooks usage --project-only
+                                           Track only this project (monorepos)
         ccg setup hooks audio              Enable audio notifications
+        ccg setup hooks audio --list-sounds
+                                           List and preview available sounds
         ccg setup hooks audio-tts          Enable TTS (choose which hooks)
         ccg setup hooks png                Enable automatic PNG exports
+        ccg setup hooks png -o ~/usage.svg --svg
+                                           Pin the hook's output and format
+        ccg setup hooks png --theme viridis --metric prompts
+                                           Export with a theme and metric
         ccg setup hooks uv-standard        Enforce uv for Python packages
         ccg setup hooks bundler-standard   Enforce Bun for JS packages
     """
     console = Console()
-    _setup_hooks(console, hook_type, user=user)
+    if list_sounds:
+        if hook_type not in (None, "audio"):
+            console.print("[red]--list-sounds only applies to the audio hook[/red]")
+            raise typer.Exit(1)
+        audio.list_sounds(console)
+        return
+    _setup_hooks(
+        console, hook_type, user=user,
+        usage_project_only=project_only, png_output=output, png_svg=svg,
+        png_theme=theme, png_metric=metric,
+    )
@@ -3,8 +3,29 @@ import sys
 from datetime import datetime
 
 from rich.console import Console
+from rich.table import Table
 
+from src.aggregation.daily_stats import (
+    DailyStats,
+    aggregate_daily_snapshots,
+    average_by_weekday,
+    summarize_by_month,
+)
+from src.aggregation.event_stats import (
+    RETRY_STORM_MIN_ERRORS,
+    summarize_compactions,
+    summarize_events,
+)
+from src.aggregation.mcp_stats import summarize_mcp_calls
+from src.aggregation.session_stats import SESSION_IDLE_GAP, concurrency_by_day, split_automation
 from src.commands.update_usage import ingest_token_usage
+from src.config.user_config import (
+    get_automation_folders,
+    get_pricing_multipliers,
+    get_pricing_overrides,
+    get_pricing_tier,
+    get_storage_mode,
+)
 from src.storage import api
 from src.storage.api import (
     get_database_stats,
@@ -17,14 +38,28 @@ from src.storage.api import (
 #region Functions
 
 
-def run(console: Console, fast: bool = False, force: bool = False) -> None:
+def run(console: Console, fast: bool = False, force: bool = False, mcp: bool = False) -> None:
     """
     Show statistics about the historical database.
 
     Displays comprehensive statistics including:
     - Summary: total tokens, prompts, responses, sessions, days tracked
     - Cost analysis: estimated API costs vs Max Plan costs
+    - Reasoning: thinking tokens and their share of output and cost
+    - Attachments: images and files per day, and input tokens on days
+      with attachments vs without
+    - Errors & interruptions: API errors, rate limits, retry storms, and
+      tokens used by failed turns
+    - Context compactions: messages until compaction, and projects that
+      exhaust their context chronically
+    - Concurrent sessions: peak and average sessions active at once per
+      day (full storage mode)
+    - Interactive vs automated: sessions and tokens from headless runs and
+      tagged automation folders, apart from interactive use
+    - MCP servers (with mcp=True): calls, sessions, and token traffic per server
     - Averages: tokens per session/response, cost per session/response
+    - By weekday: average tokens and cost per day of the week
+    - Month over month: tokens, cost, active days, sessions, % change
     - Text analysis: prompt length, politeness markers, phrase counts
     - Usage by model: token distribution across different models
 
@@ -32,6 +67,7 @@ def run(console: Console, fast: bool = False, force: bool = False) -> None:
         console: Rich console for output
         fast: Skip updates, read directly from database (default: False)
         force: Force re-parse all files, ignoring incremental cache (default: False)
+        mcp: Include the per-server MCP usage section (default: False)
     """
     # Check for flags in sys.argv for backward compatibility
     fast_mode = fast or "--fast" in sys.argv
@@ -107,6 +143,7 @@ def run(console: Console, fast: bool = False, force: bool = False) -> None:
 
         console.print("\n[bold]Cost Analysis[/bold]")
         console.print(f"  Est. Cost (if using API): ${db_stats['total_cost']:>10,.2f}")
+        _print_pricing_basis(console)
         console.print(f"  Plan Cost:           ${plan_cost:>14,.2f} ({num_months} month{'s' if num_months > 1 else ''} @ $200/mo)")
 
         if savings > 0:
@@ -116,6 +153,18 @@ def run(console: Console, fast: bool = False, force: bool = False) -> None:
             console.print(f"  Plan Costs More:     ${overpaid:>14,.2f}")
             console.print("  [dim]Light usage - API would be cheaper[/dim]")
 
+    _print_thinking(console, db_stats)
+    _print_subagents(console, db_stats)
+    _print_attachments(console, db_stats)
+    events = api.get_session_events()
+    _print_events(console, events)
+    _print_compactions(console, events)
+    if db_stats["total_records"] > 0:
+        _print_concurrency(console)
+    _print_automation(console)
+    if mcp:
+        _print_mcp(console)
+
     # Averages
     console.print("\n[bold]Averages[/bold]")
     console.print(f"  Tokens per Session:  {db_stats['avg_tokens_per_session']:>15,}")
@@ -124,6 +173,10 @@ def run(console: Console, fast: bool = False, force: bool = False) -> None:
         console.print(f"  Cost per Session:    ${db_stats['avg_cost_per_session']:>14,.2f}")
         console.print(f"  Cost per Response:   ${db_stats['avg_cost_per_response']:>14,.4f}")
 
+    daily_stats = aggregate_daily_snapshots(api.load_daily_snapshots()).daily_stats
+    _print_weekday_averages(console, daily_stats)
+    _print_monthly_summary(console, daily_stats, api.get_annotations())
+
     # Text Analysis (from current JSONL files)
     text_stats = get_text_analysis_stats()
 
@@ -148,6 +201,8 @@ def run(console: Console, fast: bool = False, force: bool = False) -> None:
             else:
                 console.print(f"  {model:30s} {tokens:>15,} ({percentage:5.1f}%)")
 
+    _print_unpriced_warning(console, db_stats.get("unpriced_models", []))
+
     # Database Info
     console.print(f"\n[dim]Database: {api.current_db_path()}[/dim]")
     if db_stats["total_records"] > 0:
@@ -156,6 +211,437 @@ def run(console: Console, fast: bool = False, force: bool = False) -> None:
         console.print("[dim]Storage mode: aggregate (daily totals only)[/dim]")
 
 
+def _print_pricing_basis(console: Console) -> None:
+    """Note non-standard rates (pricing_tier, pricing_multipliers, pricing_overrides) under the cost estimate."""
+    tier = get_pricing_tier()
+    if tier != "standard":
+        console.print(f"  [dim]Priced at {tier} rates (pricing_tier)[/dim]")
+    if get_pricing_multipliers():
+        console.print("  [dim]Negotiated per-model rates applied (pricing_multipliers)[/dim]")
+    overrides = get_pricing_overrides()
+    if overrides:
+        console.print(f"  [dim]Custom rates for {', '.join(sorted(overrides))} (ccg pricing set)[/dim]")
+
+
+def _print_thinking(console: Console, db_stats: dict) -> None:
+    """
+    Print the "Reasoning" section: how much output went to extended thinking.
+
+    Claude transcripts don't report thinking tokens, so those are estimated
+    from the thinking text; Codex and Hermes sources report them directly.
+    """
+    thinking_tokens = db_stats.get("thinking_tokens", 0)
+    if not thinking_tokens:
+        return
+
+    output_tokens = db_stats.get("output_tokens", 0)
+    output_share = thinking_tokens / output_tokens * 100 if output_tokens else 0
+    console.print("\n[bold]Reasoning[/bold]")
+    console.print(f"  Thinking Tokens:     {thinking_tokens:>15,} ({output_share:.0f}% of output)")
+    if db_stats["total_cost"] > 0:
+        cost_share = db_stats["thinking_cost"] / db_stats["total_cost"] * 100
+        console.print(
+            f"  Est. Thinking Cost:  ${db_stats['thinking_cost']:>14,.2f} ({cost_share:.0f}% of est. cost)"
+        )
+    console.print("  [dim]Claude thinking is estimated from logged thinking text[/dim]")
+
+
+def _print_subagents(console: Console, db_stats: dict) -> None:
+    """
+    Print the "Subagents" section: tokens and cost of delegated work.
+
+    Subagent work is part of the totals above; this breaks it out.
+    """
+    subagent_tokens = db_stats.get("subagent_tokens", 0)
+    if not subagent_tokens:
+        return
+
+    token_share = subagent_tokens / db_stats["total_tokens"] * 100 if db_stats["total_tokens"] else 0
+    console.print("\n[bold]Subagents[/bold]")
+    console.print(f"  Subagent Tokens:     {subagent_tokens:>15,} ({token_share:.0f}% of total)")
+    if db_stats["total_cost"] > 0:
+        cost_share = db_stats["subagent_cost"] / db_stats["total_cost"] * 100
+        console.print(
+            f"  Est. Subagent Cost:  ${db_stats['subagent_cost']:>14,.2f} ({cost_share:.0f}% of est. cost)"
+        )
+    console.print(f"  Sessions Delegating: {db_stats['subagent_sessions']:>15,}")
+
+
+def _print_attachments(console: Console, db_stats: dict) -> None:
+    """
+    Print the "Attachments" section: pasted images and files per day, with
+    input tokens on days that had attachments vs days that didn't.
+    """
+    total = db_stats.get("total_attachments", 0)
+    if not total:
+        return
+
+    by_day = db_stats["attachments_by_day"]
+    with_attachments = [tokens for _, attachments, tokens in by_day if attachments]
+    without = [tokens for _, attachments, tokens in by_day if not attachments]
+    console.print("\n[bold]Attachments[/bold] [dim](images and files in prompts)[/dim]")
+    console.print(f"  Attachments:         {total:>15,}")
+    console.print(f"  Per Day:             {total / len(by_day):>15,.1f}")
+    console.print(f"  Days With Any:       {db_stats['attachment_days']:>15,}")
+    console.print(
+        f"  Input Tokens/Day:    {sum(with_attachments) / len(with_attachments):>15,.0f}"
+        " [dim](days with attachments, incl. cache)[/dim]"
+    )
+    if without:
+        console.print(
+            f"                       {sum(without) / len(without):>15,.0f} [dim](days without)[/dim]"
+        )
+    # Busiest attachment days, to line up against input-token spikes
+    busiest = sorted((day for day in by_day if day[1]), key=lambda day: (-day[1], day[0]))[:3]
+    for date, attachments, tokens in busiest:
+        console.print(f"  [dim]{date}: {attachments:,} attachments, {tokens:,} input tokens[/dim]")
+
+
+def _print_events(console: Console, events: list[dict]) -> None:
+    """
+    Print the "Errors & Interruptions" section from logged session events.
+    """
+    summary = summarize_events(events)
+    if not summary.sessions_affected:
+        return
+
+    console.print("\n[bold]Errors & Interruptions[/bold]")
+    console.print(f"  API Errors:          {summary.api_errors:>15,}")
+    console.print(f"  Rate Limit Hits:     {summary.rate_limits:>15,}")
+    console.print(f"  Interrupted Turns:   {summary.interruptions:>15,}")
+    console.print(
+        f"  Retry Storms:        {summary.retry_storms:>15,}"
+        f" [dim]({RETRY_STORM_MIN_ERRORS}+ errors in a row)[/dim]"
+    )
+    console.print(f"  Failed Turns:        {summary.failed_turns:>15,}")
+    console.print(f"  Tokens Burned:       {summary.burned_tokens:>15,} [dim](used by failed turns)[/dim]")
+    for detail, count in summary.top_errors:
+        console.print(f"  [dim]{count:>5,}× {detail}[/dim]")
+
+
+def _print_mcp(console: Console) -> None:
+    """
+    Print the "MCP Servers" section: calls and token traffic per server.
+    """
+    servers = summarize_mcp_calls(api.get_mcp_calls())
+    console.print("\n[bold]MCP Servers[/bold]")
+    if not servers:
+        console.print("  [dim]No MCP tool calls recorded (run 'ccg stats --force' to scan older transcripts)[/dim]")
+        return
+
+    table = Table(show_header=True, box=None, padding=(0, 2))
+    table.add_column("Server", style="cyan")
+    table.add_column("Calls", justify="right")
+    table.add_column("Sessions", justify="right")
+    table.add_column("Call Tokens", justify="right")
+    table.add_column("Result Tokens", justify="right")
+    table.add_column("Top Tool", style="dim")
+    for server in servers:
+        tool, count = server.top_tool
+        table.add_row(
+            server.server,
+            f"{server.calls:,}",
+            f"{server.sessions:,}",
+            f"{server.call_tokens:,}",
+            f"~{server.result_tokens:,}",
+            f"{tool} ({count:,})",
+        )
+    console.print(table)
+    console.print("[dim]  Call tokens: share of the calling responses' output; result tokens: estimated from result text[/dim]")
+
+
+def _print_compactions(console: Console, events: list[dict]) -> None:
+    """
+    Print the "Context Compactions" section from logged session events.
+    """
+    summary = summarize_compactions(events)
+    if not summary.compactions:
+        return
+
+    console.print("\n[bold]Context Compactions[/bold]")
+    console.print(
+        f"  Compactions:         {summary.compactions:>15,}"
+        f" [dim]({summary.auto:,} automatic, {summary.compactions - summary.auto:,} manual)[/dim]"
+    )
+    console.print(f"  Sessions Compacted:  {summary.sessions:>15,}")
+    if summary.avg_messages is not None:
+        console.print(f"  Messages Until:      {summary.avg_messages:>15,.0f} [dim](average per compaction)[/dim]")
+    if summary.avg_context_tokens:
+        console.print(f"  Context When Full:   {summary.avg_context_tokens:>15,.0f} [dim](average tokens)[/dim]")
+    for project in summary.chronic_projects:
+        console.print(
+            f"  [yellow]⚠ {project.folder}: {project.per_session:.1f} compactions per session "
+            f"({project.compactions:,} in {project.sessions:,})[/yellow]"
+        )
+
+
+def _print_concurrency(console: Console) -> None:
+    """
+    Print the "Concurrent Sessions" section: Claude Code windows running at
+    the same time, from interleaved message timestamps.
+    """
+    days = concurrency_by_day(api.iter_historical_records())
+    overlapping = [day for day in days if day.max_concurrent >= 2]
+    if not overlapping:
+        return
+
+    peak = max(days, key=lambda day: (day.max_concurrent, day.overlap_minutes))
+    busy = sum(day.avg_concurrent for day in days) / len(days)
+    idle = int(SESSION_IDLE_GAP.total_seconds() // 60)
+    console.print(f"\n[bold]Concurrent Sessions[/bold] [dim](idle after {idle} min without messages)[/dim]")
+    console.print(f"  Days With Overlap:   {len(overlapping):>15,} [dim]of {len(days):,} active days[/dim]")
+    console.print(f"  Peak Concurrent:     {peak.max_concurrent:>15,} [dim]({peak.date})[/dim]")
+    console.print(f"  Avg While Active:    {busy:>15,.2f} [dim](sessions, mean over active days)[/dim]")
+    busiest = sorted(overlapping, key=lambda day: (-day.max_concurrent, -day.overlap_minutes, day.date))[:3]
+    for day in busiest:
+        console.print(
+            f"  [dim]{day.date}: up to {day.max_concurrent} at once, "
+            f"avg {day.avg_concurrent:.2f}, {day.overlap_minutes:,.0f} min overlapping[/dim]"
+        )
+
+
+def _print_automation(console: Console) -> None:
+    """
+    Print the "Interactive vs Automated" section, when any session counts
+    as automated (headless, or in a `ccg projects automate` folder).
+    """
+    split = split_automation(api.get_sessions(), get_automation_folders())
+    if not split.automated_sessions:
+        return
+
+    def sessions(count: int) -> str:
+        return f"{count:,} session{'s' if count != 1 else ''}"
+
+    total = (split.interactive_tokens + split.automated_tokens) or 1
+    console.print("\n[bold]Interactive vs Automated[/bold]")
+    console.print(
+        f"  Interactive:         {split.interactive_tokens:>15,} tokens"
+        f" [dim]({split.interactive_tokens / total:.0%}, {sessions(split.interactive_sessions)})[/dim]"
+    )
+    console.print(
+        f"  Automated:           {split.automated_tokens:>15,} tokens"
+        f" [dim]({split.automated_tokens / total:.0%}, {sessions(split.automated_sessions)}: "
+        f"{split.headless_sessions:,} headless, {split.tagged_sessions:,} in automation folders)[/dim]"
+    )
+
+
+def _print_weekday_averages(console: Console, daily_stats: dict[str, DailyStats]) -> None:
+    """
+    Print average tokens and cost per weekday over the tracked range.
+    """
+    averages = average_by_weekday(daily_stats)
+    if not averages or not any(a.avg_tokens for a in averages):
+        return
+
+    heaviest = max(averages, key=lambda a: a.avg_tokens)
+    max_tokens = heaviest.avg_tokens
+    console.print("\n[bold]By Weekday[/bold] [dim](average per calendar day)[/dim]")
+    for average in averages:
+        bar = "█" * round(average.avg_tokens / max_tokens * 20) if max_tokens else ""
+        line = (
+            f"  {average.weekday}  {average.avg_tokens:>15,.0f} tokens"
+            f"  ${average.avg_cost:>9,.2f}  [#ff8800]{bar:<20}[/#ff8800]"
+            f"  [dim]{average.active_days}/{average.days} active[/dim]"
+        )
+        if average is heaviest:
+            line += "  [bold]heaviest[/bold]"
+        console.print(line)
+
+
+def _print_monthly_summary(
+    console: Console,
+    daily_stats: dict[str, DailyStats],
+    annotations: dict[str, list[str]] | None = None,
+) -> None:
+    """
+    Print a month-over-month table of tokens, cost, active days and sessions.
+
+    Months with annotations (ccg annotate) get a Notes column listing them.
+    """
+    months = summarize_by_month(daily_stats)
+    if not months:
+        return
+    annotations = annotations or {}
+
+    table = Table(title="Month over Month", title_justify="left", title_style="bold", box=None)
+    table.add_column("Month")
+    table.add_column("Tokens", justify="right")
+    table.add_column("Δ", justify="right")
+    table.add_column("Cost", justify="right")
+    table.add_column("Δ", justify="right")
+    table.add_column("Active Days", justify="right")
+    table.add_column("Sessions", justify="right")
+    if annotations:
+        table.add_column("Notes", style="dim", overflow="fold")
+    for month in months:
+        row = [
+            month.month,
+            f"{month.total_tokens:,}",
+            _format_change(month.tokens_change),
+            f"${month.cost:,.2f}",
+            _format_change(month.cost_change),
+            str(month.active_days),
+            f"{month.sessions:,}",
+        ]
+        if annotations:
+            row.append(month_notes(annotations, month.month))
+        table.add_row(*row)
+    console.print()
+    console.print(table)
+
+
+def month_notes(annotations: dict[str, list[str]], month: str) -> str:
+    """
+    A month's annotations as "DD label" entries, e.g. "02 launched v2; 17 offsite".
+    """
+    return "; ".join(
+        f"{day[8:]} {label}"
+        for day in sorted(annotations)
+        if day.startswith(f"{month}-")
+        for label in annotations[day]
+    )
+
+
+def _format_change(change: float | None) -> str:
+    """
+    Format a percent change with color (green up, red down).
+    """
+    if change is None:
+        return "[dim]–[/dim]"
+    color = "green" if change >= 0 else "red"
+    return f"[{color}]{change:+.0f}%[/{color}]"
+
+
+def _print_unpriced_warning(console: Console, models: list[str]) -> None:
+    """
+    Warn about models with usage but no pricing match (excluded from cost).
+    """
+    if not models:
+        return
+    console.print(
+        f"\n[bold yellow]Warning: no pricing for {len(models)} model{'s' if len(models) != 1 else ''}"
+        " - costs above undercount their usage[/bold yellow]"
+    )
+    for model in models:
+        console.print(f"  [yellow]{model}[/yellow]")
+    console.print(f"[dim]Price one with: ccg pricing add {models[0]} --like claude-sonnet-4-5[/dim]")
+
+
+def run_by_branch(
+    console: Console,
+    fast: bool = False,
+    start_date: str | None = None,
+    end_date: str | None = None,
+) -> None:
+    """
+    Show tokens and estimated cost per project and git branch.
+
+    Attributes spend to feature branches; sessions outside a git repository
+    are listed as "(no branch)". Needs full storage mode, since aggregate
+    mode keeps no per-record branch.
+
+    Args:
+        console: Rich console for output
+        fast: Skip updates, read directly from database (default: False)
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+    """
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    branches = api.get_branch_stats(start_date, end_date)
+    if not branches:
+        if get_storage_mode() == "aggregate":
+            console.print("[yellow]Branch stats need full storage mode (aggregate mode keeps no branches)[/yellow]")
+            console.print("[dim]Switch with: ccg config set storage_mode full[/dim]")
+        else:
+            console.print("[yellow]No usage found for this range. Run ccg usage to start tracking.[/yellow]")
+        return
+
+    window = f" ({start_date or 'start'} to {end_date or 'today'})" if start_date or end_date else ""
+    table = Table(title=f"Usage by Branch{window}", title_justify="left")
+    table.add_column("Project", overflow="fold")
+    table.add_column("Branch", style="cyan", overflow="fold")
+    table.add_column("Sessions", justify="right")
+    table.add_column("Responses", justify="right")
+    table.add_column("Tokens", justify="right", style="#ff8800")
+    table.add_column("Cost", justify="right", style="green")
+    for branch in branches:
+        table.add_row(
+            branch["folder"] or "-",
+            branch["branch"] or "[dim](no branch)[/dim]",
+            f"{branch['sessions']:,}",
+            f"{branch['responses']:,}",
+            f"{branch['tokens']:,}",
+            f"${branch['cost']:,.2f}",
+        )
+    console.print(table)
+    total_cost = sum(branch["cost"] for branch in branches)
+    console.print(
+        f"[dim]{len(branches)} branch{'es' if len(branches) != 1 else ''}, "
+        f"${total_cost:,.2f} estimated at API rates[/dim]"
+    )
+
+
+def run_by_host(
+    console: Console,
+    fast: bool = False,
+    start_date: str | None = None,
+    end_date: str | None = None,
+) -> None:
+    """
+    Show tokens and estimated cost per machine.
+
+    Records carry the hostname of the machine that ingested them, so a
+    database merged with `ccg import db` still splits by host. Records
+    stored before hostnames were recorded are listed as "(unknown)".
+    Needs full storage mode.
+
+    Args:
+        console: Rich console for output
+        fast: Skip updates, read directly from database (default: False)
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+    """
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    hosts = api.get_host_stats(start_date, end_date)
+    if not hosts:
+        if get_storage_mode() == "aggregate":
+            console.print("[yellow]Host stats need full storage mode (aggregate mode keeps no records)[/yellow]")
+            console.print("[dim]Switch with: ccg config set storage_mode full[/dim]")
+        else:
+            console.print("[yellow]No usage found for this range. Run ccg usage to start tracking.[/yellow]")
+        return
+
+    window = f" ({start_date or 'start'} to {end_date or 'today'})" if start_date or end_date else ""
+    table = Table(title=f"Usage by Host{window}", title_justify="left")
+    table.add_column("Host", style="cyan", overflow="fold")
+    table.add_column("Active", style="dim")
+    table.add_column("Sessions", justify="right")
+    table.add_column("Responses", justify="right")
+    table.add_column("Tokens", justify="right", style="#ff8800")
+    table.add_column("Cost", justify="right", style="green")
+    for host in hosts:
+        table.add_row(
+            host["hostname"] or "[dim](unknown)[/dim]",
+            f"{host['first_date']} to {host['last_date']}",
+            f"{host['sessions']:,}",
+            f"{host['responses']:,}",
+            f"{host['tokens']:,}",
+            f"${host['cost']:,.2f}",
+        )
+    console.print(table)
+    total_cost = sum(host["cost"] for host in hosts)
+    console.print(
+        f"[dim]{len(hosts)} host{'s' if len(hosts) != 1 else ''}, "
+        f"${total_cost:,.2f} estimated at API rates[/dim]"
+    )
+
+
 def run_remote(console: Console) -> None:
     """
     Show statistics from the remote DuckDB server (cross-device aggregate).
@@ -197,6 +683,7 @@ def run_remote(console: Console) -> None:
 
             console.print("\n[bold]Cost Analysis[/bold]")
             console.print(f"  Est. Cost (if using API): ${db_stats['total_cost']:>10,.2f}")
+            _print_pricing_basis(console)
             console.print(f"  Plan Cost:           ${plan_cost:>14,.2f} ({num_months} month{'s' if num_months > 1 else ''} @ $200/mo)")
             if savings > 0:
                 console.print(f"  You Saved:           ${savings:>14,.2f} (vs API)")
@@ -220,6 +707,8 @@ def run_remote(console: Console) -> None:
                 else:
                     console.print(f"  {model:30s} {tokens:>15,} ({pct:5.1f}%)")
 
+        _print_unpriced_warning(console, db_stats.get("unpriced_models", []))
+
         console.print("\n[dim]Source: remote (cross-device aggregate)[/dim]")
 
     except ImportError:
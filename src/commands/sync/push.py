@@ -11,7 +11,7 @@ from typing import Any
 import typer
 from rich.console import Console
 
-from src.config.user_config import get_storage_mode, get_sync_providers
+from src.config.user_config import get_storage_mode, get_sync_providers, is_read_only
 from src.storage import get_db_path
 
 #endregion
@@ -51,6 +51,13 @@ def run_push(console: Console, force: bool = False, full: bool = False, strict:
         console.print("[yellow]Run: ccg sync setup --provider quack (or onelake)[/yellow]")
         raise typer.Exit(1)
 
+    # Pushes record their sync state in the local database
+    if is_read_only():
+        if not strict:
+            return
+        console.print("[red]Read-only mode: not pushing (a push records sync state locally)[/red]")
+        raise typer.Exit(1)
+
     storage_mode = get_storage_mode()
     if storage_mode != "full" and not force:
         if not strict:
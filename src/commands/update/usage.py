@@ -3,10 +3,14 @@ Update usage command.
 
 Updates historical database with latest data.
 """
+import sys
+from pathlib import Path
+
 import typer
 from rich.console import Console
 
 from src.commands import update_usage as _update_usage_module
+from src.config.user_config import get_redaction_mode, is_read_only
 
 
 def update_usage_command(
@@ -17,6 +21,49 @@ def update_usage_command(
              "billed-response identity (backs up the DB first; blocks quack pushes "
              "until the remote is purged)",
     ),
+    watch: bool = typer.Option(
+        False, "--watch",
+        help="Keep running and ingest transcripts as they are written "
+             "(instead of relying on the Stop hook)",
+    ),
+    hook: bool = typer.Option(
+        False, "--hook", hidden=True,
+        help="Mark this run as hook-triggered (health tracking for ccg doctor)",
+    ),
+    project: Path | None = typer.Option(
+        None, "--project",
+        help="Only ingest sessions started in this project directory, tagging "
+             "their records with it (used by the --project-only usage hook)",
+    ),
+    dry_run: bool = typer.Option(
+        False, "--dry-run",
+        help="Parse changed files and report how many new records, dates and "
+             "projects would be added, without writing to the database",
+    ),
+    strict: bool = typer.Option(
+        False, "--strict",
+        help="Report every transcript line that could not be parsed (file, line, "
+             "reason, counts by error type) instead of skipping it silently",
+    ),
+    report: Path | None = typer.Option(
+        None, "--report",
+        help="With --strict, also write the validation report to this JSON file",
+    ),
+    redact: bool = typer.Option(
+        False, "--redact",
+        help="Store no message text and hash folder paths and git branches "
+             "(or drop them with redact = strip in config) for this run",
+    ),
+    stdin: bool = typer.Option(
+        False, "--stdin",
+        help="Ingest transcript lines piped on stdin instead of reading the "
+             "transcript directories (e.g. from another machine over SSH)",
+    ),
+    hostname: str | None = typer.Option(
+        None, "--hostname",
+        help="With --stdin, the machine the piped transcripts came from "
+             "(default: this machine's hostname)",
+    ),
 ) -> None:
     """
     Update historical database with latest data.
@@ -32,12 +79,48 @@ def update_usage_command(
         ccg update usage             Update the usage database
         ccg update usage --push      Update, then push to the remote in one process
         ccg update usage --rebuild   Repair inflated history from surviving transcripts
+        ccg update usage --project .  Ingest only this project's sessions
+        ccg update usage --watch     Ingest continuously until Ctrl+C
+        ccg update usage --dry-run   Preview what an update would add
+        ccg update usage --strict --report lost.json
+                                     List lines lost to malformed or changed logs
+        ccg update usage --redact    Keep only numeric usage data for this run
+        ssh box cat '~/.claude/projects/*/*.jsonl' | ccg update usage --stdin --hostname box
+                                     Collect a remote machine's usage without file sync
     """
     console = Console()
+    redact_mode = (get_redaction_mode() or "hash") if redact else None
+    if report is not None and not strict:
+        console.print("[red]--report requires --strict[/red]")
+        raise typer.Exit(1)
+    if hostname is not None and not stdin:
+        console.print("[red]--hostname requires --stdin[/red]")
+        raise typer.Exit(1)
+    if stdin and (dry_run or rebuild or watch or project is not None):
+        console.print("[red]--stdin cannot be combined with --dry-run, --rebuild, --watch, or --project[/red]")
+        raise typer.Exit(1)
+    if stdin and sys.stdin.isatty():
+        console.print("[red]--stdin expects piped transcript lines (ssh box cat ... | ccg update usage --stdin)[/red]")
+        raise typer.Exit(1)
+    if dry_run:
+        _update_usage_module.preview_token_usage(
+            console, project=project, strict=strict, report_path=report, redact=redact_mode
+        )
+        return
+    if is_read_only():
+        console.print("[red]Read-only mode: not updating the database (--dry-run previews without writing)[/red]")
+        raise typer.Exit(1)
     if rebuild:
         _update_usage_module.rebuild_token_usage(console)
         return
-    _update_usage_module.run(console)
+    if watch:
+        _update_usage_module.watch(console, project=project)
+        return
+    _update_usage_module.run(
+        console, project=project, trigger="hook" if hook else "manual",
+        strict=strict, report_path=report, redact=redact_mode,
+        stdin=sys.stdin if stdin else None, hostname=hostname,
+    )
     if push:
         from src.commands.sync.push import run_push
         run_push(console, strict=False)
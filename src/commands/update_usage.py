@@ -1,37 +1,200 @@
 #region Imports
-from datetime import datetime
+import json
+import time
+from dataclasses import replace
+from datetime import datetime, timedelta
 from pathlib import Path
+from typing import TextIO
 
 from rich.console import Console
 
-from src.config.settings import get_claude_jsonl_files
+from src.aggregation.session_stats import build_sessions
+from src.commands.watch_actions import WatchActions
+from src.config.settings import (
+    collect_jsonl_files,
+    get_claude_data_dirs,
+    get_claude_jsonl_files,
+    get_project_jsonl_files,
+    transcript_project_dir,
+)
 from src.config.user_config import (
+    get_content_retention,
     get_device_id,
     get_extra_sources,
+    get_mmap_threshold,
+    get_redaction_mode,
     get_storage_mode,
+    is_read_only,
 )
 from src.data.codex_parser import parse_all_codex_files
 from src.data.hermes_parser import parse_all_hermes_files
-from src.data.jsonl_parser import parse_all_jsonl_files
-from src.models.usage_record import UsageRecord
+from src.data.jsonl_parser import (
+    ParseStats,
+    dedupe_records,
+    file_head_digest,
+    is_compressed,
+    parse_all_jsonl_files,
+    parse_jsonl_stream,
+    parse_jsonl_tail,
+    resume_offset,
+    transcript_key,
+)
+from src.data.redaction import redact_events, redact_quarantine, redact_records
+from src.data.transcript_watcher import TranscriptWatcher
+from src.errors import GoblinError, StaleDataError
+from src.models.usage_record import QuarantinedEntry, SessionEvent, TranscriptSummary, UsageRecord
 from src.storage import api, get_db_path
+from src.storage.backups import create_backup
+from src.storage.ingest_lock import ingest_lock
+from src.storage.pricing_remote import remote_pricing_is_stale, update_remote_pricing
+from src.storage.refresh_marker import read_refresh_payload, touch_refresh_marker
+from src.utils._system import atomic_write_text
 
 #endregion
 
 
+#region Constants
+# Seconds without transcript writes before a watch-mode ingest
+WATCH_QUIET_SECONDS = 2.0
+
+# Longest a watch-mode ingest is deferred while writes keep arriving
+WATCH_MAX_DELAY_SECONDS = 30.0
+
+# Lost lines listed by --strict before "... and N more"
+STRICT_REPORT_ISSUES = 10
+
+# Source name for piped transcripts (warnings, issues, aggregate ledger keys)
+STDIN_SOURCE = "<stdin>"
+
+# Age of the last full update at which --fast commands start warning
+STALE_WARNING = timedelta(days=1)
+#endregion
+
+
 #region Functions
 
 
-def _parse_source_files(file_paths: list[Path], source_format: str) -> list[UsageRecord]:
-    """Dispatch a configured source to its transcript parser."""
+def _parse_source_files(
+    file_paths: list[Path],
+    source_format: str,
+    quarantine: list[QuarantinedEntry] | None = None,
+    events: list[SessionEvent] | None = None,
+    stats: ParseStats | None = None,
+    summaries: list[TranscriptSummary] | None = None,
+) -> list[UsageRecord]:
+    """Dispatch a configured source to its transcript parser (line stats and summaries: Claude only)."""
     if source_format == "codex":
         return parse_all_codex_files(file_paths)
     if source_format == "hermes":
         return parse_all_hermes_files(file_paths)
-    return parse_all_jsonl_files(file_paths)
+    return parse_all_jsonl_files(file_paths, quarantine, events, stats, summaries, get_mmap_threshold())
+
+
+def _parse_appended(
+    file_paths: list[Path],
+    pre_stats: dict[str, tuple[int, int]],
+    quarantine: list[QuarantinedEntry],
+    events: list[SessionEvent] | None = None,
+    stats: ParseStats | None = None,
+    summaries: list[TranscriptSummary] | None = None,
+) -> tuple[list[UsageRecord], list[tuple[str, int, int, str]]]:
+    """
+    Parse only the lines appended to Claude transcripts since the last run.
+
+    Files that shrank, were rewritten, or have no saved offset are parsed
+    from the start, as are compressed archives (they are not appended to).
+    Unreadable files are skipped with a warning, as in parse_all_jsonl_files.
+
+    Returns:
+        Tuple of (deduplicated records, ingestion_state entries to save)
+    """
+    state = api.get_ingestion_state([str(f) for f in file_paths])
+    mmap_threshold = get_mmap_threshold()
+    records: list[UsageRecord] = []
+    entries: list[tuple[str, int, int, str]] = []
+    for f in file_paths:
+        if is_compressed(f):
+            records.extend(parse_all_jsonl_files([f], quarantine, events, stats, summaries))
+            continue
+        try:
+            file_records, offset = parse_jsonl_tail(
+                f, resume_offset(f, state.get(str(f))), quarantine, events, stats, summaries, mmap_threshold
+            )
+            digest = file_head_digest(f, offset)
+        except OSError as e:
+            print(f"Warning: Error parsing {f}: {e}")
+            continue
+        records.extend(file_records)
+        size = pre_stats.get(str(f), (0, offset))[1]
+        entries.append((str(f), size, offset, digest))
+    return dedupe_records(records), entries
 
 
-def ingest_token_usage(console: Console, force: bool = False, verbose: bool = True) -> int:
+def _is_within(folder: str, project: Path) -> bool:
+    """True if folder is the project directory or inside it (project already resolved)."""
+    try:
+        return Path(folder).resolve().is_relative_to(project)
+    except (OSError, ValueError):
+        return False
+
+
+def _scope_to_project(records: list[UsageRecord], project: Path) -> list[UsageRecord] | None:
+    """
+    Tag one transcript's records with the project root, if it belongs there.
+
+    A session belongs to the project when it started inside it; records
+    from the root or its subdirectories get project set to the root and
+    keep their own folder. Each distinct folder is resolved once.
+
+    Args:
+        records: One transcript's records
+        project: Resolved project root
+
+    Returns:
+        Tagged records, or None if the session ran in another project
+    """
+    if not records:
+        return []
+    within: dict[str, bool] = {}
+    for record in records:
+        if record.folder not in within:
+            within[record.folder] = _is_within(record.folder, project)
+    if not within[records[0].folder]:
+        return None
+    root = str(project)
+    return [replace(r, project=root) if within[r.folder] else r for r in records]
+
+
+def _collect_sources(project: Path | None) -> list[tuple[list[Path], dict | None]]:
+    """
+    Transcript files to ingest, grouped by source.
+
+    Each source is (jsonl files, device overrides); None overrides means
+    this device's identity from config. A project scope reads only that
+    project's transcripts and skips extra sources.
+    """
+    sources: list[tuple[list[Path], dict | None]] = []
+    jsonl_files = get_project_jsonl_files(project) if project is not None else get_claude_jsonl_files()
+    if jsonl_files:
+        sources.append((jsonl_files, None))
+    for extra in get_extra_sources() if project is None else []:
+        extra_dir = Path(extra["path"])
+        if extra_dir.is_dir():
+            extra_files = collect_jsonl_files(extra_dir)
+            if extra_files:
+                sources.append((extra_files, extra))
+    return sources
+
+
+def ingest_token_usage(
+    console: Console,
+    force: bool = False,
+    verbose: bool = True,
+    project: Path | None = None,
+    strict: bool = False,
+    report_path: Path | None = None,
+    redact: str | None = None,
+) -> int:
     """
     Parse stale JSONL files from all configured sources and save records.
 
@@ -40,27 +203,189 @@ def ingest_token_usage(console: Console, force: bool = False, verbose: bool = Tr
     only files whose mtime/size changed since the last run are parsed
     (tracked in file_metadata); force reparses everything.
 
+    Only one ingestion runs at a time. A run that queued behind another
+    returns immediately if an ingestion that started after it queued has
+    already finished, since that run saw everything this one would.
+
     Args:
         console: Rich console for output
         force: Reparse all files, ignoring the incremental cache
         verbose: Print per-source save counts and the no-op message
+        project: Only ingest sessions started in this project (or below),
+            tagging their records with it; extra sources are skipped
+        strict: Collect every Claude transcript line that was lost
+            (malformed, bad timestamp, unexpected shape) and print a
+            validation report instead of per-line warnings
+        report_path: With strict, also write the report here as JSON
+        redact: Redaction mode ("hash" or "strip") overriding the "redact"
+            config key; when redacting, no message text is stored and
+            folders and branches are hashed or dropped before saving
+
+    Does nothing in read-only mode (see is_read_only).
 
     Returns:
         Number of new records saved across all sources
     """
-    # Each source is (jsonl files, device overrides); None overrides means
-    # this device's identity from config.
-    sources: list[tuple[list[Path], dict | None]] = []
-    jsonl_files = get_claude_jsonl_files()
-    if jsonl_files:
-        sources.append((jsonl_files, None))
-    for extra in get_extra_sources():
-        extra_dir = Path(extra["path"])
-        if extra_dir.is_dir():
-            extra_files = list(extra_dir.rglob("*.jsonl"))
-            if extra_files:
-                sources.append((extra_files, extra))
+    if is_read_only():
+        if verbose:
+            console.print("[dim]Read-only mode: skipped ingestion[/dim]")
+        return 0
+    if project is not None:
+        project = project.expanduser().resolve()
+
+    queued_at = datetime.now().isoformat()
+    try:
+        with ingest_lock() as waited:
+            if waited and not force and _covered_since(queued_at, project):
+                if verbose:
+                    console.print("[dim]Another update just ingested this data[/dim]")
+                return 0
+            return _ingest_sources(console, force, verbose, project, strict, report_path, redact)
+    except TimeoutError as e:
+        console.print(f"[yellow]⚠ Skipped ingestion: {e}[/yellow]")
+        return 0
+
+
+def ingest_stdin(
+    console: Console,
+    stream: TextIO,
+    verbose: bool = True,
+    strict: bool = False,
+    report_path: Path | None = None,
+    redact: str | None = None,
+    hostname: str | None = None,
+) -> int:
+    """
+    Parse transcript lines piped on a stream and save their records.
+
+    For remote collection without file sync, e.g.
+    `ssh box cat '~/.claude/projects/*/*.jsonl' | ccg update usage --stdin`.
+    Full mode upserts by message identity, so piping the same lines again
+    adds nothing. Aggregate mode is refused: its delta ledger is keyed by
+    transcript file, and piped lines have none to key on.
+
+    Args:
+        console: Rich console for output
+        stream: Text stream of Claude Code transcript lines
+        verbose: Print the ingest summary
+        strict: Print a validation report of lost lines (see ingest_token_usage)
+        report_path: With strict, also write the report here as JSON
+        redact: Redaction mode overriding the "redact" config key
+        hostname: Machine the transcripts came from (default: this machine's
+            hostname setting)
+
+    Does nothing in read-only mode (see is_read_only).
+
+    Returns:
+        Number of new records saved
+
+    Raises:
+        GoblinError: The database is in aggregate storage mode
+    """
+    if is_read_only():
+        if verbose:
+            console.print("[dim]Read-only mode: skipped ingestion[/dim]")
+        return 0
+    if get_storage_mode() == "aggregate":
+        raise GoblinError(
+            "Piped transcripts need full storage mode",
+            "Aggregate mode tracks totals per transcript file, so piping a session again would "
+            "double or replace it. Switch with: ccg config set storage_mode full",
+        )
+
+    started_at = datetime.now().isoformat()
+    clock = time.monotonic()
+    quarantine: list[QuarantinedEntry] = []
+    stats = ParseStats(issues=[] if strict else None)
+    redact = redact or get_redaction_mode()
+    retention = None if redact else get_content_retention()
+    host_kwargs = {"hostname": hostname} if hostname else {}
+
+    records = dedupe_records(list(parse_jsonl_stream(stream, STDIN_SOURCE, quarantine, stats)))
+    records = redact_records(records, redact)
+    try:
+        with ingest_lock():
+            _refresh_pricing_if_stale(console, verbose)
+            saved = api.save_snapshot(records, storage_mode="full", **host_kwargs) if records else 0
+            mcp_saved = api.save_mcp_calls(records)
+            api.save_tool_usage(records)
+            api.save_sessions(build_sessions(records, titles=retention is not None))
+            if retention and records:
+                api.save_message_content(records, *retention)
+            held = api.save_quarantined_entries(redact_quarantine(quarantine, redact))
+            api.save_unknown_fields(stats.unknown_fields)
+    except TimeoutError as e:
+        console.print(f"[yellow]⚠ Skipped ingestion: {e}[/yellow]")
+        return 0
+
+    if held:
+        console.print(
+            f"[yellow]⚠ Quarantined {held} entries with bad timestamps "
+            f"(review: ccg doctor --quarantine)[/yellow]"
+        )
+    if records:
+        touch_refresh_marker(saved, started_at=started_at, db=str(get_db_path()))
+    if verbose:
+        console.print(f"[green]Read {stats.lines:,} piped lines in {time.monotonic() - clock:.1f}s[/green]")
+        if stats.skipped:
+            console.print(f"  Lines: {stats.skipped:,} skipped")
+        console.print(f"  New: {saved:,} token records" + (f", {mcp_saved:,} MCP calls" if mcp_saved else ""))
+    if strict:
+        _print_parse_report(console, stats, report_path)
+    return saved
+
+
+def _refresh_pricing_if_stale(console: Console, verbose: bool) -> None:
+    """
+    Fetch remote pricing when "pricing_auto_update" is on and the cache has aged out.
+
+    Runs under the ingestion lock, so concurrent hook runs fetch once. A
+    failed fetch keeps the current rates.
+    """
+    if not remote_pricing_is_stale():
+        return
+    try:
+        models = update_remote_pricing()
+    except RuntimeError as e:
+        if verbose:
+            console.print(f"[yellow]⚠ Pricing refresh failed, keeping current rates: {e}[/yellow]")
+        return
+    api.sync_model_pricing()
+    if verbose:
+        console.print(f"[dim]Refreshed pricing for {len(models)} models[/dim]")
+
+
+def _covered_since(queued_at: str, project: Path | None) -> bool:
+    """
+    True if an ingestion covering project started after queued_at and finished.
+
+    An unscoped run covers every project; a scoped run covers only its own.
+    Runs against another profile's database cover nothing.
+    """
+    payload = read_refresh_payload() or {}
+    if payload.get("started_at", "") < queued_at:
+        return False
+    if payload.get("db", str(get_db_path())) != str(get_db_path()):
+        return False
+    covered = payload.get("project")
+    return covered is None or (project is not None and covered == str(project))
 
+
+def _ingest_sources(
+    console: Console,
+    force: bool,
+    verbose: bool,
+    project: Path | None,
+    strict: bool = False,
+    report_path: Path | None = None,
+    redact: str | None = None,
+) -> int:
+    """Body of ingest_token_usage, run while holding the ingestion lock."""
+    started_at = datetime.now().isoformat()
+    clock = time.monotonic()
+    _refresh_pricing_if_stale(console, verbose)
+
+    sources = _collect_sources(project)
     all_files = [f for files, _ in sources for f in files]
     if not all_files:
         return 0
@@ -72,10 +397,27 @@ def ingest_token_usage(console: Console, force: bool = False, verbose: bool = Tr
         stale_files, deleted_files = all_files, []
     else:
         stale_files, deleted_files = api.get_stale_files(all_files)
+        if project is not None:
+            # Files outside the scanned project dirs are not deleted, just unscanned
+            scanned_dirs = {transcript_project_dir(f) for f in all_files}
+            deleted_files = [
+                d for d in deleted_files
+                if transcript_project_dir(Path(d)) in scanned_dirs - {None}
+            ]
     stale_set = {str(f) for f in stale_files}
 
     storage_mode = get_storage_mode()
     total_saved = 0
+    mcp_saved = 0
+    failed_sources = 0
+    quarantine: list[QuarantinedEntry] = []
+    events: list[SessionEvent] = []
+    summaries: list[TranscriptSummary] = []
+    stats = ParseStats(issues=[] if strict else None)
+    redact = redact or get_redaction_mode()
+    # Opt-in message text storage: (per-record cap, total cap) or None.
+    # Redaction wins over store_content.
+    retention = None if redact else get_content_retention()
     for files, overrides in sources:
         source_stale = [f for f in files if str(f) in stale_set]
         if not source_stale:
@@ -95,44 +437,300 @@ def ingest_token_usage(console: Console, force: bool = False, verbose: bool = Tr
                     pass
             device_kwargs = {}
             if overrides:
+                # Records from an extra source belong to that device's machine
                 device_kwargs = {
                     "device_id": overrides["device_id"],
                     "device_name": overrides["device_name"],
                     "device_type": overrides["device_type"],
+                    "hostname": overrides["device_name"],
                 }
             source_format = overrides.get("format", "claude") if overrides else "claude"
+            scoped: dict[Path, list[UsageRecord]] | None = None
+            if project is not None:
+                # Sessions from sibling projects (encoded-prefix over-match)
+                # stay stale for an unscoped run to pick up
+                scoped = {}
+                for f in source_stale:
+                    file_events: list[SessionEvent] = []
+                    file_summaries: list[TranscriptSummary] = []
+                    records = _scope_to_project(
+                        _parse_source_files([f], source_format, quarantine, file_events, stats, file_summaries),
+                        project,
+                    )
+                    if records is not None:
+                        scoped[f] = records
+                        events.extend(file_events)
+                        summaries.extend(file_summaries)
+                source_stale = list(scoped)
             if storage_mode == "aggregate":
                 # Per-file delta accounting: each file's contribution is
                 # tracked so a grown file's reparse adds only the difference
                 saved_count = 0
                 for f in source_stale:
-                    records = _parse_source_files([f], source_format)
+                    records = (
+                        scoped[f] if scoped is not None
+                        else _parse_source_files([f], source_format, quarantine, events, stats, summaries)
+                    )
                     if records:
-                        saved_count += api.save_file_aggregate(f, records, **device_kwargs)
+                        records = redact_records(records, redact)
+                        # An archive shares its original's ledger rows, so
+                        # gzipping an ingested transcript adds nothing twice
+                        saved_count += api.save_file_aggregate(transcript_key(f), records, **device_kwargs)
+                        mcp_saved += api.save_mcp_calls(records)
+                        api.save_tool_usage(records)
+                        api.save_sessions(build_sessions(records, titles=retention is not None))
+                        if retention:
+                            api.save_message_content(records, *retention)
             else:
-                records = _parse_source_files(source_stale, source_format)
+                # Full mode upserts by message identity, so re-reading or
+                # skipping already-saved lines is safe; aggregate mode needs
+                # whole files for its per-file deltas and always re-parses
+                offsets: list[tuple[str, int, int, str]] = []
+                if scoped is not None:
+                    records = [r for f in source_stale for r in scoped[f]]
+                elif source_format == "claude" and not force:
+                    records, offsets = _parse_appended(
+                        source_stale, pre_stats, quarantine, events, stats, summaries
+                    )
+                else:
+                    records = _parse_source_files(
+                        source_stale, source_format, quarantine, events, stats, summaries
+                    )
+                records = redact_records(records, redact)
                 saved_count = api.save_snapshot(
                     records,
                     storage_mode=storage_mode,
                     **device_kwargs,
                 ) if records else 0
+                mcp_saved += api.save_mcp_calls(records)
+                api.save_tool_usage(records)
+                api.save_sessions(build_sessions(records, titles=retention is not None))
+                if retention and records:
+                    api.save_message_content(records, *retention)
+                api.update_ingestion_state(offsets)
             total_saved += saved_count
-            if verbose and saved_count:
-                source_label = f" ({overrides['device_name']})" if overrides else ""
-                console.print(f"[green]Saved {saved_count} new token records{source_label}[/green]")
             api.update_files_metadata(source_stale, record_count=0, stats=pre_stats)
         except Exception as e:
+            failed_sources += 1
             console.print(f"[yellow]⚠ Source {label} failed, will retry next run: {e}[/yellow]")
 
+    # Bad-timestamp entries wait in quarantine for `ccg doctor --quarantine`
+    held = api.save_quarantined_entries(redact_quarantine(quarantine, redact))
+    if held:
+        console.print(
+            f"[yellow]⚠ Quarantined {held} entries with bad timestamps "
+            f"(review: ccg doctor --quarantine)[/yellow]"
+        )
+
+    # Fields Claude Code added to its transcripts, reported by `ccg doctor`
+    api.save_unknown_fields(stats.unknown_fields)
+
+    # API errors and interruptions, reported by `ccg stats`
+    events_saved = api.save_session_events(redact_events(events, redact))
+
+    # Session names for `ccg sessions`; summaries are text, so redaction drops them
+    if not redact:
+        api.save_session_summaries(summaries)
+
     if deleted_files:
         api.remove_deleted_file_metadata(deleted_files)
 
+    # --fast commands measure staleness from the last run that saw everything
+    if project is None and not failed_sources:
+        api.record_full_ingest(datetime.now().isoformat())
+
+    # Wake live dashboards / status bars watching the marker
+    if stale_files or deleted_files:
+        touch_refresh_marker(
+            total_saved,
+            started_at=started_at,
+            project=str(project) if project is not None else None,
+            db=str(get_db_path()),
+        )
+
     if verbose and not stale_files and not deleted_files:
-        console.print("[dim]No new data to ingest[/dim]")
+        console.print(
+            f"[dim]No new data to ingest ({len(all_files)} file{'s' if len(all_files) != 1 else ''} "
+            f"unchanged)[/dim]"
+        )
+    elif verbose:
+        _print_ingest_summary(
+            console,
+            scanned=len(all_files),
+            parsed=len(stale_files),
+            stats=stats,
+            saved={"token records": total_saved, "session events": events_saved, "MCP calls": mcp_saved},
+            elapsed=time.monotonic() - clock,
+        )
+    if strict:
+        _print_parse_report(console, stats, report_path)
 
     return total_saved
 
 
+def _print_ingest_summary(
+    console: Console,
+    scanned: int,
+    parsed: int,
+    stats: ParseStats,
+    saved: dict[str, int],
+    elapsed: float,
+) -> None:
+    """
+    Print the breakdown of one ingestion run.
+
+    Args:
+        console: Rich console for output
+        scanned: Transcript files considered
+        parsed: Files parsed because they changed (the rest were skipped)
+        stats: Line counts from the Claude transcript parser
+        saved: New rows per kind, e.g. {"token records": 12}
+        elapsed: Wall-clock seconds the run took
+    """
+    console.print(
+        f"[green]Parsed {parsed:,} of {scanned:,} file{'s' if scanned != 1 else ''} "
+        f"({scanned - parsed:,} unchanged) in {elapsed:.1f}s[/green]"
+    )
+    if stats.lines:
+        reasons = [
+            f"{count:,} {label}"
+            for label, count in (
+                ("malformed", stats.malformed),
+                ("non-message", stats.non_message),
+                ("bad timestamp", stats.bad_timestamps),
+                ("unexpected shape", stats.unexpected_shape),
+            )
+            if count
+        ]
+        detail = f" ({', '.join(reasons)})" if reasons else ""
+        console.print(f"  Lines: {stats.lines:,} parsed, {stats.skipped:,} skipped{detail}")
+    if stats.unknown_fields:
+        console.print(
+            f"  [yellow]Unknown transcript fields: {', '.join(sorted(stats.unknown_fields))} "
+            f"(see: ccg doctor)[/yellow]"
+        )
+    # Token records are always listed; other kinds only when something was added
+    counts = [
+        f"{count:,} {label}" for i, (label, count) in enumerate(saved.items()) if count or i == 0
+    ]
+    console.print("  New: " + ", ".join(counts))
+
+
+def _print_parse_report(console: Console, stats: ParseStats, report_path: Path | None) -> None:
+    """
+    Print a strict-mode validation report, optionally writing it as JSON.
+
+    Args:
+        console: Rich console for output
+        stats: Stats collected with issues enabled
+        report_path: Where to write the full JSON report, or None
+    """
+    report = stats.report()
+    issues = stats.issues or []
+    if not issues:
+        console.print(f"[green]Strict: all {report['lines']:,} parsed lines were valid[/green]")
+    else:
+        console.print(
+            f"[yellow]Strict: {len(issues):,} line{'s' if len(issues) != 1 else ''} lost in "
+            f"{report['files']:,} file{'s' if report['files'] != 1 else ''}[/yellow]"
+        )
+        for reason, count in report["counts"].items():
+            console.print(f"  {reason}: {count:,}")
+        for issue in issues[:STRICT_REPORT_ISSUES]:
+            position = f"line {issue.line}" if issue.line is not None else f"byte {issue.byte_offset}"
+            console.print(f"  [dim]{issue.file} ({position}): {issue.reason}: {issue.detail}[/dim]")
+        if len(issues) > STRICT_REPORT_ISSUES:
+            console.print(f"  [dim]... and {len(issues) - STRICT_REPORT_ISSUES:,} more[/dim]")
+    if report["skipped_types"]:
+        types = ", ".join(f"{name} {count:,}" for name, count in report["skipped_types"].items())
+        console.print(f"  [dim]Non-message entries (not errors): {types}[/dim]")
+
+    if report_path is not None:
+        report_path = report_path.expanduser()
+        report_path.parent.mkdir(parents=True, exist_ok=True)
+        atomic_write_text(report_path, json.dumps(report, indent=2) + "\n")
+        console.print(f"[dim]Report written to {report_path}[/dim]")
+
+
+def preview_token_usage(
+    console: Console,
+    force: bool = False,
+    project: Path | None = None,
+    strict: bool = False,
+    report_path: Path | None = None,
+    redact: str | None = None,
+) -> dict:
+    """
+    Report what ingest_token_usage would add, without writing to the database.
+
+    Parses the same stale files (every file with force) in full and compares
+    them with the database using the save paths' identity rules, so the
+    counts match what a real run of the current storage mode would save.
+
+    Args:
+        console: Rich console for output
+        force: Preview a reparse of all files, ignoring the incremental cache
+        project: Only consider sessions started in this project (or below)
+        strict: Print a validation report of lost lines (see ingest_token_usage)
+        report_path: With strict, also write the report here as JSON
+        redact: Redaction mode overriding the "redact" config key, so
+            new projects are compared in their stored form
+
+    Returns:
+        Dict with files (changed files with records), new_records,
+        new_dates and new_projects
+    """
+    if project is not None:
+        project = project.expanduser().resolve()
+
+    sources = _collect_sources(project)
+    all_files = [f for files, _ in sources for f in files]
+    stale_files = all_files if force or not all_files else api.get_stale_files(all_files)[0]
+    stale_set = {str(f) for f in stale_files}
+
+    parsed: dict[str, list[UsageRecord]] = {}
+    stats = ParseStats(issues=[] if strict else None)
+    redact = redact or get_redaction_mode()
+    for files, overrides in sources:
+        source_format = overrides.get("format", "claude") if overrides else "claude"
+        for f in files:
+            if str(f) not in stale_set:
+                continue
+            records = _parse_source_files([f], source_format, stats=stats)
+            if project is not None:
+                records = _scope_to_project(records, project)
+            # Sessions from other projects (None) and files without records add nothing
+            if records:
+                parsed.setdefault(str(transcript_key(f)), []).extend(redact_records(records, redact))
+
+    preview = api.preview_ingest(parsed, get_storage_mode())
+    preview["files"] = len(parsed)
+
+    if not parsed:
+        console.print("[dim]Dry run: no new data to ingest[/dim]")
+        if strict:
+            _print_parse_report(console, stats, report_path)
+        return preview
+
+    console.print(
+        f"[cyan]Dry run: {preview['files']} changed file{'s' if preview['files'] != 1 else ''} "
+        f"would add {preview['new_records']} new records[/cyan]"
+    )
+    dates = preview["new_dates"]
+    if dates:
+        span = dates[0] if len(dates) == 1 else f"{dates[0]} to {dates[-1]}"
+        console.print(f"  New dates: {len(dates)} ({span})")
+    else:
+        console.print("  New dates: 0")
+    console.print(f"  New projects: {len(preview['new_projects'])}")
+    for folder in preview["new_projects"]:
+        console.print(f"    [dim]{folder}[/dim]")
+    console.print("[dim]Nothing was written to the database[/dim]")
+    if strict:
+        _print_parse_report(console, stats, report_path)
+    return preview
+
+
 def rebuild_token_usage(console: Console) -> int:
     """
     Recompute usage_records from surviving transcripts (repair command).
@@ -142,13 +740,21 @@ def rebuild_token_usage(console: Console) -> int:
     whose transcripts aged out are untouched (their rows are the only
     surviving record). Backs up the database file first and sets the quack
     purge guard before any delete so a racing hook push cannot double the
-    remote.
+    remote. Holds the ingestion lock so hook runs queue behind it.
 
     Returns:
         Number of records saved by the re-ingest
     """
-    import shutil
+    try:
+        with ingest_lock():
+            return _rebuild_sources(console)
+    except TimeoutError as e:
+        console.print(f"[red]Cannot rebuild: {e}[/red]")
+        return 0
+
 
+def _rebuild_sources(console: Console) -> int:
+    """Body of rebuild_token_usage, run while holding the ingestion lock."""
     from src.storage.duckdb_backend import (
         delete_session_rows,
         recompute_daily_snapshots,
@@ -168,11 +774,8 @@ def rebuild_token_usage(console: Console) -> int:
         console.print("[red]--rebuild currently supports the DuckDB backend only[/red]")
         return 0
 
-    backup = db_path.with_name(
-        f"{db_path.name}.pre-rebuild-{datetime.now().strftime('%Y%m%d%H%M%S')}"
-    )
-    shutil.copy2(db_path, backup)
-    if not backup.exists():
+    backup = create_backup("pre-rebuild", db_path)
+    if backup is None or not backup.exists():
         console.print("[red]Backup failed; aborting rebuild[/red]")
         return 0
     console.print(f"[dim]Backup: {backup}[/dim]")
@@ -181,16 +784,7 @@ def rebuild_token_usage(console: Console) -> int:
     # after it, before the remote purge) must refuse.
     set_sync_state(QUACK_PURGE_KEY, "1", db_path=db_path)
 
-    sources: list[tuple[list[Path], dict | None]] = []
-    jsonl_files = get_claude_jsonl_files()
-    if jsonl_files:
-        sources.append((jsonl_files, None))
-    for extra in get_extra_sources():
-        extra_dir = Path(extra["path"])
-        if extra_dir.is_dir():
-            extra_files = list(extra_dir.rglob("*.jsonl"))
-            if extra_files:
-                sources.append((extra_files, extra))
+    sources = _collect_sources(None)
 
     total_saved = 0
     for files, overrides in sources:
@@ -204,7 +798,7 @@ def rebuild_token_usage(console: Console) -> int:
                 except OSError:
                     pass
             source_format = overrides.get("format", "claude") if overrides else "claude"
-            records = _parse_source_files(files, source_format)
+            records = redact_records(_parse_source_files(files, source_format), get_redaction_mode())
             if not records:
                 continue
             device_kwargs = {}
@@ -221,7 +815,12 @@ def rebuild_token_usage(console: Console) -> int:
 
             sessions = sorted({r.session_id for r in records})
             deleted_dates = delete_session_rows(sessions, device_id, db_path=db_path)
-            saved_count = api.save_snapshot(records, storage_mode="full", **device_kwargs)
+            saved_count = api.save_snapshot(
+                records, storage_mode="full", hostname=overrides["device_name"] if overrides else None,
+                **device_kwargs,
+            )
+            titles = get_redaction_mode() is None and get_content_retention() is not None
+            api.save_sessions(build_sessions(records, titles=titles))
             affected = sorted(set(deleted_dates) | {r.date_key for r in records})
             recompute_daily_snapshots(affected, db_path=db_path, **device_kwargs)
             api.update_files_metadata(files, record_count=0, stats=pre_stats)
@@ -232,6 +831,7 @@ def rebuild_token_usage(console: Console) -> int:
         except Exception as e:
             console.print(f"[yellow]⚠ Rebuild of {label} failed: {e}[/yellow]")
 
+    touch_refresh_marker(total_saved)
     console.print(
         "[yellow]Quack pushes are blocked until the remote is purged; "
         "then run: ccg sync push --quack-purged --full[/yellow]"
@@ -239,21 +839,49 @@ def rebuild_token_usage(console: Console) -> int:
     return total_saved
 
 
-def run(console: Console) -> None:
+def run(
+    console: Console,
+    project: Path | None = None,
+    trigger: str = "manual",
+    strict: bool = False,
+    report_path: Path | None = None,
+    redact: str | None = None,
+    stdin: TextIO | None = None,
+    hostname: str | None = None,
+) -> None:
     """
     Update usage database and fill in gaps with empty records.
 
     Incremental: only parses JSONL files whose mtime/size has changed since
     the last run (tracked in the file_metadata table). On a typical Stop-hook
     invocation, exactly one file is stale and a handful of new records get
-    inserted.
+    inserted. Every run is logged in update_runs for `ccg doctor`.
 
     Args:
         console: Rich console for output
+        project: Only ingest sessions from this project (scoped usage hook)
+        trigger: "hook" when run by the Stop hook, "watch" from --watch,
+            "pull" from `ccg pull`, "manual" otherwise
+        strict: Print a validation report of lost lines (see ingest_token_usage)
+        report_path: With strict, also write the report here as JSON
+        redact: Redaction mode overriding the "redact" config key
+        stdin: Ingest transcript lines from this stream instead of the
+            transcript directories (see ingest_stdin)
+        hostname: With stdin, the machine the piped transcripts came from
     """
+    started_at = datetime.now().isoformat()
+    new_records = 0
+    error = None
     try:
-        # Save current snapshot (tokens) -- incremental via get_stale_files
-        ingest_token_usage(console)
+        if stdin is not None:
+            new_records = ingest_stdin(
+                console, stdin, strict=strict, report_path=report_path, redact=redact, hostname=hostname
+            )
+        else:
+            # Save current snapshot (tokens) -- incremental via get_stale_files
+            new_records = ingest_token_usage(
+                console, project=project, strict=strict, report_path=report_path, redact=redact
+            )
 
         # Fill in date gaps so the heatmap is contiguous. Coverage comes from
         # a cheap count/min/max query, not the full stats aggregation.
@@ -271,10 +899,145 @@ def run(console: Console) -> None:
             f"[green]Complete! Coverage: {coverage['oldest_date']} to {coverage['newest_date']}[/green]"
         )
 
+    except GoblinError as e:
+        error = str(e)
+        console.print(f"[red]Error updating usage: {e}[/red]")
+        if e.hint:
+            console.print(f"[dim]{e.hint}[/dim]")
     except Exception as e:
+        error = str(e) or type(e).__name__
         console.print(f"[red]Error updating usage: {e}[/red]")
         import traceback
         traceback.print_exc()
+    finally:
+        _log_update_run(started_at, trigger, new_records, error)
+
+
+def watch(console: Console, project: Path | None = None, quiet: float = WATCH_QUIET_SECONDS) -> None:
+    """
+    Keep the database current by ingesting transcripts as they are written.
+
+    Runs a normal update first, then waits for writes under the Claude
+    projects directory and ingests each debounced batch until interrupted.
+    Every batch goes through ingest_token_usage, so it shares the ingestion
+    lock with hooks and other commands and is logged in update_runs.
+    Batches that saved records then run the "watch_actions" from config.
+
+    Args:
+        console: Rich console for output
+        project: Only ingest sessions from this project
+        quiet: Seconds without writes before a batch is ingested
+    """
+    run(console, project=project, trigger="watch")
+    data_dirs = [d for d in get_claude_data_dirs() if d.exists()]
+    if not data_dirs:
+        console.print(f"[red]Nothing to watch: {get_claude_data_dirs()[0]} does not exist[/red]")
+        return
+
+    actions = WatchActions()
+    with TranscriptWatcher(data_dirs) as watcher:
+        mode = "filesystem events" if watcher.native else "polling (install claude-goblin[watch] for events)"
+        watched = ", ".join(str(d) for d in data_dirs)
+        console.print(f"[cyan]Watching {watched} via {mode}. Press Ctrl+C to stop.[/cyan]")
+        if actions.actions:
+            console.print(f"[dim]After each ingest: {actions.describe()}[/dim]")
+        try:
+            while True:
+                changed = watcher.wait_for_batch(quiet, WATCH_MAX_DELAY_SECONDS)
+                started_at = datetime.now().isoformat()
+                new_records, error = 0, None
+                try:
+                    new_records = ingest_token_usage(console, verbose=False, project=project)
+                except Exception as e:
+                    error = str(e) or type(e).__name__
+                    console.print(f"[red]Error updating usage: {e}[/red]")
+                finally:
+                    _log_update_run(started_at, "watch", new_records, error)
+                if new_records:
+                    console.print(
+                        f"[dim]{datetime.now():%H:%M:%S}[/dim] Saved {new_records} new records "
+                        f"from {len(changed)} file{'s' if len(changed) != 1 else ''}"
+                    )
+                    actions.run(console, new_records, changed)
+        except KeyboardInterrupt:
+            console.print("\n[cyan]Stopped watching[/cyan]")
+
+
+def last_full_update() -> datetime | None:
+    """
+    When an ingest over every transcript source last finished.
+
+    Databases from before ingest_status was tracked fall back to the newest
+    successful `ccg update usage` run.
+
+    Returns:
+        Local finish time, or None if nothing has been recorded
+    """
+    try:
+        finished = api.get_last_full_ingest()
+        if finished is None:
+            runs = [run for run in api.get_update_runs(limit=20) if run["exit_status"] == 0]
+            finished = runs[0]["finished_at"] if runs else None
+    except Exception:
+        # e.g. a --read-only database whose schema predates ingest_status
+        return None
+    return datetime.fromisoformat(finished) if finished else None
+
+
+def format_age(age: timedelta) -> str:
+    """Rough age for messages, e.g. "3 days", "5 hours", "12 minutes"."""
+    for unit, seconds in (("day", 86400), ("hour", 3600), ("minute", 60)):
+        count = int(age.total_seconds() // seconds)
+        if count >= 1:
+            return f"{count} {unit}{'s' if count != 1 else ''}"
+    return "under a minute"
+
+
+def check_fast_staleness(console: Console, max_stale: timedelta | None = None, now: datetime | None = None) -> None:
+    """
+    Warn when a --fast command reads data that hooks have stopped updating.
+
+    Prints a warning once the last full update is older than STALE_WARNING.
+    With max_stale (--max-stale or the "max_stale" config key), refuses
+    instead when it is older than that, or unknown.
+
+    Args:
+        console: Rich console for output
+        max_stale: Oldest acceptable last full update, or None to only warn
+        now: Reference time (default: now)
+
+    Raises:
+        StaleDataError: If the data is older than max_stale
+    """
+    last = last_full_update()
+    age = (now or datetime.now()) - last if last else None
+    if max_stale is not None and (age is None or age > max_stale):
+        seen = f"last full update {format_age(age)} ago" if age is not None else "no full update recorded"
+        raise StaleDataError(f"{seen}, limit {format_age(max_stale)}")
+    if age is None:
+        console.print(
+            "[yellow]⚠ No full update recorded yet; --fast data may be incomplete (ccg update usage)[/yellow]"
+        )
+    elif age > STALE_WARNING:
+        console.print(
+            f"[yellow]⚠ Data last fully updated {format_age(age)} ago; "
+            f"hooks may have stopped (ccg update usage, ccg doctor)[/yellow]"
+        )
+
+
+def _log_update_run(started_at: str, trigger: str, new_records: int, error: str | None) -> None:
+    """Record the run in update_runs; logging never fails the update itself."""
+    try:
+        api.record_update_run(
+            started_at,
+            datetime.now().isoformat(),
+            trigger,
+            exit_status=1 if error else 0,
+            new_records=new_records,
+            error=error,
+        )
+    except Exception:
+        pass
 
 
 #endregion
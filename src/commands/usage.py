@@ -1,19 +1,28 @@
 #region Imports
 import sys
-import time
+from collections.abc import Iterable
+from dataclasses import replace
+from datetime import datetime
 from pathlib import Path
 
 from rich.console import Console
 
-from src.aggregation.daily_stats import aggregate_all
+from src.aggregation.daily_stats import GroupBy, aggregate, aggregate_all
+from src.aggregation.event_stats import summarize_compactions
+from src.aggregation.model_stats import aggregate_by_model_day, model_days_from_snapshots, totals_by_model
+from src.aggregation.project_stats import check_project_budgets
 from src.commands.update_usage import ingest_token_usage
 from src.config.settings import (
     DEFAULT_REFRESH_INTERVAL,
     get_claude_jsonl_files,
 )
+from src.config.user_config import get_project_budgets
 from src.data.jsonl_parser import parse_all_jsonl_files
+from src.errors import GoblinError
+from src.models.usage_record import UsageRecord
 from src.storage import api
-from src.storage.api import load_historical_records
+from src.storage.pricing import PricingTable
+from src.storage.refresh_marker import read_refresh_marker, wait_for_refresh
 from src.visualization.dashboard import render_dashboard
 
 #endregion
@@ -22,7 +31,14 @@ from src.visualization.dashboard import render_dashboard
 #region Functions
 
 
-def run(console: Console, live: bool = False, fast: bool = False, anon: bool = False, force: bool = False) -> None:
+def run(
+    console: Console,
+    live: bool = False,
+    fast: bool = False,
+    anon: bool = False,
+    force: bool = False,
+    interval: int | None = None,
+) -> None:
     """
     Handle the usage command.
 
@@ -35,6 +51,8 @@ def run(console: Console, live: bool = False, fast: bool = False, anon: bool = F
         fast: Skip all updates, read directly from DB (default: False)
         anon: Anonymize project names to project-001, project-002, etc (default: False)
         force: Force re-parse all files, ignoring incremental cache (default: False)
+        interval: Live-mode fallback refresh in seconds (default: 5, or none
+            with fast)
 
     Exit:
         Exits with status 0 on success, 1 on error
@@ -60,12 +78,14 @@ def run(console: Console, live: bool = False, fast: bool = False, anon: bool = F
 
         # Run with or without live refresh
         if run_live:
-            _run_live_dashboard(jsonl_files, console, fast_mode, anonymize, force_reparse)
+            _run_live_dashboard(jsonl_files, console, fast_mode, anonymize, force_reparse, interval)
         else:
             _display_dashboard(jsonl_files, console, fast_mode, anonymize, force_reparse)
 
-    except FileNotFoundError as e:
+    except (GoblinError, FileNotFoundError) as e:
         console.print(f"[red]Error: {e}[/red]")
+        if getattr(e, "hint", None):
+            console.print(f"[dim]{e.hint}[/dim]")
         sys.exit(1)
     except KeyboardInterrupt:
         console.print("\n[cyan]Exiting...[/cyan]")
@@ -77,7 +97,14 @@ def run(console: Console, live: bool = False, fast: bool = False, anon: bool = F
         sys.exit(1)
 
 
-def _run_live_dashboard(jsonl_files: list[Path], console: Console, fast_mode: bool = False, anonymize: bool = False, force: bool = False) -> None:
+def _run_live_dashboard(
+    jsonl_files: list[Path],
+    console: Console,
+    fast_mode: bool = False,
+    anonymize: bool = False,
+    force: bool = False,
+    interval: int | None = None,
+) -> None:
     """
     Run dashboard with auto-refresh.
 
@@ -89,7 +116,13 @@ def _run_live_dashboard(jsonl_files: list[Path], console: Console, fast_mode: bo
         force: Force re-parse all files on first run only.
                Note: In live mode, --force only applies to the initial refresh.
                Subsequent refreshes use incremental parsing for efficiency.
+        interval: Seconds to wait for new data before refreshing anyway.
+            Defaults to DEFAULT_REFRESH_INTERVAL, or to waiting only for
+            new data in fast mode
     """
+    if interval is None and not fast_mode:
+        interval = DEFAULT_REFRESH_INTERVAL
+
     if force:
         console.print(
             "[yellow]Note: --force only applies to the first refresh in live mode.[/yellow]"
@@ -98,8 +131,9 @@ def _run_live_dashboard(jsonl_files: list[Path], console: Console, fast_mode: bo
             "[yellow]Subsequent refreshes will use incremental parsing.[/yellow]\n"
         )
 
+    fallback = f" (and every {interval} seconds)" if interval else ""
     console.print(
-        f"[dim]Auto-refreshing every {DEFAULT_REFRESH_INTERVAL} seconds. "
+        f"[dim]Refreshing whenever 'ccg update usage' records new data{fallback}. "
         "Press Ctrl+C to exit.[/dim]\n"
     )
 
@@ -109,7 +143,10 @@ def _run_live_dashboard(jsonl_files: list[Path], console: Console, fast_mode: bo
             # Only force on first run in live mode (documented behavior)
             _display_dashboard(jsonl_files, console, fast_mode, anonymize, force and first_run)
             first_run = False
-            time.sleep(DEFAULT_REFRESH_INTERVAL)
+            # The update marker wakes us as soon as ingestion lands new data.
+            # Fast mode never ingests itself, so unless an interval was given
+            # it only refreshes on the marker instead of re-querying on a timer.
+            wait_for_refresh(read_refresh_marker(), timeout=interval)
         except KeyboardInterrupt:
             raise
 
@@ -155,12 +192,12 @@ def _display_dashboard(jsonl_files: list[Path], console: Console, fast_mode: boo
 
     # Step 3: Prepare dashboard
     with console.status("[bold #ff8800]Preparing dashboard...", spinner="dots", spinner_style="#ff8800"):
-        # In fast mode, load from DB (aggregate records)
-        # Otherwise, use parsed JSONL records for detailed breakdowns
-        if fast_mode:
-            all_records = load_historical_records()
+        # In fast mode, stream records from the DB on each pass instead of
+        # loading the table; otherwise use the parsed JSONL records
+        if fast_mode or not current_records:
+            all_records = api.StoredRecords()
         else:
-            all_records = current_records if current_records else load_historical_records()
+            all_records = current_records
 
     if not all_records:
         console.clear()
@@ -172,20 +209,49 @@ def _display_dashboard(jsonl_files: list[Path], console: Console, fast_mode: boo
     # Clear screen before displaying dashboard
     console.clear()
 
-    # Get date range for footer
-    dates = sorted(set(r.date_key for r in all_records))
-    date_range = None
-    if dates:
-        date_range = f"{dates[0]} to {dates[-1]}"
+    # Projects whose sessions keep compacting (from logged session events)
+    chronic_projects = summarize_compactions(api.get_session_events()).chronic_projects
+
+    # This month's spend against any project budgets
+    budgets = []
+    if project_budgets := get_project_budgets():
+        today = datetime.now().date()
+        month_projects = api.get_daily_projects(today.replace(day=1).isoformat(), today.isoformat())
+        budgets = check_project_budgets(project_budgets, month_projects)
 
     # Anonymize project names if requested
     if anonymize:
+        mapping = _project_mapping(all_records)
         all_records = _anonymize_projects(all_records)
+        chronic_projects = [
+            replace(p, folder=mapping.get(p.folder, "project-other")) for p in chronic_projects
+        ]
+        budgets = [replace(b, folder=mapping.get(b.folder, "project-other")) for b in budgets]
 
     # Aggregate statistics
     stats = aggregate_all(all_records)
 
-    render_dashboard(stats, all_records, console, clear_screen=False, date_range=date_range, fast_mode=fast_mode)
+    # Get date range for footer
+    dates = sorted(stats.daily_stats)
+    date_range = None
+    if dates:
+        date_range = f"{dates[0]} to {dates[-1]}"
+
+    # Per-model tokens and cost; fast mode reads the per-day model rollup
+    # instead of pricing every record
+    pricing_rows = api.get_model_pricing()
+    pricing = PricingTable(pricing_rows)
+    if fast_mode:
+        model_days = model_days_from_snapshots(api.load_daily_snapshots(), pricing)
+    else:
+        model_days = aggregate_by_model_day(all_records, pricing)
+
+    render_dashboard(
+        stats, all_records, console, clear_screen=False, date_range=date_range,
+        fast_mode=fast_mode, pricing_rows=pricing_rows,
+        chronic_projects=chronic_projects, budgets=budgets, model_totals=totals_by_model(model_days),
+        annotations=api.get_annotations(),
+    )
 
 
 def run_remote(console: Console, anon: bool = False) -> None:
@@ -234,41 +300,44 @@ def run_remote(console: Console, anon: bool = False) -> None:
         console.print(f"[red]Error: {e}[/red]")
 
 
-def _anonymize_projects(records: list) -> list:
+def _project_mapping(records: Iterable[UsageRecord]) -> dict[str, str]:
+    """
+    Anonymized name for each project folder: project-001 is the highest usage.
+
+    Args:
+        records: Usage records (a list or api.StoredRecords)
+
+    Returns:
+        Dict of folder -> project-NNN
+    """
+    # Every folder gets a name, even one without tokens, so none leaks through
+    projects = aggregate(records, GroupBy.PROJECT)
+    sorted_projects = sorted(projects.values(), key=lambda p: p.total_tokens, reverse=True)
+    return {
+        project.date: f"project-{str(i+1).zfill(3)}"
+        for i, project in enumerate(sorted_projects)
+    }
+
+
+def _anonymize_projects(records: Iterable[UsageRecord]) -> Iterable[UsageRecord]:
     """
     Anonymize project folder names by ranking them by total tokens and replacing
     with project-001, project-002, etc (where project-001 is the highest usage).
 
     Args:
-        records: List of UsageRecord objects
+        records: Usage records (a list or api.StoredRecords)
 
     Returns:
-        List of UsageRecord objects with anonymized folder names
+        Records with anonymized folder names; stored records stay streamed
     """
-    from collections import defaultdict
-    from dataclasses import replace
-
-    # Calculate total tokens per project
-    project_totals = defaultdict(int)
-    for record in records:
-        if record.token_usage:
-            project_totals[record.folder] += record.token_usage.total_tokens
-
-    # Sort projects by total tokens (descending) and create mapping
-    sorted_projects = sorted(project_totals.items(), key=lambda x: x[1], reverse=True)
-    project_mapping = {
-        folder: f"project-{str(i+1).zfill(3)}"
-        for i, (folder, _) in enumerate(sorted_projects)
-    }
+    project_mapping = _project_mapping(records)
 
-    # Replace folder names in records
-    anonymized_records = []
-    for record in records:
-        anonymized_records.append(
-            replace(record, folder=project_mapping.get(record.folder, record.folder))
-        )
+    def anonymize(record: UsageRecord) -> UsageRecord:
+        return replace(record, folder=project_mapping.get(record.folder, record.folder))
 
-    return anonymized_records
+    if isinstance(records, api.StoredRecords):
+        return records.map(anonymize)
+    return [anonymize(record) for record in records]
 
 
 #endregion
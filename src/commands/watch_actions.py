@@ -0,0 +1,167 @@
+"""
+Post-ingest actions for `ccg update usage --watch`.
+
+Lets the watch daemon take over automation that would otherwise need one
+Stop hook each (PNG export, sync push, notifications). Actions come from the
+"watch_actions" config list and run after every batch that saved records.
+"""
+#region Imports
+import json
+import subprocess
+import sys
+import time
+import urllib.error
+import urllib.request
+from datetime import datetime
+from pathlib import Path
+
+import typer
+from rich.console import Console
+
+from src.config.user_config import get_watch_actions
+from src.storage import get_db_path
+
+#endregion
+
+
+#region Constants
+# Longest an export or shell command action may run
+WATCH_ACTION_TIMEOUT_SECONDS = 300
+
+# Longest a webhook request may take
+WEBHOOK_TIMEOUT_SECONDS = 10
+
+# Global options forwarded to `ccg` subprocesses so they read the same data
+_FORWARDED_OPTIONS = ("--profile", "--data-dir")
+#endregion
+
+
+#region Classes
+
+
+class WatchActions:
+    """
+    Runs the configured post-ingest actions, honoring each min_interval.
+
+    Attributes:
+        actions: Validated entries from get_watch_actions
+    """
+
+    def __init__(self, actions: list[dict] | None = None) -> None:
+        self.actions = get_watch_actions() if actions is None else actions
+        self._last_run: dict[int, float] = {}
+
+    def describe(self) -> str:
+        """Comma-separated action types, for the startup banner."""
+        return ", ".join(action["type"] for action in self.actions)
+
+    def run(self, console: Console, new_records: int, changed: set[Path]) -> None:
+        """
+        Run every action that is not throttled.
+
+        A failing action is reported and skipped; it never stops the watcher
+        or the remaining actions.
+
+        Args:
+            console: Rich console for output
+            new_records: Records saved by the batch
+            changed: Transcripts written in the batch
+        """
+        now = time.monotonic()
+        for i, action in enumerate(self.actions):
+            last = self._last_run.get(i)
+            if last is not None and now - last < action["min_interval"]:
+                continue
+            self._last_run[i] = now
+            try:
+                _run_action(console, action, new_records, changed)
+            except Exception as e:
+                console.print(f"[yellow]⚠ watch action '{action['type']}' failed: {e}[/yellow]")
+
+
+#endregion
+
+
+#region Functions
+
+
+def ccg_argv(*args: str) -> list[str]:
+    """
+    Command line for running ccg as a subprocess of this one.
+
+    Uses the current interpreter (so it works without ccg on PATH) and
+    forwards --profile/--data-dir, so the child sees the same database.
+
+    Args:
+        *args: Subcommand and its arguments
+
+    Returns:
+        argv list for subprocess
+    """
+    return [sys.executable, "-m", "src.cli", *_forwarded_global_options(sys.argv[1:]), *args]
+
+
+def _forwarded_global_options(argv: list[str]) -> list[str]:
+    """Global options that precede the subcommand in argv."""
+    forwarded = []
+    i = 0
+    while i < len(argv) and argv[i].startswith("-"):
+        arg = argv[i]
+        if arg in _FORWARDED_OPTIONS and i + 1 < len(argv):
+            forwarded += argv[i:i + 2]
+            i += 1
+        elif arg.startswith(tuple(f"{option}=" for option in _FORWARDED_OPTIONS)):
+            forwarded.append(arg)
+        i += 1
+    return forwarded
+
+
+def _run_action(console: Console, action: dict, new_records: int, changed: set[Path]) -> None:
+    """Dispatch one action by type."""
+    if action["type"] == "export":
+        _run_subprocess(ccg_argv("export", "--fast", *action["args"]), shell=False)
+        console.print("[dim]  ↳ export: image regenerated[/dim]")
+    elif action["type"] == "sync":
+        from src.commands.sync.push import run_push
+        try:
+            run_push(console, strict=False)
+        except typer.Exit:
+            pass  # Each failing sink was already reported
+    elif action["type"] == "webhook":
+        payload = {
+            "event": "ingest",
+            "timestamp": datetime.now().isoformat(),
+            "new_records": new_records,
+            "files": sorted(str(path) for path in changed),
+            "db": str(get_db_path()),
+        }
+        request = urllib.request.Request(
+            action["url"],
+            data=json.dumps(payload).encode("utf-8"),
+            headers={"Content-Type": "application/json", **action["headers"]},
+            method="POST",
+        )
+        try:
+            with urllib.request.urlopen(request, timeout=WEBHOOK_TIMEOUT_SECONDS):
+                pass
+        except urllib.error.URLError as e:
+            raise RuntimeError(f"{action['url']}: {getattr(e, 'reason', e)}") from e
+    elif action["type"] == "command":
+        _run_subprocess(action["command"], shell=True)
+
+
+def _run_subprocess(command: list[str] | str, shell: bool) -> None:
+    """Run a command to completion; raise with its stderr tail on failure."""
+    result = subprocess.run(
+        command,
+        shell=shell,
+        capture_output=True,
+        text=True,
+        timeout=WATCH_ACTION_TIMEOUT_SECONDS,
+    )
+    if result.returncode != 0:
+        detail = (result.stderr or result.stdout).strip().splitlines()
+        raise RuntimeError(detail[-1] if detail else f"exit status {result.returncode}")
+
+
+#endregion
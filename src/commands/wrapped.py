@@ -0,0 +1,73 @@
+#region Imports
+from pathlib import Path
+
+from rich.console import Console
+
+from src.aggregation.daily_stats import aggregate_daily_snapshots
+from src.aggregation.wrapped import build_year_wrapped
+from src.commands.export import DEFAULT_EXPORT_DIR
+from src.commands.update_usage import ingest_token_usage
+from src.storage import api
+from src.utils._system import open_file
+from src.visualization.wrapped import export_wrapped, render_wrapped_svg
+
+#endregion
+
+
+#region Functions
+
+
+def run(
+    console: Console,
+    year: int,
+    format_type: str = "png",
+    output: Path | None = None,
+    fast: bool = False,
+    should_open: bool = False,
+) -> None:
+    """
+    Export a shareable year-in-review image.
+
+    Args:
+        console: Rich console for output
+        year: Calendar year to review
+        format_type: "png" or "svg"
+        output: Output file (default: ~/.claude/usage/claude-wrapped-<year>.<format>)
+        fast: Skip ingestion and read from the database only
+        should_open: Open the image after export
+    """
+    if not fast:
+        with console.status("[bold #ff8800]Updating changed files...", spinner="dots", spinner_style="#ff8800"):
+            ingest_token_usage(console, verbose=False)
+
+    start, end = f"{year}-01-01", f"{year}-12-31"
+    with console.status(f"[bold #ff8800]Loading data for {year}...", spinner="dots", spinner_style="#ff8800"):
+        days = api.load_daily_snapshots(start_date=start, end_date=end)
+        if not any(day["total_tokens"] for day in days):
+            console.print(f"[yellow]No usage recorded in {year}.[/yellow]")
+            return
+        wrapped = build_year_wrapped(
+            year,
+            days,
+            api.get_project_tokens(start, end),
+            api.get_hourly_range(start, end),
+        )
+        svg = render_wrapped_svg(wrapped, aggregate_daily_snapshots(days))
+
+    if output is None:
+        DEFAULT_EXPORT_DIR.mkdir(parents=True, exist_ok=True)
+        output = DEFAULT_EXPORT_DIR / f"claude-wrapped-{year}.{format_type}"
+    output = output.expanduser().absolute()
+
+    try:
+        export_wrapped(svg, output, format_type)
+    except ImportError as e:
+        console.print(f"[red]{e}[/red]")
+        return
+
+    console.print(f"[green]✓ Exported to: {output}[/green]")
+    if should_open:
+        open_file(output)
+
+
+#endregion
@@ -1,14 +1,27 @@
 #region Imports
+import os
+import re
 from pathlib import Path
 from typing import Final
 
+from src.errors import MissingDataDirError
+
 #endregion
 
 
 #region Constants
-# Claude data directory
+# Default Claude data directory (see get_claude_data_dir for overrides)
 CLAUDE_DATA_DIR: Final[Path] = Path.home() / ".claude" / "projects"
 
+# Claude Code's own variable for a relocated ~/.claude
+CLAUDE_CONFIG_DIR_ENV: Final[str] = "CLAUDE_CONFIG_DIR"
+
+# Transcript directories for ccg only (os.pathsep-separated); beats CLAUDE_CONFIG_DIR
+CCG_DATA_DIR_ENV: Final[str] = "CCG_DATA_DIR"
+
+# Transcript files read by ingestion: live logs plus compressed archives
+JSONL_PATTERNS: Final[tuple[str, ...]] = ("*.jsonl", "*.jsonl.gz", "*.jsonl.zst")
+
 # Default refresh interval for dashboard (seconds)
 DEFAULT_REFRESH_INTERVAL: Final[int] = 5
 
@@ -21,24 +34,153 @@ GRAPH_DAYS_PER_WEEK: Final[int] = 7
 #endregion
 
 
+#region State
+# Set by the global --data-dir / --profile options; wins over the environment
+_data_dir_overrides: list[Path] = []
+#endregion
+
+
 #region Functions
 
 
+def set_claude_data_dirs(paths: list[Path] | None) -> None:
+    """
+    Override the transcript directories for this process.
+
+    Set by the global --data-dir option (one directory) or --profile (one
+    per selected profile, so merged profiles read all of them).
+
+    Args:
+        paths: Directories holding Claude Code's project transcripts, or
+            None to go back to the environment/default
+    """
+    global _data_dir_overrides
+    _data_dir_overrides = [path.expanduser().resolve() for path in paths or []]
+
+
+def get_claude_data_dirs() -> list[Path]:
+    """
+    Directories Claude Code writes project transcripts to.
+
+    Resolution order: the --data-dir/--profile options, then $CCG_DATA_DIR,
+    then $CLAUDE_CONFIG_DIR/projects (the variable Claude Code itself
+    honors), then ~/.claude/projects.
+
+    Returns:
+        Paths to the transcript directories (may not exist)
+    """
+    if _data_dir_overrides:
+        return list(_data_dir_overrides)
+    env_dirs = [d for d in os.environ.get(CCG_DATA_DIR_ENV, "").split(os.pathsep) if d.strip()]
+    if env_dirs:
+        return [Path(d.strip()).expanduser() for d in env_dirs]
+    config_dir = os.environ.get(CLAUDE_CONFIG_DIR_ENV)
+    if config_dir:
+        return [Path(config_dir).expanduser() / "projects"]
+    return [CLAUDE_DATA_DIR]
+
+
+def get_claude_data_dir() -> Path:
+    """
+    Primary transcript directory (the first of get_claude_data_dirs).
+    """
+    return get_claude_data_dirs()[0]
+
+
+def transcript_project_dir(file_path: Path) -> str | None:
+    """
+    Claude's encoded project folder for a transcript under a data directory.
+
+    Args:
+        file_path: Transcript path
+
+    Returns:
+        First path component below the data directory, or None if the file
+        isn't inside a project folder of any data directory
+    """
+    for data_dir in get_claude_data_dirs():
+        if file_path.is_relative_to(data_dir):
+            relative = file_path.relative_to(data_dir).parts
+            if len(relative) > 1:
+                return relative[0]
+    return None
+
+
+def collect_jsonl_files(directory: Path) -> list[Path]:
+    """
+    Find transcripts under a directory, including .jsonl.gz/.jsonl.zst archives.
+
+    Args:
+        directory: Directory searched recursively
+
+    Returns:
+        List of Path objects matching JSONL_PATTERNS
+    """
+    return [f for pattern in JSONL_PATTERNS for f in directory.rglob(pattern)]
+
+
 def get_claude_jsonl_files() -> list[Path]:
     """
-    Get all JSONL files from Claude's project data directory.
+    Get all JSONL files from Claude's project data directories
+    (get_claude_data_dirs).
+
+    Archived transcripts (.jsonl.gz, .jsonl.zst) are included; the parsers
+    open them transparently.
 
     Returns:
         List of Path objects pointing to JSONL files
 
     Raises:
-        FileNotFoundError: If Claude data directory doesn't exist
+        MissingDataDirError: If Claude data directory doesn't exist (a
+            FileNotFoundError)
     """
-    if not CLAUDE_DATA_DIR.exists():
-        raise FileNotFoundError(
-            f"Claude data directory not found at {CLAUDE_DATA_DIR}. "
-            "Make sure Claude Code has been run at least once."
+    data_dirs = [d for d in get_claude_data_dirs() if d.exists()]
+    if not data_dirs:
+        raise MissingDataDirError(
+            get_claude_data_dir(),
+            "Make sure Claude Code has been run at least once, "
+            f"or point --data-dir / {CCG_DATA_DIR_ENV} / {CLAUDE_CONFIG_DIR_ENV} at it.",
         )
 
-    return list(CLAUDE_DATA_DIR.rglob("*.jsonl"))
+    return [f for data_dir in data_dirs for f in collect_jsonl_files(data_dir)]
+
+
+def encode_project_dir(project: Path) -> str:
+    """
+    Name Claude Code gives a project's transcript directory.
+
+    Every character outside [A-Za-z0-9] becomes "-", so /repo/app maps to
+    ~/.claude/projects/-repo-app/.
+
+    Args:
+        project: Absolute project path
+
+    Returns:
+        Encoded directory name
+    """
+    return re.sub(r"[^A-Za-z0-9]", "-", str(project))
+
+
+def get_project_jsonl_files(project: Path) -> list[Path]:
+    """
+    Get JSONL files for sessions started in a project or its subdirectories.
+
+    Matching is by encoded directory prefix, which can over-match siblings
+    (/repo-old for /repo); callers confirm membership from the records.
+
+    Args:
+        project: Absolute project path
+
+    Returns:
+        List of Path objects pointing to JSONL files
+    """
+    encoded = encode_project_dir(project)
+    files: list[Path] = []
+    for data_dir in get_claude_data_dirs():
+        if not data_dir.exists():
+            continue
+        for project_dir in data_dir.iterdir():
+            if project_dir.is_dir() and (project_dir.name == encoded or project_dir.name.startswith(encoded + "-")):
+                files.extend(collect_jsonl_files(project_dir))
+    return files
 #endregion
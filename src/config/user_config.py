@@ -1,11 +1,16 @@
 #region Imports
 import json
+import os
 import platform
 import re
+import string
 import uuid
+from datetime import timedelta
 from pathlib import Path
 from typing import Any
 
+from src.utils._system import atomic_write
+
 #endregion
 
 
@@ -24,11 +29,77 @@ VALID_DEVICE_ID_PATTERN = re.compile(r'^[a-zA-Z0-9][a-zA-Z0-9_-]{0,63}$')
 # Valid device name pattern: printable ASCII, 1-128 chars
 # More permissive but still safe for display
 VALID_DEVICE_NAME_PATTERN = re.compile(r'^[\x20-\x7E]{1,128}$')
+
+# Placeholders allowed in the "export_output" path template
+EXPORT_TEMPLATE_FIELDS = ("year", "metric", "format", "date", "from", "to")
+VALID_EXPORT_FORMATS = ["png", "svg"]
+
+# Post-ingest actions run by `ccg update usage --watch`
+VALID_WATCH_ACTIONS = ["export", "sync", "webhook", "command"]
+
+# Claude Code subscription plans ("plan_type")
+VALID_PLAN_TYPES = ["pro", "max_5x", "max_20x"]
+
+# Ingest-time privacy redaction ("redact")
+VALID_REDACT_MODES = ["off", "hash", "strip"]
+
+# API rate tiers cost estimates can be priced at ("pricing_tier")
+VALID_PRICING_TIERS = ["standard", "batch", "priority"]
+
+# Durations such as "90m", "12h", "1d", "2w" ("max_stale", --max-stale)
+DURATION_PATTERN = re.compile(r'^(\d+(?:\.\d+)?)\s*([mhdw])$')
+DURATION_UNITS = {"m": "minutes", "h": "hours", "d": "days", "w": "weeks"}
 #endregion
 
 
 #region Constants
 CONFIG_PATH = Path.home() / ".claude" / "goblin_config.json"
+
+# Caps for opt-in message content storage ("store_content")
+DEFAULT_CONTENT_MAX_RECORD_KB = 16
+DEFAULT_CONTENT_MAX_TOTAL_MB = 100
+
+# Timestamped database backups kept under ~/.claude/usage/backups ("backup_keep")
+DEFAULT_BACKUP_KEEP = 10
+
+# Remote pricing for `ccg pricing update` ("pricing_source") and how often
+# auto-refresh fetches it ("pricing_update_hours")
+DEFAULT_PRICING_SOURCE = "https://raw.githubusercontent.com/BerriAI/litellm/main/model_prices_and_context_window.json"
+DEFAULT_PRICING_UPDATE_HOURS = 24
+
+# Environment variables that override config keys (flag > env > config > default)
+CONFIG_ENV_VARS = {
+    "storage_mode": "CCG_STORAGE_MODE",
+    "storage_format": "CCG_STORAGE_FORMAT",
+    "plan_type": "CCG_PLAN",
+    "device_name": "CCG_DEVICE_NAME",
+    "device_type": "CCG_DEVICE_TYPE",
+    "hostname": "CCG_HOSTNAME",
+    "export_format": "CCG_EXPORT_FORMAT",
+    "export_output": "CCG_EXPORT_OUTPUT",
+    "store_content": "CCG_STORE_CONTENT",
+    "content_max_record_kb": "CCG_CONTENT_MAX_RECORD_KB",
+    "content_max_total_mb": "CCG_CONTENT_MAX_TOTAL_MB",
+    "redact": "CCG_REDACT",
+    "pricing_tier": "CCG_PRICING_TIER",
+    "read_only": "CCG_READ_ONLY",
+    "backup_keep": "CCG_BACKUP_KEEP",
+    "pricing_auto_update": "CCG_PRICING_AUTO_UPDATE",
+    "pricing_update_hours": "CCG_PRICING_UPDATE_HOURS",
+    "pricing_source": "CCG_PRICING_SOURCE",
+    "encryption": "CCG_ENCRYPTION",
+    "max_stale": "CCG_MAX_STALE",
+    "mmap_threshold_mb": "CCG_MMAP_THRESHOLD_MB",
+}
+#endregion
+
+
+#region State
+# Set by the global --read-only option; the "read_only" config key also applies
+_read_only_override = False
+
+# Set by the global --max-stale option; overrides the "max_stale" config key
+_max_stale_override: timedelta | None = None
 #endregion
 
 
@@ -62,9 +133,7 @@ def save_config(config: dict) -> None:
     Raises:
         IOError: If config cannot be written
     """
-    CONFIG_PATH.parent.mkdir(parents=True, exist_ok=True)
-
-    with open(CONFIG_PATH, "w", encoding="utf-8") as f:
+    with atomic_write(CONFIG_PATH) as f:
         json.dump(config, f, indent=2)
 
 
@@ -96,8 +165,7 @@ def get_storage_mode() -> str:
     Returns:
         Either "aggregate" or "full"
     """
-    config = load_config()
-    return config.get("storage_mode", "aggregate")
+    return get_setting("storage_mode", "aggregate")
 
 
 def set_storage_mode(mode: str) -> None:
@@ -125,8 +193,7 @@ def get_plan_type() -> str:
     Returns:
         One of "pro", "max_5x", or "max_20x"
     """
-    config = load_config()
-    return config.get("plan_type", "max_20x")
+    return get_setting("plan_type", "max_20x")
 
 
 def set_plan_type(plan: str) -> None:
@@ -139,7 +206,7 @@ def set_plan_type(plan: str) -> None:
     Raises:
         ValueError: If plan is not valid
     """
-    if plan not in ["pro", "max_5x", "max_20x"]:
+    if plan not in VALID_PLAN_TYPES:
         raise ValueError(f"Invalid plan type: {plan}. Must be 'pro', 'max_5x', or 'max_20x'")
 
     config = load_config()
@@ -187,8 +254,7 @@ def get_storage_format() -> str:
     Returns:
         Either "sqlite" or "duckdb"
     """
-    config = load_config()
-    return config.get("storage_format", "sqlite")
+    return get_setting("storage_format", "sqlite")
 
 
 def set_storage_format(format_type: str) -> None:
@@ -338,8 +404,20 @@ def get_device_name() -> str | None:
     Returns:
         Device name string or None if not set
     """
-    config = load_config()
-    return config.get("device_name")
+    return get_setting("device_name")
+
+
+def get_hostname() -> str | None:
+    """
+    Name of this machine, stamped on the usage records it ingests.
+
+    The "hostname" config key (or CCG_HOSTNAME) overrides the system
+    hostname, for machines whose hostnames are generic or change.
+
+    Returns:
+        Hostname, or None if the system reports none
+    """
+    return get_setting("hostname") or platform.node() or None
 
 
 def set_device_name(name: str) -> None:
@@ -370,8 +448,7 @@ def get_device_type_config() -> str | None:
     Returns:
         Device type string or None if not set
     """
-    config = load_config()
-    return config.get("device_type")
+    return get_setting("device_type")
 
 
 def set_device_type_config(device_type: str) -> None:
@@ -439,6 +516,428 @@ def get_extra_sources() -> list[dict]:
     return sources
 
 
+def get_automation_folders() -> list[str]:
+    """
+    Get project folders tagged as automation.
+
+    Reads the "automation_folders" config list (set with `ccg projects
+    automate`). Sessions in these folders or below count as automated
+    rather than interactive usage.
+
+    Returns:
+        Expanded folder paths; non-string entries are skipped
+    """
+    folders = load_config().get("automation_folders", [])
+    if not isinstance(folders, list):
+        return []
+    return [str(Path(folder).expanduser()) for folder in folders if isinstance(folder, str) and folder]
+
+
+def set_automation_folders(folders: list[str]) -> None:
+    """
+    Replace the list of project folders tagged as automation.
+
+    Args:
+        folders: Folder paths (stored as given)
+    """
+    config = load_config()
+    if folders:
+        config["automation_folders"] = folders
+    else:
+        config.pop("automation_folders", None)
+    save_config(config)
+
+
+def get_export_output_template() -> str | None:
+    """
+    Get the default output path template for `ccg export`.
+
+    Reads the "export_output" config string, e.g.
+    "~/Pictures/claude/{year}-{metric}.{format}". Placeholders are listed in
+    EXPORT_TEMPLATE_FIELDS. A template ending in "/" names a directory that
+    receives the default filename.
+
+    Returns:
+        Template string, or None to use ~/.claude/usage/claude-usage.<format>
+    """
+    template = get_setting("export_output")
+    if isinstance(template, str) and template.strip():
+        return template.strip()
+    return None
+
+
+def get_export_format() -> str:
+    """
+    Get the default image format for `ccg export`.
+
+    Reads the "export_format" config key; --svg/--png override it.
+
+    Returns:
+        Either "png" (default) or "svg"
+    """
+    export_format = get_setting("export_format", "png")
+    return export_format if export_format in VALID_EXPORT_FORMATS else "png"
+
+
+def get_content_retention() -> tuple[int, int] | None:
+    """
+    Get the message content storage caps, if content storage is enabled.
+
+    Content is stored only when the "store_content" config key is true.
+    "content_max_record_kb" truncates each message and
+    "content_max_total_mb" bounds the whole table (oldest evicted first).
+
+    Returns:
+        (per-record byte cap, total byte cap), or None when disabled
+    """
+    if get_setting("store_content") is not True:
+        return None
+
+    def _positive(key: str, default: int) -> float:
+        value = get_setting(key, default)
+        return value if isinstance(value, (int, float)) and value > 0 else default
+
+    record_kb = _positive("content_max_record_kb", DEFAULT_CONTENT_MAX_RECORD_KB)
+    total_mb = _positive("content_max_total_mb", DEFAULT_CONTENT_MAX_TOTAL_MB)
+    return int(record_kb * 1024), int(total_mb * 1024 * 1024)
+
+
+def get_mmap_threshold() -> int | None:
+    """
+    Get the transcript size from which ingestion reads through mmap.
+
+    Reads the "mmap_threshold_mb" config key (or CCG_MMAP_THRESHOLD_MB).
+    Unset leaves every transcript on the buffered reader.
+
+    Returns:
+        Threshold in bytes, or None when mmap reads are off
+    """
+    value = get_setting("mmap_threshold_mb")
+    if isinstance(value, bool) or not isinstance(value, (int, float)) or value <= 0:
+        return None
+    return int(value * 1024 * 1024)
+
+
+def get_redaction_mode() -> str | None:
+    """
+    Get the ingest-time redaction mode.
+
+    Reads the "redact" config key. "hash" replaces folder paths and git
+    branches with stable digests (per-project breakdowns still work);
+    "strip" drops them. Both drop message text.
+
+    Returns:
+        "hash", "strip", or None when redaction is off
+    """
+    mode = get_setting("redact", "off")
+    return mode if mode in ("hash", "strip") else None
+
+
+def get_backup_keep() -> int:
+    """
+    Get how many timestamped database backups to keep.
+
+    Reads the "backup_keep" config key; the oldest backups beyond it are
+    deleted whenever a new one is made.
+
+    Returns:
+        Number of backups to keep (at least 1)
+    """
+    keep = get_setting("backup_keep", DEFAULT_BACKUP_KEEP)
+    return keep if isinstance(keep, int) and keep >= 1 else DEFAULT_BACKUP_KEEP
+
+
+def set_read_only(enabled: bool) -> None:
+    """
+    Force read-only mode for this process (the global --read-only option).
+
+    Args:
+        enabled: True to forbid writes regardless of the "read_only" key
+    """
+    global _read_only_override
+    _read_only_override = enabled
+
+
+def is_read_only() -> bool:
+    """
+    Whether ccg may write the usage database and Claude's settings.json.
+
+    True when --read-only was given or the "read_only" config key (or
+    CCG_READ_ONLY) is true. Databases are then opened read-only, ingestion
+    is skipped, and commands that would write refuse to run.
+
+    Returns:
+        True if writes are forbidden
+    """
+    return _read_only_override or get_setting("read_only") is True
+
+
+def parse_duration(value: str) -> timedelta:
+    """
+    Parse a duration such as "90m", "12h", "1d", or "2w".
+
+    Raises:
+        ValueError: If the value is not a positive number with a unit
+    """
+    match = DURATION_PATTERN.match(value.strip().lower())
+    if not match or float(match.group(1)) <= 0:
+        raise ValueError("Must be a positive duration such as 90m, 12h, 1d, or 2w")
+    return timedelta(**{DURATION_UNITS[match.group(2)]: float(match.group(1))})
+
+
+def set_max_stale(max_stale: timedelta | None) -> None:
+    """
+    Set the staleness limit for this process (the global --max-stale option).
+
+    Args:
+        max_stale: Oldest acceptable last full ingest, or None for the config key
+    """
+    global _max_stale_override
+    _max_stale_override = max_stale
+
+
+def get_max_stale() -> timedelta | None:
+    """
+    Get how old the last full ingest may be before --fast commands refuse.
+
+    Returns:
+        The --max-stale value, else the "max_stale" config key, else None
+        (stale data only warns)
+    """
+    if _max_stale_override is not None:
+        return _max_stale_override
+    value = get_setting("max_stale")
+    try:
+        return parse_duration(value) if isinstance(value, str) else None
+    except ValueError:
+        return None
+
+
+def is_encryption_enabled() -> bool:
+    """
+    Whether the SQLite database is opened with SQLCipher.
+
+    Reads the "encryption" config key (or CCG_ENCRYPTION); off by default.
+    The key itself never lives in the config (see storage.encryption).
+
+    Returns:
+        True if connections must be keyed
+    """
+    return get_setting("encryption") is True
+
+
+def get_pricing_tier() -> str:
+    """
+    Get the API rate tier cost estimates are priced at.
+
+    Returns:
+        "standard" (default), "batch", or "priority"
+    """
+    tier = get_setting("pricing_tier", "standard")
+    return tier if tier in VALID_PRICING_TIERS else "standard"
+
+
+def get_pricing_multipliers() -> dict[str, float]:
+    """
+    Get per-model cost multipliers ("pricing_multipliers").
+
+    For organizations with negotiated rates: each model name (or alias)
+    maps to a multiplier of its standard rates, replacing the pricing tier's
+    multiplier for that model. "*" applies to every other model.
+
+    Returns:
+        Mapping of model name to multiplier (invalid entries dropped)
+    """
+    multipliers = load_config().get("pricing_multipliers", {})
+    if not isinstance(multipliers, dict):
+        return {}
+    return {
+        str(model): float(value)
+        for model, value in multipliers.items()
+        if isinstance(value, (int, float)) and not isinstance(value, bool) and value >= 0
+    }
+
+
+def is_pricing_auto_update() -> bool:
+    """
+    Check whether `ccg update usage` refreshes remote pricing ("pricing_auto_update").
+
+    Off by default: it is a network request to "pricing_source".
+    """
+    return get_setting("pricing_auto_update") is True
+
+
+def get_pricing_update_hours() -> float:
+    """
+    Get the age in hours after which auto-refresh fetches pricing again.
+
+    Returns:
+        The "pricing_update_hours" key, or DEFAULT_PRICING_UPDATE_HOURS
+    """
+    hours = get_setting("pricing_update_hours", DEFAULT_PRICING_UPDATE_HOURS)
+    valid = isinstance(hours, (int, float)) and not isinstance(hours, bool) and hours > 0
+    return hours if valid else DEFAULT_PRICING_UPDATE_HOURS
+
+
+def get_pricing_source() -> str:
+    """
+    Get the URL of the LiteLLM-format pricing file `ccg pricing update` fetches.
+
+    Returns:
+        The "pricing_source" key, or DEFAULT_PRICING_SOURCE
+    """
+    return get_setting("pricing_source") or DEFAULT_PRICING_SOURCE
+
+
+def get_pricing_overrides() -> dict[str, dict[str, float]]:
+    """
+    Get per-model rate overrides ("pricing_overrides").
+
+    Set with `ccg pricing set`. Each model name maps to per-MTok rates under
+    the keys model_pricing.json uses ("input_per_mtok", "output_per_mtok",
+    "cache_write_per_mtok", "cache_read_per_mtok", optionally
+    "cache_write_1h_per_mtok"). Stored in the config rather than the
+    database, so they survive re-initializing or replacing it.
+
+    Returns:
+        Mapping of model name to rates (entries without input and output
+        rates, and negative or non-numeric rates, dropped)
+    """
+    overrides = load_config().get("pricing_overrides", {})
+    if not isinstance(overrides, dict):
+        return {}
+    valid = {}
+    for model, rates in overrides.items():
+        if not isinstance(rates, dict):
+            continue
+        rates = {
+            str(key): float(value)
+            for key, value in rates.items()
+            if isinstance(value, (int, float)) and not isinstance(value, bool) and value >= 0
+        }
+        if "input_per_mtok" in rates and "output_per_mtok" in rates:
+            valid[str(model)] = rates
+    return valid
+
+
+def set_pricing_overrides(overrides: dict[str, dict[str, float]]) -> None:
+    """
+    Replace the per-model rate overrides.
+
+    Args:
+        overrides: Model name -> rates (see get_pricing_overrides)
+    """
+    config = load_config()
+    if overrides:
+        config["pricing_overrides"] = overrides
+    else:
+        config.pop("pricing_overrides", None)
+    save_config(config)
+
+
+def get_project_budgets() -> dict[str, float]:
+    """
+    Get monthly cost budgets per project folder ("project_budgets").
+
+    Set with `ccg budget set`. A budget covers the folder and everything
+    below it, measured in estimated API dollars per calendar month.
+
+    Returns:
+        Mapping of expanded folder path to monthly budget (invalid entries dropped)
+    """
+    budgets = load_config().get("project_budgets", {})
+    if not isinstance(budgets, dict):
+        return {}
+    return {
+        str(Path(folder).expanduser()): float(amount)
+        for folder, amount in budgets.items()
+        if folder and isinstance(amount, (int, float)) and not isinstance(amount, bool) and amount > 0
+    }
+
+
+def set_project_budgets(budgets: dict[str, float]) -> None:
+    """
+    Replace the monthly cost budgets per project folder.
+
+    Args:
+        budgets: Folder path -> monthly budget in dollars (stored as given)
+    """
+    config = load_config()
+    if budgets:
+        config["project_budgets"] = budgets
+    else:
+        config.pop("project_budgets", None)
+    save_config(config)
+
+
+def get_profiles() -> dict[str, dict]:
+    """
+    Get named Claude Code profiles (`--profile`).
+
+    Reads the "profiles" config mapping of name -> entry. Each entry needs a
+    "data_dir" (the profile's transcript directory, e.g.
+    ~/.claude-work/projects) and may set "db_path" (default:
+    ~/.claude/usage/profile-<name>.<db extension>).
+
+    Returns:
+        Dict of name -> {"data_dir": Path, "db_path": Path | None};
+        entries without a data_dir are skipped
+    """
+    profiles = {}
+    for name, entry in (load_config().get("profiles") or {}).items():
+        if not isinstance(entry, dict) or not entry.get("data_dir"):
+            continue
+        db_path = entry.get("db_path")
+        profiles[name] = {
+            "data_dir": Path(entry["data_dir"]).expanduser(),
+            "db_path": Path(db_path).expanduser() if db_path else None,
+        }
+    return profiles
+
+
+def get_watch_actions() -> list[dict]:
+    """
+    Get the post-ingest actions for `ccg update usage --watch`.
+
+    Reads the "watch_actions" config list. Each entry has a "type":
+    - export: regenerate the heatmap image; optional "args" list passed to
+      `ccg export` (e.g. ["--svg", "-o", "~/usage.svg"])
+    - sync: push to the configured sync sinks (`ccg sync push`)
+    - webhook: POST a JSON summary of the ingest to "url", with optional
+      "headers"
+    - command: run the shell command in "command"
+
+    Any entry may set "min_interval" (seconds) to run at most that often.
+
+    Returns:
+        List of validated action dicts; invalid entries are skipped
+    """
+    actions = []
+    for entry in load_config().get("watch_actions") or []:
+        if not isinstance(entry, dict) or entry.get("type") not in VALID_WATCH_ACTIONS:
+            continue
+        action = {"type": entry["type"]}
+        if action["type"] == "export":
+            args = entry.get("args") or []
+            if not isinstance(args, list):
+                continue
+            action["args"] = [str(arg) for arg in args]
+        elif action["type"] == "webhook":
+            if not isinstance(entry.get("url"), str) or not entry["url"].startswith(("http://", "https://")):
+                continue
+            action["url"] = entry["url"]
+            headers = entry.get("headers")
+            action["headers"] = {str(k): str(v) for k, v in headers.items()} if isinstance(headers, dict) else {}
+        elif action["type"] == "command":
+            if not isinstance(entry.get("command"), str) or not entry["command"].strip():
+                continue
+            action["command"] = entry["command"]
+        interval = entry.get("min_interval", 0)
+        action["min_interval"] = interval if isinstance(interval, (int, float)) and interval > 0 else 0
+        actions.append(action)
+    return actions
+
+
 def _is_nested_sync_config(sync_config: dict[str, Any]) -> bool:
     """True when every key is a provider name mapping to a dict (nested layout)."""
     if not sync_config:
@@ -667,7 +1166,8 @@ def initialize_device_info() -> tuple[str, str, str]:
     if changed:
         save_config(config)
 
-    return device_id, device_name, device_type
+    # CCG_DEVICE_NAME / CCG_DEVICE_TYPE win over the stored values
+    return device_id, get_device_name() or device_name, get_device_type_config() or device_type
 
 
 def is_sync_configured() -> bool:
@@ -681,3 +1181,203 @@ def is_sync_configured() -> bool:
 
 
 #endregion
+
+
+#region CLI Config Keys
+# Keys `ccg config set` accepts: name -> (description, default). Structured
+# keys (profiles, extra_sources, watch_actions, sync settings) and the
+# storage format (which needs a migration) are edited elsewhere.
+SETTABLE_CONFIG_KEYS: dict[str, tuple[str, Any]] = {
+    "storage_mode": ("aggregate (daily totals) or full (every record)", "aggregate"),
+    "plan_type": ("Subscription plan: pro, max_5x, or max_20x", "max_20x"),
+    "device_name": ("Name recorded with this device's usage", None),
+    "device_type": ("macos, windows, or linux (detected when unset)", None),
+    "hostname": ("Machine name stamped on ingested records (default: system hostname)", None),
+    "export_format": ("Default ccg export format: png or svg", "png"),
+    "export_output": ("Default ccg export path template, e.g. ~/Pictures/{year}.{format}", None),
+    "store_content": ("Keep message text for analysis: true or false", False),
+    "content_max_record_kb": ("Message text cap per record (KB)", DEFAULT_CONTENT_MAX_RECORD_KB),
+    "content_max_total_mb": ("Message text cap in total (MB)", DEFAULT_CONTENT_MAX_TOTAL_MB),
+    "redact": ("Redact text, folders and branches at ingest: off, hash, or strip", "off"),
+    "pricing_tier": ("Price cost estimates at standard, batch, or priority rates", "standard"),
+    "read_only": ("Never write the database or Claude settings: true or false", False),
+    "backup_keep": ("Timestamped database backups to keep", DEFAULT_BACKUP_KEEP),
+    "pricing_auto_update": ("Refresh remote pricing during ccg update usage: true or false", False),
+    "pricing_update_hours": ("Hours before auto-refresh fetches pricing again", DEFAULT_PRICING_UPDATE_HOURS),
+    "pricing_source": ("URL of a LiteLLM-format pricing file", DEFAULT_PRICING_SOURCE),
+    "encryption": ("Open the SQLite database with SQLCipher: true or false (see ccg db encrypt)", False),
+    "max_stale": ("Refuse --fast commands when the last full update is older than this, e.g. 1d", None),
+    "mmap_threshold_mb": ("Read transcripts at least this large (MB) through mmap; off when unset", None),
+}
+
+# Shorthands accepted in place of the stored key name
+CONFIG_KEY_ALIASES = {"plan": "plan_type", "mode": "storage_mode"}
+
+_TRUE_WORDS = ("true", "yes", "on", "1")
+_FALSE_WORDS = ("false", "no", "off", "0")
+
+
+def resolve_config_key(key: str) -> str:
+    """
+    Stored name for a key typed on the command line.
+
+    Accepts aliases and hyphens ("plan", "export-format").
+    """
+    key = key.strip().lower().replace("-", "_")
+    return CONFIG_KEY_ALIASES.get(key, key)
+
+
+def parse_config_value(key: str, raw: str) -> Any:
+    """
+    Validate and convert a command-line value for a settable key.
+
+    Args:
+        key: Stored key name (see resolve_config_key)
+        raw: Value as typed
+
+    Returns:
+        Value to store
+
+    Raises:
+        KeyError: If the key can't be set from the command line
+        ValueError: If the value is invalid for the key
+    """
+    if key not in SETTABLE_CONFIG_KEYS:
+        raise KeyError(key)
+    value = raw.strip()
+
+    def _choice(choices: list[str]) -> str:
+        normalized = value.lower().replace("-", "_") if key == "plan_type" else value.lower()
+        if normalized not in choices:
+            raise ValueError(f"Must be one of: {', '.join(choices)}")
+        return normalized
+
+    if key == "storage_mode":
+        return _choice(["aggregate", "full"])
+    if key == "plan_type":
+        return _choice(VALID_PLAN_TYPES)
+    if key == "device_type":
+        return _choice(VALID_DEVICE_TYPES)
+    if key == "export_format":
+        return _choice(VALID_EXPORT_FORMATS)
+    if key == "redact":
+        return _choice(VALID_REDACT_MODES)
+    if key == "pricing_tier":
+        return _choice(VALID_PRICING_TIERS)
+    if key in ("device_name", "hostname"):
+        if not validate_device_name(value):
+            raise ValueError("Must be 1-128 printable ASCII characters")
+        return value
+    if key == "export_output":
+        try:
+            names = {name for _, name, _, _ in string.Formatter().parse(value) if name is not None}
+        except ValueError as e:
+            raise ValueError(f"Invalid template: {e}") from None
+        unknown = sorted(names - set(EXPORT_TEMPLATE_FIELDS))
+        if unknown or not value:
+            allowed = ", ".join(f"{{{field}}}" for field in EXPORT_TEMPLATE_FIELDS)
+            raise ValueError(f"Unknown placeholder {', '.join(unknown)} (allowed: {allowed})" if unknown else "Empty path")
+        return value
+    if key in ("store_content", "read_only", "pricing_auto_update", "encryption"):
+        if value.lower() in _TRUE_WORDS:
+            return True
+        if value.lower() in _FALSE_WORDS:
+            return False
+        raise ValueError("Must be true or false")
+    if key == "backup_keep":
+        if not value.isdigit() or int(value) < 1:
+            raise ValueError("Must be a whole number of at least 1")
+        return int(value)
+    if key == "pricing_source":
+        if not value.startswith(("https://", "http://")):
+            raise ValueError("Must be an http(s) URL")
+        return value
+    if key == "max_stale":
+        parse_duration(value)
+        return value.lower()
+
+    # content_max_*, pricing_update_hours, mmap_threshold_mb: positive numbers
+    try:
+        number = float(value)
+    except ValueError:
+        raise ValueError("Must be a number") from None
+    if number <= 0:
+        raise ValueError("Must be greater than 0")
+    return int(number) if number.is_integer() else number
+
+
+def set_config_value(key: str, value: Any) -> None:
+    """
+    Store a value already checked by parse_config_value.
+
+    Args:
+        key: Stored key name
+        value: Parsed value
+    """
+    config = load_config()
+    config[key] = value
+    save_config(config)
+
+
+def unset_config_value(key: str) -> bool:
+    """
+    Remove a key so its default applies again.
+
+    Returns:
+        True if the key was set
+    """
+    config = load_config()
+    if key not in config:
+        return False
+    del config[key]
+    save_config(config)
+    return True
+
+
+#endregion
+
+
+#region Environment Overrides
+
+
+def get_env_override(key: str) -> tuple[str, Any] | None:
+    """
+    Environment variable set for a config key, and its parsed value.
+
+    Args:
+        key: Stored key name (see CONFIG_ENV_VARS)
+
+    Returns:
+        (variable name, value), with value None when the variable holds an
+        invalid value (it is then ignored); None when the variable is unset
+    """
+    name = CONFIG_ENV_VARS.get(key)
+    raw = os.environ.get(name, "").strip() if name else ""
+    if not raw:
+        return None
+    if key == "storage_format":
+        return name, raw.lower() if raw.lower() in VALID_STORAGE_FORMATS else None
+    try:
+        return name, parse_config_value(key, raw)
+    except ValueError:
+        return name, None
+
+
+def get_setting(key: str, default: Any = None) -> Any:
+    """
+    Effective value of a config key: environment, then config file, then default.
+
+    Args:
+        key: Stored key name
+        default: Value when neither the environment nor the file sets it
+
+    Returns:
+        The resolved value
+    """
+    override = get_env_override(key)
+    if override is not None and override[1] is not None:
+        return override[1]
+    return load_config().get(key, default)
+
+
+#endregion
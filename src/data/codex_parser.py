@@ -3,6 +3,7 @@ from collections.abc import Iterator
 from datetime import datetime
 from pathlib import Path
 
+from src.data.jsonl_parser import open_transcript, transcript_key
 from src.models.usage_record import TokenUsage, UsageRecord
 
 #endregion
@@ -33,9 +34,10 @@ def parse_codex_file(file_path: Path) -> Iterator[UsageRecord]:
       - cache_read_tokens = last.cached_input_tokens
       - cache_creation    = 0 (Codex has no separate cache-write meter)
       - output_tokens     = last.output_tokens (already includes reasoning tokens)
+      - thinking_tokens   = last.reasoning_output_tokens (part of output_tokens)
 
-    session_id is the file stem (unique per file, so resumed sessions can't
-    collide) and message_uuid is the session id plus a per-file turn counter:
+    session_id is the file stem, minus any archive suffix (unique per file,
+    so resumed sessions can't collide) and message_uuid is the session id plus a per-file turn counter:
     globally unique so the cross-session assistant dedupe never collapses
     turns from different sessions, while re-ingestion of an appended file
     stays idempotent against the (session_id, message_uuid) key.
@@ -48,14 +50,14 @@ def parse_codex_file(file_path: Path) -> Iterator[UsageRecord]:
     """
     import json
 
-    session_id = file_path.stem
+    session_id = transcript_key(file_path).stem
     model: str | None = None
     cwd = "unknown"
     version = "codex"
     turn = 0
 
     try:
-        with open(file_path, encoding="utf-8") as fh:
+        with open_transcript(file_path) as fh:
             for line in fh:
                 line = line.strip()
                 if not line:
@@ -86,6 +88,7 @@ def parse_codex_file(file_path: Path) -> Iterator[UsageRecord]:
                 total_in = int(last.get("input_tokens", 0) or 0)
                 cached_in = int(last.get("cached_input_tokens", 0) or 0)
                 out = int(last.get("output_tokens", 0) or 0)
+                reasoning = int(last.get("reasoning_output_tokens", 0) or 0)
                 uncached_in = max(total_in - cached_in, 0)
 
                 if uncached_in == 0 and cached_in == 0 and out == 0:
@@ -108,6 +111,7 @@ def parse_codex_file(file_path: Path) -> Iterator[UsageRecord]:
                         output_tokens=out,
                         cache_creation_tokens=0,
                         cache_read_tokens=cached_in,
+                        thinking_tokens=min(reasoning, out),
                     ),
                 )
     except (OSError, UnicodeDecodeError):
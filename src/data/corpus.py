@@ -0,0 +1,138 @@
+"""
+Transcript corpus validation.
+
+Claude Code changes its transcript format without notice. Running the
+parser over a set of transcripts and listing every field, entry type, and
+content block type it has not been taught about shows a format change
+before it shows up as wrong numbers. Used by `ccg dev validate-corpus` and
+the fixture tests in tests/fixtures/transcripts.
+"""
+#region Imports
+import json
+from collections import Counter
+from collections.abc import Iterable
+from dataclasses import dataclass, field
+from pathlib import Path
+
+from src.config.settings import collect_jsonl_files
+from src.data.jsonl_parser import ParseStats, find_unknown_fields, open_transcript, parse_jsonl_file
+
+#endregion
+
+
+#region Constants
+KNOWN_ENTRY_TYPES = frozenset({
+    "user", "assistant", "system", "summary", "file-history-snapshot", "queue-operation", "progress",
+})
+
+KNOWN_BLOCK_TYPES = frozenset({
+    "text", "thinking", "redacted_thinking", "tool_use", "server_tool_use", "tool_result",
+    "web_search_tool_result", "web_fetch_tool_result", "image", "document",
+})
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class CorpusReport:
+    """
+    What the parser made of a set of transcripts.
+
+    Attributes:
+        files: Transcripts read
+        records: Usage records parsed
+        stats: Strict-mode line counts and lost-line issues
+        unknown: Occurrences of each unknown field ("message.usage.foo"),
+            entry type ("type=foo"), or block type ("message.content[].type=foo")
+        examples: First file:line each unknown item was seen at
+    """
+
+    files: int = 0
+    records: int = 0
+    stats: ParseStats = field(default_factory=lambda: ParseStats(issues=[]))
+    unknown: Counter = field(default_factory=Counter)
+    examples: dict[str, str] = field(default_factory=dict)
+
+    @property
+    def clean(self) -> bool:
+        """True when every line parsed and nothing unknown turned up."""
+        return not self.stats.issues and not self.unknown
+
+    def to_dict(self) -> dict:
+        """JSON-ready form for `ccg dev validate-corpus --json`."""
+        return {
+            "files": self.files,
+            "records": self.records,
+            "parse": self.stats.report(),
+            "unknown": [
+                {"item": item, "count": count, "example": self.examples[item]}
+                for item, count in self.unknown.most_common()
+            ],
+        }
+#endregion
+
+
+#region Functions
+
+
+def find_unknown(entry: dict) -> list[str]:
+    """
+    Fields, entry type, and content block types of one entry missing from the known sets.
+
+    Args:
+        entry: Parsed transcript line
+
+    Returns:
+        Unknown items as "path.field", "type=name", or "message.content[].type=name"
+    """
+    unknown = []
+    entry_type = entry.get("type")
+    if isinstance(entry_type, str) and entry_type not in KNOWN_ENTRY_TYPES:
+        unknown.append(f"type={entry_type}")
+
+    unknown.extend(find_unknown_fields(entry))
+
+    message = entry.get("message")
+    blocks = message.get("content") if isinstance(message, dict) else None
+    for block in blocks if isinstance(blocks, list) else ():
+        block_type = block.get("type") if isinstance(block, dict) else None
+        if isinstance(block_type, str) and block_type not in KNOWN_BLOCK_TYPES:
+            unknown.append(f"message.content[].type={block_type}")
+    return unknown
+
+
+def validate_corpus(paths: Iterable[Path]) -> CorpusReport:
+    """
+    Parse transcripts strictly and collect everything the parser doesn't know.
+
+    Args:
+        paths: Transcript files, or directories searched for them
+
+    Returns:
+        CorpusReport over every file found
+    """
+    report = CorpusReport()
+    files = []
+    for path in paths:
+        files.extend(collect_jsonl_files(path) if path.is_dir() else [path])
+
+    for file_path in files:
+        report.files += 1
+        report.records += sum(1 for _ in parse_jsonl_file(file_path, stats=report.stats))
+        with open_transcript(file_path) as f:
+            for line_num, line in enumerate(f, start=1):
+                try:
+                    entry = json.loads(line)
+                except json.JSONDecodeError:
+                    continue  # Already reported by the strict parse
+                if not isinstance(entry, dict):
+                    continue
+                for item in find_unknown(entry):
+                    report.unknown[item] += 1
+                    report.examples.setdefault(item, f"{file_path}:{line_num}")
+    return report
+
+
+#endregion
@@ -5,6 +5,7 @@ from datetime import datetime
 from pathlib import Path
 from typing import Any
 
+from src.data.jsonl_parser import open_transcript
 from src.models.usage_record import TokenUsage, UsageRecord
 
 # endregion
@@ -80,6 +81,7 @@ def _parse_record(data: dict[str, Any]) -> UsageRecord | None:
             output_tokens=output_tokens,
             cache_creation_tokens=cache_write_tokens,
             cache_read_tokens=cache_read_tokens,
+            thinking_tokens=min(_non_negative_int(usage.get("reasoning_tokens")), output_tokens),
         ),
     )
 
@@ -93,7 +95,7 @@ def parse_hermes_file(file_path: Path) -> Iterator[UsageRecord]:
     identifiers, and credentials are deliberately absent from this format.
     """
     try:
-        with open(file_path, encoding="utf-8") as fh:
+        with open_transcript(file_path) as fh:
             for line in fh:
                 line = line.strip()
                 if not line:
@@ -1,10 +1,277 @@
 #region Imports
+import gzip
+import hashlib
+import io
 import json
+import mmap
+import os
+from collections import Counter
 from collections.abc import Iterator
-from datetime import datetime
+from contextlib import contextmanager
+from dataclasses import asdict, dataclass, field, replace
+from datetime import datetime, timedelta, timezone
 from pathlib import Path
+from typing import TextIO
+
+from src.errors import TranscriptParseError
+from src.models.transcript_entry import (
+    ENTRY_ALIASES,
+    USAGE_ALIASES,
+    ContentBlock,
+    TranscriptEntry,
+    TranscriptUsage,
+    alias_names,
+)
+from src.models.usage_record import QuarantinedEntry, SessionEvent, TokenUsage, TranscriptSummary, UsageRecord
+
+# orjson (claude-goblin[fast]) decodes transcript lines several times faster;
+# its JSONDecodeError subclasses json's, so malformed lines are handled alike
+try:
+    from orjson import loads as _json_loads
+except ImportError:
+    _json_loads = json.loads
+
+#endregion
+
+
+#region Constants
+# Leading bytes hashed to detect a transcript rewritten in place
+HEAD_DIGEST_BYTES = 4096
+
+# Timestamps outside [earliest, now + skew] are quarantined instead of
+# being bucketed into a bogus day
+EARLIEST_PLAUSIBLE_TIMESTAMP = datetime(2024, 1, 1, tzinfo=timezone.utc)
+MAX_CLOCK_SKEW = timedelta(days=1)
+
+# Characters of the raw line kept with a quarantined entry
+QUARANTINE_LINE_CHARS = 2000
+
+# Archive suffixes opened transparently (after .jsonl)
+COMPRESSED_SUFFIXES = (".gz", ".zst")
+
+# Claude transcripts don't report thinking tokens separately; they are
+# estimated from the thinking text at roughly this many characters per token
+CHARS_PER_THINKING_TOKEN = 4
+
+# User entry Claude Code writes when a turn is cancelled (Esc)
+INTERRUPTED_PREFIX = "[Request interrupted by user"
+
+# API error text that means a rate or usage limit rather than a failure
+RATE_LIMIT_MARKERS = ("rate limit", "rate_limit", "usage limit", "limit reached", "429")
+
+# Characters of error text kept with an event
+EVENT_DETAIL_CHARS = 300
+
+# User content blocks counted as attachments: pasted images and files (PDFs)
+ATTACHMENT_BLOCK_TYPES = ("image", "document")
+
+# Characters of an error message kept with a strict-mode parse issue
+ISSUE_DETAIL_CHARS = 200
+
+# Longest session summary kept (Claude Code writes one short line)
+SUMMARY_MAX_CHARS = 200
+
+# Fields Claude Code is known to write (and the aliases TranscriptEntry
+# accepts for them), by where they appear; those the parser doesn't read
+# are known to be irrelevant to usage
+KNOWN_FIELDS: dict[str, frozenset[str]] = {
+    "": frozenset({
+        "type", "subtype", "uuid", "parentUuid", "logicalParentUuid", "timestamp", "sessionId",
+        "cwd", "gitBranch", "version", "userType", "entrypoint", "isSidechain", "agentId", "slug",
+        "isMeta", "message", "requestId", "toolUseResult", "sourceToolUseID", "toolUseID",
+        "parentToolUseID", "isCompactSummary", "compactMetadata", "isVisibleInTranscriptOnly",
+        "isApiErrorMessage", "error", "retryInMs", "retryAttempt", "maxRetries", "cause", "level",
+        "content", "summary", "leafUuid", "messageId", "snapshot", "isSnapshotUpdate", "operation",
+        "thinkingMetadata", "todos", "permissionMode", "imagePasteIds", "durationMs", "data",
+        "hookCount", "hookInfos", "hookErrors", "preventedContinuation", "stopReason", "hasOutput",
+    }) | alias_names(ENTRY_ALIASES),
+    "message": frozenset({
+        "id", "type", "role", "model", "content", "stop_reason", "stop_sequence", "usage",
+        "container", "context_management",
+    }),
+    "message.usage": frozenset({
+        "input_tokens", "output_tokens", "cache_creation_input_tokens", "cache_read_input_tokens",
+        "cache_creation", "service_tier", "server_tool_use", "output_tokens_details", "thinking_tokens",
+    }) | alias_names(USAGE_ALIASES),
+    "message.usage.cache_creation": frozenset({"ephemeral_5m_input_tokens", "ephemeral_1h_input_tokens"}),
+}
+#endregion
+
+
+#region Exceptions
+
+
+class TimestampError(ValueError):
+    """Entry's timestamp is missing, unparseable, or implausible."""
+
+    def __init__(self, reason: str, raw_timestamp: str):
+        super().__init__(reason)
+        self.reason = reason
+        self.raw_timestamp = raw_timestamp
+#endregion
+
+
+#region Classes
+
+
+@dataclass(frozen=True)
+class ParseIssue:
+    """
+    A transcript line strict parsing could not turn into a record.
+
+    Attributes:
+        file: Transcript path
+        line: 1-based line number (None for tail parses)
+        byte_offset: Offset of the line (tail parses only)
+        reason: "malformed_json", "bad_timestamp", or "unexpected_shape"
+        detail: Parser error message
+    """
+
+    file: str
+    line: int | None
+    byte_offset: int | None
+    reason: str
+    detail: str
+
+
+@dataclass
+class ParseStats:
+    """
+    Line counts collected while parsing Claude transcripts.
+
+    Attributes:
+        lines: Non-blank lines read
+        malformed: Lines that were not valid JSON
+        non_message: Valid entries without usage data (summaries, tool
+            results, system entries, ...)
+        bad_timestamps: Entries quarantined (or skipped) for bad timestamps
+        unexpected_shape: Entries whose structure the parser did not
+            expect (strict mode only; otherwise they raise)
+        skipped_types: Non-message entries by their "type" field
+        unknown_fields: Occurrences of each top-level, message, or usage
+            field missing from KNOWN_FIELDS ("message.usage.foo")
+        issues: Strict mode: one ParseIssue per lost line, replacing the
+            per-line warnings; None when not collecting
+    """
+    lines: int = 0
+    malformed: int = 0
+    non_message: int = 0
+    bad_timestamps: int = 0
+    unexpected_shape: int = 0
+    skipped_types: Counter = field(default_factory=Counter)
+    unknown_fields: Counter = field(default_factory=Counter)
+    issues: list[ParseIssue] | None = None
+
+    @property
+    def skipped(self) -> int:
+        """Lines that produced no record."""
+        return self.malformed + self.non_message + self.bad_timestamps + self.unexpected_shape
+
+    def report(self) -> dict:
+        """
+        Strict-mode validation report as JSON-serializable data.
+
+        Returns:
+            Dict with line totals, issue counts by reason, non-message
+            entry types, the number of affected files, and every issue
+        """
+        issues = self.issues or []
+        return {
+            "lines": self.lines,
+            "skipped": self.skipped,
+            "counts": dict(Counter(issue.reason for issue in issues).most_common()),
+            "skipped_types": dict(self.skipped_types.most_common()),
+            "files": len({issue.file for issue in issues}),
+            "issues": [asdict(issue) for issue in issues],
+        }
+
+
+class _EventTracker:
+    """
+    Extracts SessionEvents from one transcript, line by line.
+
+    Tracks the tokens of the current turn (assistant responses since the
+    last real user prompt) so a turn that ends in an interruption or an
+    error shown to the user records what it had burned, and the messages
+    since the last context compaction.
+    """
+
+    def __init__(self, events: list[SessionEvent], from_start: bool = True) -> None:
+        self.events = events
+        self._turn: dict[str, int] = {}
+        self._messages: set[str] = set()
+        # A tail parse can't know the messages before its start offset
+        self._counting = from_start
+
+    def observe(self, entry: TranscriptEntry, record: UsageRecord | None) -> None:
+        """Update turn state from one parsed entry, emitting any event."""
+        if record is not None:
+            self._messages.add(record.message_uuid)
+        if record is not None and record.token_usage:
+            # Streamed entries repeat a response with growing usage
+            key = record.message_uuid
+            self._turn[key] = max(self._turn.get(key, 0), record.token_usage.total_tokens)
+            return
+
+        if entry.type == "system" and entry.subtype == "compact_boundary":
+            metadata = entry.raw.get("compactMetadata") or {}
+            pre_tokens = metadata.get("preTokens")
+            self._emit(
+                entry, "compaction", str(metadata.get("trigger") or "unknown"), None,
+                messages_before=len(self._messages) if self._counting else None,
+                context_tokens=pre_tokens if isinstance(pre_tokens, int) else 0,
+            )
+            self._messages = set()
+            self._counting = True
+        elif entry.type == "system" and entry.subtype == "api_error":
+            detail = _error_detail(entry.raw.get("error") or entry.raw.get("content"))
+            retry = entry.raw.get("retryAttempt")
+            self._emit(entry, _error_type(detail), detail, retry if isinstance(retry, int) else None)
+        elif entry.type == "assistant" and entry.is_api_error_message:
+            # The synthetic message that ends a turn after retries give up
+            detail = _error_detail(entry.raw.get("error") or entry.message.text)
+            self._emit(entry, _error_type(detail), detail, None, ends_turn=True)
+        elif entry.type == "user" and not entry.is_meta:
+            text = entry.message.text
+            if text.startswith(INTERRUPTED_PREFIX):
+                self._emit(entry, "interrupted", text.strip("[]"), None, ends_turn=True)
+            elif text:
+                self._end_turn()  # A new prompt starts a new turn
+
+    def _end_turn(self) -> int:
+        tokens = sum(self._turn.values())
+        self._turn = {}
+        return tokens
+
+    def _emit(
+        self,
+        entry: TranscriptEntry,
+        event_type: str,
+        detail: str,
+        retry: int | None,
+        ends_turn: bool = False,
+        messages_before: int | None = None,
+        context_tokens: int = 0,
+    ) -> None:
+        turn_tokens = self._end_turn() if ends_turn else 0
+        try:
+            timestamp = _parse_timestamp(entry.timestamp)
+        except TimestampError:
+            return
+        self.events.append(SessionEvent(
+            event_id=entry.uuid or hashlib.sha256(json.dumps(entry.raw, sort_keys=True).encode()).hexdigest()[:32],
+            session_id=entry.session_id,
+            timestamp=timestamp,
+            event_type=event_type,
+            detail=detail[:EVENT_DETAIL_CHARS],
+            folder=entry.cwd,
+            retry_attempt=retry,
+            ends_turn=ends_turn,
+            turn_tokens=turn_tokens,
+            messages_before=messages_before,
+            context_tokens=context_tokens,
+        ))
 
-from src.models.usage_record import TokenUsage, UsageRecord
 
 #endregion
 
@@ -12,7 +279,87 @@ from src.models.usage_record import TokenUsage, UsageRecord
 #region Functions
 
 
-def parse_jsonl_file(file_path: Path) -> Iterator[UsageRecord]:
+def is_compressed(file_path: Path) -> bool:
+    """True for .jsonl.gz / .jsonl.zst archives."""
+    return file_path.suffix in COMPRESSED_SUFFIXES
+
+
+def transcript_key(file_path: Path) -> Path:
+    """
+    Path of the live transcript an archive was made from.
+
+    session.jsonl.gz -> session.jsonl; other paths are returned unchanged.
+    """
+    return file_path.with_suffix("") if is_compressed(file_path) else file_path
+
+
+def open_transcript(file_path: Path) -> TextIO:
+    """
+    Open a transcript for reading as text, decompressing archives.
+
+    zstd archives need Python 3.14's compression.zstd or the zstandard
+    package (claude-goblin[archives]).
+
+    Raises:
+        FileNotFoundError: If the file doesn't exist
+        OSError: If a .zst file is found but no zstd module is installed
+    """
+    if file_path.suffix == ".gz":
+        return gzip.open(file_path, "rt", encoding="utf-8")
+    if file_path.suffix == ".zst":
+        try:
+            from compression import zstd
+            return zstd.open(file_path, "rt", encoding="utf-8")
+        except ImportError:
+            pass
+        try:
+            import zstandard
+        except ImportError:
+            raise OSError("zstd support not installed (uv pip install claude-goblin[archives])") from None
+        reader = zstandard.ZstdDecompressor().stream_reader(open(file_path, "rb"), closefd=True)
+        return io.TextIOWrapper(reader, encoding="utf-8")
+    return open(file_path, encoding="utf-8")
+
+
+def _use_mmap(file_path: Path, threshold: int | None, start_offset: int = 0) -> bool:
+    """True if an uncompressed file has at least threshold bytes left to read."""
+    if threshold is None or is_compressed(file_path):
+        return False
+    try:
+        return file_path.stat().st_size - start_offset >= threshold
+    except OSError:
+        return False
+
+
+def _mmap_lines(file_path: Path, start_offset: int = 0) -> Iterator[bytes]:
+    """
+    Lines of a file from a byte offset, sliced out of a read-only mmap.
+
+    Each line keeps its trailing newline, as when iterating a binary file,
+    so a last line still being written can be told apart. Bytes appended
+    after the file is mapped are left for the next read.
+    """
+    with open(file_path, "rb") as f:
+        size = os.fstat(f.fileno()).st_size
+        if size <= start_offset:
+            return  # Truncated since it was sized; mmap can't map an empty file
+        with mmap.mmap(f.fileno(), 0, access=mmap.ACCESS_READ) as mapped:
+            pos = start_offset
+            while pos < size:
+                end = mapped.find(b"\n", pos)
+                end = size if end == -1 else end + 1
+                yield mapped[pos:end]
+                pos = end
+
+
+def parse_jsonl_file(
+    file_path: Path,
+    quarantine: list[QuarantinedEntry] | None = None,
+    events: list[SessionEvent] | None = None,
+    stats: ParseStats | None = None,
+    summaries: list[TranscriptSummary] | None = None,
+    mmap_threshold: int | None = None,
+) -> Iterator[UsageRecord]:
     """
     Parse a single JSONL file and yield UsageRecord objects.
 
@@ -23,35 +370,187 @@ def parse_jsonl_file(file_path: Path) -> Iterator[UsageRecord]:
 
     Args:
         file_path: Path to the JSONL file to parse
+        quarantine: Collects entries with bad timestamps; when None they
+            are skipped with a warning
+        events: Collects API errors, rate-limit hits, interruptions, and
+            compactions; when None they are skipped
+        stats: Accumulates line counts, when given
+        summaries: Collects the session summary entries Claude Code writes;
+            when None they are skipped
+        mmap_threshold: Size in bytes from which an uncompressed file is
+            read through mmap (user_config.get_mmap_threshold); None to
+            always use the buffered reader
 
     Yields:
         UsageRecord objects for each assistant message with usage data
 
     Raises:
         FileNotFoundError: If the file doesn't exist
-        json.JSONDecodeError: If the file contains invalid JSON
+        TranscriptParseError: If an entry has an unexpected shape (not with
+            stats.issues set)
     """
     if not file_path.exists():
         raise FileNotFoundError(f"File not found: {file_path}")
 
-    with open(file_path, encoding="utf-8") as f:
+    tracker = _EventTracker(events) if events is not None else None
+    if _use_mmap(file_path, mmap_threshold):
+        for line_num, raw in enumerate(_mmap_lines(file_path), start=1):
+            line = raw.decode("utf-8", errors="replace")
+            record = _parse_line(line, file_path, quarantine, tracker, stats, summaries, line_num=line_num)
+            if record:
+                yield record
+        return
+
+    with open_transcript(file_path) as f:
         for line_num, line in enumerate(f, start=1):
-            line = line.strip()
-            if not line:
-                continue
+            record = _parse_line(line, file_path, quarantine, tracker, stats, summaries, line_num=line_num)
+            if record:
+                yield record
+
+
+def parse_jsonl_stream(
+    stream: TextIO,
+    source: str = "<stdin>",
+    quarantine: list[QuarantinedEntry] | None = None,
+    stats: ParseStats | None = None,
+) -> Iterator[UsageRecord]:
+    """
+    Parse JSONL lines from an open stream (e.g. transcripts piped over SSH).
 
-            try:
-                data = json.loads(line)
-                record = _parse_record(data)
-                if record:
-                    yield record
-            except json.JSONDecodeError as e:
-                # Skip malformed lines but continue processing
-                print(f"Warning: Skipping malformed JSON at {file_path}:{line_num}: {e}")
-                continue
+    The stream may concatenate many transcripts, so session events are not
+    tracked (their turn bookkeeping is per file), and an unexpectedly shaped
+    entry is skipped with a warning rather than ending the whole stream.
 
+    Args:
+        stream: Text stream of transcript lines
+        source: Name used for the stream in warnings and issues
+        quarantine: Collects entries with bad timestamps (see parse_jsonl_file)
+        stats: Accumulates line counts (see parse_jsonl_file)
 
-def parse_all_jsonl_files(file_paths: list[Path]) -> list[UsageRecord]:
+    Yields:
+        UsageRecord objects for each message with usage data
+    """
+    source_path = Path(source)
+    for line_num, line in enumerate(stream, start=1):
+        try:
+            record = _parse_line(line, source_path, quarantine, None, stats, line_num=line_num)
+        except TranscriptParseError as e:
+            print(f"Warning: Skipping entry: {e}")
+            continue
+        if record:
+            yield record
+
+
+def parse_jsonl_tail(
+    file_path: Path,
+    start_offset: int = 0,
+    quarantine: list[QuarantinedEntry] | None = None,
+    events: list[SessionEvent] | None = None,
+    stats: ParseStats | None = None,
+    summaries: list[TranscriptSummary] | None = None,
+    mmap_threshold: int | None = None,
+) -> tuple[list[UsageRecord], int]:
+    """
+    Parse the complete lines of a JSONL file from a byte offset onwards.
+
+    A trailing line without a newline is still being written, so it is
+    left for the next run and the returned offset stops before it.
+
+    Args:
+        file_path: Path to the JSONL file to parse
+        start_offset: Byte offset to seek to (a line boundary)
+        quarantine: Collects entries with bad timestamps (see parse_jsonl_file)
+        events: Collects session events (see parse_jsonl_file); a turn that
+            began before start_offset counts only its appended tokens, and
+            a first compaction after it has no message count
+        stats: Accumulates line counts (see parse_jsonl_file)
+        summaries: Collects session summary entries (see parse_jsonl_file)
+        mmap_threshold: Bytes left to read from which the file goes
+            through mmap (see parse_jsonl_file)
+
+    Returns:
+        Tuple of (records, byte offset after the last complete line)
+
+    Raises:
+        FileNotFoundError: If the file doesn't exist
+    """
+    records: list[UsageRecord] = []
+    offset = start_offset
+    tracker = _EventTracker(events, from_start=start_offset == 0) if events is not None else None
+    with _tail_lines(file_path, start_offset, _use_mmap(file_path, mmap_threshold, start_offset)) as lines:
+        for raw in lines:
+            if not raw.endswith(b"\n"):
+                break
+            line_offset = offset
+            offset += len(raw)
+            record = _parse_line(
+                raw.decode("utf-8", errors="replace"), file_path, quarantine, tracker, stats, summaries,
+                byte_offset=line_offset,
+            )
+            if record:
+                records.append(record)
+
+    return records, offset
+
+
+@contextmanager
+def _tail_lines(file_path: Path, start_offset: int, use_mmap: bool) -> Iterator[Iterator[bytes]]:
+    """Binary lines from start_offset, through mmap or a buffered reader."""
+    if use_mmap:
+        lines = _mmap_lines(file_path, start_offset)
+        try:
+            yield lines
+        finally:
+            lines.close()  # Unmap even when the caller stops early
+        return
+    with open(file_path, "rb") as f:
+        f.seek(start_offset)
+        yield f
+
+
+def file_head_digest(file_path: Path, length: int) -> str:
+    """
+    Hash of a file's first min(length, HEAD_DIGEST_BYTES) bytes.
+
+    Stored alongside a parse offset; a different digest at the next run
+    means the file was rewritten rather than appended to.
+    """
+    with open(file_path, "rb") as f:
+        return hashlib.sha256(f.read(min(length, HEAD_DIGEST_BYTES))).hexdigest()
+
+
+def resume_offset(file_path: Path, saved: tuple[int, int, str] | None) -> int:
+    """
+    Byte offset to resume parsing a file from, or 0 for a full re-parse.
+
+    Falls back to 0 when the file was never parsed incrementally, has
+    shrunk below the saved offset, or was rewritten (head digest changed).
+
+    Args:
+        file_path: Path to the JSONL file
+        saved: (size_bytes, byte_offset, head_digest) from ingestion_state
+    """
+    if saved is None:
+        return 0
+    _, offset, digest = saved
+    try:
+        if offset <= 0 or file_path.stat().st_size < offset:
+            return 0
+        if file_head_digest(file_path, offset) != digest:
+            return 0
+    except OSError:
+        return 0
+    return offset
+
+
+def parse_all_jsonl_files(
+    file_paths: list[Path],
+    quarantine: list[QuarantinedEntry] | None = None,
+    events: list[SessionEvent] | None = None,
+    stats: ParseStats | None = None,
+    summaries: list[TranscriptSummary] | None = None,
+    mmap_threshold: int | None = None,
+) -> list[UsageRecord]:
     """
     Parse multiple JSONL files and return deduplicated usage records.
 
@@ -65,6 +564,12 @@ def parse_all_jsonl_files(file_paths: list[Path]) -> list[UsageRecord]:
 
     Args:
         file_paths: List of paths to JSONL files
+        quarantine: Collects entries with bad timestamps (see parse_jsonl_file)
+        events: Collects session events (see parse_jsonl_file)
+        stats: Accumulates line counts (see parse_jsonl_file)
+        summaries: Collects session summary entries (see parse_jsonl_file)
+        mmap_threshold: Size from which files are read through mmap (see
+            parse_jsonl_file)
 
     Returns:
         List of deduplicated UsageRecord objects across all files
@@ -78,7 +583,7 @@ def parse_all_jsonl_files(file_paths: list[Path]) -> list[UsageRecord]:
     records: list[UsageRecord] = []
     for file_path in file_paths:
         try:
-            records.extend(parse_jsonl_file(file_path))
+            records.extend(parse_jsonl_file(file_path, quarantine, events, stats, summaries, mmap_threshold))
         except FileNotFoundError:
             print(f"Warning: File not found, skipping: {file_path}")
         except Exception as e:
@@ -92,9 +597,16 @@ def dedupe_records(records: list[UsageRecord]) -> list[UsageRecord]:
     Collapse assistant records sharing a billed-response identity.
 
     Keeps, per message_uuid, the record with max total tokens (ties: latest
-    timestamp), preserving first-appearance order. User records pass through.
+    timestamp), preserving first-appearance order. Claude Code writes each
+    content block of a response as its own entry, so the kept record gets
+    the union of all entries' tool calls (by tool_use id, so replays don't
+    double count), text, and the largest thinking estimate. User records
+    pass through.
     """
     best: dict[str, UsageRecord] = {}
+    tool_uses: dict[str, dict[str, str]] = {}
+    texts: dict[str, dict[str, None]] = {}
+    thinking: dict[str, int] = {}
     order: list[str] = []
     others: list[UsageRecord] = []
     for record in records:
@@ -102,6 +614,11 @@ def dedupe_records(records: list[UsageRecord]) -> list[UsageRecord]:
             others.append(record)
             continue
         key = record.message_uuid
+        tool_uses.setdefault(key, {}).update(record.tool_uses)
+        if record.content:
+            texts.setdefault(key, {})[record.content] = None
+        if record.token_usage and record.token_usage.thinking_tokens:
+            thinking[key] = max(thinking.get(key, 0), record.token_usage.thinking_tokens)
         current = best.get(key)
         if current is None:
             best[key] = record
@@ -113,55 +630,289 @@ def dedupe_records(records: list[UsageRecord]) -> list[UsageRecord]:
             new_total == cur_total and record.timestamp > current.timestamp
         ):
             best[key] = record
-    return others + [best[key] for key in order]
 
+    deduped = []
+    for key in order:
+        record = best[key]
+        merged_tools = tuple(tool_uses[key].items())
+        merged_text = "\n".join(texts[key]) if key in texts else None
+        if merged_tools != record.tool_uses or merged_text != record.content:
+            record = replace(
+                record,
+                tool_uses=merged_tools,
+                content=merged_text,
+                char_count=len(merged_text) if merged_text else 0,
+            )
+        # Streamed entries carry partial output counts, so the thinking
+        # estimate is capped only against the final response's output
+        if record.token_usage:
+            capped = min(thinking.get(key, 0), record.token_usage.output_tokens)
+            if capped != record.token_usage.thinking_tokens:
+                record = replace(record, token_usage=replace(record.token_usage, thinking_tokens=capped))
+        deduped.append(record)
+    return others + deduped
+
+
+def find_unknown_fields(entry: dict) -> list[str]:
+    """
+    Top-level, message, and usage fields of one entry missing from KNOWN_FIELDS.
+
+    A new field usually means Claude Code changed its transcript format,
+    possibly moving data the parser reads.
+
+    Args:
+        entry: Parsed transcript line
+
+    Returns:
+        Unknown fields as dotted paths ("timestamp2", "message.usage.foo")
+    """
+    unknown = []
+    for path, known in KNOWN_FIELDS.items():
+        node = entry
+        for key in path.split(".") if path else ():
+            node = node.get(key) if isinstance(node, dict) else None
+        if isinstance(node, dict):
+            prefix = f"{path}." if path else ""
+            unknown.extend(f"{prefix}{key}" for key in node if key not in known)
+    return unknown
+
+
+def is_known_field(name: str) -> bool:
+    """Whether a dotted field path (as find_unknown_fields returns) is in KNOWN_FIELDS."""
+    path, _, key = name.rpartition(".")
+    return key in KNOWN_FIELDS.get(path, ())
+
+
+def _parse_line(
+    line: str,
+    file_path: Path,
+    quarantine: list[QuarantinedEntry] | None,
+    tracker: "_EventTracker | None" = None,
+    stats: ParseStats | None = None,
+    summaries: list[TranscriptSummary] | None = None,
+    line_num: int | None = None,
+    byte_offset: int | None = None,
+) -> UsageRecord | None:
+    """
+    Parse one JSONL line, skipping malformed JSON and quarantining bad timestamps.
+
+    Args:
+        line: Raw line text
+        file_path: Transcript the line came from
+        quarantine: Collects entries with bad timestamps; None to warn instead
+        tracker: The file's event tracker, when events are collected
+        stats: Line counters to update, when collected; with stats.issues
+            set (strict mode), lost lines are recorded there instead of
+            warned about, and unexpectedly shaped entries are skipped
+            instead of raising
+        summaries: Collects session summary entries, attributed to the
+            session the transcript file is named after
+        line_num: Line number, for warnings and issues
+        byte_offset: Line's byte offset (tail parses), for warnings and issues
+    """
+    line = line.strip()
+    if not line:
+        return None
+    if stats is not None:
+        stats.lines += 1
+    strict = stats is not None and stats.issues is not None
+    location = f"{file_path}:{line_num}" if line_num is not None else f"{file_path} (byte {byte_offset})"
+
+    def report(reason: str, detail: str) -> None:
+        stats.issues.append(ParseIssue(
+            file=str(file_path), line=line_num, byte_offset=byte_offset,
+            reason=reason, detail=detail[:ISSUE_DETAIL_CHARS],
+        ))
+
+    try:
+        data = _json_loads(line)
+        if stats is not None and isinstance(data, dict):
+            stats.unknown_fields.update(find_unknown_fields(data))
+        entry = TranscriptEntry.from_dict(data)
+        record = _parse_record(entry)
+        if tracker is not None:
+            tracker.observe(entry, record)
+        if summaries is not None and entry.type == "summary" and entry.summary:
+            summaries.append(TranscriptSummary(
+                session_id=transcript_key(file_path).stem,
+                summary=str(entry.summary).strip()[:SUMMARY_MAX_CHARS],
+                leaf_uuid=entry.leaf_uuid,
+            ))
+        if record is None and stats is not None:
+            stats.non_message += 1
+            stats.skipped_types[str(entry.type)] += 1
+        return record
+    except json.JSONDecodeError as e:
+        # Skip malformed lines but continue processing
+        if stats is not None:
+            stats.malformed += 1
+        if strict:
+            report("malformed_json", str(e))
+        else:
+            print(f"Warning: Skipping malformed JSON at {location}: {e}")
+    except TimestampError as e:
+        if stats is not None:
+            stats.bad_timestamps += 1
+        if strict:
+            report("bad_timestamp", f"{e.reason}: {e.raw_timestamp!r}")
+        elif quarantine is None:
+            print(f"Warning: Skipping entry at {location}: {e.reason}")
+        if quarantine is not None:
+            quarantine.append(QuarantinedEntry(
+                file_path=str(file_path),
+                entry_id=entry.uuid or hashlib.sha256(line.encode("utf-8")).hexdigest()[:32],
+                session_id=entry.session_id,
+                raw_timestamp=e.raw_timestamp,
+                reason=e.reason,
+                raw_line=line[:QUARANTINE_LINE_CHARS],
+            ))
+    except (AttributeError, KeyError, TypeError) as e:
+        # A schema change (e.g. a field that is no longer an object)
+        if not strict:
+            raise TranscriptParseError(file_path, line_num, f"{type(e).__name__}: {e}") from e
+        stats.unexpected_shape += 1
+        report("unexpected_shape", f"{type(e).__name__}: {e}")
+    return None
+
+
+def _error_detail(error: object) -> str:
+    """
+    One-line description of a logged API error.
+
+    Errors are logged as text or as (nested) API error objects; the status
+    code and the innermost message are kept.
+    """
+    if isinstance(error, str):
+        return " ".join(error.split())
+    if not isinstance(error, dict):
+        return "unknown error"
+    status = error.get("status")
+    message = None
+    node = error
+    while isinstance(node, dict):
+        message = node.get("message") or node.get("type") or message
+        node = node.get("error")
+    if isinstance(node, str):
+        message = node
+    parts = [str(status)] if status else []
+    if message:
+        parts.append(" ".join(str(message).split()))
+    return " ".join(parts) or "unknown error"
+
+
+def _error_type(detail: str) -> str:
+    """"rate_limit" for rate/usage-limit errors, else "api_error"."""
+    lowered = detail.lower()
+    return "rate_limit" if any(marker in lowered for marker in RATE_LIMIT_MARKERS) else "api_error"
+
+
+def _parse_timestamp(raw: object) -> datetime:
+    """
+    Parse an entry timestamp, rejecting implausible values.
+
+    Raises:
+        TimestampError: If the timestamp is missing, unparseable, or outside
+            [EARLIEST_PLAUSIBLE_TIMESTAMP, now + MAX_CLOCK_SKEW]
+    """
+    if not raw:
+        raise TimestampError("missing timestamp", "")
+    if not isinstance(raw, str):
+        raise TimestampError("timestamp is not a string", json.dumps(raw))
+
+    try:
+        timestamp = datetime.fromisoformat(raw.replace("Z", "+00:00"))
+    except ValueError:
+        raise TimestampError("unparseable timestamp", raw) from None
+
+    # Naive timestamps are local time (as date_key treats them)
+    aware = timestamp if timestamp.tzinfo else timestamp.astimezone()
+    if aware < EARLIEST_PLAUSIBLE_TIMESTAMP:
+        raise TimestampError(f"timestamp before {EARLIEST_PLAUSIBLE_TIMESTAMP.date()}", raw)
+    if aware > datetime.now(timezone.utc) + MAX_CLOCK_SKEW:
+        raise TimestampError("timestamp in the future", raw)
+    return timestamp
+
+
+def _thinking_tokens(usage: TranscriptUsage, thinking_chars: int) -> int:
+    """
+    Thinking tokens for one response: reported if the log has them, else estimated.
+
+    Args:
+        usage: The message's usage
+        thinking_chars: Characters of thinking text in the entry's content blocks
+    """
+    if usage.thinking_tokens is not None:
+        return usage.thinking_tokens
+    return thinking_chars // CHARS_PER_THINKING_TOKEN
+
+
+def _cache_creation_split(usage: TranscriptUsage) -> tuple[int, int]:
+    """
+    Cache-write tokens for one response, split into (5m TTL, 1h TTL).
+
+    Newer logs break cache writes down per TTL under `cache_creation`; older
+    ones only report the top-level `cache_creation_input_tokens`, which is
+    all 5m cache. Any part of the top-level total the breakdown doesn't
+    account for is counted as 5m.
+
+    Args:
+        usage: The message's usage
+    """
+    ephemeral_1h = usage.ephemeral_1h_input_tokens
+    return max(usage.ephemeral_5m_input_tokens, usage.cache_creation_input_tokens - ephemeral_1h), ephemeral_1h
+
+
+def _tool_result_chars(block: ContentBlock) -> int:
+    """Characters of text a tool_result block returns (images count as 0)."""
+    content = block.content
+    if isinstance(content, str):
+        return len(content)
+    if isinstance(content, list):
+        return sum(
+            len(part.get("text") or "")
+            for part in content
+            if isinstance(part, dict) and part.get("type") == "text"
+        )
+    return 0
 
-def _parse_record(data: dict) -> UsageRecord | None:
+
+def _parse_record(entry: TranscriptEntry) -> UsageRecord | None:
     """
-    Parse a single JSON record into a UsageRecord.
+    Parse a single transcript entry into a UsageRecord.
 
     Processes both user prompts and assistant responses.
     Skips system events and other message types.
 
     Args:
-        data: Parsed JSON object from JSONL line
+        entry: Typed view of one JSONL line
 
     Returns:
         UsageRecord for user or assistant messages, None otherwise
     """
-    message_type = data.get("type")
-
-    # Only process user and assistant messages
-    if message_type not in ("user", "assistant"):
-        return None
+    message_type = entry.type
 
-    # Parse timestamp
-    timestamp_str = data.get("timestamp")
-    if not timestamp_str:
+    # Only user and assistant entries carry a message
+    if entry.message is None:
         return None
 
-    timestamp = datetime.fromisoformat(timestamp_str.replace("Z", "+00:00"))
+    # Parse timestamp (raises TimestampError for the caller to quarantine)
+    timestamp = _parse_timestamp(entry.timestamp)
 
-    # Extract metadata (common to both user and assistant)
-    session_id = data.get("sessionId", "unknown")
-    folder = data.get("cwd", "unknown")
-    git_branch = data.get("gitBranch")
-    version = data.get("version", "unknown")
+    # Subagent entries (Task tool, subagents/agent-*.jsonl) are sidechains
+    # logged under the spawning session's id
+    is_subagent = entry.is_sidechain
 
-    # Extract message data
-    message = data.get("message", {})
-    model = message.get("model")
+    message = entry.message
+    model = message.model
 
     # Identity: assistant rows key on the billed API response (message id +
     # request id) so streaming flush entries and session-fork replays of the
     # same response dedupe to one record; user/legacy rows keep the
     # transcript entry uuid.
-    api_id = message.get("id")
-    request_id = data.get("requestId")
-    if message_type == "assistant" and api_id:
-        message_uuid = f"{api_id}:{request_id}" if request_id else api_id
+    if message_type == "assistant" and message.id:
+        message_uuid = f"{message.id}:{entry.request_id}" if entry.request_id else message.id
     else:
-        message_uuid = data.get("uuid", "unknown")
+        message_uuid = entry.uuid or "unknown"
 
     # Filter out synthetic models (test/internal artifacts)
     if model == "<synthetic>":
@@ -170,49 +921,64 @@ def _parse_record(data: dict) -> UsageRecord | None:
     # Extract content for analysis
     content = None
     char_count = 0
-    if isinstance(message.get("content"), str):
-        content = message["content"]
+    tool_uses = []
+    tool_results = []
+    attachment_count = 0
+    thinking_chars = 0
+    if isinstance(message.content, str):
+        content = message.content
         char_count = len(content)
-    elif isinstance(message.get("content"), list):
-        # Handle content blocks (concatenate text)
+    elif message.content is not None:
+        # Handle content blocks (concatenate text, collect tool calls).
+        # tool_result blocks arrive in the following user entry and carry
+        # only the tool_use id, so calls are counted from tool_use blocks.
         text_parts = []
-        for block in message["content"]:
-            if isinstance(block, dict) and block.get("type") == "text":
-                text_parts.append(block.get("text", ""))
+        for block in message.content:
+            if block.type == "text":
+                text_parts.append(block.text or "")
+            elif block.type == "thinking":
+                thinking_chars += len(block.thinking or "")
+            elif block.type in ("tool_use", "server_tool_use") and message_type == "assistant":
+                tool_id = block.id or f"{message_uuid}#{len(tool_uses)}"
+                tool_uses.append((tool_id, block.name or "unknown"))
+            elif block.type in ATTACHMENT_BLOCK_TYPES and message_type == "user":
+                attachment_count += 1
+            elif block.type == "tool_result" and message_type == "user" and block.tool_use_id:
+                tool_results.append((block.tool_use_id, _tool_result_chars(block)))
         content = "\n".join(text_parts) if text_parts else None
         char_count = len(content) if content else 0
 
     # Extract token usage (only available for assistant messages)
     token_usage = None
-    if message_type == "assistant":
-        usage_data = message.get("usage")
-        if usage_data:
-            cache_creation = usage_data.get("cache_creation", {})
-            cache_creation_tokens = (
-                cache_creation.get("cache_creation_input_tokens", 0)
-                + cache_creation.get("ephemeral_5m_input_tokens", 0)
-                + cache_creation.get("ephemeral_1h_input_tokens", 0)
-            )
-
-            token_usage = TokenUsage(
-                input_tokens=usage_data.get("input_tokens", 0),
-                output_tokens=usage_data.get("output_tokens", 0),
-                cache_creation_tokens=cache_creation_tokens,
-                cache_read_tokens=usage_data.get("cache_read_input_tokens", 0),
-                cache_creation_1h_tokens=cache_creation.get("ephemeral_1h_input_tokens", 0),
-            )
+    if message_type == "assistant" and message.usage:
+        usage = message.usage
+        cache_creation_5m, cache_creation_1h = _cache_creation_split(usage)
+        token_usage = TokenUsage(
+            input_tokens=usage.input_tokens,
+            output_tokens=usage.output_tokens,
+            cache_creation_tokens=cache_creation_5m + cache_creation_1h,
+            cache_read_tokens=usage.cache_read_input_tokens,
+            cache_creation_1h_tokens=cache_creation_1h,
+            thinking_tokens=_thinking_tokens(usage, thinking_chars),
+        )
 
     return UsageRecord(
         timestamp=timestamp,
-        session_id=session_id,
+        session_id=entry.session_id,
         message_uuid=message_uuid,
         message_type=message_type,
         model=model,
-        folder=folder,
-        git_branch=git_branch,
-        version=version,
+        folder=entry.cwd,
+        git_branch=entry.git_branch,
+        version=entry.version,
         token_usage=token_usage,
         content=content,
         char_count=char_count,
+        tool_uses=tuple(tool_uses),
+        tool_results=tuple(tool_results),
+        attachment_count=attachment_count,
+        is_subagent=is_subagent,
+        agent_id=entry.agent_id if is_subagent else None,
+        entrypoint=entry.entrypoint,
     )
 #endregion
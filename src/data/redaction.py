@@ -0,0 +1,92 @@
+"""
+Ingest-time privacy redaction.
+
+Applied to parsed records before they are saved, so a redacting database
+never holds message text, project folder paths, or git branch names. Token
+counts, models, timestamps and session ids are kept, so every numeric
+analytic still works.
+"""
+#region Imports
+import hashlib
+from dataclasses import replace
+
+from src.models.usage_record import QuarantinedEntry, SessionEvent, UsageRecord
+
+#endregion
+
+
+#region Constants
+# Hex digits of the digest kept for hashed folders and branches
+DIGEST_CHARS = 12
+
+# Folder recorded in "strip" mode (one pseudo-project for everything)
+STRIPPED_FOLDER = "redacted"
+#endregion
+
+
+#region Functions
+
+
+def _digest(value: str) -> str:
+    return hashlib.sha256(value.encode("utf-8")).hexdigest()[:DIGEST_CHARS]
+
+
+def redact_folder(folder: str, mode: str) -> str:
+    """
+    Redacted form of a project folder path.
+
+    "hash" keeps projects distinct as "/redacted/<digest>" (the same folder
+    always hashes alike, across runs and devices); "strip" folds them into
+    one "redacted" folder.
+    """
+    if mode == "strip":
+        return STRIPPED_FOLDER
+    return f"/{STRIPPED_FOLDER}/{_digest(folder)}"
+
+
+def redact_branch(branch: str | None, mode: str) -> str | None:
+    """Redacted git branch: a "branch-<digest>" in hash mode, None in strip mode."""
+    if branch is None or mode == "strip":
+        return None
+    return f"branch-{_digest(branch)}"
+
+
+def redact_records(records: list[UsageRecord], mode: str | None) -> list[UsageRecord]:
+    """
+    Drop message text and redact folders and branches.
+
+    Args:
+        records: Parsed usage records
+        mode: "hash", "strip", or None to return records unchanged
+
+    Returns:
+        Redacted records
+    """
+    if mode is None:
+        return records
+    return [
+        replace(
+            record,
+            folder=redact_folder(record.folder, mode),
+            git_branch=redact_branch(record.git_branch, mode),
+            content=None,
+        )
+        for record in records
+    ]
+
+
+def redact_events(events: list[SessionEvent], mode: str | None) -> list[SessionEvent]:
+    """Redact session event folders (see redact_records)."""
+    if mode is None:
+        return events
+    return [replace(event, folder=redact_folder(event.folder, mode)) for event in events]
+
+
+def redact_quarantine(entries: list[QuarantinedEntry], mode: str | None) -> list[QuarantinedEntry]:
+    """Drop the raw transcript line kept with quarantined entries."""
+    if mode is None:
+        return entries
+    return [replace(entry, raw_line="") for entry in entries]
+
+
+#endregion
@@ -0,0 +1,164 @@
+"""
+Filesystem watcher for Claude Code transcripts.
+
+Used by `ccg update usage --watch`. Relies on OS notifications through
+watchdog when it is installed (claude-goblin[watch]) and falls back to
+polling file sizes and mtimes otherwise.
+"""
+#region Imports
+import threading
+import time
+from pathlib import Path
+
+try:
+    from watchdog.events import FileSystemEventHandler
+    from watchdog.observers import Observer
+    WATCHDOG_AVAILABLE = True
+except ImportError:
+    FileSystemEventHandler = object
+    WATCHDOG_AVAILABLE = False
+
+#endregion
+
+
+#region Constants
+# Seconds between directory scans when watchdog is unavailable
+POLL_INTERVAL_SECONDS = 2.0
+#endregion
+
+
+#region Classes
+
+
+class _TranscriptEventHandler(FileSystemEventHandler):
+    """Forwards writes to *.jsonl files to the watcher."""
+
+    def __init__(self, watcher: "TranscriptWatcher") -> None:
+        super().__init__()
+        self._watcher = watcher
+
+    def on_any_event(self, event) -> None:
+        if event.is_directory:
+            return
+        for path in (getattr(event, "src_path", None), getattr(event, "dest_path", None)):
+            if path and str(path).endswith(".jsonl"):
+                self._watcher._notify(Path(path))
+
+
+class TranscriptWatcher:
+    """
+    Collects paths of transcripts written under one or more directories.
+
+    Use as a context manager; wait() blocks until something changed.
+
+    Attributes:
+        roots: Directories watched recursively
+        native: True when OS notifications are used instead of polling
+    """
+
+    def __init__(
+        self,
+        roots: list[Path],
+        poll_interval: float = POLL_INTERVAL_SECONDS,
+        native: bool | None = None,
+    ) -> None:
+        self.roots = roots
+        self.native = WATCHDOG_AVAILABLE if native is None else native
+        self._poll_interval = poll_interval
+        self._changed: set[Path] = set()
+        self._lock = threading.Lock()
+        self._event = threading.Event()
+        self._observer = None
+        self._snapshot: dict[Path, tuple[int, int]] = {}
+
+    def __enter__(self) -> "TranscriptWatcher":
+        if self.native:
+            self._observer = Observer()
+            handler = _TranscriptEventHandler(self)
+            for root in self.roots:
+                self._observer.schedule(handler, str(root), recursive=True)
+            self._observer.start()
+        else:
+            self._snapshot = self._scan()
+        return self
+
+    def __exit__(self, *exc) -> None:
+        if self._observer is not None:
+            self._observer.stop()
+            self._observer.join()
+            self._observer = None
+
+    def _notify(self, path: Path) -> None:
+        with self._lock:
+            self._changed.add(path)
+        self._event.set()
+
+    def _scan(self) -> dict[Path, tuple[int, int]]:
+        snapshot = {}
+        for path in (p for root in self.roots for p in root.rglob("*.jsonl")):
+            try:
+                stat = path.stat()
+            except OSError:
+                continue
+            snapshot[path] = (stat.st_mtime_ns, stat.st_size)
+        return snapshot
+
+    def _poll(self) -> None:
+        snapshot = self._scan()
+        for path, stat in snapshot.items():
+            if self._snapshot.get(path) != stat:
+                self._notify(path)
+        self._snapshot = snapshot
+
+    def wait(self, timeout: float | None = None) -> set[Path]:
+        """
+        Block until a transcript changes or the timeout passes.
+
+        Args:
+            timeout: Seconds to wait (None waits indefinitely)
+
+        Returns:
+            Paths changed since the last call (empty on timeout)
+        """
+        deadline = None if timeout is None else time.monotonic() + timeout
+        while not self._event.is_set():
+            remaining = None if deadline is None else deadline - time.monotonic()
+            if remaining is not None and remaining <= 0:
+                break
+            if self.native:
+                self._event.wait(remaining)
+            else:
+                time.sleep(self._poll_interval if remaining is None else min(self._poll_interval, remaining))
+                self._poll()
+
+        with self._lock:
+            changed, self._changed = self._changed, set()
+            self._event.clear()
+        return changed
+
+    def wait_for_batch(self, quiet: float, max_delay: float) -> set[Path]:
+        """
+        Wait for changes, then debounce until writes pause.
+
+        Claude Code appends several lines per response in quick succession;
+        collecting until `quiet` seconds pass without a write turns those
+        into one ingest. `max_delay` bounds the wait during a long stream.
+
+        Args:
+            quiet: Seconds without writes that end the batch
+            max_delay: Longest time to keep extending the batch
+
+        Returns:
+            Paths changed in the batch (never empty)
+        """
+        changed = self.wait()
+        started = time.monotonic()
+        while time.monotonic() - started < max_delay:
+            more = self.wait(min(quiet, max_delay - (time.monotonic() - started)))
+            if not more:
+                break
+            changed |= more
+        return changed
+
+
+#endregion
@@ -0,0 +1,92 @@
+"""
+Errors raised by the storage and data layers.
+
+Each carries a `hint` saying how to recover, which the CLI prints under the
+message (see cli.main). MissingDataDirError is still a FileNotFoundError,
+as raised before, for callers that catch that.
+"""
+#region Imports
+from pathlib import Path
+
+#endregion
+
+
+#region Exceptions
+
+
+class GoblinError(Exception):
+    """
+    Base for errors with a recovery hint.
+
+    Attributes:
+        hint: What the user can do about it, or None
+    """
+
+    hint: str | None = None
+
+    def __init__(self, message: str, hint: str | None = None):
+        super().__init__(message)
+        if hint is not None:
+            self.hint = hint
+
+
+class DatabaseLockedError(GoblinError):
+    """Another process kept the usage database locked past every retry."""
+
+    def __init__(self, db_path: Path, detail: str):
+        super().__init__(
+            f"Usage database is locked: {db_path} ({detail})",
+            "Another ccg process (often the Stop hook or `ccg update usage --watch`) is writing. "
+            "Retry in a moment, or stop the process holding it.",
+        )
+        self.db_path = db_path
+
+
+class SchemaMismatchError(GoblinError):
+    """The database's schema does not match what this release reads."""
+
+    def __init__(self, db_path: Path, found: int, expected: int, hint: str):
+        super().__init__(f"{db_path} has schema version {found}; this release uses {expected}", hint)
+        self.db_path = db_path
+        self.found = found
+        self.expected = expected
+
+
+class TranscriptParseError(GoblinError):
+    """A transcript line has a shape the parser does not understand."""
+
+    def __init__(self, file: Path | str, line: int | None, detail: str):
+        location = f"{file}:{line}" if line is not None else str(file)
+        super().__init__(
+            f"Unexpected transcript entry at {location}: {detail}",
+            "Claude Code may have changed its log format. `ccg update usage --strict` skips such "
+            "lines and reports them; please file an issue with the report.",
+        )
+        self.file = str(file)
+        self.line = line
+
+
+class EncryptionError(GoblinError):
+    """The encrypted database cannot be opened (no SQLCipher, no key, or a wrong key)."""
+
+
+class StaleDataError(GoblinError):
+    """A --fast command's data is older than the --max-stale limit."""
+
+    def __init__(self, detail: str):
+        super().__init__(
+            f"Usage data is too stale for --fast: {detail}",
+            "Run `ccg update usage` (or drop --fast). If the Stop hook should keep it current, "
+            "check it with `ccg doctor`.",
+        )
+
+
+class MissingDataDirError(GoblinError, FileNotFoundError):
+    """No Claude Code transcript directory exists."""
+
+    def __init__(self, data_dir: Path, hint: str):
+        super().__init__(f"Claude data directory not found at {data_dir}", hint)
+        self.data_dir = data_dir
+
+
+#endregion
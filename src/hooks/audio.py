@@ -1,10 +1,11 @@
 #region Imports
 import platform
+import sys
 from pathlib import Path
 
 from rich.console import Console
 
-from src.utils._system import get_sound_command
+from src.utils._system import get_sound_command, list_system_sounds, play_sound
 
 #endregion
 
@@ -23,7 +24,8 @@ def setup(console: Console, settings: dict, settings_path: Path) -> None:
     """
     # Offer sound choices
     console.print("[bold cyan]Choose notification sounds:[/bold cyan]\n")
-    console.print("[dim]You'll pick three sounds: completion, permission requests, and conversation compaction[/dim]\n")
+    console.print("[dim]You'll pick three sounds: completion, permission requests, and conversation compaction[/dim]")
+    console.print("[dim]Hear them first with: ccg setup hooks audio --list-sounds[/dim]\n")
 
     # Check if audio-tts hook exists
     if "Notification" in settings.get("hooks", {}):
@@ -210,6 +212,44 @@ def setup(console: Console, settings: dict, settings_path: Path) -> None:
     console.print("  • All hooks run in the background")
 
 
+def list_sounds(console: Console) -> None:
+    """
+    List the system sounds the audio hook can play, and preview them on request.
+
+    In a terminal, prompts for a number to play until Enter is pressed on
+    an empty line; otherwise only lists.
+
+    Args:
+        console: Rich console for output
+    """
+    sounds = list_system_sounds()
+    if not sounds:
+        console.print(f"[yellow]No system sounds found on {platform.system()}[/yellow]")
+        return
+
+    console.print(f"[bold cyan]System sounds ({len(sounds)}):[/bold cyan]")
+    for idx, name in enumerate(sounds, 1):
+        console.print(f"  {idx}. {name}")
+    if not sys.stdin.isatty():
+        return
+
+    while True:
+        console.print("\n[dim]Enter a number to preview (Enter to finish):[/dim] ", end="")
+        try:
+            user_input = input().strip()
+        except (EOFError, KeyboardInterrupt):
+            console.print()
+            return
+        if not user_input:
+            return
+        if not user_input.isdigit() or not 1 <= int(user_input) <= len(sounds):
+            console.print("[yellow]Invalid selection[/yellow]")
+        elif play_sound(sounds[int(user_input) - 1]):
+            console.print(f"[dim]Playing {sounds[int(user_input) - 1]}[/dim]")
+        else:
+            console.print("[yellow]Could not play that sound[/yellow]")
+
+
 def is_hook(hook) -> bool:
     """
     Check if a hook is an audio notification hook.
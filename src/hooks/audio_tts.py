@@ -4,6 +4,8 @@ from pathlib import Path
 
 from rich.console import Console
 
+from src.utils._system import atomic_write_text
+
 #endregion
 
 
@@ -169,7 +171,7 @@ echo "$message" | say -v {voice} &
 # echo "$(date): TTS spoke: $message" >> ~/.claude/tts_hook.log
 """
 
-    hook_script.write_text(hook_script_content, encoding="utf-8")
+    atomic_write_text(hook_script, hook_script_content)
     hook_script.chmod(0o755)  # Make executable
 
     # Initialize hook structures
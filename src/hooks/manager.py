@@ -6,7 +6,9 @@ from pathlib import Path
 
 from rich.console import Console
 
+from src.config.user_config import is_read_only
 from src.hooks import audio, audio_tts, awesome_hooks, png, usage
+from src.utils._system import atomic_write
 
 #endregion
 
@@ -14,7 +16,16 @@ from src.hooks import audio, audio_tts, awesome_hooks, png, usage
 #region Functions
 
 
-def setup_hooks(console: Console, hook_type: str | None = None, user: bool = False) -> None:
+def setup_hooks(
+    console: Console,
+    hook_type: str | None = None,
+    user: bool = False,
+    usage_project_only: bool = False,
+    png_output: str | None = None,
+    png_svg: bool = False,
+    png_theme: str | None = None,
+    png_metric: str | None = None,
+) -> None:
     """
     Set up Claude Code hooks for automation.
 
@@ -22,6 +33,11 @@ def setup_hooks(console: Console, hook_type: str | None = None, user: bool = Fal
         console: Rich console for output
         hook_type: Type of hook to set up ('usage', 'audio', 'png', or None for menu)
         user: If True, install at user level (~/.claude/), otherwise project level (.claude/)
+        usage_project_only: usage hook only: ingest just the current project
+        png_output: png hook only: fixed output path instead of export settings
+        png_svg: png hook only: always export SVG
+        png_theme: png hook only: color gradient passed as --theme
+        png_metric: png hook only: heatmap metric passed as --metric
     """
     if user:
         settings_path = Path.home() / ".claude" / "settings.json"
@@ -48,6 +64,10 @@ def setup_hooks(console: Console, hook_type: str | None = None, user: bool = Fal
         console.print("Example: ccg setup hooks uv-standard        (project-level)")
         return
 
+    if is_read_only():
+        console.print(f"[red]Read-only mode: not writing {settings_path}[/red]")
+        return
+
     console.print(f"[bold cyan]Setting up {hook_type} hook ({scope}-level)[/bold cyan]\n")
 
     try:
@@ -73,13 +93,15 @@ def setup_hooks(console: Console, hook_type: str | None = None, user: bool = Fal
 
         # Delegate to specific hook module
         if hook_type == "usage":
-            usage.setup(console, settings, settings_path)
+            usage.setup(console, settings, settings_path, project_only=usage_project_only)
         elif hook_type == "audio":
             audio.setup(console, settings, settings_path)
         elif hook_type == "audio-tts":
             audio_tts.setup(console, settings, settings_path)
         elif hook_type == "png":
-            png.setup(console, settings, settings_path)
+            png.setup(
+                console, settings, settings_path, output=png_output, svg=png_svg, theme=png_theme, metric=png_metric
+            )
         elif hook_type in ["bundler-standard", "file-name-consistency", "uv-standard"]:
             awesome_hooks.setup(console, settings, settings_path, hook_type, user=user)
         else:
@@ -88,7 +110,7 @@ def setup_hooks(console: Console, hook_type: str | None = None, user: bool = Fal
             return
 
         # Write settings back
-        with open(settings_path, "w", encoding="utf-8") as f:
+        with atomic_write(settings_path) as f:
             json.dump(settings, f, indent=2)
 
         console.print(f"\n[dim]Hook location: {settings_path}[/dim]")
@@ -120,6 +142,10 @@ def remove_hooks(console: Console, hook_type: str | None = None, user: bool = Fa
         console.print(f"[yellow]No Claude Code settings file found at {scope} level.[/yellow]")
         return
 
+    if is_read_only():
+        console.print(f"[red]Read-only mode: not writing {settings_path}[/red]")
+        return
+
     console.print(f"[bold cyan]Removing hooks ({scope}-level)[/bold cyan]\n")
 
     try:
@@ -223,7 +249,7 @@ def remove_hooks(console: Console, hook_type: str | None = None, user: bool = Fa
             return
 
         # Write settings back
-        with open(settings_path, "w", encoding="utf-8") as f:
+        with atomic_write(settings_path) as f:
             json.dump(settings, f, indent=2)
 
         console.print(f"[green]✓ Removed {removed_count} {removed_type} hook(s)[/green]")
@@ -4,43 +4,82 @@ from pathlib import Path
 
 from rich.console import Console
 
+from src.config.user_config import get_export_format, get_export_output_template
+from src.visualization.export import HEATMAP_METRICS, HEATMAP_THEMES
+
 #endregion
 
 
 #region Functions
 
 
-def setup(console: Console, settings: dict, settings_path: Path) -> None:
+def setup(
+    console: Console,
+    settings: dict,
+    settings_path: Path,
+    output: str | None = None,
+    svg: bool = False,
+    theme: str | None = None,
+    metric: str | None = None,
+) -> None:
     """
     Set up the PNG auto-update hook.
 
+    Without a fixed output the hook runs a bare `ccg export`, so every run
+    follows the current "export_output" and "export_format" config. A theme
+    and metric are passed as --theme and --metric; the metric also fills
+    the {metric} placeholder of the output template.
+
     Args:
         console: Rich console for output
         settings: Settings dictionary to modify
         settings_path: Path to settings.json file
+        output: Fixed output path (skips the prompt)
+        svg: Always export SVG regardless of export_format
+        theme: Color gradient, one of HEATMAP_THEMES (default: claude)
+        metric: What a day's color measures, one of HEATMAP_METRICS (default: tokens)
     """
-    # Ask for output path
-    default_output = str(Path.home() / ".claude" / "usage" / "claude-usage.png")
-    console.print("[bold cyan]Configure PNG auto-update:[/bold cyan]\n")
-    console.print(f"[dim]Default output: {default_output}[/dim]")
-    console.print("[dim]Enter custom path (or press Enter for default):[/dim] ", end="")
-
-    try:
-        user_input = input().strip()
-        output_path = user_input if user_input else default_output
-    except (EOFError, KeyboardInterrupt):
-        console.print("\n[yellow]Cancelled[/yellow]")
+    if theme is not None and theme not in HEATMAP_THEMES:
+        console.print(f"[red]Invalid theme: {theme} (choose from {', '.join(HEATMAP_THEMES)})[/red]")
+        return
+    if metric is not None and metric not in HEATMAP_METRICS:
+        console.print(f"[red]Invalid metric: {metric} (choose from {', '.join(HEATMAP_METRICS)})[/red]")
         return
 
-    # Create directory if it doesn't exist
-    output_dir = Path(output_path).parent
-    output_dir.mkdir(parents=True, exist_ok=True)
-
-    # Quote the path (spaces in home dirs); Windows hooks run through cmd.exe
+    template = get_export_output_template()
+    export_format = "svg" if svg else get_export_format()
+    default_output = template or str(Path.home() / ".claude" / "usage" / f"claude-usage.{export_format}")
+
+    if output is None:
+        # Ask for output path
+        console.print("[bold cyan]Configure PNG auto-update:[/bold cyan]\n")
+        console.print(f"[dim]Default output (from export settings): {default_output}[/dim]")
+        console.print("[dim]Enter custom path (or press Enter for default):[/dim] ", end="")
+
+        try:
+            output = input().strip() or None
+        except (EOFError, KeyboardInterrupt):
+            console.print("\n[yellow]Cancelled[/yellow]")
+            return
+
+    args = "ccg export"
+    if svg:
+        args += " --svg"
+    if theme:
+        args += f" --theme {theme}"
+    if metric:
+        args += f" --metric {metric}"
+    if output:
+        # Create directory if it doesn't exist
+        Path(output).expanduser().parent.mkdir(parents=True, exist_ok=True)
+        # Quote the path (spaces in home dirs)
+        args += f' -o "{output}"'
+
+    # Windows hooks run through cmd.exe
     if sys.platform == "win32":
-        hook_command = f'ccg export -o "{output_path}" >NUL 2>&1'
+        hook_command = f"{args} >NUL 2>&1"
     else:
-        hook_command = f'ccg export -o "{output_path}" > /dev/null 2>&1 &'
+        hook_command = f"{args} > /dev/null 2>&1 &"
 
     # Remove existing PNG hooks
     original_count = len(settings["hooks"]["Stop"])
@@ -64,8 +103,14 @@ def setup(console: Console, settings: dict, settings_path: Path) -> None:
 
     console.print("[green]✓ Successfully configured PNG auto-update hook[/green]")
     console.print("\n[bold]What this does:[/bold]")
-    console.print("  • Exports PNG after each Claude response completes")
-    console.print(f"  • Overwrites: {output_path}")
+    console.print(f"  • Exports {export_format.upper()} after each Claude response completes")
+    if theme or metric:
+        console.print(f"  • Colors days by {metric or 'tokens'} with the {theme or 'claude'} theme")
+    if output:
+        console.print(f"  • Overwrites: {output}")
+    else:
+        console.print(f"  • Overwrites: {default_output}")
+        console.print("  • Follows export_output / export_format in goblin_config.json")
     console.print("  • Runs silently in the background")
 
 
@@ -73,7 +118,8 @@ def is_hook(hook) -> bool:
     """
     Check if a hook is a PNG export hook.
 
-    Recognizes both old-style (--export) and new-style (export) commands.
+    Recognizes both old-style (--export) and new-style (export) commands,
+    with or without a fixed -o path.
 
     Args:
         hook: Hook configuration dictionary
@@ -87,8 +133,8 @@ def is_hook(hook) -> bool:
         cmd = h.get("command", "")
         # Support both old-style (--export) and new-style (export)
         # Also support both claude-goblin and ccg aliases
-        if (("claude-goblin --export" in cmd or "claude-goblin export" in cmd or
-             "ccg --export" in cmd or "ccg export" in cmd) and "-o" in cmd):
+        if ("claude-goblin --export" in cmd or "claude-goblin export" in cmd or
+                "ccg --export" in cmd or "ccg export" in cmd):
             return True
     return False
 
@@ -0,0 +1,197 @@
+"""
+Dry runs of installed Claude Code hooks.
+
+`ccg hooks test <type>` finds the commands a hook type installed in the
+project and user settings.json, feeds each the JSON payload Claude Code
+would send on stdin, and reports the exit code and output, so a broken
+hook shows up before it is relied on mid-session.
+"""
+#region Imports
+import json
+import os
+import subprocess
+import time
+from collections.abc import Callable
+from dataclasses import dataclass
+from pathlib import Path
+
+from src.hooks import audio, audio_tts, awesome_hooks, png, usage
+
+#endregion
+
+
+#region Constants
+# Claude Code's default hook timeout
+HOOK_TIMEOUT_SECONDS = 60
+
+# Characters of stdout/stderr kept per run
+OUTPUT_CHARS = 2000
+
+# Hook type -> recognizer of its settings.json entries
+HOOK_MATCHERS: dict[str, Callable[[dict], bool]] = {
+    "usage": usage.is_hook,
+    "audio": audio.is_hook,
+    "audio-tts": audio_tts.is_hook,
+    "png": png.is_hook,
+    "bundler-standard": awesome_hooks.is_bundler_standard_hook,
+    "file-name-consistency": awesome_hooks.is_file_name_consistency_hook,
+    "uv-standard": awesome_hooks.is_uv_standard_hook,
+}
+
+# Session id in synthetic payloads, so hook logs can tell test runs apart
+TEST_SESSION_ID = "ccg-hooks-test"
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class InstalledHook:
+    """
+    One hook command from a settings.json.
+
+    Attributes:
+        event: Hook event it runs on ("Stop", "Notification", ...)
+        command: Shell command
+        scope: "project" or "user"
+        settings_path: File it was found in
+    """
+
+    event: str
+    command: str
+    scope: str
+    settings_path: Path
+
+
+@dataclass
+class HookRun:
+    """
+    Outcome of feeding a hook its payload.
+
+    Attributes:
+        hook: The hook that ran
+        exit_code: Process exit code (None on timeout)
+        stdout: Captured standard output, truncated
+        stderr: Captured standard error, truncated
+        seconds: Wall-clock run time
+    """
+
+    hook: InstalledHook
+    exit_code: int | None
+    stdout: str
+    stderr: str
+    seconds: float
+
+    @property
+    def ok(self) -> bool:
+        """Exit code 0; 2 blocks the action in Claude Code, anything else is an error."""
+        return self.exit_code == 0
+#endregion
+
+
+#region Functions
+
+
+def settings_paths(cwd: Path | None = None) -> list[tuple[str, Path]]:
+    """Project and user settings.json files, as setup_hooks writes them."""
+    return [
+        ("project", (cwd or Path.cwd()) / ".claude" / "settings.json"),
+        ("user", Path.home() / ".claude" / "settings.json"),
+    ]
+
+
+def find_installed_hooks(hook_type: str, cwd: Path | None = None) -> list[InstalledHook]:
+    """
+    Commands of one hook type installed at project or user level.
+
+    Args:
+        hook_type: Key of HOOK_MATCHERS
+        cwd: Project directory (default: the current directory)
+
+    Returns:
+        Installed hooks, project level first
+    """
+    matcher = HOOK_MATCHERS[hook_type]
+    found = []
+    for scope, path in settings_paths(cwd):
+        try:
+            settings = json.loads(path.read_text(encoding="utf-8"))
+        except (OSError, json.JSONDecodeError):
+            continue
+        hooks = settings.get("hooks") if isinstance(settings, dict) else None
+        for event, entries in (hooks if isinstance(hooks, dict) else {}).items():
+            for entry in entries if isinstance(entries, list) else []:
+                if not matcher(entry):
+                    continue
+                for hook in entry.get("hooks", []):
+                    if isinstance(hook, dict) and hook.get("command"):
+                        found.append(InstalledHook(event, hook["command"], scope, path))
+    return found
+
+
+def synthetic_payload(event: str, cwd: Path, transcript_path: Path) -> dict:
+    """
+    The JSON Claude Code sends a hook on stdin, with harmless values.
+
+    Args:
+        event: Hook event name
+        cwd: Working directory reported to the hook
+        transcript_path: Transcript file reported to the hook
+
+    Returns:
+        Payload with the common fields plus the event's own
+    """
+    payload = {
+        "session_id": TEST_SESSION_ID,
+        "transcript_path": str(transcript_path),
+        "cwd": str(cwd),
+        "hook_event_name": event,
+    }
+    if event in ("Stop", "SubagentStop"):
+        payload["stop_hook_active"] = False
+    elif event == "Notification":
+        payload["message"] = "Claude needs your permission to use Bash"
+    elif event == "PreCompact":
+        payload.update({"trigger": "manual", "custom_instructions": ""})
+    elif event in ("PreToolUse", "PostToolUse"):
+        payload.update({"tool_name": "Bash", "tool_input": {"command": "echo ccg hooks test"}})
+    return payload
+
+
+def run_hook(
+    hook: InstalledHook,
+    payload: dict,
+    cwd: Path,
+    timeout: float = HOOK_TIMEOUT_SECONDS,
+) -> HookRun:
+    """
+    Run a hook command through the shell with its payload on stdin.
+
+    Args:
+        hook: Hook to run
+        payload: JSON payload (see synthetic_payload)
+        cwd: Working directory, also exported as CLAUDE_PROJECT_DIR
+        timeout: Seconds before the run is abandoned
+
+    Returns:
+        HookRun with exit code and captured output
+    """
+    env = {**os.environ, "CLAUDE_PROJECT_DIR": str(cwd)}
+    start = time.monotonic()
+    try:
+        result = subprocess.run(
+            hook.command, shell=True, input=json.dumps(payload), capture_output=True,
+            text=True, cwd=cwd, env=env, timeout=timeout,
+        )
+        exit_code, stdout, stderr = result.returncode, result.stdout, result.stderr
+    except subprocess.TimeoutExpired as e:
+        exit_code = None
+        stdout = e.stdout.decode(errors="replace") if isinstance(e.stdout, bytes) else e.stdout or ""
+        stderr = f"Timed out after {timeout:.0f}s"
+    return HookRun(
+        hook, exit_code, stdout[-OUTPUT_CHARS:], stderr[-OUTPUT_CHARS:], time.monotonic() - start,
+    )
+
+
+#endregion
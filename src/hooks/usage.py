@@ -14,7 +14,7 @@ from src.storage import api
 #region Functions
 
 
-def setup(console: Console, settings: dict, settings_path: Path) -> None:
+def setup(console: Console, settings: dict, settings_path: Path, project_only: bool = False) -> None:
     """
     Set up the usage tracking hook.
 
@@ -22,6 +22,8 @@ def setup(console: Console, settings: dict, settings_path: Path) -> None:
         console: Rich console for output
         settings: Settings dictionary to modify
         settings_path: Path to settings.json file
+        project_only: Scope the hook to the project Claude Code runs in
+            (CLAUDE_PROJECT_DIR), ingesting and tagging only its sessions
     """
     # Check current storage mode
     current_mode = get_storage_mode()
@@ -56,12 +58,19 @@ def setup(console: Console, settings: dict, settings_path: Path) -> None:
 
     # Windows hooks run through cmd.exe: NUL instead of /dev/null, no & backgrounding
     if sys.platform == "win32":
-        hook_command = "ccg update usage >NUL 2>&1"
+        scope = ' --project "%CLAUDE_PROJECT_DIR%"' if project_only else ""
+        hook_command = f"ccg update usage --hook{scope} >NUL 2>&1"
     else:
-        hook_command = "ccg update usage > /dev/null 2>&1 &"
+        scope = ' --project "$CLAUDE_PROJECT_DIR"' if project_only else ""
+        hook_command = f"ccg update usage --hook{scope} > /dev/null 2>&1 &"
 
-    # Check if already exists
+    # Check if already exists; a hook with a different scope gets replaced
     hook_exists = any(is_hook(hook) for hook in settings["hooks"]["Stop"])
+    same_scope = any(
+        h.get("command") == hook_command
+        for hook in settings["hooks"]["Stop"] if is_hook(hook)
+        for h in hook.get("hooks", [])
+    )
 
     # Warn if changing storage modes
     if current_mode != storage_mode and hook_exists:
@@ -90,6 +99,7 @@ def setup(console: Console, settings: dict, settings_path: Path) -> None:
             backup_choice = input().strip().lower()
             if backup_choice in ["yes", "y"]:
                 if db_path.exists():
+                    api.checkpoint_database(db_path)
                     shutil.copy2(db_path, backup_path)
                     console.print(f"[green]✓ Backup created: {backup_path}[/green]")
                     console.print("[dim]To restore: ccg restore usage[/dim]")
@@ -114,11 +124,15 @@ def setup(console: Console, settings: dict, settings_path: Path) -> None:
     # Save storage mode preference
     set_storage_mode(storage_mode)
 
-    if hook_exists:
+    if hook_exists and same_scope:
         console.print("\n[yellow]Usage tracking hook already configured![/yellow]")
         console.print(f"[cyan]Storage mode updated to: {storage_mode}[/cyan]")
         return
 
+    if hook_exists:
+        settings["hooks"]["Stop"] = [hook for hook in settings["hooks"]["Stop"] if not is_hook(hook)]
+        console.print("[cyan]Replaced existing usage tracking hook[/cyan]")
+
     # Add hook
     settings["hooks"]["Stop"].append({
         "matcher": "*",
@@ -131,6 +145,8 @@ def setup(console: Console, settings: dict, settings_path: Path) -> None:
     console.print(f"[green]✓ Successfully configured usage tracking hook ({storage_mode} mode)[/green]")
     console.print("\n[bold]What this does:[/bold]")
     console.print("  • Runs after each Claude response completes")
+    if project_only:
+        console.print("  • Ingests only this project's sessions, tagged with the project root")
     if storage_mode == "aggregate":
         console.print("  • Saves daily usage totals (lightweight)")
     else:
@@ -0,0 +1,278 @@
+#region Imports
+from dataclasses import dataclass
+from typing import Any
+
+#endregion
+
+
+#region Constants
+# Alternate spellings accepted for a field, keyed by the name Claude Code
+# writes. Transcripts re-exported by SDK wrappers and proxies spell
+# top-level fields in snake_case and usage counters in camelCase.
+ENTRY_ALIASES: dict[str, tuple[str, ...]] = {
+    "sessionId": ("session_id",),
+    "gitBranch": ("git_branch",),
+    "requestId": ("request_id",),
+    "isSidechain": ("is_sidechain",),
+    "agentId": ("agent_id",),
+    "isMeta": ("is_meta",),
+    "isApiErrorMessage": ("is_api_error_message",),
+    "leafUuid": ("leaf_uuid",),
+}
+USAGE_ALIASES: dict[str, tuple[str, ...]] = {
+    "input_tokens": ("inputTokens",),
+    "output_tokens": ("outputTokens",),
+    "cache_creation_input_tokens": ("cacheCreationInputTokens",),
+    "cache_read_input_tokens": ("cacheReadInputTokens",),
+    "thinking_tokens": ("thinkingTokens",),
+}
+
+# Entry types whose message is read (others are events or metadata)
+MESSAGE_TYPES = ("user", "assistant")
+
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass(frozen=True, slots=True)
+class TranscriptUsage:
+    """
+    The usage object of one assistant response.
+
+    Attributes:
+        input_tokens: Uncached input tokens
+        output_tokens: Output tokens, thinking included
+        cache_creation_input_tokens: Cache-write tokens (all TTLs)
+        cache_read_input_tokens: Cache-read tokens
+        ephemeral_5m_input_tokens: Cache writes with a 5m TTL, when broken down
+        ephemeral_1h_input_tokens: Cache writes with a 1h TTL, when broken down
+        thinking_tokens: Thinking tokens, when the log reports them
+            (`thinking_tokens` or `output_tokens_details.reasoning_tokens`)
+    """
+
+    input_tokens: int = 0
+    output_tokens: int = 0
+    cache_creation_input_tokens: int = 0
+    cache_read_input_tokens: int = 0
+    ephemeral_5m_input_tokens: int = 0
+    ephemeral_1h_input_tokens: int = 0
+    thinking_tokens: int | None = None
+
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> "TranscriptUsage":
+        """
+        Read a usage object.
+
+        Raises:
+            AttributeError: If data or its cache_creation breakdown is not an object
+        """
+        breakdown = data.get("cache_creation") or {}
+        details = data.get("output_tokens_details") or {}
+        thinking = None
+        for reported in (_field(data, "thinking_tokens", USAGE_ALIASES), details.get("reasoning_tokens")):
+            if isinstance(reported, int) and reported >= 0:
+                thinking = reported
+                break
+        return cls(
+            input_tokens=_field(data, "input_tokens", USAGE_ALIASES) or 0,
+            output_tokens=_field(data, "output_tokens", USAGE_ALIASES) or 0,
+            cache_creation_input_tokens=_field(data, "cache_creation_input_tokens", USAGE_ALIASES) or 0,
+            cache_read_input_tokens=_field(data, "cache_read_input_tokens", USAGE_ALIASES) or 0,
+            ephemeral_5m_input_tokens=breakdown.get("ephemeral_5m_input_tokens") or 0,
+            ephemeral_1h_input_tokens=breakdown.get("ephemeral_1h_input_tokens") or 0,
+            thinking_tokens=thinking,
+        )
+
+
+@dataclass(frozen=True, slots=True)
+class ContentBlock:
+    """
+    One block of a message's content list.
+
+    Attributes:
+        type: Block type ("text", "thinking", "tool_use", "tool_result", "image", ...)
+        text: Text of a text block
+        thinking: Text of a thinking block
+        id: Id of a tool_use block
+        name: Tool name of a tool_use block
+        tool_use_id: Call a tool_result block answers
+        content: Result content of a tool_result block (text or blocks)
+    """
+
+    type: str | None
+    text: str | None = None
+    thinking: str | None = None
+    id: str | None = None
+    name: str | None = None
+    tool_use_id: str | None = None
+    content: Any = None
+
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> "ContentBlock":
+        """Read a content block."""
+        return cls(
+            type=data.get("type"),
+            text=data.get("text"),
+            thinking=data.get("thinking"),
+            id=data.get("id"),
+            name=data.get("name"),
+            tool_use_id=data.get("tool_use_id"),
+            content=data.get("content"),
+        )
+
+
+@dataclass(frozen=True, slots=True)
+class TranscriptMessage:
+    """
+    The API message of a user or assistant entry.
+
+    Attributes:
+        id: API message id (assistant responses)
+        model: Model that produced the response
+        content: String content, or the object blocks of a content list
+            (None when absent or of another shape)
+        usage: Usage of an assistant response, when logged
+    """
+
+    id: str | None = None
+    model: str | None = None
+    content: str | tuple[ContentBlock, ...] | None = None
+    usage: TranscriptUsage | None = None
+
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> "TranscriptMessage":
+        """
+        Read a message object.
+
+        Raises:
+            AttributeError: If data or its usage is not an object
+        """
+        content = data.get("content")
+        if isinstance(content, list):
+            content = tuple(ContentBlock.from_dict(block) for block in content if isinstance(block, dict))
+        elif not isinstance(content, str):
+            content = None
+        usage = data.get("usage")
+        return cls(
+            id=data.get("id"),
+            model=data.get("model"),
+            content=content,
+            usage=TranscriptUsage.from_dict(usage) if usage else None,
+        )
+
+    @property
+    def text(self) -> str:
+        """String content, or the text blocks joined by newlines."""
+        if isinstance(self.content, str):
+            return self.content
+        if self.content:
+            return "\n".join(block.text or "" for block in self.content if block.type == "text")
+        return ""
+
+
+@dataclass(frozen=True, slots=True)
+class TranscriptEntry:
+    """
+    One line of a Claude Code transcript, with field aliases resolved.
+
+    Attributes:
+        type: Entry type ("user", "assistant", "system", "summary", ...)
+        subtype: System entry subtype ("compact_boundary", "api_error", ...)
+        uuid: Transcript entry uuid
+        timestamp: Raw timestamp, validated where it is used
+        session_id: Session the entry was logged under
+        cwd: Working directory
+        git_branch: Git branch, when in a repository
+        version: Claude Code version
+        request_id: API request id of an assistant response
+        is_sidechain: True for subagent entries
+        agent_id: Subagent id, on subagent entries
+        entrypoint: How Claude Code was started, when recorded
+        is_meta: True for entries Claude Code injects rather than the user types
+        is_api_error_message: True for the synthetic message ending a failed turn
+        summary: Text of a summary entry
+        leaf_uuid: Last entry a summary covers
+        message: The API message of user and assistant entries
+        raw: The decoded line, for fields read only by event extraction
+    """
+
+    type: str | None
+    subtype: str | None
+    uuid: str | None
+    timestamp: Any
+    session_id: str
+    cwd: str
+    git_branch: str | None
+    version: str
+    request_id: str | None
+    is_sidechain: bool
+    agent_id: str | None
+    entrypoint: str | None
+    is_meta: bool
+    is_api_error_message: bool
+    summary: Any
+    leaf_uuid: str | None
+    message: TranscriptMessage | None
+    raw: dict[str, Any]
+
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> "TranscriptEntry":
+        """
+        Read a decoded transcript line.
+
+        Raises:
+            AttributeError: If the line, or the message of a user or
+                assistant entry, is not an object
+        """
+        entry_type = data.get("type")
+        message = None
+        if entry_type in MESSAGE_TYPES:
+            message = TranscriptMessage.from_dict(data.get("message") or {})
+        entrypoint = data.get("entrypoint")
+        return cls(
+            type=entry_type,
+            subtype=data.get("subtype"),
+            uuid=data.get("uuid"),
+            timestamp=data.get("timestamp"),
+            session_id=_field(data, "sessionId", ENTRY_ALIASES) or "unknown",
+            cwd=data.get("cwd") or "unknown",
+            git_branch=_field(data, "gitBranch", ENTRY_ALIASES),
+            version=data.get("version") or "unknown",
+            request_id=_field(data, "requestId", ENTRY_ALIASES),
+            is_sidechain=_field(data, "isSidechain", ENTRY_ALIASES) is True,
+            agent_id=_field(data, "agentId", ENTRY_ALIASES),
+            entrypoint=entrypoint if isinstance(entrypoint, str) else None,
+            is_meta=bool(_field(data, "isMeta", ENTRY_ALIASES)),
+            is_api_error_message=bool(_field(data, "isApiErrorMessage", ENTRY_ALIASES)),
+            summary=data.get("summary"),
+            leaf_uuid=_field(data, "leafUuid", ENTRY_ALIASES),
+            message=message,
+            raw=data,
+        )
+
+
+#endregion
+
+
+#region Functions
+
+
+def alias_names(aliases: dict[str, tuple[str, ...]]) -> frozenset[str]:
+    """Every alternate spelling in an alias table."""
+    return frozenset(alias for names in aliases.values() for alias in names)
+
+
+def _field(data: dict[str, Any], name: str, aliases: dict[str, tuple[str, ...]]) -> Any:
+    """A field's value under its own name, else under its first alias present."""
+    value = data.get(name)
+    if value is None:
+        for alias in aliases.get(name, ()):
+            value = data.get(alias)
+            if value is not None:
+                break
+    return value
+
+
+#endregion
@@ -1,6 +1,13 @@
 #region Imports
+from collections import Counter
 from dataclasses import dataclass
-from datetime import datetime
+from datetime import datetime, timedelta
+from typing import TYPE_CHECKING
+
+from src.utils.folders import short_folder
+
+if TYPE_CHECKING:
+    from src.storage.pricing import PricingTable
 
 #endregion
 
@@ -18,6 +25,8 @@ class TokenUsage:
         output_tokens: Number of output tokens
         cache_creation_tokens: Number of tokens written to cache
         cache_read_tokens: Number of tokens read from cache
+        cache_creation_1h_tokens: Part of cache_creation_tokens written with a 1h TTL
+        thinking_tokens: Part of output_tokens spent on extended thinking/reasoning
     """
 
     input_tokens: int
@@ -25,6 +34,7 @@ class TokenUsage:
     cache_creation_tokens: int
     cache_read_tokens: int
     cache_creation_1h_tokens: int = 0
+    thinking_tokens: int = 0
 
     @property
     def total_tokens(self) -> int:
@@ -36,6 +46,40 @@ class TokenUsage:
             + self.cache_read_tokens
         )
 
+    @property
+    def cache_creation_5m_tokens(self) -> int:
+        """Part of cache_creation_tokens written with the default 5m TTL."""
+        return self.cache_creation_tokens - self.cache_creation_1h_tokens
+
+    @property
+    def context_tokens(self) -> int:
+        """Prompt size of the request: input plus cache writes and reads."""
+        return self.input_tokens + self.cache_creation_tokens + self.cache_read_tokens
+
+    def cost(self, pricing: "PricingTable", model: str | None) -> float:
+        """
+        Estimated API cost of this usage in dollars.
+
+        Requests whose prompt exceeds the pricing table's long-context
+        threshold bill at the model's long-context rates.
+
+        Args:
+            pricing: Pricing lookup (storage.api.get_pricing_table)
+            model: Model that produced the usage
+
+        Returns:
+            Cost in dollars (0.0 for unpriced models)
+        """
+        return pricing.cost(
+            model,
+            self.input_tokens,
+            self.output_tokens,
+            self.cache_creation_tokens,
+            self.cache_read_tokens,
+            self.cache_creation_1h_tokens,
+            long_context=self.context_tokens > pricing.long_context_threshold,
+        )
+
 
 @dataclass(frozen=True)
 class UsageRecord:
@@ -54,6 +98,24 @@ class UsageRecord:
         token_usage: Token usage details (None for user messages)
         content: Message content text (for analysis)
         char_count: Character count of message content
+        tool_uses: (tool_use id, tool name) for each tool call in the response
+        tool_results: (tool_use id, result characters) for each tool result
+            a user entry returns to the model
+        attachment_count: Images and files (document blocks) attached to a
+            user prompt
+        is_subagent: True for subagent (sidechain) work delegated by a session.
+            Subagent records keep the spawning session's session_id, so
+            session totals include delegated work
+        agent_id: Subagent that did the work (the transcript's agentId;
+            None for the main conversation or when not logged)
+        entrypoint: How Claude Code was started, when the transcript
+            records it (e.g. "cli", or "sdk-cli" for `claude -p`)
+        hostname: Machine the record was ingested on, when read back from
+            the database (None for freshly parsed records; the saving
+            machine stamps its own)
+        project: Project root the record was scoped to by
+            `ccg update usage --project` (None for unscoped ingests);
+            folder keeps the directory the session actually ran in
     """
 
     timestamp: datetime
@@ -67,6 +129,14 @@ class UsageRecord:
     token_usage: TokenUsage | None
     content: str | None = None
     char_count: int = 0
+    tool_uses: tuple[tuple[str, str], ...] = ()
+    tool_results: tuple[tuple[str, int], ...] = ()
+    attachment_count: int = 0
+    is_subagent: bool = False
+    agent_id: str | None = None
+    entrypoint: str | None = None
+    hostname: str | None = None
+    project: str | None = None
 
     @property
     def date_key(self) -> str:
@@ -85,6 +155,35 @@ class UsageRecord:
         local_timestamp = self.timestamp.astimezone()  # Convert to local timezone
         return local_timestamp.strftime("%Y-%m-%d")
 
+    @property
+    def tool_calls(self) -> tuple[tuple[str, int], ...]:
+        """
+        Tools called in this response as (tool name, count), most used first.
+
+        MCP tools keep their full "mcp__<server>__<tool>" name.
+        """
+        return tuple(Counter(name for _, name in self.tool_uses).most_common())
+
+    @property
+    def mcp_calls(self) -> tuple[tuple[str, str, str], ...]:
+        """
+        MCP tool calls in this response as (tool_use id, server, tool).
+
+        MCP tools are named "mcp__<server>__<tool>"; other tools are skipped.
+        """
+        calls = []
+        for tool_id, name in self.tool_uses:
+            parts = name.split("__", 2)
+            if len(parts) == 3 and parts[0] == "mcp" and parts[1]:
+                calls.append((tool_id, parts[1], parts[2]))
+        return tuple(calls)
+
+    def cost(self, pricing: "PricingTable") -> float:
+        """Estimated API cost of this record in dollars (0.0 without token usage)."""
+        if self.token_usage is None:
+            return 0.0
+        return self.token_usage.cost(pricing, self.model)
+
     @property
     def is_user_prompt(self) -> bool:
         """Check if this is a user prompt message."""
@@ -94,4 +193,124 @@ class UsageRecord:
     def is_assistant_response(self) -> bool:
         """Check if this is an assistant response message."""
         return self.message_type == "assistant"
+
+
+@dataclass(frozen=True)
+class QuarantinedEntry:
+    """
+    A transcript entry held back from ingestion for manual review.
+
+    Attributes:
+        file_path: Transcript the entry came from
+        entry_id: Transcript entry uuid (or a hash of the line when missing)
+        session_id: Session the entry belongs to
+        raw_timestamp: Timestamp exactly as written ("" when missing)
+        reason: Why the entry was quarantined
+        raw_line: The JSONL line (truncated)
+    """
+
+    file_path: str
+    entry_id: str
+    session_id: str
+    raw_timestamp: str
+    reason: str
+    raw_line: str
+
+
+@dataclass(frozen=True)
+class TranscriptSummary:
+    """
+    A summary entry Claude Code writes into a transcript, naming the session
+    (e.g. "Refactor auth middleware").
+
+    Attributes:
+        session_id: Session of the transcript the entry was written to
+        summary: The summary line
+        leaf_uuid: Entry uuid of the last message the summary covers
+    """
+
+    session_id: str
+    summary: str
+    leaf_uuid: str | None = None
+
+
+@dataclass(frozen=True)
+class SessionEvent:
+    """
+    An API error, rate-limit hit, user interruption, or context compaction
+    logged in a session.
+
+    Attributes:
+        event_id: Transcript entry uuid
+        session_id: Session the event belongs to
+        timestamp: When the event occurred
+        event_type: "api_error", "rate_limit", "interrupted", or "compaction"
+        detail: Error status/message, the interruption notice, or the
+            compaction trigger ("auto" or "manual")
+        folder: Project folder path
+        retry_attempt: Claude Code's retry counter for API errors it retries
+        ends_turn: True when the turn failed here (interrupted, or an error
+            shown to the user after retries); False for retried errors
+        turn_tokens: Tokens the turn had used when it failed (0 unless
+            ends_turn)
+        messages_before: For compactions, messages since the session start
+            or the previous compaction (None when the parse began mid-file)
+        context_tokens: For compactions, context size when it was compacted
+    """
+
+    event_id: str
+    session_id: str
+    timestamp: datetime
+    event_type: str
+    detail: str
+    folder: str
+    retry_attempt: int | None = None
+    ends_turn: bool = False
+    turn_tokens: int = 0
+    messages_before: int | None = None
+    context_tokens: int = 0
+
+    @property
+    def date_key(self) -> str:
+        """Local YYYY-MM-DD, matching UsageRecord.date_key."""
+        return self.timestamp.astimezone().strftime("%Y-%m-%d")
+
+
+@dataclass(frozen=True)
+class Session:
+    """
+    One Claude Code session, as kept in the sessions table.
+
+    Attributes:
+        session_id: Session identifier
+        folder: Project folder the session ran in
+        start: Timestamp of the first message
+        end: Timestamp of the last message
+        messages: Prompts plus responses
+        total_tokens: Total tokens across all categories
+        title: Snippet of the first prompt (only stored with store_content)
+        entrypoint: How Claude Code was started, when recorded (see
+            UsageRecord.entrypoint)
+    """
+
+    session_id: str
+    folder: str
+    start: datetime
+    end: datetime
+    messages: int = 0
+    total_tokens: int = 0
+    title: str | None = None
+    entrypoint: str | None = None
+
+    @property
+    def duration(self) -> timedelta:
+        """Time between the first and last message."""
+        return self.end - self.start
+
+    @property
+    def label(self) -> str:
+        """Human-readable name: the title, else project and start time."""
+        if self.title:
+            return self.title
+        return f"{short_folder(self.folder)} {self.start.astimezone():%Y-%m-%d %H:%M}"
 #endregion
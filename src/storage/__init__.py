@@ -6,6 +6,7 @@ migration utilities for switching between formats and adding
 device metadata for cross-device sync.
 """
 #region Imports
+import os
 from pathlib import Path
 from typing import Optional
 
@@ -22,12 +23,54 @@ from src.config.user_config import (
 
 #region Constants
 DEFAULT_USAGE_DIR = Path.home() / ".claude" / "usage"
+
+# Database file for this process; beats the config-derived path, not --profile
+CCG_DB_PATH_ENV = "CCG_DB_PATH"
+#endregion
+
+
+#region State
+# Set by the global --profile option (see set_db_path_override)
+_db_path_override: Path | None = None
 #endregion
 
 
 #region Database Path Functions
 
 
+def set_db_path_override(path: Path | None) -> None:
+    """
+    Point this process at a profile's database instead of the configured one.
+
+    Args:
+        path: Database file, or None to use the device/legacy path again
+    """
+    global _db_path_override
+    _db_path_override = path
+
+
+def profile_db_path(names: list[str], configured: Path | None = None) -> Path:
+    """
+    Database for one profile or a merged selection of profiles.
+
+    A merged selection gets its own database (profile-a+b), filled from the
+    profiles' databases (imports.db.merge_profile_databases) and their
+    transcripts, so merging never writes into the individual profiles'
+    histories.
+
+    Args:
+        names: Selected profile names
+        configured: The profile's db_path setting (single profile only)
+
+    Returns:
+        Path to the database file
+    """
+    if configured is not None and len(names) == 1:
+        return configured
+    ext = ".duckdb" if get_storage_format() == "duckdb" else ".db"
+    return DEFAULT_USAGE_DIR / f"profile-{'+'.join(sorted(names))}{ext}"
+
+
 def get_db_path(
     device_id: str | None = None,
     storage_format: str | None = None,
@@ -42,6 +85,9 @@ def get_db_path(
     When no sync is configured, uses the legacy path:
     - ~/.claude/usage/usage_history.db
 
+    When neither argument is given, a --profile selection
+    (set_db_path_override) wins, then $CCG_DB_PATH.
+
     Args:
         device_id: Device identifier (uses config if not provided)
         storage_format: Storage format - "sqlite" or "duckdb" (uses config if not provided)
@@ -49,6 +95,12 @@ def get_db_path(
     Returns:
         Path to the database file
     """
+    if _db_path_override is not None and device_id is None and storage_format is None:
+        return _db_path_override
+    env_path = os.environ.get(CCG_DB_PATH_ENV, "").strip()
+    if env_path and device_id is None and storage_format is None:
+        return Path(env_path).expanduser()
+
     # Use provided values or fall back to config
     if storage_format is None:
         storage_format = get_storage_format()
@@ -128,6 +180,8 @@ def is_duckdb_mode() -> bool:
 
 __all__ = [
     "get_db_path",
+    "profile_db_path",
+    "set_db_path_override",
     "get_legacy_db_path",
     "ensure_device_initialized",
     "get_backend_module",
@@ -11,7 +11,13 @@ into snapshot_db or duckdb_backend, unless they need backend internals
 (e.g. raw sqlite3 access for one-off scripts).
 """
 #region Imports
+import shutil
+import tempfile
+from collections.abc import Callable, Iterator
+from contextlib import contextmanager
+from datetime import datetime
 from pathlib import Path
+from types import ModuleType
 
 from src.config.user_config import (
     get_device_id as _cfg_device_id,
@@ -22,9 +28,19 @@ from src.config.user_config import (
 from src.config.user_config import (
     get_device_type_config as _cfg_device_type,
 )
-from src.models.usage_record import UsageRecord
+from src.config.user_config import (
+    get_hostname as _cfg_hostname,
+)
+from src.models.usage_record import QuarantinedEntry, Session, SessionEvent, TranscriptSummary, UsageRecord
 from src.storage import get_backend_module, get_db_path
+from src.storage.pricing import PricingTable
+
+#endregion
+
 
+#region Constants
+# Write-ahead log files SQLite (-wal, -shm) and DuckDB (.wal) keep beside the database
+DATABASE_SIDECAR_SUFFIXES = ("-wal", "-shm", ".wal")
 #endregion
 
 
@@ -39,6 +55,14 @@ def current_db_path() -> Path:
     return get_db_path()
 
 
+def _year_range(year: int | None, start_date: str | None, end_date: str | None) -> tuple[str | None, str | None]:
+    """Narrow a date range to one calendar year, so a year filter reaches SQL as dates."""
+    if year is None:
+        return start_date, end_date
+    first, last = f"{year:04d}-01-01", f"{year:04d}-12-31"
+    return max(start_date or first, first), min(end_date or last, last)
+
+
 #endregion
 
 
@@ -55,6 +79,7 @@ def save_snapshot(
     device_id: str | None = None,
     device_name: str | None = None,
     device_type: str | None = None,
+    hostname: str | None = None,
     db: Path | None = None,
 ) -> int:
     return _backend().save_snapshot(
@@ -64,6 +89,7 @@ def save_snapshot(
         device_id=device_id if device_id is not None else _cfg_device_id(),
         device_name=device_name if device_name is not None else _cfg_device_name(),
         device_type=device_type if device_type is not None else _cfg_device_type(),
+        hostname=hostname if hostname is not None else _cfg_hostname(),
     )
 
 
@@ -73,6 +99,7 @@ def save_file_aggregate(
     device_id: str | None = None,
     device_name: str | None = None,
     device_type: str | None = None,
+    hostname: str | None = None,
     db: Path | None = None,
 ) -> int:
     return _backend().save_file_aggregate(
@@ -82,6 +109,51 @@ def save_file_aggregate(
         device_id=device_id if device_id is not None else _cfg_device_id(),
         device_name=device_name if device_name is not None else _cfg_device_name(),
         device_type=device_type if device_type is not None else _cfg_device_type(),
+        hostname=hostname if hostname is not None else _cfg_hostname(),
+    )
+
+
+def upsert_daily_snapshot(
+    date: str,
+    totals: dict[str, int],
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+    hostname: str | None = None,
+    db: Path | None = None,
+    models: dict[str, dict[str, int]] | None = None,
+) -> None:
+    _backend().upsert_daily_snapshot(
+        date,
+        totals,
+        db_path=db or get_db_path(),
+        device_id=device_id if device_id is not None else _cfg_device_id(),
+        device_name=device_name if device_name is not None else _cfg_device_name(),
+        device_type=device_type if device_type is not None else _cfg_device_type(),
+        hostname=hostname if hostname is not None else _cfg_hostname(),
+        models=models,
+    )
+
+
+def increment_daily_snapshot(
+    date: str,
+    delta: dict[str, int],
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+    hostname: str | None = None,
+    db: Path | None = None,
+    models: dict[str, dict[str, int]] | None = None,
+) -> None:
+    _backend().increment_daily_snapshot(
+        date,
+        delta,
+        db_path=db or get_db_path(),
+        device_id=device_id if device_id is not None else _cfg_device_id(),
+        device_name=device_name if device_name is not None else _cfg_device_name(),
+        device_type=device_type if device_type is not None else _cfg_device_type(),
+        hostname=hostname if hostname is not None else _cfg_hostname(),
+        models=models,
     )
 
 
@@ -89,8 +161,175 @@ def load_historical_records(
     start_date: str | None = None,
     end_date: str | None = None,
     db: Path | None = None,
+    folder: str | None = None,
+    model: str | None = None,
+    limit: int | None = None,
+    offset: int = 0,
+    year: int | None = None,
 ) -> list[UsageRecord]:
-    return _backend().load_historical_records(start_date, end_date, db_path=db or get_db_path())
+    start_date, end_date = _year_range(year, start_date, end_date)
+    return _backend().load_historical_records(
+        start_date, end_date, db_path=db or get_db_path(),
+        folder=folder, model=model, limit=limit, offset=offset,
+    )
+
+
+def iter_historical_records(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    folder: str | None = None,
+    model: str | None = None,
+    limit: int | None = None,
+    offset: int = 0,
+    db: Path | None = None,
+    year: int | None = None,
+) -> Iterator[UsageRecord]:
+    start_date, end_date = _year_range(year, start_date, end_date)
+    return _backend().iter_historical_records(
+        start_date, end_date, folder=folder, model=model,
+        limit=limit, offset=offset, db_path=db or get_db_path(),
+    )
+
+
+class StoredRecords:
+    """
+    Stored usage records that can be walked more than once without being held in memory.
+
+    Each pass streams a fresh iter_historical_records query. Aggregate-mode
+    databases have no usage_records rows, so a pass that streams nothing
+    falls back to the per-day synthetic records load_historical_records
+    builds from daily_snapshots.
+    """
+
+    def __init__(
+        self,
+        start_date: str | None = None,
+        end_date: str | None = None,
+        db: Path | None = None,
+        transform: Callable[[UsageRecord], UsageRecord] | None = None,
+    ) -> None:
+        self.start_date = start_date
+        self.end_date = end_date
+        self.db = db
+        self.transform = transform
+
+    def __iter__(self) -> Iterator[UsageRecord]:
+        streamed = False
+        for record in iter_historical_records(self.start_date, self.end_date, db=self.db):
+            streamed = True
+            yield self.transform(record) if self.transform else record
+        if not streamed:
+            for record in load_historical_records(self.start_date, self.end_date, db=self.db):
+                yield self.transform(record) if self.transform else record
+
+    def __bool__(self) -> bool:
+        return next(iter(self), None) is not None
+
+    def map(self, transform: Callable[[UsageRecord], UsageRecord]) -> "StoredRecords":
+        """The same records with transform applied to each as it is streamed."""
+        inner = self.transform
+        return StoredRecords(
+            self.start_date, self.end_date, self.db,
+            transform=(lambda record: transform(inner(record))) if inner else transform,
+        )
+
+
+def load_daily_snapshots(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db: Path | None = None,
+) -> list[dict]:
+    return _backend().load_daily_snapshots(start_date, end_date, db_path=db or get_db_path())
+
+
+def get_project_activity(db: Path | None = None) -> list[dict]:
+    return _backend().get_project_activity(db or get_db_path())
+
+
+def get_project_tokens(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db: Path | None = None,
+) -> dict[str, int]:
+    return _backend().get_project_tokens(start_date, end_date, db_path=db or get_db_path())
+
+
+def get_daily_projects(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db: Path | None = None,
+) -> dict[str, dict[str, dict]]:
+    return _backend().get_daily_projects(start_date, end_date, db_path=db or get_db_path())
+
+
+def get_branch_stats(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db: Path | None = None,
+) -> list[dict]:
+    return _backend().get_branch_stats(start_date, end_date, db_path=db or get_db_path())
+
+
+def get_host_stats(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db: Path | None = None,
+) -> list[dict]:
+    return _backend().get_host_stats(start_date, end_date, db_path=db or get_db_path())
+
+
+def get_hourly_snapshots(hours: int = 24, now: datetime | None = None, db: Path | None = None) -> list[dict]:
+    return _backend().get_hourly_snapshots(hours, now, db_path=db or get_db_path())
+
+
+def get_hourly_range(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db: Path | None = None,
+) -> list[dict]:
+    return _backend().get_hourly_range(start_date, end_date, db_path=db or get_db_path())
+
+
+def preview_ingest(
+    files: dict[str, list[UsageRecord]],
+    storage_mode: str,
+    db: Path | None = None,
+) -> dict:
+    return _backend().preview_ingest(files, storage_mode, db_path=db or get_db_path())
+
+
+def save_monthly_summary(month: str, summary: dict, db: Path | None = None) -> None:
+    _backend().save_monthly_summary(month, summary, db_path=db or get_db_path())
+
+
+def get_monthly_summary(month: str, db: Path | None = None) -> dict | None:
+    return _backend().get_monthly_summary(month, db_path=db or get_db_path())
+
+
+def add_session_tags(session_id: str, tags: list[str], db: Path | None = None) -> None:
+    _backend().add_session_tags(session_id, tags, db_path=db or get_db_path())
+
+
+def remove_session_tags(session_id: str, tags: list[str] | None = None, db: Path | None = None) -> int:
+    return _backend().remove_session_tags(session_id, tags, db_path=db or get_db_path())
+
+
+def get_session_tags(db: Path | None = None) -> dict[str, list[str]]:
+    return _backend().get_session_tags(db_path=db or get_db_path())
+
+
+def add_annotation(date: str, label: str, db: Path | None = None) -> bool:
+    return _backend().add_annotation(date, label, db_path=db or get_db_path())
+
+
+def remove_annotations(date: str, label: str | None = None, db: Path | None = None) -> int:
+    return _backend().remove_annotations(date, label, db_path=db or get_db_path())
+
+
+def get_annotations(
+    start_date: str | None = None, end_date: str | None = None, db: Path | None = None
+) -> dict[str, list[str]]:
+    return _backend().get_annotations(start_date, end_date, db_path=db or get_db_path())
 
 
 def get_database_stats(db: Path | None = None) -> dict:
@@ -112,10 +351,161 @@ def update_files_metadata(
     )
 
 
+def clone_model_pricing(model_name: str, like_model: str, db: Path | None = None) -> str:
+    return _backend().clone_model_pricing(model_name, like_model, db_path=db or get_db_path())
+
+
+def sync_model_pricing(db: Path | None = None) -> int:
+    return _backend().sync_model_pricing(db or get_db_path())
+
+
+def get_model_pricing(db: Path | None = None) -> list[tuple]:
+    return _backend().get_model_pricing(db or get_db_path())
+
+
+def get_pricing_table(db: Path | None = None) -> PricingTable:
+    """Pricing lookup for UsageRecord.cost, built from get_model_pricing."""
+    return PricingTable(get_model_pricing(db))
+
+
+def get_database_health(db: Path | None = None) -> dict:
+    return _backend().get_database_health(db or get_db_path())
+
+
 def get_update_coverage(db: Path | None = None) -> dict:
     return _backend().get_update_coverage(db or get_db_path())
 
 
+def record_update_run(
+    started_at: str,
+    finished_at: str,
+    trigger: str,
+    exit_status: int,
+    new_records: int = 0,
+    error: str | None = None,
+    db: Path | None = None,
+) -> None:
+    _backend().record_update_run(
+        started_at, finished_at, trigger, exit_status,
+        new_records=new_records, error=error, db_path=db or get_db_path(),
+    )
+
+
+def record_full_ingest(finished_at: str, db: Path | None = None) -> None:
+    _backend().record_full_ingest(finished_at, db_path=db or get_db_path())
+
+
+def get_last_full_ingest(db: Path | None = None) -> str | None:
+    return _backend().get_last_full_ingest(db_path=db or get_db_path())
+
+
+def set_ingest_status(key: str, value: str, db: Path | None = None) -> None:
+    _backend().set_ingest_status(key, value, db_path=db or get_db_path())
+
+
+def get_ingest_status(key: str, db: Path | None = None) -> str | None:
+    return _backend().get_ingest_status(key, db_path=db or get_db_path())
+
+
+def get_update_runs(trigger: str | None = None, limit: int = 20, db: Path | None = None) -> list[dict]:
+    return _backend().get_update_runs(trigger=trigger, limit=limit, db_path=db or get_db_path())
+
+
+def get_ingestion_state(file_paths: list[str], db: Path | None = None) -> dict[str, tuple[int, int, str]]:
+    return _backend().get_ingestion_state(file_paths, db_path=db or get_db_path())
+
+
+def update_ingestion_state(entries: list[tuple[str, int, int, str]], db: Path | None = None) -> None:
+    _backend().update_ingestion_state(entries, db_path=db or get_db_path())
+
+
+def save_quarantined_entries(entries: list[QuarantinedEntry], db: Path | None = None) -> int:
+    return _backend().save_quarantined_entries(entries, db_path=db or get_db_path())
+
+
+def get_quarantined_entries(limit: int | None = None, db: Path | None = None) -> list[dict]:
+    return _backend().get_quarantined_entries(limit=limit, db_path=db or get_db_path())
+
+
+def count_quarantined_entries(db: Path | None = None) -> int:
+    return _backend().count_quarantined_entries(db_path=db or get_db_path())
+
+
+def dismiss_quarantined_entries(keys: list[tuple[str, str]], db: Path | None = None) -> int:
+    return _backend().dismiss_quarantined_entries(keys, db_path=db or get_db_path())
+
+
+def save_unknown_fields(counts: dict[str, int], db: Path | None = None) -> None:
+    _backend().save_unknown_fields(counts, db_path=db or get_db_path())
+
+
+def get_unknown_fields(db: Path | None = None) -> list[dict]:
+    return _backend().get_unknown_fields(db_path=db or get_db_path())
+
+
+def save_session_events(events: list[SessionEvent], db: Path | None = None) -> int:
+    return _backend().save_session_events(events, db_path=db or get_db_path())
+
+
+def get_session_events(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db: Path | None = None,
+) -> list[dict]:
+    return _backend().get_session_events(start_date=start_date, end_date=end_date, db_path=db or get_db_path())
+
+
+def save_mcp_calls(records: list[UsageRecord], db: Path | None = None) -> int:
+    return _backend().save_mcp_calls(records, db_path=db or get_db_path())
+
+
+def get_mcp_calls(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db: Path | None = None,
+) -> list[dict]:
+    return _backend().get_mcp_calls(start_date=start_date, end_date=end_date, db_path=db or get_db_path())
+
+
+def save_tool_usage(records: list[UsageRecord], db: Path | None = None) -> int:
+    return _backend().save_tool_usage(records, db_path=db or get_db_path())
+
+
+def get_tool_usage(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db: Path | None = None,
+) -> list[dict]:
+    return _backend().get_tool_usage(start_date=start_date, end_date=end_date, db_path=db or get_db_path())
+
+
+def save_sessions(sessions: list[Session], db: Path | None = None) -> int:
+    return _backend().save_sessions(sessions, db_path=db or get_db_path())
+
+
+def get_sessions(session_ids: list[str] | None = None, db: Path | None = None) -> list[Session]:
+    return _backend().get_sessions(session_ids, db_path=db or get_db_path())
+
+
+def save_session_summaries(summaries: list[TranscriptSummary], db: Path | None = None) -> int:
+    return _backend().save_session_summaries(summaries, db_path=db or get_db_path())
+
+
+def get_session_summaries(session_ids: list[str] | None = None, db: Path | None = None) -> dict[str, str]:
+    return _backend().get_session_summaries(session_ids, db_path=db or get_db_path())
+
+
+def save_message_content(
+    records: list[UsageRecord],
+    max_record_bytes: int,
+    max_total_bytes: int,
+    db: Path | None = None,
+) -> int:
+    return _backend().save_message_content(
+        records, max_record_bytes, max_total_bytes, db_path=db or get_db_path()
+    )
+
+
 def remove_deleted_file_metadata(deleted_paths: list[str], db: Path | None = None) -> None:
     _backend().remove_deleted_file_metadata(deleted_paths, db_path=db or get_db_path())
 
@@ -127,8 +517,82 @@ def get_text_analysis_stats() -> dict:
     return _impl()
 
 
+def recompute_daily_snapshots(dates: list[str], db: Path | None = None) -> None:
+    _backend().recompute_daily_snapshots(dates, db_path=db or get_db_path())
+
+
+def get_record_dates(db: Path | None = None) -> list[str]:
+    return _backend().get_record_dates(db_path=db or get_db_path())
+
+
+def verify_daily_snapshots(db: Path | None = None) -> list[dict]:
+    return _backend().verify_daily_snapshots(db_path=db or get_db_path())
+
+
 def fill_empty_daily_snapshots(start_date: str, end_date: str, db: Path | None = None) -> int:
     return _backend().fill_empty_daily_snapshots(start_date, end_date, db_path=db or get_db_path())
 
 
+def checkpoint_database(db: Path | None = None) -> None:
+    _backend().checkpoint_database(db or get_db_path())
+
+
+def storage_handle(db: Path | None = None):
+    """
+    Share one database connection across the storage calls in a with block.
+
+    The CLI opens one per command (see cli.main_callback).
+    """
+    return _backend().storage_handle(db or get_db_path())
+
+
+def release_storage_handle(db: Path | None = None) -> None:
+    """Close the shared connection to a database, if one is open."""
+    _backend().release_storage_handle(db or get_db_path())
+
+
+def remove_database_files(db: Path | None = None) -> None:
+    """
+    Delete the database file and its write-ahead log files.
+
+    A log left behind would be replayed into the next database created at
+    the same path. A shared connection to it is closed first.
+    """
+    db_path = db or get_db_path()
+    _backend().release_storage_handle(db_path)
+    for path in (db_path, *(db_path.with_name(db_path.name + suffix) for suffix in DATABASE_SIDECAR_SUFFIXES)):
+        path.unlink(missing_ok=True)
+
+
+@contextmanager
+def database_copy(path: Path) -> Iterator[tuple[ModuleType, Path]]:
+    """
+    Read another database file through a temporary copy.
+
+    Opening a database runs init_database, which migrates files written by
+    older releases; reading a copy leaves backups and other machines'
+    databases untouched. The backend follows the file's extension, not the
+    configured storage_format.
+
+    Args:
+        path: Database file (.duckdb for DuckDB, anything else SQLite)
+
+    Yields:
+        (backend module, path of the copy); the copy is deleted on exit
+    """
+    if path.suffix == ".duckdb":
+        from src.storage import duckdb_backend as backend
+    else:
+        from src.storage import snapshot_db as backend
+
+    with tempfile.TemporaryDirectory() as tmp:
+        copy = Path(tmp) / path.name
+        shutil.copy2(path, copy)
+        for suffix in DATABASE_SIDECAR_SUFFIXES:
+            sidecar = path.with_name(path.name + suffix)
+            if sidecar.exists():
+                shutil.copy2(sidecar, copy.with_name(copy.name + suffix))
+        yield backend, copy
+
+
 #endregion
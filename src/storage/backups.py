@@ -0,0 +1,169 @@
+"""
+Timestamped database backups.
+
+Commands that replace or rewrite the usage database (remove, restore,
+import, rebuild) first copy it to ~/.claude/usage/backups/ under a name
+carrying the time and the reason. The newest "backup_keep" backups of each
+database are kept; `ccg restore usage` lists them and restores any one.
+"""
+#region Imports
+import re
+import shutil
+from dataclasses import dataclass
+from datetime import datetime
+from pathlib import Path
+
+from src.config.user_config import get_backup_keep
+from src.storage import DEFAULT_USAGE_DIR, api
+
+#endregion
+
+
+#region Constants
+BACKUP_DIR = DEFAULT_USAGE_DIR / "backups"
+
+# Timestamp in backup names: <db stem>-<stamp>-<reason><db suffix>
+BACKUP_STAMP_FORMAT = "%Y%m%d-%H%M%S"
+_BACKUP_NAME = re.compile(r"^(?P<stem>.+)-(?P<stamp>\d{8}-\d{6})-(?P<reason>[a-z-]+)$")
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class Backup:
+    """
+    One backup of a database.
+
+    Attributes:
+        path: Backup file
+        created: When it was taken
+        reason: Operation it was taken before ("remove", "import", ...),
+            or "legacy" for a single <db>.bak from older releases
+    """
+
+    path: Path
+    created: datetime
+    reason: str
+
+    @property
+    def size(self) -> int:
+        return self.path.stat().st_size
+
+
+#endregion
+
+
+#region Functions
+
+
+def list_backups(db_path: Path | None = None, backup_dir: Path = BACKUP_DIR) -> list[Backup]:
+    """
+    Backups of a database, newest first.
+
+    Includes the single <db>.bak that older releases wrote next to the
+    database, so it stays restorable.
+
+    Args:
+        db_path: Database the backups were taken of (default: current)
+        backup_dir: Directory holding timestamped backups
+
+    Returns:
+        Backups sorted newest first
+    """
+    db_path = db_path or api.current_db_path()
+    backups = []
+    if backup_dir.is_dir():
+        for path in backup_dir.glob(f"{db_path.stem}-*{db_path.suffix}"):
+            match = _BACKUP_NAME.match(path.name[:len(path.name) - len(db_path.suffix)])
+            if match is None or match["stem"] != db_path.stem:
+                continue
+            created = datetime.strptime(match["stamp"], BACKUP_STAMP_FORMAT)
+            backups.append(Backup(path=path, created=created, reason=match["reason"]))
+
+    legacy = db_path.with_name(f"{db_path.name}.bak")
+    if legacy.is_file():
+        backups.append(Backup(path=legacy, created=datetime.fromtimestamp(legacy.stat().st_mtime), reason="legacy"))
+    # Backups taken within the same second order by when the copy was made
+    return sorted(backups, key=lambda backup: (backup.created, backup.path.stat().st_ctime), reverse=True)
+
+
+def rotate_backups(db_path: Path | None = None, keep: int | None = None, backup_dir: Path = BACKUP_DIR) -> list[Path]:
+    """
+    Delete a database's oldest timestamped backups beyond keep.
+
+    The legacy <db>.bak is never deleted.
+
+    Args:
+        db_path: Database the backups were taken of (default: current)
+        keep: Backups to keep (default: the "backup_keep" config key)
+        backup_dir: Directory holding timestamped backups
+
+    Returns:
+        Deleted backup files
+    """
+    keep = keep or get_backup_keep()
+    timestamped = [backup for backup in list_backups(db_path, backup_dir) if backup.reason != "legacy"]
+    removed = []
+    for backup in timestamped[keep:]:
+        backup.path.unlink(missing_ok=True)
+        removed.append(backup.path)
+    return removed
+
+
+def create_backup(
+    reason: str,
+    db_path: Path | None = None,
+    backup_dir: Path = BACKUP_DIR,
+    rotate: bool = True,
+) -> Path | None:
+    """
+    Copy a database into the backup directory before changing it.
+
+    The write-ahead log is checkpointed first, so the copy holds every
+    commit on its own.
+
+    Args:
+        reason: Operation about to run, lowercase with hyphens ("pre-restore")
+        db_path: Database to back up (default: current)
+        backup_dir: Directory for timestamped backups
+        rotate: Delete the oldest backups beyond "backup_keep" afterwards
+
+    Returns:
+        The backup file, or None if the database does not exist
+    """
+    db_path = db_path or api.current_db_path()
+    if not db_path.exists():
+        return None
+
+    backup_dir.mkdir(parents=True, exist_ok=True)
+    stamp = datetime.now().strftime(BACKUP_STAMP_FORMAT)
+    backup = backup_dir / f"{db_path.stem}-{stamp}-{reason}{db_path.suffix}"
+    api.checkpoint_database(db_path)
+    shutil.copy2(db_path, backup)
+    if rotate:
+        rotate_backups(db_path, backup_dir=backup_dir)
+    return backup
+
+
+def resolve_backup(choice: str, db_path: Path | None = None, backup_dir: Path = BACKUP_DIR) -> Path:
+    """
+    Backup file for a --from value: an index from list_backups (1 = newest) or a path.
+
+    Raises:
+        ValueError: If the index is out of range or the file does not exist
+    """
+    if choice.isdigit():
+        backups = list_backups(db_path, backup_dir)
+        index = int(choice)
+        if not 1 <= index <= len(backups):
+            raise ValueError(f"No backup #{index} ({len(backups)} available; see ccg restore usage --list)")
+        return backups[index - 1].path
+    path = Path(choice).expanduser()
+    if not path.is_file():
+        raise ValueError(f"Backup not found: {path}")
+    return path
+
+
+#endregion
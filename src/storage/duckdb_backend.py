@@ -5,7 +5,10 @@ Mirrors the snapshot_db.py API but uses DuckDB for storage.
 Required for MotherDuck cloud sync and analytical queries.
 """
 #region Imports
-from datetime import datetime
+import bisect
+from collections.abc import Iterator
+from contextlib import contextmanager
+from datetime import datetime, timedelta, timezone
 from pathlib import Path
 
 try:
@@ -14,7 +17,23 @@ try:
 except ImportError:
     DUCKDB_AVAILABLE = False
 
-from src.models.usage_record import TokenUsage, UsageRecord
+from src.config.user_config import is_read_only
+from src.errors import DatabaseLockedError
+from src.models.usage_record import (
+    QuarantinedEntry,
+    Session,
+    SessionEvent,
+    TokenUsage,
+    TranscriptSummary,
+    UsageRecord,
+)
+from src.storage.pricing import (
+    LONG_CONTEXT_SQL,
+    match_pricing_model,
+    price_grouped_model_usage,
+    price_model_usage,
+)
+from src.storage.pricing_remote import with_remote_pricing
 
 #endregion
 
@@ -26,17 +45,78 @@ DEFAULT_DB_PATH = Path.home() / ".claude" / "usage" / "usage_history.duckdb"
 # write path; the DDL + pricing seed cost is worth paying once per process,
 # not once per call.
 _INITIALIZED_DBS: set[str] = set()
+
+# Charged API rates (console.anthropic.com): cache writes 1.25x (5m) /
+# 2x (1h) base input, cache reads 0.1x. Sonnet 5 carries introductory
+# pricing until 2026-08-31 - revert to 3.00/15.00/3.75/6.00/0.30 after.
+BUNDLED_PRICING_ROWS = [
+    ('claude-fable-5', 10.00, 50.00, 12.50, 1.00, 20.00, 'Claude Fable 5'),
+    ('claude-opus-4-8', 5.00, 25.00, 6.25, 0.50, 10.00, 'Claude Opus 4.8'),
+    ('claude-opus-4-7', 5.00, 25.00, 6.25, 0.50, 10.00, 'Claude Opus 4.7'),
+    ('claude-opus-4-6', 5.00, 25.00, 6.25, 0.50, 10.00, 'Claude Opus 4.6'),
+    ('claude-sonnet-5', 2.00, 10.00, 2.50, 0.20, 4.00, 'Claude Sonnet 5 (intro pricing until 2026-08-31)'),
+    ('claude-sonnet-4-6', 3.00, 15.00, 3.75, 0.30, 6.00, 'Claude Sonnet 4.6'),
+    ('claude-haiku-4-5', 1.00, 5.00, 1.25, 0.10, 2.00, 'Claude Haiku 4.5'),
+    ('claude-opus-4-5-20251101', 15.00, 75.00, 18.75, 1.50, 30.00, 'Claude Opus 4.5'),
+    ('claude-opus-4-1-20250805', 15.00, 75.00, 18.75, 1.50, 30.00, 'Claude Opus 4.1'),
+    ('claude-sonnet-4-5-20250929', 3.00, 15.00, 3.75, 0.30, 6.00, 'Claude Sonnet 4.5'),
+    ('claude-haiku-4-5-20251001', 1.00, 5.00, 1.25, 0.10, 2.00, 'Claude Haiku 4.5'),
+    ('claude-haiku-3-5-20241022', 0.80, 4.00, 1.00, 0.08, 1.60, 'Claude 3.5 Haiku'),
+    ('claude-sonnet-4-20250514', 3.00, 15.00, 3.75, 0.30, 6.00, 'Legacy Sonnet 4'),
+    ('claude-opus-4-20250514', 15.00, 75.00, 18.75, 1.50, 30.00, 'Legacy Opus 4'),
+    ('claude-sonnet-3-7-20250219', 3.00, 15.00, 3.75, 0.30, 6.00, 'Legacy Sonnet 3.7'),
+    ('gpt-5.5', 5.00, 30.00, 0.00, 0.5, 0.00, 'OpenAI GPT-5.5 (Codex)'),
+    ('gpt-5.6-sol', 5.00, 30.00, 0.00, 0.5, 0.00, 'OpenAI GPT-5.6 Sol (Codex)'),
+    ('gpt-5.6-terra', 2.50, 15.00, 0.00, 0.25, 0.00, 'OpenAI GPT-5.6 Terra (Codex)'),
+    ('gpt-5.3-codex', 1.75, 14.00, 0.00, 0.175, 0.00, 'OpenAI GPT-5.3 Codex'),
+    ('gpt-5.3-codex-spark', 1.75, 14.00, 0.00, 0.175, 0.00, 'OpenAI GPT-5.3 Codex Spark'),
+    ('gpt-5-codex', 1.25, 10.00, 0.00, 0.125, 0.00, 'OpenAI GPT-5 Codex'),
+    ('gemini-3-pro-preview', 2.00, 12.00, 0.00, 0.2, 0.00, 'Google Gemini 3 Pro Preview'),
+    ('gemini-2.5-pro', 1.25, 10.00, 0.00, 0.125, 0.00, 'Google Gemini 2.5 Pro'),
+    ('gemini-2.5-flash', 0.30, 2.50, 0.00, 0.03, 0.00, 'Google Gemini 2.5 Flash'),
+    ('<synthetic>', 0.00, 0.00, 0.00, 0.00, 0.00, 'Test/synthetic model'),
+]
 #endregion
 
 
 #region Utility Functions
 
 
+def _sync_model_pricing(conn) -> None:
+    """Write BUNDLED_PRICING_ROWS, with fetched remote rates on top, to model_pricing."""
+    timestamp = datetime.now().isoformat()
+    for model_name, input_price, output_price, cache_write, cache_read, cache_write_1h, notes in (
+        with_remote_pricing(BUNDLED_PRICING_ROWS)
+    ):
+        conn.execute("""
+            INSERT OR REPLACE INTO model_pricing (
+                model_name, input_price_per_mtok, output_price_per_mtok,
+                cache_write_price_per_mtok, cache_read_price_per_mtok,
+                cache_write_1h_price_per_mtok, last_updated, notes
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        """, [model_name, input_price, output_price, cache_write, cache_read, cache_write_1h, timestamp, notes])
+
+
 def is_duckdb_available() -> bool:
     """Check if DuckDB is installed."""
     return DUCKDB_AVAILABLE
 
 
+def _connect(db_path: Path, read_only: bool = False):
+    """
+    Open the database; read-only mode (see is_read_only) forces read_only.
+
+    Raises:
+        DatabaseLockedError: If another process holds the file's write lock
+    """
+    try:
+        return duckdb.connect(str(db_path), read_only=read_only or is_read_only())
+    except duckdb.IOException as e:
+        if "lock" not in str(e).lower():
+            raise
+        raise DatabaseLockedError(db_path, str(e)) from e
+
+
 def require_duckdb() -> None:
     """Raise an error if DuckDB is not available."""
     if not DUCKDB_AVAILABLE:
@@ -62,6 +142,24 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
     - limits_snapshots: Usage limits history
     - file_metadata: JSONL file tracking for incremental parsing
     - model_pricing: Model pricing information
+    - daily_model_snapshots: Per-day token splits by model
+    - hourly_snapshots: Per-UTC-hour totals for intraday analysis
+    - update_runs: Timestamp and exit status of each `ccg update usage` run
+    - ingest_status: When the last ingest of every source finished
+    - ingestion_state: Byte offset parsed so far in each transcript
+    - quarantined_entries: Entries with bad timestamps awaiting review
+    - unknown_fields: Transcript fields the parser doesn't know, with counts
+    - tool_usage: One row per tool call, counted per date/session/tool
+    - message_content: Opt-in message text, size-capped
+    - monthly_summaries: Recap facts for finished months
+    - session_tags: User labels on sessions
+    - annotations: Dated milestone labels marked on exported heatmaps
+    - session_events: API errors, rate-limit hits, interruptions, and
+      context compactions
+    - mcp_calls: MCP tool invocations with their estimated token traffic
+    - sessions: Per-session metadata (project, start/end, messages, title)
+
+    In read-only mode nothing is created or migrated; the file is read as is.
 
     Args:
         db_path: Path to the DuckDB database file
@@ -72,12 +170,12 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
     """
     require_duckdb()
 
-    if str(db_path) in _INITIALIZED_DBS and db_path.exists():
+    if is_read_only() or (str(db_path) in _INITIALIZED_DBS and db_path.exists()):
         return
 
     db_path.parent.mkdir(parents=True, exist_ok=True)
 
-    conn = duckdb.connect(str(db_path))
+    conn = _connect(db_path)
     try:
         # Table for daily aggregated snapshots
         conn.execute("""
@@ -94,7 +192,8 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
                 snapshot_timestamp VARCHAR NOT NULL,
                 device_id VARCHAR,
                 device_name VARCHAR,
-                device_type VARCHAR
+                device_type VARCHAR,
+                hostname VARCHAR
             )
         """)
 
@@ -117,9 +216,16 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
                 cache_read_tokens INTEGER NOT NULL,
                 total_tokens INTEGER NOT NULL,
                 cache_creation_1h_tokens INTEGER DEFAULT 0,
+                thinking_tokens INTEGER DEFAULT 0,
+                is_subagent INTEGER DEFAULT 0,
+                parent_session_id VARCHAR,
+                attachment_count INTEGER DEFAULT 0,
                 device_id VARCHAR,
                 device_name VARCHAR,
                 device_type VARCHAR,
+                hostname VARCHAR,
+                project VARCHAR,
+                agent_id VARCHAR,
                 UNIQUE(session_id, message_uuid)
             )
         """)
@@ -131,6 +237,30 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
             "cache_creation_1h_tokens INTEGER DEFAULT 0"
         )
 
+        # Thinking/reasoning share of output_tokens (not added to totals)
+        conn.execute(
+            "ALTER TABLE usage_records ADD COLUMN IF NOT EXISTS "
+            "thinking_tokens INTEGER DEFAULT 0"
+        )
+
+        # Subagent (sidechain) work and the session that delegated it
+        conn.execute("ALTER TABLE usage_records ADD COLUMN IF NOT EXISTS is_subagent INTEGER DEFAULT 0")
+        conn.execute("ALTER TABLE usage_records ADD COLUMN IF NOT EXISTS parent_session_id VARCHAR")
+
+        # Images and files attached to user prompts
+        conn.execute("ALTER TABLE usage_records ADD COLUMN IF NOT EXISTS attachment_count INTEGER DEFAULT 0")
+
+        # Machine each record was ingested on, for merged multi-host histories
+        conn.execute("ALTER TABLE usage_records ADD COLUMN IF NOT EXISTS hostname VARCHAR")
+        conn.execute("ALTER TABLE daily_snapshots ADD COLUMN IF NOT EXISTS hostname VARCHAR")
+
+        # Project root of records ingested with `ccg update usage --project`
+        conn.execute("ALTER TABLE usage_records ADD COLUMN IF NOT EXISTS project VARCHAR")
+
+        # Subagent behind sidechain records (parent_session_id above only
+        # ever repeated session_id and is no longer written)
+        conn.execute("ALTER TABLE usage_records ADD COLUMN IF NOT EXISTS agent_id VARCHAR")
+
         # Create sequence for auto-increment if not exists
         conn.execute("""
             CREATE SEQUENCE IF NOT EXISTS usage_records_id_seq START 1
@@ -172,6 +302,177 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
             )
         """)
 
+        # One row per `ccg update usage` run (hook health in `ccg doctor`)
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS update_runs (
+                started_at VARCHAR PRIMARY KEY,
+                finished_at VARCHAR NOT NULL,
+                trigger VARCHAR NOT NULL,
+                exit_status INTEGER NOT NULL,
+                new_records INTEGER NOT NULL,
+                error VARCHAR
+            )
+        """)
+
+        # Finish time of the last ingest over every source (--fast staleness)
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS ingest_status (
+                key VARCHAR PRIMARY KEY,
+                value VARCHAR NOT NULL
+            )
+        """)
+
+        # Byte offset reached in each transcript (incremental full-mode parsing)
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS ingestion_state (
+                file_path VARCHAR PRIMARY KEY,
+                size_bytes BIGINT NOT NULL,
+                byte_offset BIGINT NOT NULL,
+                head_digest VARCHAR NOT NULL,
+                updated_at VARCHAR NOT NULL
+            )
+        """)
+
+        # Recap facts for finished months (`ccg recap`)
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS monthly_summaries (
+                month VARCHAR PRIMARY KEY,
+                generated_at VARCHAR NOT NULL,
+                summary VARCHAR NOT NULL
+            )
+        """)
+
+        # Free-form labels attached to sessions (`ccg sessions tag`)
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS session_tags (
+                session_id VARCHAR NOT NULL,
+                tag VARCHAR NOT NULL,
+                created_at VARCHAR NOT NULL,
+                PRIMARY KEY (session_id, tag)
+            )
+        """)
+
+        # Dated milestones ("launched v2") marked on exported heatmaps
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS annotations (
+                date VARCHAR NOT NULL,
+                label VARCHAR NOT NULL,
+                created_at VARCHAR NOT NULL,
+                PRIMARY KEY (date, label)
+            )
+        """)
+
+        # Transcript entries with bad timestamps, held for `ccg doctor --quarantine`
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS quarantined_entries (
+                file_path VARCHAR NOT NULL,
+                entry_id VARCHAR NOT NULL,
+                session_id VARCHAR NOT NULL,
+                raw_timestamp VARCHAR NOT NULL,
+                reason VARCHAR NOT NULL,
+                raw_line VARCHAR NOT NULL,
+                quarantined_at VARCHAR NOT NULL,
+                dismissed BOOLEAN NOT NULL DEFAULT FALSE,
+                PRIMARY KEY (file_path, entry_id)
+            )
+        """)
+
+        # Transcript fields the parser doesn't know, reported by `ccg doctor`
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS unknown_fields (
+                field VARCHAR PRIMARY KEY,
+                occurrences BIGINT NOT NULL,
+                first_seen VARCHAR NOT NULL,
+                last_seen VARCHAR NOT NULL
+            )
+        """)
+
+        # API errors, rate-limit hits, and interrupted turns from transcripts
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS session_events (
+                event_id VARCHAR NOT NULL,
+                session_id VARCHAR NOT NULL,
+                date VARCHAR NOT NULL,
+                timestamp VARCHAR NOT NULL,
+                event_type VARCHAR NOT NULL,
+                detail VARCHAR NOT NULL,
+                folder VARCHAR NOT NULL,
+                retry_attempt INTEGER,
+                ends_turn BOOLEAN NOT NULL DEFAULT FALSE,
+                turn_tokens INTEGER NOT NULL DEFAULT 0,
+                messages_before INTEGER,
+                context_tokens BIGINT NOT NULL DEFAULT 0,
+                PRIMARY KEY (session_id, event_id)
+            )
+        """)
+
+        # Compaction details (messages since the last reset, context size)
+        conn.execute("ALTER TABLE session_events ADD COLUMN IF NOT EXISTS messages_before INTEGER")
+        conn.execute("ALTER TABLE session_events ADD COLUMN IF NOT EXISTS context_tokens BIGINT DEFAULT 0")
+
+        # MCP tool calls ("mcp__<server>__<tool>"), one row per tool_use id
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS mcp_calls (
+                tool_use_id VARCHAR PRIMARY KEY,
+                session_id VARCHAR NOT NULL,
+                date VARCHAR NOT NULL,
+                timestamp VARCHAR NOT NULL,
+                server VARCHAR NOT NULL,
+                tool VARCHAR NOT NULL,
+                call_tokens BIGINT NOT NULL DEFAULT 0,
+                result_chars BIGINT NOT NULL DEFAULT 0
+            )
+        """)
+
+        # Every tool call (built-in and MCP), one row per tool_use id;
+        # get_tool_usage counts them per date, session, and tool
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS tool_usage (
+                tool_use_id VARCHAR PRIMARY KEY,
+                date VARCHAR NOT NULL,
+                session_id VARCHAR NOT NULL,
+                tool VARCHAR NOT NULL
+            )
+        """)
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_tool_usage_date ON tool_usage(date, tool)")
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_tool_usage_session ON tool_usage(session_id)")
+
+        # Session metadata, so commands can name sessions by a readable label
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS sessions (
+                session_id VARCHAR PRIMARY KEY,
+                folder VARCHAR,
+                started_at VARCHAR NOT NULL,
+                ended_at VARCHAR NOT NULL,
+                messages BIGINT NOT NULL DEFAULT 0,
+                total_tokens BIGINT NOT NULL DEFAULT 0,
+                title VARCHAR
+            )
+        """)
+        conn.execute("ALTER TABLE sessions ADD COLUMN IF NOT EXISTS entrypoint VARCHAR")
+
+        # Summary lines Claude Code writes into transcripts, the latest per session
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS session_summaries (
+                session_id VARCHAR PRIMARY KEY,
+                summary VARCHAR NOT NULL,
+                leaf_uuid VARCHAR
+            )
+        """)
+
+        # Opt-in message text ("store_content"), capped per record and in total
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS message_content (
+                entry_key VARCHAR PRIMARY KEY,
+                session_id VARCHAR NOT NULL,
+                message_type VARCHAR NOT NULL,
+                timestamp VARCHAR NOT NULL,
+                content VARCHAR NOT NULL,
+                content_bytes BIGINT NOT NULL,
+                truncated BOOLEAN NOT NULL
+            )
+        """)
+
         # Table for sync bookkeeping (e.g. push watermarks)
         conn.execute("""
             CREATE TABLE IF NOT EXISTS sync_state (
@@ -199,46 +500,7 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
             "cache_write_1h_price_per_mtok DOUBLE"
         )
 
-        # Charged API rates (console.anthropic.com): cache writes 1.25x (5m) /
-        # 2x (1h) base input, cache reads 0.1x. Sonnet 5 carries introductory
-        # pricing until 2026-08-31 - revert to 3.00/15.00/3.75/6.00/0.30 after.
-        pricing_data = [
-            ('claude-fable-5', 10.00, 50.00, 12.50, 1.00, 20.00, 'Claude Fable 5'),
-            ('claude-opus-4-8', 5.00, 25.00, 6.25, 0.50, 10.00, 'Claude Opus 4.8'),
-            ('claude-opus-4-7', 5.00, 25.00, 6.25, 0.50, 10.00, 'Claude Opus 4.7'),
-            ('claude-opus-4-6', 5.00, 25.00, 6.25, 0.50, 10.00, 'Claude Opus 4.6'),
-            ('claude-sonnet-5', 2.00, 10.00, 2.50, 0.20, 4.00, 'Claude Sonnet 5 (intro pricing until 2026-08-31)'),
-            ('claude-sonnet-4-6', 3.00, 15.00, 3.75, 0.30, 6.00, 'Claude Sonnet 4.6'),
-            ('claude-haiku-4-5', 1.00, 5.00, 1.25, 0.10, 2.00, 'Claude Haiku 4.5'),
-            ('claude-opus-4-5-20251101', 15.00, 75.00, 18.75, 1.50, 30.00, 'Claude Opus 4.5'),
-            ('claude-opus-4-1-20250805', 15.00, 75.00, 18.75, 1.50, 30.00, 'Claude Opus 4.1'),
-            ('claude-sonnet-4-5-20250929', 3.00, 15.00, 3.75, 0.30, 6.00, 'Claude Sonnet 4.5'),
-            ('claude-haiku-4-5-20251001', 1.00, 5.00, 1.25, 0.10, 2.00, 'Claude Haiku 4.5'),
-            ('claude-haiku-3-5-20241022', 0.80, 4.00, 1.00, 0.08, 1.60, 'Claude 3.5 Haiku'),
-            ('claude-sonnet-4-20250514', 3.00, 15.00, 3.75, 0.30, 6.00, 'Legacy Sonnet 4'),
-            ('claude-opus-4-20250514', 15.00, 75.00, 18.75, 1.50, 30.00, 'Legacy Opus 4'),
-            ('claude-sonnet-3-7-20250219', 3.00, 15.00, 3.75, 0.30, 6.00, 'Legacy Sonnet 3.7'),
-            ('gpt-5.5', 5.00, 30.00, 0.00, 0.5, 0.00, 'OpenAI GPT-5.5 (Codex)'),
-            ('gpt-5.6-sol', 5.00, 30.00, 0.00, 0.5, 0.00, 'OpenAI GPT-5.6 Sol (Codex)'),
-            ('gpt-5.6-terra', 2.50, 15.00, 0.00, 0.25, 0.00, 'OpenAI GPT-5.6 Terra (Codex)'),
-            ('gpt-5.3-codex', 1.75, 14.00, 0.00, 0.175, 0.00, 'OpenAI GPT-5.3 Codex'),
-            ('gpt-5.3-codex-spark', 1.75, 14.00, 0.00, 0.175, 0.00, 'OpenAI GPT-5.3 Codex Spark'),
-            ('gpt-5-codex', 1.25, 10.00, 0.00, 0.125, 0.00, 'OpenAI GPT-5 Codex'),
-            ('gemini-3-pro-preview', 2.00, 12.00, 0.00, 0.2, 0.00, 'Google Gemini 3 Pro Preview'),
-            ('gemini-2.5-pro', 1.25, 10.00, 0.00, 0.125, 0.00, 'Google Gemini 2.5 Pro'),
-            ('gemini-2.5-flash', 0.30, 2.50, 0.00, 0.03, 0.00, 'Google Gemini 2.5 Flash'),
-            ('<synthetic>', 0.00, 0.00, 0.00, 0.00, 0.00, 'Test/synthetic model'),
-        ]
-
-        timestamp = datetime.now().isoformat()
-        for model_name, input_price, output_price, cache_write, cache_read, cache_write_1h, notes in pricing_data:
-            conn.execute("""
-                INSERT OR REPLACE INTO model_pricing (
-                    model_name, input_price_per_mtok, output_price_per_mtok,
-                    cache_write_price_per_mtok, cache_read_price_per_mtok,
-                    cache_write_1h_price_per_mtok, last_updated, notes
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            """, [model_name, input_price, output_price, cache_write, cache_read, cache_write_1h, timestamp, notes])
+        _sync_model_pricing(conn)
 
         # Per-file aggregate contributions ledger (aggregate storage mode):
         # what each transcript file last added to daily_snapshots, so a
@@ -260,6 +522,94 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
             )
         """)
 
+        # Per-day model mix: token splits by model, kept in both storage
+        # modes so costs and model breakdowns never need usage_records.
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS daily_model_snapshots (
+                date VARCHAR NOT NULL,
+                model VARCHAR NOT NULL,
+                responses INTEGER NOT NULL,
+                input_tokens BIGINT NOT NULL,
+                output_tokens BIGINT NOT NULL,
+                cache_creation_tokens BIGINT NOT NULL,
+                cache_read_tokens BIGINT NOT NULL,
+                cache_creation_1h_tokens BIGINT NOT NULL,
+                total_tokens BIGINT NOT NULL,
+                PRIMARY KEY (date, model)
+            )
+        """)
+
+        # Model-mix counterpart of file_contributions (aggregate mode deltas)
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS file_model_contributions (
+                file_path VARCHAR NOT NULL,
+                date VARCHAR NOT NULL,
+                model VARCHAR NOT NULL,
+                responses INTEGER NOT NULL,
+                input_tokens BIGINT NOT NULL,
+                output_tokens BIGINT NOT NULL,
+                cache_creation_tokens BIGINT NOT NULL,
+                cache_read_tokens BIGINT NOT NULL,
+                cache_creation_1h_tokens BIGINT NOT NULL,
+                total_tokens BIGINT NOT NULL,
+                PRIMARY KEY (file_path, date, model)
+            )
+        """)
+
+        # Per-UTC-hour totals for intraday views and rolling limit windows,
+        # kept in both storage modes like the model mix
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS hourly_snapshots (
+                hour VARCHAR PRIMARY KEY,
+                prompts INTEGER NOT NULL,
+                responses INTEGER NOT NULL,
+                input_tokens BIGINT NOT NULL,
+                output_tokens BIGINT NOT NULL,
+                cache_creation_tokens BIGINT NOT NULL,
+                cache_read_tokens BIGINT NOT NULL,
+                cache_creation_1h_tokens BIGINT NOT NULL,
+                total_tokens BIGINT NOT NULL
+            )
+        """)
+
+        # Hourly counterpart of file_contributions (aggregate mode deltas)
+        conn.execute("""
+            CREATE TABLE IF NOT EXISTS file_hourly_contributions (
+                file_path VARCHAR NOT NULL,
+                hour VARCHAR NOT NULL,
+                prompts INTEGER NOT NULL,
+                responses INTEGER NOT NULL,
+                input_tokens BIGINT NOT NULL,
+                output_tokens BIGINT NOT NULL,
+                cache_creation_tokens BIGINT NOT NULL,
+                cache_read_tokens BIGINT NOT NULL,
+                cache_creation_1h_tokens BIGINT NOT NULL,
+                total_tokens BIGINT NOT NULL,
+                PRIMARY KEY (file_path, hour)
+            )
+        """)
+
+        # One-time backfill of the model mix from existing detail rows
+        if conn.execute("SELECT COUNT(*) FROM daily_model_snapshots").fetchone()[0] == 0:
+            conn.execute(_MODEL_MIX_REBUILD_SQL.format(where=""))
+        if conn.execute("SELECT COUNT(*) FROM hourly_snapshots").fetchone()[0] == 0:
+            _rebuild_hourly_snapshots(conn, [
+                row[0] for row in conn.execute("SELECT DISTINCT date FROM usage_records").fetchall()
+            ])
+
+        # A billed response is stored once across sessions (forks replay it
+        # under new session ids). DuckDB has no partial indexes, so user rows
+        # index as NULL, which a unique index never compares. Built once;
+        # files from before it drop their duplicate copies first.
+        if not conn.execute(
+            "SELECT 1 FROM duckdb_indexes() WHERE index_name = 'idx_usage_records_response'"
+        ).fetchone():
+            _dedupe_responses(conn)
+            conn.execute("""
+                CREATE UNIQUE INDEX idx_usage_records_response
+                ON usage_records ((CASE WHEN message_type = 'assistant' THEN message_uuid END))
+            """)
+
         _INITIALIZED_DBS.add(str(db_path))
     finally:
         conn.close()
@@ -296,865 +646,3743 @@ _CONTRIB_FIELDS = (
     "cache_creation_tokens", "cache_read_tokens", "total_tokens",
 )
 
+_DAILY_FIELDS = (
+    "prompts", "responses", "sessions", "input_tokens", "output_tokens",
+    "cache_creation_tokens", "cache_read_tokens",
+)
 
-def save_file_aggregate(
-    file_path: Path,
-    records: list[UsageRecord],
-    db_path: Path = DEFAULT_DB_PATH,
-    device_id: str | None = None,
-    device_name: str | None = None,
-    device_type: str | None = None,
-) -> int:
-    """
-    Apply one transcript file's aggregate contribution as a DELTA.
+_DAILY_COLUMNS = (
+    "total_prompts", "total_responses", "total_sessions", "input_tokens",
+    "output_tokens", "cache_creation_tokens", "cache_read_tokens",
+)
 
-    Computes the file's fresh per-date sums, subtracts what the ledger says
-    the file previously contributed, applies the difference to
-    daily_snapshots, and stores the fresh sums back. A file already tracked
-    in file_metadata but absent from the ledger was parsed under the old
-    add-everything logic: its transition parse contributes zero (previous is
-    primed to fresh) so pre-ledger totals are never re-added.
 
-    Returns:
-        Number of assistant responses newly accounted for (delta)
+def _write_daily_snapshot(
+    db,
+    date: str,
+    values: dict[str, int],
+    timestamp: str,
+    device_id: str | None,
+    device_name: str | None,
+    device_type: str | None,
+    hostname: str | None = None,
+) -> None:
+    """INSERT OR REPLACE one daily_snapshots row; total_tokens is derived."""
+    row = [max(int(values.get(field, 0) or 0), 0) for field in _DAILY_FIELDS]
+    db.execute(
+        f"""
+        INSERT OR REPLACE INTO daily_snapshots (
+            date, {', '.join(_DAILY_COLUMNS)}, total_tokens,
+            snapshot_timestamp, device_id, device_name, device_type, hostname
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        """,
+        [date, *row, sum(row[3:]), timestamp, device_id, device_name, device_type, hostname or None],
+    )
+
+
+def _apply_daily_delta(
+    db,
+    date: str,
+    delta: dict[str, int],
+    timestamp: str,
+    device_id: str | None,
+    device_name: str | None,
+    device_type: str | None,
+    hostname: str | None = None,
+) -> None:
     """
-    init_database(db_path)
-    fresh = _aggregate_by_date(records)
+    Add a delta to one day's daily_snapshots row, clamping fields at zero.
 
-    conn = duckdb.connect(str(db_path))
-    try:
-        rows = conn.execute(
-            f"SELECT date, {', '.join(_CONTRIB_FIELDS)} FROM file_contributions WHERE file_path = ?",
-            [str(file_path)],
-        ).fetchall()
-        previous = {
-            row[0]: dict(zip(_CONTRIB_FIELDS, row[1:])) for row in rows
-        }
-        if not previous:
-            tracked = conn.execute(
-                "SELECT 1 FROM file_metadata WHERE file_path = ?", [str(file_path)]
-            ).fetchone()
-            if tracked is not None:
-                previous = {date: dict(day) for date, day in fresh.items()}
+    Args:
+        db: sqlite3 cursor or DuckDB connection (same execute/fetchone API)
+        date: Day to update (YYYY-MM-DD)
+        delta: Signed changes keyed by _DAILY_FIELDS (missing keys are 0)
+    """
+    existing = db.execute(
+        f"SELECT {', '.join(_DAILY_COLUMNS)} FROM daily_snapshots WHERE date = ?",
+        [date],
+    ).fetchone()
+    base = existing or (0,) * len(_DAILY_FIELDS)
+    merged = {
+        field: (value or 0) + delta.get(field, 0)
+        for field, value in zip(_DAILY_FIELDS, base)
+    }
+    _write_daily_snapshot(db, date, merged, timestamp, device_id, device_name, device_type, hostname)
+
+
+_MODEL_MIX_FIELDS = (
+    "responses", "input_tokens", "output_tokens", "cache_creation_tokens",
+    "cache_read_tokens", "cache_creation_1h_tokens", "total_tokens",
+)
 
-        timestamp = datetime.now().isoformat()
-        new_responses = 0
-        for date, day in fresh.items():
-            prev = previous.get(date, dict.fromkeys(_CONTRIB_FIELDS, 0))
-            delta = {field: day[field] - prev[field] for field in _CONTRIB_FIELDS}
-            new_responses += max(delta["responses"], 0)
-            if any(delta.values()):
-                existing = conn.execute(
-                    """
-                    SELECT total_prompts, total_responses, total_sessions,
-                           input_tokens, output_tokens, cache_creation_tokens,
-                           cache_read_tokens
-                    FROM daily_snapshots WHERE date = ?
-                    """,
-                    [date],
-                ).fetchone()
-                base = existing or (0, 0, 0, 0, 0, 0, 0)
-                merged = [
-                    max(base[0] + delta["prompts"], 0),
-                    max(base[1] + delta["responses"], 0),
-                    max(base[2] + delta["sessions"], 0),
-                    max(base[3] + delta["input_tokens"], 0),
-                    max(base[4] + delta["output_tokens"], 0),
-                    max(base[5] + delta["cache_creation_tokens"], 0),
-                    max(base[6] + delta["cache_read_tokens"], 0),
-                ]
-                total = merged[3] + merged[4] + merged[5] + merged[6]
-                conn.execute(
-                    """
-                    INSERT OR REPLACE INTO daily_snapshots (
-                        date, total_prompts, total_responses, total_sessions,
-                        total_tokens, input_tokens, output_tokens,
-                        cache_creation_tokens, cache_read_tokens,
-                        snapshot_timestamp, device_id, device_name, device_type
-                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                    """,
-                    [date, merged[0], merged[1], merged[2], total,
-                     merged[3], merged[4], merged[5], merged[6],
-                     timestamp, device_id, device_name, device_type],
-                )
+# Rebuilds daily_model_snapshots rows from usage_records; {where} narrows the
+# dates (callers delete those dates' rows first). Shared with snapshot_db.
+_MODEL_MIX_REBUILD_SQL = """
+    INSERT OR REPLACE INTO daily_model_snapshots (
+        date, model, responses, input_tokens, output_tokens,
+        cache_creation_tokens, cache_read_tokens, cache_creation_1h_tokens,
+        total_tokens
+    )
+    SELECT
+        date,
+        COALESCE(model, 'unknown'),
+        COUNT(*),
+        COALESCE(SUM(input_tokens), 0),
+        COALESCE(SUM(output_tokens), 0),
+        COALESCE(SUM(cache_creation_tokens), 0),
+        COALESCE(SUM(cache_read_tokens), 0),
+        COALESCE(SUM(cache_creation_1h_tokens), 0),
+        COALESCE(SUM(total_tokens), 0)
+    FROM usage_records
+    WHERE message_type = 'assistant' {where}
+    GROUP BY date, COALESCE(model, 'unknown')
+"""
 
-        conn.execute("DELETE FROM file_contributions WHERE file_path = ?", [str(file_path)])
-        for date, day in fresh.items():
-            conn.execute(
-                f"""
-                INSERT INTO file_contributions (file_path, date, {', '.join(_CONTRIB_FIELDS)})
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                """,
-                [str(file_path), date] + [day[field] for field in _CONTRIB_FIELDS],
-            )
-        return new_responses
-    finally:
-        conn.close()
 
+# Per-model usage summed over the daily_model_snapshots rollup, for
+# get_database_stats on aggregate-mode databases (no usage_records).
+# Rows match price_model_usage plus a trailing total. Shared with snapshot_db.
+_MODEL_MIX_TOTALS_SQL = """
+    SELECT model, SUM(input_tokens), SUM(output_tokens), SUM(cache_creation_tokens),
+           SUM(cache_read_tokens), SUM(cache_creation_1h_tokens), SUM(total_tokens)
+    FROM daily_model_snapshots
+    WHERE model <> 'unknown'
+    GROUP BY model
+    ORDER BY SUM(total_tokens) DESC
+"""
 
-def save_snapshot(
-    records: list[UsageRecord],
-    db_path: Path = DEFAULT_DB_PATH,
-    storage_mode: str = "aggregate",
-    device_id: str | None = None,
-    device_name: str | None = None,
-    device_type: str | None = None,
-) -> int:
-    """
-    Save usage records to the DuckDB database as a snapshot.
+# Days tracked and their range, read from the day totals when no records are kept
+_SNAPSHOT_RANGE_SQL = "SELECT COUNT(*), MIN(date), MAX(date) FROM daily_snapshots"
 
-    Args:
-        records: List of usage records to save
-        db_path: Path to the DuckDB database file
-        storage_mode: "aggregate" (daily totals only) or "full" (individual records)
-        device_id: Device identifier for multi-device sync
-        device_name: Human-readable device name
-        device_type: Device type (macos, windows, linux)
 
-    Returns:
-        Number of new records saved
+def _aggregate_by_date_model(records: list[UsageRecord]) -> dict[tuple[str, str], dict[str, int]]:
+    """Per-(date, model) response/token sums of assistant records."""
+    sums: dict[tuple[str, str], dict[str, int]] = {}
+    for record in records:
+        if not record.is_assistant_response:
+            continue
+        mix = sums.setdefault(
+            (record.date_key, record.model or "unknown"), dict.fromkeys(_MODEL_MIX_FIELDS, 0)
+        )
+        mix["responses"] += 1
+        if record.token_usage:
+            mix["input_tokens"] += record.token_usage.input_tokens
+            mix["output_tokens"] += record.token_usage.output_tokens
+            mix["cache_creation_tokens"] += record.token_usage.cache_creation_tokens
+            mix["cache_read_tokens"] += record.token_usage.cache_read_tokens
+            mix["cache_creation_1h_tokens"] += record.token_usage.cache_creation_1h_tokens
+            mix["total_tokens"] += record.token_usage.total_tokens
+    return sums
 
-    Raises:
-        duckdb.Error: If database operation fails
-    """
-    require_duckdb()
 
-    if not records:
-        return 0
+def _merge_model_mix(db, deltas: dict[tuple[str, str], dict[str, int]]) -> None:
+    """
+    Add per-(date, model) deltas into daily_model_snapshots, clamped at zero.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection (same execute/fetchone API)
+        deltas: Output shape of _aggregate_by_date_model
+    """
+    columns = ", ".join(_MODEL_MIX_FIELDS)
+    placeholders = ", ".join("?" for _ in range(len(_MODEL_MIX_FIELDS) + 2))
+    for (date, model), delta in deltas.items():
+        if not any(delta.values()):
+            continue
+        row = db.execute(
+            f"SELECT {columns} FROM daily_model_snapshots WHERE date = ? AND model = ?",
+            [date, model],
+        ).fetchone()
+        base = row or (0,) * len(_MODEL_MIX_FIELDS)
+        merged = [max(b + delta[f], 0) for b, f in zip(base, _MODEL_MIX_FIELDS)]
+        db.execute(
+            f"INSERT OR REPLACE INTO daily_model_snapshots (date, model, {columns}) "
+            f"VALUES ({placeholders})",
+            [date, model, *merged],
+        )
+
+
+_HOURLY_FIELDS = (
+    "prompts", "responses", "input_tokens", "output_tokens", "cache_creation_tokens",
+    "cache_read_tokens", "cache_creation_1h_tokens", "total_tokens",
+)
+
+_HOURLY_RECORD_COLUMNS = (
+    "timestamp, message_type, input_tokens, output_tokens, cache_creation_tokens, "
+    "cache_read_tokens, cache_creation_1h_tokens, total_tokens"
+)
+
+
+def _write_day_model_mix(
+    db,
+    date: str,
+    values: dict[str, int],
+    models: dict[str, dict[str, int]] | None,
+    replace: bool,
+) -> None:
+    """
+    Keep daily_model_snapshots in step with a day written without records.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection (same execute/fetchone API)
+        date: Day written (YYYY-MM-DD)
+        values: The day's totals (or delta), keyed like _DAILY_FIELDS
+        models: Per-model split of values, keyed like _MODEL_MIX_FIELDS;
+            None books the responses and tokens under "unknown"
+        replace: Replace the day's rows instead of adding to them
+    """
+    if replace:
+        db.execute("DELETE FROM daily_model_snapshots WHERE date = ?", [date])
+    deltas = {}
+    for model, mix in (models if models is not None else {"unknown": values}).items():
+        row = {field: int(mix.get(field, 0) or 0) for field in _MODEL_MIX_FIELDS}
+        row["total_tokens"] = sum(
+            row[field] for field in ("input_tokens", "output_tokens", "cache_creation_tokens", "cache_read_tokens")
+        )
+        deltas[(date, model)] = row
+    _merge_model_mix(db, deltas)
+
+
+def _hour_key(timestamp: datetime) -> str:
+    """UTC hour bucket of a timestamp, e.g. "2026-10-16T14:00:00+00:00" (naive = local)."""
+    return timestamp.astimezone(timezone.utc).strftime("%Y-%m-%dT%H:00:00+00:00")
+
+
+def _aggregate_by_hour(records: list[UsageRecord]) -> dict[str, dict[str, int]]:
+    """Per-UTC-hour prompt/response/token sums."""
+    sums: dict[str, dict[str, int]] = {}
+    for record in records:
+        hour = sums.setdefault(_hour_key(record.timestamp), dict.fromkeys(_HOURLY_FIELDS, 0))
+        if record.is_user_prompt:
+            hour["prompts"] += 1
+        elif record.is_assistant_response:
+            hour["responses"] += 1
+        if record.token_usage:
+            hour["input_tokens"] += record.token_usage.input_tokens
+            hour["output_tokens"] += record.token_usage.output_tokens
+            hour["cache_creation_tokens"] += record.token_usage.cache_creation_tokens
+            hour["cache_read_tokens"] += record.token_usage.cache_read_tokens
+            hour["cache_creation_1h_tokens"] += record.token_usage.cache_creation_1h_tokens
+            hour["total_tokens"] += record.token_usage.total_tokens
+    return sums
+
+
+def _merge_hourly(db, deltas: dict[str, dict[str, int]]) -> None:
+    """
+    Add per-hour deltas into hourly_snapshots, clamped at zero.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        deltas: Output shape of _aggregate_by_hour
+    """
+    columns = ", ".join(_HOURLY_FIELDS)
+    placeholders = ", ".join("?" for _ in range(len(_HOURLY_FIELDS) + 1))
+    for hour, delta in deltas.items():
+        if not any(delta.values()):
+            continue
+        row = db.execute(f"SELECT {columns} FROM hourly_snapshots WHERE hour = ?", [hour]).fetchone()
+        base = row or (0,) * len(_HOURLY_FIELDS)
+        merged = [max(b + delta[f], 0) for b, f in zip(base, _HOURLY_FIELDS)]
+        db.execute(
+            f"INSERT OR REPLACE INTO hourly_snapshots (hour, {columns}) VALUES ({placeholders})",
+            [hour, *merged],
+        )
+
+
+def _local_day_hours(date: str) -> tuple[str, str]:
+    """First and last UTC hour buckets overlapping a local date (inclusive)."""
+    start = datetime.fromisoformat(date)
+    return _hour_key(start), _hour_key(start + timedelta(days=1) - timedelta(microseconds=1))
+
+
+def _rebuild_hourly_snapshots(db, dates: list[str]) -> None:
+    """
+    Recompute hourly_snapshots from usage_records for every hour overlapping the dates.
+
+    usage_records are keyed by local date, hours by UTC, so the neighbouring
+    dates' rows are read too; hours left without rows are removed.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        dates: Local dates (YYYY-MM-DD) whose hours to rebuild
+    """
+    days = sorted(set(dates))
+    if not days:
+        return
+    windows = [_local_day_hours(date) for date in days]
+    first = (datetime.fromisoformat(days[0]) - timedelta(days=1)).strftime("%Y-%m-%d")
+    last = (datetime.fromisoformat(days[-1]) + timedelta(days=1)).strftime("%Y-%m-%d")
+    rows = db.execute(
+        f"SELECT {_HOURLY_RECORD_COLUMNS} FROM usage_records WHERE date >= ? AND date <= ?",
+        [first, last],
+    ).fetchall()
+
+    sums: dict[str, dict[str, int]] = {}
+    for row in rows:
+        try:
+            hour = _hour_key(datetime.fromisoformat(row[0]))
+        except (TypeError, ValueError):
+            continue
+        position = bisect.bisect_right(windows, (hour, "~")) - 1
+        if position < 0 or hour > windows[position][1]:
+            continue
+        bucket = sums.setdefault(hour, dict.fromkeys(_HOURLY_FIELDS, 0))
+        if row[1] == "user":
+            bucket["prompts"] += 1
+        elif row[1] == "assistant":
+            bucket["responses"] += 1
+        for field, value in zip(_HOURLY_FIELDS[2:], row[2:]):
+            bucket[field] += value or 0
+
+    for lo, hi in windows:
+        db.execute("DELETE FROM hourly_snapshots WHERE hour >= ? AND hour <= ?", [lo, hi])
+    columns = ", ".join(_HOURLY_FIELDS)
+    placeholders = ", ".join("?" for _ in range(len(_HOURLY_FIELDS) + 1))
+    for hour, bucket in sums.items():
+        db.execute(
+            f"INSERT OR REPLACE INTO hourly_snapshots (hour, {columns}) VALUES ({placeholders})",
+            [hour, *[bucket[f] for f in _HOURLY_FIELDS]],
+        )
+
+
+def _read_hourly_snapshots(db, hours: int, now: datetime | None) -> list[dict]:
+    """
+    Hourly totals for the last N hours (the current one included), oldest first.
+
+    Hours without usage are absent. Backs get_hourly_snapshots in both backends.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        hours: Window length in hours
+        now: End of the window (default: now)
+    """
+    since = _hour_key((now or datetime.now(timezone.utc)) - timedelta(hours=max(hours, 1) - 1))
+    rows = db.execute(
+        f"SELECT hour, {', '.join(_HOURLY_FIELDS)} FROM hourly_snapshots WHERE hour >= ? ORDER BY hour",
+        [since],
+    ).fetchall()
+    return [{"hour": row[0], **dict(zip(_HOURLY_FIELDS, row[1:]))} for row in rows]
+
+
+def _read_hourly_range(db, start_date: str | None, end_date: str | None) -> list[dict]:
+    """
+    Hourly totals for every hour overlapping a local date range, oldest first.
+
+    Backs get_hourly_range in both backends.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        start_date: Inclusive local start (YYYY-MM-DD), or None for all
+        end_date: Inclusive local end (YYYY-MM-DD), or None for all
+    """
+    query = f"SELECT hour, {', '.join(_HOURLY_FIELDS)} FROM hourly_snapshots WHERE 1=1"
+    params: list[str] = []
+    if start_date:
+        query += " AND hour >= ?"
+        params.append(_local_day_hours(start_date)[0])
+    if end_date:
+        query += " AND hour <= ?"
+        params.append(_local_day_hours(end_date)[1])
+    rows = db.execute(query + " ORDER BY hour", params).fetchall()
+    return [{"hour": row[0], **dict(zip(_HOURLY_FIELDS, row[1:]))} for row in rows]
+
+
+# ingest_status key holding the finish time of the last all-sources ingest
+LAST_FULL_INGEST_KEY = "last_full_ingest"
+
+
+def _read_ingest_status(db, key: str) -> str | None:
+    """One ingest_status value (shared by both backends)."""
+    row = db.execute("SELECT value FROM ingest_status WHERE key = ?", [key]).fetchone()
+    return row[0] if row else None
+
+
+def _read_last_full_ingest(db) -> str | None:
+    """Finish time of the last all-sources ingest. Backs get_last_full_ingest in both backends."""
+    return _read_ingest_status(db, LAST_FULL_INGEST_KEY)
+
+
+_PRICING_ROWS_SQL = """
+    SELECT model_name, input_price_per_mtok, output_price_per_mtok,
+           cache_write_price_per_mtok, cache_read_price_per_mtok,
+           cache_write_1h_price_per_mtok
+    FROM model_pricing
+"""
+
+
+def _read_daily_snapshots(db, start_date: str | None, end_date: str | None) -> list[dict]:
+    """
+    Daily totals joined with their model mix and per-day cost.
+
+    Backs load_daily_snapshots in both backends.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+    """
+    where, params = "WHERE 1=1", []
+    if start_date:
+        where += " AND date >= ?"
+        params.append(start_date)
+    if end_date:
+        where += " AND date <= ?"
+        params.append(end_date)
+
+    days = []
+    for row in db.execute(
+        f"""
+        SELECT date, total_prompts, total_responses, total_sessions, total_tokens,
+               input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens
+        FROM daily_snapshots {where} ORDER BY date
+        """,
+        params,
+    ).fetchall():
+        days.append({
+            "date": row[0], "prompts": row[1] or 0, "responses": row[2] or 0,
+            "sessions": row[3] or 0, "total_tokens": row[4] or 0,
+            "input_tokens": row[5] or 0, "output_tokens": row[6] or 0,
+            "cache_creation_tokens": row[7] or 0, "cache_read_tokens": row[8] or 0,
+            "models": {}, "cost": 0.0,
+        })
+
+    mix_rows = db.execute(
+        f"SELECT date, model, {', '.join(_MODEL_MIX_FIELDS)} FROM daily_model_snapshots {where}",
+        params,
+    ).fetchall()
+    by_date = {day["date"]: day for day in days}
+    for row in mix_rows:
+        if row[0] in by_date:
+            by_date[row[0]]["models"][row[1]] = dict(zip(_MODEL_MIX_FIELDS, row[2:]))
+
+    pricing_rows = db.execute(_PRICING_ROWS_SQL).fetchall()
+    costs = price_grouped_model_usage(
+        [(r[0], r[1], r[3], r[4], r[5], r[6], r[7]) for r in mix_rows], pricing_rows
+    )
+    for date, cost in costs.items():
+        if date in by_date:
+            by_date[date]["cost"] = cost
+    return days
+
+
+def _project_from_transcript_path(file_path: str) -> str:
+    """
+    Project directory name (Claude's encoded folder) for a transcript path.
+
+    Transcripts live at ~/.claude/projects/<encoded-folder>/<session>.jsonl
+    (or under the configured data directory), possibly nested deeper for
+    subagents.
+    """
+    from src.config.settings import transcript_project_dir
+
+    path = Path(file_path)
+    project_dir = transcript_project_dir(path)
+    if project_dir is not None:
+        return project_dir
+    parts = path.parts
+    if "projects" in parts:
+        index = len(parts) - 1 - parts[::-1].index("projects")
+        if index + 1 < len(parts) - 1:
+            return parts[index + 1]
+    return Path(file_path).parent.name
+
+
+def _read_project_activity(db) -> list[dict]:
+    """
+    Lifetime usage and activity bounds per project folder, with cost.
+
+    Full mode groups usage_records by folder. Aggregate-mode databases have
+    no folder column, so projects come from the transcript paths in the
+    per-file contribution ledgers (one transcript file per session).
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+
+    Returns:
+        One dict per project: folder, first_date, last_date, active_days,
+        sessions, prompts, total_tokens, cost
+    """
+    pricing_rows = db.execute(_PRICING_ROWS_SQL).fetchall()
+    projects: dict[str, dict] = {}
+
+    if db.execute("SELECT COUNT(*) FROM usage_records").fetchone()[0]:
+        for row in db.execute("""
+            SELECT folder, MIN(date), MAX(date), COUNT(DISTINCT date),
+                   COUNT(DISTINCT session_id),
+                   SUM(CASE WHEN message_type = 'user' THEN 1 ELSE 0 END),
+                   SUM(total_tokens)
+            FROM usage_records
+            GROUP BY folder
+        """).fetchall():
+            projects[row[0]] = {
+                "folder": row[0], "first_date": row[1], "last_date": row[2],
+                "active_days": row[3] or 0, "sessions": row[4] or 0,
+                "prompts": row[5] or 0, "total_tokens": row[6] or 0, "cost": 0.0,
+            }
+        model_rows = db.execute("""
+            SELECT folder, model, SUM(input_tokens), SUM(output_tokens),
+                   SUM(cache_creation_tokens), SUM(cache_read_tokens),
+                   SUM(COALESCE(cache_creation_1h_tokens, 0))
+            FROM usage_records
+            WHERE model IS NOT NULL
+            GROUP BY folder, model
+        """).fetchall()
+    else:
+        dates: dict[str, set[str]] = {}
+        for row in db.execute("""
+            SELECT file_path, date, SUM(prompts), SUM(total_tokens)
+            FROM file_contributions
+            GROUP BY file_path, date
+        """).fetchall():
+            folder = _project_from_transcript_path(row[0])
+            project = projects.setdefault(folder, {
+                "folder": folder, "first_date": row[1], "last_date": row[1],
+                "active_days": 0, "sessions": set(), "prompts": 0,
+                "total_tokens": 0, "cost": 0.0,
+            })
+            project["first_date"] = min(project["first_date"], row[1])
+            project["last_date"] = max(project["last_date"], row[1])
+            project["sessions"].add(row[0])
+            project["prompts"] += row[2] or 0
+            project["total_tokens"] += row[3] or 0
+            dates.setdefault(folder, set()).add(row[1])
+        for folder, project in projects.items():
+            project["sessions"] = len(project["sessions"])
+            project["active_days"] = len(dates[folder])
+        model_rows = [
+            (_project_from_transcript_path(row[0]), *row[1:])
+            for row in db.execute("""
+                SELECT file_path, model, SUM(input_tokens), SUM(output_tokens),
+                       SUM(cache_creation_tokens), SUM(cache_read_tokens),
+                       SUM(cache_creation_1h_tokens)
+                FROM file_model_contributions
+                GROUP BY file_path, model
+            """).fetchall()
+        ]
+
+    for folder, cost in price_grouped_model_usage(model_rows, pricing_rows).items():
+        if folder in projects:
+            projects[folder]["cost"] = cost
+    return sorted(projects.values(), key=lambda p: p["last_date"], reverse=True)
+
+
+def _read_project_tokens(db, start_date: str | None, end_date: str | None) -> dict[str, int]:
+    """
+    Total tokens per project folder within a date range.
+
+    Same sources as _read_project_activity: usage_records in full mode,
+    transcript paths from file_contributions in aggregate mode.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+    """
+    where, params = "WHERE 1=1", []
+    if start_date:
+        where += " AND date >= ?"
+        params.append(start_date)
+    if end_date:
+        where += " AND date <= ?"
+        params.append(end_date)
+
+    tokens: dict[str, int] = {}
+    if db.execute("SELECT COUNT(*) FROM usage_records").fetchone()[0]:
+        for folder, total in db.execute(
+            f"SELECT folder, SUM(total_tokens) FROM usage_records {where} GROUP BY folder", params
+        ).fetchall():
+            tokens[folder] = total or 0
+    else:
+        for file_path, total in db.execute(
+            f"SELECT file_path, SUM(total_tokens) FROM file_contributions {where} GROUP BY file_path",
+            params,
+        ).fetchall():
+            folder = _project_from_transcript_path(file_path)
+            tokens[folder] = tokens.get(folder, 0) + (total or 0)
+    return tokens
+
+
+def _read_branch_stats(db, start_date: str | None, end_date: str | None) -> list[dict]:
+    """
+    Tokens and estimated cost per project and git branch.
+
+    Needs usage_records (full storage mode); aggregate-mode databases keep
+    no branch and return nothing.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+
+    Returns:
+        Dicts with folder, branch (None when not in a git repo), tokens,
+        cost, sessions, and responses, costliest first
+    """
+    where, params = "WHERE 1=1", []
+    if start_date:
+        where += " AND date >= ?"
+        params.append(start_date)
+    if end_date:
+        where += " AND date <= ?"
+        params.append(end_date)
+
+    branches: dict[tuple, dict] = {}
+    for folder, branch, sessions, responses in db.execute(f"""
+        SELECT folder, git_branch, COUNT(DISTINCT session_id),
+               SUM(CASE WHEN message_type = 'assistant' THEN 1 ELSE 0 END)
+        FROM usage_records {where}
+        GROUP BY folder, git_branch
+    """, params).fetchall():
+        branches[(folder, branch)] = {
+            "folder": folder, "branch": branch, "tokens": 0, "cost": 0.0,
+            "sessions": sessions, "responses": responses or 0,
+        }
+
+    rows = db.execute(f"""
+        SELECT folder, git_branch, model, SUM(input_tokens), SUM(output_tokens),
+               SUM(cache_creation_tokens), SUM(cache_read_tokens),
+               SUM(COALESCE(cache_creation_1h_tokens, 0)), SUM(total_tokens)
+        FROM usage_records {where}
+        GROUP BY folder, git_branch, model
+    """, params).fetchall()
+    for folder, branch, *_, total in rows:
+        branches[(folder, branch)]["tokens"] += total or 0
+    pricing_rows = db.execute(_PRICING_ROWS_SQL).fetchall()
+    for key, cost in price_grouped_model_usage(
+        [((row[0], row[1]), *row[2:8]) for row in rows], pricing_rows
+    ).items():
+        branches[key]["cost"] = cost
+    return sorted(branches.values(), key=lambda b: (-b["cost"], -b["tokens"], b["folder"] or "", b["branch"] or ""))
+
+
+def _read_host_stats(db, start_date: str | None, end_date: str | None) -> list[dict]:
+    """
+    Tokens and estimated cost per machine the records were ingested on.
+
+    Needs usage_records (full storage mode); aggregate-mode databases
+    return nothing. Records stored before hostnames were recorded are
+    grouped under None.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+
+    Returns:
+        Dicts with hostname, tokens, cost, sessions, responses, and the
+        first and last active date, costliest first
+    """
+    where, params = "WHERE 1=1", []
+    if start_date:
+        where += " AND date >= ?"
+        params.append(start_date)
+    if end_date:
+        where += " AND date <= ?"
+        params.append(end_date)
+
+    hosts: dict[str | None, dict] = {}
+    for hostname, sessions, responses, first, last in db.execute(f"""
+        SELECT hostname, COUNT(DISTINCT session_id),
+               SUM(CASE WHEN message_type = 'assistant' THEN 1 ELSE 0 END),
+               MIN(date), MAX(date)
+        FROM usage_records {where}
+        GROUP BY hostname
+    """, params).fetchall():
+        hosts[hostname] = {
+            "hostname": hostname, "tokens": 0, "cost": 0.0, "sessions": sessions,
+            "responses": responses or 0, "first_date": first, "last_date": last,
+        }
+
+    rows = db.execute(f"""
+        SELECT hostname, model, SUM(input_tokens), SUM(output_tokens),
+               SUM(cache_creation_tokens), SUM(cache_read_tokens),
+               SUM(COALESCE(cache_creation_1h_tokens, 0)), SUM(total_tokens)
+        FROM usage_records {where}
+        GROUP BY hostname, model
+    """, params).fetchall()
+    for hostname, *_, total in rows:
+        hosts[hostname]["tokens"] += total or 0
+    pricing_rows = db.execute(_PRICING_ROWS_SQL).fetchall()
+    for key, cost in price_grouped_model_usage([row[:7] for row in rows], pricing_rows).items():
+        hosts[key]["cost"] = cost
+    return sorted(hosts.values(), key=lambda h: (-h["cost"], -h["tokens"], h["hostname"] or ""))
+
+
+def _read_daily_projects(db, start_date: str | None, end_date: str | None) -> dict[str, dict[str, dict]]:
+    """
+    Tokens and estimated cost per project for each day.
+
+    Same sources as _read_project_activity: usage_records in full mode,
+    transcript paths from file_model_contributions in aggregate mode.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+
+    Returns:
+        {date: {folder: {"tokens": int, "cost": float}}}
+    """
+    where, params = "WHERE 1=1", []
+    if start_date:
+        where += " AND date >= ?"
+        params.append(start_date)
+    if end_date:
+        where += " AND date <= ?"
+        params.append(end_date)
+
+    if db.execute("SELECT COUNT(*) FROM usage_records").fetchone()[0]:
+        rows = db.execute(f"""
+            SELECT date, folder, model, SUM(input_tokens), SUM(output_tokens),
+                   SUM(cache_creation_tokens), SUM(cache_read_tokens),
+                   SUM(COALESCE(cache_creation_1h_tokens, 0)), SUM(total_tokens)
+            FROM usage_records {where}
+            GROUP BY date, folder, model
+        """, params).fetchall()
+    else:
+        rows = [
+            (row[0], _project_from_transcript_path(row[1]), *row[2:])
+            for row in db.execute(f"""
+                SELECT date, file_path, model, SUM(input_tokens), SUM(output_tokens),
+                       SUM(cache_creation_tokens), SUM(cache_read_tokens),
+                       SUM(cache_creation_1h_tokens), SUM(total_tokens)
+                FROM file_model_contributions {where}
+                GROUP BY date, file_path, model
+            """, params).fetchall()
+        ]
+
+    days: dict[str, dict[str, dict]] = {}
+    for date, folder, *_, total in rows:
+        project = days.setdefault(date, {}).setdefault(folder, {"tokens": 0, "cost": 0.0})
+        project["tokens"] += total or 0
+    pricing_rows = db.execute(_PRICING_ROWS_SQL).fetchall()
+    for (date, folder), cost in price_grouped_model_usage(
+        [((row[0], row[1]), *row[2:8]) for row in rows], pricing_rows
+    ).items():
+        days[date][folder]["cost"] = cost
+    return days
+
+
+def _preview_ingest(db, files: dict[str, list[UsageRecord]], storage_mode: str) -> dict:
+    """
+    What saving freshly parsed transcripts would add, without writing.
+
+    Mirrors the save paths: full mode counts records whose identity is not
+    in usage_records yet (assistant rows by billed-response id, user rows
+    per session); aggregate mode counts each file's positive response delta
+    against its contribution ledger, treating tracked pre-ledger files as
+    already counted.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection, or None for no database yet
+        files: Parsed records per transcript (keyed like the ledger)
+        storage_mode: "full" or "aggregate"
+
+    Returns:
+        Dict with new_records, new_dates and new_projects (sorted lists)
+    """
+    def rows(sql: str) -> list[tuple]:
+        return db.execute(sql).fetchall() if db is not None else []
+
+    known_dates = {row[0] for row in rows(
+        "SELECT date FROM daily_snapshots WHERE total_prompts > 0 OR total_responses > 0"
+    )}
+    new_records = 0
+    new_dates: set[str] = set()
+    new_projects: set[str] = set()
+
+    if storage_mode == "full":
+        known_projects = {row[0] for row in rows("SELECT DISTINCT folder FROM usage_records")}
+        responses = {row[0] for row in rows(
+            "SELECT message_uuid FROM usage_records WHERE message_type = 'assistant'"
+        )}
+        messages = set(rows(
+            "SELECT session_id, message_uuid FROM usage_records WHERE message_type <> 'assistant'"
+        ))
+        for records in files.values():
+            for record in records:
+                if record.message_type == "assistant":
+                    seen, key = responses, record.message_uuid
+                else:
+                    seen, key = messages, (record.session_id, record.message_uuid)
+                if key in seen:
+                    continue
+                seen.add(key)
+                new_records += 1
+                if record.date_key not in known_dates:
+                    new_dates.add(record.date_key)
+                if record.folder not in known_projects:
+                    new_projects.add(record.folder)
+    else:
+        ledger: dict[str, dict[str, int]] = {}
+        for file_path, date, responses_count in rows(
+            "SELECT file_path, date, responses FROM file_contributions"
+        ):
+            ledger.setdefault(file_path, {})[date] = responses_count or 0
+        tracked = {row[0] for row in rows("SELECT file_path FROM file_metadata")}
+        known_projects = {_project_from_transcript_path(path) for path in ledger}
+        for file_path, records in files.items():
+            if file_path not in ledger and file_path in tracked:
+                continue
+            previous = ledger.get(file_path, {})
+            added = 0
+            for date, day in _aggregate_by_date(records).items():
+                delta = day["responses"] - previous.get(date, 0)
+                if delta <= 0 and date in previous:
+                    continue
+                added += max(delta, 0)
+                if date not in known_dates:
+                    new_dates.add(date)
+            new_records += added
+            project = _project_from_transcript_path(file_path)
+            if file_path not in ledger and records and project not in known_projects:
+                new_projects.add(project)
+
+    return {
+        "new_records": new_records,
+        "new_dates": sorted(new_dates),
+        "new_projects": sorted(new_projects),
+    }
+
+
+def _apply_file_model_mix(db, file_path: Path, records: list[UsageRecord], primed: bool) -> None:
+    """
+    Model-mix half of save_file_aggregate: diff against the file's ledger.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        file_path: Transcript file being (re)applied
+        records: The file's fresh records
+        primed: True when the file predates the ledger (contributes zero)
+    """
+    fresh = _aggregate_by_date_model(records)
+    columns = ", ".join(_MODEL_MIX_FIELDS)
+    rows = db.execute(
+        f"SELECT date, model, {columns} FROM file_model_contributions WHERE file_path = ?",
+        [str(file_path)],
+    ).fetchall()
+    previous = {(row[0], row[1]): dict(zip(_MODEL_MIX_FIELDS, row[2:])) for row in rows}
+    if not previous and primed:
+        previous = {key: dict(mix) for key, mix in fresh.items()}
+
+    zero = dict.fromkeys(_MODEL_MIX_FIELDS, 0)
+    _merge_model_mix(db, {
+        key: {f: mix[f] - previous.get(key, zero)[f] for f in _MODEL_MIX_FIELDS}
+        for key, mix in fresh.items()
+    })
+
+    db.execute("DELETE FROM file_model_contributions WHERE file_path = ?", [str(file_path)])
+    placeholders = ", ".join("?" for _ in range(len(_MODEL_MIX_FIELDS) + 3))
+    for (date, model), mix in fresh.items():
+        db.execute(
+            f"INSERT INTO file_model_contributions (file_path, date, model, {columns}) "
+            f"VALUES ({placeholders})",
+            [str(file_path), date, model, *[mix[f] for f in _MODEL_MIX_FIELDS]],
+        )
+
+
+def _apply_file_hourly(db, file_path: Path, records: list[UsageRecord], primed: bool) -> None:
+    """
+    Hourly half of save_file_aggregate: diff against the file's ledger.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        file_path: Transcript file being (re)applied
+        records: The file's fresh records
+        primed: True when the file predates the ledger (contributes zero)
+    """
+    fresh = _aggregate_by_hour(records)
+    columns = ", ".join(_HOURLY_FIELDS)
+    rows = db.execute(
+        f"SELECT hour, {columns} FROM file_hourly_contributions WHERE file_path = ?",
+        [str(file_path)],
+    ).fetchall()
+    previous = {row[0]: dict(zip(_HOURLY_FIELDS, row[1:])) for row in rows}
+    if not previous and primed:
+        previous = {hour: dict(sums) for hour, sums in fresh.items()}
+
+    zero = dict.fromkeys(_HOURLY_FIELDS, 0)
+    _merge_hourly(db, {
+        hour: {f: sums[f] - previous.get(hour, zero)[f] for f in _HOURLY_FIELDS}
+        for hour, sums in fresh.items()
+    })
+
+    db.execute("DELETE FROM file_hourly_contributions WHERE file_path = ?", [str(file_path)])
+    placeholders = ", ".join("?" for _ in range(len(_HOURLY_FIELDS) + 2))
+    for hour, sums in fresh.items():
+        db.execute(
+            f"INSERT INTO file_hourly_contributions (file_path, hour, {columns}) VALUES ({placeholders})",
+            [str(file_path), hour, *[sums[f] for f in _HOURLY_FIELDS]],
+        )
+
+
+def save_file_aggregate(
+    file_path: Path,
+    records: list[UsageRecord],
+    db_path: Path = DEFAULT_DB_PATH,
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+    hostname: str | None = None,
+) -> int:
+    """
+    Apply one transcript file's aggregate contribution as a DELTA.
+
+    Computes the file's fresh per-date sums, subtracts what the ledger says
+    the file previously contributed, applies the difference to
+    daily_snapshots, and stores the fresh sums back. A file already tracked
+    in file_metadata but absent from the ledger was parsed under the old
+    add-everything logic: its transition parse contributes zero (previous is
+    primed to fresh) so pre-ledger totals are never re-added.
+
+    Returns:
+        Number of assistant responses newly accounted for (delta)
+    """
+    init_database(db_path)
+    fresh = _aggregate_by_date(records)
+
+    conn = _connect(db_path)
+    try:
+        rows = conn.execute(
+            f"SELECT date, {', '.join(_CONTRIB_FIELDS)} FROM file_contributions WHERE file_path = ?",
+            [str(file_path)],
+        ).fetchall()
+        previous = {
+            row[0]: dict(zip(_CONTRIB_FIELDS, row[1:])) for row in rows
+        }
+        primed = False
+        if not previous:
+            tracked = conn.execute(
+                "SELECT 1 FROM file_metadata WHERE file_path = ?", [str(file_path)]
+            ).fetchone()
+            if tracked is not None:
+                previous = {date: dict(day) for date, day in fresh.items()}
+                primed = True
+
+        timestamp = datetime.now().isoformat()
+        new_responses = 0
+        for date, day in fresh.items():
+            prev = previous.get(date, dict.fromkeys(_CONTRIB_FIELDS, 0))
+            delta = {field: day[field] - prev[field] for field in _CONTRIB_FIELDS}
+            new_responses += max(delta["responses"], 0)
+            if any(delta.values()):
+                _apply_daily_delta(
+                    conn, date, delta, timestamp, device_id, device_name, device_type, hostname
+                )
+
+        conn.execute("DELETE FROM file_contributions WHERE file_path = ?", [str(file_path)])
+        for date, day in fresh.items():
+            conn.execute(
+                f"""
+                INSERT INTO file_contributions (file_path, date, {', '.join(_CONTRIB_FIELDS)})
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                """,
+                [str(file_path), date] + [day[field] for field in _CONTRIB_FIELDS],
+            )
+        _apply_file_model_mix(conn, file_path, records, primed)
+        _apply_file_hourly(conn, file_path, records, primed)
+        return new_responses
+    finally:
+        conn.close()
+
+
+def upsert_daily_snapshot(
+    date: str,
+    totals: dict[str, int],
+    db_path: Path = DEFAULT_DB_PATH,
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+    hostname: str | None = None,
+    models: dict[str, dict[str, int]] | None = None,
+) -> None:
+    """
+    Replace one day's daily_snapshots row and model split with the given totals.
+
+    Args:
+        date: Day to write (YYYY-MM-DD)
+        totals: Values keyed by prompts, responses, sessions, input_tokens,
+            output_tokens, cache_creation_tokens, cache_read_tokens (missing
+            keys are 0; total_tokens is derived)
+        db_path: Path to the DuckDB database file
+        models: Per-model split of totals for daily_model_snapshots
+            (default: all under "unknown")
+    """
+    require_duckdb()
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        conn.begin()
+        _write_daily_snapshot(
+            conn, date, totals, datetime.now().isoformat(), device_id, device_name, device_type, hostname
+        )
+        _write_day_model_mix(conn, date, totals, models, replace=True)
+        conn.commit()
+    finally:
+        conn.close()
+
+
+def increment_daily_snapshot(
+    date: str,
+    delta: dict[str, int],
+    db_path: Path = DEFAULT_DB_PATH,
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+    hostname: str | None = None,
+    models: dict[str, dict[str, int]] | None = None,
+) -> None:
+    """
+    Add a (possibly negative) delta to one day's daily_snapshots row and model split.
+
+    Creates the rows if missing; fields never drop below zero. Lets
+    hook-driven ingestion update a single day without scanning records.
+
+    Args:
+        date: Day to update (YYYY-MM-DD)
+        delta: Changes keyed like upsert_daily_snapshot's totals
+        db_path: Path to the DuckDB database file
+        models: Per-model split of delta for daily_model_snapshots
+            (default: all under "unknown")
+    """
+    require_duckdb()
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        conn.begin()
+        _apply_daily_delta(
+            conn, date, delta, datetime.now().isoformat(), device_id, device_name, device_type, hostname
+        )
+        _write_day_model_mix(conn, date, delta, models, replace=False)
+        conn.commit()
+    finally:
+        conn.close()
+
+
+def save_snapshot(
+    records: list[UsageRecord],
+    db_path: Path = DEFAULT_DB_PATH,
+    storage_mode: str = "aggregate",
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+    hostname: str | None = None,
+) -> int:
+    """
+    Save usage records to the DuckDB database as a snapshot.
+
+    Args:
+        records: List of usage records to save
+        db_path: Path to the DuckDB database file
+        storage_mode: "aggregate" (daily totals only) or "full" (individual records)
+        device_id: Device identifier for multi-device sync
+        device_name: Human-readable device name
+        device_type: Device type (macos, windows, linux)
+        hostname: Machine stamped on records that carry none
+
+    Returns:
+        Number of new records saved
+
+    Raises:
+        duckdb.Error: If database operation fails
+    """
+    require_duckdb()
+
+    if not records:
+        return 0
+
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    saved_count = 0
+
+    try:
+        # Save individual records only if in "full" mode
+        if storage_mode == "full":
+            # Bulk path: materialize the batch as an Arrow Table, register it
+            # as a DuckDB relation, and dedupe-INSERT in one statement against
+            # the UNIQUE(session_id, message_uuid) index. Arrow's columnar
+            # vectorized loader is ~50x faster than executemany() for 100k+
+            # row batches (executemany pays per-row Python<->C overhead).
+            #
+            # Soft-import pyarrow so the install footprint stays optional --
+            # fall back to a chunked executemany VALUES path if it's missing.
+            try:
+                import pyarrow as pa
+                use_arrow = True
+            except ImportError:
+                use_arrow = False
+
+            cols = {
+                "date": [], "timestamp": [], "session_id": [], "message_uuid": [],
+                "message_type": [], "model": [], "folder": [], "git_branch": [],
+                "version": [],
+                "input_tokens": [], "output_tokens": [],
+                "cache_creation_tokens": [], "cache_read_tokens": [], "total_tokens": [],
+                "cache_creation_1h_tokens": [], "thinking_tokens": [],
+                "is_subagent": [], "agent_id": [], "attachment_count": [],
+                "hostname": [], "project": [],
+            }
+            for record in records:
+                tu = record.token_usage
+                cols["date"].append(record.date_key)
+                cols["timestamp"].append(record.timestamp.isoformat())
+                cols["session_id"].append(record.session_id)
+                cols["message_uuid"].append(record.message_uuid)
+                cols["message_type"].append(record.message_type)
+                cols["model"].append(record.model)
+                cols["folder"].append(record.folder)
+                cols["git_branch"].append(record.git_branch)
+                cols["version"].append(record.version)
+                cols["input_tokens"].append(tu.input_tokens if tu else 0)
+                cols["output_tokens"].append(tu.output_tokens if tu else 0)
+                cols["cache_creation_tokens"].append(tu.cache_creation_tokens if tu else 0)
+                cols["cache_read_tokens"].append(tu.cache_read_tokens if tu else 0)
+                cols["total_tokens"].append(tu.total_tokens if tu else 0)
+                cols["cache_creation_1h_tokens"].append(tu.cache_creation_1h_tokens if tu else 0)
+                cols["thinking_tokens"].append(tu.thinking_tokens if tu else 0)
+                cols["is_subagent"].append(int(record.is_subagent))
+                cols["agent_id"].append(record.agent_id)
+                cols["attachment_count"].append(record.attachment_count)
+                cols["hostname"].append(record.hostname or hostname or None)
+                cols["project"].append(record.project)
+
+            conn.execute("""
+                CREATE OR REPLACE TEMP TABLE staging_records (
+                    date VARCHAR, timestamp VARCHAR,
+                    session_id VARCHAR, message_uuid VARCHAR,
+                    message_type VARCHAR, model VARCHAR,
+                    folder VARCHAR, git_branch VARCHAR, version VARCHAR,
+                    input_tokens INTEGER, output_tokens INTEGER,
+                    cache_creation_tokens INTEGER, cache_read_tokens INTEGER,
+                    total_tokens INTEGER, cache_creation_1h_tokens INTEGER,
+                    thinking_tokens INTEGER, is_subagent INTEGER,
+                    agent_id VARCHAR, attachment_count INTEGER,
+                    hostname VARCHAR, project VARCHAR
+                )
+            """)
+
+            if use_arrow:
+                arrow_table = pa.table(cols)
+                conn.register("staging_arrow", arrow_table)
+                conn.execute("INSERT INTO staging_records SELECT * FROM staging_arrow")
+                conn.unregister("staging_arrow")
+            else:
+                # Fallback: chunked executemany. Slower but works without pyarrow.
+                rows = list(zip(*cols.values()))
+                chunk = 500
+                for i in range(0, len(rows), chunk):
+                    batch = rows[i:i + chunk]
+                    conn.executemany(
+                        "INSERT INTO staging_records VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                        batch,
+                    )
+
+            before = conn.execute("SELECT COUNT(*) FROM usage_records").fetchone()[0]
+
+            # Upgrade path: a message first captured mid-stream carries
+            # partial usage; when a later batch brings the same billed
+            # response with larger totals, update the row in place (never
+            # downgrade).
+            conn.execute(
+                """
+                UPDATE usage_records
+                SET timestamp = b.timestamp,
+                    input_tokens = b.input_tokens,
+                    output_tokens = b.output_tokens,
+                    cache_creation_tokens = b.cache_creation_tokens,
+                    cache_read_tokens = b.cache_read_tokens,
+                    total_tokens = b.total_tokens,
+                    cache_creation_1h_tokens = b.cache_creation_1h_tokens,
+                    thinking_tokens = GREATEST(COALESCE(usage_records.thinking_tokens, 0), b.thinking_tokens)
+                FROM (
+                    SELECT * FROM staging_records s
+                    WHERE s.message_type = 'assistant'
+                    QUALIFY row_number() OVER (
+                        PARTITION BY s.message_uuid
+                        ORDER BY s.total_tokens DESC, s.timestamp DESC
+                    ) = 1
+                ) b
+                WHERE usage_records.message_type = 'assistant'
+                  AND usage_records.message_uuid = b.message_uuid
+                  AND b.total_tokens > usage_records.total_tokens
+                """
+            )
+
+            # Insert gate: assistant rows dedupe GLOBALLY on the billed
+            # response id (session forks replay identical responses under new
+            # session ids); user rows stay session-scoped. Within the batch,
+            # the max-usage duplicate wins.
+            conn.execute(
+                """
+                INSERT INTO usage_records (
+                    id, date, timestamp, session_id, message_uuid, message_type,
+                    model, folder, git_branch, version,
+                    input_tokens, output_tokens,
+                    cache_creation_tokens, cache_read_tokens, total_tokens,
+                    cache_creation_1h_tokens, thinking_tokens,
+                    is_subagent, agent_id, attachment_count,
+                    device_id, device_name, device_type, hostname, project
+                )
+                SELECT
+                    nextval('usage_records_id_seq'),
+                    s.date, s.timestamp, s.session_id, s.message_uuid, s.message_type,
+                    s.model, s.folder, s.git_branch, s.version,
+                    s.input_tokens, s.output_tokens,
+                    s.cache_creation_tokens, s.cache_read_tokens, s.total_tokens,
+                    s.cache_creation_1h_tokens, s.thinking_tokens,
+                    s.is_subagent, s.agent_id, s.attachment_count,
+                    ?, ?, ?, s.hostname, s.project
+                FROM staging_records s
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM usage_records u
+                    WHERE u.message_uuid = s.message_uuid
+                      AND (s.message_type = 'assistant' OR u.session_id = s.session_id)
+                )
+                QUALIFY row_number() OVER (
+                    PARTITION BY CASE WHEN s.message_type = 'assistant'
+                                      THEN s.message_uuid
+                                      ELSE s.session_id || '|' || s.message_uuid END
+                    ORDER BY s.total_tokens DESC, s.timestamp DESC
+                ) = 1
+                """,
+                [device_id, device_name, device_type],
+            )
+            saved_count = conn.execute("SELECT COUNT(*) FROM usage_records").fetchone()[0] - before
+
+            # Rebuild daily snapshots only for dates present in this batch.
+            # Dates outside the batch keep their existing rows, preserving
+            # history for days whose JSONL files have aged out. One set-based
+            # upsert replaces the previous per-date query loop.
+            timestamp = datetime.now().isoformat()
+            conn.execute("""
+                INSERT OR REPLACE INTO daily_snapshots (
+                    date, total_prompts, total_responses, total_sessions, total_tokens,
+                    input_tokens, output_tokens, cache_creation_tokens,
+                    cache_read_tokens, snapshot_timestamp,
+                    device_id, device_name, device_type, hostname
+                )
+                SELECT
+                    u.date,
+                    SUM(CASE WHEN u.message_type = 'user' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN u.message_type = 'assistant' THEN 1 ELSE 0 END),
+                    COUNT(DISTINCT u.session_id),
+                    COALESCE(SUM(u.total_tokens), 0),
+                    COALESCE(SUM(u.input_tokens), 0),
+                    COALESCE(SUM(u.output_tokens), 0),
+                    COALESCE(SUM(u.cache_creation_tokens), 0),
+                    COALESCE(SUM(u.cache_read_tokens), 0),
+                    ?, ?, ?, ?, GROUP_CONCAT(DISTINCT u.hostname)
+                FROM usage_records u
+                WHERE u.date IN (SELECT DISTINCT date FROM staging_records)
+                GROUP BY u.date
+            """, [timestamp, device_id, device_name, device_type])
+
+            batch_dates = "AND date IN (SELECT DISTINCT date FROM staging_records)"
+            conn.execute(f"DELETE FROM daily_model_snapshots WHERE 1=1 {batch_dates}")
+            conn.execute(_MODEL_MIX_REBUILD_SQL.format(where=batch_dates))
+            _rebuild_hourly_snapshots(conn, [record.date_key for record in records])
+
+            conn.execute("DROP TABLE staging_records")
+        else:
+            # Aggregate mode
+            from collections import defaultdict
+            daily_aggregates = defaultdict(lambda: {
+                "prompts": 0,
+                "responses": 0,
+                "sessions": set(),
+                "input_tokens": 0,
+                "output_tokens": 0,
+                "cache_creation_tokens": 0,
+                "cache_read_tokens": 0,
+                "total_tokens": 0,
+            })
+
+            for record in records:
+                date_key = record.date_key
+                daily_aggregates[date_key]["sessions"].add(record.session_id)
+
+                if record.is_user_prompt:
+                    daily_aggregates[date_key]["prompts"] += 1
+                elif record.is_assistant_response:
+                    daily_aggregates[date_key]["responses"] += 1
+
+                if record.token_usage:
+                    daily_aggregates[date_key]["input_tokens"] += record.token_usage.input_tokens
+                    daily_aggregates[date_key]["output_tokens"] += record.token_usage.output_tokens
+                    daily_aggregates[date_key]["cache_creation_tokens"] += record.token_usage.cache_creation_tokens
+                    daily_aggregates[date_key]["cache_read_tokens"] += record.token_usage.cache_read_tokens
+                    daily_aggregates[date_key]["total_tokens"] += record.token_usage.total_tokens
+
+            timestamp = datetime.now().isoformat()
+            for date_key, agg in daily_aggregates.items():
+                existing = conn.execute("""
+                    SELECT total_prompts, total_responses, total_sessions, total_tokens,
+                           input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens
+                    FROM daily_snapshots WHERE date = ?
+                """, [date_key]).fetchone()
+
+                if existing:
+                    conn.execute("""
+                        INSERT OR REPLACE INTO daily_snapshots (
+                            date, total_prompts, total_responses, total_sessions, total_tokens,
+                            input_tokens, output_tokens, cache_creation_tokens,
+                            cache_read_tokens, snapshot_timestamp,
+                            device_id, device_name, device_type, hostname
+                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    """, [
+                        date_key,
+                        existing[0] + agg["prompts"],
+                        existing[1] + agg["responses"],
+                        existing[2] + len(agg["sessions"]),
+                        existing[3] + agg["total_tokens"],
+                        existing[4] + agg["input_tokens"],
+                        existing[5] + agg["output_tokens"],
+                        existing[6] + agg["cache_creation_tokens"],
+                        existing[7] + agg["cache_read_tokens"],
+                        timestamp,
+                        device_id,
+                        device_name,
+                        device_type,
+                        hostname or None,
+                    ])
+                else:
+                    conn.execute("""
+                        INSERT INTO daily_snapshots (
+                            date, total_prompts, total_responses, total_sessions, total_tokens,
+                            input_tokens, output_tokens, cache_creation_tokens,
+                            cache_read_tokens, snapshot_timestamp,
+                            device_id, device_name, device_type, hostname
+                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    """, [
+                        date_key,
+                        agg["prompts"],
+                        agg["responses"],
+                        len(agg["sessions"]),
+                        agg["total_tokens"],
+                        agg["input_tokens"],
+                        agg["output_tokens"],
+                        agg["cache_creation_tokens"],
+                        agg["cache_read_tokens"],
+                        timestamp,
+                        device_id,
+                        device_name,
+                        device_type,
+                        hostname or None,
+                    ])
+                saved_count += 1
+
+            _merge_model_mix(conn, _aggregate_by_date_model(records))
+            _merge_hourly(conn, _aggregate_by_hour(records))
+
+    finally:
+        conn.close()
+
+    return saved_count
+
+
+# Rows fetched per round trip when streaming usage_records
+HISTORY_BATCH_SIZE = 5000
+
+_RECORD_COLUMNS = (
+    "timestamp, session_id, message_uuid, message_type, model, folder, "
+    "git_branch, version, input_tokens, output_tokens, cache_creation_tokens, "
+    "cache_read_tokens, cache_creation_1h_tokens, thinking_tokens, "
+    "is_subagent, agent_id, attachment_count, hostname, project"
+)
+
+
+def _history_query(
+    start_date: str | None,
+    end_date: str | None,
+    folder: str | None,
+    model: str | None,
+    limit: int | None,
+    offset: int,
+) -> tuple[str, list]:
+    """Filtered usage_records SELECT shared by both backends."""
+    query = f"SELECT {_RECORD_COLUMNS} FROM usage_records WHERE 1=1"
+    params: list = []
+    for clause, value in (
+        ("date >= ?", start_date),
+        ("date <= ?", end_date),
+        ("folder = ?", folder),
+        ("model = ?", model),
+    ):
+        if value:
+            query += f" AND {clause}"
+            params.append(value)
+    query += " ORDER BY date, timestamp"
+    if limit is not None:
+        query += " LIMIT ? OFFSET ?"
+        params.extend([limit, offset])
+    return query, params
+
+
+def _row_to_record(row: tuple) -> UsageRecord:
+    """Build a UsageRecord from a _RECORD_COLUMNS row."""
+    token_usage = None
+    if (row[8] or 0) > 0 or (row[9] or 0) > 0:
+        token_usage = TokenUsage(
+            input_tokens=row[8],
+            output_tokens=row[9],
+            cache_creation_tokens=row[10],
+            cache_read_tokens=row[11],
+            cache_creation_1h_tokens=row[12] or 0,
+            thinking_tokens=row[13] or 0,
+        )
+    return UsageRecord(
+        timestamp=datetime.fromisoformat(row[0]),
+        session_id=row[1],
+        message_uuid=row[2],
+        message_type=row[3],
+        model=row[4],
+        folder=row[5],
+        git_branch=row[6],
+        version=row[7],
+        token_usage=token_usage,
+        is_subagent=bool(row[14]),
+        agent_id=row[15],
+        attachment_count=row[16] or 0,
+        hostname=row[17],
+        project=row[18],
+    )
+
+
+def iter_historical_records(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    folder: str | None = None,
+    model: str | None = None,
+    limit: int | None = None,
+    offset: int = 0,
+    db_path: Path = DEFAULT_DB_PATH,
+    batch_size: int = HISTORY_BATCH_SIZE,
+) -> Iterator[UsageRecord]:
+    """
+    Stream usage records in batches instead of materializing the table.
+
+    Args:
+        start_date: Optional start date (YYYY-MM-DD, inclusive)
+        end_date: Optional end date (YYYY-MM-DD, inclusive)
+        folder: Only records from this project folder
+        model: Only records for this model
+        limit: Maximum number of records (a page size)
+        offset: Records to skip before the page; only applies with limit
+        db_path: Path to the DuckDB database file
+        batch_size: Rows fetched per round trip
+
+    Yields:
+        UsageRecord objects ordered by date and timestamp
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return
+
+    conn = _connect(db_path)
+    try:
+        query, params = _history_query(start_date, end_date, folder, model, limit, offset)
+        result = conn.execute(query, params)
+        while True:
+            rows = result.fetchmany(batch_size)
+            if not rows:
+                break
+            for row in rows:
+                yield _row_to_record(row)
+    finally:
+        conn.close()
+
+
+def load_historical_records(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+    folder: str | None = None,
+    model: str | None = None,
+    limit: int | None = None,
+    offset: int = 0,
+) -> list[UsageRecord]:
+    """
+    Load historical usage records from the DuckDB database.
+
+    Prefer iter_historical_records for large ranges; this materializes the
+    whole result.
+
+    Args:
+        start_date: Optional start date (YYYY-MM-DD)
+        end_date: Optional end date (YYYY-MM-DD)
+        db_path: Path to the DuckDB database file
+        folder: Only records from this project folder
+        model: Only records for this model
+        limit: Maximum number of records
+        offset: Records to skip; only applies with limit
+
+    Returns:
+        List of UsageRecord objects
+    """
+    return list(iter_historical_records(
+        start_date, end_date, folder=folder, model=model,
+        limit=limit, offset=offset, db_path=db_path,
+    ))
+
+
+def load_daily_snapshots(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Load daily totals with per-model splits and cost, without usage_records.
+
+    Returns:
+        One dict per day (date, prompts, responses, sessions, token fields,
+        models: {model: token fields}, cost), ordered by date
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_daily_snapshots(conn, start_date, end_date)
+    finally:
+        conn.close()
+
+
+def get_project_activity(db_path: Path = DEFAULT_DB_PATH) -> list[dict]:
+    """
+    Lifetime usage and last activity date per project folder.
+
+    Returns:
+        One dict per project (folder, first_date, last_date, active_days,
+        sessions, prompts, total_tokens, cost), most recently active first
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_project_activity(conn)
+    finally:
+        conn.close()
+
+
+def get_project_tokens(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> dict[str, int]:
+    """
+    Total tokens per project folder within a date range.
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return {}
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_project_tokens(conn, start_date, end_date)
+    finally:
+        conn.close()
+
+
+def get_daily_projects(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> dict[str, dict[str, dict]]:
+    """
+    Tokens and estimated cost per project for each day.
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return {}
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_daily_projects(conn, start_date, end_date)
+    finally:
+        conn.close()
+
+
+def get_branch_stats(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Tokens and estimated cost per project and git branch (see _read_branch_stats).
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_branch_stats(conn, start_date, end_date)
+    finally:
+        conn.close()
+
+
+def get_host_stats(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Tokens and estimated cost per machine (see _read_host_stats).
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_host_stats(conn, start_date, end_date)
+    finally:
+        conn.close()
+
+
+def get_hourly_snapshots(
+    hours: int = 24,
+    now: datetime | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Per-hour totals for the last N hours, including the current one.
+
+    Returns:
+        One dict per hour with usage (hour as a UTC ISO string, prompts,
+        responses, token fields), oldest first
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_hourly_snapshots(conn, hours, now)
+    finally:
+        conn.close()
+
+
+def get_hourly_range(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Per-hour totals for every hour overlapping a local date range.
+
+    Returns:
+        One dict per hour with usage (hour as a UTC ISO string, prompts,
+        responses, token fields), oldest first
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_hourly_range(conn, start_date, end_date)
+    finally:
+        conn.close()
+
+
+def preview_ingest(
+    files: dict[str, list[UsageRecord]],
+    storage_mode: str,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> dict:
+    """
+    Count the records, dates and projects an ingest would add (read-only).
+
+    Like the SQLite backend, the database is not initialized first.
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return _preview_ingest(None, files, storage_mode)
+
+    conn = _connect(db_path, read_only=True)
+    try:
+        return _preview_ingest(conn, files, storage_mode)
+    finally:
+        conn.close()
+
+
+_SUMMARY_COLUMNS = ("month", "generated_at", "summary")
+
+
+def save_monthly_summary(month: str, summary: dict, db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Store a finished month's recap facts (see src.aggregation.recap).
+
+    Args:
+        month: Month in YYYY-MM format
+        summary: JSON-serializable recap facts
+        db_path: Path to the DuckDB database file
+    """
+    require_duckdb()
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    try:
+        conn.execute(
+            f"INSERT OR REPLACE INTO monthly_summaries ({', '.join(_SUMMARY_COLUMNS)}) VALUES (?, ?, ?)",
+            [month, datetime.now().isoformat(), json.dumps(summary)],
+        )
+    finally:
+        conn.close()
+
+
+def get_monthly_summary(month: str, db_path: Path = DEFAULT_DB_PATH) -> dict | None:
+    """
+    Stored recap facts for a month, or None if not summarized yet.
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return None
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        row = conn.execute("SELECT summary FROM monthly_summaries WHERE month = ?", [month]).fetchone()
+        return json.loads(row[0]) if row else None
+    finally:
+        conn.close()
+
+
+def _read_session_tags(db) -> dict[str, list[str]]:
+    """Session id -> its tags, alphabetically."""
+    tags: dict[str, list[str]] = {}
+    for session_id, tag in db.execute("SELECT session_id, tag FROM session_tags ORDER BY tag").fetchall():
+        tags.setdefault(session_id, []).append(tag)
+    return tags
+
+
+def add_session_tags(session_id: str, tags: list[str], db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Attach tags to a session (existing tags are kept).
+    """
+    require_duckdb()
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    try:
+        now = datetime.now().isoformat()
+        conn.executemany(
+            "INSERT OR IGNORE INTO session_tags (session_id, tag, created_at) VALUES (?, ?, ?)",
+            [[session_id, tag, now] for tag in tags],
+        )
+    finally:
+        conn.close()
+
+
+def remove_session_tags(session_id: str, tags: list[str] | None = None, db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Detach tags from a session; None removes all of them.
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        where, params = "session_id = ?", [session_id]
+        if tags is not None:
+            where += f" AND tag IN ({', '.join('?' for _ in tags)})"
+            params.extend(tags)
+        count = conn.execute(f"SELECT COUNT(*) FROM session_tags WHERE {where}", params).fetchone()[0]
+        conn.execute(f"DELETE FROM session_tags WHERE {where}", params)
+        return count
+    finally:
+        conn.close()
+
+
+def get_session_tags(db_path: Path = DEFAULT_DB_PATH) -> dict[str, list[str]]:
+    """
+    Tags of every tagged session.
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return {}
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_session_tags(conn)
+    finally:
+        conn.close()
+
+
+def _read_annotations(db, start_date: str | None = None, end_date: str | None = None) -> dict[str, list[str]]:
+    """Date -> its annotation labels in the order they were added, optionally within a window."""
+    where, params = [], []
+    if start_date:
+        where.append("date >= ?")
+        params.append(start_date)
+    if end_date:
+        where.append("date <= ?")
+        params.append(end_date)
+    clause = f" WHERE {' AND '.join(where)}" if where else ""
+    annotations: dict[str, list[str]] = {}
+    rows = db.execute(f"SELECT date, label FROM annotations{clause} ORDER BY date, created_at, label", params)
+    for date, label in rows.fetchall():
+        annotations.setdefault(date, []).append(label)
+    return annotations
+
+
+def add_annotation(date: str, label: str, db_path: Path = DEFAULT_DB_PATH) -> bool:
+    """
+    Annotate a day; False when the day already has this label.
+    """
+    require_duckdb()
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    try:
+        exists = conn.execute("SELECT 1 FROM annotations WHERE date = ? AND label = ?", [date, label]).fetchone()
+        if exists:
+            return False
+        conn.execute(
+            "INSERT INTO annotations (date, label, created_at) VALUES (?, ?, ?)",
+            [date, label, datetime.now().isoformat()],
+        )
+        return True
+    finally:
+        conn.close()
+
+
+def remove_annotations(date: str, label: str | None = None, db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Remove a day's annotation; None removes all of them.
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        where, params = "date = ?", [date]
+        if label is not None:
+            where += " AND label = ?"
+            params.append(label)
+        count = conn.execute(f"SELECT COUNT(*) FROM annotations WHERE {where}", params).fetchone()[0]
+        conn.execute(f"DELETE FROM annotations WHERE {where}", params)
+        return count
+    finally:
+        conn.close()
+
+
+def get_annotations(
+    start_date: str | None = None, end_date: str | None = None, db_path: Path = DEFAULT_DB_PATH
+) -> dict[str, list[str]]:
+    """
+    Annotations by date, optionally within a window.
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return {}
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_annotations(conn, start_date, end_date)
+    finally:
+        conn.close()
+
+
+def _read_thinking_stats(db, pricing_rows: list[tuple]) -> dict:
+    """
+    Output tokens, the thinking share of them, and its estimated cost.
+
+    Thinking bills at the output rate, so its cost is priced as output-only
+    usage (shared by both backends).
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        pricing_rows: model_pricing rows as passed to price_model_usage
+
+    Returns:
+        Dict with output_tokens, thinking_tokens, thinking_cost
+    """
+    rows = db.execute(f"""
+        SELECT model, SUM(output_tokens), SUM(COALESCE(thinking_tokens, 0)), {LONG_CONTEXT_SQL}
+        FROM usage_records
+        WHERE model IS NOT NULL
+        GROUP BY model, {LONG_CONTEXT_SQL}
+    """).fetchall()
+    thinking_cost, _ = price_model_usage(
+        [(model, 0, thinking or 0, 0, 0, 0, long_context) for model, _, thinking, long_context in rows],
+        pricing_rows,
+    )
+    return {
+        "output_tokens": sum(row[1] or 0 for row in rows),
+        "thinking_tokens": sum(row[2] or 0 for row in rows),
+        "thinking_cost": sum(thinking_cost.values()),
+    }
+
+
+def _read_subagent_stats(db, pricing_rows: list[tuple]) -> dict:
+    """
+    Tokens and estimated cost of subagent (sidechain) work.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        pricing_rows: model_pricing rows as passed to price_model_usage
+
+    Returns:
+        Dict with subagent_tokens, subagent_cost, and subagent_sessions
+        (sessions that delegated to subagents)
+    """
+    rows = db.execute(f"""
+        SELECT model, SUM(input_tokens), SUM(output_tokens),
+               SUM(cache_creation_tokens), SUM(cache_read_tokens),
+               SUM(COALESCE(cache_creation_1h_tokens, 0)), SUM(total_tokens),
+               {LONG_CONTEXT_SQL}
+        FROM usage_records
+        WHERE is_subagent = 1 AND model IS NOT NULL
+        GROUP BY model, {LONG_CONTEXT_SQL}
+    """).fetchall()
+    sessions = db.execute(
+        "SELECT COUNT(DISTINCT session_id) FROM usage_records WHERE is_subagent = 1"
+    ).fetchone()[0]
+    cost, _ = price_model_usage([(*row[:6], row[7]) for row in rows], pricing_rows)
+    return {
+        "subagent_tokens": sum(row[6] or 0 for row in rows),
+        "subagent_cost": sum(cost.values()),
+        "subagent_sessions": sessions or 0,
+    }
+
+
+def _read_attachment_stats(db) -> dict:
+    """
+    Attachments on user prompts per day, next to that day's input tokens.
+
+    Input tokens include cache writes and reads, where pasted images mostly
+    land after their first turn.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+
+    Returns:
+        Dict with total_attachments, attachment_days, and attachments_by_day
+        ((date, attachments, input tokens) for every tracked day, by date)
+    """
+    rows = db.execute("""
+        SELECT date, SUM(COALESCE(attachment_count, 0)),
+               SUM(input_tokens + cache_creation_tokens + cache_read_tokens)
+        FROM usage_records
+        GROUP BY date
+        ORDER BY date
+    """).fetchall()
+    by_day = [(date, attachments or 0, tokens or 0) for date, attachments, tokens in rows]
+    return {
+        "total_attachments": sum(attachments for _, attachments, _ in by_day),
+        "attachment_days": sum(1 for _, attachments, _ in by_day if attachments),
+        "attachments_by_day": by_day,
+    }
+
+
+def get_database_stats(db_path: Path = DEFAULT_DB_PATH) -> dict:
+    """
+    Get statistics about the historical database.
+
+    Args:
+        db_path: Path to the DuckDB database file
+
+    Returns:
+        Dictionary with database statistics
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return {
+            "total_records": 0,
+            "total_days": 0,
+            "oldest_date": None,
+            "newest_date": None,
+            "newest_timestamp": None,
+            "total_tokens": 0,
+            "total_prompts": 0,
+            "total_responses": 0,
+            "total_sessions": 0,
+            "tokens_by_model": {},
+            "cost_by_model": {},
+            "unpriced_models": [],
+            "total_cost": 0.0,
+            "avg_tokens_per_session": 0,
+            "avg_tokens_per_response": 0,
+            "avg_cost_per_session": 0.0,
+            "avg_cost_per_response": 0.0,
+            "output_tokens": 0,
+            "thinking_tokens": 0,
+            "thinking_cost": 0.0,
+            "subagent_tokens": 0,
+            "subagent_cost": 0.0,
+            "subagent_sessions": 0,
+            "total_attachments": 0,
+            "attachment_days": 0,
+            "attachments_by_day": [],
+        }
+
+    conn = _connect(db_path)
+
+    try:
+        # Basic counts
+        total_records = conn.execute("SELECT COUNT(*) FROM usage_records").fetchone()[0]
+        total_days = conn.execute("SELECT COUNT(DISTINCT date) FROM usage_records").fetchone()[0]
+
+        date_range = conn.execute("SELECT MIN(date), MAX(date) FROM usage_records").fetchone()
+        oldest_date, newest_date = date_range
+
+        newest_timestamp = conn.execute(
+            "SELECT MAX(snapshot_timestamp) FROM daily_snapshots"
+        ).fetchone()[0]
+
+        # Aggregate statistics
+        agg_row = conn.execute("""
+            SELECT
+                SUM(total_tokens) as total_tokens,
+                SUM(total_prompts) as total_prompts,
+                SUM(total_responses) as total_responses,
+                SUM(total_sessions) as total_sessions
+            FROM daily_snapshots
+        """).fetchone()
+
+        total_tokens = agg_row[0] or 0
+        total_prompts = agg_row[1] or 0
+        total_responses = agg_row[2] or 0
+        total_sessions = agg_row[3] or 0
+
+        # Tokens by model
+        tokens_by_model = {}
+        if total_records > 0:
+            model_rows = conn.execute("""
+                SELECT model, SUM(total_tokens) as tokens
+                FROM usage_records
+                WHERE model IS NOT NULL
+                GROUP BY model
+                ORDER BY tokens DESC
+            """).fetchall()
+            tokens_by_model = {row[0]: row[1] for row in model_rows if row[0]}
+
+        # Calculate costs (family-aware pricing resolution)
+        total_cost = 0.0
+        cost_by_model = {}
+        unpriced_models = []
+        thinking = {"output_tokens": 0, "thinking_tokens": 0, "thinking_cost": 0.0}
+        subagents = {"subagent_tokens": 0, "subagent_cost": 0.0, "subagent_sessions": 0}
+        attachments = {"total_attachments": 0, "attachment_days": 0, "attachments_by_day": []}
+
+        if total_records > 0:
+            usage_rows = conn.execute(f"""
+                SELECT
+                    model,
+                    SUM(input_tokens),
+                    SUM(output_tokens),
+                    SUM(cache_creation_tokens),
+                    SUM(cache_read_tokens),
+                    SUM(COALESCE(cache_creation_1h_tokens, 0)),
+                    {LONG_CONTEXT_SQL}
+                FROM usage_records
+                WHERE model IS NOT NULL
+                GROUP BY model, {LONG_CONTEXT_SQL}
+            """).fetchall()
+            pricing_rows = conn.execute("""
+                SELECT model_name, input_price_per_mtok, output_price_per_mtok,
+                       cache_write_price_per_mtok, cache_read_price_per_mtok,
+                       cache_write_1h_price_per_mtok
+                FROM model_pricing
+            """).fetchall()
+            cost_by_model, unpriced_models = price_model_usage(usage_rows, pricing_rows)
+            total_cost = sum(cost_by_model.values())
+            thinking = _read_thinking_stats(conn, pricing_rows)
+            subagents = _read_subagent_stats(conn, pricing_rows)
+            attachments = _read_attachment_stats(conn)
+        else:
+            # Aggregate mode: the day totals give the date range, and the
+            # per-day model rollup still splits tokens and cost by model
+            total_days, oldest_date, newest_date = conn.execute(_SNAPSHOT_RANGE_SQL).fetchone()
+            mix_rows = conn.execute(_MODEL_MIX_TOTALS_SQL).fetchall()
+            tokens_by_model = {row[0]: row[6] for row in mix_rows if row[6]}
+            cost_by_model, unpriced_models = price_model_usage(
+                [row[:6] for row in mix_rows], conn.execute(_PRICING_ROWS_SQL).fetchall()
+            )
+            total_cost = sum(cost_by_model.values())
+
+        avg_tokens_per_session = total_tokens / total_sessions if total_sessions > 0 else 0
+        avg_tokens_per_response = total_tokens / total_responses if total_responses > 0 else 0
+        avg_cost_per_session = total_cost / total_sessions if total_sessions > 0 else 0
+        avg_cost_per_response = total_cost / total_responses if total_responses > 0 else 0
+
+        return {
+            "total_records": total_records,
+            "total_days": total_days,
+            "oldest_date": oldest_date,
+            "newest_date": newest_date,
+            "newest_timestamp": newest_timestamp,
+            "total_tokens": total_tokens,
+            "total_prompts": total_prompts,
+            "total_responses": total_responses,
+            "total_sessions": total_sessions,
+            "tokens_by_model": tokens_by_model,
+            "cost_by_model": cost_by_model,
+            "unpriced_models": unpriced_models,
+            "total_cost": total_cost,
+            "avg_tokens_per_session": round(avg_tokens_per_session),
+            "avg_tokens_per_response": round(avg_tokens_per_response),
+            "avg_cost_per_session": round(avg_cost_per_session, 2),
+            "avg_cost_per_response": round(avg_cost_per_response, 4),
+            **thinking,
+            **subagents,
+            **attachments,
+        }
+    finally:
+        conn.close()
+
+
+def get_stale_files(
+    all_files: list[Path],
+    db_path: Path = DEFAULT_DB_PATH
+) -> tuple[list[Path], list[str]]:
+    """
+    Identify which JSONL files need to be re-parsed.
+
+    Args:
+        all_files: List of all JSONL file paths
+        db_path: Path to the DuckDB database file
+
+    Returns:
+        Tuple of (stale_files, deleted_file_paths)
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return (all_files, [])
+
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    stale_files = []
+    deleted_files = []
+
+    try:
+        rows = conn.execute(
+            "SELECT file_path, mtime_ns, size_bytes FROM file_metadata"
+        ).fetchall()
+        stored_metadata = {row[0]: (row[1], row[2]) for row in rows}
+
+        current_file_paths = {str(f) for f in all_files}
+
+        for file_path in all_files:
+            path_str = str(file_path)
+            try:
+                stat = file_path.stat()
+                current_mtime_ns = stat.st_mtime_ns
+                current_size = stat.st_size
+
+                if path_str not in stored_metadata:
+                    stale_files.append(file_path)
+                else:
+                    stored_mtime_ns, stored_size = stored_metadata[path_str]
+                    if current_mtime_ns != stored_mtime_ns or current_size != stored_size:
+                        stale_files.append(file_path)
+            except OSError:
+                continue
+
+        for stored_path in stored_metadata.keys():
+            if stored_path not in current_file_paths:
+                deleted_files.append(stored_path)
+
+        return (stale_files, deleted_files)
+    finally:
+        conn.close()
+
+
+def update_files_metadata(
+    file_paths: list[Path],
+    record_count: int = 0,
+    db_path: Path = DEFAULT_DB_PATH,
+    stats: dict[str, tuple[int, int]] | None = None,
+) -> None:
+    """
+    Upsert parse metadata for many files in a single connection.
+
+    stats maps file path -> (mtime_ns, size_bytes) captured BEFORE parsing;
+    without it the file is re-statted here, which can silently mark bytes
+    appended mid-ingest as seen. Files that fail to stat are skipped.
+    """
+    require_duckdb()
+
+    if not file_paths:
+        return
+
+    init_database(db_path)
+
+    timestamp = datetime.now().isoformat()
+    rows = []
+    for file_path in file_paths:
+        pre = (stats or {}).get(str(file_path))
+        if pre is not None:
+            mtime_ns, size_bytes = pre
+        else:
+            try:
+                stat = file_path.stat()
+            except OSError:
+                continue
+            mtime_ns, size_bytes = stat.st_mtime_ns, stat.st_size
+        rows.append([str(file_path), mtime_ns, size_bytes, record_count, timestamp])
+
+    if not rows:
+        return
+
+    conn = _connect(db_path)
+    try:
+        conn.executemany("""
+            INSERT OR REPLACE INTO file_metadata (
+                file_path, mtime_ns, size_bytes, record_count, last_parsed
+            ) VALUES (?, ?, ?, ?, ?)
+        """, rows)
+    finally:
+        conn.close()
+
+
+def remove_deleted_file_metadata(
+    deleted_paths: list[str],
+    db_path: Path = DEFAULT_DB_PATH
+) -> None:
+    """
+    Remove metadata for files that no longer exist.
+    """
+    require_duckdb()
+
+    if not deleted_paths or not db_path.exists():
+        return
+
+    conn = _connect(db_path)
+
+    try:
+        conn.executemany(
+            "DELETE FROM file_metadata WHERE file_path = ?",
+            [(path,) for path in deleted_paths],
+        )
+        conn.executemany(
+            "DELETE FROM ingestion_state WHERE file_path = ?",
+            [(path,) for path in deleted_paths],
+        )
+    finally:
+        conn.close()
+
+
+def clone_model_pricing(
+    model_name: str,
+    like_model: str,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> str:
+    """
+    Add (or replace) a pricing row for a model by copying another model's rates.
+
+    Raises:
+        ValueError: If like_model matches no pricing row
+    """
+    require_duckdb()
+    init_database(db_path)
+    conn = _connect(db_path)
+
+    try:
+        names = [row[0] for row in conn.execute("SELECT model_name FROM model_pricing").fetchall()]
+        source = match_pricing_model(like_model, names)
+        if source is None:
+            raise ValueError(f"No pricing found for '{like_model}'")
+
+        conn.execute("""
+            INSERT OR REPLACE INTO model_pricing (
+                model_name, input_price_per_mtok, output_price_per_mtok,
+                cache_write_price_per_mtok, cache_read_price_per_mtok,
+                cache_write_1h_price_per_mtok, last_updated, notes
+            )
+            SELECT ?, input_price_per_mtok, output_price_per_mtok,
+                   cache_write_price_per_mtok, cache_read_price_per_mtok,
+                   cache_write_1h_price_per_mtok, ?, ?
+            FROM model_pricing
+            WHERE model_name = ?
+        """, [model_name, datetime.now().isoformat(), f"Priced like {source}", source])
+        return source
+    finally:
+        conn.close()
+
+
+def sync_model_pricing(db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Apply bundled and fetched remote pricing to model_pricing now.
+
+    init_database does this once per process; call it after `ccg pricing
+    update`.
+
+    Returns:
+        Rows written
+    """
+    require_duckdb()
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    try:
+        _sync_model_pricing(conn)
+        return len(with_remote_pricing(BUNDLED_PRICING_ROWS))
+    finally:
+        conn.close()
+
+
+def get_model_pricing(db_path: Path = DEFAULT_DB_PATH) -> list[tuple]:
+    """
+    Current pricing rows, including any added with `ccg pricing add`.
+
+    Returns:
+        List of (model_name, input, output, cache_write, cache_read, cache_write_1h)
+    """
+    require_duckdb()
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return conn.execute(_PRICING_ROWS_SQL).fetchall()
+    finally:
+        conn.close()
+
+
+def get_update_coverage(db_path: Path = DEFAULT_DB_PATH) -> dict:
+    """
+    Get record count and date bounds of usage_records.
+
+    Cheap alternative to get_database_stats for flows that only need
+    coverage, not token/cost aggregates.
+
+    Returns:
+        Dict with total_records, oldest_date, newest_date
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return {"total_records": 0, "oldest_date": None, "newest_date": None}
+
+    conn = _connect(db_path, read_only=True)
+    try:
+        row = conn.execute(
+            "SELECT COUNT(*), MIN(date), MAX(date) FROM usage_records"
+        ).fetchone()
+        return {"total_records": row[0] or 0, "oldest_date": row[1], "newest_date": row[2]}
+    finally:
+        conn.close()
+
+
+def get_database_health(db_path: Path = DEFAULT_DB_PATH) -> dict:
+    """
+    Structural checks for `ccg doctor`.
+
+    DuckDB checksums its blocks on read and this schema has no secondary
+    indexes, so only orphaned snapshots are looked for.
+
+    Returns:
+        Dict with integrity_errors and missing_indexes (always empty) and
+        orphaned_snapshot_days (daily_snapshots days with tokens but no
+        usage_records)
+    """
+    require_duckdb()
+
+    conn = _connect(db_path, read_only=True)
+    try:
+        orphaned = conn.execute("""
+            SELECT COUNT(*) FROM daily_snapshots
+            WHERE total_tokens > 0
+              AND NOT EXISTS (SELECT 1 FROM usage_records WHERE usage_records.date = daily_snapshots.date)
+        """).fetchone()[0]
+    finally:
+        conn.close()
+    return {"integrity_errors": [], "missing_indexes": [], "orphaned_snapshot_days": orphaned}
+
+
+def delete_session_rows(
+    session_ids: list[str],
+    device_id: str | None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[str]:
+    """
+    Delete usage_records for the given sessions (scoped to one device).
+
+    Returns:
+        Distinct dates the deleted rows covered
+    """
+    require_duckdb()
+    if not session_ids:
+        return []
+    init_database(db_path)
+    placeholders = ", ".join("?" for _ in session_ids)
+    device_clause = "device_id = ?" if device_id is not None else "device_id IS NULL"
+    params: list[str] = list(session_ids) + ([device_id] if device_id is not None else [])
+    conn = _connect(db_path)
+    try:
+        dates = [
+            row[0] for row in conn.execute(
+                f"SELECT DISTINCT date FROM usage_records "
+                f"WHERE session_id IN ({placeholders}) AND {device_clause}",
+                params,
+            ).fetchall()
+        ]
+        conn.execute(
+            f"DELETE FROM usage_records WHERE session_id IN ({placeholders}) AND {device_clause}",
+            params,
+        )
+        return dates
+    finally:
+        conn.close()
+
+
+# Replaces daily_snapshots rows of the {placeholders} dates with totals
+# recomputed from usage_records. Shared with snapshot_db.
+_DAILY_REBUILD_SQL = """
+    INSERT OR REPLACE INTO daily_snapshots (
+        date, total_prompts, total_responses, total_sessions, total_tokens,
+        input_tokens, output_tokens, cache_creation_tokens,
+        cache_read_tokens, snapshot_timestamp,
+        device_id, device_name, device_type, hostname
+    )
+    SELECT
+        u.date,
+        SUM(CASE WHEN u.message_type = 'user' THEN 1 ELSE 0 END),
+        SUM(CASE WHEN u.message_type = 'assistant' THEN 1 ELSE 0 END),
+        COUNT(DISTINCT u.session_id),
+        COALESCE(SUM(u.total_tokens), 0),
+        COALESCE(SUM(u.input_tokens), 0),
+        COALESCE(SUM(u.output_tokens), 0),
+        COALESCE(SUM(u.cache_creation_tokens), 0),
+        COALESCE(SUM(u.cache_read_tokens), 0),
+        ?, ?, ?, ?, GROUP_CONCAT(DISTINCT u.hostname)
+    FROM usage_records u
+    WHERE u.date IN ({placeholders})
+    GROUP BY u.date
+"""
+
+
+def _recompute_daily_snapshots(
+    db,
+    dates: list[str],
+    device_id: str | None,
+    device_name: str | None,
+    device_type: str | None,
+) -> None:
+    """
+    Body of recompute_daily_snapshots (shared by both backends).
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        dates: Dates (YYYY-MM-DD) to recompute
+        device_id: Device stamped on the recomputed rows
+        device_name: Human-readable device name
+        device_type: Device type (macos, windows, linux)
+    """
+    placeholders = ", ".join("?" for _ in dates)
+    timestamp = datetime.now().isoformat()
+    db.execute(
+        _DAILY_REBUILD_SQL.format(placeholders=placeholders),
+        [timestamp, device_id, device_name, device_type, *dates],
+    )
+    db.execute(
+        f"""
+        DELETE FROM daily_snapshots
+        WHERE date IN ({placeholders})
+          AND date NOT IN (SELECT DISTINCT date FROM usage_records WHERE date IN ({placeholders}))
+        """,
+        [*dates, *dates],
+    )
+    db.execute(f"DELETE FROM daily_model_snapshots WHERE date IN ({placeholders})", dates)
+    db.execute(_MODEL_MIX_REBUILD_SQL.format(where=f"AND date IN ({placeholders})"), dates)
+    _rebuild_hourly_snapshots(db, dates)
+
+
+# Every copy of a billed response but the one dedupe_records keeps (most
+# tokens, then latest timestamp), with its date
+_DUPLICATE_RESPONSES_SQL = """
+    SELECT id, date FROM (
+        SELECT id, date, ROW_NUMBER() OVER (
+            PARTITION BY message_uuid ORDER BY total_tokens DESC, timestamp DESC, id
+        ) AS copy
+        FROM usage_records
+        WHERE message_type = 'assistant'
+    ) copies
+    WHERE copy > 1
+"""
+
+
+def _dedupe_responses(db) -> int:
+    """
+    Delete duplicate copies of billed responses (shared by both backends).
+
+    Databases from before response identity could store one response under
+    several sessions. The dates that lost rows have their daily snapshots,
+    model mix and hours recomputed, keeping each day's device stamp.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+
+    Returns:
+        Number of rows deleted
+    """
+    rows = db.execute(_DUPLICATE_RESPONSES_SQL).fetchall()
+    if not rows:
+        return 0
+    ids = [row[0] for row in rows]
+    for start in range(0, len(ids), 500):
+        batch = ids[start:start + 500]
+        db.execute(f"DELETE FROM usage_records WHERE id IN ({', '.join('?' for _ in batch)})", batch)
+
+    dates_by_device: dict[tuple, list[str]] = {}
+    for date in sorted({row[1] for row in rows}):
+        stamp = db.execute(
+            "SELECT device_id, device_name, device_type FROM daily_snapshots WHERE date = ?", [date]
+        ).fetchone()
+        dates_by_device.setdefault(tuple(stamp) if stamp else (None, None, None), []).append(date)
+    for (device_id, device_name, device_type), dates in dates_by_device.items():
+        _recompute_daily_snapshots(db, dates, device_id, device_name, device_type)
+    return len(ids)
+
+
+def _read_record_dates(db) -> list[str]:
+    """Dates that have usage_records rows, oldest first."""
+    return [row[0] for row in db.execute("SELECT DISTINCT date FROM usage_records ORDER BY date").fetchall()]
+
+
+_SNAPSHOT_CHECK_FIELDS = ("prompts", "responses", "sessions", "total_tokens")
+
+
+def _read_snapshot_mismatches(db) -> list[dict]:
+    """
+    Dates whose daily_snapshots row disagrees with their usage_records (shared by both backends).
+
+    Only dates with records are checked; snapshot rows of dates without
+    records (aged-out or aggregate history) are left alone by design.
+
+    Returns:
+        Dicts with date, records (expected totals), and snapshot (stored
+        totals, or None when the row is missing), oldest first
+    """
+    rows = db.execute("""
+        SELECT r.date, r.prompts, r.responses, r.sessions, r.total_tokens,
+               s.date, s.total_prompts, s.total_responses, s.total_sessions, s.total_tokens
+        FROM (
+            SELECT date,
+                   SUM(CASE WHEN message_type = 'user' THEN 1 ELSE 0 END) AS prompts,
+                   SUM(CASE WHEN message_type = 'assistant' THEN 1 ELSE 0 END) AS responses,
+                   COUNT(DISTINCT session_id) AS sessions,
+                   COALESCE(SUM(total_tokens), 0) AS total_tokens
+            FROM usage_records GROUP BY date
+        ) r
+        LEFT JOIN daily_snapshots s ON s.date = r.date
+        ORDER BY r.date
+    """).fetchall()
+    mismatches = []
+    for row in rows:
+        expected = dict(zip(_SNAPSHOT_CHECK_FIELDS, row[1:5]))
+        stored = dict(zip(_SNAPSHOT_CHECK_FIELDS, row[6:10])) if row[5] is not None else None
+        if stored != expected:
+            mismatches.append({"date": row[0], "records": expected, "snapshot": stored})
+    return mismatches
+
+
+def recompute_daily_snapshots(
+    dates: list[str],
+    db_path: Path = DEFAULT_DB_PATH,
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+) -> None:
+    """
+    Recompute daily_snapshots for the given dates from usage_records.
+
+    Dates left with zero usage_records rows have their snapshot row removed
+    (they were fully replaced during a rebuild), so stale inflated totals
+    never survive on empty dates.
+    """
+    require_duckdb()
+    if not dates:
+        return
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        _recompute_daily_snapshots(conn, dates, device_id, device_name, device_type)
+    finally:
+        conn.close()
+
+
+def get_record_dates(db_path: Path = DEFAULT_DB_PATH) -> list[str]:
+    """
+    Dates that have individual usage records, oldest first (empty in aggregate mode).
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_record_dates(conn)
+    finally:
+        conn.close()
+
+
+def verify_daily_snapshots(db_path: Path = DEFAULT_DB_PATH) -> list[dict]:
+    """
+    Dates whose daily snapshot disagrees with their usage records (see _read_snapshot_mismatches).
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_snapshot_mismatches(conn)
+    finally:
+        conn.close()
+
+
+_UPDATE_RUN_COLUMNS = ("started_at", "finished_at", "trigger", "exit_status", "new_records", "error")
+
+
+def _read_update_runs(db, trigger: str | None, limit: int) -> list[dict]:
+    """
+    Most recent update runs, newest first (shared by both backends).
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        trigger: Only runs with this trigger ("hook", "watch", or "manual"); None for all
+        limit: Maximum rows to return
+    """
+    where, params = "", []
+    if trigger:
+        where, params = "WHERE trigger = ?", [trigger]
+    rows = db.execute(
+        f"SELECT {', '.join(_UPDATE_RUN_COLUMNS)} FROM update_runs {where} "
+        f"ORDER BY started_at DESC LIMIT ?",
+        params + [limit],
+    ).fetchall()
+    return [dict(zip(_UPDATE_RUN_COLUMNS, row)) for row in rows]
+
+
+def record_update_run(
+    started_at: str,
+    finished_at: str,
+    trigger: str,
+    exit_status: int,
+    new_records: int = 0,
+    error: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> None:
+    """
+    Log one `ccg update usage` run in update_runs.
+
+    Args:
+        started_at: ISO timestamp the run started (primary key)
+        finished_at: ISO timestamp the run finished
+        trigger: "hook" for Stop-hook runs, "watch" for --watch, "manual" otherwise
+        exit_status: 0 on success, 1 on failure
+        new_records: Records saved by the run
+        error: Error message for failed runs
+        db_path: Path to the DuckDB database file
+    """
+    require_duckdb()
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    try:
+        conn.execute(
+            f"INSERT OR REPLACE INTO update_runs ({', '.join(_UPDATE_RUN_COLUMNS)}) "
+            f"VALUES (?, ?, ?, ?, ?, ?)",
+            [started_at, finished_at, trigger, exit_status, new_records, error],
+        )
+    finally:
+        conn.close()
+
+
+def record_full_ingest(finished_at: str, db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Note that an ingest over every transcript source finished.
+
+    Args:
+        finished_at: ISO timestamp the ingest finished
+        db_path: Path to the DuckDB database file
+    """
+    require_duckdb()
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    try:
+        conn.execute(
+            "INSERT OR REPLACE INTO ingest_status (key, value) VALUES (?, ?)",
+            [LAST_FULL_INGEST_KEY, finished_at],
+        )
+    finally:
+        conn.close()
+
+
+def get_last_full_ingest(db_path: Path = DEFAULT_DB_PATH) -> str | None:
+    """
+    When the last ingest over every transcript source finished.
+
+    Returns:
+        ISO timestamp, or None if no full ingest has been recorded
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return None
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_last_full_ingest(conn)
+    finally:
+        conn.close()
+
+
+def set_ingest_status(key: str, value: str, db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Store an ingest_status value.
+
+    Args:
+        key: Status key
+        value: Value to store (replaces any previous one)
+        db_path: Path to the DuckDB database file
+    """
+    require_duckdb()
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    try:
+        conn.execute("INSERT OR REPLACE INTO ingest_status (key, value) VALUES (?, ?)", [key, value])
+    finally:
+        conn.close()
+
+
+def get_ingest_status(key: str, db_path: Path = DEFAULT_DB_PATH) -> str | None:
+    """
+    An ingest_status value.
+
+    Returns:
+        The stored value, or None if the key is unset
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return None
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_ingest_status(conn, key)
+    finally:
+        conn.close()
+
+
+def get_update_runs(
+    trigger: str | None = None,
+    limit: int = 20,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Recent `ccg update usage` runs, newest first.
+
+    Returns:
+        List of dicts (started_at, finished_at, trigger, exit_status,
+        new_records, error)
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_update_runs(conn, trigger, limit)
+    finally:
+        conn.close()
+
+
+def _read_ingestion_state(db, file_paths: list[str]) -> dict[str, tuple[int, int, str]]:
+    """
+    Saved parse positions for the given files (shared by both backends).
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        file_paths: File path strings to look up
+
+    Returns:
+        Dict of file path -> (size_bytes, byte_offset, head_digest)
+    """
+    state: dict[str, tuple[int, int, str]] = {}
+    # Chunk to stay under SQLite's bound-parameter limit
+    for i in range(0, len(file_paths), 500):
+        chunk = file_paths[i:i + 500]
+        placeholders = ", ".join("?" for _ in chunk)
+        rows = db.execute(
+            f"SELECT file_path, size_bytes, byte_offset, head_digest FROM ingestion_state "
+            f"WHERE file_path IN ({placeholders})",
+            chunk,
+        ).fetchall()
+        state.update({path: (size, offset, digest) for path, size, offset, digest in rows})
+    return state
+
+
+def get_ingestion_state(
+    file_paths: list[str],
+    db_path: Path = DEFAULT_DB_PATH,
+) -> dict[str, tuple[int, int, str]]:
+    """
+    Byte offsets parsed so far for the given transcripts.
+
+    Returns:
+        Dict of file path -> (size_bytes, byte_offset, head_digest); files
+        never parsed incrementally are absent
+    """
+    require_duckdb()
+
+    if not file_paths or not db_path.exists():
+        return {}
 
     init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_ingestion_state(conn, file_paths)
+    finally:
+        conn.close()
 
-    conn = duckdb.connect(str(db_path))
-    saved_count = 0
 
+def update_ingestion_state(
+    entries: list[tuple[str, int, int, str]],
+    db_path: Path = DEFAULT_DB_PATH,
+) -> None:
+    """
+    Upsert parse positions after a successful incremental ingest.
+
+    Args:
+        entries: (file_path, size_bytes, byte_offset, head_digest) tuples
+        db_path: Path to the DuckDB database file
+    """
+    require_duckdb()
+
+    if not entries:
+        return
+
+    init_database(db_path)
+    timestamp = datetime.now().isoformat()
+    conn = _connect(db_path)
     try:
-        # Save individual records only if in "full" mode
-        if storage_mode == "full":
-            # Bulk path: materialize the batch as an Arrow Table, register it
-            # as a DuckDB relation, and dedupe-INSERT in one statement against
-            # the UNIQUE(session_id, message_uuid) index. Arrow's columnar
-            # vectorized loader is ~50x faster than executemany() for 100k+
-            # row batches (executemany pays per-row Python<->C overhead).
-            #
-            # Soft-import pyarrow so the install footprint stays optional --
-            # fall back to a chunked executemany VALUES path if it's missing.
-            try:
-                import pyarrow as pa
-                use_arrow = True
-            except ImportError:
-                use_arrow = False
+        conn.executemany("""
+            INSERT OR REPLACE INTO ingestion_state (
+                file_path, size_bytes, byte_offset, head_digest, updated_at
+            ) VALUES (?, ?, ?, ?, ?)
+        """, [[*entry, timestamp] for entry in entries])
+    finally:
+        conn.close()
 
-            cols = {
-                "date": [], "timestamp": [], "session_id": [], "message_uuid": [],
-                "message_type": [], "model": [], "folder": [], "git_branch": [],
-                "version": [],
-                "input_tokens": [], "output_tokens": [],
-                "cache_creation_tokens": [], "cache_read_tokens": [], "total_tokens": [],
-                "cache_creation_1h_tokens": [],
-            }
-            for record in records:
-                tu = record.token_usage
-                cols["date"].append(record.date_key)
-                cols["timestamp"].append(record.timestamp.isoformat())
-                cols["session_id"].append(record.session_id)
-                cols["message_uuid"].append(record.message_uuid)
-                cols["message_type"].append(record.message_type)
-                cols["model"].append(record.model)
-                cols["folder"].append(record.folder)
-                cols["git_branch"].append(record.git_branch)
-                cols["version"].append(record.version)
-                cols["input_tokens"].append(tu.input_tokens if tu else 0)
-                cols["output_tokens"].append(tu.output_tokens if tu else 0)
-                cols["cache_creation_tokens"].append(tu.cache_creation_tokens if tu else 0)
-                cols["cache_read_tokens"].append(tu.cache_read_tokens if tu else 0)
-                cols["total_tokens"].append(tu.total_tokens if tu else 0)
-                cols["cache_creation_1h_tokens"].append(tu.cache_creation_1h_tokens if tu else 0)
 
-            conn.execute("""
-                CREATE OR REPLACE TEMP TABLE staging_records (
-                    date VARCHAR, timestamp VARCHAR,
-                    session_id VARCHAR, message_uuid VARCHAR,
-                    message_type VARCHAR, model VARCHAR,
-                    folder VARCHAR, git_branch VARCHAR, version VARCHAR,
-                    input_tokens INTEGER, output_tokens INTEGER,
-                    cache_creation_tokens INTEGER, cache_read_tokens INTEGER,
-                    total_tokens INTEGER, cache_creation_1h_tokens INTEGER
-                )
-            """)
+_QUARANTINE_COLUMNS = (
+    "file_path", "entry_id", "session_id", "raw_timestamp", "reason", "raw_line", "quarantined_at",
+)
 
-            if use_arrow:
-                arrow_table = pa.table(cols)
-                conn.register("staging_arrow", arrow_table)
-                conn.execute("INSERT INTO staging_records SELECT * FROM staging_arrow")
-                conn.unregister("staging_arrow")
-            else:
-                # Fallback: chunked executemany. Slower but works without pyarrow.
-                rows = list(zip(*cols.values()))
-                chunk = 500
-                for i in range(0, len(rows), chunk):
-                    batch = rows[i:i + chunk]
-                    conn.executemany(
-                        "INSERT INTO staging_records VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                        batch,
-                    )
 
-            before = conn.execute("SELECT COUNT(*) FROM usage_records").fetchone()[0]
+def _quarantine_rows(entries: list[QuarantinedEntry]) -> list[tuple]:
+    """Insert rows for quarantined entries (shared by both backends)."""
+    timestamp = datetime.now().isoformat()
+    return [
+        (e.file_path, e.entry_id, e.session_id, e.raw_timestamp, e.reason, e.raw_line, timestamp)
+        for e in entries
+    ]
 
-            # Upgrade path: a message first captured mid-stream carries
-            # partial usage; when a later batch brings the same billed
-            # response with larger totals, update the row in place (never
-            # downgrade).
-            conn.execute(
-                """
-                UPDATE usage_records
-                SET timestamp = b.timestamp,
-                    input_tokens = b.input_tokens,
-                    output_tokens = b.output_tokens,
-                    cache_creation_tokens = b.cache_creation_tokens,
-                    cache_read_tokens = b.cache_read_tokens,
-                    total_tokens = b.total_tokens,
-                    cache_creation_1h_tokens = b.cache_creation_1h_tokens
-                FROM (
-                    SELECT * FROM staging_records s
-                    WHERE s.message_type = 'assistant'
-                    QUALIFY row_number() OVER (
-                        PARTITION BY s.message_uuid
-                        ORDER BY s.total_tokens DESC, s.timestamp DESC
-                    ) = 1
-                ) b
-                WHERE usage_records.message_type = 'assistant'
-                  AND usage_records.message_uuid = b.message_uuid
-                  AND b.total_tokens > usage_records.total_tokens
-                """
-            )
 
-            # Insert gate: assistant rows dedupe GLOBALLY on the billed
-            # response id (session forks replay identical responses under new
-            # session ids); user rows stay session-scoped. Within the batch,
-            # the max-usage duplicate wins.
-            conn.execute(
-                """
-                INSERT INTO usage_records (
-                    id, date, timestamp, session_id, message_uuid, message_type,
-                    model, folder, git_branch, version,
-                    input_tokens, output_tokens,
-                    cache_creation_tokens, cache_read_tokens, total_tokens,
-                    cache_creation_1h_tokens,
-                    device_id, device_name, device_type
-                )
-                SELECT
-                    nextval('usage_records_id_seq'),
-                    s.date, s.timestamp, s.session_id, s.message_uuid, s.message_type,
-                    s.model, s.folder, s.git_branch, s.version,
-                    s.input_tokens, s.output_tokens,
-                    s.cache_creation_tokens, s.cache_read_tokens, s.total_tokens,
-                    s.cache_creation_1h_tokens,
-                    ?, ?, ?
-                FROM staging_records s
-                WHERE NOT EXISTS (
-                    SELECT 1 FROM usage_records u
-                    WHERE u.message_uuid = s.message_uuid
-                      AND (s.message_type = 'assistant' OR u.session_id = s.session_id)
-                )
-                QUALIFY row_number() OVER (
-                    PARTITION BY CASE WHEN s.message_type = 'assistant'
-                                      THEN s.message_uuid
-                                      ELSE s.session_id || '|' || s.message_uuid END
-                    ORDER BY s.total_tokens DESC, s.timestamp DESC
-                ) = 1
-                """,
-                [device_id, device_name, device_type],
-            )
-            saved_count = conn.execute("SELECT COUNT(*) FROM usage_records").fetchone()[0] - before
+def _read_quarantine(db, limit: int | None) -> list[dict]:
+    """
+    Quarantined entries awaiting review, newest first (shared by both backends).
 
-            # Rebuild daily snapshots only for dates present in this batch.
-            # Dates outside the batch keep their existing rows, preserving
-            # history for days whose JSONL files have aged out. One set-based
-            # upsert replaces the previous per-date query loop.
-            timestamp = datetime.now().isoformat()
-            conn.execute("""
-                INSERT OR REPLACE INTO daily_snapshots (
-                    date, total_prompts, total_responses, total_sessions, total_tokens,
-                    input_tokens, output_tokens, cache_creation_tokens,
-                    cache_read_tokens, snapshot_timestamp,
-                    device_id, device_name, device_type
-                )
-                SELECT
-                    u.date,
-                    SUM(CASE WHEN u.message_type = 'user' THEN 1 ELSE 0 END),
-                    SUM(CASE WHEN u.message_type = 'assistant' THEN 1 ELSE 0 END),
-                    COUNT(DISTINCT u.session_id),
-                    COALESCE(SUM(u.total_tokens), 0),
-                    COALESCE(SUM(u.input_tokens), 0),
-                    COALESCE(SUM(u.output_tokens), 0),
-                    COALESCE(SUM(u.cache_creation_tokens), 0),
-                    COALESCE(SUM(u.cache_read_tokens), 0),
-                    ?, ?, ?, ?
-                FROM usage_records u
-                WHERE u.date IN (SELECT DISTINCT date FROM staging_records)
-                GROUP BY u.date
-            """, [timestamp, device_id, device_name, device_type])
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        limit: Maximum rows to return; None for all
+    """
+    sql = (
+        f"SELECT {', '.join(_QUARANTINE_COLUMNS)} FROM quarantined_entries "
+        f"WHERE dismissed = ? ORDER BY quarantined_at DESC, file_path, entry_id"
+    )
+    params: list = [False]
+    if limit is not None:
+        sql += " LIMIT ?"
+        params.append(limit)
+    return [dict(zip(_QUARANTINE_COLUMNS, row)) for row in db.execute(sql, params).fetchall()]
 
-            conn.execute("DROP TABLE staging_records")
-        else:
-            # Aggregate mode
-            from collections import defaultdict
-            daily_aggregates = defaultdict(lambda: {
-                "prompts": 0,
-                "responses": 0,
-                "sessions": set(),
-                "input_tokens": 0,
-                "output_tokens": 0,
-                "cache_creation_tokens": 0,
-                "cache_read_tokens": 0,
-                "total_tokens": 0,
-            })
 
-            for record in records:
-                date_key = record.date_key
-                daily_aggregates[date_key]["sessions"].add(record.session_id)
+def _count_quarantine(db) -> int:
+    """Quarantined entries awaiting review (shared by both backends)."""
+    return db.execute("SELECT COUNT(*) FROM quarantined_entries WHERE dismissed = ?", [False]).fetchone()[0]
 
-                if record.is_user_prompt:
-                    daily_aggregates[date_key]["prompts"] += 1
-                elif record.is_assistant_response:
-                    daily_aggregates[date_key]["responses"] += 1
 
-                if record.token_usage:
-                    daily_aggregates[date_key]["input_tokens"] += record.token_usage.input_tokens
-                    daily_aggregates[date_key]["output_tokens"] += record.token_usage.output_tokens
-                    daily_aggregates[date_key]["cache_creation_tokens"] += record.token_usage.cache_creation_tokens
-                    daily_aggregates[date_key]["cache_read_tokens"] += record.token_usage.cache_read_tokens
-                    daily_aggregates[date_key]["total_tokens"] += record.token_usage.total_tokens
+def _dismiss_quarantine(db, keys: list[tuple[str, str]]) -> int:
+    """
+    Dismiss the quarantined entries with the given keys (shared by both backends).
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        keys: (file_path, entry_id) of each entry to dismiss
+
+    Returns:
+        Number of entries dismissed (keys already dismissed don't count)
+    """
+    dismissed = 0
+    # Two parameters per key; chunk to stay under SQLite's bound-parameter limit
+    for start in range(0, len(keys), 250):
+        chunk = keys[start:start + 250]
+        match = " OR ".join("(file_path = ? AND entry_id = ?)" for _ in chunk)
+        params = [value for key in chunk for value in key]
+        dismissed += db.execute(
+            f"SELECT COUNT(*) FROM quarantined_entries WHERE dismissed = ? AND ({match})", [False, *params]
+        ).fetchone()[0]
+        db.execute(
+            f"UPDATE quarantined_entries SET dismissed = ? WHERE dismissed = ? AND ({match})", [True, False, *params]
+        )
+    return dismissed
+
+
+def save_quarantined_entries(
+    entries: list[QuarantinedEntry],
+    db_path: Path = DEFAULT_DB_PATH,
+) -> int:
+    """
+    Store entries held back from ingestion, ignoring ones already held.
+
+    Returns:
+        Number of newly quarantined entries
+    """
+    require_duckdb()
+
+    if not entries:
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        before = conn.execute("SELECT COUNT(*) FROM quarantined_entries").fetchone()[0]
+        conn.executemany(f"""
+            INSERT OR IGNORE INTO quarantined_entries ({', '.join(_QUARANTINE_COLUMNS)})
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+        """, [list(row) for row in _quarantine_rows(entries)])
+        return conn.execute("SELECT COUNT(*) FROM quarantined_entries").fetchone()[0] - before
+    finally:
+        conn.close()
+
+
+def get_quarantined_entries(
+    limit: int | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Quarantined entries awaiting review, newest first.
+
+    Returns:
+        List of dicts keyed by quarantined_entries column
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_quarantine(conn, limit)
+    finally:
+        conn.close()
+
+
+def count_quarantined_entries(db_path: Path = DEFAULT_DB_PATH) -> int:
+    """Number of quarantined entries awaiting review."""
+    require_duckdb()
+
+    if not db_path.exists():
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _count_quarantine(conn)
+    finally:
+        conn.close()
 
-            timestamp = datetime.now().isoformat()
-            for date_key, agg in daily_aggregates.items():
-                existing = conn.execute("""
-                    SELECT total_prompts, total_responses, total_sessions, total_tokens,
-                           input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens
-                    FROM daily_snapshots WHERE date = ?
-                """, [date_key]).fetchone()
 
-                if existing:
-                    conn.execute("""
-                        INSERT OR REPLACE INTO daily_snapshots (
-                            date, total_prompts, total_responses, total_sessions, total_tokens,
-                            input_tokens, output_tokens, cache_creation_tokens,
-                            cache_read_tokens, snapshot_timestamp,
-                            device_id, device_name, device_type
-                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                    """, [
-                        date_key,
-                        existing[0] + agg["prompts"],
-                        existing[1] + agg["responses"],
-                        existing[2] + len(agg["sessions"]),
-                        existing[3] + agg["total_tokens"],
-                        existing[4] + agg["input_tokens"],
-                        existing[5] + agg["output_tokens"],
-                        existing[6] + agg["cache_creation_tokens"],
-                        existing[7] + agg["cache_read_tokens"],
-                        timestamp,
-                        device_id,
-                        device_name,
-                        device_type,
-                    ])
-                else:
-                    conn.execute("""
-                        INSERT INTO daily_snapshots (
-                            date, total_prompts, total_responses, total_sessions, total_tokens,
-                            input_tokens, output_tokens, cache_creation_tokens,
-                            cache_read_tokens, snapshot_timestamp,
-                            device_id, device_name, device_type
-                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                    """, [
-                        date_key,
-                        agg["prompts"],
-                        agg["responses"],
-                        len(agg["sessions"]),
-                        agg["total_tokens"],
-                        agg["input_tokens"],
-                        agg["output_tokens"],
-                        agg["cache_creation_tokens"],
-                        agg["cache_read_tokens"],
-                        timestamp,
-                        device_id,
-                        device_name,
-                        device_type,
-                    ])
-                saved_count += 1
+def dismiss_quarantined_entries(keys: list[tuple[str, str]], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Dismiss reviewed quarantined entries.
+
+    Dismissed rows are kept (hidden) so re-parsing the same transcript
+    doesn't quarantine them again.
+
+    Args:
+        keys: (file_path, entry_id) of each reviewed entry
+        db_path: Path to the DuckDB database file
+
+    Returns:
+        Number of entries dismissed
+    """
+    require_duckdb()
+
+    if not keys or not db_path.exists():
+        return 0
 
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _dismiss_quarantine(conn, keys)
     finally:
         conn.close()
 
-    return saved_count
 
+_UNKNOWN_FIELD_COLUMNS = ("field", "occurrences", "first_seen", "last_seen")
 
-def load_historical_records(
-    start_date: str | None = None,
-    end_date: str | None = None,
-    db_path: Path = DEFAULT_DB_PATH
-) -> list[UsageRecord]:
+# Adds a run's occurrences to a field's total (shared by both backends)
+_UPSERT_UNKNOWN_FIELD_SQL = """
+    INSERT INTO unknown_fields (field, occurrences, first_seen, last_seen)
+    VALUES (?, ?, ?, ?)
+    ON CONFLICT (field) DO UPDATE SET
+        occurrences = unknown_fields.occurrences + excluded.occurrences,
+        last_seen = excluded.last_seen
+"""
+
+
+def save_unknown_fields(counts: dict[str, int], db_path: Path = DEFAULT_DB_PATH) -> None:
     """
-    Load historical usage records from the DuckDB database.
+    Add occurrences of unknown transcript fields to their running totals.
 
     Args:
-        start_date: Optional start date (YYYY-MM-DD)
-        end_date: Optional end date (YYYY-MM-DD)
-        db_path: Path to the DuckDB database file
+        counts: Field path -> occurrences in this run (ParseStats.unknown_fields)
+    """
+    require_duckdb()
+
+    if not counts:
+        return
+
+    init_database(db_path)
+    timestamp = datetime.now().isoformat()
+    conn = _connect(db_path)
+    try:
+        conn.executemany(
+            _UPSERT_UNKNOWN_FIELD_SQL,
+            [[name, count, timestamp, timestamp] for name, count in counts.items()],
+        )
+    finally:
+        conn.close()
+
+
+def get_unknown_fields(db_path: Path = DEFAULT_DB_PATH) -> list[dict]:
+    """
+    Unknown transcript fields seen during ingestion, most frequent first.
 
     Returns:
-        List of UsageRecord objects
+        List of dicts with field, occurrences, first_seen, and last_seen
     """
     require_duckdb()
 
     if not db_path.exists():
         return []
 
-    conn = duckdb.connect(str(db_path))
-
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        query = "SELECT * FROM usage_records WHERE 1=1"
-        params = []
+        rows = conn.execute(
+            f"SELECT {', '.join(_UNKNOWN_FIELD_COLUMNS)} FROM unknown_fields ORDER BY occurrences DESC, field"
+        ).fetchall()
+        return [dict(zip(_UNKNOWN_FIELD_COLUMNS, row)) for row in rows]
+    finally:
+        conn.close()
 
-        if start_date:
-            query += " AND date >= ?"
-            params.append(start_date)
 
-        if end_date:
-            query += " AND date <= ?"
-            params.append(end_date)
+_EVENT_COLUMNS = (
+    "event_id", "session_id", "date", "timestamp", "event_type", "detail", "folder",
+    "retry_attempt", "ends_turn", "turn_tokens", "messages_before", "context_tokens",
+)
 
-        query += " ORDER BY date, timestamp"
 
-        result = conn.execute(query, params)
-        columns = [desc[0] for desc in result.description]
-        rows = result.fetchall()
-
-        records = []
-        for row in rows:
-            row_dict = dict(zip(columns, row))
-
-            token_usage = None
-            if row_dict.get("input_tokens", 0) > 0 or row_dict.get("output_tokens", 0) > 0:
-                token_usage = TokenUsage(
-                    input_tokens=row_dict["input_tokens"],
-                    output_tokens=row_dict["output_tokens"],
-                    cache_creation_tokens=row_dict["cache_creation_tokens"],
-                    cache_read_tokens=row_dict["cache_read_tokens"],
+def _event_rows(events: list[SessionEvent]) -> list[tuple]:
+    """Insert rows for session events (shared by both backends)."""
+    return [
+        (
+            e.event_id, e.session_id, e.date_key, e.timestamp.isoformat(), e.event_type,
+            e.detail, e.folder, e.retry_attempt, e.ends_turn, e.turn_tokens,
+            e.messages_before, e.context_tokens,
+        )
+        for e in events
+    ]
+
+
+def _read_session_events(db, start_date: str | None, end_date: str | None) -> list[dict]:
+    """
+    Session events in a date window, oldest first (shared by both backends).
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        start_date: First date (YYYY-MM-DD), or None for all history
+        end_date: Last date (YYYY-MM-DD), or None for up to today
+    """
+    sql = f"SELECT {', '.join(_EVENT_COLUMNS)} FROM session_events WHERE 1=1"
+    params: list = []
+    if start_date:
+        sql += " AND date >= ?"
+        params.append(start_date)
+    if end_date:
+        sql += " AND date <= ?"
+        params.append(end_date)
+    sql += " ORDER BY session_id, timestamp"
+    events = [dict(zip(_EVENT_COLUMNS, row)) for row in db.execute(sql, params).fetchall()]
+    _fill_compaction_messages(db, events)
+    return events
+
+
+def _fill_compaction_messages(db, events: list[dict]) -> None:
+    """
+    Count messages before compactions the parser couldn't, from usage_records.
+
+    Incremental (tail) parses see only appended lines, so the first
+    compaction after the resume point is stored without messages_before.
+    Full storage mode has every message, so the count is recovered here;
+    in aggregate mode it stays None.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        events: Rows from _read_session_events, updated in place
+    """
+    sessions = {
+        e["session_id"] for e in events
+        if e["event_type"] == "compaction" and e["messages_before"] is None
+    }
+    for session_id in sessions:
+        timestamps = [row[0] for row in db.execute(
+            "SELECT timestamp FROM usage_records WHERE session_id = ? ORDER BY timestamp",
+            [session_id],
+        ).fetchall()]
+        if not timestamps:
+            continue
+        previous = ""
+        for event in events:
+            if event["session_id"] != session_id or event["event_type"] != "compaction":
+                continue
+            if event["messages_before"] is None:
+                event["messages_before"] = (
+                    bisect.bisect_right(timestamps, event["timestamp"])
+                    - bisect.bisect_right(timestamps, previous)
                 )
+            previous = event["timestamp"]
 
-            record = UsageRecord(
-                timestamp=datetime.fromisoformat(row_dict["timestamp"]),
-                session_id=row_dict["session_id"],
-                message_uuid=row_dict["message_uuid"],
-                message_type=row_dict["message_type"],
-                model=row_dict["model"],
-                folder=row_dict["folder"],
-                git_branch=row_dict["git_branch"],
-                version=row_dict["version"],
-                token_usage=token_usage,
-            )
-            records.append(record)
 
-        return records
+def save_session_events(
+    events: list[SessionEvent],
+    db_path: Path = DEFAULT_DB_PATH,
+) -> int:
+    """
+    Store session events, ignoring ones already stored.
+
+    Returns:
+        Number of new events
+    """
+    require_duckdb()
+
+    if not events:
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        before = conn.execute("SELECT COUNT(*) FROM session_events").fetchone()[0]
+        conn.executemany(f"""
+            INSERT OR IGNORE INTO session_events ({', '.join(_EVENT_COLUMNS)})
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        """, [list(row) for row in _event_rows(events)])
+        return conn.execute("SELECT COUNT(*) FROM session_events").fetchone()[0] - before
     finally:
         conn.close()
 
 
-def get_database_stats(db_path: Path = DEFAULT_DB_PATH) -> dict:
+def get_session_events(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
     """
-    Get statistics about the historical database.
-
-    Args:
-        db_path: Path to the DuckDB database file
+    API errors, rate-limit hits, interruptions, and compactions in a date window.
 
     Returns:
-        Dictionary with database statistics
+        List of dicts keyed by session_events column, oldest first per session
     """
     require_duckdb()
 
     if not db_path.exists():
-        return {
-            "total_records": 0,
-            "total_days": 0,
-            "oldest_date": None,
-            "newest_date": None,
-            "newest_timestamp": None,
-            "total_tokens": 0,
-            "total_prompts": 0,
-            "total_responses": 0,
-            "total_sessions": 0,
-            "tokens_by_model": {},
-            "cost_by_model": {},
-            "total_cost": 0.0,
-            "avg_tokens_per_session": 0,
-            "avg_tokens_per_response": 0,
-            "avg_cost_per_session": 0.0,
-            "avg_cost_per_response": 0.0,
-        }
-
-    conn = duckdb.connect(str(db_path))
+        return []
 
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        # Basic counts
-        total_records = conn.execute("SELECT COUNT(*) FROM usage_records").fetchone()[0]
-        total_days = conn.execute("SELECT COUNT(DISTINCT date) FROM usage_records").fetchone()[0]
+        return _read_session_events(conn, start_date, end_date)
+    finally:
+        conn.close()
 
-        date_range = conn.execute("SELECT MIN(date), MAX(date) FROM usage_records").fetchone()
-        oldest_date, newest_date = date_range
 
-        newest_timestamp = conn.execute(
-            "SELECT MAX(snapshot_timestamp) FROM daily_snapshots"
-        ).fetchone()[0]
+# Streamed entries of a response carry partial output counts, so keep the
+# largest call_tokens seen for a tool_use id
+_MCP_CALL_UPSERT_SQL = """
+    INSERT INTO mcp_calls (
+        tool_use_id, session_id, date, timestamp, server, tool, call_tokens
+    ) VALUES (?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT (tool_use_id) DO UPDATE SET
+        call_tokens = excluded.call_tokens
+    WHERE excluded.call_tokens > mcp_calls.call_tokens
+"""
 
-        # Aggregate statistics
-        agg_row = conn.execute("""
-            SELECT
-                SUM(total_tokens) as total_tokens,
-                SUM(total_prompts) as total_prompts,
-                SUM(total_responses) as total_responses,
-                SUM(total_sessions) as total_sessions
-            FROM daily_snapshots
-        """).fetchone()
 
-        total_tokens = agg_row[0] or 0
-        total_prompts = agg_row[1] or 0
-        total_responses = agg_row[2] or 0
-        total_sessions = agg_row[3] or 0
+def _write_mcp_calls(db, records: list[UsageRecord]) -> None:
+    """
+    Upsert MCP calls and attach result sizes (shared by both backends).
 
-        # Tokens by model
-        tokens_by_model = {}
-        if total_records > 0:
-            model_rows = conn.execute("""
-                SELECT model, SUM(total_tokens) as tokens
-                FROM usage_records
-                WHERE model IS NOT NULL
-                GROUP BY model
-                ORDER BY tokens DESC
-            """).fetchall()
-            tokens_by_model = {row[0]: row[1] for row in model_rows if row[0]}
+    A response's output tokens (the tool arguments and any text) are split
+    evenly across its tool calls. Results arrive in later user entries and
+    update calls already stored; results of non-MCP tools match no row.
 
-        # Calculate costs
-        total_cost = 0.0
-        cost_by_model = {}
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        records: Parsed records (assistant calls and user results)
+    """
+    calls = []
+    results = []
+    for record in records:
+        if record.tool_results:
+            results.extend([chars, tool_id] for tool_id, chars in record.tool_results)
+        mcp_calls = record.mcp_calls
+        if not mcp_calls:
+            continue
+        output = record.token_usage.output_tokens if record.token_usage else 0
+        share = output // len(record.tool_uses)
+        calls.extend(
+            [tool_id, record.session_id, record.date_key, record.timestamp.isoformat(), server, tool, share]
+            for tool_id, server, tool in mcp_calls
+        )
+    if calls:
+        db.executemany(_MCP_CALL_UPSERT_SQL, calls)
+    if results:
+        db.executemany("UPDATE mcp_calls SET result_chars = ? WHERE tool_use_id = ?", results)
 
-        if total_records > 0:
-            cost_rows = conn.execute("""
-                SELECT
-                    ur.model,
-                    SUM(ur.input_tokens) as total_input,
-                    SUM(ur.output_tokens) as total_output,
-                    SUM(ur.cache_creation_tokens - ur.cache_creation_1h_tokens) as total_cache_write_5m,
-                    SUM(ur.cache_read_tokens) as total_cache_read,
-                    mp.input_price_per_mtok,
-                    mp.output_price_per_mtok,
-                    mp.cache_write_price_per_mtok,
-                    mp.cache_read_price_per_mtok,
-                    SUM(ur.cache_creation_1h_tokens) as total_cache_write_1h,
-                    mp.cache_write_1h_price_per_mtok
-                FROM usage_records ur
-                LEFT JOIN model_pricing mp ON ur.model = mp.model_name
-                WHERE ur.model IS NOT NULL
-                GROUP BY ur.model, mp.input_price_per_mtok, mp.output_price_per_mtok,
-                         mp.cache_write_price_per_mtok, mp.cache_read_price_per_mtok,
-                         mp.cache_write_1h_price_per_mtok
-            """).fetchall()
 
-            for row in cost_rows:
-                model = row[0]
-                input_tokens = row[1] or 0
-                output_tokens = row[2] or 0
-                cache_write_tokens = row[3] or 0
-                cache_read_tokens = row[4] or 0
-                input_price = row[5] or 0.0
-                output_price = row[6] or 0.0
-                cache_write_price = row[7] or 0.0
-                cache_read_price = row[8] or 0.0
-
-                cache_write_1h_tokens = row[9] or 0
-                cache_write_1h_price = row[10] if row[10] is not None else cache_write_price * 1.6
-                model_cost = (
-                    (input_tokens / 1_000_000) * input_price +
-                    (output_tokens / 1_000_000) * output_price +
-                    (cache_write_tokens / 1_000_000) * cache_write_price +
-                    (cache_write_1h_tokens / 1_000_000) * cache_write_1h_price +
-                    (cache_read_tokens / 1_000_000) * cache_read_price
-                )
+def _read_mcp_calls(db, start_date: str | None, end_date: str | None) -> list[dict]:
+    """
+    MCP calls in a date window (shared by both backends).
 
-                cost_by_model[model] = model_cost
-                total_cost += model_cost
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        start_date: First date (YYYY-MM-DD), or None for all history
+        end_date: Last date (YYYY-MM-DD), or None for up to today
+    """
+    columns = ("server", "tool", "session_id", "date", "call_tokens", "result_chars")
+    sql = f"SELECT {', '.join(columns)} FROM mcp_calls WHERE 1=1"
+    params: list = []
+    if start_date:
+        sql += " AND date >= ?"
+        params.append(start_date)
+    if end_date:
+        sql += " AND date <= ?"
+        params.append(end_date)
+    return [dict(zip(columns, row)) for row in db.execute(sql, params).fetchall()]
+
+
+def save_mcp_calls(records: list[UsageRecord], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Store the MCP tool calls and tool result sizes in parsed records.
 
-        avg_tokens_per_session = total_tokens / total_sessions if total_sessions > 0 else 0
-        avg_tokens_per_response = total_tokens / total_responses if total_responses > 0 else 0
-        avg_cost_per_session = total_cost / total_sessions if total_sessions > 0 else 0
-        avg_cost_per_response = total_cost / total_responses if total_responses > 0 else 0
+    Returns:
+        Number of new calls
+    """
+    require_duckdb()
 
-        return {
-            "total_records": total_records,
-            "total_days": total_days,
-            "oldest_date": oldest_date,
-            "newest_date": newest_date,
-            "newest_timestamp": newest_timestamp,
-            "total_tokens": total_tokens,
-            "total_prompts": total_prompts,
-            "total_responses": total_responses,
-            "total_sessions": total_sessions,
-            "tokens_by_model": tokens_by_model,
-            "cost_by_model": cost_by_model,
-            "total_cost": total_cost,
-            "avg_tokens_per_session": round(avg_tokens_per_session),
-            "avg_tokens_per_response": round(avg_tokens_per_response),
-            "avg_cost_per_session": round(avg_cost_per_session, 2),
-            "avg_cost_per_response": round(avg_cost_per_response, 4),
-        }
+    if not any(r.tool_results or r.mcp_calls for r in records):
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        before = conn.execute("SELECT COUNT(*) FROM mcp_calls").fetchone()[0]
+        _write_mcp_calls(conn, records)
+        return conn.execute("SELECT COUNT(*) FROM mcp_calls").fetchone()[0] - before
+    finally:
+        conn.close()
+
+
+def get_mcp_calls(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    MCP tool calls in a date window.
+
+    Returns:
+        List of dicts with server, tool, session_id, date, call_tokens,
+        and result_chars
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_mcp_calls(conn, start_date, end_date)
     finally:
         conn.close()
 
 
-def get_stale_files(
-    all_files: list[Path],
-    db_path: Path = DEFAULT_DB_PATH
-) -> tuple[list[Path], list[str]]:
+_TOOL_USAGE_INSERT_SQL = """
+    INSERT INTO tool_usage (tool_use_id, date, session_id, tool) VALUES (?, ?, ?, ?)
+    ON CONFLICT (tool_use_id) DO NOTHING
+"""
+
+
+def _tool_usage_rows(records: list[UsageRecord]) -> list[list[str]]:
+    """tool_usage rows for every tool call in assistant records (shared by both backends)."""
+    return [
+        [tool_id, record.date_key, record.session_id, name]
+        for record in records
+        if record.is_assistant_response
+        for tool_id, name in record.tool_uses
+    ]
+
+
+def _read_tool_usage(db, start_date: str | None, end_date: str | None) -> list[dict]:
     """
-    Identify which JSONL files need to be re-parsed.
+    Tool call counts per date, session, and tool (shared by both backends).
 
     Args:
-        all_files: List of all JSONL file paths
-        db_path: Path to the DuckDB database file
+        db: sqlite3 cursor or DuckDB connection
+        start_date: First date (YYYY-MM-DD), or None for all history
+        end_date: Last date (YYYY-MM-DD), or None for up to today
+    """
+    sql = "SELECT date, session_id, tool, COUNT(*) FROM tool_usage WHERE 1=1"
+    params: list = []
+    if start_date:
+        sql += " AND date >= ?"
+        params.append(start_date)
+    if end_date:
+        sql += " AND date <= ?"
+        params.append(end_date)
+    sql += " GROUP BY date, session_id, tool ORDER BY date, session_id, COUNT(*) DESC, tool"
+    columns = ("date", "session_id", "tool", "count")
+    return [dict(zip(columns, row)) for row in db.execute(sql, params).fetchall()]
+
+
+def save_tool_usage(records: list[UsageRecord], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Store every tool call in parsed records, ignoring ones already stored.
 
     Returns:
-        Tuple of (stale_files, deleted_file_paths)
+        Number of new calls
     """
     require_duckdb()
 
-    if not db_path.exists():
-        return (all_files, [])
+    rows = _tool_usage_rows(records)
+    if not rows:
+        return 0
 
     init_database(db_path)
-
-    conn = duckdb.connect(str(db_path))
-    stale_files = []
-    deleted_files = []
-
+    conn = _connect(db_path)
     try:
-        rows = conn.execute(
-            "SELECT file_path, mtime_ns, size_bytes FROM file_metadata"
-        ).fetchall()
-        stored_metadata = {row[0]: (row[1], row[2]) for row in rows}
+        before = conn.execute("SELECT COUNT(*) FROM tool_usage").fetchone()[0]
+        conn.executemany(_TOOL_USAGE_INSERT_SQL, rows)
+        return conn.execute("SELECT COUNT(*) FROM tool_usage").fetchone()[0] - before
+    finally:
+        conn.close()
 
-        current_file_paths = {str(f) for f in all_files}
 
-        for file_path in all_files:
-            path_str = str(file_path)
-            try:
-                stat = file_path.stat()
-                current_mtime_ns = stat.st_mtime_ns
-                current_size = stat.st_size
+def get_tool_usage(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Tool call counts per date, session, and tool.
 
-                if path_str not in stored_metadata:
-                    stale_files.append(file_path)
-                else:
-                    stored_mtime_ns, stored_size = stored_metadata[path_str]
-                    if current_mtime_ns != stored_mtime_ns or current_size != stored_size:
-                        stale_files.append(file_path)
-            except OSError:
-                continue
+    Returns:
+        List of dicts with date, session_id, tool, and count
+    """
+    require_duckdb()
 
-        for stored_path in stored_metadata.keys():
-            if stored_path not in current_file_paths:
-                deleted_files.append(stored_path)
+    if not db_path.exists():
+        return []
 
-        return (stale_files, deleted_files)
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_tool_usage(conn, start_date, end_date)
     finally:
         conn.close()
 
 
-def update_files_metadata(
-    file_paths: list[Path],
-    record_count: int = 0,
-    db_path: Path = DEFAULT_DB_PATH,
-    stats: dict[str, tuple[int, int]] | None = None,
-) -> None:
-    """
-    Upsert parse metadata for many files in a single connection.
+# Full mode: totals recomputed from usage_records replace the stored ones
+_SESSION_REPLACE_SQL = """
+    INSERT INTO sessions (
+        session_id, folder, started_at, ended_at, messages, total_tokens, title, entrypoint
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT (session_id) DO UPDATE SET
+        started_at = excluded.started_at,
+        ended_at = excluded.ended_at,
+        messages = excluded.messages,
+        total_tokens = excluded.total_tokens,
+        title = COALESCE(sessions.title, excluded.title),
+        entrypoint = COALESCE(sessions.entrypoint, excluded.entrypoint)
+"""
 
-    stats maps file path -> (mtime_ns, size_bytes) captured BEFORE parsing;
-    without it the file is re-statted here, which can silently mark bytes
-    appended mid-ingest as seen. Files that fail to stat are skipped.
+# Aggregate mode: each parse sees a whole transcript, which only grows, so
+# widen the time span and keep the larger totals
+_SESSION_MERGE_SQL = """
+    INSERT INTO sessions (
+        session_id, folder, started_at, ended_at, messages, total_tokens, title, entrypoint
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT (session_id) DO UPDATE SET
+        started_at = CASE WHEN excluded.started_at < sessions.started_at
+                          THEN excluded.started_at ELSE sessions.started_at END,
+        ended_at = CASE WHEN excluded.ended_at > sessions.ended_at
+                        THEN excluded.ended_at ELSE sessions.ended_at END,
+        messages = CASE WHEN excluded.messages > sessions.messages
+                        THEN excluded.messages ELSE sessions.messages END,
+        total_tokens = CASE WHEN excluded.total_tokens > sessions.total_tokens
+                            THEN excluded.total_tokens ELSE sessions.total_tokens END,
+        title = COALESCE(sessions.title, excluded.title),
+        entrypoint = COALESCE(sessions.entrypoint, excluded.entrypoint)
+"""
+
+_SESSION_COLUMNS = (
+    "session_id", "folder", "started_at", "ended_at", "messages", "total_tokens", "title", "entrypoint",
+)
+
+
+def _write_sessions(db, sessions: list[Session]) -> None:
     """
-    require_duckdb()
+    Upsert session metadata (shared by both backends).
 
-    if not file_paths:
-        return
+    Sessions with rows in usage_records (full mode) take their span and
+    totals from there, since an incremental parse sees only new lines; the
+    rest merge with what is stored. A stored title is never replaced.
 
-    init_database(db_path)
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        sessions: Sessions from build_sessions for one ingestion batch
+    """
+    ids = [session.session_id for session in sessions]
+    totals: dict[str, tuple] = {}
+    for start in range(0, len(ids), 500):
+        chunk = ids[start:start + 500]
+        totals.update({
+            row[0]: row[1:] for row in db.execute(f"""
+                SELECT session_id, MIN(timestamp), MAX(timestamp), COUNT(*), SUM(total_tokens)
+                FROM usage_records
+                WHERE session_id IN ({', '.join('?' * len(chunk))})
+                GROUP BY session_id
+            """, chunk).fetchall()
+        })
 
-    timestamp = datetime.now().isoformat()
-    rows = []
-    for file_path in file_paths:
-        pre = (stats or {}).get(str(file_path))
-        if pre is not None:
-            mtime_ns, size_bytes = pre
+    replaced, merged = [], []
+    for session in sessions:
+        if session.session_id in totals:
+            started_at, ended_at, messages, tokens = totals[session.session_id]
+            replaced.append([
+                session.session_id, session.folder, started_at, ended_at,
+                messages, tokens or 0, session.title, session.entrypoint,
+            ])
         else:
-            try:
-                stat = file_path.stat()
-            except OSError:
-                continue
-            mtime_ns, size_bytes = stat.st_mtime_ns, stat.st_size
-        rows.append([str(file_path), mtime_ns, size_bytes, record_count, timestamp])
+            merged.append([
+                session.session_id, session.folder, session.start.isoformat(),
+                session.end.isoformat(), session.messages, session.total_tokens, session.title,
+                session.entrypoint,
+            ])
+    if replaced:
+        db.executemany(_SESSION_REPLACE_SQL, replaced)
+    if merged:
+        db.executemany(_SESSION_MERGE_SQL, merged)
+
+
+def _read_sessions(db, session_ids: list[str] | None) -> list[Session]:
+    """
+    Stored session metadata, newest first (shared by both backends).
 
-    if not rows:
-        return
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        session_ids: Only these sessions, or None for all
+    """
+    sql = f"SELECT {', '.join(_SESSION_COLUMNS)} FROM sessions"
+    params: list = []
+    if session_ids is not None:
+        if not session_ids:
+            return []
+        sql += f" WHERE session_id IN ({', '.join('?' * len(session_ids))})"
+        params = list(session_ids)
+    return [
+        Session(
+            session_id=row[0],
+            folder=row[1] or "",
+            start=datetime.fromisoformat(row[2]),
+            end=datetime.fromisoformat(row[3]),
+            messages=row[4] or 0,
+            total_tokens=row[5] or 0,
+            title=row[6],
+            entrypoint=row[7],
+        )
+        for row in db.execute(sql + " ORDER BY started_at DESC", params).fetchall()
+    ]
+
+
+def save_sessions(sessions: list[Session], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Store session metadata derived during ingestion.
+
+    Returns:
+        Number of sessions written
+    """
+    require_duckdb()
+
+    if not sessions:
+        return 0
 
-    conn = duckdb.connect(str(db_path))
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        conn.executemany("""
-            INSERT OR REPLACE INTO file_metadata (
-                file_path, mtime_ns, size_bytes, record_count, last_parsed
-            ) VALUES (?, ?, ?, ?, ?)
-        """, rows)
+        _write_sessions(conn, sessions)
+        return len(sessions)
     finally:
         conn.close()
 
 
-def remove_deleted_file_metadata(
-    deleted_paths: list[str],
-    db_path: Path = DEFAULT_DB_PATH
-) -> None:
+def get_sessions(session_ids: list[str] | None = None, db_path: Path = DEFAULT_DB_PATH) -> list[Session]:
     """
-    Remove metadata for files that no longer exist.
+    Stored session metadata, newest first.
     """
     require_duckdb()
 
-    if not deleted_paths or not db_path.exists():
-        return
-
-    conn = duckdb.connect(str(db_path))
+    if not db_path.exists():
+        return []
 
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        conn.executemany(
-            "DELETE FROM file_metadata WHERE file_path = ?",
-            [(path,) for path in deleted_paths],
-        )
+        return _read_sessions(conn, session_ids)
     finally:
         conn.close()
 
 
-def get_update_coverage(db_path: Path = DEFAULT_DB_PATH) -> dict:
+_SESSION_SUMMARY_UPSERT_SQL = """
+    INSERT INTO session_summaries (session_id, summary, leaf_uuid) VALUES (?, ?, ?)
+    ON CONFLICT (session_id) DO UPDATE SET
+        summary = excluded.summary,
+        leaf_uuid = excluded.leaf_uuid
+"""
+
+
+def _write_session_summaries(db, summaries: list[TranscriptSummary]) -> None:
     """
-    Get record count and date bounds of usage_records.
+    Upsert session summaries, the last entry per session winning (shared by both backends).
 
-    Cheap alternative to get_database_stats for flows that only need
-    coverage, not token/cost aggregates.
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        summaries: Entries in transcript order
+    """
+    latest = {summary.session_id: summary for summary in summaries}
+    db.executemany(
+        _SESSION_SUMMARY_UPSERT_SQL,
+        [[s.session_id, s.summary, s.leaf_uuid] for s in latest.values()],
+    )
+
+
+def _read_session_summaries(db, session_ids: list[str] | None) -> dict[str, str]:
+    """
+    Session id -> summary (shared by both backends).
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        session_ids: Only these sessions, or None for all
+    """
+    sql = "SELECT session_id, summary FROM session_summaries"
+    params: list = []
+    if session_ids is not None:
+        if not session_ids:
+            return {}
+        sql += f" WHERE session_id IN ({', '.join('?' * len(session_ids))})"
+        params = list(session_ids)
+    return dict(db.execute(sql, params).fetchall())
+
+
+def save_session_summaries(summaries: list[TranscriptSummary], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Store session summary entries, the latest replacing earlier ones.
 
     Returns:
-        Dict with total_records, oldest_date, newest_date
+        Number of summaries written
     """
     require_duckdb()
 
-    if not db_path.exists():
-        return {"total_records": 0, "oldest_date": None, "newest_date": None}
+    if not summaries:
+        return 0
 
-    conn = duckdb.connect(str(db_path), read_only=True)
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        row = conn.execute(
-            "SELECT COUNT(*), MIN(date), MAX(date) FROM usage_records"
-        ).fetchone()
-        return {"total_records": row[0] or 0, "oldest_date": row[1], "newest_date": row[2]}
+        _write_session_summaries(conn, summaries)
+        return len(summaries)
     finally:
         conn.close()
 
 
-def delete_session_rows(
-    session_ids: list[str],
-    device_id: str | None,
-    db_path: Path = DEFAULT_DB_PATH,
-) -> list[str]:
+def get_session_summaries(session_ids: list[str] | None = None, db_path: Path = DEFAULT_DB_PATH) -> dict[str, str]:
     """
-    Delete usage_records for the given sessions (scoped to one device).
-
-    Returns:
-        Distinct dates the deleted rows covered
+    Stored session summaries as session id -> summary.
     """
     require_duckdb()
-    if not session_ids:
-        return []
+
+    if not db_path.exists():
+        return {}
+
     init_database(db_path)
-    placeholders = ", ".join("?" for _ in session_ids)
-    device_clause = "device_id = ?" if device_id is not None else "device_id IS NULL"
-    params: list[str] = list(session_ids) + ([device_id] if device_id is not None else [])
-    conn = duckdb.connect(str(db_path))
+    conn = _connect(db_path)
     try:
-        dates = [
-            row[0] for row in conn.execute(
-                f"SELECT DISTINCT date FROM usage_records "
-                f"WHERE session_id IN ({placeholders}) AND {device_clause}",
-                params,
-            ).fetchall()
-        ]
-        conn.execute(
-            f"DELETE FROM usage_records WHERE session_id IN ({placeholders}) AND {device_clause}",
-            params,
-        )
-        return dates
+        return _read_session_summaries(conn, session_ids)
     finally:
         conn.close()
 
 
-def recompute_daily_snapshots(
-    dates: list[str],
+# Keep the longest text seen for a message: incremental parses may only
+# see the later streamed entries of a response
+_CONTENT_UPSERT_SQL = """
+    INSERT INTO message_content (
+        entry_key, session_id, message_type, timestamp, content, content_bytes, truncated
+    ) VALUES (?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT (entry_key) DO UPDATE SET
+        content = excluded.content,
+        content_bytes = excluded.content_bytes,
+        truncated = excluded.truncated
+    WHERE excluded.content_bytes > message_content.content_bytes
+"""
+
+
+def _content_rows(records: list[UsageRecord], max_record_bytes: int) -> list[tuple]:
+    """
+    message_content rows for records with text, truncated to the per-record cap.
+
+    Keys follow usage_records identity: assistant rows by message_uuid
+    (global), user rows by session + uuid.
+    """
+    rows = []
+    for record in records:
+        if not record.content:
+            continue
+        encoded = record.content.encode("utf-8")
+        truncated = len(encoded) > max_record_bytes
+        if truncated:
+            encoded = encoded[:max_record_bytes]
+        content = encoded.decode("utf-8", errors="ignore")
+        key = (
+            record.message_uuid if record.is_assistant_response
+            else f"{record.session_id}:{record.message_uuid}"
+        )
+        rows.append((
+            key, record.session_id, record.message_type, record.timestamp.isoformat(),
+            content, len(content.encode("utf-8")), truncated,
+        ))
+    return rows
+
+
+# Keys of the rows past the total cap: newest rows are kept first, and a
+# row goes once the running total down to it (inclusive) exceeds the cap
+_CONTENT_OVER_CAP_SQL = """
+    SELECT entry_key FROM (
+        SELECT entry_key, SUM(content_bytes) OVER (
+            ORDER BY timestamp DESC, entry_key ROWS UNBOUNDED PRECEDING
+        ) AS kept_bytes
+        FROM message_content
+    ) WHERE kept_bytes > ?
+"""
+
+
+def _enforce_content_cap(db, max_total_bytes: int) -> int:
+    """
+    Evict the oldest message_content rows until the table fits the cap.
+
+    The table's total is one SUM, so a save under the cap reads no rows;
+    over it, one ordered DELETE drops the oldest rows.
+
+    Args:
+        db: sqlite3 cursor or DuckDB connection
+        max_total_bytes: Total UTF-8 bytes of content to keep
+
+    Returns:
+        Number of rows evicted
+    """
+    total = db.execute("SELECT COALESCE(SUM(content_bytes), 0) FROM message_content").fetchone()[0]
+    if total <= max_total_bytes:
+        return 0
+    evicted = db.execute(f"SELECT COUNT(*) FROM ({_CONTENT_OVER_CAP_SQL})", [max_total_bytes]).fetchone()[0]
+    db.execute(f"DELETE FROM message_content WHERE entry_key IN ({_CONTENT_OVER_CAP_SQL})", [max_total_bytes])
+    return evicted
+
+
+def save_message_content(
+    records: list[UsageRecord],
+    max_record_bytes: int,
+    max_total_bytes: int,
     db_path: Path = DEFAULT_DB_PATH,
-    device_id: str | None = None,
-    device_name: str | None = None,
-    device_type: str | None = None,
-) -> None:
+) -> int:
     """
-    Recompute daily_snapshots for the given dates from usage_records.
+    Store message text for later search, enforcing the size caps.
 
-    Dates left with zero usage_records rows have their snapshot row removed
-    (they were fully replaced during a rebuild), so stale inflated totals
-    never survive on empty dates.
+    Returns:
+        Number of messages evicted to stay under the total cap
     """
     require_duckdb()
-    if not dates:
-        return
+
+    rows = _content_rows(records, max_record_bytes)
+    if not rows:
+        return 0
+
     init_database(db_path)
-    placeholders = ", ".join("?" for _ in dates)
-    timestamp = datetime.now().isoformat()
-    conn = duckdb.connect(str(db_path))
+    conn = _connect(db_path)
     try:
-        conn.execute(
-            f"""
-            INSERT OR REPLACE INTO daily_snapshots (
-                date, total_prompts, total_responses, total_sessions, total_tokens,
-                input_tokens, output_tokens, cache_creation_tokens,
-                cache_read_tokens, snapshot_timestamp,
-                device_id, device_name, device_type
-            )
-            SELECT
-                u.date,
-                SUM(CASE WHEN u.message_type = 'user' THEN 1 ELSE 0 END),
-                SUM(CASE WHEN u.message_type = 'assistant' THEN 1 ELSE 0 END),
-                COUNT(DISTINCT u.session_id),
-                COALESCE(SUM(u.total_tokens), 0),
-                COALESCE(SUM(u.input_tokens), 0),
-                COALESCE(SUM(u.output_tokens), 0),
-                COALESCE(SUM(u.cache_creation_tokens), 0),
-                COALESCE(SUM(u.cache_read_tokens), 0),
-                ?, ?, ?, ?
-            FROM usage_records u
-            WHERE u.date IN ({placeholders})
-            GROUP BY u.date
-            """,
-            [timestamp, device_id, device_name, device_type, *dates],
-        )
-        conn.execute(
-            f"""
-            DELETE FROM daily_snapshots
-            WHERE date IN ({placeholders})
-              AND date NOT IN (SELECT DISTINCT date FROM usage_records WHERE date IN ({placeholders}))
-            """,
-            [*dates, *dates],
-        )
+        conn.executemany(_CONTENT_UPSERT_SQL, [list(row) for row in rows])
+        return _enforce_content_cap(conn, max_total_bytes)
     finally:
         conn.close()
 
@@ -1171,7 +4399,7 @@ def get_sync_state(key: str, db_path: Path = DEFAULT_DB_PATH) -> str | None:
     if not db_path.exists():
         return None
 
-    conn = duckdb.connect(str(db_path), read_only=True)
+    conn = _connect(db_path, read_only=True)
     try:
         try:
             row = conn.execute(
@@ -1191,7 +4419,7 @@ def set_sync_state(key: str, value: str, db_path: Path = DEFAULT_DB_PATH) -> Non
     require_duckdb()
     init_database(db_path)
 
-    conn = duckdb.connect(str(db_path))
+    conn = _connect(db_path)
     try:
         conn.execute(
             "INSERT OR REPLACE INTO sync_state (key, value) VALUES (?, ?)",
@@ -1221,7 +4449,7 @@ def fill_empty_daily_snapshots(
     from datetime import datetime as _dt
     from datetime import timedelta as _td
 
-    conn = duckdb.connect(str(db_path))
+    conn = _connect(db_path)
     try:
         existing = {
             row[0]
@@ -1251,4 +4479,38 @@ def fill_empty_daily_snapshots(
         conn.close()
 
 
+def checkpoint_database(db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Fold the write-ahead log into the database file (see snapshot_db).
+
+    Args:
+        db_path: Path to the DuckDB database file
+    """
+    require_duckdb()
+
+    if not db_path.exists():
+        return
+
+    conn = _connect(db_path)
+    try:
+        conn.execute("CHECKPOINT")
+    finally:
+        conn.close()
+
+
+@contextmanager
+def storage_handle(db_path: Path = DEFAULT_DB_PATH) -> Iterator[None]:
+    """
+    Counterpart of snapshot_db.storage_handle; calls keep their own connections.
+
+    DuckDB connections to one file already share a database instance within
+    the process, and init_database runs once per process (_INITIALIZED_DBS).
+    """
+    yield None
+
+
+def release_storage_handle(db_path: Path = DEFAULT_DB_PATH) -> None:
+    """Counterpart of snapshot_db.release_storage_handle; nothing is held open."""
+
+
 #endregion
@@ -0,0 +1,183 @@
+"""
+Optional SQLCipher encryption for the SQLite usage database.
+
+With the "encryption" config key on, every SQLite connection the storage
+layer opens goes through connect() here, which opens the file with
+SQLCipher (the sqlcipher3 package, `claude-goblin[encryption]`) and keys it
+before first use. The key comes from CCG_DB_KEY or, failing that, the OS
+keychain via keyring (service "claude-goblin", account "usage-db"). It is
+never written to goblin_config.json.
+
+An existing plaintext database is converted once with `ccg db encrypt`.
+Timestamped backups are copies of the file, so they stay encrypted too.
+"""
+#region Imports
+import os
+import sqlite3
+from pathlib import Path
+
+from src.config.user_config import is_encryption_enabled
+from src.errors import EncryptionError
+
+#endregion
+
+
+#region Constants
+KEY_ENV_VAR = "CCG_DB_KEY"
+
+KEYCHAIN_SERVICE = "claude-goblin"
+KEYCHAIN_ACCOUNT = "usage-db"
+
+INSTALL_HINT = "Install SQLCipher support with: pip install 'claude-goblin[encryption]'"
+
+KEY_HINT = (
+    f"Set {KEY_ENV_VAR}, or store the key in the OS keychain with: "
+    f"keyring set {KEYCHAIN_SERVICE} {KEYCHAIN_ACCOUNT}"
+)
+#endregion
+
+
+#region Functions
+
+
+def _keychain_key() -> str | None:
+    """The key stored in the OS keychain, or None (keyring missing or no entry)."""
+    try:
+        import keyring
+    except ImportError:
+        return None
+    try:
+        return keyring.get_password(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+    except Exception:
+        # No usable keychain backend (headless Linux, locked keychain)
+        return None
+
+
+def get_database_key() -> str:
+    """
+    The database key: CCG_DB_KEY, else the OS keychain entry.
+
+    Raises:
+        EncryptionError: If neither holds a key
+    """
+    key = os.environ.get(KEY_ENV_VAR) or _keychain_key()
+    if not key:
+        raise EncryptionError("Database encryption is on but no key is available", KEY_HINT)
+    return key
+
+
+def sqlcipher_module():
+    """
+    The SQLCipher DB-API module (sqlcipher3.dbapi2).
+
+    Raises:
+        EncryptionError: If sqlcipher3 is not installed
+    """
+    try:
+        from sqlcipher3 import dbapi2
+    except ImportError:
+        raise EncryptionError("Database encryption needs the sqlcipher3 package", INSTALL_HINT) from None
+    return dbapi2
+
+
+def driver_errors(name: str) -> tuple[type[Exception], ...]:
+    """
+    An exception class of both drivers connect() may use, for except clauses.
+
+    sqlcipher3 defines its own DB-API classes rather than reusing sqlite3's,
+    so catching sqlite3.OperationalError alone misses errors from an
+    encrypted database.
+
+    Args:
+        name: DB-API exception name ("OperationalError", "IntegrityError", "Error", ...)
+
+    Returns:
+        sqlite3's class, plus sqlcipher3's when it is installed
+    """
+    classes = [getattr(sqlite3, name)]
+    try:
+        classes.append(getattr(sqlcipher_module(), name))
+    except EncryptionError:
+        pass
+    return tuple(classes)
+
+
+def _key_pragma(key: str, schema: str | None = None) -> str:
+    # PRAGMA takes no bound parameters; quote the key as an SQL string literal
+    target = f"{schema}." if schema else ""
+    return f"PRAGMA {target}key = '{key.replace(chr(39), chr(39) * 2)}'"
+
+
+def connect(database: str | Path, uri: bool = False, timeout: float = 5.0) -> sqlite3.Connection:
+    """
+    Open a SQLite connection, through SQLCipher when encryption is on.
+
+    Args:
+        database: Database path (or file: URI when uri is True)
+        uri: Interpret database as a URI (read-only mode uses mode=ro&immutable=1)
+        timeout: Seconds to wait on other writers
+
+    Returns:
+        A DB-API connection (sqlite3, or sqlcipher3 with the same API)
+
+    Raises:
+        EncryptionError: If encryption is on and sqlcipher3 or the key is
+            missing, or the key does not open the file
+    """
+    if not is_encryption_enabled():
+        return sqlite3.connect(database, uri=uri, timeout=timeout)
+
+    module = sqlcipher_module()
+    conn = module.connect(str(database), uri=uri, timeout=timeout)
+    try:
+        conn.execute(_key_pragma(get_database_key()))
+        # SQLCipher checks the key lazily; read the schema to fail here
+        conn.execute("SELECT COUNT(*) FROM sqlite_master").fetchone()
+    except module.DatabaseError:
+        conn.close()
+        raise EncryptionError(
+            f"Cannot open {database} with the configured key",
+            "Check the key, or convert a plaintext database with: ccg db encrypt",
+        ) from None
+    return conn
+
+
+def row_factory():
+    """Row class matching connect()'s module, for dict-style row access."""
+    return sqlcipher_module().Row if is_encryption_enabled() else sqlite3.Row
+
+
+def encrypt_database(db_path: Path, key: str) -> None:
+    """
+    Convert a plaintext SQLite database into an encrypted one in place.
+
+    Writes an encrypted copy next to the file with sqlcipher_export, then
+    swaps it in, so an interrupted run leaves the original untouched.
+
+    Args:
+        db_path: Plaintext database (write-ahead log already checkpointed)
+        key: Key for the encrypted file
+
+    Raises:
+        EncryptionError: If sqlcipher3 is missing or the file is not a
+            plaintext SQLite database
+    """
+    module = sqlcipher_module()
+    encrypted = db_path.with_name(db_path.name + ".encrypting")
+    encrypted.unlink(missing_ok=True)
+    conn = module.connect(str(db_path))
+    try:
+        conn.execute("SELECT COUNT(*) FROM sqlite_master").fetchone()
+        conn.execute("ATTACH DATABASE ? AS encrypted", (str(encrypted),))
+        conn.execute(_key_pragma(key, "encrypted"))
+        conn.execute("SELECT sqlcipher_export('encrypted')")
+        conn.execute("DETACH DATABASE encrypted")
+    except module.DatabaseError as e:
+        encrypted.unlink(missing_ok=True)
+        raise EncryptionError(f"Cannot encrypt {db_path}: {e}", "Is it already encrypted?") from None
+    finally:
+        conn.close()
+    os.replace(encrypted, db_path)
+
+
+#endregion
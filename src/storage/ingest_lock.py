@@ -0,0 +1,110 @@
+"""
+Single-writer lock for ingestion.
+
+Stop hooks from several sessions can fire at once. Each ingestion holds an
+advisory lock on a file next to the database, so concurrent runs queue
+instead of parsing the same transcripts and racing on the same rows.
+"""
+#region Imports
+import os
+import time
+from collections.abc import Iterator
+from contextlib import contextmanager
+from pathlib import Path
+
+from src.storage import get_db_path
+
+if os.name == "nt":
+    import msvcrt
+else:
+    import fcntl
+
+#endregion
+
+
+#region Constants
+# Appended to the database file name to name its lock file
+INGEST_LOCK_SUFFIX = ".lock"
+
+# Seconds a queued run waits for the lock before giving up
+LOCK_TIMEOUT = 60.0
+
+# How often a queued run retries the lock (seconds)
+LOCK_POLL_INTERVAL = 0.1
+#endregion
+
+
+#region Functions
+
+
+def ingest_lock_path(db_path: Path | None = None) -> Path:
+    """
+    Lock file for a database: beside it, so ingests into other profiles'
+    or $CCG_DB_PATH databases never queue behind each other.
+
+    Args:
+        db_path: Usage database (default: the one this process uses)
+    """
+    db_path = db_path or get_db_path()
+    return db_path.with_name(db_path.name + INGEST_LOCK_SUFFIX)
+
+
+def _try_lock(fd: int) -> bool:
+    """Take the exclusive lock without blocking; False if another process holds it."""
+    try:
+        if os.name == "nt":
+            msvcrt.locking(fd, msvcrt.LK_NBLCK, 1)
+        else:
+            fcntl.flock(fd, fcntl.LOCK_EX | fcntl.LOCK_NB)
+        return True
+    except OSError:
+        return False
+
+
+def _unlock(fd: int) -> None:
+    """Release the lock taken by _try_lock."""
+    if os.name == "nt":
+        os.lseek(fd, 0, os.SEEK_SET)
+        msvcrt.locking(fd, msvcrt.LK_UNLCK, 1)
+    else:
+        fcntl.flock(fd, fcntl.LOCK_UN)
+
+
+@contextmanager
+def ingest_lock(timeout: float = LOCK_TIMEOUT, path: Path | None = None) -> Iterator[bool]:
+    """
+    Hold the ingestion lock for the duration of the block.
+
+    The lock is released by the OS if the process dies, so a crashed run
+    never leaves ingestion blocked.
+
+    Args:
+        timeout: Seconds to wait for another run to finish
+        path: Lock file location (default: beside the usage database)
+
+    Yields:
+        True if this run had to wait for another one, False otherwise
+
+    Raises:
+        TimeoutError: If the lock is still held after timeout seconds
+    """
+    path = path or ingest_lock_path()
+    path.parent.mkdir(parents=True, exist_ok=True)
+    fd = os.open(path, os.O_RDWR | os.O_CREAT, 0o644)
+    try:
+        waited = False
+        deadline = time.monotonic() + timeout
+        while not _try_lock(fd):
+            if time.monotonic() >= deadline:
+                raise TimeoutError(f"Ingestion lock {path} still held after {timeout:.0f}s")
+            waited = True
+            time.sleep(LOCK_POLL_INTERVAL)
+        try:
+            yield waited
+        finally:
+            _unlock(fd)
+    finally:
+        os.close(fd)
+
+
+#endregion
@@ -13,6 +13,8 @@ from collections.abc import Generator
 from contextlib import contextmanager
 from pathlib import Path
 
+from src.storage import encryption
+
 try:
     import duckdb
     DUCKDB_AVAILABLE = True
@@ -48,7 +50,7 @@ def sqlite_connection(db_path: Path) -> Generator[sqlite3.Connection, None, None
     Yields:
         SQLite connection object
     """
-    conn = sqlite3.connect(db_path)
+    conn = encryption.connect(db_path)
     try:
         yield conn
     finally:
@@ -315,8 +317,8 @@ def migrate_sqlite_to_duckdb(
         "errors": [],
     }
 
-    sqlite_conn = sqlite3.connect(sqlite_path)
-    sqlite_conn.row_factory = sqlite3.Row
+    sqlite_conn = encryption.connect(sqlite_path)
+    sqlite_conn.row_factory = encryption.row_factory()
     duckdb_conn = duckdb.connect(str(duckdb_path))
 
     try:
@@ -350,7 +352,7 @@ def migrate_sqlite_to_duckdb(
                     row_dict.get("device_type") or device_type,
                 ])
                 stats["daily_snapshots"] += 1
-        except sqlite3.OperationalError as e:
+        except encryption.driver_errors("OperationalError") as e:
             if "no such table" not in str(e).lower():
                 logger.warning(f"Error migrating daily_snapshots: {e}")
                 stats["errors"].append(f"daily_snapshots: {e}")
@@ -401,7 +403,7 @@ def migrate_sqlite_to_duckdb(
                         row_dict.get("device_type") or device_type,
                     ])
                     stats["usage_records"] += 1
-        except sqlite3.OperationalError as e:
+        except encryption.driver_errors("OperationalError") as e:
             if "no such table" not in str(e).lower():
                 logger.warning(f"Error migrating usage_records: {e}")
                 stats["errors"].append(f"usage_records: {e}")
@@ -431,7 +433,7 @@ def migrate_sqlite_to_duckdb(
                     row_dict.get("device_type") or device_type,
                 ])
                 stats["limits_snapshots"] += 1
-        except sqlite3.OperationalError as e:
+        except encryption.driver_errors("OperationalError") as e:
             if "no such table" not in str(e).lower():
                 logger.warning(f"Error migrating limits_snapshots: {e}")
                 stats["errors"].append(f"limits_snapshots: {e}")
@@ -453,7 +455,7 @@ def migrate_sqlite_to_duckdb(
                     row_dict["last_parsed"],
                 ])
                 stats["file_metadata"] += 1
-        except sqlite3.OperationalError as e:
+        except encryption.driver_errors("OperationalError") as e:
             if "no such table" not in str(e).lower():
                 logger.warning(f"Error migrating file_metadata: {e}")
                 stats["errors"].append(f"file_metadata: {e}")
@@ -525,7 +527,7 @@ def migrate_duckdb_to_sqlite(
     }
 
     duckdb_conn = duckdb.connect(str(duckdb_path))
-    sqlite_conn = sqlite3.connect(sqlite_path)
+    sqlite_conn = encryption.connect(sqlite_path)
 
     try:
         sqlite_cursor = sqlite_conn.cursor()
@@ -601,7 +603,7 @@ def migrate_duckdb_to_sqlite(
                         row_dict.get("device_type") or device_type,
                     ))
                     stats["usage_records"] += 1
-                except sqlite3.IntegrityError:
+                except encryption.driver_errors("IntegrityError"):
                     logger.debug("Skipping duplicate usage_record")
         except duckdb.CatalogException as e:
             if "not exist" not in str(e).lower():
@@ -713,7 +715,7 @@ def get_migration_status(sqlite_path: Path, duckdb_path: Path) -> dict:
                 cursor = conn.cursor()
                 cursor.execute("SELECT COUNT(*) FROM daily_snapshots")
                 status["sqlite"]["record_count"] = cursor.fetchone()[0]
-        except sqlite3.Error as e:
+        except encryption.driver_errors("Error") as e:
             status["sqlite"]["error"] = str(e)
             logger.warning(f"Error checking SQLite status: {e}")
 
@@ -0,0 +1,430 @@
+"""
+Model pricing resolution shared by the storage backends.
+
+Pricing rows are keyed by an exact model name, but transcripts carry dated
+snapshots, provider-qualified ids (Bedrock, Vertex, OpenRouter) and new
+releases that predate a pricing update. Costs are resolved here in Python
+rather than with an exact-match SQL join, so those models are priced by
+their family instead of silently costing zero.
+"""
+#region Imports
+import json
+import re
+from collections.abc import Iterable, Mapping
+from pathlib import Path
+
+from src.config.user_config import get_pricing_multipliers, get_pricing_overrides, get_pricing_tier
+
+#endregion
+
+
+#region Constants
+PRICING_JSON_PATH = Path(__file__).parent.parent / "data" / "model_pricing.json"
+
+# Trailing snapshot date: claude-sonnet-4-5-20250929
+_DATE_SUFFIX = re.compile(r"-\d{8}$")
+# Bedrock ids: us.anthropic.claude-sonnet-4-5-20250929-v1:0
+_BEDROCK_PREFIX = re.compile(r"^(?:[a-z]{2,4}\.)?anthropic\.")
+_BEDROCK_SUFFIX = re.compile(r"-v\d+(?::\d+)?$")
+
+# Prompt size (input + cache writes + cache reads) above which a request
+# bills at its model's long-context rates, if the model has any
+LONG_CONTEXT_THRESHOLD = 200_000
+
+# Per-request long-context flag for SQL summaries over usage_records, so
+# summed rows can still be priced at the right tier
+LONG_CONTEXT_SQL = (
+    f"(COALESCE(input_tokens, 0) + COALESCE(cache_creation_tokens, 0)"
+    f" + COALESCE(cache_read_tokens, 0) > {LONG_CONTEXT_THRESHOLD})"
+)
+#endregion
+
+
+#region Functions
+
+
+def _load_pricing_json(json_path: Path) -> dict:
+    """Contents of model_pricing.json ({} if unreadable)."""
+    try:
+        with open(json_path, encoding="utf-8") as f:
+            data = json.load(f)
+    except (OSError, json.JSONDecodeError):
+        return {}
+    return data if isinstance(data, dict) else {}
+
+
+def _load_pricing_models(json_path: Path) -> dict:
+    """Model entries of model_pricing.json ({} if unreadable)."""
+    models = _load_pricing_json(json_path).get("models", {})
+    return models if isinstance(models, dict) else {}
+
+
+def load_pricing_aliases(json_path: Path = PRICING_JSON_PATH) -> dict[str, str]:
+    """
+    Load alias -> pricing model name pairs from model_pricing.json.
+
+    Each model entry may carry an "aliases" list of alternate ids that bill
+    at the same rates (e.g. the API's claude-3-5-haiku-20241022 naming).
+
+    Returns:
+        Mapping of alias to canonical pricing model name ({} if unreadable)
+    """
+    aliases: dict[str, str] = {}
+    for model_name, model_data in _load_pricing_models(json_path).items():
+        for alias in model_data.get("aliases", []) if isinstance(model_data, dict) else []:
+            if isinstance(alias, str) and alias:
+                aliases[alias] = model_name
+    return aliases
+
+
+def load_long_context_prices(json_path: Path = PRICING_JSON_PATH) -> dict[str, tuple]:
+    """
+    Load long-context rates from model_pricing.json.
+
+    A model entry may carry a "long_context" object with the same
+    *_per_mtok keys as the entry itself, applied to requests whose prompt
+    exceeds LONG_CONTEXT_THRESHOLD tokens. Rates it omits fall back to the
+    base rate (the 1h cache write to 1.6x the long-context 5m rate).
+
+    Returns:
+        Mapping of pricing model name to (input, output, cache_write,
+        cache_read, cache_write_1h) per MTok ({} if unreadable)
+    """
+    prices: dict[str, tuple] = {}
+    for model_name, model_data in _load_pricing_models(json_path).items():
+        if not isinstance(model_data, dict) or not isinstance(model_data.get("long_context"), dict):
+            continue
+        tier = model_data["long_context"]
+        prices[model_name] = (
+            tier.get("input_per_mtok", model_data.get("input_per_mtok")),
+            tier.get("output_per_mtok", model_data.get("output_per_mtok")),
+            tier.get("cache_write_per_mtok", model_data.get("cache_write_per_mtok")),
+            tier.get("cache_read_per_mtok", model_data.get("cache_read_per_mtok")),
+            tier.get("cache_write_1h_per_mtok"),
+        )
+    return prices
+
+
+def load_tier_multipliers(tier: str, json_path: Path = PRICING_JSON_PATH) -> dict[str, float]:
+    """
+    Load rate multipliers for a pricing tier from model_pricing.json.
+
+    The top-level "tier_multipliers" object sets each tier's multiplier for
+    every model ("batch": 0.5); a model entry's own "tier_multipliers"
+    overrides it. Standard rates always multiply by 1.
+
+    Args:
+        tier: "standard", "batch", or "priority"
+
+    Returns:
+        Mapping of pricing model name to multiplier, with "*" for models
+        without their own ({} for standard rates or if unreadable)
+    """
+    if tier == "standard":
+        return {}
+    data = _load_pricing_json(json_path)
+
+    multipliers: dict[str, float] = {}
+    default = (data.get("tier_multipliers") or {}).get(tier)
+    if isinstance(default, (int, float)):
+        multipliers["*"] = float(default)
+    for model_name, model_data in _load_pricing_models(json_path).items():
+        value = (model_data.get("tier_multipliers") or {}).get(tier) if isinstance(model_data, dict) else None
+        if isinstance(value, (int, float)):
+            multipliers[model_name] = float(value)
+    return multipliers
+
+
+def load_pricing_overrides() -> dict[str, tuple]:
+    """
+    Rate overrides from the "pricing_overrides" config (`ccg pricing set`).
+
+    Cache rates an entry omits default to the standard multiples of its
+    input rate (writes 1.25x, reads 0.1x); the 1h cache write falls back as
+    in compute_cost.
+
+    Returns:
+        Mapping of model name to (input, output, cache_write, cache_read,
+        cache_write_1h) per MTok
+    """
+    prices = {}
+    for model_name, rates in get_pricing_overrides().items():
+        input_price = rates["input_per_mtok"]
+        prices[model_name] = (
+            input_price,
+            rates["output_per_mtok"],
+            rates.get("cache_write_per_mtok", input_price * 1.25),
+            rates.get("cache_read_per_mtok", input_price * 0.1),
+            rates.get("cache_write_1h_per_mtok"),
+        )
+    return prices
+
+
+def normalize_model_name(model: str) -> str:
+    """
+    Strip provider qualifiers from a model id.
+
+    Handles OpenRouter-style "anthropic/claude-..." prefixes, Bedrock
+    "us.anthropic.claude-...-v1:0" ids and Vertex "claude-...@20250929"
+    snapshots.
+    """
+    name = model.strip().lower()
+    if "/" in name:
+        name = name.rsplit("/", 1)[-1]
+    name = _BEDROCK_PREFIX.sub("", name)
+    name = _BEDROCK_SUFFIX.sub("", name)
+    return name.replace("@", "-")
+
+
+def _strip_date(name: str) -> str:
+    return _DATE_SUFFIX.sub("", name)
+
+
+def match_pricing_model(
+    model: str,
+    pricing_names: Iterable[str],
+    aliases: Mapping[str, str] | None = None,
+) -> str | None:
+    """
+    Resolve a transcript model name to a pricing row name.
+
+    Tried in order: exact match, configured alias, provider-normalized name,
+    same model with a different snapshot date, then the longest pricing
+    family that prefixes the model (claude-sonnet-4-5-latest -> Sonnet 4.5).
+    A family never absorbs a newer version number: claude-opus-4-9 does not
+    fall back to claude-opus-4.
+
+    Args:
+        model: Model name as recorded in usage_records
+        pricing_names: Model names present in the pricing table
+        aliases: Optional alias -> pricing name mapping
+
+    Returns:
+        Matching pricing model name, or None when nothing fits
+    """
+    names = set(pricing_names)
+    if model in names:
+        return model
+    if aliases and aliases.get(model) in names:
+        return aliases[model]
+
+    normalized = normalize_model_name(model)
+    if normalized in names:
+        return normalized
+    if aliases and aliases.get(normalized) in names:
+        return aliases[normalized]
+
+    # Base name -> pricing row; on collisions the latest snapshot wins
+    by_base: dict[str, str] = {}
+    for name in sorted(names):
+        by_base[_strip_date(name)] = name
+
+    base = _strip_date(normalized)
+    if base in by_base:
+        return by_base[base]
+
+    candidates = []
+    for family in by_base:
+        if not base.startswith(family + "-"):
+            continue
+        next_segment = base[len(family) + 1:].split("-", 1)[0]
+        if next_segment.isdigit():
+            continue
+        candidates.append(family)
+    if candidates:
+        return by_base[max(candidates, key=len)]
+    return None
+
+
+def compute_cost(
+    input_tokens: int,
+    output_tokens: int,
+    cache_write_tokens: int,
+    cache_read_tokens: int,
+    cache_write_1h_tokens: int,
+    prices: tuple[float | None, ...],
+) -> float:
+    """
+    Dollar cost of a token bundle at one pricing row.
+
+    cache_write_tokens is the TOTAL cache write (5m + 1h); the 1h share bills
+    at its own rate, falling back to 1.6x the 5m rate (2x vs 1.25x base
+    input) when the row has none.
+
+    Args:
+        prices: (input, output, cache_write, cache_read, cache_write_1h) per MTok
+    """
+    input_price, output_price, cache_write_price, cache_read_price, cache_write_1h_price = (
+        tuple(prices) + (None,) * 5
+    )[:5]
+    cache_write_price = cache_write_price or 0.0
+    if cache_write_1h_price is None:
+        cache_write_1h_price = cache_write_price * 1.6
+    return (
+        (input_tokens / 1_000_000) * (input_price or 0.0)
+        + (output_tokens / 1_000_000) * (output_price or 0.0)
+        + ((cache_write_tokens - cache_write_1h_tokens) / 1_000_000) * cache_write_price
+        + (cache_write_1h_tokens / 1_000_000) * cache_write_1h_price
+        + (cache_read_tokens / 1_000_000) * (cache_read_price or 0.0)
+    )
+
+
+class PricingTable:
+    """
+    Model pricing rows with cached fuzzy model resolution.
+
+    The one pricing lookup shared by SQL-side summaries and per-record costs
+    (UsageRecord.cost), so every view prices a model the same way.
+
+    Args:
+        pricing_rows: (model_name, input, output, cache_write, cache_read, cache_write_1h)
+        aliases: Alias -> pricing model name (defaults to model_pricing.json aliases)
+        long_context_prices: Pricing model name -> long-context rates
+            (defaults to model_pricing.json long_context entries)
+        multipliers: Model name -> multiplier applied to every cost, "*" for
+            the rest (defaults to the configured pricing_tier's multipliers,
+            overridden by pricing_multipliers)
+        overrides: Model name -> rates replacing that model's row (and its
+            long-context rates), or added as a new row when it has none
+            (defaults to load_pricing_overrides)
+    """
+
+    long_context_threshold = LONG_CONTEXT_THRESHOLD
+
+    def __init__(
+        self,
+        pricing_rows: Iterable[tuple],
+        aliases: Mapping[str, str] | None = None,
+        long_context_prices: Mapping[str, tuple] | None = None,
+        multipliers: Mapping[str, float] | None = None,
+        overrides: Mapping[str, tuple] | None = None,
+    ):
+        self.prices = {row[0]: tuple(row[1:6]) for row in pricing_rows}
+        self.aliases = load_pricing_aliases() if aliases is None else aliases
+        self.long_context_prices = dict(
+            load_long_context_prices() if long_context_prices is None else long_context_prices
+        )
+        # An override replaces the row for the same model (up to provider
+        # prefix and snapshot date), never a family it would fall back to
+        for name, prices in (load_pricing_overrides() if overrides is None else overrides).items():
+            target = match_pricing_model(name, self.prices, self.aliases)
+            if target is None or (
+                target != self.aliases.get(name)
+                and _strip_date(normalize_model_name(target)) != _strip_date(normalize_model_name(name))
+            ):
+                target = name
+            self.prices[target] = tuple(prices)
+            self.long_context_prices.pop(target, None)
+        self._matches: dict[str, str | None] = {}
+        if multipliers is None:
+            multipliers = {**load_tier_multipliers(get_pricing_tier()), **get_pricing_multipliers()}
+        # Keyed by pricing row, so a multiplier set for an alias or dated id
+        # reaches the model it prices
+        self.multipliers = {
+            name if name == "*" else (self.match(name) or name): value
+            for name, value in multipliers.items()
+        }
+
+    def match(self, model: str | None) -> str | None:
+        """Pricing row name for a model (see match_pricing_model), or None."""
+        if not model:
+            return None
+        if model not in self._matches:
+            self._matches[model] = match_pricing_model(model, self.prices, self.aliases)
+        return self._matches[model]
+
+    def cost(
+        self,
+        model: str | None,
+        input_tokens: int = 0,
+        output_tokens: int = 0,
+        cache_write_tokens: int = 0,
+        cache_read_tokens: int = 0,
+        cache_write_1h_tokens: int = 0,
+        long_context: bool = False,
+    ) -> float:
+        """
+        Dollar cost of a token bundle for one model (0.0 when unpriced).
+
+        cache_write_tokens is the total cache write, as in compute_cost.
+        long_context marks tokens from requests above long_context_threshold;
+        they bill at the model's long-context rates when it has them. The
+        model's multiplier (pricing tier) scales the result.
+        """
+        match = self.match(model)
+        if match is None:
+            return 0.0
+        prices = self.prices[match]
+        if long_context:
+            prices = self.long_context_prices.get(match, prices)
+        multiplier = self.multipliers.get(match, self.multipliers.get("*", 1.0))
+        return multiplier * compute_cost(
+            input_tokens, output_tokens, cache_write_tokens, cache_read_tokens, cache_write_1h_tokens,
+            prices=prices,
+        )
+
+
+def price_model_usage(
+    usage_rows: Iterable[tuple],
+    pricing_rows: Iterable[tuple],
+) -> tuple[dict[str, float], list[str]]:
+    """
+    Cost per model for summed usage, with fuzzy pricing resolution.
+
+    Args:
+        usage_rows: (model, input, output, cache_write_total, cache_read,
+            cache_write_1h[, long_context]); a model may span two rows split
+            on LONG_CONTEXT_SQL
+        pricing_rows: (model_name, input, output, cache_write, cache_read, cache_write_1h)
+
+    Returns:
+        Tuple of (cost_by_model, unpriced_models); unpriced models with
+        any usage are listed, sorted, and cost nothing
+    """
+    pricing = PricingTable(pricing_rows)
+
+    cost_by_model: dict[str, float] = {}
+    unpriced: list[str] = []
+    for row in usage_rows:
+        model = row[0]
+        if not model:
+            continue
+        tokens = [value or 0 for value in row[1:6]]
+        if pricing.match(model) is None:
+            if any(tokens) and model not in unpriced:
+                unpriced.append(model)
+            continue
+        cost = pricing.cost(model, *tokens, long_context=bool(row[6]) if len(row) > 6 else False)
+        cost_by_model[model] = cost_by_model.get(model, 0.0) + cost
+    return cost_by_model, sorted(unpriced)
+
+
+def price_grouped_model_usage(
+    usage_rows: Iterable[tuple],
+    pricing_rows: Iterable[tuple],
+) -> dict[str, float]:
+    """
+    Cost per group key (a date, a session id, ...) for per-model usage rows.
+
+    Args:
+        usage_rows: (key, model, input, output, cache_write_total, cache_read,
+            cache_write_1h[, long_context])
+        pricing_rows: (model_name, input, output, cache_write, cache_read, cache_write_1h)
+
+    Returns:
+        Mapping of key to dollar cost (unpriced models contribute nothing)
+    """
+    pricing = PricingTable(pricing_rows)
+
+    cost_by_key: dict[str, float] = {}
+    for row in usage_rows:
+        key, model = row[0], row[1]
+        cost = pricing.cost(
+            model, *[value or 0 for value in row[2:7]],
+            long_context=bool(row[7]) if len(row) > 7 else False,
+        )
+        cost_by_key[key] = cost_by_key.get(key, 0.0) + cost
+    return cost_by_key
+
+
+#endregion
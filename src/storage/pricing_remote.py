@@ -0,0 +1,239 @@
+"""
+Model pricing fetched from a remote source.
+
+`ccg pricing update` (or, when "pricing_auto_update" is on, `ccg update
+usage` once the cache is older than "pricing_update_hours") downloads a
+pricing file such as LiteLLM's model_prices_and_context_window.json and
+keeps its Anthropic models in remote_pricing.json beside the usage
+database (~/.claude/usage/ by default), shaped like
+src/data/model_pricing.json plus the fetch time. Both backends layer
+it over their bundled rows whenever they sync model_pricing, so fetched
+rates survive re-initialization and new models are priced before a ccg
+release ships them.
+"""
+#region Imports
+import json
+import urllib.error
+import urllib.request
+from datetime import datetime, timedelta
+from pathlib import Path
+
+from src.config.user_config import get_pricing_source, get_pricing_update_hours, is_pricing_auto_update
+from src.storage import get_db_path
+from src.utils._system import atomic_write_text
+
+#endregion
+
+
+#region Constants
+# Cache file name, in the directory of the usage database
+REMOTE_PRICING_NAME = "remote_pricing.json"
+
+FETCH_TIMEOUT_SECONDS = 10
+
+# LiteLLM per-token cost keys -> model_pricing.json per-MTok keys
+_LITELLM_KEYS = {
+    "input_cost_per_token": "input_per_mtok",
+    "output_cost_per_token": "output_per_mtok",
+    "cache_creation_input_token_cost": "cache_write_per_mtok",
+    "cache_read_input_token_cost": "cache_read_per_mtok",
+    "cache_creation_input_token_cost_above_1hr": "cache_write_1h_per_mtok",
+}
+#endregion
+
+
+#region Functions
+
+
+def remote_pricing_path() -> Path:
+    """Cache file beside the usage database, so each profile or $CCG_DB_PATH setup keeps its own."""
+    return get_db_path().parent / REMOTE_PRICING_NAME
+
+
+def _is_rate(value) -> bool:
+    """True for a numeric rate (JSON booleans are not rates)."""
+    return isinstance(value, (int, float)) and not isinstance(value, bool)
+
+
+def parse_litellm_pricing(data: dict) -> dict[str, dict]:
+    """
+    Anthropic model rates from a LiteLLM-format pricing file.
+
+    Keeps first-party ids ("claude-...", provider "anthropic"); Bedrock and
+    Vertex ids resolve to them through the usual model matching.
+
+    Args:
+        data: Parsed pricing file (model id -> entry with *_cost_per_token keys)
+
+    Returns:
+        Model name -> rates under model_pricing.json keys
+    """
+    models = {}
+    for name, entry in data.items():
+        if (
+            not isinstance(entry, dict)
+            or not name.startswith("claude-")
+            or entry.get("litellm_provider") != "anthropic"
+        ):
+            continue
+        rates = {
+            key: round(float(entry[cost_key]) * 1_000_000, 6)
+            for cost_key, key in _LITELLM_KEYS.items()
+            if _is_rate(entry.get(cost_key))
+        }
+        if "input_per_mtok" in rates and "output_per_mtok" in rates:
+            models[name] = rates
+    return models
+
+
+def load_remote_pricing(path: Path | None = None) -> dict:
+    """
+    The cached remote pricing file ({} if never fetched or unreadable).
+
+    Returns:
+        Dict with "source", "fetched_at", "attempted_at", and "models"
+    """
+    try:
+        data = json.loads((path or remote_pricing_path()).read_text(encoding="utf-8"))
+    except (OSError, json.JSONDecodeError):
+        return {}
+    return data if isinstance(data, dict) else {}
+
+
+def remote_pricing_rows(path: Path | None = None) -> list[tuple]:
+    """
+    Cached remote rates as model_pricing rows.
+
+    Returns:
+        (model_name, input, output, cache_write, cache_read, cache_write_1h,
+        notes) tuples, as load_model_pricing returns; omitted cache rates
+        default to 1.25x (writes) and 0.1x (reads) of input. Models with a
+        missing or non-numeric rate (a hand-edited cache) are skipped
+    """
+    models = load_remote_pricing(path).get("models")
+    rows = []
+    for name, rates in (models if isinstance(models, dict) else {}).items():
+        if (
+            not isinstance(rates, dict)
+            or not _is_rate(rates.get("input_per_mtok"))
+            or not _is_rate(rates.get("output_per_mtok"))
+            or not all(_is_rate(rates[key]) for key in _LITELLM_KEYS.values() if key in rates)
+        ):
+            continue
+        input_price = rates["input_per_mtok"]
+        cache_write = rates.get("cache_write_per_mtok", round(input_price * 1.25, 6))
+        rows.append((
+            name,
+            input_price,
+            rates["output_per_mtok"],
+            cache_write,
+            rates.get("cache_read_per_mtok", round(input_price * 0.1, 6)),
+            rates.get("cache_write_1h_per_mtok", round(cache_write * 1.6, 4)),
+            "Fetched by ccg pricing update",
+        ))
+    return rows
+
+
+def with_remote_pricing(rows: list[tuple], path: Path | None = None) -> list[tuple]:
+    """
+    Bundled pricing rows with cached remote rates layered on top.
+
+    A fetched model replaces the bundled row of the same name; models the
+    bundled pricing lacks are added.
+
+    Args:
+        rows: Bundled (model_name, ..., notes) rows
+
+    Returns:
+        Merged rows
+    """
+    merged = {row[0]: row for row in rows}
+    merged.update((row[0], row) for row in remote_pricing_rows(path))
+    return list(merged.values())
+
+
+def fetch_remote_pricing(url: str | None = None) -> dict[str, dict]:
+    """
+    Download a LiteLLM-format pricing file and keep its Anthropic models.
+
+    Args:
+        url: Pricing file URL (default: the "pricing_source" config key)
+
+    Returns:
+        Model name -> rates (see parse_litellm_pricing)
+
+    Raises:
+        RuntimeError: If the download fails or holds no Anthropic models
+    """
+    url = url or get_pricing_source()
+    request = urllib.request.Request(url, headers={"User-Agent": "claude-goblin"})
+    try:
+        with urllib.request.urlopen(request, timeout=FETCH_TIMEOUT_SECONDS) as response:
+            data = json.loads(response.read().decode("utf-8"))
+    except (urllib.error.URLError, TimeoutError) as e:
+        raise RuntimeError(f"{url}: {getattr(e, 'reason', e)}") from e
+    except (json.JSONDecodeError, UnicodeDecodeError) as e:
+        raise RuntimeError(f"{url}: not a JSON pricing file ({e})") from e
+
+    models = parse_litellm_pricing(data) if isinstance(data, dict) else {}
+    if not models:
+        raise RuntimeError(f"{url}: no Anthropic model pricing found")
+    return models
+
+
+def update_remote_pricing(url: str | None = None, path: Path | None = None) -> dict[str, dict]:
+    """
+    Fetch remote pricing and cache it with the fetch time.
+
+    A failed fetch keeps the previous cache but records the attempt, so
+    auto-refresh waits a full period before retrying.
+
+    Args:
+        url: Pricing file URL (default: the "pricing_source" config key)
+        path: Cache file (default: beside the usage database)
+
+    Returns:
+        The fetched models
+
+    Raises:
+        RuntimeError: If the fetch fails
+    """
+    url = url or get_pricing_source()
+    path = path or remote_pricing_path()
+    now = datetime.now().isoformat(timespec="seconds")
+    cache = load_remote_pricing(path)
+    try:
+        models = fetch_remote_pricing(url)
+    except RuntimeError:
+        if cache:
+            atomic_write_text(path, json.dumps({**cache, "attempted_at": now}, indent=2) + "\n")
+        raise
+    path.parent.mkdir(parents=True, exist_ok=True)
+    atomic_write_text(path, json.dumps({
+        "source": url,
+        "fetched_at": now,
+        "attempted_at": now,
+        "models": models,
+    }, indent=2) + "\n")
+    return models
+
+
+def remote_pricing_is_stale(now: datetime | None = None, path: Path | None = None) -> bool:
+    """
+    Whether auto-refresh should fetch: on, and the last attempt older than "pricing_update_hours".
+
+    Args:
+        now: Reference time (default: now)
+        path: Cache file (default: beside the usage database)
+    """
+    if not is_pricing_auto_update():
+        return False
+    attempted = load_remote_pricing(path).get("attempted_at")
+    try:
+        last = datetime.fromisoformat(attempted)
+    except (TypeError, ValueError):
+        return True
+    return (now or datetime.now()) - last >= timedelta(hours=get_pricing_update_hours())
+
+
+#endregion
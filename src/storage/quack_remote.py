@@ -19,6 +19,7 @@ except ImportError:
 
 from src.config.user_config import get_sync_config
 from src.models.usage_record import TokenUsage, UsageRecord
+from src.storage.pricing import LONG_CONTEXT_SQL, price_model_usage
 
 #endregion
 
@@ -755,7 +756,7 @@ def get_database_stats() -> dict:
                 "oldest_date": None, "newest_date": None, "newest_timestamp": None,
                 "total_tokens": 0, "total_prompts": 0, "total_responses": 0,
                 "total_sessions": 0, "tokens_by_model": {}, "cost_by_model": {},
-                "total_cost": 0.0, "avg_tokens_per_session": 0,
+                "unpriced_models": [], "total_cost": 0.0, "avg_tokens_per_session": 0,
                 "avg_tokens_per_response": 0, "avg_cost_per_session": 0.0,
                 "avg_cost_per_response": 0.0,
             }
@@ -792,33 +793,26 @@ def get_database_stats() -> dict:
 
         total_cost = 0.0
         cost_by_model = {}
+        unpriced_models = []
         try:
-            cost_rows = conn.execute("""
-                SELECT ur.model,
-                    SUM(ur.input_tokens), SUM(ur.output_tokens),
-                    SUM(ur.cache_creation_tokens), SUM(ur.cache_read_tokens),
-                    mp.input_price_per_mtok, mp.output_price_per_mtok,
-                    mp.cache_write_price_per_mtok, mp.cache_read_price_per_mtok,
-                    SUM(COALESCE(ur.cache_creation_1h_tokens, 0)),
-                    mp.cache_write_1h_price_per_mtok
-                FROM remote.usage_records ur
-                LEFT JOIN remote.model_pricing mp ON ur.model = mp.model_name
-                WHERE ur.model IS NOT NULL
-                GROUP BY ur.model, mp.input_price_per_mtok, mp.output_price_per_mtok,
-                         mp.cache_write_price_per_mtok, mp.cache_read_price_per_mtok,
-                         mp.cache_write_1h_price_per_mtok
+            usage_rows = conn.execute(f"""
+                SELECT model,
+                    SUM(input_tokens), SUM(output_tokens),
+                    SUM(cache_creation_tokens), SUM(cache_read_tokens),
+                    SUM(COALESCE(cache_creation_1h_tokens, 0)),
+                    {LONG_CONTEXT_SQL}
+                FROM remote.usage_records
+                WHERE model IS NOT NULL
+                GROUP BY model, {LONG_CONTEXT_SQL}
             """).fetchall()
-            for row in cost_rows:
-                write_1h_price = row[10] if row[10] is not None else (row[7] or 0) * 1.6
-                model_cost = (
-                    ((row[1] or 0) / 1_000_000) * (row[5] or 0) +
-                    ((row[2] or 0) / 1_000_000) * (row[6] or 0) +
-                    (((row[3] or 0) - (row[9] or 0)) / 1_000_000) * (row[7] or 0) +
-                    ((row[9] or 0) / 1_000_000) * write_1h_price +
-                    ((row[4] or 0) / 1_000_000) * (row[8] or 0)
-                )
-                cost_by_model[row[0]] = model_cost
-                total_cost += model_cost
+            pricing_rows = conn.execute("""
+                SELECT model_name, input_price_per_mtok, output_price_per_mtok,
+                       cache_write_price_per_mtok, cache_read_price_per_mtok,
+                       cache_write_1h_price_per_mtok
+                FROM remote.model_pricing
+            """).fetchall()
+            cost_by_model, unpriced_models = price_model_usage(usage_rows, pricing_rows)
+            total_cost = sum(cost_by_model.values())
         except Exception:
             pass
 
@@ -834,6 +828,7 @@ def get_database_stats() -> dict:
             "total_sessions": total_sessions,
             "tokens_by_model": tokens_by_model,
             "cost_by_model": cost_by_model,
+            "unpriced_models": unpriced_models,
             "total_cost": total_cost,
             "avg_tokens_per_session": round(total_tokens / total_sessions) if total_sessions else 0,
             "avg_tokens_per_response": round(total_tokens / total_responses) if total_responses else 0,
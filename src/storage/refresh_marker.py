@@ -0,0 +1,129 @@
+"""
+Lightweight "database changed" signal for live views.
+
+Ingestion rewrites a tiny marker file next to the usage database; the live
+dashboard and TUI (and any external status bar) stat() it instead of
+re-running the stats query on a timer, refreshing the moment new data lands.
+"""
+#region Imports
+import json
+import time
+from datetime import datetime
+from pathlib import Path
+
+from src.storage import get_db_path
+from src.utils._system import atomic_write_text
+
+#endregion
+
+
+#region Constants
+# Marker file name, in the directory of the database it signals for
+REFRESH_MARKER_NAME = "last_updated.json"
+
+# How often waiters stat() the marker (seconds)
+MARKER_POLL_INTERVAL = 0.5
+#endregion
+
+
+#region Functions
+
+
+def refresh_marker_path(db_path: Path | None = None) -> Path:
+    """
+    Marker file for a database: beside it, so profiles and databases set by
+    $CCG_DB_PATH elsewhere don't share one.
+
+    Args:
+        db_path: Usage database (default: the one this process uses)
+    """
+    return (db_path or get_db_path()).parent / REFRESH_MARKER_NAME
+
+
+def touch_refresh_marker(
+    new_records: int = 0,
+    started_at: str | None = None,
+    project: str | None = None,
+    db: str | None = None,
+    path: Path | None = None,
+) -> None:
+    """
+    Record that the usage database just changed.
+
+    Written atomically (temp file + rename) so readers never see a partial
+    file. Failures are swallowed: the marker is an optimization, never a
+    reason to fail ingestion.
+
+    Args:
+        new_records: Number of records the update saved
+        started_at: ISO timestamp the ingestion started (lets queued runs
+            tell whether it already covered them)
+        project: Project the ingestion was scoped to; None for all sources
+        db: Database the ingestion wrote to (databases in one directory
+            share the marker)
+        path: Marker file location (default: next to the usage database)
+    """
+    payload = {"updated_at": datetime.now().isoformat(), "new_records": new_records}
+    if started_at:
+        payload["started_at"] = started_at
+        payload["project"] = project
+        payload["db"] = db
+    try:
+        atomic_write_text(path or refresh_marker_path(), json.dumps(payload))
+    except OSError:
+        pass
+
+
+def read_refresh_marker(path: Path | None = None) -> int | None:
+    """
+    Cheap change token for the marker: its mtime in nanoseconds.
+
+    Returns:
+        Marker mtime_ns, or None if no update has been recorded yet
+    """
+    try:
+        return (path or refresh_marker_path()).stat().st_mtime_ns
+    except OSError:
+        return None
+
+
+def read_refresh_payload(path: Path | None = None) -> dict | None:
+    """
+    Contents of the marker file.
+
+    Returns:
+        Marker payload, or None if missing or unreadable
+    """
+    try:
+        return json.loads((path or refresh_marker_path()).read_text(encoding="utf-8"))
+    except (OSError, json.JSONDecodeError):
+        return None
+
+
+def wait_for_refresh(
+    last_seen: int | None,
+    timeout: float | None,
+    path: Path | None = None,
+) -> bool:
+    """
+    Block until the marker changes or the timeout elapses.
+
+    Args:
+        last_seen: Token from read_refresh_marker at the last refresh
+        timeout: Seconds to wait; None waits indefinitely
+        path: Marker file location (default: next to the usage database)
+
+    Returns:
+        True if the marker changed, False on timeout
+    """
+    path = path or refresh_marker_path()
+    deadline = None if timeout is None else time.monotonic() + timeout
+    while deadline is None or time.monotonic() < deadline:
+        if read_refresh_marker(path) != last_seen:
+            return True
+        remaining = MARKER_POLL_INTERVAL if deadline is None else deadline - time.monotonic()
+        time.sleep(max(min(MARKER_POLL_INTERVAL, remaining), 0))
+    return read_refresh_marker(path) != last_seen
+
+
+#endregion
@@ -0,0 +1,195 @@
+"""
+Versioned schema migrations for the SQLite database.
+
+init_database creates every table in its current shape, so a new database
+needs nothing from here. A database written by an older release is brought
+up to date by the migrations below, applied in order when it is opened and
+recorded in the schema_version table.
+
+To evolve the schema, add a column to the CREATE TABLE in init_database
+AND append a migration that adds it to existing databases. Never edit or
+renumber a released migration. Migrations must tolerate a schema that is
+already partly upgraded: SQLite commits ALTER TABLE immediately, so a run
+interrupted mid-migration repeats it on the next open.
+"""
+#region Imports
+import sqlite3
+from collections.abc import Callable
+from datetime import datetime
+
+#endregion
+
+
+#region Helper Functions
+
+
+def _columns(cursor: sqlite3.Cursor, table: str) -> set[str]:
+    cursor.execute(f"PRAGMA table_info({table})")
+    return {row[1] for row in cursor.fetchall()}
+
+
+def _add_columns(cursor: sqlite3.Cursor, table: str, *columns: str) -> None:
+    """Add "<name> <type>" columns to a table, skipping any it already has."""
+    existing = _columns(cursor, table)
+    for column in columns:
+        if column.split()[0] not in existing:
+            cursor.execute(f"ALTER TABLE {table} ADD COLUMN {column}")
+
+
+#endregion
+
+
+#region Migrations
+
+
+def _add_device_columns(cursor: sqlite3.Cursor) -> None:
+    for table in ("daily_snapshots", "usage_records", "limits_snapshots"):
+        _add_columns(cursor, table, "device_id TEXT", "device_name TEXT", "device_type TEXT")
+
+
+def _add_cache_creation_1h(cursor: sqlite3.Cursor) -> None:
+    _add_columns(cursor, "usage_records", "cache_creation_1h_tokens INTEGER DEFAULT 0")
+
+
+def _add_thinking_tokens(cursor: sqlite3.Cursor) -> None:
+    _add_columns(cursor, "usage_records", "thinking_tokens INTEGER DEFAULT 0")
+
+
+def _add_subagent_columns(cursor: sqlite3.Cursor) -> None:
+    _add_columns(cursor, "usage_records", "is_subagent INTEGER DEFAULT 0", "parent_session_id TEXT")
+
+
+def _add_attachment_count(cursor: sqlite3.Cursor) -> None:
+    _add_columns(cursor, "usage_records", "attachment_count INTEGER DEFAULT 0")
+
+
+def _add_compaction_details(cursor: sqlite3.Cursor) -> None:
+    _add_columns(
+        cursor, "session_events", "messages_before INTEGER", "context_tokens INTEGER NOT NULL DEFAULT 0"
+    )
+
+
+def _add_session_entrypoint(cursor: sqlite3.Cursor) -> None:
+    _add_columns(cursor, "sessions", "entrypoint TEXT")
+
+
+def _add_cache_write_1h_price(cursor: sqlite3.Cursor) -> None:
+    _add_columns(cursor, "model_pricing", "cache_write_1h_price_per_mtok REAL")
+
+
+def _backfill_model_mix(cursor: sqlite3.Cursor) -> None:
+    cursor.execute("SELECT COUNT(*) FROM daily_model_snapshots")
+    if cursor.fetchone()[0] == 0:
+        from src.storage.duckdb_backend import _MODEL_MIX_REBUILD_SQL
+        cursor.execute(_MODEL_MIX_REBUILD_SQL.format(where=""))
+
+
+
+def _add_hostname(cursor: sqlite3.Cursor) -> None:
+    _add_columns(cursor, "usage_records", "hostname TEXT")
+    _add_columns(cursor, "daily_snapshots", "hostname TEXT")
+
+
+def _add_project(cursor: sqlite3.Cursor) -> None:
+    _add_columns(cursor, "usage_records", "project TEXT")
+
+
+def _add_agent_id(cursor: sqlite3.Cursor) -> None:
+    _add_columns(cursor, "usage_records", "agent_id TEXT")
+
+
+def _backfill_hourly(cursor: sqlite3.Cursor) -> None:
+    cursor.execute("SELECT COUNT(*) FROM hourly_snapshots")
+    if cursor.fetchone()[0] == 0:
+        from src.storage.duckdb_backend import _rebuild_hourly_snapshots
+        cursor.execute("SELECT DISTINCT date FROM usage_records")
+        _rebuild_hourly_snapshots(cursor, [row[0] for row in cursor.fetchall()])
+
+def _unique_responses(cursor: sqlite3.Cursor) -> None:
+    from src.storage.duckdb_backend import _dedupe_responses
+    _dedupe_responses(cursor)
+    cursor.execute("""
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_usage_records_response
+        ON usage_records(message_uuid) WHERE message_type = 'assistant'
+    """)
+
+
+# (version, description, migration), in the order they are applied
+MIGRATIONS: list[tuple[int, str, Callable[[sqlite3.Cursor], None]]] = [
+    (1, "Device metadata columns for multi-device sync", _add_device_columns),
+    (2, "1h cache-write split on usage_records", _add_cache_creation_1h),
+    (3, "Thinking tokens on usage_records", _add_thinking_tokens),
+    (4, "Subagent flag and parent session on usage_records", _add_subagent_columns),
+    (5, "Prompt attachment counts on usage_records", _add_attachment_count),
+    (6, "Compaction details on session_events", _add_compaction_details),
+    (7, "Session entrypoint", _add_session_entrypoint),
+    (8, "1h cache-write price on model_pricing", _add_cache_write_1h_price),
+    (9, "Backfill daily_model_snapshots from usage_records", _backfill_model_mix),
+    (10, "Backfill hourly_snapshots from usage_records", _backfill_hourly),
+    (11, "Hostname on usage_records and daily_snapshots", _add_hostname),
+    (12, "Drop duplicate responses and index usage_records by response", _unique_responses),
+    (13, "Project scope on usage_records", _add_project),
+    (14, "Subagent id on usage_records", _add_agent_id),
+]
+
+LATEST_SCHEMA_VERSION = MIGRATIONS[-1][0]
+
+
+#endregion
+
+
+#region Functions
+
+
+def get_schema_version(cursor: sqlite3.Cursor) -> int:
+    """
+    Highest migration applied to a database (0 before any were recorded).
+
+    Args:
+        cursor: SQLite cursor
+    """
+    cursor.execute(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'"
+    )
+    if cursor.fetchone() is None:
+        return 0
+    cursor.execute("SELECT MAX(version) FROM schema_version")
+    return cursor.fetchone()[0] or 0
+
+
+def apply_migrations(cursor: sqlite3.Cursor) -> list[int]:
+    """
+    Apply pending migrations in order, recording each in schema_version.
+
+    Called by init_database after the tables exist. A database from a newer
+    release (version above LATEST_SCHEMA_VERSION) is left untouched.
+
+    Args:
+        cursor: SQLite cursor (the caller commits)
+
+    Returns:
+        Versions applied by this call, in order
+    """
+    current = get_schema_version(cursor)
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        )
+    """)
+
+    applied = []
+    for version, description, migration in MIGRATIONS:
+        if version <= current:
+            continue
+        migration(cursor)
+        cursor.execute(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)",
+            (version, description, datetime.now().isoformat()),
+        )
+        applied.append(version)
+    return applied
+
+
+#endregion
@@ -1,23 +1,241 @@
 #region Imports
+import functools
+import inspect
 import json
 import sqlite3
+import threading
+import time
+from collections.abc import Callable, Iterator
+from contextlib import contextmanager
+from dataclasses import replace
 from datetime import datetime
 from pathlib import Path
+from typing import TypeVar
 
-from src.models.usage_record import UsageRecord
+from src.config.user_config import is_read_only
+from src.errors import DatabaseLockedError, SchemaMismatchError
+from src.models.usage_record import QuarantinedEntry, Session, SessionEvent, TranscriptSummary, UsageRecord
+from src.storage import encryption
+from src.storage.pricing import LONG_CONTEXT_SQL, match_pricing_model, price_model_usage
+from src.storage.pricing_remote import with_remote_pricing
+from src.storage.schema_migrations import LATEST_SCHEMA_VERSION, apply_migrations, get_schema_version
 
 #endregion
 
 
 #region Constants
 DEFAULT_DB_PATH = Path.home() / ".claude" / "usage" / "usage_history.db"
-DEVICE_COLUMNS = ["device_id", "device_name", "device_type"]
+
+# Seconds a connection waits for another process's lock (the Stop hook
+# ingesting while `ccg usage --live` reads) before "database is locked"
+BUSY_TIMEOUT_SECONDS = 15.0
+
+# Rows per usage_records insert batch (one executemany and commit each)
+INSERT_BATCH_ROWS = 5000
+
+# Keys per existing-row lookup, under the 999 bound-parameter limit of
+# older SQLite builds
+LOOKUP_PARAMS = 500
+
+# Whole-call retries for writes that still hit a lock, e.g. a transaction
+# whose read snapshot went stale under WAL (backoff doubles from the delay)
+LOCK_RETRIES = 3
+LOCK_RETRY_DELAY_SECONDS = 0.5
+
+# Indexes init_database creates, checked by `ccg doctor`
+EXPECTED_INDEXES = (
+    "idx_usage_records_date",
+    "idx_usage_records_response",
+    "idx_usage_records_branch",
+    "idx_usage_records_hostname",
+    "idx_limits_snapshots_date",
+    "idx_message_content_timestamp",
+    "idx_tool_usage_date",
+    "idx_tool_usage_session",
+)
+
+_T = TypeVar("_T")
+#endregion
+
+
+#region State
+# Handle whose connection _connect shares (see storage_handle)
+_active_handle: "StorageHandle | None" = None
 #endregion
 
 
 #region Helper Functions
 
 
+def _open(db_path: Path, read_only: bool = False) -> sqlite3.Connection:
+    """
+    Open and configure a new connection.
+
+    Waits up to BUSY_TIMEOUT_SECONDS on other writers and enforces foreign
+    keys. In read-only mode (or with read_only, for one call that must not
+    write) the file is opened with mode=ro&immutable=1, so
+    any write fails with "attempt to write a readonly database" instead of
+    reaching disk. mode=ro alone still creates the -wal/-shm sidecars of a
+    WAL database (and fails on a read-only mount); immutable skips them,
+    at the cost of not seeing commits a running writer has not yet
+    checkpointed. With "encryption" on the file is opened through SQLCipher
+    (see storage.encryption).
+    """
+    if read_only or is_read_only():
+        conn = encryption.connect(
+            f"{db_path.resolve().as_uri()}?mode=ro&immutable=1", uri=True, timeout=BUSY_TIMEOUT_SECONDS
+        )
+    else:
+        conn = encryption.connect(db_path, timeout=BUSY_TIMEOUT_SECONDS)
+    conn.execute("PRAGMA foreign_keys=ON")
+    return conn
+
+
+def _connect(db_path: Path) -> sqlite3.Connection:
+    """
+    Connection for one storage call; close it when done.
+
+    Inside storage_handle (on the thread that opened it, for its database)
+    this is the handle's shared connection, whose close() only rolls back
+    what the call left uncommitted. Otherwise a new connection is opened.
+    """
+    handle = _active_handle
+    if handle is not None and handle.covers(db_path):
+        return handle.connection()
+    return _open(db_path)
+
+
+def _is_lock_error(error: Exception) -> bool:
+    message = str(error).lower()
+    return "locked" in message or "busy" in message
+
+
+def _retry_on_lock(func: Callable[..., _T]) -> Callable[..., _T]:
+    """
+    Retry a write that fails on a lock held by another process.
+
+    Writers commit once at the end, so a failed attempt has rolled back
+    and the whole call can run again. A lock outlasting every retry raises
+    DatabaseLockedError.
+    """
+    signature = inspect.signature(func)
+
+    @functools.wraps(func)
+    def wrapper(*args, **kwargs) -> _T:
+        for attempt in range(LOCK_RETRIES):
+            try:
+                return func(*args, **kwargs)
+            except encryption.driver_errors("OperationalError") as e:
+                if not _is_lock_error(e):
+                    raise
+                time.sleep(LOCK_RETRY_DELAY_SECONDS * 2 ** attempt)
+        try:
+            return func(*args, **kwargs)
+        except encryption.driver_errors("OperationalError") as e:
+            if not _is_lock_error(e):
+                raise
+            bound = signature.bind_partial(*args, **kwargs)
+            bound.apply_defaults()
+            raise DatabaseLockedError(bound.arguments.get("db_path", DEFAULT_DB_PATH), str(e)) from e
+    return wrapper
+
+
+class _SharedConnection:
+    """A storage call's view of a StorageHandle connection; close() keeps it open."""
+
+    def __init__(self, handle: "StorageHandle", conn: sqlite3.Connection):
+        self._handle = handle
+        self._conn = conn
+
+    def __getattr__(self, name: str):
+        return getattr(self._conn, name)
+
+    def close(self) -> None:
+        self._handle._release()
+
+
+class StorageHandle:
+    """
+    One configured connection to a database, shared by the storage calls
+    made while the handle is open.
+
+    Opened lazily on first use and owned by the thread that created the
+    handle; other threads and other database files get their own
+    connections. init_database runs once per handle instead of on every
+    call. A storage call's close() rolls back what it left uncommitted once
+    no other call is using the connection, as closing its own connection did.
+
+    Attributes:
+        db_path: Database file the handle serves
+        initialized: init_database already ran through this handle
+    """
+
+    def __init__(self, db_path: Path):
+        self.db_path = db_path.resolve()
+        self.initialized = False
+        self._owner = threading.get_ident()
+        self._conn: sqlite3.Connection | None = None
+        self._users = 0
+
+    def covers(self, db_path: Path) -> bool:
+        """True if calls for db_path on the current thread share this handle."""
+        return threading.get_ident() == self._owner and (db_path == self.db_path or db_path.resolve() == self.db_path)
+
+    def connection(self) -> _SharedConnection:
+        if self._conn is None:
+            self._conn = _open(self.db_path)
+        self._users += 1
+        return _SharedConnection(self, self._conn)
+
+    def _release(self) -> None:
+        self._users -= 1
+        if self._users == 0 and self._conn is not None and self._conn.in_transaction:
+            self._conn.rollback()
+
+    def close(self) -> None:
+        """
+        Close the connection; the next call opens a fresh one.
+
+        Needed before the database file is replaced or deleted.
+        """
+        if self._conn is not None:
+            self._conn.close()
+            self._conn = None
+        self._users = 0
+        self.initialized = False
+
+
+@contextmanager
+def storage_handle(db_path: Path = DEFAULT_DB_PATH) -> Iterator[StorageHandle]:
+    """
+    Share one connection across the storage calls made inside the block.
+
+    A command that saves, reads stats, and reads history then opens the
+    database once instead of once per call.
+
+    Args:
+        db_path: Database the calls use; calls for other files are unaffected
+
+    Yields:
+        The StorageHandle, closed on exit
+    """
+    global _active_handle
+    previous, handle = _active_handle, StorageHandle(db_path)
+    _active_handle = handle
+    try:
+        yield handle
+    finally:
+        _active_handle = previous
+        handle.close()
+
+
+def release_storage_handle(db_path: Path = DEFAULT_DB_PATH) -> None:
+    """Close the active handle's connection to db_path, e.g. before deleting the file."""
+    handle = _active_handle
+    if handle is not None and handle.covers(db_path):
+        handle.close()
+
+
 def load_model_pricing() -> list[tuple]:
     """
     Load model pricing from JSON file with hardcoded fallback.
@@ -67,30 +285,70 @@ def load_model_pricing() -> list[tuple]:
     return fallback_pricing
 
 
-def _add_device_columns_if_missing(cursor: sqlite3.Cursor, table_name: str) -> None:
-    """
-    Add device metadata columns to a table if they don't exist.
+#endregion
 
-    Used for migrating existing databases to support multi-device sync.
 
-    Args:
-        cursor: SQLite cursor
-        table_name: Name of the table to modify
-    """
-    cursor.execute(f"PRAGMA table_info({table_name})")
-    existing_columns = {row[1] for row in cursor.fetchall()}
+#region Functions
 
-    for col in DEVICE_COLUMNS:
-        if col not in existing_columns:
-            cursor.execute(f"ALTER TABLE {table_name} ADD COLUMN {col} TEXT")
 
+def _sync_model_pricing(cursor: sqlite3.Cursor) -> int:
+    """
+    Write bundled pricing, with fetched remote rates on top, to model_pricing.
 
-#endregion
+    Only changed rows are written: every open runs this, and a write here
+    would lock out other processes (e.g. the Stop hook) on every read.
 
+    Returns:
+        Rows written
+    """
+    pricing_data = with_remote_pricing(load_model_pricing())
+    cursor.execute("""
+        SELECT model_name, input_price_per_mtok, output_price_per_mtok,
+               cache_write_price_per_mtok, cache_read_price_per_mtok,
+               cache_write_1h_price_per_mtok, notes
+        FROM model_pricing
+    """)
+    stored_pricing = {row[0]: row for row in cursor.fetchall()}
 
-#region Functions
+    timestamp = datetime.now().isoformat()
+    written = 0
+    for model_name, input_price, output_price, cache_write, cache_read, cache_write_1h, notes in pricing_data:
+        if stored_pricing.get(model_name) == (
+            model_name, input_price, output_price, cache_write, cache_read, cache_write_1h, notes
+        ):
+            continue
+        cursor.execute("""
+            INSERT OR REPLACE INTO model_pricing (
+                model_name, input_price_per_mtok, output_price_per_mtok,
+                cache_write_price_per_mtok, cache_read_price_per_mtok,
+                cache_write_1h_price_per_mtok, last_updated, notes
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        """, (model_name, input_price, output_price, cache_write, cache_read, cache_write_1h, timestamp, notes))
+        written += 1
+    return written
+
+
+def _check_read_only_schema(db_path: Path) -> None:
+    """Raise SchemaMismatchError if a database opened read-only predates this release's schema."""
+    if not db_path.exists():
+        return
+    conn = _connect(db_path)
+    try:
+        found = get_schema_version(conn.cursor())
+    finally:
+        conn.close()
+    if found < LATEST_SCHEMA_VERSION:
+        raise SchemaMismatchError(
+            db_path, found, LATEST_SCHEMA_VERSION,
+            "Migrations cannot run in read-only mode. Open it once without --read-only "
+            "(with write access) to upgrade it.",
+        )
+    handle = _active_handle
+    if handle is not None and handle.covers(db_path):
+        handle.initialized = True
 
 
+@_retry_on_lock
 def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
     """
     Initialize the SQLite database for historical snapshots.
@@ -99,18 +357,37 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
     - daily_snapshots: Daily aggregated usage data
     - usage_records: Individual usage records for detailed analysis
 
+    and upgrades an existing database with any pending schema migrations
+    (see schema_migrations).
+
+    In read-only mode nothing is created or migrated; the file is read as
+    is, and must already be at the current schema. Inside storage_handle
+    this runs once per handle.
+
     Args:
         db_path: Path to the SQLite database file
 
     Raises:
+        SchemaMismatchError: If a read-only database needs migrations
         sqlite3.Error: If database initialization fails
     """
+    handle = _active_handle
+    if handle is not None and handle.initialized and handle.covers(db_path) and db_path.exists():
+        return
+    if is_read_only():
+        _check_read_only_schema(db_path)
+        return
+
     db_path.parent.mkdir(parents=True, exist_ok=True)
 
-    conn = sqlite3.connect(db_path)
+    conn = _connect(db_path)
     try:
         cursor = conn.cursor()
 
+        # Write-ahead logging lets readers (`ccg usage --live`) run while the
+        # Stop hook writes; the mode is stored in the file, so this sticks
+        cursor.execute("PRAGMA journal_mode=WAL")
+
         # Table for daily aggregated snapshots
         cursor.execute("""
             CREATE TABLE IF NOT EXISTS daily_snapshots (
@@ -126,13 +403,11 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
                 snapshot_timestamp TEXT NOT NULL,
                 device_id TEXT,
                 device_name TEXT,
-                device_type TEXT
+                device_type TEXT,
+                hostname TEXT
             )
         """)
 
-        # Add device columns if they don't exist (for migration)
-        _add_device_columns_if_missing(cursor, "daily_snapshots")
-
         # Table for detailed usage records
         cursor.execute("""
             CREATE TABLE IF NOT EXISTS usage_records (
@@ -152,29 +427,37 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
                 cache_read_tokens INTEGER NOT NULL,
                 total_tokens INTEGER NOT NULL,
                 cache_creation_1h_tokens INTEGER DEFAULT 0,
+                thinking_tokens INTEGER DEFAULT 0,
+                is_subagent INTEGER DEFAULT 0,
+                parent_session_id TEXT,
+                attachment_count INTEGER DEFAULT 0,
                 device_id TEXT,
                 device_name TEXT,
                 device_type TEXT,
+                hostname TEXT,
+                project TEXT,
+                agent_id TEXT,
                 UNIQUE(session_id, message_uuid)
             )
         """)
 
-        # Add device columns if they don't exist (for migration)
-        _add_device_columns_if_missing(cursor, "usage_records")
-
-        # 1h cache-write split (bills at 2x base input vs 1.25x for 5m)
-        cursor.execute("PRAGMA table_info(usage_records)")
-        if "cache_creation_1h_tokens" not in {row[1] for row in cursor.fetchall()}:
-            cursor.execute(
-                "ALTER TABLE usage_records ADD COLUMN cache_creation_1h_tokens INTEGER DEFAULT 0"
-            )
-
         # Index for faster date-based queries
         cursor.execute("""
             CREATE INDEX IF NOT EXISTS idx_usage_records_date
             ON usage_records(date)
         """)
 
+        # Index for per-branch queries (`ccg stats --by branch`)
+        cursor.execute("""
+            CREATE INDEX IF NOT EXISTS idx_usage_records_branch
+            ON usage_records(git_branch, date)
+        """)
+
+        # A billed response is stored once across sessions (forks replay it
+        # under new session ids); save_snapshot checks first, and schema
+        # migration 12 builds idx_usage_records_response to enforce it, after
+        # dropping duplicates older databases may hold.
+
         # Table for usage limits snapshots
         cursor.execute("""
             CREATE TABLE IF NOT EXISTS limits_snapshots (
@@ -192,9 +475,6 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
             )
         """)
 
-        # Add device columns if they don't exist (for migration)
-        _add_device_columns_if_missing(cursor, "limits_snapshots")
-
         # Index for faster date-based queries on limits
         cursor.execute("""
             CREATE INDEX IF NOT EXISTS idx_limits_snapshots_date
@@ -246,30 +526,438 @@ def init_database(db_path: Path = DEFAULT_DB_PATH) -> None:
             )
         """)
 
-        cursor.execute("PRAGMA table_info(model_pricing)")
-        if "cache_write_1h_price_per_mtok" not in {row[1] for row in cursor.fetchall()}:
-            cursor.execute(
-                "ALTER TABLE model_pricing ADD COLUMN cache_write_1h_price_per_mtok REAL"
+        # Per-day model mix: token splits by model, kept in both storage
+        # modes so costs and model breakdowns never need usage_records.
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS daily_model_snapshots (
+                date TEXT NOT NULL,
+                model TEXT NOT NULL,
+                responses INTEGER NOT NULL,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cache_creation_tokens INTEGER NOT NULL,
+                cache_read_tokens INTEGER NOT NULL,
+                cache_creation_1h_tokens INTEGER NOT NULL,
+                total_tokens INTEGER NOT NULL,
+                PRIMARY KEY (date, model)
+            )
+        """)
+
+        # Model-mix counterpart of file_contributions (aggregate mode deltas)
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS file_model_contributions (
+                file_path TEXT NOT NULL,
+                date TEXT NOT NULL,
+                model TEXT NOT NULL,
+                responses INTEGER NOT NULL,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cache_creation_tokens INTEGER NOT NULL,
+                cache_read_tokens INTEGER NOT NULL,
+                cache_creation_1h_tokens INTEGER NOT NULL,
+                total_tokens INTEGER NOT NULL,
+                PRIMARY KEY (file_path, date, model)
             )
+        """)
 
-        # Populate pricing data from JSON file (with fallback)
-        pricing_data = load_model_pricing()
+        # Per-UTC-hour totals for intraday views and rolling limit windows,
+        # kept in both storage modes like the model mix
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS hourly_snapshots (
+                hour TEXT PRIMARY KEY,
+                prompts INTEGER NOT NULL,
+                responses INTEGER NOT NULL,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cache_creation_tokens INTEGER NOT NULL,
+                cache_read_tokens INTEGER NOT NULL,
+                cache_creation_1h_tokens INTEGER NOT NULL,
+                total_tokens INTEGER NOT NULL
+            )
+        """)
 
-        timestamp = datetime.now().isoformat()
-        for model_name, input_price, output_price, cache_write, cache_read, cache_write_1h, notes in pricing_data:
-            cursor.execute("""
-                INSERT OR REPLACE INTO model_pricing (
-                    model_name, input_price_per_mtok, output_price_per_mtok,
-                    cache_write_price_per_mtok, cache_read_price_per_mtok,
-                    cache_write_1h_price_per_mtok, last_updated, notes
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            """, (model_name, input_price, output_price, cache_write, cache_read, cache_write_1h, timestamp, notes))
+        # Hourly counterpart of file_contributions (aggregate mode deltas)
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS file_hourly_contributions (
+                file_path TEXT NOT NULL,
+                hour TEXT NOT NULL,
+                prompts INTEGER NOT NULL,
+                responses INTEGER NOT NULL,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cache_creation_tokens INTEGER NOT NULL,
+                cache_read_tokens INTEGER NOT NULL,
+                cache_creation_1h_tokens INTEGER NOT NULL,
+                total_tokens INTEGER NOT NULL,
+                PRIMARY KEY (file_path, hour)
+            )
+        """)
+
+        # One row per `ccg update usage` run; hook-triggered runs feed the
+        # hook health check in `ccg doctor`
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS update_runs (
+                started_at TEXT PRIMARY KEY,
+                finished_at TEXT NOT NULL,
+                trigger TEXT NOT NULL,
+                exit_status INTEGER NOT NULL,
+                new_records INTEGER NOT NULL,
+                error TEXT
+            )
+        """)
+
+        # Finish time of the last ingest over every transcript source, so
+        # --fast commands can tell when hooks stopped updating the database
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS ingest_status (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )
+        """)
+
+        # Byte offset reached in each transcript, so full-mode ingestion
+        # parses only lines appended since the last run
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS ingestion_state (
+                file_path TEXT PRIMARY KEY,
+                size_bytes INTEGER NOT NULL,
+                byte_offset INTEGER NOT NULL,
+                head_digest TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+        """)
+
+        # Recap facts for finished months (`ccg recap`)
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS monthly_summaries (
+                month TEXT PRIMARY KEY,
+                generated_at TEXT NOT NULL,
+                summary TEXT NOT NULL
+            )
+        """)
+
+        # Free-form labels attached to sessions (`ccg sessions tag`)
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS session_tags (
+                session_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (session_id, tag)
+            )
+        """)
+
+        # Dated milestones ("launched v2") marked on exported heatmaps
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS annotations (
+                date TEXT NOT NULL,
+                label TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (date, label)
+            )
+        """)
+
+        # Transcript entries with bad timestamps, held for `ccg doctor --quarantine`
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS quarantined_entries (
+                file_path TEXT NOT NULL,
+                entry_id TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                raw_timestamp TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                raw_line TEXT NOT NULL,
+                quarantined_at TEXT NOT NULL,
+                dismissed INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (file_path, entry_id)
+            )
+        """)
+
+        # Transcript fields the parser doesn't know, reported by `ccg doctor`
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS unknown_fields (
+                field TEXT PRIMARY KEY,
+                occurrences INTEGER NOT NULL,
+                first_seen TEXT NOT NULL,
+                last_seen TEXT NOT NULL
+            )
+        """)
+
+        # API errors, rate-limit hits, and interrupted turns from transcripts
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS session_events (
+                event_id TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                date TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                detail TEXT NOT NULL,
+                folder TEXT NOT NULL,
+                retry_attempt INTEGER,
+                ends_turn INTEGER NOT NULL DEFAULT 0,
+                turn_tokens INTEGER NOT NULL DEFAULT 0,
+                messages_before INTEGER,
+                context_tokens INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (session_id, event_id)
+            )
+        """)
+
+        # MCP tool calls ("mcp__<server>__<tool>"), one row per tool_use id
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS mcp_calls (
+                tool_use_id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                date TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                server TEXT NOT NULL,
+                tool TEXT NOT NULL,
+                call_tokens INTEGER NOT NULL DEFAULT 0,
+                result_chars INTEGER NOT NULL DEFAULT 0
+            )
+        """)
+
+        # Every tool call (built-in and MCP), one row per tool_use id;
+        # get_tool_usage counts them per date, session, and tool
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS tool_usage (
+                tool_use_id TEXT PRIMARY KEY,
+                date TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                tool TEXT NOT NULL
+            )
+        """)
+        cursor.execute("""
+            CREATE INDEX IF NOT EXISTS idx_tool_usage_date
+            ON tool_usage(date, tool)
+        """)
+        cursor.execute("""
+            CREATE INDEX IF NOT EXISTS idx_tool_usage_session
+            ON tool_usage(session_id)
+        """)
+
+        # Session metadata, so commands can name sessions by a readable label
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS sessions (
+                session_id TEXT PRIMARY KEY,
+                folder TEXT,
+                started_at TEXT NOT NULL,
+                ended_at TEXT NOT NULL,
+                messages INTEGER NOT NULL DEFAULT 0,
+                total_tokens INTEGER NOT NULL DEFAULT 0,
+                title TEXT,
+                entrypoint TEXT
+            )
+        """)
+
+        # Summary lines Claude Code writes into transcripts ("Refactor auth
+        # middleware"), the latest per session
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS session_summaries (
+                session_id TEXT PRIMARY KEY,
+                summary TEXT NOT NULL,
+                leaf_uuid TEXT
+            )
+        """)
+
+        # Opt-in message text ("store_content"), capped per record and in total
+        cursor.execute("""
+            CREATE TABLE IF NOT EXISTS message_content (
+                entry_key TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                message_type TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                content TEXT NOT NULL,
+                content_bytes INTEGER NOT NULL,
+                truncated INTEGER NOT NULL
+            )
+        """)
+        cursor.execute("""
+            CREATE INDEX IF NOT EXISTS idx_message_content_timestamp
+            ON message_content(timestamp)
+        """)
+
+        # Upgrade tables created by older releases (columns added since,
+        # one-time backfills); a no-op once the database is current
+        apply_migrations(cursor)
+
+        # Index for per-machine queries (`ccg stats --by host`); created after
+        # the migrations since older databases only now gain the column
+        cursor.execute("""
+            CREATE INDEX IF NOT EXISTS idx_usage_records_hostname
+            ON usage_records(hostname, date)
+        """)
+
+        _sync_model_pricing(cursor)
 
         conn.commit()
+        if handle is not None and handle.covers(db_path):
+            handle.initialized = True
     finally:
         conn.close()
 
 
+def _save_usage_records(
+    conn: sqlite3.Connection,
+    records: list[UsageRecord],
+    device_id: str | None,
+    device_name: str | None,
+    device_type: str | None,
+    hostname: str | None,
+) -> int:
+    """
+    Insert new usage_records rows in batches (full storage mode).
+
+    Existing keys are looked up in bulk instead of per record, and every
+    INSERT_BATCH_ROWS rows are one executemany committed on their own, so a
+    first ingest of a large history stays fast without holding the write
+    lock for the whole run. Records read back from another database keep
+    their own hostname; the rest get hostname.
+
+    Returns:
+        Number of rows inserted
+    """
+    cursor = conn.cursor()
+
+    # Assistant rows dedupe GLOBALLY on the billed-response id (session
+    # forks replay identical responses under new session ids); user rows
+    # stay session-scoped. A repeat with larger usage replaces the earlier
+    # capture (mid-stream partial), never the other way round.
+    incoming: dict[tuple[str, ...], UsageRecord] = {}
+    for record in records:
+        if record.message_type == "assistant":
+            key: tuple[str, ...] = (record.message_uuid,)
+        else:
+            key = (record.session_id, record.message_uuid)
+        earlier = incoming.get(key)
+        if earlier is None:
+            incoming[key] = record
+        elif record.message_type == "assistant" and _total_tokens(record) > _total_tokens(earlier):
+            incoming[key] = _keep_thinking(record, earlier)
+
+    saved_count = 0
+    pending = list(incoming.items())
+    for start in range(0, len(pending), INSERT_BATCH_ROWS):
+        batch = pending[start:start + INSERT_BATCH_ROWS]
+        stored = _stored_usage_keys(cursor, [key for key, _ in batch])
+
+        inserts = []
+        upgrades = []
+        for key, record in batch:
+            usage = record.token_usage
+            if key in stored:
+                row_id, stored_total = stored[key]
+                if record.message_type == "assistant" and usage and usage.total_tokens > stored_total:
+                    upgrades.append((
+                        record.timestamp.isoformat(),
+                        usage.input_tokens, usage.output_tokens,
+                        usage.cache_creation_tokens, usage.cache_read_tokens,
+                        usage.total_tokens, usage.cache_creation_1h_tokens,
+                        usage.thinking_tokens, row_id,
+                    ))
+                continue
+            inserts.append((
+                record.date_key,
+                record.timestamp.isoformat(),
+                record.session_id,
+                record.message_uuid,
+                record.message_type,
+                record.model,
+                record.folder,
+                record.git_branch,
+                record.version,
+                usage.input_tokens if usage else 0,
+                usage.output_tokens if usage else 0,
+                usage.cache_creation_tokens if usage else 0,
+                usage.cache_read_tokens if usage else 0,
+                usage.total_tokens if usage else 0,
+                usage.cache_creation_1h_tokens if usage else 0,
+                usage.thinking_tokens if usage else 0,
+                int(record.is_subagent),
+                record.agent_id,
+                record.attachment_count,
+                device_id,
+                device_name,
+                device_type,
+                record.hostname or hostname or None,
+                record.project,
+            ))
+
+        cursor.executemany("""
+            UPDATE usage_records
+            SET timestamp = ?, input_tokens = ?, output_tokens = ?,
+                cache_creation_tokens = ?, cache_read_tokens = ?,
+                total_tokens = ?, cache_creation_1h_tokens = ?,
+                thinking_tokens = MAX(COALESCE(thinking_tokens, 0), ?)
+            WHERE id = ?
+        """, upgrades)
+        # OR IGNORE: the same key inserted by another process since the lookup
+        cursor.executemany("""
+            INSERT OR IGNORE INTO usage_records (
+                date, timestamp, session_id, message_uuid, message_type,
+                model, folder, git_branch, version,
+                input_tokens, output_tokens,
+                cache_creation_tokens, cache_read_tokens, total_tokens,
+                cache_creation_1h_tokens, thinking_tokens,
+                is_subagent, agent_id, attachment_count,
+                device_id, device_name, device_type, hostname, project
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        """, inserts)
+        saved_count += max(cursor.rowcount, 0) if inserts else 0
+        conn.commit()
+
+    return saved_count
+
+
+def _stored_usage_keys(
+    cursor: sqlite3.Cursor, keys: list[tuple[str, ...]]
+) -> dict[tuple[str, ...], tuple[int, int]]:
+    """
+    (row id, total_tokens) of the usage_records rows matching dedupe keys.
+
+    Assistant keys are (message_uuid,) and user keys (session_id,
+    message_uuid), each looked up through its own unique index.
+    """
+    stored: dict[tuple[str, ...], tuple[int, int]] = {}
+    response_uuids = [key[0] for key in keys if len(key) == 1]
+    prompt_uuids: dict[str, list[str]] = {}
+    for key in keys:
+        if len(key) == 2:
+            prompt_uuids.setdefault(key[0], []).append(key[1])
+
+    for start in range(0, len(response_uuids), LOOKUP_PARAMS):
+        chunk = response_uuids[start:start + LOOKUP_PARAMS]
+        cursor.execute(
+            "SELECT message_uuid, id, total_tokens FROM usage_records "
+            f"WHERE message_type = 'assistant' AND message_uuid IN ({', '.join('?' * len(chunk))})",
+            chunk,
+        )
+        for message_uuid, row_id, total in cursor.fetchall():
+            stored[(message_uuid,)] = (row_id, total or 0)
+
+    for session_id, uuids in prompt_uuids.items():
+        for start in range(0, len(uuids), LOOKUP_PARAMS):
+            chunk = uuids[start:start + LOOKUP_PARAMS]
+            cursor.execute(
+                "SELECT message_uuid, id, total_tokens FROM usage_records "
+                f"WHERE session_id = ? AND message_uuid IN ({', '.join('?' * len(chunk))})",
+                [session_id, *chunk],
+            )
+            for message_uuid, row_id, total in cursor.fetchall():
+                stored[(session_id, message_uuid)] = (row_id, total or 0)
+    return stored
+
+
+def _total_tokens(record: UsageRecord) -> int:
+    return record.token_usage.total_tokens if record.token_usage else 0
+
+
+def _keep_thinking(record: UsageRecord, earlier: UsageRecord) -> UsageRecord:
+    """record, keeping the larger thinking count of the two captures."""
+    if record.token_usage is None or earlier.token_usage is None:
+        return record
+    thinking = max(record.token_usage.thinking_tokens, earlier.token_usage.thinking_tokens)
+    return replace(record, token_usage=replace(record.token_usage, thinking_tokens=thinking))
+
+
+@_retry_on_lock
 def save_snapshot(
     records: list[UsageRecord],
     db_path: Path = DEFAULT_DB_PATH,
@@ -277,6 +965,7 @@ def save_snapshot(
     device_id: str | None = None,
     device_name: str | None = None,
     device_type: str | None = None,
+    hostname: str | None = None,
 ) -> int:
     """
     Save usage records to the database as a snapshot.
@@ -291,6 +980,7 @@ def save_snapshot(
         device_id: Device identifier for multi-device sync
         device_name: Human-readable device name
         device_type: Device type (macos, windows, linux)
+        hostname: Machine stamped on records that carry none
 
     Returns:
         Number of new records saved
@@ -303,7 +993,7 @@ def save_snapshot(
 
     init_database(db_path)
 
-    conn = sqlite3.connect(db_path)
+    conn = _connect(db_path)
     saved_count = 0
 
     try:
@@ -311,83 +1001,7 @@ def save_snapshot(
 
         # Save individual records only if in "full" mode
         if storage_mode == "full":
-            for record in records:
-                # Get token values (0 for user messages without token_usage)
-                input_tokens = record.token_usage.input_tokens if record.token_usage else 0
-                output_tokens = record.token_usage.output_tokens if record.token_usage else 0
-                cache_creation_tokens = record.token_usage.cache_creation_tokens if record.token_usage else 0
-                cache_read_tokens = record.token_usage.cache_read_tokens if record.token_usage else 0
-                total_tokens = record.token_usage.total_tokens if record.token_usage else 0
-                cache_creation_1h = record.token_usage.cache_creation_1h_tokens if record.token_usage else 0
-
-                # Assistant rows dedupe GLOBALLY on the billed-response id
-                # (session forks replay identical responses under new session
-                # ids); user rows stay session-scoped. An existing row with
-                # smaller usage upgrades in place (mid-stream partial
-                # capture), never downgrades.
-                if record.message_type == "assistant":
-                    cursor.execute(
-                        "SELECT id, total_tokens FROM usage_records "
-                        "WHERE message_uuid = ? AND message_type = 'assistant'",
-                        (record.message_uuid,),
-                    )
-                else:
-                    cursor.execute(
-                        "SELECT id, total_tokens FROM usage_records "
-                        "WHERE session_id = ? AND message_uuid = ?",
-                        (record.session_id, record.message_uuid),
-                    )
-                existing = cursor.fetchone()
-                if existing is not None:
-                    if record.message_type == "assistant" and total_tokens > (existing[1] or 0):
-                        cursor.execute("""
-                            UPDATE usage_records
-                            SET timestamp = ?, input_tokens = ?, output_tokens = ?,
-                                cache_creation_tokens = ?, cache_read_tokens = ?,
-                                total_tokens = ?, cache_creation_1h_tokens = ?
-                            WHERE id = ?
-                        """, (
-                            record.timestamp.isoformat(),
-                            input_tokens, output_tokens,
-                            cache_creation_tokens, cache_read_tokens,
-                            total_tokens, cache_creation_1h, existing[0],
-                        ))
-                    continue
-
-                try:
-                    cursor.execute("""
-                        INSERT INTO usage_records (
-                            date, timestamp, session_id, message_uuid, message_type,
-                            model, folder, git_branch, version,
-                            input_tokens, output_tokens,
-                            cache_creation_tokens, cache_read_tokens, total_tokens,
-                            cache_creation_1h_tokens,
-                            device_id, device_name, device_type
-                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                    """, (
-                        record.date_key,
-                        record.timestamp.isoformat(),
-                        record.session_id,
-                        record.message_uuid,
-                        record.message_type,
-                        record.model,
-                        record.folder,
-                        record.git_branch,
-                        record.version,
-                        input_tokens,
-                        output_tokens,
-                        cache_creation_tokens,
-                        cache_read_tokens,
-                        total_tokens,
-                        cache_creation_1h,
-                        device_id,
-                        device_name,
-                        device_type,
-                    ))
-                    saved_count += 1
-                except sqlite3.IntegrityError:
-                    # Same key inserted concurrently between check and insert
-                    pass
+            saved_count = _save_usage_records(conn, records, device_id, device_name, device_type, hostname)
 
         # Update daily snapshots (aggregate by date)
         if storage_mode == "full":
@@ -396,52 +1010,40 @@ def save_snapshot(
             # Instead, recalculate only for dates that currently have records
             timestamp = datetime.now().isoformat()
 
-            # Get all dates that currently have usage_records
-            cursor.execute("SELECT DISTINCT date FROM usage_records")
-            dates_with_records = [row[0] for row in cursor.fetchall()]
-
-            for date in dates_with_records:
-                # Calculate totals for this date from usage_records
-                cursor.execute("""
-                    SELECT
-                        SUM(CASE WHEN message_type = 'user' THEN 1 ELSE 0 END) as total_prompts,
-                        SUM(CASE WHEN message_type = 'assistant' THEN 1 ELSE 0 END) as total_responses,
-                        COUNT(DISTINCT session_id) as total_sessions,
-                        SUM(total_tokens) as total_tokens,
-                        SUM(input_tokens) as input_tokens,
-                        SUM(output_tokens) as output_tokens,
-                        SUM(cache_creation_tokens) as cache_creation_tokens,
-                        SUM(cache_read_tokens) as cache_read_tokens
-                    FROM usage_records
-                    WHERE date = ?
-                """, (date,))
-
-                row = cursor.fetchone()
-
-                # Use INSERT OR REPLACE only for dates that currently have data
-                # This preserves historical daily_snapshots for dates no longer in usage_records
-                cursor.execute("""
-                    INSERT OR REPLACE INTO daily_snapshots (
-                        date, total_prompts, total_responses, total_sessions, total_tokens,
-                        input_tokens, output_tokens, cache_creation_tokens,
-                        cache_read_tokens, snapshot_timestamp,
-                        device_id, device_name, device_type
-                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                """, (
+            # INSERT OR REPLACE only for dates that currently have data;
+            # this preserves daily_snapshots for dates no longer in usage_records
+            cursor.execute("""
+                INSERT OR REPLACE INTO daily_snapshots (
+                    date, total_prompts, total_responses, total_sessions, total_tokens,
+                    input_tokens, output_tokens, cache_creation_tokens,
+                    cache_read_tokens, snapshot_timestamp,
+                    device_id, device_name, device_type, hostname
+                )
+                SELECT
                     date,
-                    row[0] or 0,
-                    row[1] or 0,
-                    row[2] or 0,
-                    row[3] or 0,
-                    row[4] or 0,
-                    row[5] or 0,
-                    row[6] or 0,
-                    row[7] or 0,
-                    timestamp,
-                    device_id,
-                    device_name,
-                    device_type,
-                ))
+                    SUM(CASE WHEN message_type = 'user' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN message_type = 'assistant' THEN 1 ELSE 0 END),
+                    COUNT(DISTINCT session_id),
+                    COALESCE(SUM(total_tokens), 0),
+                    COALESCE(SUM(input_tokens), 0),
+                    COALESCE(SUM(output_tokens), 0),
+                    COALESCE(SUM(cache_creation_tokens), 0),
+                    COALESCE(SUM(cache_read_tokens), 0),
+                    ?, ?, ?, ?, GROUP_CONCAT(DISTINCT hostname)
+                FROM usage_records
+                GROUP BY date
+            """, (timestamp, device_id, device_name, device_type))
+
+            # Model mix only for the dates in this batch, like the DuckDB backend
+            from src.storage.duckdb_backend import _MODEL_MIX_REBUILD_SQL
+            batch_dates = sorted({record.date_key for record in records})
+            placeholders = ", ".join("?" for _ in batch_dates)
+            cursor.execute(f"DELETE FROM daily_model_snapshots WHERE date IN ({placeholders})", batch_dates)
+            cursor.execute(_MODEL_MIX_REBUILD_SQL.format(where=f"AND date IN ({placeholders})"), batch_dates)
+
+            # Hourly totals only for the hours this batch can touch
+            from src.storage.duckdb_backend import _rebuild_hourly_snapshots
+            _rebuild_hourly_snapshots(cursor, batch_dates)
         else:
             # In aggregate mode, compute from incoming records
             from collections import defaultdict
@@ -492,8 +1094,8 @@ def save_snapshot(
                             date, total_prompts, total_responses, total_sessions, total_tokens,
                             input_tokens, output_tokens, cache_creation_tokens,
                             cache_read_tokens, snapshot_timestamp,
-                            device_id, device_name, device_type
-                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                            device_id, device_name, device_type, hostname
+                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                     """, (
                         date_key,
                         existing[0] + agg["prompts"],
@@ -508,6 +1110,7 @@ def save_snapshot(
                         device_id,
                         device_name,
                         device_type,
+                        hostname or None,
                     ))
                 else:
                     # New date, insert fresh
@@ -516,8 +1119,8 @@ def save_snapshot(
                             date, total_prompts, total_responses, total_sessions, total_tokens,
                             input_tokens, output_tokens, cache_creation_tokens,
                             cache_read_tokens, snapshot_timestamp,
-                            device_id, device_name, device_type
-                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                            device_id, device_name, device_type, hostname
+                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                     """, (
                         date_key,
                         agg["prompts"],
@@ -532,9 +1135,19 @@ def save_snapshot(
                         device_id,
                         device_name,
                         device_type,
+                        hostname or None,
                     ))
                 saved_count += 1
 
+            from src.storage.duckdb_backend import (
+                _aggregate_by_date_model,
+                _aggregate_by_hour,
+                _merge_hourly,
+                _merge_model_mix,
+            )
+            _merge_model_mix(cursor, _aggregate_by_date_model(records))
+            _merge_hourly(cursor, _aggregate_by_hour(records))
+
         conn.commit()
     finally:
         conn.close()
@@ -542,6 +1155,94 @@ def save_snapshot(
     return saved_count
 
 
+@_retry_on_lock
+def upsert_daily_snapshot(
+    date: str,
+    totals: dict[str, int],
+    db_path: Path = DEFAULT_DB_PATH,
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+    hostname: str | None = None,
+    models: dict[str, dict[str, int]] | None = None,
+) -> None:
+    """
+    Replace one day's daily_snapshots row and model split with the given totals.
+
+    Args:
+        date: Day to write (YYYY-MM-DD)
+        totals: Values keyed by prompts, responses, sessions, input_tokens,
+            output_tokens, cache_creation_tokens, cache_read_tokens (missing
+            keys are 0; total_tokens is derived)
+        db_path: Path to the SQLite database file
+        device_id: Device identifier for multi-device sync
+        device_name: Human-readable device name
+        device_type: Device type (macos, windows, linux)
+        hostname: Machine stamped on the row
+        models: Per-model split of totals for daily_model_snapshots
+            (default: all under "unknown")
+    """
+    from src.storage.duckdb_backend import _write_daily_snapshot, _write_day_model_mix
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        cursor = conn.cursor()
+        _write_daily_snapshot(
+            cursor, date, totals, datetime.now().isoformat(),
+            device_id, device_name, device_type, hostname,
+        )
+        _write_day_model_mix(cursor, date, totals, models, replace=True)
+        conn.commit()
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def increment_daily_snapshot(
+    date: str,
+    delta: dict[str, int],
+    db_path: Path = DEFAULT_DB_PATH,
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+    hostname: str | None = None,
+    models: dict[str, dict[str, int]] | None = None,
+) -> None:
+    """
+    Add a (possibly negative) delta to one day's daily_snapshots row and model split.
+
+    Creates the rows if missing; fields never drop below zero. Lets
+    hook-driven ingestion update a single day without scanning records.
+
+    Args:
+        date: Day to update (YYYY-MM-DD)
+        delta: Changes keyed like upsert_daily_snapshot's totals
+        db_path: Path to the SQLite database file
+        device_id: Device identifier for multi-device sync
+        device_name: Human-readable device name
+        device_type: Device type (macos, windows, linux)
+        hostname: Machine stamped on the row
+        models: Per-model split of delta for daily_model_snapshots
+            (default: all under "unknown")
+    """
+    from src.storage.duckdb_backend import _apply_daily_delta, _write_day_model_mix
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        cursor = conn.cursor()
+        _apply_daily_delta(
+            cursor, date, delta, datetime.now().isoformat(),
+            device_id, device_name, device_type, hostname,
+        )
+        _write_day_model_mix(cursor, date, delta, models, replace=False)
+        conn.commit()
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
 def save_file_aggregate(
     file_path: Path,
     records: list[UsageRecord],
@@ -549,6 +1250,7 @@ def save_file_aggregate(
     device_id: str | None = None,
     device_name: str | None = None,
     device_type: str | None = None,
+    hostname: str | None = None,
 ) -> int:
     """
     Apply one transcript file's aggregate contribution as a DELTA.
@@ -563,12 +1265,18 @@ def save_file_aggregate(
     Returns:
         Number of assistant responses newly accounted for (delta)
     """
-    from src.storage.duckdb_backend import _CONTRIB_FIELDS, _aggregate_by_date
+    from src.storage.duckdb_backend import (
+        _CONTRIB_FIELDS,
+        _aggregate_by_date,
+        _apply_daily_delta,
+        _apply_file_hourly,
+        _apply_file_model_mix,
+    )
 
     init_database(db_path)
     fresh = _aggregate_by_date(records)
 
-    conn = sqlite3.connect(str(db_path))
+    conn = _connect(db_path)
     try:
         cursor = conn.cursor()
         cursor.execute(
@@ -576,12 +1284,14 @@ def save_file_aggregate(
             (str(file_path),),
         )
         previous = {row[0]: dict(zip(_CONTRIB_FIELDS, row[1:])) for row in cursor.fetchall()}
+        primed = False
         if not previous:
             cursor.execute(
                 "SELECT 1 FROM file_metadata WHERE file_path = ?", (str(file_path),)
             )
             if cursor.fetchone() is not None:
                 previous = {date: dict(day) for date, day in fresh.items()}
+                primed = True
 
         timestamp = datetime.now().isoformat()
         new_responses = 0
@@ -590,38 +1300,8 @@ def save_file_aggregate(
             delta = {field: day[field] - prev[field] for field in _CONTRIB_FIELDS}
             new_responses += max(delta["responses"], 0)
             if any(delta.values()):
-                cursor.execute(
-                    """
-                    SELECT total_prompts, total_responses, total_sessions,
-                           input_tokens, output_tokens, cache_creation_tokens,
-                           cache_read_tokens
-                    FROM daily_snapshots WHERE date = ?
-                    """,
-                    (date,),
-                )
-                base = cursor.fetchone() or (0, 0, 0, 0, 0, 0, 0)
-                merged = [
-                    max(base[0] + delta["prompts"], 0),
-                    max(base[1] + delta["responses"], 0),
-                    max(base[2] + delta["sessions"], 0),
-                    max(base[3] + delta["input_tokens"], 0),
-                    max(base[4] + delta["output_tokens"], 0),
-                    max(base[5] + delta["cache_creation_tokens"], 0),
-                    max(base[6] + delta["cache_read_tokens"], 0),
-                ]
-                total = merged[3] + merged[4] + merged[5] + merged[6]
-                cursor.execute(
-                    """
-                    INSERT OR REPLACE INTO daily_snapshots (
-                        date, total_prompts, total_responses, total_sessions,
-                        total_tokens, input_tokens, output_tokens,
-                        cache_creation_tokens, cache_read_tokens,
-                        snapshot_timestamp, device_id, device_name, device_type
-                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                    """,
-                    (date, merged[0], merged[1], merged[2], total,
-                     merged[3], merged[4], merged[5], merged[6],
-                     timestamp, device_id, device_name, device_type),
+                _apply_daily_delta(
+                    cursor, date, delta, timestamp, device_id, device_name, device_type, hostname
                 )
 
         cursor.execute("DELETE FROM file_contributions WHERE file_path = ?", (str(file_path),))
@@ -633,97 +1313,118 @@ def save_file_aggregate(
                 """,
                 tuple([str(file_path), date] + [day[field] for field in _CONTRIB_FIELDS]),
             )
+        _apply_file_model_mix(cursor, file_path, records, primed)
+        _apply_file_hourly(cursor, file_path, records, primed)
         conn.commit()
         return new_responses
     finally:
         conn.close()
 
 
-def load_historical_records(
+def iter_historical_records(
     start_date: str | None = None,
     end_date: str | None = None,
-    db_path: Path = DEFAULT_DB_PATH
-) -> list[UsageRecord]:
+    folder: str | None = None,
+    model: str | None = None,
+    limit: int | None = None,
+    offset: int = 0,
+    db_path: Path = DEFAULT_DB_PATH,
+    batch_size: int | None = None,
+) -> Iterator[UsageRecord]:
     """
-    Load historical usage records from the database.
+    Stream usage records in batches instead of materializing the table.
 
-    Supports both storage modes:
-    - Full mode: Loads individual records from usage_records table
-    - Aggregate mode: Loads daily summaries from daily_snapshots and converts to synthetic records
+    Full mode only: reads usage_records. Aggregate-mode synthetic records
+    come from load_historical_records.
 
     Args:
         start_date: Optional start date in YYYY-MM-DD format (inclusive)
         end_date: Optional end date in YYYY-MM-DD format (inclusive)
+        folder: Only records from this project folder
+        model: Only records for this model
+        limit: Maximum number of records (a page size)
+        offset: Records to skip before the page; only applies with limit
         db_path: Path to the SQLite database file
+        batch_size: Rows fetched per round trip (default HISTORY_BATCH_SIZE)
 
-    Returns:
-        List of UsageRecord objects
-
-    Raises:
-        sqlite3.Error: If database query fails
+    Yields:
+        UsageRecord objects ordered by date and timestamp
     """
+    from src.storage.duckdb_backend import HISTORY_BATCH_SIZE, _history_query, _row_to_record
+
     if not db_path.exists():
-        return []
+        return
 
-    conn = sqlite3.connect(db_path)
+    init_database(db_path)
+    conn = _connect(db_path)
 
     try:
         cursor = conn.cursor()
+        query, params = _history_query(start_date, end_date, folder, model, limit, offset)
+        cursor.execute(query, params)
+        while True:
+            rows = cursor.fetchmany(batch_size or HISTORY_BATCH_SIZE)
+            if not rows:
+                break
+            for row in rows:
+                yield _row_to_record(row)
+    finally:
+        conn.close()
 
-        # First try to load from usage_records (full mode)
-        query = "SELECT * FROM usage_records WHERE 1=1"
-        params = []
-
-        if start_date:
-            query += " AND date >= ?"
-            params.append(start_date)
 
-        if end_date:
-            query += " AND date <= ?"
-            params.append(end_date)
+def load_historical_records(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+    folder: str | None = None,
+    model: str | None = None,
+    limit: int | None = None,
+    offset: int = 0,
+) -> list[UsageRecord]:
+    """
+    Load historical usage records from the database.
 
-        query += " ORDER BY date, timestamp"
+    Supports both storage modes:
+    - Full mode: Loads individual records from usage_records table
+    - Aggregate mode: Loads daily summaries from daily_snapshots and converts to synthetic records
 
-        cursor.execute(query, params)
+    Prefer iter_historical_records for large ranges; this materializes the
+    whole result.
 
-        records = []
-        for row in cursor.fetchall():
-            # Parse the row into a UsageRecord
-            # Row columns: id, date, timestamp, session_id, message_uuid, message_type,
-            #              model, folder, git_branch, version,
-            #              input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, total_tokens
-            from src.models.usage_record import TokenUsage
-
-            # Only create TokenUsage if tokens exist (assistant messages)
-            token_usage = None
-            if row[10] > 0 or row[11] > 0:  # if input_tokens or output_tokens exist
-                token_usage = TokenUsage(
-                    input_tokens=row[10],
-                    output_tokens=row[11],
-                    cache_creation_tokens=row[12],
-                    cache_read_tokens=row[13],
-                )
+    Args:
+        start_date: Optional start date in YYYY-MM-DD format (inclusive)
+        end_date: Optional end date in YYYY-MM-DD format (inclusive)
+        db_path: Path to the SQLite database file
+        folder: Only records from this project folder
+        model: Only records for this model
+        limit: Maximum number of records
+        offset: Records to skip; only applies with limit
 
-            record = UsageRecord(
-                timestamp=datetime.fromisoformat(row[2]),
-                session_id=row[3],
-                message_uuid=row[4],
-                message_type=row[5],
-                model=row[6],
-                folder=row[7],
-                git_branch=row[8],
-                version=row[9],
-                token_usage=token_usage,
-            )
-            records.append(record)
+    Returns:
+        List of UsageRecord objects
 
-        # If no records from usage_records, try daily_snapshots (aggregate mode)
-        if not records:
-            records = _load_from_daily_snapshots(cursor, start_date, end_date)
+    Raises:
+        sqlite3.Error: If database query fails
+    """
+    records = list(iter_historical_records(
+        start_date, end_date, folder=folder, model=model,
+        limit=limit, offset=offset, db_path=db_path,
+    ))
+
+    # If no records from usage_records, try daily_snapshots (aggregate mode).
+    # Synthetic records carry no project folder, so a folder filter can't match.
+    if not records and folder is None and db_path.exists():
+        conn = _connect(db_path)
+        try:
+            records = _load_from_daily_snapshots(conn.cursor(), start_date, end_date)
+        finally:
+            conn.close()
+        if model is not None:
+            records = [r for r in records if r.model == model]
+        if limit is not None:
+            records = records[offset:offset + limit]
 
-        return records
-    finally:
-        conn.close()
+    return records
 
 
 def _load_from_daily_snapshots(
@@ -734,7 +1435,10 @@ def _load_from_daily_snapshots(
     """
     Load records from daily_snapshots table and convert to synthetic UsageRecord objects.
 
-    Used when storage mode is 'aggregate' and usage_records is empty.
+    Used when storage mode is 'aggregate' and usage_records is empty. Days
+    with a model mix yield one record per model so model breakdowns and
+    costs survive; anything the mix doesn't cover (days recorded before it
+    existed) stays under the "aggregate" model.
 
     Args:
         cursor: SQLite cursor
@@ -742,497 +1446,1897 @@ def _load_from_daily_snapshots(
         end_date: Optional end date filter
 
     Returns:
-        List of synthetic UsageRecord objects (one per day)
+        List of synthetic UsageRecord objects (one per day and model)
     """
     from src.models.usage_record import TokenUsage
+    from src.storage.duckdb_backend import _read_daily_snapshots
 
-    query = "SELECT date, total_tokens, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, total_sessions FROM daily_snapshots WHERE 1=1"
-    params = []
-
-    if start_date:
-        query += " AND date >= ?"
-        params.append(start_date)
-
-    if end_date:
-        query += " AND date <= ?"
-        params.append(end_date)
-
-    query += " ORDER BY date"
-
-    cursor.execute(query, params)
+    token_fields = ("input_tokens", "output_tokens", "cache_creation_tokens", "cache_read_tokens")
 
     records = []
-    for row in cursor.fetchall():
-        date_str, total_tokens, input_tokens, output_tokens, cache_creation, cache_read, sessions = row
-
-        # Create a synthetic UsageRecord for each day
-        token_usage = TokenUsage(
-            input_tokens=input_tokens or 0,
-            output_tokens=output_tokens or 0,
-            cache_creation_tokens=cache_creation or 0,
-            cache_read_tokens=cache_read or 0,
-        )
+    for day in _read_daily_snapshots(cursor, start_date, end_date):
+        date_str = day["date"]
+        splits = dict(day["models"])
+        residual = {
+            field: max(day[field] - sum(mix[field] for mix in splits.values()), 0)
+            for field in token_fields
+        }
+        if any(residual.values()) or not splits:
+            splits["aggregate"] = residual
+
+        for model, mix in splits.items():
+            token_usage = TokenUsage(
+                input_tokens=mix["input_tokens"] or 0,
+                output_tokens=mix["output_tokens"] or 0,
+                cache_creation_tokens=mix["cache_creation_tokens"] or 0,
+                cache_read_tokens=mix["cache_read_tokens"] or 0,
+                cache_creation_1h_tokens=mix.get("cache_creation_1h_tokens", 0) or 0,
+            )
 
-        record = UsageRecord(
-            timestamp=datetime.fromisoformat(f"{date_str}T12:00:00"),
-            session_id=f"daily-{date_str}",
-            message_uuid=f"aggregate-{date_str}",
-            message_type="assistant",
-            model="aggregate",
-            folder="aggregate",
-            git_branch=None,
-            version=None,
-            token_usage=token_usage,
-        )
-        records.append(record)
+            record = UsageRecord(
+                timestamp=datetime.fromisoformat(f"{date_str}T12:00:00"),
+                session_id=f"daily-{date_str}",
+                message_uuid=f"aggregate-{date_str}-{model}",
+                message_type="assistant",
+                model=model,
+                folder="aggregate",
+                git_branch=None,
+                version=None,
+                token_usage=token_usage,
+            )
+            records.append(record)
 
     return records
 
 
-def get_text_analysis_stats(db_path: Path = DEFAULT_DB_PATH) -> dict:
+def load_daily_snapshots(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
     """
-    Analyze message content from JSONL files for text statistics.
+    Load daily totals with per-model splits and cost, without usage_records.
+
+    Args:
+        start_date: Optional start date in YYYY-MM-DD format (inclusive)
+        end_date: Optional end date in YYYY-MM-DD format (inclusive)
+        db_path: Path to the SQLite database file
 
     Returns:
-        Dictionary with text analysis statistics
+        One dict per day (date, prompts, responses, sessions, token fields,
+        models: {model: token fields}, cost), ordered by date
     """
-    from src.config.settings import get_claude_jsonl_files
-    from src.data.jsonl_parser import parse_all_jsonl_files
-    from src.utils.text_analysis import (
-        count_absolutely_right_phrases,
-        count_perfect_phrases,
-        count_please_phrases,
-        count_swears,
-        count_thank_phrases,
-    )
+    from src.storage.duckdb_backend import _read_daily_snapshots
 
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        # Get current JSONL files
-        jsonl_files = get_claude_jsonl_files()
-        if not jsonl_files:
-            return {
-                "user_swears": 0,
-                "assistant_swears": 0,
-                "perfect_count": 0,
-                "absolutely_right_count": 0,
-                "user_thanks": 0,
-                "user_please": 0,
-                "avg_user_prompt_chars": 0,
-                "total_user_chars": 0,
-            }
+        return _read_daily_snapshots(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
 
-        # Parse all records
-        records = parse_all_jsonl_files(jsonl_files)
 
-        user_swears = 0
-        assistant_swears = 0
-        perfect_count = 0
-        absolutely_right_count = 0
-        user_thanks = 0
-        user_please = 0
-        total_user_chars = 0
-        user_prompt_count = 0
+def get_project_activity(db_path: Path = DEFAULT_DB_PATH) -> list[dict]:
+    """
+    Lifetime usage and last activity date per project folder.
 
-        for record in records:
-            if not record.content:
-                continue
+    Args:
+        db_path: Path to the SQLite database file
 
-            if record.is_user_prompt:
-                user_swears += count_swears(record.content)
-                user_thanks += count_thank_phrases(record.content)
-                user_please += count_please_phrases(record.content)
-                total_user_chars += record.char_count
-                user_prompt_count += 1
-            elif record.is_assistant_response:
-                assistant_swears += count_swears(record.content)
-                perfect_count += count_perfect_phrases(record.content)
-                absolutely_right_count += count_absolutely_right_phrases(record.content)
+    Returns:
+        One dict per project (folder, first_date, last_date, active_days,
+        sessions, prompts, total_tokens, cost), most recently active first
+    """
+    from src.storage.duckdb_backend import _read_project_activity
 
-        avg_user_prompt_chars = total_user_chars / user_prompt_count if user_prompt_count > 0 else 0
+    if not db_path.exists():
+        return []
 
-        return {
-            "user_swears": user_swears,
-            "assistant_swears": assistant_swears,
-            "perfect_count": perfect_count,
-            "absolutely_right_count": absolutely_right_count,
-            "user_thanks": user_thanks,
-            "user_please": user_please,
-            "avg_user_prompt_chars": round(avg_user_prompt_chars),
-            "total_user_chars": total_user_chars,
-        }
-    except Exception:
-        # Return zeros if analysis fails
-        return {
-            "user_swears": 0,
-            "assistant_swears": 0,
-            "perfect_count": 0,
-            "absolutely_right_count": 0,
-            "user_thanks": 0,
-            "user_please": 0,
-            "avg_user_prompt_chars": 0,
-            "total_user_chars": 0,
-        }
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_project_activity(conn.cursor())
+    finally:
+        conn.close()
 
 
-def get_database_stats(db_path: Path = DEFAULT_DB_PATH) -> dict:
+def get_project_tokens(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> dict[str, int]:
     """
-    Get statistics about the historical database.
+    Total tokens per project folder within a date range.
 
     Args:
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
         db_path: Path to the SQLite database file
 
     Returns:
-        Dictionary with statistics including:
-        - total_records, total_days, oldest_date, newest_date, newest_timestamp
-        - total_tokens, total_prompts, total_sessions
-        - tokens_by_model: dict of model -> token count
-        - avg_tokens_per_session, avg_tokens_per_prompt
+        Dict of project folder -> total tokens
     """
-    if not db_path.exists():
-        return {
-            "total_records": 0,
-            "total_days": 0,
-            "oldest_date": None,
-            "newest_date": None,
-            "newest_timestamp": None,
-            "total_tokens": 0,
-            "total_prompts": 0,
-            "total_responses": 0,
-            "total_sessions": 0,
-            "tokens_by_model": {},
-            "cost_by_model": {},
-            "total_cost": 0.0,
-            "avg_tokens_per_session": 0,
-            "avg_tokens_per_response": 0,
-            "avg_cost_per_session": 0.0,
-            "avg_cost_per_response": 0.0,
-        }
+    from src.storage.duckdb_backend import _read_project_tokens
 
-    conn = sqlite3.connect(db_path)
+    if not db_path.exists():
+        return {}
 
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        cursor = conn.cursor()
+        return _read_project_tokens(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
 
-        # Basic counts
-        cursor.execute("SELECT COUNT(*) FROM usage_records")
-        total_records = cursor.fetchone()[0]
+
+def get_daily_projects(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> dict[str, dict[str, dict]]:
+    """
+    Tokens and estimated cost per project for each day.
+
+    Args:
+        start_date: Optional start date in YYYY-MM-DD format (inclusive)
+        end_date: Optional end date in YYYY-MM-DD format (inclusive)
+        db_path: Path to the SQLite database file
+
+    Returns:
+        {date: {folder: {"tokens": int, "cost": float}}}
+    """
+    from src.storage.duckdb_backend import _read_daily_projects
+
+    if not db_path.exists():
+        return {}
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_daily_projects(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
+
+
+def get_branch_stats(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Tokens and estimated cost per project and git branch.
+
+    Args:
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Dicts with folder, branch, tokens, cost, sessions, and responses,
+        costliest first (empty in aggregate storage mode)
+    """
+    from src.storage.duckdb_backend import _read_branch_stats
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_branch_stats(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
+
+
+def get_host_stats(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Tokens and estimated cost per machine the records were ingested on.
+
+    Args:
+        start_date: Optional inclusive start (YYYY-MM-DD)
+        end_date: Optional inclusive end (YYYY-MM-DD)
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Dicts with hostname, tokens, cost, sessions, responses, first_date,
+        and last_date, costliest first (empty in aggregate storage mode)
+    """
+    from src.storage.duckdb_backend import _read_host_stats
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_host_stats(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
+
+
+def get_hourly_snapshots(
+    hours: int = 24,
+    now: datetime | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Per-hour totals for the last N hours, including the current one.
+
+    Args:
+        hours: Window length in hours (5 covers a rate-limit window)
+        now: End of the window (default: now)
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Dicts with hour (UTC ISO string), prompts, responses, and token
+        fields, oldest first; hours without usage are absent
+    """
+    from src.storage.duckdb_backend import _read_hourly_snapshots
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_hourly_snapshots(conn.cursor(), hours, now)
+    finally:
+        conn.close()
+
+
+def get_hourly_range(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Per-hour totals for every hour overlapping a local date range.
+
+    Args:
+        start_date: Inclusive start (YYYY-MM-DD), or None for the first hour
+        end_date: Inclusive end (YYYY-MM-DD), or None for the last hour
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Dicts with hour (UTC ISO string), prompts, responses, and token
+        fields, oldest first; hours without usage are absent
+    """
+    from src.storage.duckdb_backend import _read_hourly_range
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_hourly_range(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
+
+
+def preview_ingest(
+    files: dict[str, list[UsageRecord]],
+    storage_mode: str,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> dict:
+    """
+    Count the records, dates and projects an ingest would add (read-only).
+
+    The database is opened read-only and not initialized, so a dry run
+    never creates tables, migrates, or touches the file.
+
+    Args:
+        files: Parsed records per transcript, keyed like the contribution ledger
+        storage_mode: "full" or "aggregate"
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Dict with new_records, new_dates and new_projects
+    """
+    from src.storage.duckdb_backend import _preview_ingest
+
+    if not db_path.exists():
+        return _preview_ingest(None, files, storage_mode)
+
+    conn = _open(db_path, read_only=True)
+    try:
+        return _preview_ingest(conn.cursor(), files, storage_mode)
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def save_monthly_summary(month: str, summary: dict, db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Store a finished month's recap facts (see src.aggregation.recap).
+
+    Args:
+        month: Month in YYYY-MM format
+        summary: JSON-serializable recap facts
+        db_path: Path to the SQLite database file
+    """
+    from src.storage.duckdb_backend import _SUMMARY_COLUMNS
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        conn.execute(
+            f"INSERT OR REPLACE INTO monthly_summaries ({', '.join(_SUMMARY_COLUMNS)}) VALUES (?, ?, ?)",
+            (month, datetime.now().isoformat(), json.dumps(summary)),
+        )
+        conn.commit()
+    finally:
+        conn.close()
+
+
+def get_monthly_summary(month: str, db_path: Path = DEFAULT_DB_PATH) -> dict | None:
+    """
+    Stored recap facts for a month.
+
+    Args:
+        month: Month in YYYY-MM format
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Recap facts, or None if the month hasn't been summarized
+    """
+    if not db_path.exists():
+        return None
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        row = conn.execute("SELECT summary FROM monthly_summaries WHERE month = ?", (month,)).fetchone()
+        return json.loads(row[0]) if row else None
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def add_session_tags(session_id: str, tags: list[str], db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Attach tags to a session (existing tags are kept).
+
+    Args:
+        session_id: Full session identifier
+        tags: Labels to attach
+        db_path: Path to the SQLite database file
+    """
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        now = datetime.now().isoformat()
+        conn.executemany(
+            "INSERT OR IGNORE INTO session_tags (session_id, tag, created_at) VALUES (?, ?, ?)",
+            [(session_id, tag, now) for tag in tags],
+        )
+        conn.commit()
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def remove_session_tags(session_id: str, tags: list[str] | None = None, db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Detach tags from a session.
+
+    Args:
+        session_id: Full session identifier
+        tags: Labels to detach; None removes all of the session's tags
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Number of tags removed
+    """
+    if not db_path.exists():
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        where, params = "session_id = ?", [session_id]
+        if tags is not None:
+            where += f" AND tag IN ({', '.join('?' for _ in tags)})"
+            params.extend(tags)
+        removed = conn.execute(f"DELETE FROM session_tags WHERE {where}", params).rowcount
+        conn.commit()
+        return removed
+    finally:
+        conn.close()
+
+
+def get_session_tags(db_path: Path = DEFAULT_DB_PATH) -> dict[str, list[str]]:
+    """
+    Tags of every tagged session.
+
+    Args:
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Dict of session id -> tags, alphabetically
+    """
+    from src.storage.duckdb_backend import _read_session_tags
+
+    if not db_path.exists():
+        return {}
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_session_tags(conn.cursor())
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def add_annotation(date: str, label: str, db_path: Path = DEFAULT_DB_PATH) -> bool:
+    """
+    Annotate a day with a milestone label.
+
+    Args:
+        date: Day (YYYY-MM-DD)
+        label: Milestone text, e.g. "launched v2"
+        db_path: Path to the SQLite database file
+
+    Returns:
+        False when the day already has this label
+    """
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        added = conn.execute(
+            "INSERT OR IGNORE INTO annotations (date, label, created_at) VALUES (?, ?, ?)",
+            (date, label, datetime.now().isoformat()),
+        ).rowcount
+        conn.commit()
+        return added > 0
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def remove_annotations(date: str, label: str | None = None, db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Remove annotations from a day.
+
+    Args:
+        date: Day (YYYY-MM-DD)
+        label: Label to remove; None removes all of the day's annotations
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Number of annotations removed
+    """
+    if not db_path.exists():
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        where, params = "date = ?", [date]
+        if label is not None:
+            where += " AND label = ?"
+            params.append(label)
+        removed = conn.execute(f"DELETE FROM annotations WHERE {where}", params).rowcount
+        conn.commit()
+        return removed
+    finally:
+        conn.close()
+
+
+def get_annotations(
+    start_date: str | None = None, end_date: str | None = None, db_path: Path = DEFAULT_DB_PATH
+) -> dict[str, list[str]]:
+    """
+    Annotations by date, optionally within a window.
+
+    Args:
+        start_date: First day to include (YYYY-MM-DD), None for no lower bound
+        end_date: Last day to include (YYYY-MM-DD), None for no upper bound
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Dict of date -> labels in the order they were added
+    """
+    from src.storage.duckdb_backend import _read_annotations
+
+    if not db_path.exists():
+        return {}
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_annotations(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
+
+
+def get_text_analysis_stats(db_path: Path = DEFAULT_DB_PATH) -> dict:
+    """
+    Analyze message content from JSONL files for text statistics.
+
+    Returns:
+        Dictionary with text analysis statistics
+    """
+    from src.config.settings import get_claude_jsonl_files
+    from src.data.jsonl_parser import parse_all_jsonl_files
+    from src.utils.text_analysis import (
+        count_absolutely_right_phrases,
+        count_perfect_phrases,
+        count_please_phrases,
+        count_swears,
+        count_thank_phrases,
+    )
+
+    try:
+        # Get current JSONL files
+        jsonl_files = get_claude_jsonl_files()
+        if not jsonl_files:
+            return {
+                "user_swears": 0,
+                "assistant_swears": 0,
+                "perfect_count": 0,
+                "absolutely_right_count": 0,
+                "user_thanks": 0,
+                "user_please": 0,
+                "avg_user_prompt_chars": 0,
+                "total_user_chars": 0,
+            }
+
+        # Parse all records
+        records = parse_all_jsonl_files(jsonl_files)
+
+        user_swears = 0
+        assistant_swears = 0
+        perfect_count = 0
+        absolutely_right_count = 0
+        user_thanks = 0
+        user_please = 0
+        total_user_chars = 0
+        user_prompt_count = 0
+
+        for record in records:
+            if not record.content:
+                continue
+
+            if record.is_user_prompt:
+                user_swears += count_swears(record.content)
+                user_thanks += count_thank_phrases(record.content)
+                user_please += count_please_phrases(record.content)
+                total_user_chars += record.char_count
+                user_prompt_count += 1
+            elif record.is_assistant_response:
+                assistant_swears += count_swears(record.content)
+                perfect_count += count_perfect_phrases(record.content)
+                absolutely_right_count += count_absolutely_right_phrases(record.content)
+
+        avg_user_prompt_chars = total_user_chars / user_prompt_count if user_prompt_count > 0 else 0
+
+        return {
+            "user_swears": user_swears,
+            "assistant_swears": assistant_swears,
+            "perfect_count": perfect_count,
+            "absolutely_right_count": absolutely_right_count,
+            "user_thanks": user_thanks,
+            "user_please": user_please,
+            "avg_user_prompt_chars": round(avg_user_prompt_chars),
+            "total_user_chars": total_user_chars,
+        }
+    except Exception:
+        # Return zeros if analysis fails
+        return {
+            "user_swears": 0,
+            "assistant_swears": 0,
+            "perfect_count": 0,
+            "absolutely_right_count": 0,
+            "user_thanks": 0,
+            "user_please": 0,
+            "avg_user_prompt_chars": 0,
+            "total_user_chars": 0,
+        }
+
+
+def get_database_stats(db_path: Path = DEFAULT_DB_PATH) -> dict:
+    """
+    Get statistics about the historical database.
+
+    Args:
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Dictionary with statistics including:
+        - total_records, total_days, oldest_date, newest_date, newest_timestamp
+          (days and dates from the day totals in aggregate mode)
+        - total_tokens, total_prompts, total_sessions
+        - tokens_by_model: dict of model -> token count (from the per-day
+          model rollup in aggregate mode)
+        - avg_tokens_per_session, avg_tokens_per_prompt
+        - output_tokens, thinking_tokens, thinking_cost (reasoning overhead)
+        - subagent_tokens, subagent_cost, subagent_sessions (delegated work)
+    """
+    from src.storage.duckdb_backend import (
+        _MODEL_MIX_TOTALS_SQL,
+        _PRICING_ROWS_SQL,
+        _SNAPSHOT_RANGE_SQL,
+        _read_attachment_stats,
+        _read_subagent_stats,
+        _read_thinking_stats,
+    )
+
+    if not db_path.exists():
+        return {
+            "total_records": 0,
+            "total_days": 0,
+            "oldest_date": None,
+            "newest_date": None,
+            "newest_timestamp": None,
+            "total_tokens": 0,
+            "total_prompts": 0,
+            "total_responses": 0,
+            "total_sessions": 0,
+            "tokens_by_model": {},
+            "cost_by_model": {},
+            "unpriced_models": [],
+            "total_cost": 0.0,
+            "avg_tokens_per_session": 0,
+            "avg_tokens_per_response": 0,
+            "avg_cost_per_session": 0.0,
+            "avg_cost_per_response": 0.0,
+            "output_tokens": 0,
+            "thinking_tokens": 0,
+            "thinking_cost": 0.0,
+            "subagent_tokens": 0,
+            "subagent_cost": 0.0,
+            "subagent_sessions": 0,
+            "total_attachments": 0,
+            "attachment_days": 0,
+            "attachments_by_day": [],
+        }
+
+    conn = _connect(db_path)
+
+    try:
+        cursor = conn.cursor()
+
+        # Basic counts
+        cursor.execute("SELECT COUNT(*) FROM usage_records")
+        total_records = cursor.fetchone()[0]
 
         cursor.execute("SELECT COUNT(DISTINCT date) FROM usage_records")
         total_days = cursor.fetchone()[0]
 
-        cursor.execute("SELECT MIN(date), MAX(date) FROM usage_records")
-        oldest_date, newest_date = cursor.fetchone()
+        cursor.execute("SELECT MIN(date), MAX(date) FROM usage_records")
+        oldest_date, newest_date = cursor.fetchone()
+
+        # Get newest snapshot timestamp
+        cursor.execute("SELECT MAX(snapshot_timestamp) FROM daily_snapshots")
+        newest_timestamp = cursor.fetchone()[0]
+
+        # Aggregate statistics from daily_snapshots
+        cursor.execute("""
+            SELECT
+                SUM(total_tokens) as total_tokens,
+                SUM(total_prompts) as total_prompts,
+                SUM(total_responses) as total_responses,
+                SUM(total_sessions) as total_sessions
+            FROM daily_snapshots
+        """)
+        row = cursor.fetchone()
+        total_tokens = row[0] or 0
+        total_prompts = row[1] or 0
+        total_responses = row[2] or 0
+        total_sessions = row[3] or 0
+
+        # Tokens by model (only available if usage_records exist)
+        tokens_by_model = {}
+        if total_records > 0:
+            cursor.execute("""
+                SELECT model, SUM(total_tokens) as tokens
+                FROM usage_records
+                GROUP BY model
+                ORDER BY tokens DESC
+            """)
+            tokens_by_model = {row[0]: row[1] for row in cursor.fetchall() if row[0]}
+
+        # Calculate costs: pricing resolves by model family, not just an
+        # exact join, so new snapshots of known models are still priced
+        total_cost = 0.0
+        cost_by_model = {}
+        unpriced_models = []
+        thinking = {"output_tokens": 0, "thinking_tokens": 0, "thinking_cost": 0.0}
+        subagents = {"subagent_tokens": 0, "subagent_cost": 0.0, "subagent_sessions": 0}
+        attachments = {"total_attachments": 0, "attachment_days": 0, "attachments_by_day": []}
+
+        if total_records > 0:
+            cursor.execute(f"""
+                SELECT
+                    model,
+                    SUM(input_tokens),
+                    SUM(output_tokens),
+                    SUM(cache_creation_tokens),
+                    SUM(cache_read_tokens),
+                    SUM(COALESCE(cache_creation_1h_tokens, 0)),
+                    {LONG_CONTEXT_SQL}
+                FROM usage_records
+                WHERE model IS NOT NULL
+                GROUP BY model, {LONG_CONTEXT_SQL}
+            """)
+            usage_rows = cursor.fetchall()
+            cursor.execute("""
+                SELECT model_name, input_price_per_mtok, output_price_per_mtok,
+                       cache_write_price_per_mtok, cache_read_price_per_mtok,
+                       cache_write_1h_price_per_mtok
+                FROM model_pricing
+            """)
+            pricing_rows = cursor.fetchall()
+            cost_by_model, unpriced_models = price_model_usage(usage_rows, pricing_rows)
+            total_cost = sum(cost_by_model.values())
+            thinking = _read_thinking_stats(cursor, pricing_rows)
+            subagents = _read_subagent_stats(cursor, pricing_rows)
+            attachments = _read_attachment_stats(cursor)
+        else:
+            # Aggregate mode: the day totals give the date range, and the
+            # per-day model rollup still splits tokens and cost by model
+            total_days, oldest_date, newest_date = cursor.execute(_SNAPSHOT_RANGE_SQL).fetchone()
+            mix_rows = cursor.execute(_MODEL_MIX_TOTALS_SQL).fetchall()
+            tokens_by_model = {row[0]: row[6] for row in mix_rows if row[6]}
+            cost_by_model, unpriced_models = price_model_usage(
+                [row[:6] for row in mix_rows], cursor.execute(_PRICING_ROWS_SQL).fetchall()
+            )
+            total_cost = sum(cost_by_model.values())
+
+        # Calculate averages
+        avg_tokens_per_session = total_tokens / total_sessions if total_sessions > 0 else 0
+        avg_tokens_per_response = total_tokens / total_responses if total_responses > 0 else 0
+        avg_cost_per_session = total_cost / total_sessions if total_sessions > 0 else 0
+        avg_cost_per_response = total_cost / total_responses if total_responses > 0 else 0
+
+        return {
+            "total_records": total_records,
+            "total_days": total_days,
+            "oldest_date": oldest_date,
+            "newest_date": newest_date,
+            "newest_timestamp": newest_timestamp,
+            "total_tokens": total_tokens,
+            "total_prompts": total_prompts,
+            "total_responses": total_responses,
+            "total_sessions": total_sessions,
+            "tokens_by_model": tokens_by_model,
+            "cost_by_model": cost_by_model,
+            "unpriced_models": unpriced_models,
+            "total_cost": total_cost,
+            "avg_tokens_per_session": round(avg_tokens_per_session),
+            "avg_tokens_per_response": round(avg_tokens_per_response),
+            "avg_cost_per_session": round(avg_cost_per_session, 2),
+            "avg_cost_per_response": round(avg_cost_per_response, 4),
+            **thinking,
+            **subagents,
+            **attachments,
+        }
+    finally:
+        conn.close()
+
+
+def get_stale_files(
+    all_files: list[Path],
+    db_path: Path = DEFAULT_DB_PATH
+) -> tuple[list[Path], list[str]]:
+    """
+    Identify which JSONL files need to be re-parsed based on mtime/size changes.
+
+    Compares current file stats against stored metadata to find:
+    - New files (not in database)
+    - Modified files (mtime or size changed)
+    - Deleted files (in database but not on disk)
+
+    Args:
+        all_files: List of all JSONL file paths on disk
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Tuple of (stale_files, deleted_file_paths):
+        - stale_files: List of Path objects that need re-parsing
+        - deleted_file_paths: List of file path strings that were deleted
+    """
+    if not db_path.exists():
+        # No database yet, all files are stale
+        return (all_files, [])
+
+    init_database(db_path)
+
+    conn = _connect(db_path)
+    stale_files = []
+    deleted_files = []
+
+    try:
+        cursor = conn.cursor()
+
+        # Get all stored file metadata
+        cursor.execute("SELECT file_path, mtime_ns, size_bytes FROM file_metadata")
+        stored_metadata = {row[0]: (row[1], row[2]) for row in cursor.fetchall()}
+
+        # Convert current files to set of path strings for comparison
+        current_file_paths = {str(f) for f in all_files}
+
+        # Check each current file
+        for file_path in all_files:
+            path_str = str(file_path)
+            try:
+                stat = file_path.stat()
+                current_mtime_ns = stat.st_mtime_ns
+                current_size = stat.st_size
+
+                if path_str not in stored_metadata:
+                    # New file
+                    stale_files.append(file_path)
+                else:
+                    stored_mtime_ns, stored_size = stored_metadata[path_str]
+                    if current_mtime_ns != stored_mtime_ns or current_size != stored_size:
+                        # Modified file
+                        stale_files.append(file_path)
+            except OSError:
+                # File inaccessible, skip
+                continue
+
+        # Find deleted files (in DB but not on disk)
+        for stored_path in stored_metadata.keys():
+            if stored_path not in current_file_paths:
+                deleted_files.append(stored_path)
+
+        return (stale_files, deleted_files)
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def update_files_metadata(
+    file_paths: list[Path],
+    record_count: int = 0,
+    db_path: Path = DEFAULT_DB_PATH,
+    stats: dict[str, tuple[int, int]] | None = None,
+) -> None:
+    """
+    Upsert parse metadata for many files in a single connection.
+
+    Files that fail to stat are skipped.
+
+    Args:
+        file_paths: Paths to the JSONL files
+        record_count: Number of records parsed from each file
+        db_path: Path to the SQLite database file
+    """
+    if not file_paths:
+        return
+
+    init_database(db_path)
+
+    timestamp = datetime.now().isoformat()
+    rows = []
+    for file_path in file_paths:
+        pre = (stats or {}).get(str(file_path))
+        if pre is not None:
+            mtime_ns, size_bytes = pre
+        else:
+            try:
+                stat = file_path.stat()
+            except OSError:
+                continue
+            mtime_ns, size_bytes = stat.st_mtime_ns, stat.st_size
+        rows.append((str(file_path), mtime_ns, size_bytes, record_count, timestamp))
+
+    if not rows:
+        return
+
+    conn = _connect(db_path)
+    try:
+        conn.executemany("""
+            INSERT OR REPLACE INTO file_metadata (
+                file_path, mtime_ns, size_bytes, record_count, last_parsed
+            ) VALUES (?, ?, ?, ?, ?)
+        """, rows)
+        conn.commit()
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def remove_deleted_file_metadata(
+    deleted_paths: list[str],
+    db_path: Path = DEFAULT_DB_PATH
+) -> None:
+    """
+    Remove metadata for files that no longer exist.
+
+    Args:
+        deleted_paths: List of file path strings to remove
+        db_path: Path to the SQLite database file
+    """
+    if not deleted_paths:
+        return
+
+    if not db_path.exists():
+        return
+
+    conn = _connect(db_path)
+
+    try:
+        cursor = conn.cursor()
+
+        for path in deleted_paths:
+            cursor.execute("DELETE FROM file_metadata WHERE file_path = ?", (path,))
+            cursor.execute("DELETE FROM ingestion_state WHERE file_path = ?", (path,))
+
+        conn.commit()
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def clone_model_pricing(
+    model_name: str,
+    like_model: str,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> str:
+    """
+    Add (or replace) a pricing row for a model by copying another model's rates.
+
+    Args:
+        model_name: Model name exactly as it appears in usage_records
+        like_model: Pricing model to copy; resolved like cost lookups, so
+            "claude-sonnet-4-5" finds claude-sonnet-4-5-20250929
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Name of the pricing row the rates were copied from
+
+    Raises:
+        ValueError: If like_model matches no pricing row
+    """
+    init_database(db_path)
+    conn = _connect(db_path)
+
+    try:
+        cursor = conn.cursor()
+        cursor.execute("SELECT model_name FROM model_pricing")
+        source = match_pricing_model(like_model, [row[0] for row in cursor.fetchall()])
+        if source is None:
+            raise ValueError(f"No pricing found for '{like_model}'")
+
+        cursor.execute("""
+            INSERT OR REPLACE INTO model_pricing (
+                model_name, input_price_per_mtok, output_price_per_mtok,
+                cache_write_price_per_mtok, cache_read_price_per_mtok,
+                cache_write_1h_price_per_mtok, last_updated, notes
+            )
+            SELECT ?, input_price_per_mtok, output_price_per_mtok,
+                   cache_write_price_per_mtok, cache_read_price_per_mtok,
+                   cache_write_1h_price_per_mtok, ?, ?
+            FROM model_pricing
+            WHERE model_name = ?
+        """, (model_name, datetime.now().isoformat(), f"Priced like {source}", source))
+
+        conn.commit()
+        return source
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def sync_model_pricing(db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Apply bundled and fetched remote pricing to model_pricing now.
+
+    init_database does this on open; call it after `ccg pricing update`
+    within a command whose database is already open.
+
+    Args:
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Rows written
+    """
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        written = _sync_model_pricing(conn.cursor())
+        conn.commit()
+        return written
+    finally:
+        conn.close()
+
+
+def get_model_pricing(db_path: Path = DEFAULT_DB_PATH) -> list[tuple]:
+    """
+    Current pricing rows, including any added with `ccg pricing add`.
+
+    Args:
+        db_path: Path to the SQLite database file
+
+    Returns:
+        List of (model_name, input, output, cache_write, cache_read, cache_write_1h)
+    """
+    from src.storage.duckdb_backend import _PRICING_ROWS_SQL
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return conn.execute(_PRICING_ROWS_SQL).fetchall()
+    finally:
+        conn.close()
+
+
+def get_update_coverage(db_path: Path = DEFAULT_DB_PATH) -> dict:
+    """
+    Get record count and date bounds of usage_records.
+
+    Cheap alternative to get_database_stats for flows that only need
+    coverage, not token/cost aggregates.
+
+    Returns:
+        Dict with total_records, oldest_date, newest_date
+    """
+    if not db_path.exists():
+        return {"total_records": 0, "oldest_date": None, "newest_date": None}
+
+    conn = _connect(db_path)
+    try:
+        row = conn.execute(
+            "SELECT COUNT(*), MIN(date), MAX(date) FROM usage_records"
+        ).fetchone()
+        return {"total_records": row[0] or 0, "oldest_date": row[1], "newest_date": row[2]}
+    finally:
+        conn.close()
+
+
+def get_database_health(db_path: Path = DEFAULT_DB_PATH) -> dict:
+    """
+    Structural checks for `ccg doctor`.
+
+    Reads the file as is: run before anything that calls init_database,
+    which would recreate missing indexes.
+
+    Args:
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Dict with integrity_errors (PRAGMA integrity_check messages, empty
+        when intact), missing_indexes (of EXPECTED_INDEXES), and
+        orphaned_snapshot_days (daily_snapshots days with tokens but no
+        usage_records)
+    """
+    conn = _connect(db_path)
+    try:
+        integrity = [row[0] for row in conn.execute("PRAGMA integrity_check").fetchall()]
+        present = {row[0] for row in conn.execute("SELECT name FROM sqlite_master WHERE type = 'index'")}
+        orphaned = conn.execute("""
+            SELECT COUNT(*) FROM daily_snapshots
+            WHERE total_tokens > 0
+              AND NOT EXISTS (SELECT 1 FROM usage_records WHERE usage_records.date = daily_snapshots.date)
+        """).fetchone()[0]
+    finally:
+        conn.close()
+    return {
+        "integrity_errors": [] if integrity == ["ok"] else integrity,
+        "missing_indexes": [name for name in EXPECTED_INDEXES if name not in present],
+        "orphaned_snapshot_days": orphaned,
+    }
+
+
+@_retry_on_lock
+def record_update_run(
+    started_at: str,
+    finished_at: str,
+    trigger: str,
+    exit_status: int,
+    new_records: int = 0,
+    error: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> None:
+    """
+    Log one `ccg update usage` run in update_runs.
+
+    Args:
+        started_at: ISO timestamp the run started (primary key)
+        finished_at: ISO timestamp the run finished
+        trigger: "hook" for Stop-hook runs, "watch" for --watch, "manual" otherwise
+        exit_status: 0 on success, 1 on failure
+        new_records: Records saved by the run
+        error: Error message for failed runs
+        db_path: Path to the SQLite database file
+    """
+    from src.storage.duckdb_backend import _UPDATE_RUN_COLUMNS
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        conn.execute(
+            f"INSERT OR REPLACE INTO update_runs ({', '.join(_UPDATE_RUN_COLUMNS)}) "
+            f"VALUES (?, ?, ?, ?, ?, ?)",
+            (started_at, finished_at, trigger, exit_status, new_records, error),
+        )
+        conn.commit()
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def record_full_ingest(finished_at: str, db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Note that an ingest over every transcript source finished.
+
+    Args:
+        finished_at: ISO timestamp the ingest finished
+        db_path: Path to the SQLite database file
+    """
+    from src.storage.duckdb_backend import LAST_FULL_INGEST_KEY
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        conn.execute(
+            "INSERT OR REPLACE INTO ingest_status (key, value) VALUES (?, ?)",
+            (LAST_FULL_INGEST_KEY, finished_at),
+        )
+        conn.commit()
+    finally:
+        conn.close()
+
+
+def get_last_full_ingest(db_path: Path = DEFAULT_DB_PATH) -> str | None:
+    """
+    When the last ingest over every transcript source finished.
+
+    Args:
+        db_path: Path to the SQLite database file
+
+    Returns:
+        ISO timestamp, or None if no full ingest has been recorded
+    """
+    from src.storage.duckdb_backend import _read_last_full_ingest
+
+    if not db_path.exists():
+        return None
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_last_full_ingest(conn.cursor())
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def set_ingest_status(key: str, value: str, db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Store an ingest_status value.
+
+    Args:
+        key: Status key
+        value: Value to store (replaces any previous one)
+        db_path: Path to the SQLite database file
+    """
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        conn.execute("INSERT OR REPLACE INTO ingest_status (key, value) VALUES (?, ?)", (key, value))
+        conn.commit()
+    finally:
+        conn.close()
+
+
+def get_ingest_status(key: str, db_path: Path = DEFAULT_DB_PATH) -> str | None:
+    """
+    An ingest_status value.
+
+    Args:
+        key: Status key
+        db_path: Path to the SQLite database file
+
+    Returns:
+        The stored value, or None if the key is unset
+    """
+    from src.storage.duckdb_backend import _read_ingest_status
+
+    if not db_path.exists():
+        return None
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_ingest_status(conn.cursor(), key)
+    finally:
+        conn.close()
+
+
+def get_update_runs(
+    trigger: str | None = None,
+    limit: int = 20,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Recent `ccg update usage` runs, newest first.
+
+    Args:
+        trigger: Only runs with this trigger ("hook", "watch", or "manual"); None for all
+        limit: Maximum rows to return
+        db_path: Path to the SQLite database file
+
+    Returns:
+        List of dicts (started_at, finished_at, trigger, exit_status,
+        new_records, error)
+    """
+    from src.storage.duckdb_backend import _read_update_runs
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_update_runs(conn.cursor(), trigger, limit)
+    finally:
+        conn.close()
+
+
+def get_ingestion_state(
+    file_paths: list[str],
+    db_path: Path = DEFAULT_DB_PATH,
+) -> dict[str, tuple[int, int, str]]:
+    """
+    Byte offsets parsed so far for the given transcripts.
+
+    Args:
+        file_paths: File path strings to look up
+        db_path: Path to the SQLite database file
 
-        # Get newest snapshot timestamp
-        cursor.execute("SELECT MAX(snapshot_timestamp) FROM daily_snapshots")
-        newest_timestamp = cursor.fetchone()[0]
+    Returns:
+        Dict of file path -> (size_bytes, byte_offset, head_digest); files
+        never parsed incrementally are absent
+    """
+    from src.storage.duckdb_backend import _read_ingestion_state
 
-        # Aggregate statistics from daily_snapshots
-        cursor.execute("""
-            SELECT
-                SUM(total_tokens) as total_tokens,
-                SUM(total_prompts) as total_prompts,
-                SUM(total_responses) as total_responses,
-                SUM(total_sessions) as total_sessions
-            FROM daily_snapshots
-        """)
-        row = cursor.fetchone()
-        total_tokens = row[0] or 0
-        total_prompts = row[1] or 0
-        total_responses = row[2] or 0
-        total_sessions = row[3] or 0
+    if not file_paths or not db_path.exists():
+        return {}
 
-        # Tokens by model (only available if usage_records exist)
-        tokens_by_model = {}
-        if total_records > 0:
-            cursor.execute("""
-                SELECT model, SUM(total_tokens) as tokens
-                FROM usage_records
-                GROUP BY model
-                ORDER BY tokens DESC
-            """)
-            tokens_by_model = {row[0]: row[1] for row in cursor.fetchall() if row[0]}
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_ingestion_state(conn.cursor(), file_paths)
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def update_ingestion_state(
+    entries: list[tuple[str, int, int, str]],
+    db_path: Path = DEFAULT_DB_PATH,
+) -> None:
+    """
+    Upsert parse positions after a successful incremental ingest.
+
+    Args:
+        entries: (file_path, size_bytes, byte_offset, head_digest) tuples
+        db_path: Path to the SQLite database file
+    """
+    if not entries:
+        return
+
+    init_database(db_path)
+    timestamp = datetime.now().isoformat()
+    conn = _connect(db_path)
+    try:
+        conn.executemany("""
+            INSERT OR REPLACE INTO ingestion_state (
+                file_path, size_bytes, byte_offset, head_digest, updated_at
+            ) VALUES (?, ?, ?, ?, ?)
+        """, [(*entry, timestamp) for entry in entries])
+        conn.commit()
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def save_quarantined_entries(
+    entries: list[QuarantinedEntry],
+    db_path: Path = DEFAULT_DB_PATH,
+) -> int:
+    """
+    Store entries held back from ingestion, ignoring ones already held.
+
+    Args:
+        entries: Entries collected by the parser
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Number of newly quarantined entries
+    """
+    from src.storage.duckdb_backend import _QUARANTINE_COLUMNS, _quarantine_rows
+
+    if not entries:
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        before = conn.total_changes
+        conn.executemany(f"""
+            INSERT OR IGNORE INTO quarantined_entries ({', '.join(_QUARANTINE_COLUMNS)})
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+        """, _quarantine_rows(entries))
+        conn.commit()
+        return conn.total_changes - before
+    finally:
+        conn.close()
+
+
+def get_quarantined_entries(
+    limit: int | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Quarantined entries awaiting review, newest first.
+
+    Args:
+        limit: Maximum rows to return; None for all
+        db_path: Path to the SQLite database file
+
+    Returns:
+        List of dicts keyed by quarantined_entries column
+    """
+    from src.storage.duckdb_backend import _read_quarantine
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_quarantine(conn.cursor(), limit)
+    finally:
+        conn.close()
+
+
+def count_quarantined_entries(db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Number of quarantined entries awaiting review.
+
+    Args:
+        db_path: Path to the SQLite database file
+    """
+    from src.storage.duckdb_backend import _count_quarantine
+
+    if not db_path.exists():
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _count_quarantine(conn.cursor())
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def dismiss_quarantined_entries(keys: list[tuple[str, str]], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Dismiss reviewed quarantined entries.
+
+    Dismissed rows are kept (hidden) so re-parsing the same transcript
+    doesn't quarantine them again.
+
+    Args:
+        keys: (file_path, entry_id) of each reviewed entry
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Number of entries dismissed
+    """
+    from src.storage.duckdb_backend import _dismiss_quarantine
+
+    if not keys or not db_path.exists():
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        dismissed = _dismiss_quarantine(conn.cursor(), keys)
+        conn.commit()
+        return dismissed
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def save_unknown_fields(counts: dict[str, int], db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Add occurrences of unknown transcript fields to their running totals.
+
+    Args:
+        counts: Field path -> occurrences in this run (ParseStats.unknown_fields)
+        db_path: Path to the SQLite database file
+    """
+    from src.storage.duckdb_backend import _UPSERT_UNKNOWN_FIELD_SQL
+
+    if not counts:
+        return
+
+    init_database(db_path)
+    timestamp = datetime.now().isoformat()
+    conn = _connect(db_path)
+    try:
+        conn.executemany(
+            _UPSERT_UNKNOWN_FIELD_SQL,
+            [(name, count, timestamp, timestamp) for name, count in counts.items()],
+        )
+        conn.commit()
+    finally:
+        conn.close()
+
+
+def get_unknown_fields(db_path: Path = DEFAULT_DB_PATH) -> list[dict]:
+    """
+    Unknown transcript fields seen during ingestion, most frequent first.
+
+    Args:
+        db_path: Path to the SQLite database file
+
+    Returns:
+        List of dicts with field, occurrences, first_seen, and last_seen
+    """
+    from src.storage.duckdb_backend import _UNKNOWN_FIELD_COLUMNS
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        rows = conn.execute(
+            f"SELECT {', '.join(_UNKNOWN_FIELD_COLUMNS)} FROM unknown_fields ORDER BY occurrences DESC, field"
+        ).fetchall()
+        return [dict(zip(_UNKNOWN_FIELD_COLUMNS, row)) for row in rows]
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def save_session_events(
+    events: list[SessionEvent],
+    db_path: Path = DEFAULT_DB_PATH,
+) -> int:
+    """
+    Store session events, ignoring ones already stored.
+
+    Args:
+        events: Events from the transcript parser
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Number of new events
+    """
+    from src.storage.duckdb_backend import _EVENT_COLUMNS, _event_rows
+
+    if not events:
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        before = conn.total_changes
+        conn.executemany(f"""
+            INSERT OR IGNORE INTO session_events ({', '.join(_EVENT_COLUMNS)})
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        """, _event_rows(events))
+        conn.commit()
+        return conn.total_changes - before
+    finally:
+        conn.close()
+
+
+def get_session_events(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    API errors, rate-limit hits, interruptions, and compactions in a date window.
+
+    Args:
+        start_date: First date (YYYY-MM-DD), or None for all history
+        end_date: Last date (YYYY-MM-DD), or None for up to today
+        db_path: Path to the SQLite database file
+
+    Returns:
+        List of dicts keyed by session_events column, oldest first per session
+    """
+    from src.storage.duckdb_backend import _read_session_events
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_session_events(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def save_mcp_calls(records: list[UsageRecord], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Store the MCP tool calls and tool result sizes in parsed records.
+
+    Args:
+        records: Parsed records (assistant calls and user results)
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Number of new calls
+    """
+    from src.storage.duckdb_backend import _write_mcp_calls
+
+    if not any(r.tool_results or r.mcp_calls for r in records):
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        cursor = conn.cursor()
+        before = cursor.execute("SELECT COUNT(*) FROM mcp_calls").fetchone()[0]
+        _write_mcp_calls(cursor, records)
+        conn.commit()
+        return cursor.execute("SELECT COUNT(*) FROM mcp_calls").fetchone()[0] - before
+    finally:
+        conn.close()
+
+
+@_retry_on_lock
+def save_tool_usage(records: list[UsageRecord], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Store every tool call in parsed records, ignoring ones already stored.
+
+    Args:
+        records: Parsed records
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Number of new calls
+    """
+    from src.storage.duckdb_backend import _TOOL_USAGE_INSERT_SQL, _tool_usage_rows
+
+    rows = _tool_usage_rows(records)
+    if not rows:
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        cursor = conn.cursor()
+        before = cursor.execute("SELECT COUNT(*) FROM tool_usage").fetchone()[0]
+        cursor.executemany(_TOOL_USAGE_INSERT_SQL, rows)
+        conn.commit()
+        return cursor.execute("SELECT COUNT(*) FROM tool_usage").fetchone()[0] - before
+    finally:
+        conn.close()
+
+
+def get_tool_usage(
+    start_date: str | None = None,
+    end_date: str | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> list[dict]:
+    """
+    Tool call counts per date, session, and tool.
+
+    Args:
+        start_date: First date (YYYY-MM-DD), or None for all history
+        end_date: Last date (YYYY-MM-DD), or None for up to today
+        db_path: Path to the SQLite database file
+
+    Returns:
+        List of dicts with date, session_id, tool, and count
+    """
+    from src.storage.duckdb_backend import _read_tool_usage
+
+    if not db_path.exists():
+        return []
+
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_tool_usage(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
 
-        # Calculate costs by joining with pricing table
-        total_cost = 0.0
-        cost_by_model = {}
 
-        if total_records > 0:
-            cursor.execute("""
-                SELECT
-                    ur.model,
-                    SUM(ur.input_tokens) as total_input,
-                    SUM(ur.output_tokens) as total_output,
-                    SUM(ur.cache_creation_tokens) as total_cache_write,
-                    SUM(ur.cache_read_tokens) as total_cache_read,
-                    mp.input_price_per_mtok,
-                    mp.output_price_per_mtok,
-                    mp.cache_write_price_per_mtok,
-                    mp.cache_read_price_per_mtok,
-                    SUM(COALESCE(ur.cache_creation_1h_tokens, 0)) as total_cache_write_1h,
-                    mp.cache_write_1h_price_per_mtok
-                FROM usage_records ur
-                LEFT JOIN model_pricing mp ON ur.model = mp.model_name
-                WHERE ur.model IS NOT NULL
-                GROUP BY ur.model
-            """)
+@_retry_on_lock
+def save_sessions(sessions: list[Session], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Store session metadata derived during ingestion.
 
-            for row in cursor.fetchall():
-                model = row[0]
-                input_tokens = row[1] or 0
-                output_tokens = row[2] or 0
-                cache_write_tokens = row[3] or 0
-                cache_read_tokens = row[4] or 0
-                cache_write_1h_tokens = row[9] or 0
-
-                # Pricing per million tokens; 1h cache writes bill at 2x base
-                # input (vs 1.25x for the 5m tier)
-                input_price = row[5] or 0.0
-                output_price = row[6] or 0.0
-                cache_write_price = row[7] or 0.0
-                cache_read_price = row[8] or 0.0
-                cache_write_1h_price = row[10] if row[10] is not None else cache_write_price * 1.6
-
-                # Calculate cost in dollars
-                model_cost = (
-                    (input_tokens / 1_000_000) * input_price +
-                    (output_tokens / 1_000_000) * output_price +
-                    ((cache_write_tokens - cache_write_1h_tokens) / 1_000_000) * cache_write_price +
-                    (cache_write_1h_tokens / 1_000_000) * cache_write_1h_price +
-                    (cache_read_tokens / 1_000_000) * cache_read_price
-                )
+    Args:
+        sessions: Sessions from build_sessions
+        db_path: Path to the SQLite database file
 
-                cost_by_model[model] = model_cost
-                total_cost += model_cost
+    Returns:
+        Number of sessions written
+    """
+    from src.storage.duckdb_backend import _write_sessions
 
-        # Calculate averages
-        avg_tokens_per_session = total_tokens / total_sessions if total_sessions > 0 else 0
-        avg_tokens_per_response = total_tokens / total_responses if total_responses > 0 else 0
-        avg_cost_per_session = total_cost / total_sessions if total_sessions > 0 else 0
-        avg_cost_per_response = total_cost / total_responses if total_responses > 0 else 0
+    if not sessions:
+        return 0
 
-        return {
-            "total_records": total_records,
-            "total_days": total_days,
-            "oldest_date": oldest_date,
-            "newest_date": newest_date,
-            "newest_timestamp": newest_timestamp,
-            "total_tokens": total_tokens,
-            "total_prompts": total_prompts,
-            "total_responses": total_responses,
-            "total_sessions": total_sessions,
-            "tokens_by_model": tokens_by_model,
-            "cost_by_model": cost_by_model,
-            "total_cost": total_cost,
-            "avg_tokens_per_session": round(avg_tokens_per_session),
-            "avg_tokens_per_response": round(avg_tokens_per_response),
-            "avg_cost_per_session": round(avg_cost_per_session, 2),
-            "avg_cost_per_response": round(avg_cost_per_response, 4),
-        }
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        _write_sessions(conn.cursor(), sessions)
+        conn.commit()
+        return len(sessions)
     finally:
         conn.close()
 
 
-def get_stale_files(
-    all_files: list[Path],
-    db_path: Path = DEFAULT_DB_PATH
-) -> tuple[list[Path], list[str]]:
+def get_sessions(session_ids: list[str] | None = None, db_path: Path = DEFAULT_DB_PATH) -> list[Session]:
     """
-    Identify which JSONL files need to be re-parsed based on mtime/size changes.
-
-    Compares current file stats against stored metadata to find:
-    - New files (not in database)
-    - Modified files (mtime or size changed)
-    - Deleted files (in database but not on disk)
+    Stored session metadata, newest first.
 
     Args:
-        all_files: List of all JSONL file paths on disk
+        session_ids: Only these sessions, or None for all
         db_path: Path to the SQLite database file
-
-    Returns:
-        Tuple of (stale_files, deleted_file_paths):
-        - stale_files: List of Path objects that need re-parsing
-        - deleted_file_paths: List of file path strings that were deleted
     """
+    from src.storage.duckdb_backend import _read_sessions
+
     if not db_path.exists():
-        # No database yet, all files are stale
-        return (all_files, [])
+        return []
 
     init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_sessions(conn.cursor(), session_ids)
+    finally:
+        conn.close()
 
-    conn = sqlite3.connect(db_path)
-    stale_files = []
-    deleted_files = []
 
+@_retry_on_lock
+def save_session_summaries(summaries: list[TranscriptSummary], db_path: Path = DEFAULT_DB_PATH) -> int:
+    """
+    Store session summary entries, the latest replacing earlier ones.
+
+    Args:
+        summaries: Entries collected while parsing transcripts, oldest first
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Number of summaries written
+    """
+    from src.storage.duckdb_backend import _write_session_summaries
+
+    if not summaries:
+        return 0
+
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        cursor = conn.cursor()
+        _write_session_summaries(conn.cursor(), summaries)
+        conn.commit()
+        return len(summaries)
+    finally:
+        conn.close()
 
-        # Get all stored file metadata
-        cursor.execute("SELECT file_path, mtime_ns, size_bytes FROM file_metadata")
-        stored_metadata = {row[0]: (row[1], row[2]) for row in cursor.fetchall()}
 
-        # Convert current files to set of path strings for comparison
-        current_file_paths = {str(f) for f in all_files}
+def get_session_summaries(
+    session_ids: list[str] | None = None,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> dict[str, str]:
+    """
+    Stored session summaries.
 
-        # Check each current file
-        for file_path in all_files:
-            path_str = str(file_path)
-            try:
-                stat = file_path.stat()
-                current_mtime_ns = stat.st_mtime_ns
-                current_size = stat.st_size
+    Args:
+        session_ids: Only these sessions, or None for all
+        db_path: Path to the SQLite database file
 
-                if path_str not in stored_metadata:
-                    # New file
-                    stale_files.append(file_path)
-                else:
-                    stored_mtime_ns, stored_size = stored_metadata[path_str]
-                    if current_mtime_ns != stored_mtime_ns or current_size != stored_size:
-                        # Modified file
-                        stale_files.append(file_path)
-            except OSError:
-                # File inaccessible, skip
-                continue
+    Returns:
+        Mapping of session id to summary
+    """
+    from src.storage.duckdb_backend import _read_session_summaries
 
-        # Find deleted files (in DB but not on disk)
-        for stored_path in stored_metadata.keys():
-            if stored_path not in current_file_paths:
-                deleted_files.append(stored_path)
+    if not db_path.exists():
+        return {}
 
-        return (stale_files, deleted_files)
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_session_summaries(conn.cursor(), session_ids)
     finally:
         conn.close()
 
 
-def update_files_metadata(
-    file_paths: list[Path],
-    record_count: int = 0,
+def get_mcp_calls(
+    start_date: str | None = None,
+    end_date: str | None = None,
     db_path: Path = DEFAULT_DB_PATH,
-    stats: dict[str, tuple[int, int]] | None = None,
-) -> None:
+) -> list[dict]:
     """
-    Upsert parse metadata for many files in a single connection.
-
-    Files that fail to stat are skipped.
+    MCP tool calls in a date window.
 
     Args:
-        file_paths: Paths to the JSONL files
-        record_count: Number of records parsed from each file
+        start_date: First date (YYYY-MM-DD), or None for all history
+        end_date: Last date (YYYY-MM-DD), or None for up to today
         db_path: Path to the SQLite database file
+
+    Returns:
+        List of dicts with server, tool, session_id, date, call_tokens,
+        and result_chars
     """
-    if not file_paths:
-        return
+    from src.storage.duckdb_backend import _read_mcp_calls
+
+    if not db_path.exists():
+        return []
 
     init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        return _read_mcp_calls(conn.cursor(), start_date, end_date)
+    finally:
+        conn.close()
 
-    timestamp = datetime.now().isoformat()
-    rows = []
-    for file_path in file_paths:
-        pre = (stats or {}).get(str(file_path))
-        if pre is not None:
-            mtime_ns, size_bytes = pre
-        else:
-            try:
-                stat = file_path.stat()
-            except OSError:
-                continue
-            mtime_ns, size_bytes = stat.st_mtime_ns, stat.st_size
-        rows.append((str(file_path), mtime_ns, size_bytes, record_count, timestamp))
 
+@_retry_on_lock
+def save_message_content(
+    records: list[UsageRecord],
+    max_record_bytes: int,
+    max_total_bytes: int,
+    db_path: Path = DEFAULT_DB_PATH,
+) -> int:
+    """
+    Store message text for later search, enforcing the size caps.
+
+    Args:
+        records: Parsed records; ones without content are skipped
+        max_record_bytes: UTF-8 bytes kept per message (rest truncated)
+        max_total_bytes: Table size cap; oldest messages are evicted first
+        db_path: Path to the SQLite database file
+
+    Returns:
+        Number of messages evicted to stay under the total cap
+    """
+    from src.storage.duckdb_backend import (
+        _CONTENT_UPSERT_SQL,
+        _content_rows,
+        _enforce_content_cap,
+    )
+
+    rows = _content_rows(records, max_record_bytes)
     if not rows:
-        return
+        return 0
 
-    conn = sqlite3.connect(db_path)
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        conn.executemany("""
-            INSERT OR REPLACE INTO file_metadata (
-                file_path, mtime_ns, size_bytes, record_count, last_parsed
-            ) VALUES (?, ?, ?, ?, ?)
-        """, rows)
+        cursor = conn.cursor()
+        cursor.executemany(_CONTENT_UPSERT_SQL, rows)
+        evicted = _enforce_content_cap(cursor, max_total_bytes)
         conn.commit()
+        return evicted
     finally:
         conn.close()
 
 
-def remove_deleted_file_metadata(
-    deleted_paths: list[str],
-    db_path: Path = DEFAULT_DB_PATH
-) -> None:
+def get_file_metadata_count(db_path: Path = DEFAULT_DB_PATH) -> int:
     """
-    Remove metadata for files that no longer exist.
+    Get the count of files tracked in file_metadata table.
 
     Args:
-        deleted_paths: List of file path strings to remove
         db_path: Path to the SQLite database file
-    """
-    if not deleted_paths:
-        return
 
+    Returns:
+        Number of files tracked
+    """
     if not db_path.exists():
-        return
+        return 0
 
-    conn = sqlite3.connect(db_path)
+    conn = _connect(db_path)
 
     try:
         cursor = conn.cursor()
+        cursor.execute("SELECT COUNT(*) FROM file_metadata")
+        return cursor.fetchone()[0]
+    except encryption.driver_errors("OperationalError"):
+        # Table doesn't exist yet
+        return 0
+    finally:
+        conn.close()
 
-        for path in deleted_paths:
-            cursor.execute("DELETE FROM file_metadata WHERE file_path = ?", (path,))
 
+@_retry_on_lock
+def recompute_daily_snapshots(
+    dates: list[str],
+    db_path: Path = DEFAULT_DB_PATH,
+    device_id: str | None = None,
+    device_name: str | None = None,
+    device_type: str | None = None,
+) -> None:
+    """
+    Recompute daily_snapshots (with model mix and hours) for the given dates from usage_records.
+
+    Dates left with zero usage_records rows have their snapshot row removed.
+
+    Args:
+        dates: Dates (YYYY-MM-DD) to recompute
+        db_path: Path to the SQLite database file
+        device_id: Device stamped on the recomputed rows
+        device_name: Human-readable device name
+        device_type: Device type (macos, windows, linux)
+    """
+    from src.storage.duckdb_backend import _recompute_daily_snapshots
+
+    if not dates:
+        return
+    init_database(db_path)
+    conn = _connect(db_path)
+    try:
+        _recompute_daily_snapshots(conn.cursor(), dates, device_id, device_name, device_type)
         conn.commit()
     finally:
         conn.close()
 
 
-def get_update_coverage(db_path: Path = DEFAULT_DB_PATH) -> dict:
+def get_record_dates(db_path: Path = DEFAULT_DB_PATH) -> list[str]:
     """
-    Get record count and date bounds of usage_records.
+    Dates that have individual usage records.
 
-    Cheap alternative to get_database_stats for flows that only need
-    coverage, not token/cost aggregates.
+    Args:
+        db_path: Path to the SQLite database file
 
     Returns:
-        Dict with total_records, oldest_date, newest_date
+        Dates (YYYY-MM-DD), oldest first; empty in aggregate storage mode
     """
+    from src.storage.duckdb_backend import _read_record_dates
+
     if not db_path.exists():
-        return {"total_records": 0, "oldest_date": None, "newest_date": None}
+        return []
 
-    conn = sqlite3.connect(db_path)
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        row = conn.execute(
-            "SELECT COUNT(*), MIN(date), MAX(date) FROM usage_records"
-        ).fetchone()
-        return {"total_records": row[0] or 0, "oldest_date": row[1], "newest_date": row[2]}
+        return _read_record_dates(conn.cursor())
     finally:
         conn.close()
 
 
-def get_file_metadata_count(db_path: Path = DEFAULT_DB_PATH) -> int:
+def verify_daily_snapshots(db_path: Path = DEFAULT_DB_PATH) -> list[dict]:
     """
-    Get the count of files tracked in file_metadata table.
+    Dates whose daily snapshot disagrees with their usage records.
 
     Args:
         db_path: Path to the SQLite database file
 
     Returns:
-        Number of files tracked
+        Dicts with date, records (prompts, responses, sessions, total_tokens
+        from usage_records), and snapshot (the stored row's, or None)
     """
-    if not db_path.exists():
-        return 0
+    from src.storage.duckdb_backend import _read_snapshot_mismatches
 
-    conn = sqlite3.connect(db_path)
+    if not db_path.exists():
+        return []
 
+    init_database(db_path)
+    conn = _connect(db_path)
     try:
-        cursor = conn.cursor()
-        cursor.execute("SELECT COUNT(*) FROM file_metadata")
-        return cursor.fetchone()[0]
-    except sqlite3.OperationalError:
-        # Table doesn't exist yet
-        return 0
+        return _read_snapshot_mismatches(conn.cursor())
     finally:
         conn.close()
 
 
+@_retry_on_lock
 def fill_empty_daily_snapshots(
     start_date: str,
     end_date: str,
@@ -1246,7 +3350,7 @@ def fill_empty_daily_snapshots(
     from datetime import timedelta as _td
 
     init_database(db_path)
-    conn = sqlite3.connect(db_path)
+    conn = _connect(db_path)
     try:
         cursor = conn.cursor()
         cursor.execute("SELECT date FROM daily_snapshots")
@@ -1275,4 +3379,25 @@ def fill_empty_daily_snapshots(
         return inserted
     finally:
         conn.close()
+
+
+@_retry_on_lock
+def checkpoint_database(db_path: Path = DEFAULT_DB_PATH) -> None:
+    """
+    Fold the write-ahead log into the database file.
+
+    Run before copying the .db file on its own (backups), so the copy holds
+    every commit even while another process keeps the log open.
+
+    Args:
+        db_path: Path to the SQLite database file
+    """
+    if not db_path.exists():
+        return
+
+    conn = _connect(db_path)
+    try:
+        conn.execute("PRAGMA wal_checkpoint(TRUNCATE)")
+    finally:
+        conn.close()
 #endregion
@@ -7,7 +7,7 @@ and managing Claude Code utilities.
 
 from __future__ import annotations
 
-from datetime import datetime
+from datetime import datetime, timedelta
 from typing import TYPE_CHECKING
 
 try:
@@ -31,6 +31,9 @@ EMBER = "#ff6600"
 SAGE = "#88aa77"
 SLATE = "#667788"
 
+# Weeks of history the activity heatmap shows
+HEATMAP_WEEKS = 12
+
 
 CSS = """
 Screen {
@@ -195,7 +198,7 @@ if TEXTUAL_AVAILABLE:
         def __init__(self) -> None:
             super().__init__()
             self.stats: dict = {}
-            self.records: list = []
+            self.daily_tokens: dict[str, int] = {}
 
         def compose(self) -> ComposeResult:
             yield Header()
@@ -223,15 +226,33 @@ if TEXTUAL_AVAILABLE:
 
         def on_mount(self) -> None:
             """Load data when app starts."""
+            from src.storage.refresh_marker import MARKER_POLL_INTERVAL, read_refresh_marker, refresh_marker_path
+
+            self._marker_path = refresh_marker_path()
+            self._marker = read_refresh_marker(self._marker_path)
             self.load_data()
+            self.set_interval(MARKER_POLL_INTERVAL, self.check_refresh_marker)
+
+        def check_refresh_marker(self) -> None:
+            """Reload when `ccg update usage` signals new data."""
+            from src.storage.refresh_marker import read_refresh_marker
+
+            marker = read_refresh_marker(self._marker_path)
+            if marker != self._marker:
+                self._marker = marker
+                self.load_data()
 
         def load_data(self) -> None:
             """Load usage data from database."""
             try:
-                from src.storage.snapshot_db import get_database_stats, load_historical_records
+                from src.storage.snapshot_db import get_database_stats, load_daily_snapshots
 
                 self.stats = get_database_stats()
-                self.records = load_historical_records()
+                # The heatmap only needs per-day totals for its weeks
+                since = datetime.now().date() - timedelta(weeks=HEATMAP_WEEKS + 1)
+                self.daily_tokens = {
+                    day["date"]: day["total_tokens"] for day in load_daily_snapshots(start_date=since.isoformat())
+                }
 
                 self.update_kpis()
                 self.update_activity()
@@ -257,19 +278,7 @@ if TEXTUAL_AVAILABLE:
             """Update activity heatmap."""
             content = self.query_one("#activity-content", Static)
 
-            if not self.records:
-                content.update("No activity data available")
-                return
-
-            # Build simple text-based heatmap
-            from collections import defaultdict
-            from datetime import timedelta
-
-            daily_tokens: dict[str, int] = defaultdict(int)
-            for record in self.records:
-                if record.token_usage:
-                    daily_tokens[record.date_key] += record.token_usage.total_tokens
-
+            daily_tokens = {date: tokens for date, tokens in self.daily_tokens.items() if tokens}
             if not daily_tokens:
                 content.update("No token data available")
                 return
@@ -277,14 +286,14 @@ if TEXTUAL_AVAILABLE:
             today = datetime.now().date()
             max_tokens = max(daily_tokens.values())
 
-            # Build last 12 weeks
+            # Build last HEATMAP_WEEKS weeks
             lines = []
-            lines.append("Activity Heatmap (last 12 weeks)")
+            lines.append(f"Activity Heatmap (last {HEATMAP_WEEKS} weeks)")
             lines.append("")
 
             heat_chars = [".", ":", "*", "#", "@"]
 
-            for week_offset in range(12, -1, -1):
+            for week_offset in range(HEATMAP_WEEKS, -1, -1):
                 week_start = today - timedelta(days=today.weekday() + 7 * week_offset)
                 week_line = ""
                 for day in range(7):
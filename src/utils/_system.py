@@ -1,14 +1,40 @@
 #region Imports
+import os
 import platform
 import re
+import stat
 import subprocess
+import tempfile
+from collections.abc import Iterator
+from contextlib import contextmanager
 from pathlib import Path
+from typing import IO
 
 #endregion
 
 #region Constants
 # Valid sound name pattern: alphanumeric, spaces, hyphens, underscores only
 VALID_SOUND_NAME_PATTERN = re.compile(r'^[a-zA-Z0-9\s_-]+$')
+
+MACOS_SOUND_DIR = Path("/System/Library/Sounds")
+
+# Windows sound names -> files in C:\Windows\Media; only these are playable
+WINDOWS_MEDIA_DIR = Path("C:\\Windows\\Media")
+WINDOWS_SOUNDS = {
+    "Windows Notify": "Windows Notify System Generic.wav",
+    "Windows Ding": "Windows Ding.wav",
+    "chimes": "chimes.wav",
+    "chord": "chord.wav",
+    "notify": "notify.wav",
+    "tada": "tada.wav",
+    "Windows Background": "Windows Background.wav",
+}
+
+# Linux sound directories, in the order the play command tries them
+LINUX_SOUND_DIRS = (
+    (Path("/usr/share/sounds/freedesktop/stereo"), ".oga"),
+    (Path("/usr/share/sounds/alsa"), ".wav"),
+)
 #endregion
 
 
@@ -34,6 +60,79 @@ def open_file(file_path: Path) -> None:
         pass  # Silently fail if opening doesn't work
 
 
+@contextmanager
+def atomic_write(path: Path, binary: bool = False, newline: str | None = None) -> Iterator[IO]:
+    """
+    Open a file for writing so readers only ever see a complete version.
+
+    Writes go to a temporary file in the same directory, which is fsynced
+    and renamed over the target once the block exits cleanly. Until then the
+    previous file stays in place; if the block raises (or the process dies)
+    the target is untouched. A symlinked target is written through the link,
+    and an existing file keeps its permissions.
+
+    Args:
+        path: File to write
+        binary: Open in binary mode instead of UTF-8 text
+        newline: Text-mode newline handling, as for open() ("" for csv)
+
+    Yields:
+        Writable file object for the temporary file
+    """
+    path = Path(path)
+    if path.is_symlink():
+        path = path.resolve()
+    path.parent.mkdir(parents=True, exist_ok=True)
+    fd, tmp_name = tempfile.mkstemp(dir=path.parent, prefix=f".{path.name}.", suffix=".tmp")
+    tmp_path = Path(tmp_name)
+    try:
+        text_kwargs = {"encoding": "utf-8", "newline": newline}
+        with os.fdopen(fd, "wb" if binary else "w", **({} if binary else text_kwargs)) as f:
+            yield f
+            f.flush()
+            os.fsync(f.fileno())
+        try:
+            mode = stat.S_IMODE(path.stat().st_mode)
+        except OSError:
+            umask = os.umask(0)
+            os.umask(umask)
+            mode = 0o666 & ~umask
+        os.chmod(tmp_path, mode)
+        os.replace(tmp_path, path)
+    except BaseException:
+        tmp_path.unlink(missing_ok=True)
+        raise
+    _fsync_directory(path.parent)
+
+
+def atomic_write_text(path: Path, text: str) -> None:
+    """
+    Write a text file atomically (see atomic_write).
+
+    Args:
+        path: File to write
+        text: Content, written as UTF-8
+    """
+    with atomic_write(path) as f:
+        f.write(text)
+
+
+def _fsync_directory(directory: Path) -> None:
+    """Persist a rename on POSIX; directories can't be opened on Windows."""
+    if os.name != "posix":
+        return
+    try:
+        fd = os.open(directory, os.O_RDONLY)
+    except OSError:
+        return
+    try:
+        os.fsync(fd)
+    except OSError:
+        pass
+    finally:
+        os.close(fd)
+
+
 def get_sound_command(sound_name: str) -> str | None:
     """
     Get the command to play a sound (cross-platform).
@@ -55,29 +154,19 @@ def get_sound_command(sound_name: str) -> str | None:
     system = platform.system()
 
     if system == "Darwin":  # macOS
-        sound_path = Path(f"/System/Library/Sounds/{sound_name}.aiff")
+        sound_path = MACOS_SOUND_DIR / f"{sound_name}.aiff"
         if not sound_path.exists():
             return None
         return f"afplay /System/Library/Sounds/{sound_name}.aiff &"
 
     elif system == "Windows":
-        # Map sound names to Windows Media files
-        windows_sounds = {
-            "Windows Notify": "Windows Notify System Generic.wav",
-            "Windows Ding": "Windows Ding.wav",
-            "chimes": "chimes.wav",
-            "chord": "chord.wav",
-            "notify": "notify.wav",
-            "tada": "tada.wav",
-            "Windows Background": "Windows Background.wav",
-        }
         # Only allow mapped sound names on Windows (no arbitrary file access)
-        if sound_name not in windows_sounds:
+        if sound_name not in WINDOWS_SOUNDS:
             return None
-        sound_file = windows_sounds[sound_name]
+        sound_file = WINDOWS_SOUNDS[sound_name]
 
         # Validate sound file exists
-        sound_path = Path(f"C:\\Windows\\Media\\{sound_file}")
+        sound_path = WINDOWS_MEDIA_DIR / sound_file
         if not sound_path.exists():
             return None
 
@@ -90,4 +179,43 @@ def get_sound_command(sound_name: str) -> str | None:
         return f"(paplay /usr/share/sounds/freedesktop/stereo/{sound_name}.oga 2>/dev/null || aplay /usr/share/sounds/alsa/{sound_name}.wav 2>/dev/null) &"
 
 
+def list_system_sounds(system: str | None = None) -> list[str]:
+    """
+    Sound names get_sound_command can play on this machine.
+
+    Args:
+        system: platform.system() value to list for (default: this platform)
+
+    Returns:
+        Sorted sound names: .aiff files in /System/Library/Sounds (macOS),
+        the mapped Windows Media files that exist (Windows), or the
+        freedesktop and ALSA sound files (Linux)
+    """
+    system = system or platform.system()
+    if system == "Darwin":
+        names = {path.stem for path in MACOS_SOUND_DIR.glob("*.aiff")}
+    elif system == "Windows":
+        names = {name for name, sound_file in WINDOWS_SOUNDS.items() if (WINDOWS_MEDIA_DIR / sound_file).exists()}
+    else:
+        names = {path.stem for directory, suffix in LINUX_SOUND_DIRS for path in directory.glob(f"*{suffix}")}
+    return sorted((name for name in names if VALID_SOUND_NAME_PATTERN.match(name)), key=str.lower)
+
+
+def play_sound(sound_name: str) -> bool:
+    """
+    Play a sound in the background, as the audio hook would.
+
+    Args:
+        sound_name: Name from list_system_sounds
+
+    Returns:
+        False if the sound can't be played on this platform
+    """
+    command = get_sound_command(sound_name)
+    if command is None:
+        return False
+    subprocess.run(command, shell=True, check=False, stdout=subprocess.DEVNULL, stderr=subprocess.DEVNULL)
+    return True
+
+
 #endregion
@@ -0,0 +1,40 @@
+#region Imports
+import re
+
+#endregion
+
+
+#region Constants
+# Claude's encoded project folder names ("-home-me-api", "C--work-api"),
+# which aggregate storage mode records in place of paths
+ENCODED_FOLDER = re.compile(r"(-|[A-Za-z]--)[A-Za-z0-9-]*")
+
+#endregion
+
+
+#region Functions
+
+
+def short_folder(folder: str) -> str:
+    """
+    Last two segments of a project folder, for tables and tooltips.
+
+    Windows separators are normalized, so "C:\\work\\acme\\api" and
+    "/home/me/acme/api" both read "acme/api". Encoded folder names read
+    the same way ("-home-me-acme-api" is "acme/api"), though a dash inside
+    a folder name splits it there too. Paths of two segments or fewer are
+    returned unchanged.
+
+    Args:
+        folder: Project folder path, or Claude's encoded folder name
+
+    Returns:
+        Shortened folder name
+    """
+    if ENCODED_FOLDER.fullmatch(folder):
+        return "/".join([part for part in folder.split("-") if part][-2:]) or folder
+    parts = folder.replace("\\", "/").rstrip("/").split("/")
+    return "/".join(parts[-2:]) if len(parts) > 2 else folder
+
+
+#endregion
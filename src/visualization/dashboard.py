@@ -8,7 +8,13 @@ from rich.table import Table
 from rich.text import Text
 
 from src.aggregation.daily_stats import AggregatedStats
+from src.aggregation.event_stats import ChronicProject
+from src.aggregation.model_stats import ModelDay
+from src.aggregation.project_stats import ProjectBudget, aggregate_by_project
+from src.aggregation.recap import activity_streaks
+from src.aggregation.session_stats import summarize_sessions
 from src.models.usage_record import UsageRecord
+from src.storage.pricing import PricingTable
 
 #endregion
 
@@ -19,6 +25,8 @@ ORANGE = "#ff8800"
 CYAN = "cyan"
 DIM = "grey50"
 BAR_WIDTH = 20
+KPI_WIDTH = 22
+RECENT_DAYS = 7
 #endregion
 
 
@@ -46,6 +54,21 @@ def _format_number(num: int) -> str:
         return f"{num:,}".replace(",", ".")
 
 
+def _format_hours(hours: float) -> str:
+    """
+    Format an active-hours estimate, or a dash when none is available.
+
+    Args:
+        hours: Estimated active hours
+
+    Returns:
+        Formatted string (e.g., "3.5h")
+    """
+    if hours <= 0:
+        return "–"
+    return f"{hours:,.1f}h"
+
+
 def _create_bar(value: int, max_value: int, width: int = BAR_WIDTH, color: str = ORANGE) -> Text:
     """
     Create a simple text bar for visualization.
@@ -69,7 +92,7 @@ def _create_bar(value: int, max_value: int, width: int = BAR_WIDTH, color: str =
     return bar
 
 
-def render_dashboard(stats: AggregatedStats, records: list[UsageRecord], console: Console, clear_screen: bool = True, date_range: str = None, fast_mode: bool = False) -> None:
+def render_dashboard(stats: AggregatedStats, records: list[UsageRecord], console: Console, clear_screen: bool = True, date_range: str = None, fast_mode: bool = False, pricing_rows: list[tuple] | None = None, chronic_projects: list[ChronicProject] | None = None, budgets: list[ProjectBudget] | None = None, model_totals: list[ModelDay] | None = None, annotations: dict[str, list[str]] | None = None) -> None:
     """
     Render a concise, modern dashboard with KPI cards and breakdowns.
 
@@ -80,21 +103,34 @@ def render_dashboard(stats: AggregatedStats, records: list[UsageRecord], console
         clear_screen: If True, clear the screen before rendering (default True)
         date_range: Optional date range string to display in footer
         fast_mode: If True, show warning that data is from last update
+        pricing_rows: Model pricing rows; when given, adds a top-sessions-by-cost
+            panel and a cost column to the project panel
+        chronic_projects: Projects that keep exhausting their context; when
+            given, adds a warning panel
+        budgets: Project budgets for this month; any over budget are
+            flagged in a warning panel
+        model_totals: Per-model tokens and cost (model_stats.totals_by_model);
+            when given, the model panel uses them and adds a cost column
+        annotations: Dated labels from ccg annotate, shown next to their
+            day in the recent-days panel
     """
     if clear_screen:
         console.clear()
 
     # Use simple text layout for narrow terminals (< 90 cols)
     if console.width < 90:
-        _render_simple_dashboard(stats, records, console, date_range, fast_mode)
+        _render_simple_dashboard(stats, records, console, date_range, fast_mode, chronic_projects, budgets)
         return
 
     # Create KPI cards
-    kpi_section = _create_kpi_section(stats.overall_totals)
+    kpi_section = _create_kpi_section(stats.overall_totals, _streaks(stats))
 
     # Create breakdowns
-    model_breakdown = _create_model_breakdown(records)
-    project_breakdown = _create_project_breakdown(records)
+    daily_breakdown = _create_daily_breakdown(stats, annotations=annotations)
+    model_breakdown = _create_model_breakdown(records, model_totals)
+    subagent_breakdown = _create_subagent_breakdown(records)
+    host_breakdown = _create_host_breakdown(records)
+    project_breakdown = _create_project_breakdown(records, pricing_rows)
 
     # Create footer with export info and date range
     footer = _create_footer(date_range, fast_mode=fast_mode)
@@ -102,14 +138,32 @@ def render_dashboard(stats: AggregatedStats, records: list[UsageRecord], console
     # Render all components
     console.print(kpi_section, end="")
     console.print()  # Blank line between sections
+    console.print(daily_breakdown, end="")
+    console.print()  # Blank line between sections
     console.print(model_breakdown, end="")
     console.print()  # Blank line between sections
+    if subagent_breakdown is not None:
+        console.print(subagent_breakdown, end="")
+        console.print()  # Blank line between sections
+    if host_breakdown is not None:
+        console.print(host_breakdown, end="")
+        console.print()  # Blank line between sections
     console.print(project_breakdown, end="")
-    console.print()  # Blank line before footer
+    console.print()  # Blank line between sections
+    if pricing_rows is not None:
+        console.print(_create_top_sessions(records, pricing_rows), end="")
+        console.print()  # Blank line before footer
+    if chronic_projects:
+        console.print(_create_context_warnings(chronic_projects), end="")
+        console.print()  # Blank line before footer
+    over_budget = [budget for budget in budgets or [] if budget.over_budget]
+    if over_budget:
+        console.print(_create_budget_warnings(over_budget), end="")
+        console.print()  # Blank line before footer
     console.print(footer)
 
 
-def _render_simple_dashboard(stats: AggregatedStats, records: list[UsageRecord], console: Console, date_range: str = None, fast_mode: bool = False) -> None:
+def _render_simple_dashboard(stats: AggregatedStats, records: list[UsageRecord], console: Console, date_range: str = None, fast_mode: bool = False, chronic_projects: list[ChronicProject] | None = None, budgets: list[ProjectBudget] | None = None) -> None:
     """
     Render a simple text-based dashboard for narrow terminals.
 
@@ -119,6 +173,8 @@ def _render_simple_dashboard(stats: AggregatedStats, records: list[UsageRecord],
         console: Rich console
         date_range: Optional date range
         fast_mode: If True, show fast mode warning
+        chronic_projects: Projects that keep exhausting their context
+        budgets: Project budgets for this month
     """
     overall = stats.overall_totals
 
@@ -129,6 +185,8 @@ def _render_simple_dashboard(stats: AggregatedStats, records: list[UsageRecord],
     console.print(f"  Tokens:   [bold {ORANGE}]{_format_number(overall.total_tokens)}[/bold {ORANGE}]")
     console.print(f"  Prompts:  [bold white]{_format_number(overall.total_prompts)}[/bold white]")
     console.print(f"  Sessions: [bold white]{_format_number(overall.total_sessions)}[/bold white]")
+    console.print(f"  Active:   [bold white]{_format_hours(overall.active_hours)}[/bold white]")
+    console.print(f"  Streak:   [bold white]{_format_streak(*_streaks(stats))}[/bold white]")
     console.print()
 
     # Model breakdown
@@ -146,6 +204,23 @@ def _render_simple_dashboard(stats: AggregatedStats, records: list[UsageRecord],
             console.print(f"  {name[:25]:<25} [{ORANGE}]{_format_number(tokens):>8}[/{ORANGE}] [{CYAN}]{pct:5.1f}%[/{CYAN}]")
         console.print()
 
+    main_tokens, subagent_tokens = _split_subagent_tokens(records)
+    if subagent_tokens:
+        pct = subagent_tokens / (main_tokens + subagent_tokens) * 100
+        console.print(
+            f"  Subagents: [{ORANGE}]{_format_number(subagent_tokens)}[/{ORANGE}] [{CYAN}]{pct:.1f}%[/{CYAN}]"
+        )
+        console.print()
+
+    host_tokens = _host_tokens(records)
+    if len(host_tokens) > 1:
+        console.print("[bold]Hosts:[/bold]")
+        total = sum(host_tokens.values())
+        for host, tokens in sorted(host_tokens.items(), key=lambda x: x[1], reverse=True)[:5]:
+            pct = (tokens / total * 100) if total > 0 else 0
+            console.print(f"  {host[:25]:<25} [{ORANGE}]{_format_number(tokens):>8}[/{ORANGE}] [{CYAN}]{pct:5.1f}%[/{CYAN}]")
+        console.print()
+
     # Project breakdown
     folder_tokens: dict[str, int] = {}
     for record in records:
@@ -163,6 +238,19 @@ def _render_simple_dashboard(stats: AggregatedStats, records: list[UsageRecord],
             console.print(f"  {name:<25} [{ORANGE}]{_format_number(tokens):>8}[/{ORANGE}] [{CYAN}]{pct:5.1f}%[/{CYAN}]")
         console.print()
 
+    for project in chronic_projects or []:
+        console.print(
+            f"[yellow]⚠ {project.folder.split('/')[-1][:25]}: "
+            f"{project.per_session:.1f} compactions/session[/yellow]"
+        )
+
+    for budget in budgets or []:
+        if budget.over_budget:
+            console.print(
+                f"[bold red]⚠ {budget.folder.split('/')[-1][:25]}: "
+                f"${budget.spent:,.2f} of ${budget.budget:,.2f} budget[/bold red]"
+            )
+
     # Footer
     if fast_mode:
         console.print("[bold red]Fast mode: data from cache[/bold red]")
@@ -171,12 +259,29 @@ def _render_simple_dashboard(stats: AggregatedStats, records: list[UsageRecord],
     console.print(f"[{DIM}]Tip: ccg export --open for heatmap[/{DIM}]")
 
 
-def _create_kpi_section(overall) -> Group:
+def _streaks(stats: AggregatedStats) -> tuple[int, int]:
+    """Current and longest streaks of active days, up to today."""
+    today = datetime.now().date().isoformat()
+    return activity_streaks(stats.daily_stats, min(stats.daily_stats, default=today), today)
+
+
+def _format_streak(current: int, longest: int) -> str:
+    """
+    Format the current streak next to the best one.
+
+    Returns:
+        Formatted string (e.g., "12d · best 40d")
+    """
+    return f"{current}d · best {longest}d"
+
+
+def _create_kpi_section(overall, streaks: tuple[int, int] = (0, 0)) -> Group:
     """
     Create KPI cards showing key metrics.
 
     Args:
         overall: Overall statistics
+        streaks: (current, longest) streak of consecutive active days
 
     Returns:
         Group containing KPI cards
@@ -185,13 +290,15 @@ def _create_kpi_section(overall) -> Group:
     kpi_grid.add_column(justify="center")
     kpi_grid.add_column(justify="center")
     kpi_grid.add_column(justify="center")
+    kpi_grid.add_column(justify="center")
+    kpi_grid.add_column(justify="center")
 
     # Total Tokens card
     tokens_card = Panel(
         Text(_format_number(overall.total_tokens), style=f"bold {ORANGE}"),
         title="Total Tokens",
         border_style="white",
-        width=28,
+        width=KPI_WIDTH,
     )
 
     # Total Prompts card
@@ -199,7 +306,7 @@ def _create_kpi_section(overall) -> Group:
         Text(_format_number(overall.total_prompts), style="bold white"),
         title="Prompts Sent",
         border_style="white",
-        width=28,
+        width=KPI_WIDTH,
     )
 
     # Total Sessions card
@@ -207,30 +314,109 @@ def _create_kpi_section(overall) -> Group:
         Text(_format_number(overall.total_sessions), style="bold white"),
         title="Active Sessions",
         border_style="white",
-        width=28,
+        width=KPI_WIDTH,
+    )
+
+    # Active Hours card (5-minute activity buckets)
+    hours_card = Panel(
+        Text(_format_hours(overall.active_hours), style="bold white"),
+        title="Active Hours",
+        border_style="white",
+        width=KPI_WIDTH,
+    )
+
+    # Streak card (consecutive active days, GitHub-contributions style)
+    streak_card = Panel(
+        Text(_format_streak(*streaks), style="bold white"),
+        title="Streak",
+        border_style="white",
+        width=KPI_WIDTH,
     )
 
-    kpi_grid.add_row(tokens_card, prompts_card, sessions_card)
+    kpi_grid.add_row(tokens_card, prompts_card, sessions_card, hours_card, streak_card)
 
     return Group(kpi_grid)
 
 
-def _create_model_breakdown(records: list[UsageRecord]) -> Panel:
+def _create_daily_breakdown(
+    stats: AggregatedStats,
+    days: int = RECENT_DAYS,
+    annotations: dict[str, list[str]] | None = None,
+) -> Panel:
+    """
+    Create table of the most recent days with tokens, prompts and active hours.
+
+    Args:
+        stats: Aggregated statistics
+        days: Number of most recent days with data to show
+        annotations: Dated labels; adds a notes column when any fall on
+            the days shown
+
+    Returns:
+        Panel with per-day table
+    """
+    recent = sorted(stats.daily_stats.values(), key=lambda d: d.date, reverse=True)[:days]
+
+    if not recent:
+        return Panel(
+            Text("No daily data available", style=DIM),
+            title="[bold]Recent Days",
+            border_style="white",
+        )
+
+    table = Table(box=None, padding=(0, 2), header_style=DIM)
+    table.add_column("Date", style="white", justify="left")
+    table.add_column("Tokens", style=ORANGE, justify="right")
+    table.add_column("Prompts", style="white", justify="right")
+    table.add_column("Active Hours", style=CYAN, justify="right")
+    annotations = annotations or {}
+    show_notes = any(day.date in annotations for day in recent)
+    if show_notes:
+        table.add_column("Notes", style=DIM, justify="left", overflow="fold")
+
+    for day in recent:
+        row = [
+            day.date,
+            _format_number(day.total_tokens),
+            _format_number(day.total_prompts),
+            _format_hours(day.active_hours),
+        ]
+        if show_notes:
+            row.append("; ".join(annotations.get(day.date, [])))
+        table.add_row(*row)
+
+    return Panel(
+        table,
+        title="[bold]Recent Days",
+        border_style="white",
+    )
+
+
+def _create_model_breakdown(records: list[UsageRecord], model_totals: list[ModelDay] | None = None) -> Panel:
     """
     Create table showing token usage per model.
 
     Args:
         records: List of usage records
+        model_totals: Per-model tokens and cost; used instead of records
+            when given, adding a cost column
 
     Returns:
         Panel with model breakdown table
     """
     # Aggregate tokens by model
     model_tokens: dict[str, int] = defaultdict(int)
+    model_costs: dict[str, float] = {}
 
-    for record in records:
-        if record.model and record.token_usage and record.model != "<synthetic>":
-            model_tokens[record.model] += record.token_usage.total_tokens
+    if model_totals is not None:
+        for total in model_totals:
+            if total.model not in ("<synthetic>", "unknown") and total.total_tokens:
+                model_tokens[total.model] += total.total_tokens
+                model_costs[total.model] = model_costs.get(total.model, 0.0) + total.cost
+    else:
+        for record in records:
+            if record.model and record.token_usage and record.model != "<synthetic>":
+                model_tokens[record.model] += record.token_usage.total_tokens
 
     if not model_tokens:
         return Panel(
@@ -252,6 +438,8 @@ def _create_model_breakdown(records: list[UsageRecord]) -> Panel:
     table.add_column("Bar", justify="left")
     table.add_column("Tokens", style=ORANGE, justify="right")
     table.add_column("Percentage", style=CYAN, justify="right")
+    if model_costs:
+        table.add_column("Cost", style="green", justify="right")
 
     for model, tokens in sorted_models:
         # Shorten model name
@@ -264,36 +452,197 @@ def _create_model_breakdown(records: list[UsageRecord]) -> Panel:
         # Create bar
         bar = _create_bar(tokens, max_tokens, width=20)
 
+        row = [display_name, bar, _format_number(tokens), f"{percentage:.1f}%"]
+        if model_costs:
+            row.append(f"${model_costs[model]:,.2f}")
+        table.add_row(*row)
+
+    return Panel(
+        table,
+        title="[bold]Tokens by Model",
+        border_style="white",
+    )
+
+
+def _split_subagent_tokens(records: list[UsageRecord]) -> tuple[int, int]:
+    """
+    Tokens from main conversations and from subagents they delegated to.
+
+    Returns:
+        (main tokens, subagent tokens)
+    """
+    main_tokens = subagent_tokens = 0
+    for record in records:
+        if not record.token_usage:
+            continue
+        if record.is_subagent:
+            subagent_tokens += record.token_usage.total_tokens
+        else:
+            main_tokens += record.token_usage.total_tokens
+    return main_tokens, subagent_tokens
+
+
+def _create_subagent_breakdown(records: list[UsageRecord]) -> Panel | None:
+    """
+    Create a panel splitting tokens between main sessions and subagents.
+
+    Args:
+        records: List of usage records
+
+    Returns:
+        Panel, or None when no subagent usage is recorded (including
+        aggregate storage mode)
+    """
+    main_tokens, subagent_tokens = _split_subagent_tokens(records)
+    if not subagent_tokens:
+        return None
+
+    total_tokens = main_tokens + subagent_tokens
+    max_tokens = max(main_tokens, subagent_tokens)
+    delegating = len({r.session_id for r in records if r.is_subagent})
+
+    table = Table(show_header=False, box=None, padding=(0, 2))
+    table.add_column("Source", style="white", justify="left", width=25)
+    table.add_column("Bar", justify="left")
+    table.add_column("Tokens", style=ORANGE, justify="right")
+    table.add_column("Percentage", style=CYAN, justify="right")
+    for label, tokens in (("Main sessions", main_tokens), ("Subagents", subagent_tokens)):
         table.add_row(
-            display_name,
-            bar,
+            label,
+            _create_bar(tokens, max_tokens, width=20),
             _format_number(tokens),
-            f"{percentage:.1f}%",
+            f"{tokens / total_tokens * 100:.1f}%",
         )
 
     return Panel(
         table,
-        title="[bold]Tokens by Model",
+        title=f"[bold]Subagent Usage[/bold] [dim]({delegating} session{'s' if delegating != 1 else ''} delegating)[/dim]",
+        border_style="white",
+    )
+
+
+def _host_tokens(records: list[UsageRecord]) -> dict[str, int]:
+    """Tokens per machine the records were ingested on ("(unknown)" when not recorded)."""
+    host_tokens: dict[str, int] = {}
+    for record in records:
+        if record.token_usage:
+            host = record.hostname or "(unknown)"
+            host_tokens[host] = host_tokens.get(host, 0) + record.token_usage.total_tokens
+    return host_tokens
+
+
+def _create_host_breakdown(records: list[UsageRecord], limit: int = 5) -> Panel | None:
+    """
+    Create a panel of token usage per machine.
+
+    Args:
+        records: List of usage records
+        limit: Hosts to show
+
+    Returns:
+        Panel, or None unless the records come from more than one machine
+        (e.g. a database merged with `ccg import db`)
+    """
+    host_tokens = _host_tokens(records)
+    if len(host_tokens) < 2:
+        return None
+
+    total_tokens = sum(host_tokens.values())
+    top = sorted(host_tokens.items(), key=lambda x: x[1], reverse=True)[:limit]
+    max_tokens = top[0][1]
+
+    table = Table(show_header=False, box=None, padding=(0, 2))
+    table.add_column("Host", style="white", justify="left", width=25)
+    table.add_column("Bar", justify="left")
+    table.add_column("Tokens", style=ORANGE, justify="right")
+    table.add_column("Percentage", style=CYAN, justify="right")
+    for host, tokens in top:
+        table.add_row(
+            host[:25],
+            _create_bar(tokens, max_tokens, width=20),
+            _format_number(tokens),
+            f"{tokens / total_tokens * 100:.1f}%" if total_tokens else "0.0%",
+        )
+
+    return Panel(
+        table,
+        title=f"[bold]Usage by Host[/bold] [dim]({len(host_tokens)} hosts)[/dim]",
+        border_style="white",
+    )
+
+
+def _create_top_sessions(records: list[UsageRecord], pricing_rows: list[tuple], limit: int = 5) -> Panel:
+    """
+    Create table of the most expensive sessions.
+
+    Args:
+        records: List of usage records
+        pricing_rows: Model pricing rows from storage.api.get_model_pricing
+        limit: Number of sessions to show
+
+    Returns:
+        Panel with top sessions table
+    """
+    # Aggregate-mode synthetic day records are not real sessions
+    summaries = [
+        s for s in summarize_sessions(records, pricing_rows)
+        if s.total_tokens > 0 and s.folder != "aggregate"
+    ]
+
+    if not summaries:
+        return Panel(
+            Text("No session data available", style=DIM),
+            title="[bold]Top Sessions by Cost",
+            border_style="white",
+        )
+
+    table = Table(show_header=False, box=None, padding=(0, 2))
+    table.add_column("Started", style=DIM, justify="left")
+    table.add_column("Project", style="white", justify="left", overflow="crop")
+    table.add_column("Tokens", style=ORANGE, justify="right")
+    table.add_column("Cost", style=CYAN, justify="right")
+
+    for summary in summaries[:limit]:
+        parts = summary.folder.split("/")
+        display_name = "/".join(parts[-2:]) if len(parts) > 2 else summary.folder
+        table.add_row(
+            summary.start.astimezone().strftime("%Y-%m-%d %H:%M"),
+            display_name[:35],
+            _format_number(summary.total_tokens),
+            f"${summary.cost:,.2f}",
+        )
+
+    return Panel(
+        table,
+        title="[bold]Top Sessions by Cost",
         border_style="white",
     )
 
 
-def _create_project_breakdown(records: list[UsageRecord]) -> Panel:
+def _create_project_breakdown(records: list[UsageRecord], pricing_rows: list[tuple] | None = None) -> Panel:
     """
     Create table showing token usage per project.
 
     Args:
         records: List of usage records
+        pricing_rows: Model pricing rows; when given, adds a cost column
 
     Returns:
         Panel with project breakdown table
     """
-    # Aggregate tokens by folder
+    # Aggregate tokens (and cost) by folder
     folder_tokens: dict[str, int] = defaultdict(int)
+    folder_costs: dict[str, float] = {}
 
-    for record in records:
-        if record.token_usage:
-            folder_tokens[record.folder] += record.token_usage.total_tokens
+    if pricing_rows is not None:
+        for project in aggregate_by_project(records, PricingTable(pricing_rows)):
+            if project.total_tokens:
+                folder_tokens[project.folder] = project.total_tokens
+                folder_costs[project.folder] = project.cost
+    else:
+        for record in records:
+            if record.token_usage:
+                folder_tokens[record.folder] += record.token_usage.total_tokens
 
     if not folder_tokens:
         return Panel(
@@ -318,6 +667,8 @@ def _create_project_breakdown(records: list[UsageRecord]) -> Panel:
     table.add_column("Bar", justify="left", overflow="crop")
     table.add_column("Tokens", style=ORANGE, justify="right")
     table.add_column("Percentage", style=CYAN, justify="right")
+    if folder_costs:
+        table.add_column("Cost", style="green", justify="right")
 
     for folder, tokens in sorted_folders:
         # Show only last 2-3 parts of path and truncate if needed
@@ -338,12 +689,10 @@ def _create_project_breakdown(records: list[UsageRecord]) -> Panel:
         # Create bar
         bar = _create_bar(tokens, max_tokens, width=20)
 
-        table.add_row(
-            display_name,
-            bar,
-            _format_number(tokens),
-            f"{percentage:.1f}%",
-        )
+        row = [display_name, bar, _format_number(tokens), f"{percentage:.1f}%"]
+        if folder_costs:
+            row.append(f"${folder_costs[folder]:,.2f}")
+        table.add_row(*row)
 
     return Panel(
         table,
@@ -352,6 +701,69 @@ def _create_project_breakdown(records: list[UsageRecord]) -> Panel:
     )
 
 
+def _create_context_warnings(chronic_projects: list[ChronicProject], limit: int = 5) -> Panel:
+    """
+    Create a warning panel for projects that keep exhausting their context.
+
+    Args:
+        chronic_projects: Projects from summarize_compactions, worst first
+        limit: Most projects listed
+
+    Returns:
+        Panel listing compactions per session for each project
+    """
+    table = Table(show_header=False, box=None, padding=(0, 2))
+    table.add_column("Project", style="white", justify="left", overflow="crop")
+    table.add_column("Per Session", style=ORANGE, justify="right")
+    table.add_column("Compactions", style=DIM, justify="right")
+
+    for project in chronic_projects[:limit]:
+        parts = project.folder.split("/")
+        display_name = ".../" + "/".join(parts[-2:]) if len(parts) > 3 else project.folder
+        table.add_row(
+            display_name[:35],
+            f"{project.per_session:.1f} / session",
+            f"{project.compactions:,} in {project.sessions:,} sessions",
+        )
+
+    return Panel(
+        table,
+        title="[bold yellow]⚠ Chronic Context Exhaustion",
+        border_style="yellow",
+    )
+
+
+def _create_budget_warnings(over_budget: list[ProjectBudget]) -> Panel:
+    """
+    Create a warning panel for projects over their monthly budget.
+
+    Args:
+        over_budget: Over-budget projects from check_project_budgets, most used first
+
+    Returns:
+        Panel listing spend against budget for each project
+    """
+    table = Table(show_header=False, box=None, padding=(0, 2))
+    table.add_column("Project", style="white", justify="left", overflow="crop")
+    table.add_column("Spent", style="bold red", justify="right")
+    table.add_column("Budget", style=DIM, justify="right")
+
+    for budget in over_budget:
+        parts = budget.folder.split("/")
+        display_name = ".../" + "/".join(parts[-2:]) if len(parts) > 3 else budget.folder
+        table.add_row(
+            display_name[:35],
+            f"${budget.spent:,.2f} ({budget.used:.0%})",
+            f"of ${budget.budget:,.2f} / month",
+        )
+
+    return Panel(
+        table,
+        title="[bold red]⚠ Over Budget This Month",
+        border_style="red",
+    )
+
+
 def _create_footer(date_range: str = None, fast_mode: bool = False) -> Text:
     """
     Create footer with export command info and date range.
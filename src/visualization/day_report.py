@@ -0,0 +1,263 @@
+"""
+Per-day detail pages for the HTML heatmap report and the live preview.
+
+Each active day of the heatmap links to a page listing that day's
+sessions, projects, and models, so the heatmap can be explored in a
+browser instead of only hovered.
+"""
+#region Imports
+from concurrent.futures import ThreadPoolExecutor
+from dataclasses import dataclass, field
+from datetime import date as date_type
+from datetime import datetime
+from html import escape
+from pathlib import Path
+
+from src.aggregation.daily_stats import AggregatedStats
+from src.models.usage_record import Session
+from src.storage import api
+from src.utils._system import atomic_write_text
+from src.visualization.export import (
+    DEFAULT_HEATMAP_METRIC,
+    DEFAULT_HEATMAP_SCALE,
+    DEFAULT_HEATMAP_THEME,
+    render_heatmap_svg,
+)
+
+#endregion
+
+
+#region Constants
+# Threads rendering and writing report files; each write fsyncs, so a
+# year of day pages is bound by disk waits rather than the GIL
+EXPORT_WORKERS = 8
+
+PAGE_STYLE = """
+  body { margin: 0 auto; max-width: 960px; padding: 24px; background: #262624; color: #FAF9F5;
+         font: 14px -apple-system, sans-serif; }
+  a { color: #CB7B5D; }
+  h1 { font-size: 20px; }
+  h2 { font-size: 16px; margin-top: 28px; color: #C2C0B7; }
+  table { border-collapse: collapse; width: 100%; }
+  th, td { text-align: left; padding: 4px 10px 4px 0; border-bottom: 1px solid #3C3C3A; }
+  td.num, th.num { text-align: right; }
+  .summary { color: #C2C0B7; }
+  svg { max-width: 100%; height: auto; }
+"""
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class DayDetails:
+    """
+    Everything a day page shows.
+
+    Attributes:
+        date: Day in YYYY-MM-DD format
+        totals: The day's daily snapshot (prompts, responses, sessions,
+            token fields, models, cost), or None if it has none
+        projects: {folder: {"tokens", "cost"}} for the day
+        sessions: Sessions active on the day (from the sessions table)
+    """
+
+    date: str
+    totals: dict | None = None
+    projects: dict[str, dict] = field(default_factory=dict)
+    sessions: list[Session] = field(default_factory=list)
+#endregion
+
+
+#region Functions
+
+
+def load_day_details(start_date: str | None = None, end_date: str | None = None) -> dict[str, DayDetails]:
+    """
+    Day details for every active day in a window.
+
+    Args:
+        start_date: First day (YYYY-MM-DD), or None for all history
+        end_date: Last day (YYYY-MM-DD), or None for up to today
+
+    Returns:
+        Mapping of date to DayDetails, for days with any tokens
+    """
+    details: dict[str, DayDetails] = {}
+    for day in api.load_daily_snapshots(start_date=start_date, end_date=end_date):
+        if day["total_tokens"]:
+            details[day["date"]] = DayDetails(date=day["date"], totals=day)
+    for date, projects in api.get_daily_projects(start_date, end_date).items():
+        details.setdefault(date, DayDetails(date=date)).projects = projects
+    if not details:
+        return details
+
+    first, last = min(details), max(details)
+    for session in api.get_sessions():
+        session_start = session.start.astimezone().strftime("%Y-%m-%d")
+        session_end = session.end.astimezone().strftime("%Y-%m-%d")
+        if session_end < first or session_start > last:
+            continue
+        for date, day in details.items():
+            if session_start <= date <= session_end:
+                day.sessions.append(session)
+    for day in details.values():
+        day.sessions.sort(key=lambda s: s.start)
+    return details
+
+
+def render_day_page(day: DayDetails, back_href: str) -> str:
+    """
+    Render one day's detail page.
+
+    Args:
+        day: The day's details from load_day_details
+        back_href: Link back to the heatmap
+
+    Returns:
+        HTML document
+    """
+    weekday = datetime.strptime(day.date, "%Y-%m-%d").strftime("%A")
+    parts = [
+        "<!doctype html>",
+        '<html>\n<head>\n<meta charset="utf-8">',
+        f"<title>Claude Code usage on {day.date}</title>",
+        f"<style>{PAGE_STYLE}</style>\n</head>\n<body>",
+        f'<p><a href="{escape(back_href)}">← Heatmap</a></p>',
+        f"<h1>{day.date} ({weekday})</h1>",
+    ]
+
+    totals = day.totals or {}
+    cost = sum(p["cost"] for p in day.projects.values()) if day.projects else totals.get("cost", 0.0)
+    tokens = totals.get("total_tokens") or sum(p["tokens"] for p in day.projects.values())
+    summary = [f"{tokens:,} tokens"]
+    if totals:
+        summary[:0] = [
+            _count(totals["prompts"], "prompt"),
+            _count(totals["responses"], "response"),
+            _count(totals["sessions"], "session"),
+        ]
+    if cost:
+        summary.append(f"${cost:,.2f} estimated API cost")
+    parts.append(f'<p class="summary">{", ".join(summary)}</p>')
+
+    if day.projects:
+        total = sum(p["tokens"] for p in day.projects.values()) or 1
+        rows = [
+            (escape(folder), f"{p['tokens']:,}", f"{p['tokens'] / total:.0%}", f"${p['cost']:,.2f}")
+            for folder, p in sorted(day.projects.items(), key=lambda item: item[1]["tokens"], reverse=True)
+        ]
+        parts.append(_table("Projects", ("Project", "Tokens", "Share", "Cost"), rows))
+
+    models = totals.get("models") or {}
+    if models:
+        rows = [
+            (escape(model), f"{m.get('responses', 0):,}", f"{m.get('total_tokens', 0):,}")
+            for model, m in sorted(models.items(), key=lambda item: item[1].get("total_tokens", 0), reverse=True)
+        ]
+        parts.append(_table("Models", ("Model", "Responses", "Tokens"), rows))
+
+    if day.sessions:
+        rows = [
+            (
+                escape(session.label),
+                f"{session.start.astimezone():%H:%M} – {session.end.astimezone():%H:%M}",
+                f"{session.messages:,}",
+                f"{session.total_tokens:,}",
+            )
+            for session in day.sessions
+        ]
+        parts.append(_table("Sessions", ("Session", "Time", "Messages", "Tokens (whole session)"), rows))
+    else:
+        parts.append('<p class="summary">No session details recorded for this day.</p>')
+
+    parts.append("</body>\n</html>\n")
+    return "\n".join(parts)
+
+
+def export_html_report(
+    stats: AggregatedStats,
+    output_path: Path,
+    year: int | None = None,
+    start_date: date_type | None = None,
+    end_date: date_type | None = None,
+    scale: str = DEFAULT_HEATMAP_SCALE,
+    theme: str = DEFAULT_HEATMAP_THEME,
+    annotations: dict[str, list[str]] | None = None,
+    metric: str = DEFAULT_HEATMAP_METRIC,
+) -> int:
+    """
+    Write the heatmap as an HTML page whose days link to detail pages.
+
+    Day pages go in a "<name>-days" folder next to output_path, so the
+    report can be moved or shared as one folder. Pages and the heatmap are
+    rendered and written on EXPORT_WORKERS threads.
+
+    Args:
+        stats: Aggregated statistics to visualize
+        output_path: Path of the index page
+        year: Year to display (defaults to current year)
+        start_date: First day of a custom window (overrides year)
+        end_date: Last day of a custom window (overrides year)
+        scale: Color intensity scale, one of HEATMAP_SCALES
+        theme: Color gradient, one of HEATMAP_THEMES
+        annotations: Milestone labels by date, marked on the heatmap
+        metric: What the heatmap colors measure, one of HEATMAP_METRICS
+
+    Returns:
+        Number of day pages written
+    """
+    if start_date is not None or end_date is not None:
+        window = (start_date.isoformat() if start_date else None, end_date.isoformat() if end_date else None)
+    else:
+        year = year or datetime.now().year
+        window = (f"{year}-01-01", f"{year}-12-31")
+    details = load_day_details(*window)
+
+    days_dir = output_path.with_name(f"{output_path.stem}-days")
+    days_dir.mkdir(parents=True, exist_ok=True)
+
+    def write_day(day: DayDetails) -> None:
+        atomic_write_text(days_dir / f"{day.date}.html", render_day_page(day, f"../{output_path.name}"))
+
+    def write_index() -> None:
+        svg = render_heatmap_svg(
+            stats, year=year, start_date=start_date, end_date=end_date,
+            day_projects={date: day.projects for date, day in details.items()},
+            day_links=f"{days_dir.name}/{{date}}.html", scale=scale, theme=theme, annotations=annotations,
+            metric=metric,
+        )
+        atomic_write_text(output_path, "\n".join([
+            "<!doctype html>",
+            '<html>\n<head>\n<meta charset="utf-8">',
+            "<title>Claude Code usage</title>",
+            f"<style>{PAGE_STYLE}</style>\n</head>\n<body>",
+            svg,
+            '<p class="summary">Click a day for its sessions, projects, and models.</p>',
+            "</body>\n</html>\n",
+        ]))
+
+    with ThreadPoolExecutor(max_workers=EXPORT_WORKERS) as pool:
+        futures = [pool.submit(write_index)] + [pool.submit(write_day, day) for day in details.values()]
+        for future in futures:
+            future.result()  # Re-raise the first failed write
+    return len(details)
+
+
+def _count(n: int, noun: str) -> str:
+    return f"{n:,} {noun}{'s' if n != 1 else ''}"
+
+
+def _table(heading: str, columns: tuple[str, ...], rows: list[tuple[str, ...]]) -> str:
+    """HTML section with a heading and a table; columns after the first are numeric."""
+    num = ' class="num"'
+    head = "".join(f"<th{num if i else ''}>{escape(col)}</th>" for i, col in enumerate(columns))
+    body = "".join(
+        "<tr>" + "".join(f"<td{num if i else ''}>{cell}</td>" for i, cell in enumerate(row)) + "</tr>"
+        for row in rows
+    )
+    return f"<h2>{escape(heading)}</h2>\n<table><tr>{head}</tr>{body}</table>"
+
+
+#endregion
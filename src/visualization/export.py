@@ -1,9 +1,16 @@
 #region Imports
+import math
+from bisect import bisect_right
+from dataclasses import dataclass
 from datetime import date as date_type
 from datetime import datetime, timedelta
 from pathlib import Path
+from xml.sax.saxutils import escape
 
 from src.aggregation.daily_stats import AggregatedStats, DailyStats
+from src.aggregation.recap import activity_streaks
+from src.utils._system import atomic_write, atomic_write_text
+from src.utils.folders import short_folder
 
 #endregion
 
@@ -22,6 +29,103 @@ SCALE_FACTOR = 3  # 3x resolution
 CELL_SIZE = 12 * SCALE_FACTOR
 CELL_GAP = 3 * SCALE_FACTOR
 CELL_TOTAL = CELL_SIZE + CELL_GAP
+
+# Canvas floors so short --from/--to windows don't clip the title
+MIN_SVG_WIDTH = 560
+MIN_PNG_GRID_WEEKS = 30
+
+# Colored legend swatches, each labeled with its daily upper bound
+LEGEND_BUCKETS = 4
+
+# How daily tokens map to color intensity (--scale)
+HEATMAP_SCALES = ("linear", "sqrt", "log", "percentile")
+DEFAULT_HEATMAP_SCALE = "sqrt"
+
+# Gradient stops for active days, lowest to highest intensity (--theme).
+# viridis and cividis are perceptually uniform and colorblind-safe
+HEATMAP_THEMES: dict[str, tuple[tuple[int, int, int], ...]] = {
+    "claude": ((60, 60, 58), CLAUDE_ORANGE_RGB),  # CLAUDE_DARK_GREY to orange
+    "viridis": ((68, 1, 84), (59, 82, 139), (33, 145, 140), (94, 201, 98), (253, 231, 37)),
+    "cividis": ((0, 32, 77), (65, 77, 107), (124, 123, 120), (188, 175, 111), (255, 234, 70)),
+}
+DEFAULT_HEATMAP_THEME = "claude"
+
+# What a day's color measures (--metric), with its heatmap title and legend label
+HEATMAP_METRICS: dict[str, tuple[str, str]] = {
+    "tokens": ("Token Usage", "Tokens/day"),
+    "prompts": ("Prompts", "Prompts/day"),
+}
+DEFAULT_HEATMAP_METRIC = "tokens"
+
+# Annotation markers: a dot in the cell's top-right corner, plus one note
+# line per annotation under the legend
+ANNOTATION_MARKER_RADIUS = 2 * SCALE_FACTOR
+MAX_ANNOTATION_NOTES = 8
+SVG_NOTE_LINE_HEIGHT = 16
+ANNOTATION_NOTE_CHARS = 80
+#endregion
+
+
+#region Data Classes
+
+
+@dataclass
+class ColorScale:
+    """
+    Maps daily token (or prompt) counts to a 0-1 color intensity, and that to a color.
+
+    "linear" is proportional to the busiest day, "sqrt" (default) and
+    "log" compress the top so quieter days stay visible, and "percentile"
+    ranks each day among the active days shown, so images from different
+    years use their full color range the same way.
+
+    Attributes:
+        kind: One of HEATMAP_SCALES
+        values: Sorted metric counts of the active days being scaled against
+        theme: Gradient the intensity picks from, one of HEATMAP_THEMES
+        metric: What the counts measure, one of HEATMAP_METRICS
+    """
+
+    kind: str
+    values: list[int]
+    theme: str = DEFAULT_HEATMAP_THEME
+    metric: str = DEFAULT_HEATMAP_METRIC
+
+    @property
+    def max_tokens(self) -> int:
+        return self.values[-1] if self.values else 1
+
+    def ratio(self, tokens: int) -> float:
+        """Color intensity for a day's tokens (0.0 to 1.0)."""
+        if tokens <= 0:
+            return 0.0
+        peak = self.max_tokens
+        if self.kind == "linear":
+            ratio = tokens / peak
+        elif self.kind == "log":
+            ratio = math.log1p(tokens) / math.log1p(peak)
+        elif self.kind == "percentile":
+            ratio = bisect_right(self.values, tokens) / len(self.values) if self.values else 1.0
+        else:
+            ratio = (tokens / peak) ** 0.5
+        return min(ratio, 1.0)
+
+    def bound(self, ratio: float) -> int:
+        """Most tokens a day can have and still sit at or below an intensity."""
+        peak = self.max_tokens
+        if self.kind == "linear":
+            return round(peak * ratio)
+        if self.kind == "log":
+            return round(math.expm1(ratio * math.log1p(peak)))
+        if self.kind == "percentile":
+            if not self.values:
+                return peak
+            return self.values[max(math.ceil(ratio * len(self.values)) - 1, 0)]
+        return round(peak * ratio ** 2)
+
+    def value(self, day_stats: DailyStats) -> int:
+        """A day's count of the scaled metric."""
+        return day_stats.total_prompts if self.metric == "prompts" else day_stats.total_tokens
 #endregion
 
 
@@ -32,7 +136,14 @@ def export_heatmap_svg(
     stats: AggregatedStats,
     output_path: Path,
     title: str | None = None,
-    year: int | None = None
+    year: int | None = None,
+    start_date: date_type | None = None,
+    end_date: date_type | None = None,
+    day_projects: dict[str, dict[str, dict]] | None = None,
+    scale: str = DEFAULT_HEATMAP_SCALE,
+    theme: str = DEFAULT_HEATMAP_THEME,
+    annotations: dict[str, list[str]] | None = None,
+    metric: str = DEFAULT_HEATMAP_METRIC,
 ) -> None:
     """
     Export the activity heatmap as an SVG file.
@@ -42,60 +153,81 @@ def export_heatmap_svg(
         output_path: Path where SVG file will be saved
         title: Optional title for the graph
         year: Year to display (defaults to current year)
+        start_date: First day of a custom window (overrides year)
+        end_date: Last day of a custom window (overrides year)
+        day_projects: Per-day project usage for tooltips (see render_heatmap_svg)
+        scale: Color intensity scale, one of HEATMAP_SCALES
+        theme: Color gradient, one of HEATMAP_THEMES
+        annotations: Milestone labels by date, marked on their days
+        metric: What the colors measure, one of HEATMAP_METRICS
 
     Raises:
         IOError: If file cannot be written
     """
-    # Show full year: Jan 1 to Dec 31
-    today = datetime.now().date()
-    display_year = year if year is not None else today.year
-    start_date = datetime(display_year, 1, 1).date()
-    end_date = datetime(display_year, 12, 31).date()
+    svg = render_heatmap_svg(
+        stats, title, year, start_date, end_date, day_projects, scale=scale, theme=theme, annotations=annotations,
+        metric=metric,
+    )
+    atomic_write_text(output_path, svg)
 
-    # Build weeks structure
-    jan1_day = (start_date.weekday() + 1) % 7
-    weeks: list[list[tuple[DailyStats | None, date_type | None]]] = []
-    current_week: list[tuple[DailyStats | None, date_type | None]] = []
-
-    # Pad first week with None
-    for _ in range(jan1_day):
-        current_week.append((None, None))
-
-    # Add all days from Jan 1 to Dec 31
-    current_date = start_date
-    while current_date <= end_date:
-        date_key = current_date.strftime("%Y-%m-%d")
-        day_stats = stats.daily_stats.get(date_key)
-        current_week.append((day_stats, current_date))
 
-        if len(current_week) == 7:
-            weeks.append(current_week)
-            current_week = []
+def render_heatmap_svg(
+    stats: AggregatedStats,
+    title: str | None = None,
+    year: int | None = None,
+    start_date: date_type | None = None,
+    end_date: date_type | None = None,
+    day_projects: dict[str, dict[str, dict]] | None = None,
+    day_links: str | None = None,
+    scale: str = DEFAULT_HEATMAP_SCALE,
+    theme: str = DEFAULT_HEATMAP_THEME,
+    annotations: dict[str, list[str]] | None = None,
+    metric: str = DEFAULT_HEATMAP_METRIC,
+) -> str:
+    """
+    Render the activity heatmap as an SVG document string.
 
-        current_date += timedelta(days=1)
+    Args:
+        stats: Aggregated statistics to visualize
+        title: Optional title for the graph
+        year: Year to display (defaults to current year)
+        start_date: First day of a custom window (overrides year)
+        end_date: Last day of a custom window (overrides year)
+        day_projects: {date: {folder: {"tokens", "cost"}}} from
+            api.get_daily_projects; adds each day's cost and top project
+            to its tooltip
+        day_links: Link target for active days, with a {date} placeholder
+            (e.g. "days/{date}.html"); cells link only when the SVG is
+            inlined in a page
+        scale: Color intensity scale, one of HEATMAP_SCALES
+        theme: Color gradient, one of HEATMAP_THEMES
+        annotations: {date: [label]} from api.get_annotations; days in
+            the window get a marker, their labels in the tooltip, and a
+            note line under the legend
+        metric: What the colors measure, one of HEATMAP_METRICS
 
-    # Pad final week with None
-    if current_week:
-        while len(current_week) < 7:
-            current_week.append((None, None))
-        weeks.append(current_week)
+    Returns:
+        SVG markup
+    """
+    custom_range = start_date is not None or end_date is not None
+    start_date, end_date, default_title = _resolve_range(year, start_date, end_date)
+    weeks = _build_weeks(stats, start_date, end_date)
+    annotations = _window_annotations(annotations, start_date, end_date)
 
     # Calculate dimensions
     num_weeks = len(weeks)
     width = (num_weeks * CELL_TOTAL) + 120  # Extra space for labels
+    width = max(width, MIN_SVG_WIDTH)  # Short windows still fit the title
     height = (7 * CELL_TOTAL) + 80  # Extra space for title and legend
+    height += (len(_annotation_notes(annotations)) + 1) * SVG_NOTE_LINE_HEIGHT  # Notes and streak footer
 
-    # Calculate max tokens for scaling
-    max_tokens = max(
-        (s.total_tokens for s in stats.daily_stats.values()), default=1
-    ) if stats.daily_stats else 1
+    color_scale = _color_scale(stats, weeks if custom_range else None, scale, theme, metric)
 
     # Generate SVG with dynamic title
-    default_title = f"Your Claude Code activity in {display_year}"
-    svg = _generate_svg(weeks, width, height, max_tokens, title or default_title)
-
-    # Write to file
-    output_path.write_text(svg, encoding="utf-8")
+    return _generate_svg(
+        weeks, width, height, color_scale, title or default_title, day_projects or {}, day_links, annotations,
+        _streak_footer(stats, start_date, end_date),
+    )
 
 
 def export_heatmap_png(
@@ -103,9 +235,15 @@ def export_heatmap_png(
     output_path: Path,
     title: str | None = None,
     year: int | None = None,
+    start_date: date_type | None = None,
+    end_date: date_type | None = None,
+    scale: str = DEFAULT_HEATMAP_SCALE,
+    theme: str = DEFAULT_HEATMAP_THEME,
+    annotations: dict[str, list[str]] | None = None,
+    metric: str = DEFAULT_HEATMAP_METRIC,
 ) -> None:
     """
-    Export the token activity heatmap as a PNG file.
+    Export the activity heatmap as a PNG file.
 
     Requires Pillow: pip install pillow
 
@@ -114,6 +252,13 @@ def export_heatmap_png(
         output_path: Path where PNG file will be saved
         title: Optional title for the graph
         year: Year to display (defaults to current year)
+        start_date: First day of a custom window (overrides year)
+        end_date: Last day of a custom window (overrides year)
+        scale: Color intensity scale, one of HEATMAP_SCALES
+        theme: Color gradient, one of HEATMAP_THEMES
+        annotations: Milestone labels by date, marked on their days and
+            listed under the legend
+        metric: What the colors measure, one of HEATMAP_METRICS
 
     Raises:
         ImportError: If Pillow is not installed
@@ -129,33 +274,12 @@ def export_heatmap_png(
 
     # Build weeks structure (same as SVG)
     today = datetime.now().date()
-    display_year = year if year is not None else today.year
-    start_date = datetime(display_year, 1, 1).date()
-    end_date = datetime(display_year, 12, 31).date()
-
-    jan1_day = (start_date.weekday() + 1) % 7
-    weeks: list[list[tuple[DailyStats | None, date_type | None]]] = []
-    current_week: list[tuple[DailyStats | None, date_type | None]] = []
-
-    for _ in range(jan1_day):
-        current_week.append((None, None))
-
-    current_date = start_date
-    while current_date <= end_date:
-        date_key = current_date.strftime("%Y-%m-%d")
-        day_stats = stats.daily_stats.get(date_key)
-        current_week.append((day_stats, current_date))
-
-        if len(current_week) == 7:
-            weeks.append(current_week)
-            current_week = []
-
-        current_date += timedelta(days=1)
-
-    if current_week:
-        while len(current_week) < 7:
-            current_week.append((None, None))
-        weeks.append(current_week)
+    custom_range = start_date is not None or end_date is not None
+    start_date, end_date, default_title = _resolve_range(year, start_date, end_date)
+    weeks = _build_weeks(stats, start_date, end_date)
+    annotations = _window_annotations(annotations, start_date, end_date)
+    notes = _annotation_notes(annotations)
+    footer = _streak_footer(stats, start_date, end_date)
 
     # Calculate dimensions
     num_weeks = len(weeks)
@@ -171,13 +295,17 @@ def export_heatmap_png(
     heatmap_title_space = 20 * SCALE_FACTOR  # Space for individual heatmap titles
     month_label_space = 12 * SCALE_FACTOR  # Space for month labels above each grid
     legend_height = CELL_SIZE + (8 * SCALE_FACTOR)  # Legend squares + small buffer
+    note_line_height = 14 * SCALE_FACTOR
+    notes_height = (len(notes) + 1) * note_line_height  # Notes and streak footer
 
     # Main title at the top
     main_title_height = 20 * SCALE_FACTOR
     main_title_to_first_heatmap = 25 * SCALE_FACTOR
 
     # Each heatmap section includes: title + month labels + grid + legend
-    single_heatmap_section_height = heatmap_title_space + month_label_space + grid_height + legend_height
+    single_heatmap_section_height = (
+        heatmap_title_space + month_label_space + grid_height + legend_height + notes_height
+    )
     num_heatmaps = 1
 
     # Total height
@@ -185,13 +313,13 @@ def export_heatmap_png(
     content_height = (num_heatmaps * single_heatmap_section_height) + ((num_heatmaps - 1) * heatmap_vertical_gap)
     bottom_padding = base_padding
 
-    width = base_padding + day_label_space + grid_width + base_padding
+    # Short windows still get a canvas wide enough for the title
+    width = base_padding + day_label_space + max(grid_width, MIN_PNG_GRID_WEEKS * CELL_TOTAL) + base_padding
     height = top_padding + content_height + bottom_padding
 
-    # Calculate max tokens
-    max_tokens = max(
-        (s.total_tokens for s in stats.daily_stats.values()), default=1
-    ) if stats.daily_stats else 1
+    # Color scale against the days shown
+    color_scale = _color_scale(stats, weeks if custom_range else None, scale, theme, metric)
+    section_title, legend_label = HEATMAP_METRICS[metric]
 
     # Create image
     img = Image.new('RGB', (width, height), _hex_to_rgb(CLAUDE_BG))
@@ -241,10 +369,10 @@ def export_heatmap_png(
     pixel_size = int(SCALE_FACTOR * 4)
     icon_width = _draw_claude_guy(draw, title_x, title_y, pixel_size)
     title_text_x = title_x + icon_width + (8 * SCALE_FACTOR)
-    default_title = f"Your Claude Code activity in {display_year}"
     draw.text((title_text_x, title_y), title or default_title, fill=_hex_to_rgb(CLAUDE_TEXT), font=title_font)
 
     corner_radius = 2 * SCALE_FACTOR
+    multi_year = start_date.year != end_date.year
     day_names = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"]
 
     # Helper function to draw one complete heatmap section
@@ -272,7 +400,7 @@ def export_heatmap_png(
                     month = date.month
                     if month != last_month:
                         x = grid_x + (week_idx * CELL_TOTAL)
-                        month_name = date.strftime("%b")
+                        month_name = _month_label(date, multi_year)
                         draw.text((x, month_y), month_name, fill=_hex_to_rgb(CLAUDE_TEXT_SECONDARY), font=label_font)
                         last_month = month
                     break
@@ -289,46 +417,59 @@ def export_heatmap_png(
                 color = gradient_func(day_stats, date)
                 draw.rounded_rectangle([x, y, x + CELL_SIZE, y + CELL_SIZE],
                                         radius=corner_radius, fill=color, outline=_hex_to_rgb(CLAUDE_BG))
+                if date.isoformat() in annotations:
+                    draw_marker(x + CELL_SIZE - 2 * ANNOTATION_MARKER_RADIUS, y + 2 * ANNOTATION_MARKER_RADIUS)
 
-        # Draw legend: dark grey + orange gradient
-        draw.text((grid_x, legend_y), "Less", fill=_hex_to_rgb(CLAUDE_TEXT_SECONDARY), font=label_font)
-        text_bbox = draw.textbbox((grid_x, legend_y), "Less", font=label_font)
-        text_width = text_bbox[2] - text_bbox[0]
-
-        legend_extra_gap = int(CELL_GAP * 0.3)
-        legend_square_spacing = CELL_SIZE + CELL_GAP + legend_extra_gap
-        squares_start = grid_x + text_width + (CELL_GAP * 2)
-
-        draw.rounded_rectangle([squares_start, legend_square_y, squares_start + CELL_SIZE, legend_square_y + CELL_SIZE],
-                                radius=corner_radius, fill=_hex_to_rgb(CLAUDE_DARK_GREY))
-        for i in range(1, 5):
-            intensity = 0.2 + ((i - 1) / 3) * 0.8
-            r = int(CLAUDE_ORANGE_RGB[0] * intensity)
-            g = int(CLAUDE_ORANGE_RGB[1] * intensity)
-            b = int(CLAUDE_ORANGE_RGB[2] * intensity)
-            x = squares_start + (i * legend_square_spacing)
-            draw.rounded_rectangle([x, legend_square_y, x + CELL_SIZE, legend_square_y + CELL_SIZE],
-                                    radius=corner_radius, fill=(r, g, b))
-
-        more_x = squares_start + (5 * legend_square_spacing) + CELL_GAP
-        draw.text((more_x, legend_y), "More", fill=_hex_to_rgb(CLAUDE_TEXT_SECONDARY), font=label_font)
+        # Draw legend: one swatch per color bucket, labeled with its range
+        text_color = _hex_to_rgb(CLAUDE_TEXT_SECONDARY)
+        draw.text((grid_x, legend_y), legend_label, fill=text_color, font=label_font)
+        text_bbox = draw.textbbox((grid_x, legend_y), legend_label, font=label_font)
+        x = grid_x + (text_bbox[2] - text_bbox[0]) + (CELL_GAP * 2)
 
-    def tokens_gradient(day_stats, date):
-        color_str = _get_color(day_stats, max_tokens, date, today)
+        for color, label in _legend_buckets(color_scale):
+            fill = _parse_rgb(color) if color.startswith('rgb(') else _hex_to_rgb(color)
+            draw.rounded_rectangle([x, legend_square_y, x + CELL_SIZE, legend_square_y + CELL_SIZE],
+                                    radius=corner_radius, fill=fill)
+            x += CELL_SIZE + CELL_GAP
+            draw.text((x, legend_y), label, fill=text_color, font=label_font)
+            label_bbox = draw.textbbox((x, legend_y), label, font=label_font)
+            x += (label_bbox[2] - label_bbox[0]) + (CELL_GAP * 3)
+
+        # Annotation notes, one line each under the legend
+        note_y = legend_y + legend_height
+        for note in notes:
+            draw_marker(grid_x + ANNOTATION_MARKER_RADIUS, note_y + note_line_height // 3)
+            draw.text((grid_x + CELL_SIZE // 2 + CELL_GAP, note_y), note, fill=text_color, font=label_font)
+            note_y += note_line_height
+
+        # Streak footer under the notes
+        draw.text((grid_x, note_y), footer, fill=text_color, font=label_font)
+
+    def draw_marker(cx, cy):
+        r = ANNOTATION_MARKER_RADIUS
+        draw.ellipse([cx - r, cy - r, cx + r, cy + r], fill=_hex_to_rgb(CLAUDE_TEXT), outline=_hex_to_rgb(CLAUDE_BG))
+
+    def metric_gradient(day_stats, date):
+        color_str = _get_color(day_stats, color_scale, date, today)
         return _parse_rgb(color_str) if color_str.startswith('rgb(') else _hex_to_rgb(color_str)
 
-    draw_heatmap_section(heatmap_y_positions[0], "Token Usage", tokens_gradient)
+    draw_heatmap_section(heatmap_y_positions[0], section_title, metric_gradient)
 
     # Save image
-    img.save(output_path, 'PNG')
+    with atomic_write(output_path, binary=True) as f:
+        img.save(f, 'PNG')
 
 
 def _generate_svg(
     weeks: list[list[tuple[DailyStats | None, date_type | None]]],
     width: int,
     height: int,
-    max_tokens: int,
-    title: str
+    color_scale: ColorScale,
+    title: str,
+    day_projects: dict[str, dict[str, dict]] | None = None,
+    day_links: str | None = None,
+    annotations: dict[str, list[str]] | None = None,
+    footer: str | None = None,
 ) -> str:
     """
     Generate SVG markup for the heatmap.
@@ -337,8 +478,12 @@ def _generate_svg(
         weeks: List of weeks with daily stats
         width: SVG width in pixels
         height: SVG height in pixels
-        max_tokens: Maximum token count for scaling
+        color_scale: Maps day tokens to color intensity
         title: Title text
+        day_projects: Per-day project usage for tooltips
+        day_links: Link target template for active days ({date} placeholder)
+        annotations: Milestone labels by date, already limited to the window
+        footer: Line under the notes (the window's activity streaks)
 
     Returns:
         SVG markup as a string
@@ -351,6 +496,7 @@ def _generate_svg(
         f'  .day-label {{ fill: {CLAUDE_TEXT_SECONDARY}; font: 10px -apple-system, sans-serif; }}',
         f'  .title {{ fill: {CLAUDE_TEXT}; font: bold 16px -apple-system, sans-serif; }}',
         f'  .legend-text {{ fill: {CLAUDE_TEXT_SECONDARY}; font: 10px -apple-system, sans-serif; }}',
+        f'  .annotation {{ fill: {CLAUDE_TEXT}; stroke: {CLAUDE_BG}; stroke-width: 1; pointer-events: none; }}',
         '</style>',
         f'<rect width="{width}" height="{height}" fill="{CLAUDE_BG}"/>',
     ]
@@ -370,6 +516,8 @@ def _generate_svg(
         svg_parts.append(f'<text x="5" y="{y + 4}" class="day-label" text-anchor="start">{day_name}</text>')
 
     # Month labels (X-axis)
+    dates = [date for week in weeks for _, date in week if date is not None]
+    multi_year = bool(dates) and dates[0].year != dates[-1].year
     last_month = None
     for week_idx, week in enumerate(weeks):
         for day_stats, date in week:
@@ -377,12 +525,13 @@ def _generate_svg(
                 month = date.month
                 if month != last_month:
                     x = 40 + (week_idx * CELL_TOTAL)
-                    month_name = date.strftime("%b")
+                    month_name = _month_label(date, multi_year)
                     svg_parts.append(f'<text x="{x}" y="50" class="month-label">{month_name}</text>')
                     last_month = month
                 break
 
     # Heatmap cells
+    annotations = annotations or {}
     today = datetime.now().date()
     for week_idx, week in enumerate(weeks):
         for day_idx, (day_stats, date) in enumerate(week):
@@ -393,43 +542,234 @@ def _generate_svg(
             x = 40 + (week_idx * CELL_TOTAL)
             y = 60 + (day_idx * CELL_TOTAL)
 
-            color = _get_color(day_stats, max_tokens, date, today)
+            color = _get_color(day_stats, color_scale, date, today)
 
             # Add tooltip with date and stats
             if day_stats and day_stats.total_tokens > 0:
-                tooltip = f"{date}: {day_stats.total_prompts} prompts, {day_stats.total_tokens:,} tokens"
+                tooltip = _day_tooltip(date, day_stats, (day_projects or {}).get(date.isoformat(), {}))
             elif date > today:
                 tooltip = f"{date}: Future"
             else:
                 tooltip = f"{date}: No activity"
-
-            svg_parts.append(f'<rect x="{x}" y="{y}" width="{CELL_SIZE}" height="{CELL_SIZE}" fill="{color}" class="day-cell"><title>{tooltip}</title></rect>')
-
-    # Legend - show gradient from dark to bright orange
-    legend_y = height - 20
+            labels = annotations.get(date.isoformat(), [])
+            tooltip += "".join(f"\n◆ {escape(label)}" for label in labels)
+
+            cell = f'<rect x="{x}" y="{y}" width="{CELL_SIZE}" height="{CELL_SIZE}" fill="{color}" class="day-cell"><title>{tooltip}</title></rect>'
+            if day_links and day_stats and day_stats.total_tokens > 0:
+                cell = f'<a href="{escape(day_links.format(date=date.isoformat()))}">{cell}</a>'
+            svg_parts.append(cell)
+            if labels:
+                # Drawn over the cell; pointer-events: none keeps its tooltip and link
+                r = ANNOTATION_MARKER_RADIUS
+                svg_parts.append(
+                    f'<circle cx="{x + CELL_SIZE - 2 * r}" cy="{y + 2 * r}" r="{r}" class="annotation"/>'
+                )
+
+    # Legend - one swatch per color bucket, labeled with its range
+    notes = _annotation_notes(annotations)
+    legend_y = height - 12 - (len(notes) + bool(footer)) * SVG_NOTE_LINE_HEIGHT
     legend_x = 40
-    svg_parts.append(f'<text x="{legend_x}" y="{legend_y}" class="legend-text">Less</text>')
+    legend_label = HEATMAP_METRICS[color_scale.metric][1]
+    svg_parts.append(f'<text x="{legend_x}" y="{legend_y}" class="legend-text">{legend_label}</text>')
+
+    x = legend_x + 60
+    for color, label in _legend_buckets(color_scale):
+        svg_parts.append(f'<rect x="{x}" y="{legend_y - 10}" width="12" height="12" fill="{color}" class="day-cell"/>')
+        svg_parts.append(f'<text x="{x + 16}" y="{legend_y}" class="legend-text">{escape(label)}</text>')
+        x += 16 + (6 * len(label)) + 12
 
-    # Show 5 sample cells from gradient
-    for i in range(5):
-        intensity = 0.2 + (i / 4) * 0.8
-        r = int(CLAUDE_ORANGE_RGB[0] * intensity)
-        g = int(CLAUDE_ORANGE_RGB[1] * intensity)
-        b = int(CLAUDE_ORANGE_RGB[2] * intensity)
-        color = f"rgb({r},{g},{b})"
-        x = legend_x + 35 + (i * (CELL_SIZE + 2))
-        svg_parts.append(f'<rect x="{x}" y="{legend_y - CELL_SIZE + 2}" width="{CELL_SIZE}" height="{CELL_SIZE}" fill="{color}" class="day-cell"/>')
+    # Annotation notes, one line each under the legend
+    for i, note in enumerate(notes, start=1):
+        y = legend_y + i * SVG_NOTE_LINE_HEIGHT
+        svg_parts.append(f'<circle cx="{legend_x + 4}" cy="{y - 4}" r="4" class="annotation"/>')
+        svg_parts.append(f'<text x="{legend_x + 14}" y="{y}" class="legend-text">{escape(note)}</text>')
 
-    svg_parts.append(f'<text x="{legend_x + 35 + (5 * (CELL_SIZE + 2)) + 5}" y="{legend_y}" class="legend-text">More</text>')
+    if footer:
+        y = legend_y + (len(notes) + 1) * SVG_NOTE_LINE_HEIGHT
+        svg_parts.append(f'<text x="{legend_x}" y="{y}" class="legend-text">{escape(footer)}</text>')
 
     svg_parts.append('</svg>')
 
     return '\n'.join(svg_parts)
 
 
+def _day_tooltip(date: date_type, day_stats: DailyStats, projects: dict[str, dict]) -> str:
+    """
+    Tooltip text for an active day: prompts, tokens, cost, and top project.
+
+    Cost comes from the day's projects when known, else from the daily
+    snapshot; the top project is the one with the most tokens.
+    """
+    parts = [f"{day_stats.total_prompts} prompts", f"{day_stats.total_tokens:,} tokens"]
+    cost = sum(p["cost"] for p in projects.values()) if projects else day_stats.cost
+    if cost:
+        parts.append(f"${cost:,.2f}")
+    tooltip = f"{date}: {', '.join(parts)}"
+    if projects:
+        folder, top = max(projects.items(), key=lambda item: item[1]["tokens"])
+        total = sum(p["tokens"] for p in projects.values())
+        share = f" ({top['tokens'] / total:.0%})" if total and len(projects) > 1 else ""
+        tooltip += f"\nTop project: {short_folder(folder)}{share}"
+    return escape(tooltip)
+
+
+def _window_annotations(
+    annotations: dict[str, list[str]] | None, start_date: date_type, end_date: date_type
+) -> dict[str, list[str]]:
+    """Annotations on days between start_date and end_date."""
+    first, last = start_date.isoformat(), end_date.isoformat()
+    return {date: labels for date, labels in (annotations or {}).items() if first <= date <= last and labels}
+
+
+def _annotation_notes(annotations: dict[str, list[str]]) -> list[str]:
+    """
+    Note lines listed under the legend: "YYYY-MM-DD  label", oldest first.
+
+    Past MAX_ANNOTATION_NOTES the last line counts the rest ("+N more").
+    """
+    notes = []
+    for date in sorted(annotations):
+        for label in annotations[date]:
+            note = f"{date}  {label}"
+            notes.append(note if len(note) <= ANNOTATION_NOTE_CHARS else note[:ANNOTATION_NOTE_CHARS - 1] + "…")
+    if len(notes) > MAX_ANNOTATION_NOTES:
+        hidden = len(notes) - MAX_ANNOTATION_NOTES + 1
+        notes = notes[:MAX_ANNOTATION_NOTES - 1] + [f"+{hidden} more"]
+    return notes
+
+
+def _streak_footer(stats: AggregatedStats, start_date: date_type, end_date: date_type) -> str:
+    """
+    Footer line with the window's activity streaks, GitHub-contributions style.
+
+    The current streak only shows while the window reaches today; a past
+    window gets just its longest streak.
+    """
+    today = datetime.now().date()
+    end = min(end_date, today)
+    current, longest = activity_streaks(stats.daily_stats, start_date.isoformat(), end.isoformat())
+    footer = f"Longest streak: {_days(longest)}"
+    if end_date >= today:
+        footer = f"Current streak: {_days(current)} · {footer}"
+    return footer
+
+
+def _days(count: int) -> str:
+    return f"{count} day" if count == 1 else f"{count} days"
+
+
+def _resolve_range(
+    year: int | None,
+    start_date: date_type | None,
+    end_date: date_type | None,
+) -> tuple[date_type, date_type, str]:
+    """
+    Resolve the displayed window and its default title.
+
+    A custom window wins over year. A missing end defaults to today; a
+    missing start defaults to 52 weeks before the end.
+
+    Returns:
+        Tuple of (start_date, end_date, default_title)
+    """
+    today = datetime.now().date()
+    if start_date is None and end_date is None:
+        display_year = year if year is not None else today.year
+        return (
+            datetime(display_year, 1, 1).date(),
+            datetime(display_year, 12, 31).date(),
+            f"Your Claude Code activity in {display_year}",
+        )
+
+    end = end_date or today
+    start = start_date or end - timedelta(days=364)
+    if start > end:
+        raise ValueError(f"Start date {start} is after end date {end}")
+    return start, end, f"Your Claude Code activity from {start:%b %d, %Y} to {end:%b %d, %Y}"
+
+
+def _build_weeks(
+    stats: AggregatedStats,
+    start_date: date_type,
+    end_date: date_type,
+) -> list[list[tuple[DailyStats | None, date_type | None]]]:
+    """
+    Lay out [start_date, end_date] as Sunday-first week columns.
+
+    The first and last weeks are padded with (None, None) cells so every
+    column has exactly seven rows.
+    """
+    first_day = (start_date.weekday() + 1) % 7
+    weeks: list[list[tuple[DailyStats | None, date_type | None]]] = []
+    current_week: list[tuple[DailyStats | None, date_type | None]] = []
+
+    # Pad first week with None
+    for _ in range(first_day):
+        current_week.append((None, None))
+
+    current_date = start_date
+    while current_date <= end_date:
+        date_key = current_date.strftime("%Y-%m-%d")
+        day_stats = stats.daily_stats.get(date_key)
+        current_week.append((day_stats, current_date))
+
+        if len(current_week) == 7:
+            weeks.append(current_week)
+            current_week = []
+
+        current_date += timedelta(days=1)
+
+    # Pad final week with None
+    if current_week:
+        while len(current_week) < 7:
+            current_week.append((None, None))
+        weeks.append(current_week)
+
+    return weeks
+
+
+def _color_scale(
+    stats: AggregatedStats,
+    weeks: list[list[tuple[DailyStats | None, date_type | None]]] | None = None,
+    scale: str = DEFAULT_HEATMAP_SCALE,
+    theme: str = DEFAULT_HEATMAP_THEME,
+    metric: str = DEFAULT_HEATMAP_METRIC,
+) -> ColorScale:
+    """
+    Color scale over the daily token (or prompt) counts being shown.
+
+    Year exports scale against all loaded data; custom windows pass their
+    weeks so a busy day outside the window doesn't wash out the strip.
+
+    Raises:
+        ValueError: If scale is not one of HEATMAP_SCALES, theme not one
+            of HEATMAP_THEMES, or metric not one of HEATMAP_METRICS
+    """
+    if scale not in HEATMAP_SCALES:
+        raise ValueError(f"Unknown heatmap scale: {scale} (choose from {', '.join(HEATMAP_SCALES)})")
+    if theme not in HEATMAP_THEMES:
+        raise ValueError(f"Unknown heatmap theme: {theme} (choose from {', '.join(HEATMAP_THEMES)})")
+    if metric not in HEATMAP_METRICS:
+        raise ValueError(f"Unknown heatmap metric: {metric} (choose from {', '.join(HEATMAP_METRICS)})")
+    color_scale = ColorScale(kind=scale, values=[], theme=theme, metric=metric)
+    if weeks is None:
+        values = [color_scale.value(s) for s in stats.daily_stats.values()]
+    else:
+        values = [color_scale.value(s) for week in weeks for s, _ in week if s is not None]
+    color_scale.values = sorted(v for v in values if v > 0)
+    return color_scale
+
+
+def _month_label(date: date_type, multi_year: bool) -> str:
+    """Month label for the x-axis; January carries the year when the window spans years."""
+    if multi_year and date.month == 1:
+        return date.strftime("%b '%y")
+    return date.strftime("%b")
+
+
 def _get_color(
     day_stats: DailyStats | None,
-    max_tokens: int,
+    color_scale: ColorScale,
     date: date_type,
     today: date_type
 ) -> str:
@@ -438,7 +778,7 @@ def _get_color(
 
     Args:
         day_stats: Statistics for the day
-        max_tokens: Maximum tokens for scaling
+        color_scale: Maps the day's metric to color intensity
         date: The date of this cell
         today: Today's date
 
@@ -450,24 +790,70 @@ def _get_color(
         return CLAUDE_LIGHT_GREY
 
     # Past days with no activity: dark grey
-    if not day_stats or day_stats.total_tokens == 0:
+    if not day_stats or color_scale.value(day_stats) == 0:
         return CLAUDE_DARK_GREY
 
-    # Calculate intensity ratio (0.0 to 1.0)
-    ratio = day_stats.total_tokens / max_tokens if max_tokens > 0 else 0
+    return _intensity_color(color_scale.ratio(color_scale.value(day_stats)), color_scale.theme)
 
-    # Apply non-linear scaling to make differences more visible
-    ratio = ratio ** 0.5
 
-    # True continuous gradient from dark grey to orange
-    dark_grey = _hex_to_rgb(CLAUDE_DARK_GREY)
-    r = int(dark_grey[0] + (CLAUDE_ORANGE_RGB[0] - dark_grey[0]) * ratio)
-    g = int(dark_grey[1] + (CLAUDE_ORANGE_RGB[1] - dark_grey[1]) * ratio)
-    b = int(dark_grey[2] + (CLAUDE_ORANGE_RGB[2] - dark_grey[2]) * ratio)
+def _intensity_color(ratio: float, theme: str = DEFAULT_HEATMAP_THEME) -> str:
+    """
+    Gradient color for a color intensity.
+
+    Args:
+        ratio: Intensity from ColorScale.ratio (0.0 to 1.0)
+        theme: Gradient to pick from, one of HEATMAP_THEMES
+
+    Returns:
+        RGB color string
+    """
+    # Continuous gradient, interpolated between the two stops around ratio
+    stops = HEATMAP_THEMES[theme]
+    position = min(max(ratio, 0.0), 1.0) * (len(stops) - 1)
+    index = min(int(position), len(stops) - 2)
+    low, high = stops[index], stops[index + 1]
+    fraction = position - index
+    r, g, b = (int(low[i] + (high[i] - low[i]) * fraction) for i in range(3))
 
     return f"rgb({r},{g},{b})"
 
 
+def _legend_buckets(color_scale: ColorScale, buckets: int = LEGEND_BUCKETS) -> list[tuple[str, str]]:
+    """
+    Legend swatches with the daily range each color stands for.
+
+    The gradient is continuous, so each bucket spans an equal share of it
+    and is drawn in the color of its upper bound. Bounds undo the color
+    scale, so they are real token (or prompt) counts for the data shown.
+
+    Args:
+        color_scale: Scale the cells were colored with
+        buckets: Number of colored buckets
+
+    Returns:
+        (color, label) pairs: an idle-day swatch, then one per distinct bucket
+    """
+    swatches = [(CLAUDE_DARK_GREY, "0")]
+    previous = 0
+    for i in range(1, buckets + 1):
+        upper = color_scale.bound(i / buckets)
+        # Tiny maxima round several bounds together; list each range once
+        if upper <= previous:
+            continue
+        swatches.append((_intensity_color(color_scale.ratio(upper), color_scale.theme), f"≤{_compact_tokens(upper)}"))
+        previous = upper
+    return swatches
+
+
+def _compact_tokens(tokens: int) -> str:
+    """Short token count for legend labels (e.g. "850", "45K", "1.2M")."""
+    for divisor, suffix in ((1_000_000_000, "B"), (1_000_000, "M"), (1_000, "K")):
+        if tokens >= divisor:
+            value = tokens / divisor
+            return f"{value:.1f}{suffix}" if value < 10 else f"{value:.0f}{suffix}"
+    return str(tokens)
+
+
 def _hex_to_rgb(hex_color: str) -> tuple[int, int, int]:
     """Convert hex color to RGB tuple."""
     hex_color = hex_color.lstrip('#')
@@ -0,0 +1,139 @@
+"""
+Localhost preview server for the activity heatmap.
+
+Serves a small page that shows the heatmap SVG and swaps in a fresh render
+whenever the refresh marker changes, so a pinned browser tab always shows
+current activity without re-running an export. Active days link to
+/day/<date> detail pages when a day renderer is given.
+"""
+#region Imports
+import json
+import re
+from collections.abc import Callable
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+from src.storage.refresh_marker import read_refresh_marker
+
+#endregion
+
+
+#region Constants
+DEFAULT_PORT = 8765
+
+# How often the page asks whether the database changed (milliseconds)
+PAGE_POLL_MS = 2000
+
+# Day page paths: /day/YYYY-MM-DD
+DAY_PATH = re.compile(r"^/day/(\d{4}-\d{2}-\d{2})$")
+
+# Page polls /version and re-fetches the SVG only when the marker changed.
+# The SVG is inlined (not an <img>) so its day cells stay clickable.
+PAGE_TEMPLATE = """<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Claude Goblin heatmap</title>
+<style>
+  body {{ margin: 0; background: #262624; display: flex; justify-content: center; }}
+  #heatmap {{ margin-top: 24px; }}
+  #heatmap svg {{ max-width: 100%; height: auto; }}
+</style>
+</head>
+<body>
+<div id="heatmap" role="img" aria-label="Claude Code activity heatmap"></div>
+<script>
+  let version = "{version}";
+  async function load() {{
+    const response = await fetch("/heatmap.svg?v=" + encodeURIComponent(version), {{cache: "no-store"}});
+    document.getElementById("heatmap").innerHTML = await response.text();
+  }}
+  load();
+  setInterval(async () => {{
+    try {{
+      const response = await fetch("/version", {{cache: "no-store"}});
+      const latest = (await response.json()).version;
+      if (latest !== version) {{
+        version = latest;
+        await load();
+      }}
+    }} catch (e) {{}}
+  }}, {poll_ms});
+</script>
+</body>
+</html>
+"""
+#endregion
+
+
+#region Functions
+
+
+def make_heatmap_server(
+    render_svg: Callable[[], str],
+    port: int = DEFAULT_PORT,
+    host: str = "127.0.0.1",
+    render_day: Callable[[str], str | None] | None = None,
+) -> ThreadingHTTPServer:
+    """
+    Build (but do not start) the heatmap preview server.
+
+    Args:
+        render_svg: Called per image request; returns fresh SVG markup
+        port: Port to listen on (0 picks a free port)
+        host: Interface to bind; localhost only by default
+        render_day: Called with a YYYY-MM-DD date for /day/<date>; returns
+            the day's HTML page, or None if the day has no activity
+
+    Returns:
+        Server ready for serve_forever()
+    """
+
+    class HeatmapHandler(BaseHTTPRequestHandler):
+        def do_GET(self) -> None:
+            path = self.path.split("?", 1)[0]
+            if path == "/":
+                page = PAGE_TEMPLATE.format(version=_version(), poll_ms=PAGE_POLL_MS)
+                self._send(200, "text/html; charset=utf-8", page)
+            elif path == "/heatmap.svg":
+                try:
+                    self._send(200, "image/svg+xml", render_svg())
+                except Exception as e:
+                    self._send(500, "text/plain; charset=utf-8", f"Error rendering heatmap: {e}")
+            elif path == "/version":
+                self._send(200, "application/json", json.dumps({"version": _version()}))
+            elif render_day is not None and (match := DAY_PATH.match(path)):
+                try:
+                    page = render_day(match.group(1))
+                except Exception as e:
+                    self._send(500, "text/plain; charset=utf-8", f"Error rendering day: {e}")
+                    return
+                if page is None:
+                    self._send(404, "text/plain; charset=utf-8", "No activity on this day")
+                else:
+                    self._send(200, "text/html; charset=utf-8", page)
+            else:
+                self._send(404, "text/plain; charset=utf-8", "Not found")
+
+        def _send(self, status: int, content_type: str, body: str) -> None:
+            data = body.encode("utf-8")
+            self.send_response(status)
+            self.send_header("Content-Type", content_type)
+            self.send_header("Content-Length", str(len(data)))
+            self.send_header("Cache-Control", "no-store")
+            self.end_headers()
+            self.wfile.write(data)
+
+        def log_message(self, format: str, *args) -> None:
+            pass  # Keep the terminal quiet; the browser polls constantly
+
+    return ThreadingHTTPServer((host, port), HeatmapHandler)
+
+
+def _version() -> str:
+    """
+    Current refresh-marker token as a string ("0" before any update).
+    """
+    return str(read_refresh_marker() or 0)
+
+
+#endregion
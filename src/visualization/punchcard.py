@@ -0,0 +1,185 @@
+#region Imports
+from pathlib import Path
+from xml.sax.saxutils import escape
+
+from rich.console import Console
+from rich.text import Text
+
+from src.aggregation.activity_matrix import ActivityMatrix
+from src.aggregation.daily_stats import WEEKDAY_NAMES
+from src.utils._system import atomic_write_text
+from src.visualization.export import (
+    CLAUDE_BG,
+    CLAUDE_DARK_GREY,
+    CLAUDE_TEXT,
+    CLAUDE_TEXT_SECONDARY,
+    DEFAULT_HEATMAP_THEME,
+    _compact_tokens,
+    _intensity_color,
+)
+
+#endregion
+
+
+#region Constants
+ORANGE = "#ff8800"
+MUTED_ORANGE = "#cc7a33"
+DIM = "grey50"
+
+# Terminal glyphs from quietest to busiest active hour; idle hours get IDLE_GLYPH
+PUNCH_GLYPHS = ("∙", "•", "●")
+IDLE_GLYPH = "·"
+
+# SVG layout (pixels)
+SVG_CELL = 28
+SVG_LABEL_WIDTH = 44
+SVG_TOTAL_WIDTH = 60
+SVG_TOP = 64
+SVG_MAX_RADIUS = SVG_CELL // 2 - 2
+#endregion
+
+
+#region Functions
+
+
+def _ratio(value: int, peak: int) -> float:
+    """Square-root intensity, so quieter hours stay visible next to the peak."""
+    return (value / peak) ** 0.5 if value > 0 and peak > 0 else 0.0
+
+
+def _metric_label(metric: str) -> str:
+    return "prompts" if metric == "prompts" else "tokens"
+
+
+def render_punchcard(console: Console, matrix: ActivityMatrix, metric: str = "tokens") -> None:
+    """
+    Print the weekday x hour matrix as a terminal punch card.
+
+    Each hour is a dot sized and shaded by its share of the busiest hour;
+    rows end with the weekday total, and the busiest slot is named below.
+
+    Args:
+        console: Rich console for output
+        matrix: Activity matrix (aggregation.activity_matrix)
+        metric: "tokens" or "prompts"
+    """
+    grid = matrix.grid(metric)
+    peak = max(max(row) for row in grid)
+
+    header = Text("     ", style=DIM)
+    for hour in range(24):
+        header.append(f"{hour:<3}" if hour % 3 == 0 else "   ")
+    header.append("  Total")
+    console.print(header)
+
+    for weekday, row in enumerate(grid):
+        line = Text(f"{WEEKDAY_NAMES[weekday]}  ", style="white")
+        for value in row:
+            ratio = _ratio(value, peak)
+            if ratio == 0:
+                line.append(f"{IDLE_GLYPH}  ", style=DIM)
+                continue
+            glyph = PUNCH_GLYPHS[min(int(ratio * len(PUNCH_GLYPHS)), len(PUNCH_GLYPHS) - 1)]
+            line.append(f"{glyph}  ", style=ORANGE if ratio >= 0.5 else MUTED_ORANGE)
+        line.append(f"{_compact_tokens(sum(row)):>7}", style=ORANGE)
+        console.print(line)
+
+    busiest = matrix.peak(metric)
+    if busiest:
+        weekday, hour, value = busiest
+        console.print(
+            f"\n[dim]Busiest: {weekday} {hour:02d}:00-{hour:02d}:59 "
+            f"({value:,} {_metric_label(metric)}), local time[/dim]"
+        )
+
+
+def render_punchcard_svg(
+    matrix: ActivityMatrix,
+    metric: str = "tokens",
+    title: str = "When you use Claude Code",
+    theme: str = DEFAULT_HEATMAP_THEME,
+) -> str:
+    """
+    SVG punch card: one circle per weekday and hour, area by activity.
+
+    Args:
+        matrix: Activity matrix (aggregation.activity_matrix)
+        metric: "tokens" or "prompts"
+        title: Title text
+        theme: Color gradient, one of HEATMAP_THEMES
+
+    Returns:
+        SVG markup as a string
+    """
+    grid = matrix.grid(metric)
+    peak = max(max(row) for row in grid)
+    label = _metric_label(metric)
+    width = SVG_LABEL_WIDTH + 24 * SVG_CELL + SVG_TOTAL_WIDTH
+    height = SVG_TOP + 7 * SVG_CELL + 40
+
+    svg_parts = [
+        f'<svg width="{width}" height="{height}" xmlns="http://www.w3.org/2000/svg">',
+        '<style>',
+        f'  .hour-label {{ fill: {CLAUDE_TEXT_SECONDARY}; font: 10px -apple-system, sans-serif; }}',
+        f'  .day-label {{ fill: {CLAUDE_TEXT_SECONDARY}; font: 11px -apple-system, sans-serif; }}',
+        f'  .title {{ fill: {CLAUDE_TEXT}; font: bold 16px -apple-system, sans-serif; }}',
+        f'  .legend-text {{ fill: {CLAUDE_TEXT_SECONDARY}; font: 10px -apple-system, sans-serif; }}',
+        '</style>',
+        f'<rect width="{width}" height="{height}" fill="{CLAUDE_BG}"/>',
+        f'<text x="10" y="25" class="title">{escape(title)}</text>',
+    ]
+
+    for hour in range(0, 24, 3):
+        x = SVG_LABEL_WIDTH + hour * SVG_CELL + SVG_CELL // 2
+        svg_parts.append(f'<text x="{x}" y="{SVG_TOP - 10}" class="hour-label" text-anchor="middle">{hour:02d}</text>')
+
+    for weekday, row in enumerate(grid):
+        cy = SVG_TOP + weekday * SVG_CELL + SVG_CELL // 2
+        svg_parts.append(f'<text x="10" y="{cy + 4}" class="day-label">{WEEKDAY_NAMES[weekday]}</text>')
+        for hour, value in enumerate(row):
+            cx = SVG_LABEL_WIDTH + hour * SVG_CELL + SVG_CELL // 2
+            ratio = _ratio(value, peak)
+            tooltip = escape(f"{WEEKDAY_NAMES[weekday]} {hour:02d}:00\n{value:,} {label}")
+            if ratio == 0:
+                radius, color = 2, CLAUDE_DARK_GREY
+            else:
+                # Radius from the square-root ratio, so circle area tracks the value
+                radius, color = max(round(SVG_MAX_RADIUS * ratio, 1), 2), _intensity_color(ratio, theme)
+            svg_parts.append(
+                f'<circle cx="{cx}" cy="{cy}" r="{radius}" fill="{color}"><title>{tooltip}</title></circle>'
+            )
+        total_x = SVG_LABEL_WIDTH + 24 * SVG_CELL + SVG_TOTAL_WIDTH - 10
+        svg_parts.append(
+            f'<text x="{total_x}" y="{cy + 4}" class="legend-text" text-anchor="end">'
+            f'{_compact_tokens(sum(row))}</text>'
+        )
+
+    footer_y = SVG_TOP + 7 * SVG_CELL + 24
+    busiest = matrix.peak(metric)
+    footer = f"Circle area: {label} per hour, local time"
+    if busiest:
+        weekday, hour, value = busiest
+        footer += f" · busiest {weekday} {hour:02d}:00 ({_compact_tokens(value)})"
+    svg_parts.append(f'<text x="10" y="{footer_y}" class="legend-text">{escape(footer)}</text>')
+    svg_parts.append('</svg>')
+
+    return '\n'.join(svg_parts)
+
+
+def export_punchcard_svg(
+    matrix: ActivityMatrix,
+    output_path: Path,
+    metric: str = "tokens",
+    title: str = "When you use Claude Code",
+    theme: str = DEFAULT_HEATMAP_THEME,
+) -> None:
+    """
+    Write the punch card SVG to a file.
+
+    Raises:
+        IOError: If file cannot be written
+    """
+    atomic_write_text(output_path, render_punchcard_svg(matrix, metric, title, theme))
+
+
+#endregion
@@ -0,0 +1,197 @@
+#region Imports
+from datetime import date, datetime
+from pathlib import Path
+from xml.sax.saxutils import escape
+
+from src.aggregation.daily_stats import AggregatedStats
+from src.aggregation.recap import format_tokens
+from src.aggregation.wrapped import YearWrapped
+from src.utils._system import atomic_write, atomic_write_text
+from src.utils.folders import short_folder
+from src.visualization.export import (
+    CLAUDE_BG,
+    CLAUDE_DARK_GREY,
+    CLAUDE_ORANGE_RGB,
+    CLAUDE_TEXT,
+    CLAUDE_TEXT_SECONDARY,
+    _generate_clawd_svg,
+    render_heatmap_svg,
+)
+
+#endregion
+
+
+#region Constants
+CLAUDE_ORANGE = "#{:02X}{:02X}{:02X}".format(*CLAUDE_ORANGE_RGB)
+
+# Layout (pixels, at the same 3x scale as the heatmap export)
+WRAPPED_PADDING = 60
+HEADER_HEIGHT = 200
+TILE_HEIGHT = 220
+TILE_GAP = 40
+PANEL_HEIGHT = 460
+SECTION_GAP = 50
+FONT = "-apple-system, sans-serif"
+#endregion
+
+
+#region Functions
+
+
+def render_wrapped_svg(wrapped: YearWrapped, stats: AggregatedStats) -> str:
+    """
+    Render the year-in-review image as an SVG document string.
+
+    Panels, top to bottom: header, four stat tiles, the year's heatmap (the
+    `ccg export` renderer, embedded as-is), then top projects and fun facts
+    side by side.
+
+    Args:
+        wrapped: Facts for the year
+        stats: Aggregated statistics for the heatmap panel
+
+    Returns:
+        SVG markup
+    """
+    heatmap = render_heatmap_svg(stats, title=f"Activity in {wrapped.year}", year=wrapped.year)
+    heatmap_width = int(heatmap.split('width="', 1)[1].split('"', 1)[0])
+    heatmap_height = int(heatmap.split('height="', 1)[1].split('"', 1)[0])
+
+    width = heatmap_width + 2 * WRAPPED_PADDING
+    inner = heatmap_width
+    tiles_y = WRAPPED_PADDING + HEADER_HEIGHT
+    heatmap_y = tiles_y + TILE_HEIGHT + SECTION_GAP
+    panels_y = heatmap_y + heatmap_height + SECTION_GAP
+    height = panels_y + PANEL_HEIGHT + WRAPPED_PADDING
+
+    parts = [
+        f'<svg width="{width}" height="{height}" xmlns="http://www.w3.org/2000/svg">',
+        '<style>',
+        f'  .headline {{ fill: {CLAUDE_TEXT}; font: bold 96px {FONT}; }}',
+        f'  .year {{ fill: {CLAUDE_ORANGE}; font: bold 96px {FONT}; }}',
+        f'  .value {{ fill: {CLAUDE_TEXT}; font: bold 84px {FONT}; }}',
+        f'  .label {{ fill: {CLAUDE_TEXT_SECONDARY}; font: 32px {FONT}; }}',
+        f'  .panel-title {{ fill: {CLAUDE_TEXT}; font: bold 44px {FONT}; }}',
+        f'  .item {{ fill: {CLAUDE_TEXT}; font: 36px {FONT}; }}',
+        f'  .item-value {{ fill: {CLAUDE_TEXT_SECONDARY}; font: 36px {FONT}; }}',
+        '</style>',
+        f'<rect width="{width}" height="{height}" fill="{CLAUDE_BG}"/>',
+    ]
+
+    # Header: Clawd, then "Claude Code Wrapped <year>"
+    parts.append(_generate_clawd_svg(WRAPPED_PADDING, WRAPPED_PADDING + 20, 12))
+    title_x = WRAPPED_PADDING + 8 * 12 + 40
+    parts.append(
+        f'<text x="{title_x}" y="{WRAPPED_PADDING + 110}" class="headline">Claude Code Wrapped '
+        f'<tspan class="year">{wrapped.year}</tspan></text>'
+    )
+
+    tiles = [
+        (format_tokens(wrapped.total_tokens), "tokens"),
+        (f"${wrapped.cost:,.0f}" if wrapped.cost >= 100 else f"${wrapped.cost:,.2f}", "at API prices"),
+        (str(wrapped.active_days), "active days"),
+        (f"{wrapped.longest_streak}d", "longest streak"),
+    ]
+    tile_width = (inner - TILE_GAP * (len(tiles) - 1)) // len(tiles)
+    for i, (value, label) in enumerate(tiles):
+        x = WRAPPED_PADDING + i * (tile_width + TILE_GAP)
+        parts.append(_panel(x, tiles_y, tile_width, TILE_HEIGHT))
+        parts.append(f'<text x="{x + 40}" y="{tiles_y + 110}" class="value">{escape(value)}</text>')
+        parts.append(f'<text x="{x + 40}" y="{tiles_y + 170}" class="label">{label}</text>')
+
+    parts.append(heatmap.replace("<svg ", f'<svg x="{WRAPPED_PADDING}" y="{heatmap_y}" ', 1))
+
+    panel_width = (inner - TILE_GAP) // 2
+    parts.extend(_projects_panel(wrapped, WRAPPED_PADDING, panels_y, panel_width))
+    parts.extend(_facts_panel(wrapped, WRAPPED_PADDING + panel_width + TILE_GAP, panels_y, panel_width))
+
+    parts.append('</svg>')
+    return '\n'.join(parts)
+
+
+def export_wrapped(svg: str, output_path: Path, format_type: str) -> None:
+    """
+    Write a rendered wrapped image as SVG or PNG.
+
+    Args:
+        svg: Markup from render_wrapped_svg
+        output_path: Destination file
+        format_type: "svg" or "png"
+
+    Raises:
+        ImportError: If PNG is requested and CairoSVG is not installed
+        IOError: If file cannot be written
+    """
+    if format_type == "svg":
+        atomic_write_text(output_path, svg)
+        return
+    try:
+        import cairosvg
+    except ImportError:
+        raise ImportError(
+            "PNG wrapped export requires CairoSVG. "
+            "Install with: pip install claude-goblin[export] (or use --svg)"
+        )
+    with atomic_write(output_path, binary=True) as f:
+        cairosvg.svg2png(bytestring=svg.encode("utf-8"), write_to=f)
+
+
+def _panel(x: int, y: int, width: int, height: int) -> str:
+    return f'<rect x="{x}" y="{y}" width="{width}" height="{height}" rx="24" fill="{CLAUDE_DARK_GREY}"/>'
+
+
+def _projects_panel(wrapped: YearWrapped, x: int, y: int, width: int) -> list[str]:
+    parts = [
+        _panel(x, y, width, PANEL_HEIGHT),
+        f'<text x="{x + 40}" y="{y + 80}" class="panel-title">Top projects</text>',
+    ]
+    if not wrapped.top_projects:
+        parts.append(f'<text x="{x + 40}" y="{y + 150}" class="item-value">No project activity</text>')
+        return parts
+
+    largest = wrapped.top_projects[0][1]
+    bar_max = width - 80
+    for i, (folder, tokens) in enumerate(wrapped.top_projects):
+        row_y = y + 140 + i * 64
+        bar_width = max(8, int(bar_max * tokens / largest))
+        parts.append(
+            f'<rect x="{x + 40}" y="{row_y - 38}" width="{bar_width}" height="50" rx="8" '
+            f'fill="{CLAUDE_ORANGE}" fill-opacity="0.35"/>'
+        )
+        parts.append(f'<text x="{x + 56}" y="{row_y}" class="item">{escape(short_folder(folder))}</text>')
+        parts.append(
+            f'<text x="{x + width - 56}" y="{row_y}" class="item-value" text-anchor="end">'
+            f'{format_tokens(tokens)}</text>'
+        )
+    return parts
+
+
+def _facts_panel(wrapped: YearWrapped, x: int, y: int, width: int) -> list[str]:
+    facts = []
+    if wrapped.busiest_day:
+        busiest = date.fromisoformat(wrapped.busiest_day)
+        facts.append(("Busiest day", f"{busiest:%a %b} {busiest.day} · {format_tokens(wrapped.busiest_day_tokens)}"))
+    if wrapped.busiest_month:
+        facts.append(("Busiest month", datetime.strptime(wrapped.busiest_month, "%Y-%m").strftime("%B")))
+    if wrapped.busiest_hour is not None:
+        facts.append(("Busiest hour", f"{wrapped.busiest_hour:02d}:00–{(wrapped.busiest_hour + 1) % 24:02d}:00"))
+    if wrapped.top_model:
+        facts.append(("Favorite model", wrapped.top_model))
+    if wrapped.active_days:
+        facts.append(("Active day average", format_tokens(wrapped.total_tokens // wrapped.active_days)))
+    facts.append(("Prompts", f"{wrapped.prompts:,}"))
+
+    parts = [
+        _panel(x, y, width, PANEL_HEIGHT),
+        f'<text x="{x + 40}" y="{y + 80}" class="panel-title">Fun facts</text>',
+    ]
+    for i, (label, value) in enumerate(facts):
+        row_y = y + 140 + i * 52
+        parts.append(f'<text x="{x + 40}" y="{row_y}" class="item-value">{label}</text>')
+        parts.append(
+            f'<text x="{x + width - 40}" y="{row_y}" class="item" text-anchor="end">{escape(value)}</text>'
+        )
+    return parts
+
+
+#endregion
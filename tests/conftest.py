@@ -0,0 +1,62 @@
+from collections.abc import Callable
+from datetime import datetime, timezone
+from pathlib import Path
+
+import pytest
+
+from src.models.usage_record import TokenUsage, UsageRecord
+
+RecordFactory = Callable[..., UsageRecord]
+
+
+def _make_record(
+    uuid: str = "m1",
+    *,
+    when: datetime | None = None,
+    day: int = 1,
+    hour: int = 12,
+    minute: int = 0,
+    session: str = "s1",
+    message_type: str = "assistant",
+    model: str | None = "claude-sonnet-4-5",
+    folder: str = "/p/api",
+    branch: str | None = None,
+    tokens: int | None = 100,
+    output: int = 0,
+    usage: TokenUsage | None = None,
+    **fields,
+) -> UsageRecord:
+    """
+    One usage record: an assistant response on 2026-10-<day>, unless overridden.
+
+    User records get no model or usage. tokens=None leaves an assistant
+    record without usage; usage replaces the input/output-only default.
+    Remaining keywords are passed to UsageRecord as they are.
+    """
+    assistant = message_type == "assistant"
+    if usage is None and assistant and tokens is not None:
+        usage = TokenUsage(tokens, output, 0, 0)
+    return UsageRecord(
+        timestamp=when or datetime(2026, 10, day, hour, minute, tzinfo=timezone.utc),
+        session_id=session,
+        message_uuid=uuid,
+        message_type=message_type,
+        model=model if assistant else None,
+        folder=folder,
+        git_branch=branch,
+        version="2.0",
+        token_usage=usage if assistant else None,
+        **fields,
+    )
+
+
+@pytest.fixture
+def make_record() -> RecordFactory:
+    """Factory for UsageRecords; see _make_record for the defaults."""
+    return _make_record
+
+
+@pytest.fixture
+def db_path(tmp_path: Path) -> Path:
+    """A usage database path in the test's temporary directory (not yet created)."""
+    return tmp_path / "usage_history.db"
@@ -0,0 +1,42 @@
+from datetime import datetime, timezone
+from pathlib import Path
+
+from src.aggregation.activity_matrix import build_activity_matrix
+from src.storage.snapshot_db import get_hourly_range, save_snapshot
+from src.visualization.punchcard import render_punchcard_svg
+
+
+def test_hourly_rollup_folds_into_local_weekday_and_hour(db_path: Path, make_record) -> None:
+    busy = datetime(2026, 10, 13, 14, 10, tzinfo=timezone.utc)
+    quiet = datetime(2026, 10, 20, 14, 50, tzinfo=timezone.utc)
+    other = datetime(2026, 10, 15, 9, 0, tzinfo=timezone.utc)
+    save_snapshot([
+        make_record("u1", when=busy, message_type="user"),
+        make_record("a1", when=busy, tokens=300),
+        make_record("a2", when=quiet, tokens=200),
+        make_record("a3", when=other, tokens=100),
+    ], db_path=db_path, storage_mode="aggregate")
+
+    matrix = build_activity_matrix(get_hourly_range(db_path=db_path))
+
+    local = busy.astimezone()
+    assert matrix.tokens[local.weekday()][local.hour] == 500
+    assert matrix.prompts[local.weekday()][local.hour] == 1
+    assert sum(matrix.weekday_totals("tokens")) == 600
+    assert matrix.peak("tokens")[1:] == (local.hour, 500)
+
+    # A range ending before the second Tuesday drops it
+    first_week = build_activity_matrix(get_hourly_range("2026-10-12", "2026-10-16", db_path=db_path))
+    assert sum(first_week.hour_totals("tokens")) == 400
+
+
+def test_svg_draws_one_circle_per_weekday_hour() -> None:
+    matrix = build_activity_matrix([
+        {"hour": "2026-10-13T14:00:00+00:00", "prompts": 2, "total_tokens": 1_000},
+    ])
+
+    svg = render_punchcard_svg(matrix, metric="prompts")
+
+    assert svg.count("<circle") == 7 * 24
+    assert "2 prompts" in svg
+    assert "busiest" in svg
@@ -0,0 +1,78 @@
+from datetime import date
+from pathlib import Path
+
+from src.aggregation.daily_stats import AggregatedStats, DailyStats
+from src.commands.stats import month_notes
+from src.storage.snapshot_db import add_annotation, get_annotations, remove_annotations
+from src.visualization.export import MAX_ANNOTATION_NOTES, render_heatmap_svg
+
+
+def _stats(day: str, tokens: int) -> AggregatedStats:
+    daily = DailyStats(
+        date=day, total_prompts=1, total_responses=1, total_sessions=1,
+        total_tokens=tokens, input_tokens=tokens, output_tokens=0, cache_creation_tokens=0,
+        cache_read_tokens=0, models=set(), folders=set(),
+    )
+    return AggregatedStats(daily_stats={day: daily}, overall_totals=daily)
+
+
+def test_annotations_round_trip_within_a_window(db_path: Path) -> None:
+
+    assert add_annotation("2026-03-02", "launched v2", db_path)
+    assert not add_annotation("2026-03-02", "launched v2", db_path)
+    add_annotation("2026-03-02", "on-call", db_path)
+    add_annotation("2026-04-10", "offsite", db_path)
+
+    assert get_annotations(db_path=db_path) == {
+        "2026-03-02": ["launched v2", "on-call"],
+        "2026-04-10": ["offsite"],
+    }
+    assert get_annotations("2026-04-01", "2026-04-30", db_path) == {"2026-04-10": ["offsite"]}
+
+    assert remove_annotations("2026-03-02", "on-call", db_path) == 1
+    assert remove_annotations("2026-03-02", db_path=db_path) == 1
+    assert get_annotations(db_path=db_path) == {"2026-04-10": ["offsite"]}
+
+
+def test_svg_marks_annotated_days_and_lists_notes() -> None:
+    stats = _stats("2026-10-01", 1000)
+    annotations = {
+        "2026-10-01": ["launched <v2>"],
+        "2026-10-03": ["offsite"],
+        "2025-12-31": ["outside the window"],
+    }
+
+    svg = render_heatmap_svg(stats, start_date=date(2026, 10, 1), end_date=date(2026, 10, 7), annotations=annotations)
+    plain = render_heatmap_svg(stats, start_date=date(2026, 10, 1), end_date=date(2026, 10, 7))
+
+    assert svg.count('class="annotation"') == 4  # Two cell markers, two note bullets
+    assert "1,000 tokens\n◆ launched &lt;v2&gt;</title>" in svg
+    assert "2026-10-03: No activity\n◆ offsite</title>" in svg
+    assert "2026-10-01  launched &lt;v2&gt;</text>" in svg
+    assert "outside the window" not in svg
+    assert 'class="annotation"' not in plain
+    assert svg.split('"', 4)[1] == plain.split('"', 4)[1]  # Same width, taller canvas
+    assert int(svg.split('"', 4)[3]) > int(plain.split('"', 4)[3])
+
+
+def test_svg_notes_collapse_past_the_limit() -> None:
+    annotations = {f"2026-10-{day:02d}": [f"note {day}"] for day in range(1, MAX_ANNOTATION_NOTES + 4)}
+
+    svg = render_heatmap_svg(
+        _stats("2026-10-01", 10), start_date=date(2026, 10, 1), end_date=date(2026, 10, 31), annotations=annotations
+    )
+
+    assert f"note {MAX_ANNOTATION_NOTES - 1}</text>" in svg
+    assert f"note {MAX_ANNOTATION_NOTES}</text>" not in svg
+    assert "+4 more</text>" in svg
+
+
+def test_month_notes_list_the_months_annotations_by_day() -> None:
+    annotations = {
+        "2026-03-17": ["offsite"],
+        "2026-03-02": ["launched v2", "on-call"],
+        "2026-04-01": ["q2 planning"],
+    }
+
+    assert month_notes(annotations, "2026-03") == "02 launched v2; 02 on-call; 17 offsite"
+    assert month_notes(annotations, "2026-05") == ""
@@ -0,0 +1,34 @@
+import os
+from pathlib import Path
+
+import pytest
+
+from src.utils._system import atomic_write, atomic_write_text
+
+
+def test_failed_write_keeps_previous_file(tmp_path: Path) -> None:
+    target = tmp_path / "claude-usage.png"
+    target.write_bytes(b"old image")
+
+    with pytest.raises(RuntimeError):
+        with atomic_write(target, binary=True) as f:
+            f.write(b"half an ima")
+            raise RuntimeError("crash mid-render")
+
+    assert target.read_bytes() == b"old image"
+    assert os.listdir(tmp_path) == ["claude-usage.png"]
+
+
+def test_replaces_through_symlink_and_keeps_mode(tmp_path: Path) -> None:
+    real = tmp_path / "dotfiles" / "settings.json"
+    real.parent.mkdir()
+    real.write_text("{}")
+    real.chmod(0o600)
+    link = tmp_path / "settings.json"
+    link.symlink_to(real)
+
+    atomic_write_text(link, '{"hooks": {}}')
+
+    assert link.is_symlink()
+    assert real.read_text() == '{"hooks": {}}'
+    assert real.stat().st_mode & 0o777 == 0o600
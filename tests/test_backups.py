@@ -0,0 +1,41 @@
+import os
+from pathlib import Path
+
+from src.storage.backups import create_backup, list_backups, resolve_backup, rotate_backups
+
+
+def test_backups_list_newest_first_rotate_and_resolve(tmp_path: Path, db_path: Path, monkeypatch) -> None:
+    db_path.write_bytes(b"current")
+    legacy = tmp_path / "usage_history.db.bak"
+    legacy.write_bytes(b"legacy")
+    os.utime(legacy, (1_700_000_000, 1_700_000_000))  # November 2023
+    backup_dir = tmp_path / "backups"
+    backup_dir.mkdir()
+    for name in (
+        "usage_history-20260101-090000-remove.db",
+        "usage_history-20260301-090000-import.db",
+        "usage_history-20260201-090000-pre-restore.db",
+        "profile-work-20260401-090000-import.db",  # another database's backup
+    ):
+        (backup_dir / name).write_bytes(name.encode())
+    monkeypatch.setattr("src.storage.api.checkpoint_database", lambda path: None)
+    monkeypatch.setattr("src.storage.backups.get_backup_keep", lambda: 3)
+
+    backup = create_backup("import", db_path, backup_dir=backup_dir)
+
+    assert backup.read_bytes() == b"current"
+    listed = list_backups(db_path, backup_dir)
+    # The oldest timestamped backup was rotated out; the legacy .bak stays
+    assert [b.reason for b in listed] == ["import", "import", "pre-restore", "legacy"]
+    assert not (backup_dir / "usage_history-20260101-090000-remove.db").exists()
+    assert (backup_dir / "profile-work-20260401-090000-import.db").exists()
+
+    assert resolve_backup("1", db_path, backup_dir) == backup
+    assert resolve_backup("3", db_path, backup_dir).name == "usage_history-20260201-090000-pre-restore.db"
+    assert rotate_backups(db_path, keep=1, backup_dir=backup_dir) == [b.path for b in listed[1:3]]
+    try:
+        resolve_backup("9", db_path, backup_dir)
+    except ValueError as e:
+        assert "--list" in str(e)
+    else:
+        raise AssertionError("expected ValueError")
@@ -0,0 +1,24 @@
+from pathlib import Path
+
+from src.storage.snapshot_db import get_branch_stats, save_snapshot
+
+
+def test_branch_stats_attribute_tokens_and_cost_per_branch(db_path: Path, make_record) -> None:
+    save_snapshot([
+        make_record("a1", day=1, branch="main", session="s1", tokens=1_000_000),
+        make_record("a2", day=2, branch="feature/login", session="s2", tokens=2_000_000),
+        make_record("a3", day=2, branch="feature/login", session="s3", tokens=1_000_000),
+        make_record("a4", day=3, branch=None, session="s4", tokens=500),
+    ], db_path=db_path, storage_mode="full")
+
+    branches = get_branch_stats(db_path=db_path)
+
+    assert [(b["branch"], b["tokens"], b["sessions"], b["responses"]) for b in branches] == [
+        ("feature/login", 3_000_000, 2, 2),
+        ("main", 1_000_000, 1, 1),
+        (None, 500, 1, 1),
+    ]
+    assert branches[0]["cost"] == 3 * branches[1]["cost"] > 0
+
+    ranged = get_branch_stats("2026-10-02", "2026-10-02", db_path=db_path)
+    assert [(b["branch"], b["tokens"]) for b in ranged] == [("feature/login", 3_000_000)]
@@ -0,0 +1,46 @@
+from pathlib import Path
+
+from src.aggregation.commit_stats import correlate_commits, parse_git_log
+
+
+def test_parse_git_log_counts_commits_and_lines_per_day() -> None:
+    output = (
+        "\x1e2026-10-01\n\n12\t3\tsrc/app.py\n-\t-\tlogo.png\n"
+        "\x1e2026-10-01\n\n1\t1\tREADME.md\n"
+        "\x1e2026-10-02\n"
+    )
+
+    assert parse_git_log(output) == {
+        "2026-10-01": {"commits": 2, "lines_added": 13, "lines_deleted": 4},
+        "2026-10-02": {"commits": 1, "lines_added": 0, "lines_deleted": 0},
+    }
+
+
+def test_correlates_repo_usage_with_commits() -> None:
+    daily_projects = {
+        "2026-10-01": {"/src/api": {"tokens": 100, "cost": 2.0}, "/src/api-old": {"tokens": 50, "cost": 9.0}},
+        "2026-10-02": {"/src/api/worker": {"tokens": 300, "cost": 6.0}},
+        "2026-10-03": {"/src/api": {"tokens": 10, "cost": 0.5}},
+        "2026-10-04": {"-src-api": {"tokens": 20, "cost": 1.5}},  # aggregate-mode encoded name
+    }
+    git_days = {
+        "2026-10-01": {"commits": 2, "lines_added": 80, "lines_deleted": 20},
+        "2026-10-02": {"commits": 6, "lines_added": 250, "lines_deleted": 50},
+        "2026-10-05": {"commits": 1, "lines_added": 5, "lines_deleted": 0},
+    }
+
+    report = correlate_commits(Path("/src/api"), daily_projects, git_days)
+
+    assert [(d.date, d.commits, d.tokens) for d in report.days] == [
+        ("2026-10-01", 2, 100),
+        ("2026-10-02", 6, 300),
+        ("2026-10-03", 0, 10),
+        ("2026-10-04", 0, 20),
+        ("2026-10-05", 1, 0),
+    ]
+    assert report.cost == 10.0
+    assert report.cost_per_commit == 10.0 / 9
+    assert report.cost_per_100_lines == 10.0 / 405 * 100
+    assert report.days_without_commits == 2
+    assert report.days_without_usage == 1
+    assert report.correlation is not None and report.correlation > 0.8
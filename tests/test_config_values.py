@@ -0,0 +1,53 @@
+import json
+
+import pytest
+
+from src.config import user_config
+
+
+def test_parse_config_value_normalizes_plan() -> None:
+    key = user_config.resolve_config_key("plan")
+
+    assert key == "plan_type"
+    assert user_config.parse_config_value(key, "Max-20x") == "max_20x"
+    with pytest.raises(ValueError):
+        user_config.parse_config_value(key, "enterprise")
+
+
+def test_parse_config_value_checks_types() -> None:
+    assert user_config.parse_config_value("store_content", "yes") is True
+    assert user_config.parse_config_value("content_max_total_mb", "250") == 250
+    assert user_config.parse_config_value("export_output", "~/{year}.{format}") == "~/{year}.{format}"
+    with pytest.raises(ValueError):
+        user_config.parse_config_value("content_max_record_kb", "-1")
+    with pytest.raises(ValueError):
+        user_config.parse_config_value("export_output", "~/{month}.png")
+    with pytest.raises(KeyError):
+        user_config.parse_config_value("device_id", "abc")
+
+
+def test_set_and_unset_config_value(tmp_path, monkeypatch) -> None:
+    config_path = tmp_path / "goblin_config.json"
+    config_path.write_text(json.dumps({"storage_mode": "full"}))
+    monkeypatch.setattr(user_config, "CONFIG_PATH", config_path)
+
+    user_config.set_config_value("export_format", "svg")
+    assert user_config.get_export_format() == "svg"
+    assert user_config.get_storage_mode() == "full"
+
+    assert user_config.unset_config_value("export_format") is True
+    assert user_config.unset_config_value("export_format") is False
+    assert json.loads(config_path.read_text()) == {"storage_mode": "full"}
+
+
+def test_env_override_beats_config(tmp_path, monkeypatch) -> None:
+    config_path = tmp_path / "goblin_config.json"
+    config_path.write_text(json.dumps({"plan_type": "pro", "storage_mode": "full"}))
+    monkeypatch.setattr(user_config, "CONFIG_PATH", config_path)
+
+    monkeypatch.setenv("CCG_PLAN", "max-5x")
+    monkeypatch.setenv("CCG_STORAGE_MODE", "sideways")
+    assert user_config.get_plan_type() == "max_5x"
+    # Invalid values are ignored rather than replacing the config
+    assert user_config.get_storage_mode() == "full"
+    assert user_config.get_env_override("storage_mode") == ("CCG_STORAGE_MODE", None)
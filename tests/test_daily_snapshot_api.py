@@ -0,0 +1,54 @@
+from pathlib import Path
+
+import pytest
+
+from src.storage import api, snapshot_db
+
+SONNET = {"responses": 2, "input_tokens": 100, "output_tokens": 50}
+HAIKU = {"responses": 1, "input_tokens": 30, "output_tokens": 20}
+
+
+@pytest.fixture(autouse=True)
+def sqlite_backend(monkeypatch) -> None:
+    monkeypatch.setattr("src.storage.api.get_backend_module", lambda: snapshot_db)
+
+
+def _day(db_path: Path) -> dict:
+    [day] = snapshot_db.load_daily_snapshots(db_path=db_path)
+    return day
+
+
+def _model_tokens(day: dict) -> dict[str, int]:
+    return {model: mix["total_tokens"] for model, mix in day["models"].items()}
+
+
+def test_upsert_replaces_the_day_and_its_model_split(db_path: Path) -> None:
+    totals = {"prompts": 2, "responses": 3, "sessions": 1, "input_tokens": 130, "output_tokens": 70}
+    api.upsert_daily_snapshot(
+        "2026-10-01", totals, db=db_path, models={"claude-sonnet-4-5": SONNET, "claude-haiku-4-5": HAIKU}
+    )
+
+    day = _day(db_path)
+    assert (day["responses"], day["total_tokens"]) == (3, 200)
+    assert _model_tokens(day) == {"claude-sonnet-4-5": 150, "claude-haiku-4-5": 50}
+
+    api.upsert_daily_snapshot("2026-10-01", {"responses": 1, "input_tokens": 40}, db=db_path)
+
+    day = _day(db_path)
+    assert (day["responses"], day["total_tokens"]) == (1, 40)
+    assert _model_tokens(day) == {"unknown": 40}
+
+
+def test_increment_adds_to_the_day_and_its_model_split(db_path: Path) -> None:
+    api.increment_daily_snapshot("2026-10-01", SONNET, db=db_path, models={"claude-sonnet-4-5": SONNET})
+    api.increment_daily_snapshot("2026-10-01", HAIKU, db=db_path, models={"claude-haiku-4-5": HAIKU})
+    api.increment_daily_snapshot(
+        "2026-10-01",
+        {"responses": -1, "input_tokens": -30, "output_tokens": -20},
+        db=db_path,
+        models={"claude-haiku-4-5": {"responses": -1, "input_tokens": -30, "output_tokens": -20}},
+    )
+
+    day = _day(db_path)
+    assert (day["responses"], day["total_tokens"]) == (2, 150)
+    assert _model_tokens(day) == {"claude-sonnet-4-5": 150, "claude-haiku-4-5": 0}
@@ -0,0 +1,94 @@
+from datetime import datetime, timezone
+
+from src.aggregation.daily_stats import (
+    NO_BRANCH,
+    DailyStats,
+    GroupBy,
+    aggregate,
+    aggregate_all,
+    average_by_weekday,
+    summarize_by_month,
+)
+
+
+def _day(date: str, tokens: int, cost: float = 0.0, sessions: int = 1) -> DailyStats:
+    return DailyStats(
+        date=date,
+        total_prompts=1,
+        total_responses=1,
+        total_sessions=sessions,
+        total_tokens=tokens,
+        input_tokens=tokens,
+        output_tokens=0,
+        cache_creation_tokens=0,
+        cache_read_tokens=0,
+        models=set(),
+        folders=set(),
+        cost=cost,
+    )
+
+
+def test_average_by_weekday_counts_idle_days_as_zero() -> None:
+    # 2026-01-05 and 2026-01-12 are Mondays; the second one is idle
+    stats = {"2026-01-05": _day("2026-01-05", 100, 2.0), "2026-01-12": _day("2026-01-12", 0)}
+
+    averages = average_by_weekday(stats)
+
+    monday = averages[0]
+    assert monday.weekday == "Mon"
+    assert (monday.days, monday.active_days) == (2, 1)
+    assert monday.avg_tokens == 50
+    assert monday.avg_cost == 1.0
+    assert averages[1].days == 1 and averages[1].avg_tokens == 0
+
+
+def test_summarize_by_month_fills_gaps_and_computes_change() -> None:
+    stats = {
+        "2025-12-30": _day("2025-12-30", 100, 1.0, sessions=2),
+        "2026-02-01": _day("2026-02-01", 150, 3.0),
+        "2026-03-01": _day("2026-03-01", 300, 1.5),
+    }
+
+    months = summarize_by_month(stats)
+
+    assert [m.month for m in months] == ["2025-12", "2026-01", "2026-02", "2026-03"]
+    assert months[0].sessions == 2 and months[0].tokens_change is None
+    assert months[1].total_tokens == 0 and months[1].tokens_change == -100
+    assert months[2].tokens_change is None
+    assert months[3].tokens_change == 100
+    assert months[3].cost_change == -50
+
+
+def test_active_hours_counts_distinct_five_minute_buckets(make_record) -> None:
+    records = [
+        make_record(f"m{minute}{folder}", when=datetime(2026, 1, 5, 10, minute, tzinfo=timezone.utc),
+                    message_type="user", folder=folder)
+        for minute, folder in [(0, "/p"), (4, "/p"), (5, "/p"), (31, "/p"), (45, "aggregate")]
+    ]
+
+    # 10:00 and 10:04 share a bucket; 10:05 and 10:31 add two more;
+    # aggregate-mode placeholders never count
+    stats = aggregate_all(records)
+
+    assert stats.overall_totals.active_hours == 15 / 60
+    assert sum(d.active_hours for d in stats.daily_stats.values()) == 15 / 60
+
+
+def test_aggregate_groups_by_any_dimension(make_record) -> None:
+    records = [
+        make_record("a", day=12, session="s12", model="claude-opus-4-6", branch="main", tokens=100),
+        make_record("b", day=12, session="s12", message_type="user", branch="main"),
+        make_record("c", day=19, session="s19", tokens=50),
+        make_record("d", day=20, session="s20", model="claude-opus-4-6", branch="main", tokens=25),
+    ]
+
+    weeks = aggregate(records, GroupBy.WEEK)
+    assert {k: v.total_tokens for k, v in weeks.items()} == {"2026-W42": 100, "2026-W43": 75}
+    models = aggregate(records, GroupBy.MODEL)
+    assert {k: (v.total_responses, v.total_prompts) for k, v in models.items()} == {
+        "claude-opus-4-6": (2, 0), "claude-sonnet-4-5": (1, 0),
+    }
+    branches = aggregate(records, GroupBy.BRANCH)
+    assert {k: v.total_sessions for k, v in branches.items()} == {"main": 2, NO_BRANCH: 1}
+    assert list(aggregate(records, GroupBy.MONTH)) == ["2026-10"]
+    assert aggregate(records, GroupBy("project"))["/p/api"].total_tokens == 175
@@ -0,0 +1,25 @@
+from datetime import date
+from pathlib import Path
+
+from src.aggregation.daily_stats import AggregatedStats, DailyStats
+from src.visualization import day_report
+
+
+def _stats(day: str, tokens: int) -> DailyStats:
+    return DailyStats(day, 1, 1, 1, tokens, tokens, 0, 0, 0, set(), set())
+
+
+def test_html_report_writes_every_day_page_and_the_index(monkeypatch, tmp_path: Path) -> None:
+    days = [f"2026-03-{n:02d}" for n in range(1, 21)]
+    projects = {"/work/api": {"tokens": 10, "cost": 0.1}}
+    details = {day: day_report.DayDetails(date=day, projects=projects) for day in days}
+    monkeypatch.setattr(day_report, "load_day_details", lambda start, end: details)
+    stats = AggregatedStats(daily_stats={day: _stats(day, 10) for day in days}, overall_totals=_stats("", 200))
+    output = tmp_path / "report.html"
+
+    pages = day_report.export_html_report(stats, output, start_date=date(2026, 3, 1), end_date=date(2026, 3, 31))
+
+    assert pages == 20
+    assert sorted(p.name for p in (tmp_path / "report-days").iterdir()) == [f"{day}.html" for day in days]
+    assert 'href="report-days/2026-03-20.html"' in output.read_text(encoding="utf-8")
+    assert "../report.html" in (tmp_path / "report-days" / "2026-03-01.html").read_text(encoding="utf-8")
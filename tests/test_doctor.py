@@ -0,0 +1,136 @@
+import json
+import sqlite3
+from datetime import datetime
+from pathlib import Path
+
+from src.commands import doctor, update_usage
+from src.errors import GoblinError
+from src.models.usage_record import QuarantinedEntry
+from src.storage import snapshot_db
+
+
+def test_json_report_lists_severity_and_fix(monkeypatch, capsys) -> None:
+    monkeypatch.setattr(
+        doctor, "check_usage_hook",
+        lambda stale_days: doctor.CheckResult("Usage hook", "fail", "Never ran", fix="ccg setup hooks usage"),
+    )
+    monkeypatch.setattr(doctor, "check_quarantine", lambda: doctor.CheckResult("Quarantine", "ok", "Clean"))
+    monkeypatch.setattr(doctor, "check_data_dir", lambda: doctor.CheckResult("Data directory", "ok", "2 transcripts"))
+    monkeypatch.setattr(doctor, "check_database", lambda: [])
+    monkeypatch.setattr(doctor, "check_pricing", lambda: doctor.CheckResult("Pricing", "ok", "All priced"))
+    monkeypatch.setattr(
+        doctor, "check_transcript_format", lambda: doctor.CheckResult("Transcript format", "ok", "Known")
+    )
+
+    exit_code = doctor.run(console=None, as_json=True)
+    report = json.loads(capsys.readouterr().out)
+
+    assert exit_code == 1
+    assert report["healthy"] is False
+    checks = {check["id"]: check for check in report["checks"]}
+    assert checks["usage_hook"] == {
+        "id": "usage_hook",
+        "name": "Usage hook",
+        "status": "fail",
+        "severity": "error",
+        "detail": "Never ran",
+        "fix": "ccg setup hooks usage",
+    }
+    assert checks["quarantine"]["severity"] == "info"
+    assert checks["quarantine"]["fix"] is None
+
+
+def test_database_health_finds_missing_indexes_and_orphaned_snapshots(db_path: Path) -> None:
+    snapshot_db.init_database(db_path)
+    conn = sqlite3.connect(db_path)
+    conn.execute("DROP INDEX idx_usage_records_date")
+    conn.execute(
+        "INSERT INTO daily_snapshots (date, total_prompts, total_responses, total_sessions, total_tokens, "
+        "input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, snapshot_timestamp) "
+        "VALUES ('2026-10-01', 1, 1, 1, 500, 200, 300, 0, 0, '2026-10-01T12:00:00')"
+    )
+    conn.commit()
+    conn.close()
+
+    health = snapshot_db.get_database_health(db_path)
+
+    assert health["integrity_errors"] == []
+    assert health["missing_indexes"] == ["idx_usage_records_date"]
+    assert health["orphaned_snapshot_days"] == 1
+
+
+def test_unknown_fields_accumulate_and_warn_until_known(db_path: Path, monkeypatch) -> None:
+    snapshot_db.save_unknown_fields({"costUSD": 2, "message.usage.reasoning_tokens": 1}, db_path)
+    snapshot_db.save_unknown_fields({"costUSD": 3, "message.usage.input_tokens": 1}, db_path)
+    monkeypatch.setattr("src.storage.api.get_unknown_fields", lambda: snapshot_db.get_unknown_fields(db_path))
+
+    fields = snapshot_db.get_unknown_fields(db_path)
+    result = doctor.check_transcript_format()
+
+    assert [(row["field"], row["occurrences"]) for row in fields][0] == ("costUSD", 5)
+    assert result.status == "warn"
+    assert "costUSD (5x), message.usage.reasoning_tokens (1x);" in result.detail
+    assert "input_tokens" not in result.detail
+
+
+def test_dismiss_only_clears_the_listed_quarantine_entries(db_path: Path, monkeypatch) -> None:
+    monkeypatch.setattr("src.storage.api.get_db_path", lambda: db_path)
+    monkeypatch.setattr("src.storage.api.get_backend_module", lambda: snapshot_db)
+    monkeypatch.setattr(doctor, "is_read_only", lambda: False)
+    monkeypatch.setattr(doctor, "QUARANTINE_SHOW_LIMIT", 2)
+
+    def entry(entry_id: str) -> QuarantinedEntry:
+        return QuarantinedEntry("/t/s1.jsonl", entry_id, "s1", "", "missing timestamp", "{}")
+
+    snapshot_db.save_quarantined_entries([entry("e1"), entry("e2"), entry("e3")], db_path)
+    printed = []
+    console = type("Console", (), {"print": lambda self, *args, **kwargs: printed.append(args)})()
+
+    doctor.review_quarantine(console, dismiss=True)
+
+    # Two were listed and dismissed; the one not shown still warns
+    assert ("[green]Dismissed 2 entries[/green]",) in printed
+    assert doctor.check_quarantine().detail == "1 entries with bad timestamps held back"
+    assert [row["entry_id"] for row in snapshot_db.get_quarantined_entries(db_path=db_path)] == ["e3"]
+    assert snapshot_db.dismiss_quarantined_entries([("/t/s1.jsonl", "e1")], db_path) == 0
+
+
+def test_usage_hook_check_reads_the_hook_run_log(db_path: Path, monkeypatch) -> None:
+    monkeypatch.setattr("src.storage.api.get_db_path", lambda: db_path)
+    monkeypatch.setattr("src.storage.api.get_backend_module", lambda: snapshot_db)
+    installed = ["ccg update usage"]
+    monkeypatch.setattr(doctor, "_installed_usage_hook_commands", lambda: installed)
+    now = datetime(2026, 10, 16, 12)
+
+    def check() -> tuple[str, str]:
+        result = doctor.check_usage_hook(stale_days=3, now=now)
+        return result.status, result.detail
+
+    assert check() == ("warn", "Installed without health tracking")
+    installed[0] = "ccg update usage --hook"
+    assert check() == ("fail", "Installed but has never reported a run")
+
+    # Manual runs don't count as the hook firing
+    snapshot_db.record_update_run("2026-10-16T11:00:00", "2026-10-16T11:00:01", "manual", 0, db_path=db_path)
+    snapshot_db.record_update_run("2026-10-12T09:30:00", "2026-10-12T09:30:01", "hook", 0, db_path=db_path)
+    assert check() == ("fail", "Hasn't fired in 4 days (last run 2026-10-12 09:30)")
+
+    # A failing hook run is logged by update_usage.run and reported
+    def fail(console, **kwargs):
+        raise GoblinError("Usage database is locked")
+
+    class Clock(datetime):
+        @classmethod
+        def now(cls, tz=None):
+            return now
+
+    monkeypatch.setattr(update_usage, "ingest_token_usage", fail)
+    monkeypatch.setattr(update_usage, "datetime", Clock)
+    update_usage.run(type("Console", (), {"print": lambda self, *args, **kwargs: None})(), trigger="hook")
+    assert check() == ("fail", "Last run failed: Usage database is locked")
+
+    snapshot_db.record_update_run("2026-10-16T12:30:00", "2026-10-16T12:30:01", "hook", 0, db_path=db_path)
+    assert check() == ("warn", "Last fired 2026-10-16 12:30; 1 of the last 3 runs failed")
+
+    monkeypatch.setattr(doctor, "_installed_usage_hook_commands", lambda: [])
+    assert check()[0] == "warn"
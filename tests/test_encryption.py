@@ -0,0 +1,132 @@
+import sqlite3
+import sys
+import types
+from pathlib import Path
+
+import pytest
+
+from src.errors import EncryptionError
+from src.storage import encryption
+
+
+def test_connect_opens_plain_sqlite_when_encryption_is_off(tmp_path: Path, monkeypatch) -> None:
+    monkeypatch.setattr(encryption, "is_encryption_enabled", lambda: False)
+    conn = encryption.connect(tmp_path / "usage_history.db")
+    try:
+        assert isinstance(conn, sqlite3.Connection)
+    finally:
+        conn.close()
+    assert encryption.row_factory() is sqlite3.Row
+
+
+def test_missing_key_or_sqlcipher_raises_with_hint(monkeypatch) -> None:
+    monkeypatch.delenv(encryption.KEY_ENV_VAR, raising=False)
+    monkeypatch.setattr(encryption, "_keychain_key", lambda: None)
+    with pytest.raises(EncryptionError) as error:
+        encryption.get_database_key()
+    assert encryption.KEY_ENV_VAR in error.value.hint
+
+    monkeypatch.setenv(encryption.KEY_ENV_VAR, "secret")
+    assert encryption.get_database_key() == "secret"
+
+    monkeypatch.setitem(sys.modules, "sqlcipher3", None)
+    with pytest.raises(EncryptionError, match="sqlcipher3"):
+        encryption.sqlcipher_module()
+
+
+def test_key_pragma_quotes_the_key() -> None:
+    assert encryption._key_pragma("it's") == "PRAGMA key = 'it''s'"
+    assert encryption._key_pragma("k", "encrypted") == "PRAGMA encrypted.key = 'k'"
+
+
+class _CipherError(Exception):
+    pass
+
+
+def _fake_sqlcipher() -> types.ModuleType:
+    """
+    Stand-in for sqlcipher3.dbapi2: sqlite3 underneath, but raising its own
+    DB-API exception classes the way sqlcipher3 does.
+    """
+    dbapi2 = types.ModuleType("sqlcipher3.dbapi2")
+    dbapi2.Error = _CipherError
+    dbapi2.DatabaseError = type("DatabaseError", (_CipherError,), {})
+    dbapi2.OperationalError = type("OperationalError", (dbapi2.DatabaseError,), {})
+    dbapi2.IntegrityError = type("IntegrityError", (dbapi2.DatabaseError,), {})
+    dbapi2.Row = sqlite3.Row
+
+    def translate(error: sqlite3.Error) -> Exception:
+        return getattr(dbapi2, type(error).__name__, dbapi2.DatabaseError)(*error.args)
+
+    class Proxy:
+        def __init__(self, target) -> None:
+            object.__setattr__(self, "_target", target)
+
+        def __getattr__(self, name: str):
+            value = getattr(self._target, name)
+            if not callable(value):
+                return value
+
+            def call(*args, **kwargs):
+                try:
+                    result = value(*args, **kwargs)
+                except sqlite3.Error as e:
+                    raise translate(e) from e
+                return Proxy(result) if isinstance(result, sqlite3.Cursor) else result
+            return call
+
+        def __setattr__(self, name: str, value) -> None:
+            setattr(self._target, name, value)
+
+        def __iter__(self):
+            return iter(self._target)
+
+    dbapi2.connect = lambda *args, **kwargs: Proxy(sqlite3.connect(*args, **kwargs))
+    package = types.ModuleType("sqlcipher3")
+    package.dbapi2 = dbapi2
+    return package
+
+
+def test_encrypted_connections_retry_locks_and_init_over_duplicates(db_path: Path, monkeypatch, make_record) -> None:
+    from src.errors import DatabaseLockedError
+    from src.storage import snapshot_db
+
+    cipher = _fake_sqlcipher()
+    monkeypatch.setitem(sys.modules, "sqlcipher3", cipher)
+    monkeypatch.setattr(encryption, "is_encryption_enabled", lambda: True)
+    monkeypatch.setenv(encryption.KEY_ENV_VAR, "secret")
+    monkeypatch.setattr(snapshot_db, "BUSY_TIMEOUT_SECONDS", 0.05)
+    sleeps = []
+    monkeypatch.setattr(snapshot_db.time, "sleep", sleeps.append)
+    snapshot_db.init_database(db_path)
+
+    # A lock held past every retry surfaces as DatabaseLockedError, not
+    # as sqlcipher3's own OperationalError
+    blocker = sqlite3.connect(db_path)
+    blocker.execute("BEGIN IMMEDIATE")
+    try:
+        with pytest.raises(DatabaseLockedError):
+            snapshot_db.add_session_tags("s1", ["hook"], db_path=db_path)
+        assert len(sleeps) == snapshot_db.LOCK_RETRIES
+    finally:
+        blocker.rollback()
+        blocker.close()
+    snapshot_db.add_session_tags("s1", ["hook"], db_path=db_path)
+    assert snapshot_db.get_session_tags(db_path) == {"s1": ["hook"]}
+
+    # A response stored under two sessions (an old fork duplicate)
+    record = make_record("m1:r1", branch="main", tokens=10, output=5)
+    snapshot_db.save_snapshot([record], db_path=db_path, storage_mode="full")
+    plain = sqlite3.connect(db_path)
+    plain.execute("DROP INDEX idx_usage_records_response")
+    plain.execute("""
+        INSERT INTO usage_records (date, timestamp, session_id, message_uuid, message_type, model, folder,
+            git_branch, version, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, total_tokens)
+        SELECT date, timestamp, 's2', message_uuid, message_type, model, folder, git_branch, version,
+            input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, total_tokens
+        FROM usage_records
+    """)
+    plain.commit()
+    plain.close()
+
+    snapshot_db.init_database(db_path)
@@ -0,0 +1,46 @@
+import sqlite3
+from pathlib import Path
+
+import pytest
+
+from src.config import settings
+from src.data.jsonl_parser import parse_jsonl_file
+from src.errors import DatabaseLockedError, MissingDataDirError, TranscriptParseError
+from src.storage import snapshot_db
+from src.storage.snapshot_db import _retry_on_lock
+
+
+def test_missing_data_dir_is_still_a_file_not_found_error(tmp_path: Path, monkeypatch) -> None:
+    monkeypatch.setattr(settings, "get_claude_data_dirs", lambda: [tmp_path / "missing"])
+
+    with pytest.raises(FileNotFoundError) as raised:
+        settings.get_claude_jsonl_files()
+    assert isinstance(raised.value, MissingDataDirError)
+    assert "--data-dir" in raised.value.hint
+
+
+def test_unexpected_entry_shape_names_the_file_and_line(tmp_path: Path) -> None:
+    transcript = tmp_path / "session.jsonl"
+    transcript.write_text(
+        '{"type": "user", "timestamp": "2026-10-01T10:00:00Z"}\n'
+        '{"type": "assistant", "timestamp": "2026-10-01T10:00:01Z", "sessionId": "s1", "message": "text"}\n'
+    )
+
+    with pytest.raises(TranscriptParseError) as raised:
+        list(parse_jsonl_file(transcript))
+    assert raised.value.file == str(transcript)
+    assert raised.value.line == 2
+    assert "--strict" in raised.value.hint
+
+
+def test_a_lock_outlasting_the_retries_raises_database_locked(tmp_path: Path, monkeypatch) -> None:
+    monkeypatch.setattr(snapshot_db, "LOCK_RETRY_DELAY_SECONDS", 0)
+
+    @_retry_on_lock
+    def write(db_path: Path) -> None:
+        raise sqlite3.OperationalError("database is locked")
+
+    with pytest.raises(DatabaseLockedError) as raised:
+        write(tmp_path / "usage_history.db")
+    assert raised.value.db_path == tmp_path / "usage_history.db"
+    assert raised.value.hint
@@ -0,0 +1,63 @@
+from src.aggregation.event_stats import summarize_compactions, summarize_events
+
+
+def _event(session: str, seconds: int, event_type: str = "api_error", ends_turn: bool = False, tokens: int = 0) -> dict:
+    return {
+        "session_id": session,
+        "timestamp": f"2026-10-01T10:{seconds // 60:02d}:{seconds % 60:02d}",
+        "event_type": event_type,
+        "detail": "529 Overloaded" if event_type == "api_error" else "Request interrupted by user",
+        "ends_turn": ends_turn,
+        "turn_tokens": tokens,
+    }
+
+
+def test_counts_storms_and_tokens_burned_by_failed_turns() -> None:
+    events = [
+        _event("a", 0), _event("a", 30), _event("a", 60), _event("a", 90),  # one storm of four
+        _event("a", 600), _event("a", 610),  # too short
+        _event("a", 900, "interrupted", ends_turn=True, tokens=1200),
+        _event("b", 620), _event("b", 630),  # follows a's errors, but another session
+        _event("b", 700, "interrupted", ends_turn=True, tokens=300),
+    ]
+
+    summary = summarize_events(events)
+
+    assert summary.api_errors == 8
+    assert summary.interruptions == 2
+    assert summary.retry_storms == 1
+    assert summary.failed_turns == 2
+    assert summary.burned_tokens == 1500
+    assert summary.sessions_affected == 2
+    assert summary.top_errors == [("529 Overloaded", 8)]
+
+
+def _compaction(session: str, folder: str, messages: int | None, trigger: str = "auto") -> dict:
+    return {
+        "session_id": session,
+        "timestamp": "2026-10-01T10:00:00",
+        "event_type": "compaction",
+        "detail": trigger,
+        "folder": folder,
+        "ends_turn": False,
+        "turn_tokens": 0,
+        "messages_before": messages,
+        "context_tokens": 150_000,
+    }
+
+
+def test_compactions_report_chronic_projects() -> None:
+    events = [
+        _compaction("a", "/big", 40), _compaction("a", "/big", 20), _compaction("b", "/big", None),
+        _compaction("b", "/big", 30, trigger="manual"),
+        _compaction("c", "/small", 90),
+        _event("c", 0),
+    ]
+
+    summary = summarize_compactions(events)
+
+    assert (summary.compactions, summary.auto, summary.sessions) == (5, 4, 3)
+    assert summary.avg_messages == 45
+    assert [(p.folder, p.per_session) for p in summary.chronic_projects] == [("/big", 2.0)]
+    # Compactions are not errors
+    assert summarize_events(events).api_errors == 1
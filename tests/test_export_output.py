@@ -0,0 +1,195 @@
+import re
+from datetime import date, datetime
+from pathlib import Path
+
+import pytest
+
+from src.aggregation.daily_stats import AggregatedStats, DailyStats
+from src.commands.export import DEFAULT_EXPORT_DIR, resolve_export_template
+from src.hooks import png
+from src.visualization.export import (
+    HEATMAP_THEMES,
+    ColorScale,
+    _build_weeks,
+    _intensity_color,
+    _legend_buckets,
+    _resolve_range,
+    render_heatmap_svg,
+)
+
+
+def test_template_expands_placeholders_and_home() -> None:
+    path = resolve_export_template("~/Pictures/claude/{year}-{metric}.{format}", "png", 2026)
+
+    assert path == Path.home() / "Pictures" / "claude" / "2026-tokens.png"
+
+
+def test_template_directory_relative_and_missing_extension() -> None:
+    today = datetime.now().date().isoformat()
+
+    assert resolve_export_template("~/out/", "svg", 2026) == Path.home() / "out" / "claude-usage.svg"
+    assert resolve_export_template("shots/{date}", "png", 2026) == DEFAULT_EXPORT_DIR / "shots" / f"{today}.png"
+    assert (
+        resolve_export_template("/tmp/{from}_{to}", "svg", 2026, date(2026, 1, 1), date(2026, 3, 31))
+        == Path("/tmp/2026-01-01_2026-03-31.svg")
+    )
+
+
+def test_template_rejects_unknown_placeholder() -> None:
+    with pytest.raises(ValueError, match="theme"):
+        resolve_export_template("{year}-{theme}.png", "png", 2026)
+
+
+def test_custom_range_wins_over_year_and_rejects_a_reversed_window() -> None:
+    assert _resolve_range(2025, date(2026, 10, 13), date(2026, 10, 15)) == (
+        date(2026, 10, 13), date(2026, 10, 15), "Your Claude Code activity from Oct 13, 2026 to Oct 15, 2026",
+    )
+    assert _resolve_range(None, None, date(2026, 10, 15))[0] == date(2025, 10, 16)
+    assert _resolve_range(2025, None, None)[:2] == (date(2025, 1, 1), date(2025, 12, 31))
+
+    with pytest.raises(ValueError, match="after end date"):
+        _resolve_range(None, date(2026, 10, 15), date(2026, 10, 13))
+
+
+def test_range_inside_one_week_is_a_single_padded_column() -> None:
+    # Tuesday to Thursday
+    weeks = _build_weeks(AggregatedStats(daily_stats={}, overall_totals=None), date(2026, 10, 13), date(2026, 10, 15))
+
+    assert [[day for _, day in week] for week in weeks] == [
+        [None, None, date(2026, 10, 13), date(2026, 10, 14), date(2026, 10, 15), None, None],
+    ]
+
+
+def test_month_labels_carry_the_year_across_a_year_boundary() -> None:
+    stats = AggregatedStats(daily_stats={}, overall_totals=None)
+
+    labels = re.findall(
+        r'class="month-label">([^<]+)<',
+        render_heatmap_svg(stats, start_date=date(2026, 11, 20), end_date=date(2027, 2, 10)),
+    )
+    assert labels == ["Nov", "Dec", "Jan '27", "Feb"]
+
+    one_year = render_heatmap_svg(stats, start_date=date(2026, 11, 20), end_date=date(2026, 12, 31))
+    assert re.findall(r'class="month-label">([^<]+)<', one_year) == ["Nov", "Dec"]
+
+
+def test_svg_tooltips_show_cost_and_top_project() -> None:
+    day = DailyStats(
+        date="2026-10-01", total_prompts=3, total_responses=3, total_sessions=1,
+        total_tokens=1000, input_tokens=1000, output_tokens=0, cache_creation_tokens=0,
+        cache_read_tokens=0, models=set(), folders=set(), cost=9.0,
+    )
+    stats = AggregatedStats(daily_stats={"2026-10-01": day}, overall_totals=day)
+    day_projects = {"2026-10-01": {
+        "/home/me/R&D/api": {"tokens": 750, "cost": 1.5},
+        "/home/me/web": {"tokens": 250, "cost": 0.25},
+    }}
+
+    svg = render_heatmap_svg(
+        stats, start_date=date(2026, 10, 1), end_date=date(2026, 10, 1), day_projects=day_projects
+    )
+
+    assert "2026-10-01: 3 prompts, 1,000 tokens, $1.75\nTop project: R&amp;D/api (75%)" in svg
+
+
+def test_svg_links_active_days_only() -> None:
+    day = DailyStats(
+        date="2026-10-01", total_prompts=1, total_responses=1, total_sessions=1,
+        total_tokens=10, input_tokens=10, output_tokens=0, cache_creation_tokens=0,
+        cache_read_tokens=0, models=set(), folders=set(),
+    )
+    stats = AggregatedStats(daily_stats={"2026-10-01": day}, overall_totals=day)
+
+    svg = render_heatmap_svg(
+        stats, start_date=date(2026, 9, 30), end_date=date(2026, 10, 2), day_links="days/{date}.html"
+    )
+
+    assert svg.count("<a href=") == 1
+    assert '<a href="days/2026-10-01.html">' in svg
+
+
+def test_svg_legend_shows_token_thresholds() -> None:
+    day = DailyStats(
+        date="2026-10-01", total_prompts=1, total_responses=1, total_sessions=1,
+        total_tokens=1_600_000, input_tokens=1_600_000, output_tokens=0, cache_creation_tokens=0,
+        cache_read_tokens=0, models=set(), folders=set(),
+    )
+    stats = AggregatedStats(daily_stats={"2026-10-01": day}, overall_totals=day)
+
+    svg = render_heatmap_svg(stats, start_date=date(2026, 10, 1), end_date=date(2026, 10, 1))
+
+    assert "Less" not in svg and "More" not in svg
+    for label in ("0", "≤100K", "≤400K", "≤900K", "≤1.6M"):
+        assert f'class="legend-text">{label}</text>' in svg
+
+
+def test_color_scales_map_tokens_and_invert_for_the_legend() -> None:
+    values = [1_000, 2_000, 5_000, 100_000]
+
+    assert ColorScale("linear", values).ratio(50_000) == 0.5
+    assert ColorScale("sqrt", values).ratio(25_000) == 0.5
+    assert ColorScale("log", values).ratio(0) == 0.0
+    # Percentile ranks days, so evenly spaced colors however skewed the data
+    percentile = ColorScale("percentile", values)
+    assert [percentile.ratio(v) for v in values] == [0.25, 0.5, 0.75, 1.0]
+    assert [percentile.bound(q) for q in (0.25, 0.5, 0.75, 1.0)] == values
+    for kind in ("linear", "sqrt", "log"):
+        scale = ColorScale(kind, values)
+        assert abs(scale.ratio(scale.bound(0.5)) - 0.5) < 0.01
+
+    with pytest.raises(ValueError, match="Unknown heatmap scale"):
+        render_heatmap_svg(AggregatedStats(daily_stats={}, overall_totals=None), scale="cubic")
+
+
+def test_themes_span_their_gradient_stops() -> None:
+    values = [1_000, 100_000]
+    for theme, stops in HEATMAP_THEMES.items():
+        legend = _legend_buckets(ColorScale("linear", values, theme=theme))
+        assert legend[-1][0] == "rgb({},{},{})".format(*stops[-1])
+    assert _intensity_color(0.0, "viridis") == "rgb(68,1,84)"
+
+    with pytest.raises(ValueError, match="Unknown heatmap theme"):
+        render_heatmap_svg(AggregatedStats(daily_stats={}, overall_totals=None), theme="rainbow")
+
+
+def test_svg_colors_days_by_prompts_with_the_prompts_metric() -> None:
+    days = {
+        f"2026-10-0{n}": DailyStats(
+            date=f"2026-10-0{n}", total_prompts=prompts, total_responses=prompts, total_sessions=1,
+            total_tokens=tokens, input_tokens=tokens, output_tokens=0, cache_creation_tokens=0,
+            cache_read_tokens=0, models=set(), folders=set(),
+        )
+        for n, prompts, tokens in ((1, 40, 100), (2, 10, 5000))
+    }
+    stats = AggregatedStats(daily_stats=days, overall_totals=days["2026-10-01"])
+
+    svg = render_heatmap_svg(stats, start_date=date(2026, 10, 1), end_date=date(2026, 10, 2), metric="prompts")
+
+    # The day with more prompts gets the full-intensity color despite fewer tokens
+    peak = _intensity_color(1.0)
+    assert svg.index(f'fill="{peak}" class="day-cell"><title>2026-10-01') > 0
+    assert f'fill="{peak}" class="day-cell"><title>2026-10-02' not in svg
+    assert 'class="legend-text">Prompts/day</text>' in svg and "≤40<" in svg
+    with pytest.raises(ValueError, match="metric"):
+        render_heatmap_svg(stats, metric="cost")
+
+
+def test_png_hook_passes_theme_and_metric(tmp_path: Path, monkeypatch) -> None:
+    monkeypatch.setattr(png, "get_export_output_template", lambda: "~/shots/{year}-{metric}.{format}")
+    monkeypatch.setattr(png, "get_export_format", lambda: "png")
+    monkeypatch.setattr(png.sys, "platform", "linux")
+    settings = {"hooks": {"Stop": [{"matcher": "*", "hooks": [{"type": "command", "command": "ccg export"}]}]}}
+
+    class _Console:
+        def print(self, *args, **kwargs) -> None:
+            pass
+
+    png.setup(_Console(), settings, tmp_path / "settings.json", output="", theme="viridis", metric="prompts")
+
+    # The old hook is replaced; the new one keeps following the output template
+    assert [h["hooks"][0]["command"] for h in settings["hooks"]["Stop"]] == [
+        "ccg export --theme viridis --metric prompts > /dev/null 2>&1 &"
+    ]
+
+    png.setup(_Console(), settings, tmp_path / "settings.json", output="", theme="sepia")
+    assert len(settings["hooks"]["Stop"]) == 1 and "viridis" in settings["hooks"]["Stop"][0]["hooks"][0]["command"]
@@ -0,0 +1,83 @@
+import csv
+from pathlib import Path
+
+import pytest
+
+from src.commands.export import write_project_csvs
+
+
+def _rows(path: Path) -> list[list[str]]:
+    with open(path, newline="", encoding="utf-8") as f:
+        return list(csv.reader(f))
+
+
+def test_writes_one_csv_per_project_and_an_index(tmp_path: Path) -> None:
+    daily_projects = {
+        "2026-10-02": {"/work/acme/api": {"tokens": 300, "cost": 1.5}, "/home/me/api": {"tokens": 10, "cost": 0.01}},
+        "2026-10-01": {"/work/acme/api": {"tokens": 100, "cost": 0.5}},
+    }
+
+    index = write_project_csvs(daily_projects, tmp_path / "out")
+
+    assert [(row["project"], row["file"]) for row in index] == [
+        ("/work/acme/api", "api.csv"),
+        ("/home/me/api", "api-2.csv"),
+    ]
+    assert _rows(tmp_path / "out" / "api.csv") == [
+        ["date", "tokens", "cost"],
+        ["2026-10-01", "100", "0.5000"],
+        ["2026-10-02", "300", "1.5000"],
+    ]
+    assert _rows(tmp_path / "out" / "index.csv")[1] == [
+        "/work/acme/api", "api.csv", "2026-10-01", "2026-10-02", "2", "400", "2.0000",
+    ]
+
+
+def test_failed_project_export_keeps_the_previous_files(tmp_path: Path) -> None:
+    out_dir = tmp_path / "out"
+    write_project_csvs({"2026-10-01": {"/work/acme/api": {"tokens": 100, "cost": 0.5}}}, out_dir)
+
+    class _Unformattable(float):
+        def __format__(self, spec: str) -> str:
+            raise ValueError("write failed")
+
+    # Fails after the header row is written
+    with pytest.raises(ValueError):
+        write_project_csvs({"2026-10-02": {"/work/acme/api": {"tokens": 300, "cost": _Unformattable(1)}}}, out_dir)
+
+    assert _rows(out_dir / "api.csv")[1:] == [["2026-10-01", "100", "0.5000"]]
+    assert sorted(p.name for p in out_dir.iterdir()) == ["api.csv", "index.csv"]
+
+
+def test_data_export_writes_each_record_with_its_cost(tmp_path: Path, make_record) -> None:
+    import json
+
+    from src.commands.export import DATA_EXPORT_COLUMNS, write_usage_records
+    from src.storage.pricing import PricingTable
+
+    pricing = PricingTable(
+        [("claude-sonnet-4-5", 3.0, 15.0, 3.75, 0.3, 6.0)], aliases={}, long_context_prices={},
+        multipliers={}, overrides={},
+    )
+    records = [make_record(f"m{i}", branch="main", tokens=1_000_000, hostname="laptop") for i in range(2)]
+
+    assert write_usage_records(records, pricing, tmp_path / "out.csv", "csv") == 2
+    rows = _rows(tmp_path / "out.csv")
+    assert rows[0] == list(DATA_EXPORT_COLUMNS)
+    assert rows[1][DATA_EXPORT_COLUMNS.index("cost")] == "3.000000"
+    assert rows[1][DATA_EXPORT_COLUMNS.index("hostname")] == "laptop"
+
+    assert write_usage_records(records, pricing, tmp_path / "nested" / "out.jsonl", "jsonl") == 2
+    lines = (tmp_path / "nested" / "out.jsonl").read_text(encoding="utf-8").splitlines()
+    assert json.loads(lines[0])["cost"] == 3.0
+    assert json.loads(lines[1])["message_uuid"] == "m1"
+
+    def failing():
+        yield records[0]
+        raise OSError("database went away")
+
+    # A failed dump leaves the previous file whole
+    with pytest.raises(OSError):
+        write_usage_records(failing(), pricing, tmp_path / "out.csv", "csv")
+    assert len(_rows(tmp_path / "out.csv")) == 3
+    assert sorted(p.name for p in tmp_path.iterdir()) == ["nested", "out.csv"]
@@ -0,0 +1,65 @@
+import json
+import threading
+import urllib.error
+import urllib.request
+
+import pytest
+
+from src.visualization import heatmap_server
+from src.visualization.heatmap_server import DEFAULT_PORT, PAGE_POLL_MS, make_heatmap_server
+
+
+@pytest.fixture
+def serve(monkeypatch):
+    monkeypatch.setattr(heatmap_server, "read_refresh_marker", lambda: 1760600000.5)
+    servers = []
+
+    def start(**kwargs) -> str:
+        server = make_heatmap_server(lambda: "<svg>heatmap</svg>", port=0, **kwargs)
+        servers.append(server)
+        threading.Thread(target=server.serve_forever, daemon=True).start()
+        host, port = server.server_address[:2]
+        return f"http://{host}:{port}"
+
+    yield start
+    for server in servers:
+        server.shutdown()
+        server.server_close()
+
+
+def _get(url: str) -> tuple[int, str]:
+    try:
+        with urllib.request.urlopen(url, timeout=5) as response:
+            return response.status, response.read().decode("utf-8")
+    except urllib.error.HTTPError as e:
+        return e.code, e.read().decode("utf-8")
+
+
+def test_server_defaults_to_localhost_and_serves_page_image_and_version(serve) -> None:
+    base = serve()
+
+    assert DEFAULT_PORT == 8765
+    assert base.startswith("http://127.0.0.1:")
+    status, page = _get(base + "/")
+    assert status == 200
+    assert 'let version = "1760600000.5";' in page and f"}}, {PAGE_POLL_MS});" in page
+    assert _get(base + "/heatmap.svg?v=1") == (200, "<svg>heatmap</svg>")
+    assert json.loads(_get(base + "/version")[1]) == {"version": "1760600000.5"}
+    # Day pages are off unless a day renderer is given
+    assert _get(base + "/day/2026-10-01")[0] == 404
+    assert _get(base + "/missing")[0] == 404
+
+
+def test_version_is_zero_before_any_update(serve, monkeypatch) -> None:
+    base = serve()
+    monkeypatch.setattr(heatmap_server, "read_refresh_marker", lambda: None)
+
+    assert json.loads(_get(base + "/version")[1]) == {"version": "0"}
+
+
+def test_day_pages_404_without_activity(serve) -> None:
+    base = serve(render_day=lambda day: f"<p>{day}</p>" if day == "2026-10-01" else None)
+
+    assert _get(base + "/day/2026-10-01") == (200, "<p>2026-10-01</p>")
+    assert _get(base + "/day/2026-10-02") == (404, "No activity on this day")
+    assert _get(base + "/day/yesterday")[0] == 404
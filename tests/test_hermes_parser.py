@@ -48,6 +48,7 @@ def test_parses_one_usage_record_into_existing_schema(tmp_path: Path) -> None:
     assert record.token_usage.output_tokens == 20
     assert record.token_usage.cache_read_tokens == 70
     assert record.token_usage.cache_creation_tokens == 10
+    assert record.token_usage.thinking_tokens == 5
     assert record.token_usage.total_tokens == 200
 
 
@@ -0,0 +1,30 @@
+import json
+from pathlib import Path
+
+from src.hooks.runner import find_installed_hooks, run_hook, synthetic_payload
+
+
+def test_installed_hook_runs_with_synthetic_payload(tmp_path: Path, monkeypatch) -> None:
+    monkeypatch.setattr(Path, "home", lambda: tmp_path / "home")
+    settings = tmp_path / ".claude" / "settings.json"
+    settings.parent.mkdir()
+    settings.write_text(json.dumps({"hooks": {
+        "Stop": [
+            {"matcher": "*", "hooks": [{"type": "command", "command": "cat; : ccg update usage --hook"}]},
+            {"matcher": "*", "hooks": [{"type": "command", "command": "afplay /System/Library/Sounds/Glass.aiff &"}]},
+        ],
+        "PreToolUse": [{"matcher": "Bash", "hooks": [{"type": "command", "command": "uv-standard.py; exit 2"}]}],
+    }}))
+
+    [hook] = find_installed_hooks("usage", tmp_path)
+    payload = synthetic_payload(hook.event, tmp_path, tmp_path / "t.jsonl")
+    run = run_hook(hook, payload, tmp_path)
+
+    assert (hook.event, hook.scope) == ("Stop", "project")
+    assert run.exit_code == 0
+    assert json.loads(run.stdout) == {**payload, "stop_hook_active": False}
+    assert payload["session_id"] == "ccg-hooks-test"
+
+    [blocking] = find_installed_hooks("uv-standard", tmp_path)
+    blocked = run_hook(blocking, synthetic_payload(blocking.event, tmp_path, tmp_path / "t.jsonl"), tmp_path)
+    assert blocked.exit_code == 2 and not blocked.ok
@@ -0,0 +1,32 @@
+import sqlite3
+from pathlib import Path
+
+from src.storage.snapshot_db import get_host_stats, save_snapshot
+
+
+def test_host_stats_split_usage_per_ingesting_machine(db_path: Path, make_record) -> None:
+    save_snapshot([
+        make_record("a1", day=1, session="s1", tokens=1_000_000),
+        make_record("a2", day=2, session="s2", tokens=1_000_000),
+    ], db_path=db_path, storage_mode="full", hostname="laptop")
+    save_snapshot([make_record("b1", day=2, session="s3", tokens=3_000_000)],
+                  db_path=db_path, storage_mode="full", hostname="desktop")
+    # A record that already knows its host (e.g. merged from another db) keeps it
+    save_snapshot([make_record("c1", day=3, session="s4", tokens=500, hostname="ci-runner")],
+                  db_path=db_path, storage_mode="full", hostname="laptop")
+
+    hosts = get_host_stats(db_path=db_path)
+
+    assert [(h["hostname"], h["tokens"], h["sessions"], h["first_date"], h["last_date"]) for h in hosts] == [
+        ("desktop", 3_000_000, 1, "2026-10-02", "2026-10-02"),
+        ("laptop", 2_000_000, 2, "2026-10-01", "2026-10-02"),
+        ("ci-runner", 500, 1, "2026-10-03", "2026-10-03"),
+    ]
+
+    conn = sqlite3.connect(db_path)
+    day_hosts = conn.execute("SELECT hostname FROM daily_snapshots WHERE date = '2026-10-02'").fetchone()[0]
+    conn.close()
+    assert sorted(day_hosts.split(",")) == ["desktop", "laptop"]
+
+    ranged = get_host_stats("2026-10-01", "2026-10-01", db_path=db_path)
+    assert [(h["hostname"], h["tokens"]) for h in ranged] == [("laptop", 1_000_000)]
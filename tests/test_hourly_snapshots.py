@@ -0,0 +1,34 @@
+from datetime import datetime, timezone
+from functools import partial
+from pathlib import Path
+
+from src.storage.snapshot_db import get_hourly_snapshots, save_file_aggregate, save_snapshot
+
+NOW = datetime(2026, 10, 16, 14, 30, tzinfo=timezone.utc)
+
+
+def test_full_mode_buckets_by_utc_hour_and_windows_last_n_hours(db_path: Path, make_record) -> None:
+    today = partial(make_record, day=16)
+    save_snapshot([today("a1", hour=8), today("a2", hour=10, minute=5), today("a3", hour=10, minute=50)],
+                  db_path=db_path, storage_mode="full")
+    save_snapshot([today("a3", hour=10, minute=50), today("a4", hour=14, minute=10)],
+                  db_path=db_path, storage_mode="full")
+
+    hours = get_hourly_snapshots(5, NOW, db_path=db_path)
+
+    assert [(h["hour"], h["responses"], h["total_tokens"]) for h in hours] == [
+        ("2026-10-16T10:00:00+00:00", 2, 200),
+        ("2026-10-16T14:00:00+00:00", 1, 100),
+    ]
+    assert len(get_hourly_snapshots(24, NOW, db_path=db_path)) == 3
+
+
+def test_aggregate_mode_reapplying_a_file_replaces_its_hours(tmp_path: Path, db_path: Path, make_record) -> None:
+    today = partial(make_record, day=16)
+    transcript = tmp_path / "session.jsonl"
+    save_file_aggregate(transcript, [today("a1", hour=13)], db_path=db_path)
+    save_file_aggregate(transcript, [today("a1", hour=13), today("a2", hour=14)], db_path=db_path)
+
+    hours = get_hourly_snapshots(2, NOW, db_path=db_path)
+
+    assert [(h["hour"][11:13], h["responses"]) for h in hours] == [("13", 1), ("14", 1)]
@@ -0,0 +1,25 @@
+from pathlib import Path
+
+from src.commands.imports.db import merge_database
+from src.storage import snapshot_db
+from src.storage.snapshot_db import load_daily_snapshots, save_snapshot
+
+
+def test_import_merges_new_records_and_skips_known_ones(tmp_path: Path, monkeypatch, make_record) -> None:
+    local = tmp_path / "usage_history.db"
+    save_snapshot(
+        [make_record("a1", day=1, tokens=100), make_record("a2", day=2, tokens=200)], db_path=local, storage_mode="full"
+    )
+    other = tmp_path / "laptop.db"
+    laptop = [
+        make_record("a2", day=2, tokens=200), make_record("b1", day=2, tokens=50), make_record("b2", day=3, tokens=70)
+    ]
+    save_snapshot(laptop, db_path=other, storage_mode="full")
+    monkeypatch.setattr("src.storage.api.get_db_path", lambda: local)
+    monkeypatch.setattr("src.storage.api.get_backend_module", lambda: snapshot_db)
+
+    assert merge_database(other) == (2, 1)
+    assert merge_database(other) == (0, 3)  # importing twice adds nothing
+
+    days = {day["date"]: day["total_tokens"] for day in load_daily_snapshots(db_path=local)}
+    assert (days["2026-10-01"], days["2026-10-02"], days["2026-10-03"]) == (100, 250, 70)
@@ -0,0 +1,55 @@
+import threading
+import time
+from pathlib import Path
+
+import pytest
+
+from src.storage.ingest_lock import ingest_lock, ingest_lock_path
+
+
+def test_lock_times_out_while_another_run_holds_it(tmp_path: Path) -> None:
+    path = tmp_path / "ingest.lock"
+
+    with ingest_lock(path=path) as waited:
+        assert waited is False
+        started = time.monotonic()
+        with pytest.raises(TimeoutError, match="still held"):
+            with ingest_lock(timeout=0.3, path=path):
+                pass
+        assert time.monotonic() - started >= 0.3
+
+    # Released on exit, so the next run takes it straight away
+    with ingest_lock(timeout=0, path=path) as waited:
+        assert waited is False
+
+
+def test_queued_run_reports_that_it_waited(tmp_path: Path) -> None:
+    path = tmp_path / "ingest.lock"
+    held = threading.Event()
+
+    def hold() -> None:
+        with ingest_lock(path=path):
+            held.set()
+            time.sleep(0.3)
+
+    holder = threading.Thread(target=hold)
+    holder.start()
+    held.wait(5)
+    with ingest_lock(timeout=5, path=path) as waited:
+        assert waited is True
+    holder.join()
+
+
+def test_each_database_gets_its_own_lock(tmp_path: Path, monkeypatch) -> None:
+    work, home = tmp_path / "work" / "usage_history.db", tmp_path / "home.db"
+    monkeypatch.setattr("src.storage.ingest_lock.get_db_path", lambda: work)
+    assert ingest_lock_path() == tmp_path / "work" / "usage_history.db.lock"
+
+    with ingest_lock() as waited:
+        assert waited is False
+        # Another profile's ingest doesn't queue behind this one
+        with ingest_lock(timeout=0, path=ingest_lock_path(home)) as waited:
+            assert waited is False
+        with pytest.raises(TimeoutError):
+            with ingest_lock(timeout=0, path=ingest_lock_path(work)):
+                pass
@@ -0,0 +1,475 @@
+import gzip
+import io
+import json
+from pathlib import Path
+
+from src.data.jsonl_parser import (
+    ParseStats,
+    file_head_digest,
+    parse_all_jsonl_files,
+    parse_jsonl_file,
+    parse_jsonl_stream,
+    parse_jsonl_tail,
+    resume_offset,
+)
+
+
+def _line(index: int) -> str:
+    return json.dumps({
+        "type": "assistant",
+        "sessionId": "session-1",
+        "uuid": f"uuid-{index}",
+        "requestId": f"req-{index}",
+        "timestamp": "2026-10-01T10:00:00Z",
+        "cwd": "/repo",
+        "message": {
+            "id": f"msg-{index}",
+            "model": "claude-sonnet-4",
+            "usage": {"input_tokens": 10, "output_tokens": 5},
+        },
+    }) + "\n"
+
+
+def test_resumes_after_last_complete_line(tmp_path: Path) -> None:
+    path = tmp_path / "session.jsonl"
+    path.write_text(_line(1) + _line(2)[:-10], encoding="utf-8")
+
+    records, offset = parse_jsonl_tail(path)
+    assert len(records) == 1
+    assert offset == len(_line(1))
+
+    path.write_text(_line(1) + _line(2) + _line(3), encoding="utf-8")
+    saved = (offset, offset, file_head_digest(path, offset))
+    records, offset = parse_jsonl_tail(path, resume_offset(path, saved))
+
+    assert [r.message_uuid for r in records] == ["msg-2:req-2", "msg-3:req-3"]
+    assert offset == path.stat().st_size
+
+
+def test_stream_parses_piped_lines_and_counts_skips() -> None:
+    stats = ParseStats()
+    stream = io.StringIO(_line(1) + "{not json\n" + _line(2) + "\n")
+
+    records = list(parse_jsonl_stream(stream, stats=stats))
+
+    assert [r.message_uuid for r in records] == ["msg-1:req-1", "msg-2:req-2"]
+    assert stats.lines == 3
+    assert stats.malformed == 1
+
+
+def test_summary_entries_name_the_transcripts_session(tmp_path: Path, db_path: Path) -> None:
+    from src.data.jsonl_parser import parse_jsonl_file
+    from src.models.usage_record import TranscriptSummary
+    from src.storage.snapshot_db import get_session_summaries, save_session_summaries
+
+    path = tmp_path / "session-1.jsonl.gz"
+    with gzip.open(path, "wt", encoding="utf-8") as f:
+        f.write(json.dumps({"type": "summary", "summary": "Old name", "leafUuid": "u-1"}) + "\n")
+        f.write(_line(1))
+        f.write(json.dumps({"type": "summary", "summary": "Refactor auth middleware", "leafUuid": "u-2"}) + "\n")
+    summaries: list[TranscriptSummary] = []
+
+    assert len(list(parse_jsonl_file(path, summaries=summaries))) == 1
+    assert [(s.session_id, s.summary) for s in summaries] == [
+        ("session-1", "Old name"), ("session-1", "Refactor auth middleware"),
+    ]
+
+    save_session_summaries(summaries, db_path=db_path)
+    assert get_session_summaries(db_path=db_path) == {"session-1": "Refactor auth middleware"}
+    assert get_session_summaries(["other"], db_path=db_path) == {}
+
+
+def test_shrunk_or_rewritten_file_restarts_from_zero(tmp_path: Path) -> None:
+    path = tmp_path / "session.jsonl"
+    path.write_text(_line(1) + _line(2), encoding="utf-8")
+    _, offset = parse_jsonl_tail(path)
+    saved = (offset, offset, file_head_digest(path, offset))
+
+    path.write_text(_line(3), encoding="utf-8")
+    assert resume_offset(path, saved) == 0
+
+    path.write_text(_line(4) + _line(5) + _line(6), encoding="utf-8")
+    assert resume_offset(path, saved) == 0
+    assert resume_offset(path, None) == 0
+
+
+def test_tool_calls_merge_across_streamed_entries(tmp_path: Path) -> None:
+    def entry(uuid: str, blocks: list[dict]) -> str:
+        return json.dumps({
+            "type": "assistant",
+            "sessionId": "session-1",
+            "uuid": uuid,
+            "requestId": "req-1",
+            "timestamp": "2026-10-01T10:00:00Z",
+            "cwd": "/repo",
+            "message": {
+                "id": "msg-1",
+                "model": "claude-sonnet-4",
+                "content": blocks,
+                "usage": {"input_tokens": 10, "output_tokens": 5},
+            },
+        }) + "\n"
+
+    read = {"type": "tool_use", "id": "toolu_1", "name": "Read", "input": {}}
+    bash = {"type": "tool_use", "id": "toolu_2", "name": "Bash", "input": {}}
+    read_again = {"type": "tool_use", "id": "toolu_3", "name": "Read", "input": {}}
+    path = tmp_path / "session.jsonl"
+    # One entry per content block, plus a fork replay of the first block
+    path.write_text(
+        entry("a", [{"type": "text", "text": "Looking"}])
+        + entry("b", [read])
+        + entry("c", [bash, read_again])
+        + entry("d", [read]),
+        encoding="utf-8",
+    )
+
+    records = parse_all_jsonl_files([path])
+
+    assert len(records) == 1
+    assert records[0].tool_calls == (("Read", 2), ("Bash", 1))
+
+
+def test_bad_timestamps_are_quarantined_without_losing_the_file(tmp_path: Path) -> None:
+    def entry(uuid: str, timestamp: object) -> dict:
+        return {"type": "user", "sessionId": "session-1", "uuid": uuid, "timestamp": timestamp,
+                "cwd": "/repo", "message": {"content": "hi"}}
+
+    path = tmp_path / "session.jsonl"
+    rows = [
+        entry("garbled", "yesterday-ish"),
+        entry("ancient", "1970-01-01T00:00:00Z"),
+        entry("future", "2999-01-01T00:00:00Z"),
+        entry("missing", None),
+        entry("good", "2026-10-01T10:00:00Z"),
+    ]
+    path.write_text("\n".join(json.dumps(row) for row in rows) + "\n", encoding="utf-8")
+
+    quarantine: list = []
+    records = parse_all_jsonl_files([path], quarantine)
+
+    assert [r.message_uuid for r in records] == ["good"]
+    assert [(q.entry_id, q.reason) for q in quarantine] == [
+        ("garbled", "unparseable timestamp"),
+        ("ancient", "timestamp before 2024-01-01"),
+        ("future", "timestamp in the future"),
+        ("missing", "missing timestamp"),
+    ]
+
+
+def test_thinking_estimate_survives_streamed_entries(tmp_path: Path) -> None:
+    def entry(uuid: str, block: dict, output_tokens: int) -> str:
+        return json.dumps({
+            "type": "assistant",
+            "sessionId": "session-1",
+            "uuid": uuid,
+            "requestId": "req-1",
+            "timestamp": "2026-10-01T10:00:00Z",
+            "cwd": "/repo",
+            "message": {
+                "id": "msg-1",
+                "model": "claude-sonnet-4",
+                "content": [block],
+                "usage": {"input_tokens": 10, "output_tokens": output_tokens},
+            },
+        }) + "\n"
+
+    path = tmp_path / "session.jsonl"
+    # Thinking arrives in an early entry whose output count is still partial
+    path.write_text(
+        entry("a", {"type": "thinking", "thinking": "x" * 400, "signature": "sig"}, 8)
+        + entry("b", {"type": "text", "text": "Done"}, 300),
+        encoding="utf-8",
+    )
+
+    records = parse_all_jsonl_files([path])
+
+    assert len(records) == 1
+    assert records[0].token_usage.output_tokens == 300
+    assert records[0].token_usage.thinking_tokens == 100
+
+
+def test_cache_writes_split_by_ttl(tmp_path: Path) -> None:
+    def entry(index: int, usage: dict) -> str:
+        return json.dumps({
+            "type": "assistant",
+            "sessionId": "session-1",
+            "uuid": f"uuid-{index}",
+            "requestId": f"req-{index}",
+            "timestamp": "2026-10-01T10:00:00Z",
+            "cwd": "/repo",
+            "message": {"id": f"msg-{index}", "model": "claude-sonnet-4", "usage": usage},
+        }) + "\n"
+
+    path = tmp_path / "session.jsonl"
+    path.write_text(
+        # Older log: top-level total only, all 5m cache
+        entry(1, {"input_tokens": 1, "output_tokens": 1, "cache_creation_input_tokens": 400})
+        # Newer log: per-TTL breakdown alongside the total
+        + entry(2, {
+            "input_tokens": 1,
+            "output_tokens": 1,
+            "cache_creation_input_tokens": 700,
+            "cache_creation": {"ephemeral_5m_input_tokens": 200, "ephemeral_1h_input_tokens": 500},
+        }),
+        encoding="utf-8",
+    )
+
+    older, newer = (record.token_usage for record in parse_all_jsonl_files([path]))
+
+    assert (older.cache_creation_5m_tokens, older.cache_creation_1h_tokens) == (400, 0)
+    assert (newer.cache_creation_5m_tokens, newer.cache_creation_1h_tokens) == (200, 500)
+    assert newer.cache_creation_tokens == 700
+
+
+def test_gzip_archive_parses_like_the_live_transcript(tmp_path: Path) -> None:
+    live = tmp_path / "session.jsonl"
+    live.write_text(_line(1) + _line(2), encoding="utf-8")
+    archive = tmp_path / "session.jsonl.gz"
+    with gzip.open(archive, "wt", encoding="utf-8") as f:
+        f.write(_line(1) + _line(2))
+
+    assert parse_all_jsonl_files([archive]) == parse_all_jsonl_files([live])
+    assert len(parse_all_jsonl_files([live, archive])) == 2
+
+
+def test_sidechain_entries_are_marked_as_subagent_work(tmp_path: Path, db_path: Path) -> None:
+    main = tmp_path / "session-1.jsonl"
+    main.write_text(_line(1), encoding="utf-8")
+    sidechain = json.loads(_line(2))
+    sidechain.update(isSidechain=True, agentId="a1b2")
+    agent = tmp_path / "session-1" / "subagents" / "agent-a1b2.jsonl"
+    agent.parent.mkdir(parents=True)
+    agent.write_text(json.dumps(sidechain) + "\n", encoding="utf-8")
+
+    records = {r.message_uuid: r for r in parse_all_jsonl_files([main, agent])}
+
+    assert not records["msg-1:req-1"].is_subagent
+    assert records["msg-1:req-1"].agent_id is None
+    assert records["msg-2:req-2"].is_subagent
+    assert records["msg-2:req-2"].agent_id == "a1b2"
+    # Delegated work stays in the spawning session
+    assert records["msg-2:req-2"].session_id == "session-1"
+
+    from src.storage.snapshot_db import iter_historical_records, save_snapshot
+
+    save_snapshot(list(records.values()), db_path=db_path, storage_mode="full")
+    stored = {r.message_uuid: r.agent_id for r in iter_historical_records(db_path=db_path)}
+    assert stored == {"msg-1:req-1": None, "msg-2:req-2": "a1b2"}
+
+
+def test_errors_and_interruptions_become_session_events(tmp_path: Path) -> None:
+    def entry(**fields) -> str:
+        base = {"sessionId": "session-1", "timestamp": "2026-10-01T10:00:00Z", "cwd": "/repo"}
+        return json.dumps({**base, **fields}) + "\n"
+
+    path = tmp_path / "session.jsonl"
+    path.write_text(
+        entry(type="user", uuid="p1", message={"role": "user", "content": "refactor it"})
+        + _line(1) + _line(2)
+        + entry(type="user", uuid="i1", message={"content": [{"type": "text", "text": "[Request interrupted by user]"}]})
+        + entry(type="user", uuid="p2", message={"role": "user", "content": "try again"})
+        + entry(type="system", subtype="api_error", uuid="e1", retryAttempt=1,
+                error={"status": 529, "error": {"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}})
+        + _line(3)
+        + entry(type="assistant", uuid="e2", isApiErrorMessage=True,
+                message={"model": "<synthetic>", "content": [{"type": "text", "text": "Claude AI usage limit reached|1760000000"}]}),
+        encoding="utf-8",
+    )
+    events = []
+    records = parse_all_jsonl_files([path], events=events)
+
+    assert len(records) == 6
+    assert [(e.event_id, e.event_type, e.ends_turn, e.turn_tokens) for e in events] == [
+        ("i1", "interrupted", True, 30),
+        ("e1", "api_error", False, 0),
+        ("e2", "rate_limit", True, 15),
+    ]
+    assert events[1].detail == "529 Overloaded"
+    assert events[1].retry_attempt == 1
+
+
+def test_mcp_calls_pair_with_their_results(tmp_path: Path) -> None:
+    call = json.loads(_line(1))
+    call["message"]["content"] = [
+        {"type": "tool_use", "id": "toolu_1", "name": "mcp__github__create_issue", "input": {}},
+        {"type": "tool_use", "id": "toolu_2", "name": "Read", "input": {}},
+    ]
+    result = {
+        "type": "user",
+        "sessionId": "session-1",
+        "uuid": "r1",
+        "timestamp": "2026-10-01T10:00:01Z",
+        "cwd": "/repo",
+        "message": {"role": "user", "content": [
+            {"type": "tool_result", "tool_use_id": "toolu_1", "content": [{"type": "text", "text": "x" * 40}]},
+            {"type": "tool_result", "tool_use_id": "toolu_2", "content": "file body"},
+        ]},
+    }
+    path = tmp_path / "session.jsonl"
+    path.write_text(json.dumps(call) + "\n" + json.dumps(result) + "\n", encoding="utf-8")
+
+    records = {r.message_uuid: r for r in parse_all_jsonl_files([path])}
+
+    assert records["msg-1:req-1"].mcp_calls == (("toolu_1", "github", "create_issue"),)
+    assert records["r1"].tool_results == (("toolu_1", 40), ("toolu_2", 9))
+
+
+def test_prompt_attachments_are_counted(tmp_path: Path) -> None:
+    prompt = {
+        "type": "user",
+        "sessionId": "session-1",
+        "uuid": "p1",
+        "timestamp": "2026-10-01T10:00:00Z",
+        "cwd": "/repo",
+        "message": {"role": "user", "content": [
+            {"type": "text", "text": "why does this render wrong?"},
+            {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "..."}},
+            {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "..."}},
+            {"type": "document", "source": {"type": "base64", "media_type": "application/pdf", "data": "..."}},
+        ]},
+    }
+    path = tmp_path / "session.jsonl"
+    path.write_text(json.dumps(prompt) + "\n" + _line(1), encoding="utf-8")
+
+    records = {r.message_uuid: r for r in parse_all_jsonl_files([path])}
+
+    assert records["p1"].attachment_count == 3
+    assert records["msg-1:req-1"].attachment_count == 0
+
+
+def test_compaction_boundaries_count_messages_since_last_reset(tmp_path: Path) -> None:
+    def boundary(uuid: str, trigger: str) -> str:
+        return json.dumps({
+            "type": "system",
+            "subtype": "compact_boundary",
+            "uuid": uuid,
+            "sessionId": "session-1",
+            "timestamp": "2026-10-01T10:00:00Z",
+            "cwd": "/repo",
+            "compactMetadata": {"trigger": trigger, "preTokens": 155000},
+        }) + "\n"
+
+    path = tmp_path / "session.jsonl"
+    head = _line(1) + _line(2) + _line(3) + boundary("c1", "auto")
+    path.write_text(head + _line(4) + boundary("c2", "manual") + _line(5) + boundary("c3", "auto"), encoding="utf-8")
+
+    events = []
+    parse_all_jsonl_files([path], events=events)
+    assert [(e.event_id, e.event_type, e.detail, e.messages_before) for e in events] == [
+        ("c1", "compaction", "auto", 3),
+        ("c2", "compaction", "manual", 1),
+        ("c3", "compaction", "auto", 1),
+    ]
+    assert events[0].context_tokens == 155000
+
+    # Resuming mid-file can't count back to the previous reset
+    tail_events = []
+    parse_jsonl_tail(path, len(head.encode()) + len(_line(4).encode()), events=tail_events)
+    assert [(e.event_id, e.messages_before) for e in tail_events] == [("c2", None), ("c3", 1)]
+
+
+def test_parse_stats_count_skipped_lines(tmp_path: Path) -> None:
+    path = tmp_path / "session.jsonl"
+    path.write_text(
+        _line(1)
+        + "{not json\n"
+        + "\n"
+        + json.dumps({"type": "summary", "summary": "Refactor"}) + "\n"
+        + _line(2).replace("2026-10-01T10:00:00Z", "not-a-time"),
+        encoding="utf-8",
+    )
+
+    stats = ParseStats()
+    records = parse_all_jsonl_files([path], quarantine=[], stats=stats)
+
+    assert len(records) == 1
+    assert (stats.lines, stats.malformed, stats.non_message, stats.bad_timestamps) == (4, 1, 1, 1)
+    assert stats.skipped == 3
+
+
+def test_strict_stats_report_lost_lines(tmp_path: Path) -> None:
+    path = tmp_path / "session.jsonl"
+    path.write_text(
+        _line(1)
+        + "{not json\n"
+        + json.dumps({"type": "assistant", "timestamp": "2026-10-01T10:00:00Z", "message": "text"}) + "\n"
+        + json.dumps({"type": "summary", "summary": "Refactor"}) + "\n",
+        encoding="utf-8",
+    )
+
+    stats = ParseStats(issues=[])
+    records = parse_all_jsonl_files([path], stats=stats)
+    report = stats.report()
+
+    assert len(records) == 1
+    assert [(issue.line, issue.reason) for issue in stats.issues] == [
+        (2, "malformed_json"),
+        (3, "unexpected_shape"),
+    ]
+    assert report["counts"] == {"malformed_json": 1, "unexpected_shape": 1}
+    assert report["skipped_types"] == {"summary": 1}
+    assert report["files"] == 1
+
+
+def test_parse_stats_count_unknown_fields(tmp_path: Path) -> None:
+    entry = json.loads(_line(1))
+    entry["costUSD"] = 0.01
+    entry["message"]["usage"]["reasoning_tokens"] = 3
+    path = tmp_path / "session.jsonl"
+    path.write_text(json.dumps(entry) + "\n" + _line(2).replace('"cwd"', '"costUSD": 0, "cwd"'), encoding="utf-8")
+
+    stats = ParseStats()
+    records = parse_all_jsonl_files([path], stats=stats)
+
+    assert len(records) == 2
+    assert stats.unknown_fields == {"costUSD": 2, "message.usage.reasoning_tokens": 1}
+
+
+def test_mmap_reads_match_the_buffered_reader(tmp_path: Path) -> None:
+    path = tmp_path / "session.jsonl"
+    path.write_text(_line(1) + "{not json\n" + _line(2) + _line(3)[:-10], encoding="utf-8")
+
+    buffered, mapped = ParseStats(), ParseStats()
+    assert [r.message_uuid for r in parse_jsonl_file(path, stats=mapped, mmap_threshold=1)] == [
+        r.message_uuid for r in parse_jsonl_file(path, stats=buffered)
+    ]
+    assert (mapped.lines, mapped.malformed) == (buffered.lines, buffered.malformed)
+
+    # The tail stops before the unfinished line either way
+    assert parse_jsonl_tail(path, len(_line(1)), mmap_threshold=1)[1] == parse_jsonl_tail(path, len(_line(1)))[1]
+
+
+def test_aliased_field_spellings_parse_like_the_originals(tmp_path: Path) -> None:
+    entry = json.loads(_line(1))
+    aliased = {
+        "type": "assistant",
+        "session_id": "session-1",
+        "uuid": "uuid-1",
+        "request_id": "req-1",
+        "timestamp": "2026-10-01T10:00:00Z",
+        "cwd": "/repo",
+        "git_branch": "main",
+        "is_sidechain": True,
+        "message": {
+            "id": "msg-1",
+            "model": "claude-sonnet-4",
+            "usage": {"inputTokens": 10, "outputTokens": 5, "cacheReadInputTokens": 7, "thinkingTokens": 2},
+        },
+    }
+    entry["gitBranch"] = "main"
+    entry["isSidechain"] = True
+    entry["message"]["usage"].update(cache_read_input_tokens=7, thinking_tokens=2)
+    original, alias = tmp_path / "original.jsonl", tmp_path / "alias.jsonl"
+    original.write_text(json.dumps(entry) + "\n", encoding="utf-8")
+    alias.write_text(json.dumps(aliased) + "\n", encoding="utf-8")
+
+    stats = ParseStats()
+    [expected], [record] = list(parse_jsonl_file(original)), list(parse_jsonl_file(alias, stats=stats))
+
+    assert record == expected
+    assert (record.session_id, record.message_uuid, record.git_branch) == ("session-1", "msg-1:req-1", "main")
+    assert record.token_usage.cache_read_tokens == 7
+    assert record.token_usage.thinking_tokens == 2
+    assert record.is_subagent
+    # Aliases are known spellings, not schema drift
+    assert not stats.unknown_fields
@@ -0,0 +1,30 @@
+from src.aggregation.mcp_stats import summarize_mcp_calls
+
+
+def _call(server: str, tool: str, session: str, call_tokens: int = 10, result_chars: int = 400) -> dict:
+    return {
+        "server": server,
+        "tool": tool,
+        "session_id": session,
+        "date": "2026-10-01",
+        "call_tokens": call_tokens,
+        "result_chars": result_chars,
+    }
+
+
+def test_summarize_mcp_calls_totals_per_server() -> None:
+    servers = summarize_mcp_calls([
+        _call("github", "create_issue", "s1"),
+        _call("github", "search", "s1"),
+        _call("github", "search", "s2", result_chars=0),
+        _call("linear", "list_issues", "s2", call_tokens=30),
+    ])
+
+    assert [s.server for s in servers] == ["github", "linear"]
+    github = servers[0]
+    assert (github.calls, github.sessions, github.top_tool) == (3, 2, ("search", 2))
+    assert (github.call_tokens, github.result_tokens, github.total_tokens) == (30, 200, 230)
+
+
+def test_summarize_mcp_calls_without_calls() -> None:
+    assert summarize_mcp_calls([]) == []
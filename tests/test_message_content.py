@@ -0,0 +1,38 @@
+import sqlite3
+from pathlib import Path
+
+from src.storage.snapshot_db import save_message_content
+
+
+def _stored(db_path: Path) -> list[tuple]:
+    conn = sqlite3.connect(db_path)
+    try:
+        return conn.execute(
+            "SELECT entry_key, content, content_bytes, truncated FROM message_content ORDER BY timestamp"
+        ).fetchall()
+    finally:
+        conn.close()
+
+
+def test_each_message_is_truncated_to_the_record_cap(db_path: Path, make_record) -> None:
+    prompts = [make_record(f"uuid-{i}", minute=i, message_type="user", content=text)
+               for i, text in enumerate(["ok", "abcé" * 3], 1)]
+
+    # "é" is two bytes: a cut inside it drops the partial character
+    save_message_content(prompts, 4, 1000, db_path=db_path)
+
+    assert _stored(db_path) == [("s1:uuid-1", "ok", 2, 0), ("s1:uuid-2", "abc", 3, 1)]
+
+
+def test_oldest_messages_are_evicted_first_past_the_total_cap(db_path: Path, make_record) -> None:
+    prompts = [make_record(f"uuid-{i}", minute=i, message_type="user", content=text)
+               for i, text in enumerate(["a" * 40, "b" * 40, "c" * 40, "d" * 90], 1)]
+    assert save_message_content(prompts[:2], 100, 100, db_path=db_path) == 0
+
+    # A newer save pushes the table over the cap: the oldest row goes first
+    assert save_message_content(prompts[2:3], 100, 100, db_path=db_path) == 1
+    assert [row[0] for row in _stored(db_path)] == ["s1:uuid-2", "s1:uuid-3"]
+
+    # One large message can evict several older ones
+    assert save_message_content(prompts[3:], 100, 100, db_path=db_path) == 2
+    assert [row[0] for row in _stored(db_path)] == ["s1:uuid-4"]
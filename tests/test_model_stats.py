@@ -0,0 +1,81 @@
+from pathlib import Path
+
+import pytest
+
+from src.aggregation.model_stats import aggregate_by_model_day, model_days_from_snapshots, totals_by_model
+from src.commands import stats
+from src.models.usage_record import UsageRecord
+from src.storage import snapshot_db
+from src.storage.pricing import PricingTable
+from src.storage.snapshot_db import get_database_stats, load_daily_snapshots, save_snapshot
+
+PRICING = PricingTable(
+    [("claude-sonnet-4-5", 3.0, 15.0, 3.75, 0.3, 6.0), ("claude-haiku-4-5", 1.0, 5.0, 1.25, 0.1, 2.0)],
+    aliases={}, long_context_prices={}, multipliers={}, overrides={},
+)
+
+
+@pytest.fixture
+def records(make_record) -> list[UsageRecord]:
+    return [
+        make_record("a1", day=1, session="s1", tokens=1_000_000, output=100_000),
+        make_record("a2", day=1, session="s1", model="claude-haiku-4-5", tokens=2_000_000, output=200_000),
+        make_record("a3", day=2, session="s2", tokens=500_000, output=50_000),
+        make_record("u1", day=2, session="s2", message_type="user"),
+    ]
+
+
+def test_aggregates_tokens_and_cost_per_model_per_day(records: list[UsageRecord]) -> None:
+    days = aggregate_by_model_day(records, PRICING)
+
+    assert [(d.date, d.model, d.responses, d.total_tokens) for d in days] == [
+        ("2026-10-01", "claude-haiku-4-5", 1, 2_200_000),
+        ("2026-10-01", "claude-sonnet-4-5", 1, 1_100_000),
+        ("2026-10-02", "claude-sonnet-4-5", 1, 550_000),
+    ]
+    assert days[1].cost == pytest.approx(3.0 + 1.5)
+
+    totals = totals_by_model(days)
+    assert [(t.date, t.model, t.responses, t.total_tokens) for t in totals] == [
+        ("all", "claude-haiku-4-5", 1, 2_200_000),
+        ("all", "claude-sonnet-4-5", 2, 1_650_000),
+    ]
+    assert totals[1].cost == pytest.approx(4.5 + 2.25)
+
+
+def test_rollup_gives_the_same_breakdown_in_aggregate_mode(db_path: Path, records: list[UsageRecord]) -> None:
+    save_snapshot(records, db_path=db_path, storage_mode="aggregate")
+
+    from_rollup = model_days_from_snapshots(load_daily_snapshots(db_path=db_path), PRICING)
+
+    assert from_rollup == aggregate_by_model_day(records, PRICING)
+
+    # ccg stats reads the same rollup when there are no usage_records
+    stats = get_database_stats(db_path)
+    assert stats["total_records"] == 0
+    assert (stats["total_days"], stats["oldest_date"], stats["newest_date"]) == (2, "2026-10-01", "2026-10-02")
+    assert stats["tokens_by_model"] == {"claude-haiku-4-5": 2_200_000, "claude-sonnet-4-5": 1_650_000}
+    assert set(stats["cost_by_model"]) == {"claude-haiku-4-5", "claude-sonnet-4-5"}
+    assert stats["total_cost"] > 0
+
+
+def test_stats_command_shows_cost_for_aggregate_databases(
+    db_path: Path, records: list[UsageRecord], monkeypatch
+) -> None:
+    class _Console:
+        def __init__(self) -> None:
+            self.lines: list[str] = []
+
+        def print(self, text="", **kwargs) -> None:
+            self.lines.append(str(text))
+
+    save_snapshot(records, db_path=db_path, storage_mode="aggregate")
+    monkeypatch.setattr("src.storage.api.get_db_path", lambda: db_path)
+    monkeypatch.setattr("src.storage.api.get_backend_module", lambda: snapshot_db)
+    monkeypatch.setattr(stats, "get_storage_mode", lambda: "aggregate")
+    console = _Console()
+
+    stats.run(console, fast=True, mcp=True)
+
+    assert "  Date Range:          2026-10-01 to 2026-10-02" in console.lines
+    assert any("(1 month @ $200/mo)" in line for line in console.lines)
@@ -0,0 +1,127 @@
+from src.config import user_config
+from src.models.usage_record import TokenUsage
+from src.storage.pricing import PricingTable, load_tier_multipliers, match_pricing_model, price_model_usage
+
+PRICING_NAMES = [
+    "claude-opus-4-5-20251101",
+    "claude-opus-4-20250514",
+    "claude-sonnet-4-5-20250929",
+    "claude-haiku-3-5-20241022",
+]
+
+
+def test_match_exact_and_snapshot_date() -> None:
+    assert match_pricing_model("claude-opus-4-5-20251101", PRICING_NAMES) == "claude-opus-4-5-20251101"
+    assert match_pricing_model("claude-sonnet-4-5", PRICING_NAMES) == "claude-sonnet-4-5-20250929"
+    assert match_pricing_model("claude-sonnet-4-5-20260101", PRICING_NAMES) == "claude-sonnet-4-5-20250929"
+
+
+def test_match_provider_qualified_ids() -> None:
+    assert (
+        match_pricing_model("us.anthropic.claude-sonnet-4-5-20250929-v1:0", PRICING_NAMES)
+        == "claude-sonnet-4-5-20250929"
+    )
+    assert match_pricing_model("claude-opus-4-5@20251101", PRICING_NAMES) == "claude-opus-4-5-20251101"
+    assert match_pricing_model("anthropic/claude-sonnet-4-5", PRICING_NAMES) == "claude-sonnet-4-5-20250929"
+
+
+def test_match_alias_and_family_without_absorbing_new_versions() -> None:
+    aliases = {"claude-3-5-haiku-20241022": "claude-haiku-3-5-20241022"}
+    assert match_pricing_model("claude-3-5-haiku-20241022", PRICING_NAMES, aliases) == "claude-haiku-3-5-20241022"
+    assert match_pricing_model("claude-opus-4-5-latest", PRICING_NAMES) == "claude-opus-4-5-20251101"
+    assert match_pricing_model("claude-opus-4-9", PRICING_NAMES) is None
+    assert match_pricing_model("gpt-5", PRICING_NAMES) is None
+
+
+def test_price_model_usage_reports_unpriced() -> None:
+    usage = [
+        ("claude-sonnet-4-5", 1_000_000, 1_000_000, 0, 0, 0),
+        ("mystery-model", 10, 0, 0, 0, 0),
+    ]
+    pricing = [("claude-sonnet-4-5-20250929", 3.0, 15.0, 3.75, 0.3, 6.0)]
+
+    cost_by_model, unpriced = price_model_usage(usage, pricing)
+
+    assert cost_by_model == {"claude-sonnet-4-5": 18.0}
+    assert unpriced == ["mystery-model"]
+
+
+def test_record_cost_uses_shared_pricing_table(make_record) -> None:
+    rows = [("claude-sonnet-4-5-20250929", 3.0, 15.0, 3.75, 0.3, 6.0)]
+    pricing = PricingTable(rows, aliases={}, long_context_prices={})
+    usage = TokenUsage(
+        input_tokens=1_000_000, output_tokens=0, cache_creation_tokens=2_000_000,
+        cache_read_tokens=0, cache_creation_1h_tokens=1_000_000,
+    )
+    record = make_record(usage=usage)
+
+    assert record.cost(pricing) == 3.0 + 3.75 + 6.0
+    assert usage.cost(pricing, "mystery-model") == 0.0
+    summed, _ = price_model_usage([("claude-sonnet-4-5", 1_000_000, 0, 2_000_000, 0, 1_000_000)], rows)
+    assert record.cost(pricing) == summed["claude-sonnet-4-5"]
+
+
+def test_long_context_requests_bill_at_tier_rates() -> None:
+    rows = [("claude-sonnet-4-5-20250929", 3.0, 15.0, 3.75, 0.3, 6.0)]
+    tiers = {"claude-sonnet-4-5-20250929": (6.0, 22.5, 7.5, 0.6, None)}
+    pricing = PricingTable(rows, aliases={}, long_context_prices=tiers)
+    short = TokenUsage(input_tokens=200_000, output_tokens=1_000_000, cache_creation_tokens=0, cache_read_tokens=0)
+    long = TokenUsage(input_tokens=100_000, output_tokens=1_000_000, cache_creation_tokens=0, cache_read_tokens=150_000)
+
+    assert short.cost(pricing, "claude-sonnet-4-5") == 0.6 + 15.0
+    assert long.cost(pricing, "claude-sonnet-4-5") == 0.6 + 22.5 + 0.09
+    # Summed rows split on the per-request flag price each part at its tier
+    summed, _ = price_model_usage(
+        [
+            ("claude-sonnet-4-5", 200_000, 1_000_000, 0, 0, 0, 0),
+            ("claude-sonnet-4-5", 100_000, 1_000_000, 0, 150_000, 0, 1),
+        ],
+        rows,
+    )
+    expected = short.cost(pricing, "claude-sonnet-4-5") + long.cost(pricing, "claude-sonnet-4-5")
+    assert summed["claude-sonnet-4-5"] == expected
+
+
+def test_pricing_tier_multipliers_scale_costs() -> None:
+    batch = load_tier_multipliers("batch")
+    assert batch["*"] == 0.5
+    assert load_tier_multipliers("priority")["gpt-5-codex"] == 2.0
+    assert load_tier_multipliers("standard") == {}
+
+    rows = [
+        ("claude-sonnet-4-5-20250929", 3.0, 15.0, 3.75, 0.3, 6.0),
+        ("gpt-5-codex", 1.25, 10.0, 0.0, 0.125, 0.0),
+    ]
+    # A negotiated rate set by alias replaces the tier multiplier for that model
+    pricing = PricingTable(
+        rows, aliases={}, long_context_prices={}, multipliers={**batch, "claude-sonnet-4-5": 0.75}
+    )
+    usage = TokenUsage(input_tokens=1_000_000, output_tokens=1_000_000, cache_creation_tokens=0, cache_read_tokens=0)
+
+    assert usage.cost(pricing, "claude-sonnet-4-5-20250929") == 0.75 * 18.0
+    assert usage.cost(pricing, "gpt-5-codex") == 0.5 * 11.25
+
+
+def test_pricing_overrides_replace_rows_and_price_new_models(tmp_path, monkeypatch) -> None:
+    monkeypatch.setattr(user_config, "CONFIG_PATH", tmp_path / "goblin_config.json")
+    user_config.set_pricing_overrides({
+        "claude-sonnet-4-5": {"input_per_mtok": 2.0, "output_per_mtok": 10.0},
+        "claude-sonnet-4-7": {"input_per_mtok": 4.0, "output_per_mtok": 20.0, "cache_read_per_mtok": 1.0},
+    })
+    rows = [
+        ("claude-sonnet-4-5-20250929", 3.0, 15.0, 3.75, 0.3, 6.0),
+        ("claude-sonnet-4", 3.0, 15.0, 3.75, 0.3, 6.0),
+    ]
+    pricing = PricingTable(
+        rows, aliases={}, long_context_prices={"claude-sonnet-4-5-20250929": (6.0, 22.5, 7.5, 0.6, 12.0)},
+        multipliers={},
+    )
+    usage = TokenUsage(input_tokens=1_000_000, output_tokens=1_000_000, cache_creation_tokens=0, cache_read_tokens=0)
+
+    # The dated row takes the override, long-context rates included
+    assert usage.cost(pricing, "claude-sonnet-4-5-20250929") == 12.0
+    assert pricing.cost("claude-sonnet-4-5-20250929", 1_000_000, long_context=True) == 2.0
+    # A newer model gets its own row instead of repricing its family
+    assert usage.cost(pricing, "claude-sonnet-4-7") == 24.0
+    assert usage.cost(pricing, "claude-sonnet-4") == 18.0
+    assert pricing.prices["claude-sonnet-4-7"][2:4] == (5.0, 1.0)
@@ -0,0 +1,128 @@
+import json
+from datetime import datetime
+from pathlib import Path
+
+import pytest
+
+from src.storage import pricing_remote, snapshot_db
+from src.storage.pricing_remote import (
+    parse_litellm_pricing,
+    remote_pricing_is_stale,
+    remote_pricing_path,
+    remote_pricing_rows,
+    update_remote_pricing,
+    with_remote_pricing,
+)
+
+LITELLM_SAMPLE = {
+    "sample_spec": {"input_cost_per_token": 0.0},
+    "claude-sonnet-4-5-20250929": {
+        "litellm_provider": "anthropic",
+        "input_cost_per_token": 3e-06,
+        "output_cost_per_token": 1.5e-05,
+        "cache_creation_input_token_cost": 3.75e-06,
+        "cache_read_input_token_cost": 3e-07,
+    },
+    "claude-opus-9-20270101": {
+        "litellm_provider": "anthropic",
+        "input_cost_per_token": 5e-06,
+        "output_cost_per_token": 2.5e-05,
+    },
+    "anthropic.claude-sonnet-4-5-20250929-v1:0": {
+        "litellm_provider": "bedrock",
+        "input_cost_per_token": 3.3e-06,
+        "output_cost_per_token": 1.65e-05,
+    },
+    "gpt-5": {"litellm_provider": "openai", "input_cost_per_token": 1e-06, "output_cost_per_token": 1e-05},
+}
+
+
+def test_parse_litellm_keeps_anthropic_models_per_mtok() -> None:
+    models = parse_litellm_pricing(LITELLM_SAMPLE)
+
+    assert set(models) == {"claude-sonnet-4-5-20250929", "claude-opus-9-20270101"}
+    assert models["claude-sonnet-4-5-20250929"] == {
+        "input_per_mtok": 3.0,
+        "output_per_mtok": 15.0,
+        "cache_write_per_mtok": 3.75,
+        "cache_read_per_mtok": 0.3,
+    }
+
+
+def test_update_caches_fetch_and_failure_records_attempt(tmp_path: Path, monkeypatch) -> None:
+    cache_path = tmp_path / "remote_pricing.json"
+    monkeypatch.setattr(pricing_remote, "fetch_remote_pricing", lambda url: parse_litellm_pricing(LITELLM_SAMPLE))
+    update_remote_pricing("https://example.test/prices.json", cache_path)
+
+    cache = json.loads(cache_path.read_text())
+    assert cache["source"] == "https://example.test/prices.json"
+    assert cache["fetched_at"] == cache["attempted_at"]
+
+    def fail(url):
+        raise RuntimeError("offline")
+
+    cache_path.write_text(json.dumps({**cache, "attempted_at": "2026-01-01T00:00:00"}))
+    monkeypatch.setattr(pricing_remote, "fetch_remote_pricing", fail)
+    with pytest.raises(RuntimeError):
+        update_remote_pricing("https://example.test/prices.json", cache_path)
+
+    after = json.loads(cache_path.read_text())
+    assert after["models"] == cache["models"]
+    assert after["fetched_at"] == cache["fetched_at"]
+    assert after["attempted_at"] != "2026-01-01T00:00:00"
+
+
+def test_remote_rows_override_and_extend_bundled(tmp_path: Path) -> None:
+    cache_path = tmp_path / "remote_pricing.json"
+    cache_path.write_text(json.dumps({"models": parse_litellm_pricing(LITELLM_SAMPLE)}))
+    bundled = [
+        ("claude-sonnet-4-5-20250929", 2.0, 10.0, 2.5, 0.2, 4.0, "bundled"),
+        ("claude-haiku-4-5-20251001", 1.0, 5.0, 1.25, 0.1, 2.0, "bundled"),
+    ]
+
+    rows = {row[0]: row for row in with_remote_pricing(bundled, cache_path)}
+
+    assert rows["claude-sonnet-4-5-20250929"][1:3] == (3.0, 15.0)
+    assert rows["claude-haiku-4-5-20251001"][6] == "bundled"
+    assert rows["claude-opus-9-20270101"][1:6] == (5.0, 25.0, 6.25, 0.5, 10.0)
+
+
+def test_remote_rows_skip_non_numeric_rates(tmp_path: Path) -> None:
+    cache_path = tmp_path / "remote_pricing.json"
+    models = parse_litellm_pricing(LITELLM_SAMPLE)
+    models["claude-opus-9-20270101"]["input_per_mtok"] = "5.0"
+    models["claude-sonnet-4-5-20250929"]["cache_read_per_mtok"] = True
+    cache_path.write_text(json.dumps({"models": models}))
+
+    assert remote_pricing_rows(cache_path) == []
+
+
+def test_remote_pricing_cache_sits_beside_the_database(db_path: Path, monkeypatch) -> None:
+    monkeypatch.setattr("src.storage.pricing_remote.get_db_path", lambda: db_path)
+
+    assert remote_pricing_path() == db_path.parent / "remote_pricing.json"
+
+
+def test_sync_writes_fetched_models_to_model_pricing(db_path: Path, monkeypatch) -> None:
+    cache_path = db_path.parent / "remote_pricing.json"
+    cache_path.write_text(json.dumps({"models": parse_litellm_pricing(LITELLM_SAMPLE)}))
+    monkeypatch.setattr("src.storage.pricing_remote.get_db_path", lambda: db_path)
+
+    snapshot_db.sync_model_pricing(db_path)
+
+    prices = {row[0]: row[1:3] for row in snapshot_db.get_model_pricing(db_path)}
+    assert prices["claude-opus-9-20270101"] == (5.0, 25.0)
+
+
+def test_stale_only_when_auto_update_on_and_ttl_passed(tmp_path: Path, monkeypatch) -> None:
+    cache_path = tmp_path / "remote_pricing.json"
+    cache_path.write_text(json.dumps({"attempted_at": "2026-10-01T00:00:00"}))
+    monkeypatch.setattr("src.storage.pricing_remote.get_pricing_update_hours", lambda: 24)
+
+    monkeypatch.setattr("src.storage.pricing_remote.is_pricing_auto_update", lambda: False)
+    assert not remote_pricing_is_stale(datetime(2026, 10, 5), cache_path)
+
+    monkeypatch.setattr("src.storage.pricing_remote.is_pricing_auto_update", lambda: True)
+    assert not remote_pricing_is_stale(datetime(2026, 10, 1, 12), cache_path)
+    assert remote_pricing_is_stale(datetime(2026, 10, 2, 1), cache_path)
+    assert remote_pricing_is_stale(datetime(2026, 10, 2), tmp_path / "missing.json")
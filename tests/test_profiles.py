@@ -0,0 +1,73 @@
+from functools import partial
+from pathlib import Path
+
+import pytest
+import typer
+
+import src.storage as storage
+from src import cli
+from src.commands.imports import db as import_db
+from src.config import settings
+from src.storage import api, snapshot_db
+
+
+def _profiles(monkeypatch, tmp_path: Path) -> dict[str, dict]:
+    profiles = {
+        "work": {"data_dir": tmp_path / "work" / "projects", "db_path": tmp_path / "work.db"},
+        "home": {"data_dir": tmp_path / "home" / "projects", "db_path": None},
+    }
+    monkeypatch.setattr(cli, "get_profiles", lambda: profiles)
+    monkeypatch.setattr(storage, "DEFAULT_USAGE_DIR", tmp_path / "usage")
+    monkeypatch.setattr(storage, "get_storage_format", lambda: "sqlite")
+    monkeypatch.setattr("src.storage.api.get_backend_module", lambda: snapshot_db)
+    monkeypatch.setattr(import_db, "get_storage_mode", lambda: "full")
+    monkeypatch.setattr(import_db, "is_read_only", lambda: False)
+    monkeypatch.setattr(settings, "_data_dir_overrides", [])
+    monkeypatch.setattr(storage, "_db_path_override", None)
+    return profiles
+
+
+def test_profile_selection_picks_the_database_and_transcript_dirs(tmp_path: Path, monkeypatch) -> None:
+    profiles = _profiles(monkeypatch, tmp_path)
+
+    cli._activate_profiles(["work"])
+    assert storage.get_db_path() == tmp_path / "work.db"
+    assert settings.get_claude_data_dirs() == [profiles["work"]["data_dir"].resolve()]
+
+    # Repeats collapse; the merged database is named by the sorted selection
+    cli._activate_profiles(["work", "home", "work"])
+    assert storage.get_db_path() == tmp_path / "usage" / "profile-home+work.db"
+    assert settings.get_claude_data_dirs() == [
+        profiles["work"]["data_dir"].resolve(), profiles["home"]["data_dir"].resolve(),
+    ]
+
+    with pytest.raises(typer.Exit):
+        cli._activate_profiles(["work", "play"])
+
+
+def test_merged_profiles_start_from_each_profiles_database(tmp_path: Path, monkeypatch, make_record) -> None:
+    _profiles(monkeypatch, tmp_path)
+    work_db, home_db = tmp_path / "work.db", tmp_path / "usage" / "profile-home.db"
+    work, home = partial(make_record, session="s1", folder="/work"), partial(make_record, session="s2", folder="/home")
+    # History each profile kept after its transcripts were deleted
+    snapshot_db.save_snapshot([work("w1")], db_path=work_db, storage_mode="full")
+    snapshot_db.save_snapshot([home("h1"), home("shared", session="s3")], db_path=home_db, storage_mode="full")
+    snapshot_db.save_snapshot([home("shared", session="s3")], db_path=work_db, storage_mode="full")
+
+    cli._activate_profiles(["work", "home"])
+    merged = sorted(r.message_uuid for r in api.iter_historical_records())
+    assert merged == ["h1", "shared", "w1"]
+
+    # Unchanged profile databases aren't merged again; a changed one is
+    calls = []
+    merge = import_db.merge_database
+    monkeypatch.setattr(import_db, "merge_database", lambda path: calls.append(path.name) or merge(path))
+    cli._activate_profiles(["work", "home"])
+    assert calls == []
+
+    snapshot_db.save_snapshot([work("w2")], db_path=work_db, storage_mode="full")
+    cli._activate_profiles(["work", "home"])
+    assert calls == ["work.db"]
+    assert "w2" in {r.message_uuid for r in api.iter_historical_records()}
+    # The profiles' own databases are left alone
+    assert len(list(snapshot_db.iter_historical_records(db_path=home_db))) == 2
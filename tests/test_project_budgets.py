@@ -0,0 +1,25 @@
+from src.aggregation.project_stats import check_project_budgets
+
+
+def test_budgets_cover_subfolders_and_flag_overspend() -> None:
+    daily_projects = {
+        "2026-10-01": {
+            "/clients/acme": {"tokens": 100, "cost": 120.0},
+            "/clients/acme/api": {"tokens": 50, "cost": 40.0},
+            "/clients/acme-old": {"tokens": 10, "cost": 500.0},
+        },
+        "2026-10-02": {
+            "/clients/acme": {"tokens": 100, "cost": 60.0},
+            "/oss/goblin": {"tokens": 10, "cost": 5.0},
+        },
+    }
+    budgets = {"/clients/acme/": 200.0, "/oss/goblin": 20.0, "/idle": 10.0}
+
+    results = check_project_budgets(budgets, daily_projects)
+
+    assert [(b.folder, b.spent, b.over_budget) for b in results] == [
+        ("/clients/acme/", 220.0, True),  # a sibling with the same prefix is not counted
+        ("/oss/goblin", 5.0, False),
+        ("/idle", 0.0, False),
+    ]
+    assert results[0].used == 1.1
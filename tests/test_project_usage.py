@@ -0,0 +1,51 @@
+from datetime import date
+
+import pytest
+
+from src.aggregation.project_stats import aggregate_by_project, find_idle_projects
+from src.storage.pricing import PricingTable
+
+PRICING = PricingTable(
+    [("claude-sonnet-4-5", 3.0, 15.0, 3.75, 0.3, 6.0), ("claude-haiku-4-5", 1.0, 5.0, 1.25, 0.1, 2.0)],
+    aliases={}, long_context_prices={}, multipliers={}, overrides={},
+)
+
+
+def test_projects_get_tokens_prompts_sessions_and_cost(make_record) -> None:
+    records = [
+        make_record("u1", day=1, folder="/p/api", session="s1", message_type="user"),
+        make_record("a1", day=1, folder="/p/api", session="s1", tokens=1_000_000),
+        make_record("u2", day=3, folder="/p/api", session="s2", message_type="user"),
+        make_record("a2", day=3, folder="/p/api", session="s2", model="claude-haiku-4-5", tokens=1_000_000),
+        make_record("u3", day=2, folder="/p/web", session="s3", message_type="user"),
+        make_record("a3", day=2, folder="/p/web", session="s3", model="claude-haiku-4-5", tokens=5_000_000),
+    ]
+
+    projects = aggregate_by_project(iter(records), PRICING)
+
+    assert [
+        (p.folder, p.first_date, p.last_date, p.active_days, p.sessions, p.prompts, p.total_tokens)
+        for p in projects
+    ] == [
+        ("/p/web", "2026-10-02", "2026-10-02", 1, 1, 1, 5_000_000),
+        ("/p/api", "2026-10-01", "2026-10-03", 2, 2, 2, 2_000_000),
+    ]
+    assert projects[0].cost == pytest.approx(5.0)
+    assert projects[1].cost == pytest.approx(3.0 + 1.0)
+
+
+def test_idle_projects_are_those_quiet_for_at_least_the_window() -> None:
+    projects = [
+        {"folder": "/p/active", "first_date": "2026-09-01", "last_date": "2026-10-15", "cost": 9.0},
+        {"folder": "/p/edge", "first_date": "2026-08-01", "last_date": "2026-09-16", "cost": 1.0},
+        {"folder": "/p/cheap", "first_date": "2026-06-01", "last_date": "2026-07-01", "cost": 0.5},
+        {"folder": "/p/costly", "first_date": "2026-06-01", "last_date": "2026-07-01", "cost": 20.0},
+    ]
+
+    idle = find_idle_projects(projects, 30, today=date(2026, 10, 16))
+
+    # Longest idle first, then costliest; exactly idle_days old counts as idle
+    assert [(p.folder, p.days_idle(date(2026, 10, 16))) for p in idle] == [
+        ("/p/costly", 107), ("/p/cheap", 107), ("/p/edge", 30),
+    ]
+    assert find_idle_projects(projects, 200, today=date(2026, 10, 16)) == []
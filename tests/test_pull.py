@@ -0,0 +1,63 @@
+import io
+
+import pytest
+
+from src.commands import pull
+from src.commands.pull import DEFAULT_REMOTE_DIR, parse_ssh_target, remote_command
+
+
+def test_parses_ssh_urls_into_destination_port_dir_and_host() -> None:
+    assert parse_ssh_target("ssh://devbox") == ("devbox", None, DEFAULT_REMOTE_DIR, "devbox")
+    assert parse_ssh_target("ssh://me@devbox:2222/srv/claude/projects") == (
+        "me@devbox", 2222, "/srv/claude/projects", "devbox",
+    )
+    assert parse_ssh_target("ssh://me@devbox/~/work/.claude/projects")[2] == "~/work/.claude/projects"
+    with pytest.raises(ValueError):
+        parse_ssh_target("me@devbox")
+    with pytest.raises(ValueError):
+        parse_ssh_target("ssh://")
+
+
+def test_targets_that_look_like_ssh_options_are_rejected() -> None:
+    for target in ("ssh://-oProxyCommand=touch%20pwned", "ssh://-oProxyCommand=x@devbox"):
+        with pytest.raises(ValueError, match="may not start with '-'"):
+            parse_ssh_target(target)
+
+
+def test_destination_follows_an_end_of_options_marker(monkeypatch) -> None:
+    calls = []
+
+    class FakePopen:
+        def __init__(self, command, **kwargs) -> None:
+            calls.append(command)
+            self.stdout = io.StringIO("")
+
+        def __enter__(self):
+            return self
+
+        def __exit__(self, *exc) -> None:
+            pass
+
+        def wait(self) -> int:
+            return 0
+
+    class Console:
+        def print(self, *args, **kwargs) -> None:
+            pass
+
+    monkeypatch.setattr(pull, "is_read_only", lambda: False)
+    monkeypatch.setattr(pull.shutil, "which", lambda name: "/usr/bin/ssh")
+    monkeypatch.setattr(pull.subprocess, "Popen", FakePopen)
+    monkeypatch.setattr(pull.update_usage, "run", lambda *args, **kwargs: None)
+
+    assert pull.run(Console(), "ssh://me@devbox:2222") == 0
+    command = calls[0]
+    assert command[command.index("--") + 1] == "me@devbox"
+    assert command.index("-p") < command.index("--")
+
+
+def test_remote_command_quotes_paths_and_filters_by_age() -> None:
+    assert remote_command("~/.claude/projects").startswith("""find "$HOME"/.claude/projects -type f""")
+    command = remote_command("/srv/my projects", days=7)
+    assert command.startswith("find '/srv/my projects' -type f -name '*.jsonl' -mtime -7 -exec")
+    assert command.endswith("""sh -c 'for f; do cat "$f"; echo; done' sh {} +""")
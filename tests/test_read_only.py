@@ -0,0 +1,28 @@
+import sqlite3
+from pathlib import Path
+
+from src.storage.snapshot_db import load_daily_snapshots, save_snapshot
+
+
+def test_read_only_mode_reads_but_never_writes(tmp_path: Path, db_path: Path, monkeypatch, make_record) -> None:
+    save_snapshot([make_record("a1")], db_path=db_path, storage_mode="full")
+    before = db_path.read_bytes()
+    listing = sorted(p.name for p in tmp_path.iterdir())
+    monkeypatch.setattr("src.config.user_config._read_only_override", True)
+
+    assert [day["total_tokens"] for day in load_daily_snapshots(db_path=db_path)] == [100]
+    # A WAL database read with plain mode=ro would leave -wal/-shm sidecars
+    assert sorted(p.name for p in tmp_path.iterdir()) == listing
+    try:
+        save_snapshot([make_record("a2")], db_path=db_path, storage_mode="full")
+    except sqlite3.OperationalError as e:
+        assert "readonly" in str(e)
+    else:
+        raise AssertionError("expected the write to fail")
+    assert db_path.read_bytes() == before
+    assert sorted(p.name for p in tmp_path.iterdir()) == listing
+
+    # Nothing is created for a database that does not exist
+    missing = tmp_path / "missing" / "usage_history.db"
+    assert load_daily_snapshots(db_path=missing) == []
+    assert not missing.parent.exists()
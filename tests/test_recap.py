@@ -0,0 +1,90 @@
+from datetime import date
+
+from src.aggregation.daily_stats import AggregatedStats, DailyStats
+from src.aggregation.recap import MonthRecap, build_month_recap, current_streak, recap_sentences
+from src.visualization.export import render_heatmap_svg
+
+
+def _day(date: str, tokens: int, models: dict[str, int]) -> dict:
+    return {
+        "date": date,
+        "prompts": 2,
+        "responses": 2,
+        "sessions": 1,
+        "total_tokens": tokens,
+        "cost": tokens / 1000,
+        "models": {model: {"total_tokens": count} for model, count in models.items()},
+    }
+
+
+def test_build_month_recap_finds_biggest_day_and_streak() -> None:
+    days = [
+        _day("2025-11-02", 100, {"sonnet": 100}),
+        _day("2025-11-03", 500, {"opus": 500}),
+        _day("2025-11-04", 400, {"sonnet": 400}),
+        _day("2025-11-10", 0, {}),
+        _day("2025-12-01", 9000, {"opus": 9000}),
+    ]
+
+    recap = build_month_recap("2025-11", days, {"/work/alpha": 700, "/work/beta": 300})
+
+    assert recap.total_tokens == 1000
+    assert (recap.active_days, recap.days_in_month) == (3, 30)
+    assert (recap.biggest_day, recap.biggest_day_tokens) == ("2025-11-03", 500)
+    assert (recap.longest_streak, recap.streak_start) == (3, "2025-11-02")
+    assert recap.model_shares == {"sonnet": 50.0, "opus": 50.0}
+    assert recap.top_projects[0] == ("/work/alpha", 700)
+
+
+def test_recap_sentences_compare_against_previous_month() -> None:
+    previous = MonthRecap(month="2025-10", total_tokens=500, model_shares={"sonnet": 90.0, "opus": 10.0})
+    recap = build_month_recap(
+        "2025-11",
+        [_day("2025-11-03", 1000, {"sonnet": 400, "opus": 600})],
+        {"/work/alpha": 1000},
+    )
+
+    text = " ".join(recap_sentences(recap, previous))
+
+    assert "up 100% on the month before" in text
+    assert "Monday the 3rd" in text
+    assert "alpha (100%)" in text
+    assert "opus grew from 10% to 60%" in text
+
+
+def test_recap_names_projects_alike_in_both_storage_modes() -> None:
+    day = [_day("2025-11-03", 1000, {"sonnet": 1000})]
+    full = build_month_recap("2025-11", day, {"/home/me/acme/api": 600, "C:\\work\\web": 400})
+    # Aggregate mode knows projects only by Claude's transcript directory name
+    aggregate = build_month_recap("2025-11", day, {"-home-me-acme-api": 600, "C--work-web": 400})
+
+    assert recap_sentences(full)[2] == recap_sentences(aggregate)[2]
+    assert recap_sentences(aggregate)[2] == "Most of the work went into acme/api (60%) and work/web (40%)."
+
+
+def test_recap_round_trips_through_stored_summary() -> None:
+    recap = build_month_recap("2025-11", [_day("2025-11-03", 10, {"sonnet": 10})], {"/work/alpha": 10})
+
+    assert MonthRecap.from_dict(recap.to_dict()) == recap
+
+
+def test_current_streak_survives_an_idle_today() -> None:
+    active = {"2026-10-12", "2026-10-14", "2026-10-15"}
+
+    assert current_streak(active, "2026-10-15") == (2, "2026-10-14")
+    assert current_streak(active, "2026-10-16") == (2, "2026-10-14")
+    assert current_streak(active, "2026-10-17") == (0, None)
+
+
+def test_heatmap_footer_shows_the_window_streaks() -> None:
+    def stats_for(day: str, tokens: int) -> DailyStats:
+        return DailyStats(day, 1, 1, 1, tokens, tokens, 0, 0, 0, set(), set())
+
+    days = [("2025-11-02", 100), ("2025-11-03", 500), ("2025-11-09", 40)]
+    daily = {day: stats_for(day, tokens) for day, tokens in days}
+    stats = AggregatedStats(daily_stats=daily, overall_totals=stats_for("", 640))
+
+    svg = render_heatmap_svg(stats, start_date=date(2025, 11, 1), end_date=date(2025, 11, 30))
+
+    assert "Longest streak: 2 days" in svg
+    assert "Current streak" not in svg
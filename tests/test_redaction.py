@@ -0,0 +1,31 @@
+from functools import partial
+
+import pytest
+
+from src.data.redaction import redact_records
+
+
+@pytest.fixture
+def prompt(make_record):
+    return partial(make_record, message_type="user", content="fix the secret project", char_count=22)
+
+
+def test_hash_mode_keeps_projects_distinct_without_names(prompt) -> None:
+    a, b, again = redact_records([
+        prompt(folder="/home/me/secret", branch="feat/acme"),
+        prompt(folder="/home/me/other"),
+        prompt(folder="/home/me/secret"),
+    ], "hash")
+
+    assert a.folder == again.folder != b.folder
+    assert "secret" not in a.folder and a.folder.startswith("/redacted/")
+    assert a.git_branch.startswith("branch-") and "acme" not in a.git_branch
+    assert b.git_branch is None
+    assert a.content is None and a.char_count == 22
+
+
+def test_strip_mode_drops_folders_and_branches(prompt) -> None:
+    (record,) = redact_records([prompt(folder="/home/me/secret", branch="main")], "strip")
+
+    assert (record.folder, record.git_branch, record.content) == ("redacted", None, None)
+    assert redact_records([prompt(folder="/x", branch="main")], None)[0].folder == "/x"
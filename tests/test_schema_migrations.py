@@ -0,0 +1,145 @@
+import sqlite3
+from pathlib import Path
+
+from src.storage.schema_migrations import LATEST_SCHEMA_VERSION, apply_migrations, get_schema_version
+from src.storage.snapshot_db import init_database
+
+
+def _columns(conn: sqlite3.Connection, table: str) -> set[str]:
+    return {row[1] for row in conn.execute(f"PRAGMA table_info({table})")}
+
+
+def test_old_database_is_upgraded_on_open(db_path: Path) -> None:
+    conn = sqlite3.connect(db_path)
+    # usage_records and sessions as an early release created them
+    conn.execute("""
+        CREATE TABLE usage_records (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            date TEXT NOT NULL, timestamp TEXT NOT NULL, session_id TEXT NOT NULL,
+            message_uuid TEXT NOT NULL, message_type TEXT NOT NULL, model TEXT,
+            folder TEXT NOT NULL, git_branch TEXT, version TEXT NOT NULL,
+            input_tokens INTEGER NOT NULL, output_tokens INTEGER NOT NULL,
+            cache_creation_tokens INTEGER NOT NULL, cache_read_tokens INTEGER NOT NULL,
+            total_tokens INTEGER NOT NULL,
+            UNIQUE(session_id, message_uuid)
+        )
+    """)
+    conn.execute("""
+        INSERT INTO usage_records VALUES (
+            1, '2026-10-01', '2026-10-01T10:00:00', 's1', 'm1', 'assistant', 'claude-sonnet-4',
+            '/repo', NULL, '2.0', 10, 5, 0, 0, 15
+        )
+    """)
+    conn.execute("""
+        CREATE TABLE sessions (
+            session_id TEXT PRIMARY KEY, folder TEXT, started_at TEXT NOT NULL,
+            ended_at TEXT NOT NULL, messages INTEGER NOT NULL DEFAULT 0,
+            total_tokens INTEGER NOT NULL DEFAULT 0, title TEXT
+        )
+    """)
+    conn.commit()
+    conn.close()
+
+    init_database(db_path)
+
+    conn = sqlite3.connect(db_path)
+    try:
+        assert {"device_id", "cache_creation_1h_tokens", "thinking_tokens", "is_subagent",
+                "attachment_count", "project", "agent_id"} <= _columns(conn, "usage_records")
+        assert "entrypoint" in _columns(conn, "sessions")
+        assert get_schema_version(conn.cursor()) == LATEST_SCHEMA_VERSION
+        # Existing rows survive, and the model-mix backfill picked them up
+        assert conn.execute("SELECT total_tokens FROM usage_records").fetchone() == (15,)
+        assert conn.execute("SELECT model, responses FROM daily_model_snapshots").fetchall() == [
+            ("claude-sonnet-4", 1)
+        ]
+        # Reopening applies nothing
+        assert apply_migrations(conn.cursor()) == []
+    finally:
+        conn.close()
+
+
+def test_new_database_starts_at_latest_version(db_path: Path) -> None:
+
+    init_database(db_path)
+    init_database(db_path)
+
+    conn = sqlite3.connect(db_path)
+    try:
+        versions = [row[0] for row in conn.execute("SELECT version FROM schema_version ORDER BY version")]
+        assert versions == list(range(1, LATEST_SCHEMA_VERSION + 1))
+    finally:
+        conn.close()
+
+
+def test_new_database_enforces_one_row_per_billed_response(db_path: Path) -> None:
+    import pytest
+
+    init_database(db_path)
+
+    insert = """
+        INSERT INTO usage_records (date, timestamp, session_id, message_uuid, message_type, folder,
+            version, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, total_tokens)
+        VALUES ('2026-10-01', '2026-10-01T12:00:00', ?, ?, ?, '/repo', '2.0', 1, 0, 0, 0, 1)
+    """
+    conn = sqlite3.connect(db_path)
+    try:
+        sql = conn.execute(
+            "SELECT sql FROM sqlite_master WHERE type = 'index' AND name = 'idx_usage_records_response'"
+        ).fetchone()[0]
+        assert "UNIQUE" in sql and "WHERE message_type = 'assistant'" in sql
+        conn.execute(insert, ("s1", "msg-1:req-1", "assistant"))
+        # User rows are session-scoped, so only assistant rows are unique
+        conn.execute(insert, ("s1", "u1", "user"))
+        conn.execute(insert, ("s2", "u1", "user"))
+        with pytest.raises(sqlite3.IntegrityError):
+            conn.execute(insert, ("s2", "msg-1:req-1", "assistant"))
+    finally:
+        conn.close()
+
+
+def test_duplicate_responses_are_dropped_once_and_then_rejected(db_path: Path, make_record) -> None:
+    import pytest
+
+    from src.storage.snapshot_db import load_daily_snapshots, save_snapshot
+
+    save_snapshot([make_record("msg-1:req-1", session="s1", tokens=10)], db_path=db_path, storage_mode="full")
+    # An older release stored the same response again under a forked session
+    conn = sqlite3.connect(db_path)
+    conn.execute("DROP INDEX idx_usage_records_response")
+    conn.execute("DELETE FROM schema_version WHERE version >= 12")
+    conn.execute("""
+        INSERT INTO usage_records (date, timestamp, session_id, message_uuid, message_type, model, folder,
+            version, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, total_tokens)
+        SELECT date, timestamp, 's2', message_uuid, message_type, model, folder,
+            version, 40, 0, 0, 0, 40
+        FROM usage_records
+    """)
+    conn.execute("UPDATE daily_snapshots SET total_tokens = 50")
+    conn.commit()
+    conn.close()
+
+    init_database(db_path)
+
+    conn = sqlite3.connect(db_path)
+    try:
+        # The fuller copy is kept and the day's snapshot recomputed
+        assert conn.execute("SELECT session_id, total_tokens FROM usage_records").fetchall() == [("s2", 40)]
+        assert [day["total_tokens"] for day in load_daily_snapshots(db_path=db_path)] == [40]
+        with pytest.raises(sqlite3.IntegrityError):
+            conn.execute("""
+                INSERT INTO usage_records (date, timestamp, session_id, message_uuid, message_type, folder,
+                    version, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, total_tokens)
+                VALUES ('2026-10-01', '2026-10-01T12:00:00', 's3', 'msg-1:req-1', 'assistant', '/repo',
+                    '2.0', 1, 0, 0, 0, 1)
+            """)
+    finally:
+        conn.close()
+
+    # Saving the response again under yet another session keeps one row
+    save_snapshot([make_record("msg-1:req-1", session="s3", tokens=40)], db_path=db_path, storage_mode="full")
+    conn = sqlite3.connect(db_path)
+    try:
+        assert conn.execute("SELECT COUNT(*) FROM usage_records").fetchone() == (1,)
+    finally:
+        conn.close()
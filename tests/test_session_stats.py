@@ -0,0 +1,88 @@
+from datetime import datetime, timezone
+
+from src.aggregation.session_stats import (
+    SessionSummary,
+    build_sessions,
+    concurrency_by_day,
+    split_automation,
+    summarize_by_tag,
+)
+from src.models.usage_record import Session, UsageRecord
+
+
+def _session(session_id: str, tokens: int, cost: float) -> SessionSummary:
+    now = datetime(2026, 1, 1)
+    return SessionSummary(session_id=session_id, folder="/repo", start=now, end=now, total_tokens=tokens, cost=cost)
+
+
+def test_summarize_by_tag_counts_multi_tagged_sessions_under_each_tag() -> None:
+    summaries = [_session("a", 100, 1.0), _session("b", 50, 3.0), _session("c", 10, 0.5)]
+    tags = {"a": ["debugging", "infra"], "b": ["debugging"]}
+
+    totals = {t.tag: (t.sessions, t.total_tokens, t.cost) for t in summarize_by_tag(summaries, tags)}
+
+    assert totals == {"debugging": (2, 150, 4.0), "infra": (1, 100, 1.0), None: (1, 10, 0.5)}
+
+
+def test_build_sessions_titles_from_first_real_prompt(make_record) -> None:
+    def at(minute: int) -> datetime:
+        return datetime(2026, 1, 5, 10, minute, tzinfo=timezone.utc)
+
+    records = [
+        make_record("m30", when=at(30), session="s", folder="/home/me/repo", tokens=100, output=20),
+        make_record("m1", when=at(1), session="s", message_type="user", folder="/home/me/repo",
+                    content="Fix the   flaky\nlogin test"),
+        make_record("m0", when=at(0), session="s", message_type="user", folder="/home/me/repo",
+                    content="<command-name>/clear</command-name>"),
+    ]
+
+    [untitled] = build_sessions(records)
+    [session] = build_sessions(records, titles=True)
+
+    assert untitled.title is None
+    assert untitled.label == "me/repo " + untitled.start.astimezone().strftime("%Y-%m-%d %H:%M")
+    assert session.title == "Fix the flaky login test"
+    assert (session.messages, session.total_tokens) == (3, 120)
+    assert session.duration.total_seconds() == 30 * 60
+
+
+def test_concurrency_counts_interleaved_sessions_and_ignores_idle_gaps(make_record) -> None:
+    def at(session: str, hour: int, minute: int) -> UsageRecord:
+        return make_record(f"{session}{hour}{minute}", when=datetime(2026, 10, 1, hour, minute).astimezone(),
+                           session=session, message_type="user", folder="/repo")
+
+    records = [
+        at("a", 9, 0), at("a", 9, 5), at("a", 9, 10),
+        at("b", 9, 4), at("b", 9, 8),
+        # a idles for an hour, so 9:10-10:10 is not active
+        at("a", 10, 10), at("a", 10, 12),
+        at("c", 11, 0),
+    ]
+
+    (day,) = concurrency_by_day(records)
+
+    assert day.date == "2026-10-01"
+    assert day.max_concurrent == 2
+    assert day.overlap_minutes == 4
+    # 16 session-minutes (a: 10 + 2, b: 4) over 12 minutes with any active
+    assert round(day.avg_concurrent, 3) == round(16 / 12, 3)
+
+
+def test_split_automation_by_entrypoint_and_tagged_folder() -> None:
+    def session(session_id: str, folder: str, tokens: int, entrypoint: str | None = None) -> Session:
+        now = datetime(2026, 10, 1, tzinfo=timezone.utc)
+        return Session(session_id, folder, now, now, total_tokens=tokens, entrypoint=entrypoint)
+
+    split = split_automation(
+        [
+            session("a", "/home/me/app", 100, "cli"),
+            session("b", "/home/me/app", 40, "sdk-cli"),
+            session("c", "/home/me/bots/triage/sub", 10),
+            session("d", "/home/me/bots-old", 5),
+        ],
+        ["/home/me/bots/triage"],
+    )
+
+    assert (split.interactive_sessions, split.interactive_tokens) == (2, 105)
+    assert (split.automated_sessions, split.automated_tokens) == (2, 50)
+    assert (split.headless_sessions, split.tagged_sessions) == (1, 1)
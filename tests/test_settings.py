@@ -0,0 +1,26 @@
+import os
+from pathlib import Path
+
+from src.config import settings
+
+
+def test_data_dir_prefers_option_then_claude_config_dir(tmp_path: Path, monkeypatch) -> None:
+    monkeypatch.delenv("CLAUDE_CONFIG_DIR", raising=False)
+    monkeypatch.delenv("CCG_DATA_DIR", raising=False)
+    assert settings.get_claude_data_dir() == settings.CLAUDE_DATA_DIR
+
+    monkeypatch.setenv("CLAUDE_CONFIG_DIR", str(tmp_path / "config"))
+    assert settings.get_claude_data_dir() == tmp_path / "config" / "projects"
+
+    settings.set_claude_data_dirs([tmp_path / "logs"])
+    try:
+        assert settings.get_claude_data_dir() == (tmp_path / "logs").resolve()
+    finally:
+        settings.set_claude_data_dirs(None)
+
+
+def test_ccg_data_dir_beats_claude_config_dir(tmp_path: Path, monkeypatch) -> None:
+    monkeypatch.setenv("CLAUDE_CONFIG_DIR", str(tmp_path / "config"))
+    monkeypatch.setenv("CCG_DATA_DIR", f"{tmp_path / 'a'}{os.pathsep}{tmp_path / 'b'}")
+
+    assert settings.get_claude_data_dirs() == [tmp_path / "a", tmp_path / "b"]
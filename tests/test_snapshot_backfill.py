@@ -0,0 +1,37 @@
+import sqlite3
+from pathlib import Path
+
+from src.storage.snapshot_db import (
+    get_record_dates,
+    recompute_daily_snapshots,
+    save_snapshot,
+    verify_daily_snapshots,
+)
+
+
+def test_recompute_repairs_drifted_snapshots_and_verify_reports_them(db_path: Path, make_record) -> None:
+    save_snapshot([make_record("a1", day=1, tokens=100), make_record("a2", day=2, tokens=200)],
+                  db_path=db_path, storage_mode="full")
+    conn = sqlite3.connect(db_path)
+    conn.execute("UPDATE daily_snapshots SET total_tokens = 999 WHERE date = '2026-10-01'")
+    conn.execute("DELETE FROM daily_snapshots WHERE date = '2026-10-02'")
+    conn.execute("""
+        INSERT INTO daily_snapshots (date, total_prompts, total_responses, total_sessions, total_tokens,
+            input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, snapshot_timestamp)
+        VALUES ('2025-01-01', 1, 1, 1, 50, 50, 0, 0, 0, 'old')
+    """)
+    conn.commit()
+    conn.close()
+
+    assert get_record_dates(db_path) == ["2026-10-01", "2026-10-02"]
+    assert [(m["date"], m["snapshot"] and m["snapshot"]["total_tokens"]) for m in verify_daily_snapshots(db_path)] == [
+        ("2026-10-01", 999), ("2026-10-02", None),
+    ]
+
+    recompute_daily_snapshots(get_record_dates(db_path), db_path=db_path)
+
+    assert verify_daily_snapshots(db_path) == []
+    conn = sqlite3.connect(db_path)
+    # A date without records keeps its snapshot
+    assert conn.execute("SELECT total_tokens FROM daily_snapshots WHERE date = '2025-01-01'").fetchone() == (50,)
+    conn.close()
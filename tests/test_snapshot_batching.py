@@ -0,0 +1,88 @@
+import sqlite3
+from dataclasses import replace
+from datetime import datetime, timezone
+from pathlib import Path
+
+from src.storage import api, snapshot_db
+from src.storage.snapshot_db import init_database, save_snapshot
+
+
+def test_batched_inserts_dedupe_across_batches_and_calls(db_path: Path, monkeypatch, make_record) -> None:
+    monkeypatch.setattr(snapshot_db, "INSERT_BATCH_ROWS", 2)
+    init_database(db_path)
+
+    records = [
+        make_record("a1", output=10),
+        make_record("u1", message_type="user"),
+        make_record("a1", session="fork", output=5),  # forked replay of a1
+        make_record("a2", output=5),
+        make_record("a2", output=50),  # later, complete capture of a2
+        make_record("u1", message_type="user", session="s2"),  # user rows are session-scoped
+    ]
+    assert save_snapshot(records, db_path=db_path, storage_mode="full") == 4
+    # Re-saving inserts nothing but still upgrades a partial capture
+    assert save_snapshot([make_record("a1", output=40)], db_path=db_path, storage_mode="full") == 0
+
+    conn = sqlite3.connect(db_path)
+    try:
+        rows = conn.execute(
+            "SELECT session_id, message_uuid, output_tokens FROM usage_records ORDER BY message_uuid, session_id"
+        ).fetchall()
+        assert rows == [("s1", "a1", 40), ("s1", "a2", 50), ("s1", "u1", 0), ("s2", "u1", 0)]
+        daily = conn.execute("SELECT total_prompts, total_responses, total_sessions FROM daily_snapshots")
+        assert daily.fetchall() == [(2, 2, 2)]
+    finally:
+        conn.close()
+
+
+def test_full_mode_rebuilds_the_model_mix_only_for_batch_dates(db_path: Path, make_record) -> None:
+    save_snapshot([make_record("a1", day=1)], db_path=db_path, storage_mode="full")
+    conn = sqlite3.connect(db_path)
+    try:
+        # Stands in for a day whose detail rows no longer match its rollup
+        conn.execute("UPDATE daily_model_snapshots SET total_tokens = 999 WHERE date = '2026-10-01'")
+        conn.commit()
+
+        save_snapshot([make_record("a2", day=2, tokens=50)], db_path=db_path, storage_mode="full")
+
+        rows = conn.execute("SELECT date, total_tokens FROM daily_model_snapshots ORDER BY date").fetchall()
+        assert rows == [("2026-10-01", 999), ("2026-10-02", 50)]
+    finally:
+        conn.close()
+
+
+def test_history_filters_reach_the_query(db_path: Path, monkeypatch, make_record) -> None:
+    monkeypatch.setattr("src.storage.api.get_db_path", lambda: db_path)
+    monkeypatch.setattr("src.storage.api.get_backend_module", lambda: snapshot_db)
+    records = [make_record("a1"), make_record("a2"), make_record("u1", message_type="user")]
+    records[1] = replace(records[1], timestamp=datetime(2025, 12, 31, 23, tzinfo=timezone.utc), folder="/q")
+    save_snapshot(records, db_path=db_path, storage_mode="full")
+
+    assert [r.message_uuid for r in api.load_historical_records(year=2026)] == ["a1", "u1"]
+    assert [r.message_uuid for r in api.iter_historical_records(year=2025, folder="/q")] == ["a2"]
+    assert list(api.iter_historical_records(year=2026, model="claude-sonnet-4-5")) == [records[0]]
+    assert list(api.iter_historical_records(start_date="2026-02-01", year=2025)) == []
+
+
+def test_stored_records_stream_every_pass_and_fall_back_to_aggregates(
+    tmp_path: Path, db_path: Path, monkeypatch, make_record
+) -> None:
+    monkeypatch.setattr("src.storage.api.get_db_path", lambda: db_path)
+    monkeypatch.setattr("src.storage.api.get_backend_module", lambda: snapshot_db)
+    save_snapshot([make_record("a1"), make_record("u1", message_type="user")], db_path=db_path, storage_mode="full")
+
+    loaded, load = [], snapshot_db.load_historical_records
+    monkeypatch.setattr(snapshot_db, "load_historical_records", lambda *a, **k: loaded.append(a) or [])
+    stored = api.StoredRecords()
+    assert stored
+    assert [r.message_uuid for r in stored] == [r.message_uuid for r in stored] == ["a1", "u1"]
+    renamed = stored.map(lambda r: replace(r, folder="project-001"))
+    assert {r.folder for r in renamed} == {"project-001"}
+    # Full-mode passes never materialize the table
+    assert not loaded
+    monkeypatch.setattr(snapshot_db, "load_historical_records", load)
+
+    aggregate_db = tmp_path / "aggregate.db"
+    save_snapshot([make_record("a1", output=10)], db_path=aggregate_db, storage_mode="aggregate")
+    records = list(api.StoredRecords(db=aggregate_db))
+    assert sum(r.token_usage.total_tokens for r in records) == 110
@@ -0,0 +1,48 @@
+import json
+from pathlib import Path
+
+from src.aggregation.snapshot_diff import UsageSnapshot, diff_snapshots
+from src.commands.diff import _load_snapshot
+from src.storage.snapshot_db import save_snapshot
+
+
+def test_diff_against_older_database_and_saved_snapshot(tmp_path: Path, make_record) -> None:
+    old_db = tmp_path / "old.db"
+    save_snapshot([
+        make_record("a1", folder="/p/api", tokens=1_000),
+        make_record("a2", folder="/p/legacy", tokens=1_000),
+    ], db_path=old_db, storage_mode="full")
+    new_db = tmp_path / "new.db"
+    save_snapshot([
+        make_record("a1", folder="/p/api", tokens=1_000),
+        make_record("a3", folder="/p/api", model="claude-opus-4-5", tokens=2_000, day=2),
+        make_record("a4", folder="/p/web", model="claude-opus-4-5", tokens=500, day=2),
+    ], db_path=new_db, storage_mode="full")
+
+    old = _load_snapshot(old_db)
+    # A saved JSON snapshot reads back the same as the database it came from
+    saved = tmp_path / "old.json"
+    saved.write_text(json.dumps(old.to_dict()))
+    assert _load_snapshot(saved) == old
+
+    diff = diff_snapshots(old, _load_snapshot(new_db))
+
+    assert diff.tokens == 1_500
+    assert [(d.folder, d.status, d.tokens) for d in diff.projects] == [
+        ("/p/api", "changed", 2_000),  # largest cost change first
+        ("/p/web", "new", 500),
+        ("/p/legacy", "dropped", -1_000),
+    ]
+    assert [(model, round(before), round(after)) for model, before, after in diff.model_shifts] == [
+        ("claude-opus-4-5", 0, 71),
+        ("claude-sonnet-4-5", 100, 29),
+    ]
+
+
+def test_snapshot_rejects_other_json() -> None:
+    try:
+        UsageSnapshot.from_dict({"total_tokens": 5})
+    except ValueError as e:
+        assert "ccg diff --save" in str(e)
+    else:
+        raise AssertionError("expected ValueError")
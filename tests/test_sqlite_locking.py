@@ -0,0 +1,47 @@
+import sqlite3
+from pathlib import Path
+
+import pytest
+
+from src.storage import snapshot_db
+from src.storage.snapshot_db import _retry_on_lock, get_session_tags, init_database
+
+
+def test_database_uses_wal_so_readers_skip_writer_locks(db_path: Path) -> None:
+    init_database(db_path)
+
+    writer = sqlite3.connect(db_path)
+    try:
+        assert writer.execute("PRAGMA journal_mode").fetchone() == ("wal",)
+        writer.execute("INSERT INTO session_tags VALUES ('s1', 'hook', '2026-10-01T10:00:00')")
+        # A reader sees the last commit while the write is still open
+        assert get_session_tags(db_path) == {}
+        writer.commit()
+        assert get_session_tags(db_path) == {"s1": ["hook"]}
+    finally:
+        writer.close()
+
+
+def test_writes_retry_while_the_database_is_locked(monkeypatch) -> None:
+    monkeypatch.setattr(snapshot_db, "LOCK_RETRY_DELAY_SECONDS", 0)
+    attempts = []
+
+    @_retry_on_lock
+    def write() -> str:
+        attempts.append(1)
+        if len(attempts) < 3:
+            raise sqlite3.OperationalError("database is locked")
+        return "saved"
+
+    @_retry_on_lock
+    def broken() -> None:
+        attempts.append(1)
+        raise sqlite3.OperationalError("no such table: usage_records")
+
+    assert write() == "saved"
+    assert len(attempts) == 3
+
+    attempts.clear()
+    with pytest.raises(sqlite3.OperationalError, match="no such table"):
+        broken()
+    assert len(attempts) == 1
@@ -0,0 +1,57 @@
+from datetime import datetime, timedelta
+from pathlib import Path
+
+import pytest
+
+from src.commands import update_usage
+from src.config.user_config import parse_duration
+from src.errors import StaleDataError
+from src.storage.snapshot_db import get_last_full_ingest, get_update_runs, record_full_ingest, record_update_run
+
+NOW = datetime(2026, 10, 16, 12, 0)
+
+
+class _Console:
+    def __init__(self) -> None:
+        self.lines: list[str] = []
+
+    def print(self, text: str = "") -> None:
+        self.lines.append(text)
+
+
+def _use_db(monkeypatch, db_path: Path) -> None:
+    monkeypatch.setattr(update_usage.api, "get_last_full_ingest", lambda: get_last_full_ingest(db_path))
+    monkeypatch.setattr(
+        update_usage.api, "get_update_runs", lambda limit=20: get_update_runs(limit=limit, db_path=db_path)
+    )
+
+
+def test_parse_duration_accepts_units_and_rejects_junk() -> None:
+    assert parse_duration("90m") == timedelta(minutes=90)
+    assert parse_duration("1d") == timedelta(days=1)
+    assert parse_duration("1.5h") == timedelta(hours=1.5)
+    for bad in ("", "1", "0d", "3y", "-1d"):
+        with pytest.raises(ValueError):
+            parse_duration(bad)
+
+
+def test_fast_commands_warn_then_refuse_past_max_stale(monkeypatch, db_path: Path) -> None:
+    _use_db(monkeypatch, db_path)
+
+    # Only an older `ccg update usage` run: used until a full ingest is recorded
+    record_update_run("2026-10-10T08:00:00", "2026-10-10T08:00:05", "hook", 0, db_path=db_path)
+    assert update_usage.last_full_update() == datetime(2026, 10, 10, 8, 0, 5)
+
+    record_full_ingest((NOW - timedelta(hours=30)).isoformat(), db_path=db_path)
+    console = _Console()
+    update_usage.check_fast_staleness(console, now=NOW)
+    assert "1 day ago" in console.lines[0]
+
+    update_usage.check_fast_staleness(console, timedelta(days=2), now=NOW)
+    with pytest.raises(StaleDataError, match="last full update 1 day ago, limit 12 hours"):
+        update_usage.check_fast_staleness(console, parse_duration("12h"), now=NOW)
+
+    record_full_ingest((NOW - timedelta(minutes=5)).isoformat(), db_path=db_path)
+    fresh = _Console()
+    update_usage.check_fast_staleness(fresh, parse_duration("12h"), now=NOW)
+    assert fresh.lines == []
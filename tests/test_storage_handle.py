@@ -0,0 +1,28 @@
+from pathlib import Path
+
+from src.storage import snapshot_db
+from src.storage.snapshot_db import get_database_stats, load_daily_snapshots, save_snapshot, storage_handle
+
+
+def test_calls_inside_a_handle_share_one_connection(tmp_path: Path, db_path: Path, monkeypatch, make_record) -> None:
+    opened = []
+    real_open = snapshot_db._open
+    monkeypatch.setattr(snapshot_db, "_open", lambda path: opened.append(path) or real_open(path))
+
+    with storage_handle(db_path) as handle:
+        save_snapshot([make_record("a1")], db_path=db_path, storage_mode="full")
+        save_snapshot([make_record("a2")], db_path=db_path, storage_mode="full")
+        assert get_database_stats(db_path=db_path)["total_records"] == 2
+        assert len(opened) == 1
+
+        # Deleting the file goes through a fresh connection afterwards
+        snapshot_db.release_storage_handle(db_path)
+        for path in tmp_path.iterdir():
+            path.unlink()
+        save_snapshot([make_record("a3")], db_path=db_path, storage_mode="full")
+        assert [day["total_tokens"] for day in load_daily_snapshots(db_path=db_path)] == [100]
+        assert len(opened) == 2 and handle.initialized
+
+    # Other files, and calls after the block, open their own connections
+    save_snapshot([make_record("b1")], db_path=tmp_path / "other.db", storage_mode="full")
+    assert len(opened) > 2
@@ -0,0 +1,21 @@
+from pathlib import Path
+
+from src.utils import _system
+from src.utils._system import list_system_sounds
+
+
+def test_lists_linux_and_windows_sounds_that_exist(tmp_path: Path, monkeypatch) -> None:
+    stereo, alsa, media = tmp_path / "stereo", tmp_path / "alsa", tmp_path / "Media"
+    for directory in (stereo, alsa, media):
+        directory.mkdir()
+    for path in (
+        stereo / "complete.oga", stereo / "bell.oga", stereo / "readme.txt",
+        alsa / "Front_Center.wav", alsa / "bell.wav", alsa / "bad;name.wav",
+        media / "chimes.wav", media / "Windows Ding.wav", media / "unmapped.wav",
+    ):
+        path.touch()
+    monkeypatch.setattr(_system, "LINUX_SOUND_DIRS", ((stereo, ".oga"), (alsa, ".wav")))
+    monkeypatch.setattr(_system, "WINDOWS_MEDIA_DIR", media)
+
+    assert list_system_sounds("Linux") == ["bell", "complete", "Front_Center"]
+    assert list_system_sounds("Windows") == ["chimes", "Windows Ding"]
@@ -0,0 +1,20 @@
+from pathlib import Path
+
+from src.storage.snapshot_db import get_tool_usage, save_tool_usage
+
+
+def test_tool_calls_are_stored_once_and_counted_per_day_session_and_tool(db_path: Path, make_record) -> None:
+    records = [
+        make_record("a1", day=1, tool_uses=(("t1", "Bash"), ("t2", "Read"), ("t3", "Bash"))),
+        make_record("a2", day=2, tool_uses=(("t4", "mcp__github__search"),)),
+    ]
+
+    assert save_tool_usage(records, db_path=db_path) == 4
+    assert save_tool_usage(records, db_path=db_path) == 0
+
+    assert get_tool_usage(db_path=db_path) == [
+        {"date": "2026-10-01", "session_id": "s1", "tool": "Bash", "count": 2},
+        {"date": "2026-10-01", "session_id": "s1", "tool": "Read", "count": 1},
+        {"date": "2026-10-02", "session_id": "s1", "tool": "mcp__github__search", "count": 1},
+    ]
+    assert [row["tool"] for row in get_tool_usage("2026-10-02", db_path=db_path)] == ["mcp__github__search"]
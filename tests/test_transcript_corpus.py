@@ -0,0 +1,128 @@
+import json
+import random
+from pathlib import Path
+
+from src.data.corpus import find_unknown, validate_corpus
+from src.data.jsonl_parser import ParseStats, parse_jsonl_file
+
+FIXTURES = Path(__file__).parent / "fixtures" / "transcripts"
+
+# Cases per generated property; the seed keeps failures reproducible
+PROPERTY_CASES = 200
+
+
+def _records(name: str) -> list:
+    return list(parse_jsonl_file(FIXTURES / name, stats=ParseStats(issues=[])))
+
+
+def _random_assistant_entry(rng: random.Random, index: int) -> dict:
+    usage = {
+        "input_tokens": rng.randint(0, 50_000),
+        "output_tokens": rng.randint(0, 20_000),
+        "cache_read_input_tokens": rng.randint(0, 500_000),
+    }
+    cache_5m, cache_1h = rng.randint(0, 100_000), rng.randint(0, 100_000)
+    if rng.random() < 0.5:
+        usage["cache_creation"] = {"ephemeral_5m_input_tokens": cache_5m, "ephemeral_1h_input_tokens": cache_1h}
+        usage["cache_creation_input_tokens"] = cache_5m + cache_1h
+    else:
+        usage["cache_creation_input_tokens"] = cache_5m
+    blocks = [
+        rng.choice([
+            {"type": "text", "text": "x" * rng.randint(0, 40)},
+            {"type": "thinking", "thinking": "y" * rng.randint(0, 40)},
+            {"type": "tool_use", "id": f"toolu_{index}_{n}", "name": "Bash", "input": {}},
+        ])
+        for n in range(rng.randint(0, 4))
+    ]
+    return {
+        "type": "assistant",
+        "sessionId": "00000000-0000-4000-8000-00000000000f",
+        "uuid": f"uuid-{index}",
+        "requestId": f"req-{index}",
+        "timestamp": f"2026-10-01T10:{index // 60 % 60:02d}:{index % 60:02d}Z",
+        "cwd": "/home/user/project",
+        "isSidechain": rng.random() < 0.2,
+        "message": {"id": f"msg-{index}", "model": "claude-sonnet-4-5", "content": blocks, "usage": usage},
+    }
+
+
+def test_fixture_corpus_parses_without_unknown_fields() -> None:
+    report = validate_corpus([FIXTURES])
+
+    assert report.files == 4
+    assert report.clean, report.to_dict()
+
+
+def test_fixture_variants_parse_to_the_expected_usage() -> None:
+    legacy = [r for r in _records("legacy_cache_fields.jsonl") if r.token_usage]
+    assert legacy[0].token_usage.cache_creation_tokens == 2048
+    assert legacy[0].token_usage.cache_creation_1h_tokens == 0
+
+    cached = [r for r in _records("cache_breakdown.jsonl") if r.token_usage]
+    assert cached[0].token_usage.cache_creation_1h_tokens == 2000
+    assert cached[0].token_usage.cache_creation_5m_tokens == 1000
+    # A streamed flush entry repeats the response under the same identity
+    assert {r.message_uuid for r in cached} == {"msg_cache_0001:req_cache_0001"}
+
+    tools = _records("tool_events.jsonl")
+    assert [name for r in tools for _, name in r.tool_uses] == ["Bash", "web_search"]
+    assert [tool_id for r in tools for tool_id, _ in r.tool_results] == ["toolu_0001"]
+
+    assert all(r.is_subagent for r in _records("sidechain.jsonl"))
+
+
+def test_generated_entries_keep_their_token_counts(tmp_path: Path) -> None:
+    rng = random.Random(780)
+    entries = [_random_assistant_entry(rng, index) for index in range(PROPERTY_CASES)]
+    path = tmp_path / "generated.jsonl"
+    path.write_text("".join(json.dumps(entry) + "\n" for entry in entries), encoding="utf-8")
+
+    stats = ParseStats(issues=[])
+    records = list(parse_jsonl_file(path, stats=stats))
+
+    assert stats.issues == []
+    assert len(records) == len(entries)
+    for entry, record in zip(entries, records):
+        usage = entry["message"]["usage"]
+        tokens = record.token_usage
+        assert tokens.input_tokens == usage["input_tokens"]
+        assert tokens.output_tokens == usage["output_tokens"]
+        assert tokens.cache_read_tokens == usage["cache_read_input_tokens"]
+        assert tokens.cache_creation_tokens == usage["cache_creation_input_tokens"]
+        assert 0 <= tokens.cache_creation_1h_tokens <= tokens.cache_creation_tokens
+        assert record.is_subagent == entry["isSidechain"]
+        assert len(record.tool_uses) == sum(block["type"] == "tool_use" for block in entry["message"]["content"])
+
+
+def test_corrupted_lines_are_reported_never_raised(tmp_path: Path) -> None:
+    rng = random.Random(7802)
+    lines = []
+    for index in range(PROPERTY_CASES):
+        line = json.dumps(_random_assistant_entry(rng, index))
+        if rng.random() < 0.3:
+            line = line[:rng.randint(1, len(line) - 1)]
+        lines.append(line + "\n")
+    path = tmp_path / "corrupted.jsonl"
+    path.write_text("".join(lines), encoding="utf-8")
+
+    stats = ParseStats(issues=[])
+    records = list(parse_jsonl_file(path, stats=stats))
+
+    # Every line either became a record or was accounted for
+    assert stats.lines == len(records) + stats.skipped
+    assert len(stats.issues) == stats.malformed + stats.unexpected_shape + stats.bad_timestamps
+
+
+def test_unknown_fields_types_and_blocks_are_reported() -> None:
+    entry = json.loads((FIXTURES / "sidechain.jsonl").read_text(encoding="utf-8").splitlines()[1])
+    entry["costUSD"] = 0.01
+    entry["message"]["usage"]["cache_creation"]["ephemeral_24h_input_tokens"] = 5
+    entry["message"]["content"].append({"type": "hologram"})
+
+    assert find_unknown(entry) == [
+        "costUSD",
+        "message.usage.cache_creation.ephemeral_24h_input_tokens",
+        "message.content[].type=hologram",
+    ]
+    assert find_unknown({**entry, "type": "telemetry"})[0] == "type=telemetry"
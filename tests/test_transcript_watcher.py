@@ -0,0 +1,28 @@
+import threading
+import time
+from pathlib import Path
+
+from src.data.transcript_watcher import TranscriptWatcher
+
+
+def test_polling_watcher_batches_appends_until_writes_pause(tmp_path: Path) -> None:
+    transcript = tmp_path / "project" / "session.jsonl"
+    transcript.parent.mkdir()
+    transcript.write_text("{}\n")
+    (tmp_path / "notes.txt").write_text("ignored")
+
+    def append_lines() -> None:
+        for _ in range(3):
+            time.sleep(0.03)
+            with transcript.open("a") as f:
+                f.write("{}\n")
+
+    with TranscriptWatcher([tmp_path], poll_interval=0.01, native=False) as watcher:
+        assert watcher.wait(0.05) == set()
+        writer = threading.Thread(target=append_lines)
+        writer.start()
+        changed = watcher.wait_for_batch(quiet=0.2, max_delay=5)
+        writer.join()
+
+        assert changed == {transcript}
+        assert watcher.wait(0.05) == set()
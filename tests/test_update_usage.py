@@ -0,0 +1,168 @@
+import io
+import json
+import sqlite3
+import threading
+import time
+from datetime import datetime
+from pathlib import Path
+
+from src.commands import update_usage
+from src.storage import snapshot_db
+from src.storage.ingest_lock import ingest_lock
+from src.storage.refresh_marker import read_refresh_payload, touch_refresh_marker
+
+
+class _Console:
+    def __init__(self) -> None:
+        self.lines: list[str] = []
+
+    def print(self, text: str = "", **kwargs) -> None:
+        self.lines.append(text)
+
+
+def _entry(index: int, day: int, cwd: str) -> str:
+    return json.dumps({
+        "type": "assistant",
+        "sessionId": "session-1",
+        "uuid": f"uuid-{index}",
+        "requestId": f"req-{index}",
+        "timestamp": f"2026-10-{day:02d}T10:00:00Z",
+        "cwd": cwd,
+        "version": "2.0",
+        "message": {
+            "id": f"msg-{index}",
+            "model": "claude-sonnet-4-5",
+            "usage": {"input_tokens": 10, "output_tokens": 5},
+        },
+    }) + "\n"
+
+
+def _use_db(monkeypatch, db_path: Path) -> None:
+    monkeypatch.setattr("src.storage.api.get_db_path", lambda: db_path)
+    monkeypatch.setattr("src.storage.api.get_backend_module", lambda: snapshot_db)
+    monkeypatch.setattr("src.storage.refresh_marker.get_db_path", lambda: db_path)
+    monkeypatch.setattr("src.storage.ingest_lock.get_db_path", lambda: db_path)
+    monkeypatch.setattr(update_usage, "get_storage_mode", lambda: "full")
+    monkeypatch.setattr(update_usage, "get_redaction_mode", lambda: None)
+
+
+def test_dry_run_counts_new_data_without_touching_the_database(
+    tmp_path: Path, db_path: Path, monkeypatch, make_record
+) -> None:
+    _use_db(monkeypatch, db_path)
+    saved = make_record("msg-1:req-1", session="session-1", folder="/repo", tokens=10, output=5)
+    snapshot_db.save_snapshot([saved], db_path=db_path, storage_mode="full")
+    # Left by an older release: opening it for writing would migrate it
+    conn = sqlite3.connect(db_path)
+    conn.execute("DELETE FROM schema_version WHERE version = (SELECT MAX(version) FROM schema_version)")
+    conn.commit()
+    conn.close()
+    transcript = tmp_path / "session-1.jsonl"
+    transcript.write_text(_entry(1, 1, "/repo") + _entry(2, 2, "/repo") + _entry(3, 2, "/other"), encoding="utf-8")
+    monkeypatch.setattr(update_usage, "_collect_sources", lambda project: [([transcript], None)])
+
+    before = db_path.read_bytes(), db_path.stat().st_mtime_ns, sorted(p.name for p in tmp_path.iterdir())
+    preview = update_usage.preview_token_usage(_Console(), force=True)
+
+    assert preview == {
+        "files": 1, "new_records": 2, "new_dates": ["2026-10-02"], "new_projects": ["/other"],
+    }
+    assert (db_path.read_bytes(), db_path.stat().st_mtime_ns, sorted(p.name for p in tmp_path.iterdir())) == before
+
+
+def test_project_scope_tags_records_and_keeps_their_folders(tmp_path: Path, db_path: Path, monkeypatch) -> None:
+    _use_db(monkeypatch, db_path)
+    repo = tmp_path / "repo"
+    (repo / "api").mkdir(parents=True)
+    inside = tmp_path / "session-1.jsonl"
+    inside.write_text(
+        _entry(1, 1, str(repo)) + _entry(2, 1, str(repo / "api")) + _entry(3, 1, str(repo / "api"))
+        + _entry(4, 1, str(tmp_path)),
+        encoding="utf-8",
+    )
+    sibling = tmp_path / "session-2.jsonl"
+    sibling.write_text(_entry(5, 1, str(tmp_path / "repo-web")), encoding="utf-8")
+    monkeypatch.setattr(update_usage, "_collect_sources", lambda project: [([inside, sibling], None)])
+    checked = []
+    is_within = update_usage._is_within
+    monkeypatch.setattr(
+        update_usage, "_is_within", lambda folder, project: checked.append(folder) or is_within(folder, project)
+    )
+
+    update_usage.ingest_token_usage(_Console(), force=True, verbose=False, project=repo)
+
+    stored = {r.message_uuid: (r.folder, r.project) for r in snapshot_db.iter_historical_records(db_path=db_path)}
+    assert stored == {
+        "msg-1:req-1": (str(repo), str(repo.resolve())),
+        "msg-2:req-2": (str(repo / "api"), str(repo.resolve())),
+        "msg-3:req-3": (str(repo / "api"), str(repo.resolve())),
+        # Left the project mid-session: kept, but not tagged
+        "msg-4:req-4": (str(tmp_path), None),
+    }
+    # One check per distinct folder, not per record
+    assert sorted(checked) == sorted([str(repo), str(repo / "api"), str(tmp_path), str(tmp_path / "repo-web")])
+
+
+def test_queued_run_is_skipped_once_another_run_covered_it(tmp_path: Path, db_path: Path, monkeypatch) -> None:
+    _use_db(monkeypatch, db_path)
+    monkeypatch.setattr(update_usage, "get_db_path", lambda: db_path)
+    queued = threading.Event()
+
+    def queue_for_lock():
+        queued.set()
+        return ingest_lock(timeout=5)
+
+    monkeypatch.setattr(update_usage, "ingest_lock", queue_for_lock)
+    ingested = []
+    monkeypatch.setattr(update_usage, "_ingest_sources", lambda *args: ingested.append(args) or 1)
+    held = threading.Event()
+
+    def other_run(project: str | None) -> None:
+        # Starts after the queued run and ingests everything before releasing the lock
+        with ingest_lock():
+            held.set()
+            queued.wait(5)
+            time.sleep(0.2)  # The queued run is polling the lock by now
+            touch_refresh_marker(1, started_at=datetime.now().isoformat(), project=project, db=str(db_path))
+
+    def run_queued_behind(project: str | None, scope: Path | None) -> int:
+        queued.clear()
+        held.clear()
+        holder = threading.Thread(target=other_run, args=(project,))
+        holder.start()
+        held.wait(5)
+        try:
+            return update_usage.ingest_token_usage(_Console(), verbose=False, project=scope)
+        finally:
+            holder.join()
+
+    repo = (tmp_path / "repo").resolve()
+    # An unscoped run covers every queued run
+    assert run_queued_behind(None, repo) == 0
+    assert ingested == []
+    # A run scoped to one project doesn't cover an unscoped or other-project run
+    assert run_queued_behind(str(repo), None) == 1
+    assert run_queued_behind(str(repo), tmp_path / "web") == 1
+    assert run_queued_behind(str(repo), repo) == 0
+    assert len(ingested) == 2
+    # The marker sits beside the database it signals for
+    assert read_refresh_payload(tmp_path / "last_updated.json")["db"] == str(db_path)
+
+
+def test_stdin_ingests_in_full_mode_and_is_refused_in_aggregate_mode(db_path: Path, monkeypatch) -> None:
+    _use_db(monkeypatch, db_path)
+    monkeypatch.setattr(update_usage, "_refresh_pricing_if_stale", lambda console, verbose: None)
+    lines = _entry(1, 1, "/repo") + _entry(2, 1, "/repo")
+
+    assert update_usage.ingest_stdin(_Console(), io.StringIO(lines), verbose=False) == 2
+    assert update_usage.ingest_stdin(_Console(), io.StringIO(lines), verbose=False) == 0
+
+    monkeypatch.setattr(update_usage, "get_storage_mode", lambda: "aggregate")
+    console = _Console()
+    update_usage.run(console, stdin=io.StringIO(_entry(3, 2, "/repo")))
+
+    assert console.lines[0] == "[red]Error updating usage: Piped transcripts need full storage mode[/red]"
+    assert "ccg config set storage_mode full" in console.lines[1]
+    assert [r.message_uuid for r in snapshot_db.iter_historical_records(db_path=db_path)] == [
+        "msg-1:req-1", "msg-2:req-2",
+    ]
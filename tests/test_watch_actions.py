@@ -0,0 +1,32 @@
+from pathlib import Path
+
+from src.commands.watch_actions import WatchActions, _forwarded_global_options
+
+
+class _RecordingConsole:
+    def __init__(self) -> None:
+        self.lines: list[str] = []
+
+    def print(self, text: str = "") -> None:
+        self.lines.append(text)
+
+
+def test_forwards_only_global_data_options() -> None:
+    argv = ["--profile", "work", "--profile=home", "--data-dir", "/x", "update", "usage", "--profile", "late"]
+    assert _forwarded_global_options(argv) == ["--profile", "work", "--profile=home", "--data-dir", "/x"]
+    assert _forwarded_global_options(["update", "usage", "--watch"]) == []
+
+
+def test_actions_throttle_and_isolate_failures(tmp_path: Path) -> None:
+    log = tmp_path / "log.txt"
+    actions = WatchActions([
+        {"type": "command", "command": "exit 3", "min_interval": 0},
+        {"type": "command", "command": f"echo run >> '{log}'", "min_interval": 3600},
+    ])
+    console = _RecordingConsole()
+
+    actions.run(console, 5, {tmp_path / "a.jsonl"})
+    actions.run(console, 2, {tmp_path / "a.jsonl"})
+
+    assert log.read_text().splitlines() == ["run"]
+    assert sum("watch action 'command' failed" in line for line in console.lines) == 2
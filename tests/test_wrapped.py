@@ -0,0 +1,36 @@
+from datetime import datetime, timezone
+
+from src.aggregation.wrapped import build_year_wrapped
+
+
+def _day(date: str, tokens: int) -> dict:
+    return {
+        "date": date,
+        "prompts": 1,
+        "responses": 1,
+        "sessions": 1,
+        "total_tokens": tokens,
+        "cost": 0.0,
+        "models": {"sonnet": {"total_tokens": tokens}},
+    }
+
+
+def _hour(hour: int, tokens: int) -> dict:
+    return {"hour": f"2025-03-01T{hour:02d}:00:00+00:00", "prompts": 1, "total_tokens": tokens}
+
+
+def test_build_year_wrapped_picks_busiest_day_month_and_hour() -> None:
+    days = [_day("2025-03-01", 500), _day("2025-03-02", 100), _day("2025-07-04", 300), _day("2024-12-31", 9000)]
+    hours = [_hour(9, 100), _hour(22, 400)]
+
+    wrapped = build_year_wrapped(2025, days, {"/work/alpha": 900}, hours)
+
+    assert wrapped.total_tokens == 900
+    assert (wrapped.busiest_day, wrapped.busiest_month) == ("2025-03-01", "2025-03")
+    assert (wrapped.longest_streak, wrapped.active_days) == (2, 3)
+    assert wrapped.busiest_hour == datetime(2025, 3, 1, 22, tzinfo=timezone.utc).astimezone().hour
+    assert wrapped.top_model == "sonnet"
+
+
+def test_busiest_hour_is_unknown_without_hourly_totals() -> None:
+    assert build_year_wrapped(2025, [_day("2025-03-01", 500)], {}).busiest_hour is None